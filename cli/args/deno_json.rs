@@ -1,11 +1,40 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::PathBuf;
 
+use deno_config::glob::FilePatterns;
+use deno_config::glob::PathOrPattern;
+use deno_config::glob::PathOrPatternSet;
+use deno_config::ConfigFile;
+use deno_config::FmtConfig;
+use deno_config::FmtOptionsConfig;
+use deno_config::LintConfig;
+use deno_config::LintRulesConfig;
+use deno_config::TestConfig;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
 use deno_core::serde_json;
+use deno_core::ModuleSpecifier;
 use deno_semver::jsr::JsrDepPackageReq;
 use deno_semver::jsr::JsrPackageReqReference;
 use deno_semver::npm::NpmPackageReqReference;
+use indexmap::IndexMap;
+
+use crate::args::CoverageConfig;
+use crate::args::CoverageThresholds;
+use crate::args::FmtConfigExtras;
+use crate::args::FmtOverride;
+use crate::args::FmtPluginConfig;
+use crate::args::LintConfigExtras;
+use crate::args::LintOverride;
+use crate::args::TaskOptions;
+use crate::args::TestConfigExtras;
+use crate::args::UnstableFmtOptions;
+use crate::util::fs::exclude_path_or_patterns;
+use crate::util::fs::include_path_or_patterns;
 
 pub fn deno_json_deps(
   config: &deno_config::ConfigFile,
@@ -16,6 +45,539 @@ pub fn deno_json_deps(
   values_to_set(values)
 }
 
+/// Gets the formatter configuration along with the parts of it that
+/// deno_config doesn't understand, such as `fmt.overrides`, `fmt.plugins`,
+/// `fmt.sortImports` and `fmt.skipCodeBlockLanguages`. These keys are removed
+/// before resolving the rest of the configuration because deno_config
+/// rejects them. The unstable formatters enabled by the top-level `unstable`
+/// key are included in the extras as well.
+pub fn to_fmt_config_with_extras(
+  config: &ConfigFile,
+) -> Result<(Option<FmtConfig>, FmtConfigExtras), AnyError> {
+  let mut extras = FmtConfigExtras {
+    unstable: UnstableFmtOptions {
+      css: config.has_unstable("fmt-css"),
+      yaml: config.has_unstable("fmt-yaml"),
+    },
+    ..Default::default()
+  };
+  let Some(fmt) = config.json.fmt.as_ref().and_then(|fmt| fmt.as_object())
+  else {
+    return config.to_fmt_config().map(|c| (c, extras));
+  };
+  if let Some(overrides) = fmt.get("overrides") {
+    extras.overrides = parse_fmt_overrides(config, overrides)
+      .context("Failed to parse \"fmt.overrides\" configuration")?;
+  }
+  if let Some(plugins) = fmt.get("plugins") {
+    extras.plugins = parse_fmt_plugins(config, plugins)
+      .context("Failed to parse \"fmt.plugins\" configuration")?;
+  }
+  if let Some(sort_imports) = fmt.get("sortImports") {
+    let Some(sort_imports) = sort_imports.as_bool() else {
+      bail!("Expected \"fmt.sortImports\" to be a boolean.");
+    };
+    extras.sort_imports = sort_imports;
+  }
+  if let Some(languages) = fmt.get("skipCodeBlockLanguages") {
+    let Ok(languages) = serde_json::from_value(languages.clone()) else {
+      bail!(
+        "Expected \"fmt.skipCodeBlockLanguages\" to be an array of strings."
+      );
+    };
+    extras.skip_code_block_languages = languages;
+  }
+  let mut config = config.clone();
+  if let Some(serde_json::Value::Object(fmt)) = config.json.fmt.as_mut() {
+    fmt.remove("overrides");
+    fmt.remove("plugins");
+    fmt.remove("sortImports");
+    fmt.remove("skipCodeBlockLanguages");
+  }
+  Ok((config.to_fmt_config()?, extras))
+}
+
+fn parse_fmt_overrides(
+  config: &ConfigFile,
+  value: &serde_json::Value,
+) -> Result<Vec<FmtOverride>, AnyError> {
+  parse_overrides(config, value, |i, entry| {
+    let options: FmtOptionsConfig =
+      serde_json::from_value(serde_json::Value::Object(entry))
+        .with_context(|| format!("Invalid options in override {i}."))?;
+    Ok(options)
+  })
+  .map(|overrides| {
+    overrides
+      .into_iter()
+      .map(|(files, options)| FmtOverride { files, options })
+      .collect()
+  })
+}
+
+/// Gets the lint configuration along with the parts of it that deno_config
+/// doesn't understand, which are `lint.overrides`, `lint.plugins`,
+/// `lint.useGitignore`, `lint.followSymlinks`, `lint.maxDepth` and the
+/// severities of the rules in `lint.rules`.
+pub fn to_lint_config_with_extras(
+  config: &ConfigFile,
+) -> Result<(Option<LintConfig>, LintConfigExtras), AnyError> {
+  let Some(lint) = config.json.lint.as_ref().and_then(|lint| lint.as_object())
+  else {
+    return config.to_lint_config().map(|c| (c, Default::default()));
+  };
+  let mut extras = LintConfigExtras::default();
+  if let Some(overrides) = lint.get("overrides") {
+    extras.overrides = parse_lint_overrides(config, overrides)
+      .context("Failed to parse \"lint.overrides\" configuration")?;
+  }
+  if let Some(plugins) = lint.get("plugins") {
+    extras.plugins = parse_lint_plugins(config, plugins)
+      .context("Failed to parse \"lint.plugins\" configuration")?;
+  }
+  if let Some(use_gitignore) = lint.get("useGitignore") {
+    let Some(use_gitignore) = use_gitignore.as_bool() else {
+      bail!("Expected \"lint.useGitignore\" to be a boolean.");
+    };
+    extras.use_gitignore = use_gitignore;
+  }
+  if let Some(follow_symlinks) = lint.get("followSymlinks") {
+    let Some(follow_symlinks) = follow_symlinks.as_bool() else {
+      bail!("Expected \"lint.followSymlinks\" to be a boolean.");
+    };
+    extras.follow_symlinks = follow_symlinks;
+  }
+  if let Some(max_depth) = lint.get("maxDepth") {
+    let Some(max_depth) = max_depth.as_u64() else {
+      bail!("Expected \"lint.maxDepth\" to be a non-negative integer.");
+    };
+    extras.max_depth = Some(max_depth as usize);
+  }
+  let mut config = config.clone();
+  if let Some(serde_json::Value::Object(lint)) = config.json.lint.as_mut() {
+    lint.remove("overrides");
+    lint.remove("plugins");
+    lint.remove("useGitignore");
+    lint.remove("followSymlinks");
+    lint.remove("maxDepth");
+    if let Some(serde_json::Value::Object(rules)) = lint.get_mut("rules") {
+      extras.warn_rules = resolve_rule_severities(rules)
+        .context("Failed to parse \"lint.rules\" configuration")?;
+    }
+  }
+  Ok((config.to_lint_config()?, extras))
+}
+
+/// Moves the rules that are configured with a severity, such as
+/// `"no-explicit-any": "warn"`, to the `include` or `exclude` lists of the
+/// rules. Returns the rules that only report warnings.
+fn resolve_rule_severities(
+  rules: &mut serde_json::Map<String, serde_json::Value>,
+) -> Result<Vec<String>, AnyError> {
+  let names = rules
+    .keys()
+    .filter(|key| !matches!(key.as_str(), "tags" | "include" | "exclude"))
+    .cloned()
+    .collect::<Vec<_>>();
+  let mut warn_rules = Vec::new();
+  for name in names {
+    let list_key = match rules.remove(&name).as_ref().and_then(|v| v.as_str())
+    {
+      Some("error") => "include",
+      Some("warn") => {
+        warn_rules.push(name.clone());
+        "include"
+      }
+      Some("off") => "exclude",
+      _ => bail!(
+        "Expected the severity of rule \"{name}\" to be \"error\", \"warn\" or \"off\"."
+      ),
+    };
+    match rules
+      .entry(list_key)
+      .or_insert_with(|| serde_json::Value::Array(Vec::new()))
+    {
+      serde_json::Value::Array(list) => list.push(name.into()),
+      _ => bail!("Expected \"{list_key}\" to be an array of rule names."),
+    }
+  }
+  Ok(warn_rules)
+}
+
+fn parse_lint_overrides(
+  config: &ConfigFile,
+  value: &serde_json::Value,
+) -> Result<Vec<LintOverride>, AnyError> {
+  parse_overrides(config, value, |i, mut entry| {
+    let (rules, warn_rules) = match entry.remove("rules") {
+      Some(mut value) => {
+        let warn_rules = match &mut value {
+          serde_json::Value::Object(rules) => resolve_rule_severities(rules)
+            .with_context(|| format!("Invalid \"rules\" in override {i}."))?,
+          _ => Vec::new(),
+        };
+        let rules: LintRulesConfig = serde_json::from_value(value)
+          .with_context(|| format!("Invalid \"rules\" in override {i}."))?;
+        (rules, warn_rules)
+      }
+      None => bail!("Missing \"rules\" in override {i}."),
+    };
+    if let Some(key) = entry.keys().next() {
+      bail!("Unknown option \"{key}\" in override {i}.");
+    }
+    Ok((rules, warn_rules))
+  })
+  .map(|overrides| {
+    overrides
+      .into_iter()
+      .map(|(files, (rules, warn_rules))| LintOverride {
+        files,
+        rules,
+        warn_rules: warn_rules.into_iter().collect(),
+      })
+      .collect()
+  })
+}
+
+/// Parses the `include` and `exclude` patterns of each override, which are
+/// relative to the config file, and passes the rest of the entry to
+/// `parse_entry`.
+fn parse_overrides<T>(
+  config: &ConfigFile,
+  value: &serde_json::Value,
+  mut parse_entry: impl FnMut(
+    usize,
+    serde_json::Map<String, serde_json::Value>,
+  ) -> Result<T, AnyError>,
+) -> Result<Vec<(FilePatterns, T)>, AnyError> {
+  let Some(entries) = value.as_array() else {
+    bail!("Expected an array of overrides.");
+  };
+  let Some(base) = config
+    .specifier
+    .to_file_path()
+    .ok()
+    .and_then(|p| p.parent().map(|p| p.to_path_buf()))
+  else {
+    bail!("Overrides are only supported in local config files.");
+  };
+  let mut overrides = Vec::with_capacity(entries.len());
+  for (i, entry) in entries.iter().enumerate() {
+    let Some(entry) = entry.as_object() else {
+      bail!("Expected override {i} to be an object.");
+    };
+    let mut entry = entry.clone();
+    let include: Vec<String> = match entry.remove("include") {
+      Some(value) => serde_json::from_value(value)
+        .with_context(|| format!("Invalid \"include\" in override {i}."))?,
+      None => bail!("Missing \"include\" in override {i}."),
+    };
+    let exclude: Vec<String> = match entry.remove("exclude") {
+      Some(value) => serde_json::from_value(value)
+        .with_context(|| format!("Invalid \"exclude\" in override {i}."))?,
+      None => Vec::new(),
+    };
+    let files = FilePatterns {
+      include: Some(PathOrPatternSet::from_include_relative_path_or_patterns(
+        &base, &include,
+      )?),
+      exclude: PathOrPatternSet::from_exclude_relative_path_or_patterns(
+        &base, &exclude,
+      )?,
+      base: base.clone(),
+    };
+    overrides.push((files, parse_entry(i, entry)?));
+  }
+  Ok(overrides)
+}
+
+/// Parses the plugins, which are either a specifier of the Wasm file
+/// or an object with the specifier and the options of the plugin.
+fn parse_fmt_plugins(
+  config: &ConfigFile,
+  value: &serde_json::Value,
+) -> Result<Vec<FmtPluginConfig>, AnyError> {
+  let Some(entries) = value.as_array() else {
+    bail!("Expected an array of plugins.");
+  };
+  let mut plugins = Vec::with_capacity(entries.len());
+  for (i, entry) in entries.iter().enumerate() {
+    let (specifier, options) = match entry {
+      serde_json::Value::String(specifier) => {
+        (specifier.clone(), serde_json::Map::new())
+      }
+      serde_json::Value::Object(obj) => {
+        let mut obj = obj.clone();
+        let specifier = match obj.remove("specifier") {
+          Some(serde_json::Value::String(specifier)) => specifier,
+          Some(_) => {
+            bail!("Expected \"specifier\" of plugin {i} to be a string.")
+          }
+          None => bail!("Missing \"specifier\" in plugin {i}."),
+        };
+        let options = match obj.remove("options") {
+          Some(serde_json::Value::Object(options)) => options,
+          Some(_) => {
+            bail!("Expected \"options\" of plugin {i} to be an object.")
+          }
+          None => serde_json::Map::new(),
+        };
+        if let Some(key) = obj.keys().next() {
+          bail!("Unknown key \"{key}\" in plugin {i}.");
+        }
+        (specifier, options)
+      }
+      _ => bail!("Expected plugin {i} to be a string or an object."),
+    };
+    let specifier = config
+      .specifier
+      .join(&specifier)
+      .with_context(|| format!("Invalid specifier of plugin {i}."))?;
+    plugins.push(FmtPluginConfig { specifier, options });
+  }
+  Ok(plugins)
+}
+
+/// Parses the plugins, which are specifiers of JavaScript or Wasm modules
+/// relative to the configuration file.
+fn parse_lint_plugins(
+  config: &ConfigFile,
+  value: &serde_json::Value,
+) -> Result<Vec<ModuleSpecifier>, AnyError> {
+  let Some(entries) = value.as_array() else {
+    bail!("Expected an array of plugins.");
+  };
+  entries
+    .iter()
+    .enumerate()
+    .map(|(i, entry)| {
+      let Some(specifier) = entry.as_str() else {
+        bail!("Expected plugin {i} to be a string.");
+      };
+      config
+        .specifier
+        .join(specifier)
+        .with_context(|| format!("Invalid specifier of plugin {i}."))
+    })
+    .collect()
+}
+
+/// Gets the test configuration along with the `test.setup` and
+/// `test.teardown` modules, which deno_config doesn't understand. The
+/// `test.coverage` configuration is resolved by `to_coverage_config`.
+pub fn to_test_config_with_extras(
+  config: &ConfigFile,
+) -> Result<(Option<TestConfig>, TestConfigExtras), AnyError> {
+  let Some(test) = config.json.test.as_ref().and_then(|test| test.as_object())
+  else {
+    return config.to_test_config().map(|c| (c, Default::default()));
+  };
+  let resolve_module = |key: &str| -> Result<_, AnyError> {
+    let Some(value) = test.get(key) else {
+      return Ok(None);
+    };
+    let Some(specifier) = value.as_str() else {
+      bail!("Expected \"test.{key}\" to be a string.");
+    };
+    let specifier = config
+      .specifier
+      .join(specifier)
+      .with_context(|| format!("Invalid specifier of \"test.{key}\"."))?;
+    Ok(Some(specifier))
+  };
+  let extras = TestConfigExtras {
+    setup: resolve_module("setup")?,
+    teardown: resolve_module("teardown")?,
+  };
+  let mut config = config.clone();
+  if let Some(serde_json::Value::Object(test)) = config.json.test.as_mut() {
+    test.remove("setup");
+    test.remove("teardown");
+    test.remove("coverage");
+  }
+  let mut test_config = config.to_test_config()?;
+  // deno_config resolves specifiers like `npm:chalk` as paths
+  if let Some(test_config) = &mut test_config {
+    let files = &mut test_config.files;
+    if let Some(include) = test.get("include") {
+      let include: Vec<String> = serde_json::from_value(include.clone())
+        .context("Invalid \"test.include\".")?;
+      files.include = Some(include_path_or_patterns(&files.base, &include)?);
+    }
+    if let Some(exclude) = test.get("exclude") {
+      let exclude: Vec<String> = serde_json::from_value(exclude.clone())
+        .context("Invalid \"test.exclude\".")?;
+      let specifiers = exclude_path_or_patterns(&files.base, &exclude)?
+        .into_path_or_patterns()
+        .into_iter()
+        .filter(|p| matches!(p, PathOrPattern::RemoteUrl(_)));
+      let mut exclude =
+        std::mem::take(&mut files.exclude).into_path_or_patterns();
+      exclude.extend(specifiers);
+      files.exclude = PathOrPatternSet::new(exclude);
+    }
+  }
+  Ok((test_config, extras))
+}
+
+/// Gets the `test.coverage` configuration, which deno_config doesn't
+/// understand.
+pub fn to_coverage_config(
+  config: &ConfigFile,
+) -> Result<CoverageConfig, AnyError> {
+  let Some(coverage) = config
+    .json
+    .test
+    .as_ref()
+    .and_then(|test| test.get("coverage"))
+  else {
+    return Ok(Default::default());
+  };
+  let Some(coverage) = coverage.as_object() else {
+    bail!("Expected \"test.coverage\" to be an object.");
+  };
+  let resolve_threshold = |key: &str| -> Result<Option<u8>, AnyError> {
+    let Some(value) = coverage.get(key) else {
+      return Ok(None);
+    };
+    match value.as_u64() {
+      Some(percent) if percent <= 100 => Ok(Some(percent as u8)),
+      _ => bail!(
+        "Expected \"test.coverage.{key}\" to be a percentage between 0 and 100."
+      ),
+    }
+  };
+  let resolve_globs = |key: &str| -> Result<Option<Vec<String>>, AnyError> {
+    let Some(value) = coverage.get(key) else {
+      return Ok(None);
+    };
+    serde_json::from_value(value.clone()).with_context(|| {
+      format!("Expected \"test.coverage.{key}\" to be an array of globs.")
+    })
+  };
+  let include = resolve_globs("include")?;
+  let exclude = resolve_globs("exclude")?;
+  let files = if include.is_some() || exclude.is_some() {
+    let Some(base) = config
+      .specifier
+      .to_file_path()
+      .ok()
+      .and_then(|p| p.parent().map(|p| p.to_path_buf()))
+    else {
+      bail!("Coverage globs are only supported in local config files.");
+    };
+    Some(FilePatterns {
+      include: include
+        .map(|include| {
+          PathOrPatternSet::from_include_relative_path_or_patterns(
+            &base, &include,
+          )
+        })
+        .transpose()?,
+      exclude: PathOrPatternSet::from_exclude_relative_path_or_patterns(
+        &base,
+        &exclude.unwrap_or_default(),
+      )?,
+      base,
+    })
+  } else {
+    None
+  };
+  Ok(CoverageConfig {
+    thresholds: CoverageThresholds {
+      lines: resolve_threshold("lines")?,
+      branches: resolve_threshold("branches")?,
+    },
+    files,
+  })
+}
+
+/// The tasks configuration with the options of the tasks defined as objects.
+pub type TasksConfigWithOptions = (
+  IndexMap<String, deno_config::Task>,
+  HashMap<String, TaskOptions>,
+);
+
+/// Gets the tasks configuration along with the options of the tasks that
+/// are defined as an object, such as `{ "command": "...", "cwd": "..." }`.
+/// These tasks are replaced with their command before resolving the rest of
+/// the configuration because deno_config only understands string tasks.
+pub fn to_tasks_config_with_options(
+  config: &ConfigFile,
+) -> Result<TasksConfigWithOptions, AnyError> {
+  let has_object_tasks = config
+    .json
+    .tasks
+    .as_ref()
+    .and_then(|tasks| tasks.as_object())
+    .is_some_and(|tasks| tasks.values().any(|task| task.is_object()));
+  if !has_object_tasks {
+    return config
+      .resolve_tasks_config()
+      .map(|tasks| (tasks, HashMap::new()));
+  }
+  let base = config
+    .specifier
+    .to_file_path()
+    .ok()
+    .and_then(|p| p.parent().map(|p| p.to_path_buf()));
+  let mut config = config.clone();
+  let mut task_options = HashMap::new();
+  if let Some(serde_json::Value::Object(tasks)) = config.json.tasks.as_mut() {
+    for (name, task) in tasks.iter_mut() {
+      let serde_json::Value::Object(obj) = task else {
+        continue;
+      };
+      let (command, options) = parse_task_object(base.as_ref(), obj)
+        .with_context(|| format!("Failed to parse task '{name}'."))?;
+      *task = serde_json::Value::String(command);
+      task_options.insert(name.clone(), options);
+    }
+  }
+  Ok((config.resolve_tasks_config()?, task_options))
+}
+
+fn parse_task_object(
+  base: Option<&PathBuf>,
+  obj: &serde_json::Map<String, serde_json::Value>,
+) -> Result<(String, TaskOptions), AnyError> {
+  let mut options = TaskOptions::default();
+  let mut command = None;
+  for (key, value) in obj {
+    match key.as_str() {
+      "command" => match value {
+        serde_json::Value::String(value) => command = Some(value.clone()),
+        _ => bail!("Expected \"command\" to be a string."),
+      },
+      "cwd" => {
+        let serde_json::Value::String(value) = value else {
+          bail!("Expected \"cwd\" to be a string.");
+        };
+        let Some(base) = base else {
+          bail!("\"cwd\" is only supported in local config files.");
+        };
+        options.cwd = Some(base.join(value));
+      }
+      "env" => {
+        let Some(env) = value.as_object() else {
+          bail!("Expected \"env\" to be an object.");
+        };
+        for (name, value) in env {
+          let serde_json::Value::String(value) = value else {
+            bail!("Expected the value of env var '{name}' to be a string.");
+          };
+          options.env.insert(name.clone(), value.clone());
+        }
+      }
+      _ => bail!("Unknown key \"{key}\"."),
+    }
+  }
+  let Some(command) = command else {
+    bail!("Missing \"command\".");
+  };
+  Ok((command, options))
+}
+
 fn imports_values(value: Option<&serde_json::Value>) -> Vec<&String> {
   let Some(obj) = value.and_then(|v| v.as_object()) else {
     return Vec::new();
@@ -28,6 +28,7 @@ use std::ffi::OsString;
 use std::net::SocketAddr;
 use std::num::NonZeroU16;
 use std::num::NonZeroU32;
+use std::num::NonZeroU64;
 use std::num::NonZeroU8;
 use std::num::NonZeroUsize;
 use std::path::Path;
@@ -44,6 +45,8 @@ use super::DENO_FUTURE;
 pub struct FileFlags {
   pub ignore: Vec<String>,
   pub include: Vec<String>,
+  /// Skip the files ignored by `.gitignore` files.
+  pub gitignore: bool,
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -51,20 +54,75 @@ pub struct AddFlags {
   pub packages: Vec<String>,
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RemoveFlags {
+  pub packages: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BenchProfileFormat {
+  CpuProfile,
+  Flamegraph,
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct BenchFlags {
   pub files: FileFlags,
   pub filter: Option<String>,
+  /// Only run the benchmarks of this group.
+  pub group: Option<String>,
   pub json: bool,
+  /// Write the JSON report to this file instead of stdout.
+  pub json_path: Option<String>,
   pub no_run: bool,
+  pub profile: Option<BenchProfileFormat>,
+  pub profile_dir: Option<String>,
+  /// The warmup time of each benchmark in milliseconds.
+  pub warmup_time: Option<u64>,
+  /// The minimum number of measured iterations of each benchmark.
+  pub min_iterations: Option<NonZeroU64>,
+  /// The minimum measurement time of each benchmark in milliseconds.
+  pub max_time: Option<u64>,
+  /// Save the results as the baseline with this name.
+  pub save_baseline: Option<String>,
+  /// Compare the results with the baseline with this name.
+  pub compare_baseline: Option<String>,
+  /// The percentage a benchmark may get slower than its baseline.
+  pub regression_threshold: Option<u32>,
+  /// Also run the benchmarks with this Deno executable to compare them.
+  pub against: Option<String>,
   pub watch: Option<WatchFlags>,
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BundleSourceMap {
+  /// Write the source map to a `.map` file next to the bundle.
+  External,
+  Inline,
+  None,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BundleFormat {
+  #[default]
+  Esm,
+  /// A classic script that runs the bundle in an immediately invoked function
+  /// expression.
+  Iife,
+  Cjs,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BundleFlags {
   pub source_file: String,
   pub out_file: Option<String>,
   pub watch: Option<WatchFlags>,
+  /// Overrides the source map options of the compiler options.
+  pub sourcemap: Option<BundleSourceMap>,
+  pub no_sources_content: bool,
+  pub format: BundleFormat,
+  /// The global variable that the exports of an IIFE bundle are assigned to.
+  pub global_name: Option<String>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -75,6 +133,7 @@ pub struct CacheFlags {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CheckFlags {
   pub files: Vec<String>,
+  pub watch: Option<WatchFlags>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -85,6 +144,8 @@ pub struct CompileFlags {
   pub target: Option<String>,
   pub no_terminal: bool,
   pub include: Vec<String>,
+  pub icon: Option<String>,
+  pub exe_metadata: Option<String>,
 }
 
 impl CompileFlags {
@@ -108,6 +169,7 @@ pub enum CoverageType {
   Detailed,
   Lcov,
   Html,
+  Cobertura,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
@@ -117,6 +179,12 @@ pub struct CoverageFlags {
   pub include: Vec<String>,
   pub exclude: Vec<String>,
   pub r#type: CoverageType,
+  /// Fail when the coverage is below the thresholds.
+  pub check: bool,
+  /// The minimum percentage of covered lines with `--check`.
+  pub line_threshold: Option<u8>,
+  /// The minimum percentage of covered branches with `--check`.
+  pub branch_threshold: Option<u8>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -137,14 +205,23 @@ pub struct DocHtmlFlag {
   pub output: String,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DocMarkdownFlag {
+  pub output: String,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct DocFlags {
   pub private: bool,
   pub json: bool,
   pub lint: bool,
   pub html: Option<DocHtmlFlag>,
+  pub markdown: Option<DocMarkdownFlag>,
   pub source_files: DocSourceFileFlag,
   pub filter: Option<String>,
+  pub search_index: Option<String>,
+  pub include_tags: Vec<String>,
+  pub exclude_tags: Vec<String>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -163,6 +240,17 @@ pub struct FmtFlags {
   pub single_quote: Option<bool>,
   pub prose_wrap: Option<String>,
   pub no_semicolons: Option<bool>,
+  /// Only format the files changed since this git ref.
+  pub changed: Option<String>,
+  /// Only apply the formatting changes within these lines.
+  pub range: Option<FmtRange>,
+  pub diff_style: FmtDiffStyle,
+  /// Write the patch to this file instead of stdout.
+  pub diff_output: Option<String>,
+  /// Also format CSS, SCSS and LESS files.
+  pub unstable_css: bool,
+  /// Also format YAML files.
+  pub unstable_yaml: bool,
   pub watch: Option<WatchFlags>,
 }
 
@@ -173,6 +261,38 @@ impl FmtFlags {
   }
 }
 
+/// How `deno fmt --check` reports the changes to the unformatted files.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FmtDiffStyle {
+  /// Colored diffs with line numbers meant to be read.
+  #[default]
+  Pretty,
+  /// A unified diff that can be applied with `git apply`.
+  Patch,
+}
+
+/// A range of lines, which are 1-indexed and inclusive.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FmtRange {
+  pub start_line: usize,
+  pub end_line: usize,
+}
+
+fn fmt_range_parser(value: &str) -> Result<FmtRange, String> {
+  let invalid =
+    || format!("Invalid range '{value}'. Expected <start line>:<end line>.");
+  let (start, end) = value.split_once(':').ok_or_else(invalid)?;
+  let start_line = start.parse::<usize>().map_err(|_| invalid())?;
+  let end_line = end.parse::<usize>().map_err(|_| invalid())?;
+  if start_line == 0 || end_line < start_line {
+    return Err(invalid());
+  }
+  Ok(FmtRange {
+    start_line,
+    end_line,
+  })
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct InitFlags {
   pub dir: Option<String>,
@@ -182,6 +302,8 @@ pub struct InitFlags {
 pub struct InfoFlags {
   pub json: bool,
   pub file: Option<String>,
+  pub unused: bool,
+  pub why: Option<String>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -237,11 +359,22 @@ pub struct LintFlags {
   pub files: FileFlags,
   pub rules: bool,
   pub fix: bool,
+  pub fix_dry_run: bool,
+  /// The name of a rule to print the documentation of.
+  pub explain: Option<String>,
   pub maybe_rules_tags: Option<Vec<String>>,
   pub maybe_rules_include: Option<Vec<String>>,
   pub maybe_rules_exclude: Option<Vec<String>>,
   pub json: bool,
   pub compact: bool,
+  /// The format of the output, which is one of `pretty`, `json`, `compact`
+  /// or `sarif`.
+  pub output: Option<String>,
+  pub max_warnings: Option<usize>,
+  /// The path of the baseline file, which defaults to
+  /// `deno-lint-baseline.json` next to the config file.
+  pub baseline: Option<String>,
+  pub write_baseline: bool,
   pub watch: Option<WatchFlags>,
 }
 
@@ -329,6 +462,26 @@ pub enum TestReporterConfig {
   Tap,
 }
 
+/// One of the shards the test modules are split across, where the index is
+/// 1-indexed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TestShard {
+  pub index: usize,
+  pub count: usize,
+}
+
+fn test_shard_parser(value: &str) -> Result<TestShard, String> {
+  let invalid =
+    || format!("Invalid shard '{value}'. Expected <index>/<count>, ex. 3/8.");
+  let (index, count) = value.split_once('/').ok_or_else(invalid)?;
+  let index = index.parse::<usize>().map_err(|_| invalid())?;
+  let count = count.parse::<usize>().map_err(|_| invalid())?;
+  if index == 0 || index > count {
+    return Err(invalid());
+  }
+  Ok(TestShard { index, count })
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct TestFlags {
   pub doc: bool,
@@ -344,6 +497,22 @@ pub struct TestFlags {
   pub watch: Option<WatchFlags>,
   pub reporter: TestReporterConfig,
   pub junit_path: Option<String>,
+  pub shard: Option<TestShard>,
+  pub shard_tests: bool,
+  pub retries: usize,
+  pub timeout: Option<u64>,
+  pub update_snapshots: bool,
+  /// Run each test module in a child process.
+  pub isolate_processes: bool,
+  /// The test module run by a child process of `--isolate-processes`.
+  pub child_process_module: Option<String>,
+  pub list: bool,
+  pub json: bool,
+  /// Print the N slowest tests and steps after the summary.
+  pub report_slow: Option<NonZeroUsize>,
+  /// The paths and lines of the `path:line` arguments, which only run the
+  /// test at that line of the module.
+  pub lines: Vec<(String, u32)>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -353,6 +522,8 @@ pub struct UpgradeFlags {
   pub canary: bool,
   pub version: Option<String>,
   pub output: Option<String>,
+  pub download_only: bool,
+  pub targets: Vec<String>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -360,6 +531,12 @@ pub struct VendorFlags {
   pub specifiers: Vec<String>,
   pub output_path: Option<String>,
   pub force: bool,
+  pub trash: bool,
+  pub merge_config: bool,
+  pub update: bool,
+  pub prune: bool,
+  pub only: Vec<String>,
+  pub check: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -400,6 +577,7 @@ pub enum DenoSubcommand {
   Upgrade(UpgradeFlags),
   Vendor(VendorFlags),
   Publish(PublishFlags),
+  Remove(RemoveFlags),
 }
 
 impl DenoSubcommand {
@@ -467,6 +645,14 @@ impl Default for TypeCheckMode {
   }
 }
 
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LogFormat {
+  #[default]
+  Text,
+  /// One JSON object per log record and line.
+  Json,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum CaData {
   /// The string is a file path
@@ -518,6 +704,7 @@ pub struct Flags {
   pub lock_write: bool,
   pub lock: Option<String>,
   pub log_level: Option<Level>,
+  pub log_format: LogFormat,
   pub no_remote: bool,
   pub no_lock: bool,
   pub no_npm: bool,
@@ -833,6 +1020,76 @@ impl Flags {
     args
   }
 
+  /// Return the list of runtime arguments, like the permissions, the config
+  /// file and the unstable features, that are equivalent to the ones used to
+  /// create `self`, for running a subcommand in a child process.
+  pub fn to_runtime_args(&self) -> Vec<String> {
+    let mut args = self.to_permission_args();
+    match &self.config_flag {
+      ConfigFlag::Discover => {}
+      ConfigFlag::Path(path) => args.push(format!("--config={path}")),
+      ConfigFlag::Disabled => args.push("--no-config".to_string()),
+    }
+    if let Some(import_map_path) = &self.import_map_path {
+      args.push(format!("--import-map={import_map_path}"));
+    }
+    if let Some(lock) = &self.lock {
+      args.push(format!("--lock={lock}"));
+    }
+    if self.no_lock {
+      args.push("--no-lock".to_string());
+    }
+    if let Some(node_modules_dir) = self.node_modules_dir {
+      args.push(format!("--node-modules-dir={node_modules_dir}"));
+    }
+    if let Some(vendor) = self.vendor {
+      args.push(format!("--vendor={vendor}"));
+    }
+    if let Some(CaData::File(ca_file)) = &self.ca_data {
+      args.push(format!("--cert={ca_file}"));
+    }
+    if let Some(env_file) = &self.env_file {
+      args.push(format!("--env={env_file}"));
+    }
+    if let Some(location) = &self.location {
+      args.push(format!("--location={location}"));
+    }
+    if let Some(seed) = self.seed {
+      args.push(format!("--seed={seed}"));
+    }
+    if !self.v8_flags.is_empty() {
+      args.push(format!("--v8-flags={}", self.v8_flags.join(",")));
+    }
+    match self.log_level {
+      Some(Level::Error) => args.push("--quiet".to_string()),
+      Some(Level::Debug) => args.push("--log-level=debug".to_string()),
+      _ => {}
+    }
+    for (enabled, arg) in [
+      (self.cached_only, "--cached-only"),
+      (self.no_remote, "--no-remote"),
+      (self.no_npm, "--no-npm"),
+      (self.unstable_config.legacy_flag_enabled, "--unstable"),
+      (
+        self.unstable_config.bare_node_builtins,
+        "--unstable-bare-node-builtins",
+      ),
+      (self.unstable_config.byonm, "--unstable-byonm"),
+      (
+        self.unstable_config.sloppy_imports,
+        "--unstable-sloppy-imports",
+      ),
+    ] {
+      if enabled {
+        args.push(arg.to_string());
+      }
+    }
+    for feature in &self.unstable_config.features {
+      args.push(format!("--unstable-{feature}"));
+    }
+    args
+  }
+
   /// Extract path arguments for config search paths.
   /// If it returns Some(vec), the config should be discovered
   /// from the passed `current_dir` after trying to discover from each entry in
@@ -918,7 +1175,7 @@ impl Flags {
       }
       Add(_) | Bundle(_) | Completions(_) | Doc(_) | Fmt(_) | Init(_)
       | Uninstall(_) | Jupyter(_) | Lsp | Lint(_) | Types | Upgrade(_)
-      | Vendor(_) => None,
+      | Vendor(_) | Remove(_) => None,
       Install(_) => {
         if *DENO_FUTURE {
           Some(current_dir.to_path_buf())
@@ -1003,6 +1260,14 @@ impl Flags {
         }),
       ..
     })
+    | DenoSubcommand::Check(CheckFlags {
+      watch:
+        Some(WatchFlags {
+          exclude: excluded_paths,
+          ..
+        }),
+      ..
+    })
     | DenoSubcommand::Lint(LintFlags {
       watch:
         Some(WatchFlags {
@@ -1065,6 +1330,9 @@ static ENV_VARIABLES_HELP: &str = color_print::cstr!(
     <g>DENO_NO_UPDATE_CHECK</> Set to disable checking if a newer Deno version is
                          available
 
+    <g>DENO_NPM_TRASH</>       Set to move directories in node_modules that are
+                         replaced to the trash instead of deleting them
+
     <g>DENO_TLS_CA_STORE</>    Comma-separated list of order dependent certificate
                          stores. Possible values: "system", "mozilla".
                          Defaults to "mozilla".
@@ -1147,6 +1415,13 @@ pub fn flags_from_vec(args: Vec<OsString>) -> clap::error::Result<Flags> {
       _ => unreachable!(),
     };
   }
+  if let Some(log_format) = matches.get_one::<String>("log-format") {
+    flags.log_format = match log_format.as_str() {
+      "text" => LogFormat::Text,
+      "json" => LogFormat::Json,
+      _ => unreachable!(),
+    };
+  }
 
   if let Some((subcommand, mut m)) = matches.remove_subcommand() {
     match subcommand.as_str() {
@@ -1177,6 +1452,7 @@ pub fn flags_from_vec(args: Vec<OsString>) -> clap::error::Result<Flags> {
       "upgrade" => upgrade_parse(&mut flags, &mut m),
       "vendor" => vendor_parse(&mut flags, &mut m),
       "publish" => publish_parse(&mut flags, &mut m),
+      "remove" => remove_parse(&mut flags, &mut m),
       _ => unreachable!(),
     }
   } else {
@@ -1296,6 +1572,16 @@ fn clap_root() -> Command {
         .value_parser(["trace", "debug", "info"])
         .global(true),
     )
+    .arg(
+      Arg::new("log-format")
+        .long("log-format")
+        .help("Set the format of log output")
+        .long_help(
+          "Set the format of log output. The json format outputs every log record as a JSON object on its own line, for processing by other tools.",
+        )
+        .value_parser(["text", "json"])
+        .global(true),
+    )
     .arg(
       Arg::new("quiet")
         .short('q')
@@ -1331,6 +1617,7 @@ fn clap_root() -> Command {
         .subcommand(lsp_subcommand())
         .subcommand(lint_subcommand())
         .subcommand(publish_subcommand())
+        .subcommand(remove_subcommand())
         .subcommand(repl_subcommand())
         .subcommand(task_subcommand())
         .subcommand(test_subcommand())
@@ -1353,6 +1640,12 @@ fn add_subcommand() -> Command {
 You can add multiple dependencies at once:
 
   deno add @std/path @std/assert
+
+Use a different name for the import by prefixing the package with an alias:
+
+  deno add path@jsr:@std/path
+
+The lockfile is updated with the selected versions as well.
 ",
     )
     .defer(|cmd| {
@@ -1366,6 +1659,30 @@ You can add multiple dependencies at once:
     })
 }
 
+fn remove_subcommand() -> Command {
+  Command::new("remove")
+    .about("Remove dependencies")
+    .long_about(
+      "Remove dependencies from the configuration file and lockfile.
+
+  deno remove @std/path
+
+Dependencies can be removed by their import name or package name:
+
+  deno remove path jsr:@std/assert
+",
+    )
+    .defer(|cmd| {
+      cmd.arg(
+        Arg::new("packages")
+          .help("List of packages to remove")
+          .required(true)
+          .num_args(1..)
+          .action(ArgAction::Append),
+      )
+    })
+}
+
 fn bench_subcommand() -> Command {
   Command::new("bench")
     .about("Run benchmarks")
@@ -1380,7 +1697,15 @@ and report results to standard output:
 Directory arguments are expanded to all contained files matching the
 glob {*_,*.,}bench.{js,mjs,ts,mts,jsx,tsx}:
 
-  deno bench src/",
+  deno bench src/
+
+Write a V8 CPU profile of each benchmark into the 'bench_profile/' directory:
+
+  deno bench --profile src/
+
+Write folded stacks suitable for flamegraph tools instead:
+
+  deno bench --profile=flamegraph --profile-dir=profiles src/",
     )
     .defer(|cmd| {
       runtime_args(cmd, true, false)
@@ -1391,6 +1716,14 @@ glob {*_,*.,}bench.{js,mjs,ts,mts,jsx,tsx}:
             .action(ArgAction::SetTrue)
             .help("UNSTABLE: Output benchmark result in JSON format"),
         )
+        .arg(
+          Arg::new("json-path")
+            .long("json-path")
+            .require_equals(true)
+            .value_name("PATH")
+            .value_hint(ValueHint::FilePath)
+            .help("UNSTABLE: Write the JSON benchmark result to PATH instead of stdout. Implies --json"),
+        )
         .arg(
           Arg::new("ignore")
             .long("ignore")
@@ -1407,6 +1740,12 @@ glob {*_,*.,}bench.{js,mjs,ts,mts,jsx,tsx}:
               "Run benchmarks with this string or pattern in the bench name",
             ),
         )
+        .arg(
+          Arg::new("group")
+            .long("group")
+            .value_name("NAME")
+            .help("Only run the benchmarks of the group with this name"),
+        )
         .arg(
           Arg::new("files")
             .help("List of file names to run")
@@ -1419,6 +1758,106 @@ glob {*_,*.,}bench.{js,mjs,ts,mts,jsx,tsx}:
             .help("Cache bench modules, but don't run benchmarks")
             .action(ArgAction::SetTrue),
         )
+        .arg(
+          Arg::new("profile")
+            .long("profile")
+            .num_args(0..=1)
+            .require_equals(true)
+            .default_missing_value("cpuprofile")
+            .value_parser(["cpuprofile", "flamegraph"])
+            .help("Write a CPU profile of each benchmark. Defaults to 'cpuprofile'.")
+            .long_help(
+              "Write a CPU profile of each benchmark. Use 'cpuprofile' to write
+V8 '.cpuprofile' files that can be loaded in Chrome DevTools, or 'flamegraph'
+to write folded stacks for flamegraph tools. Defaults to 'cpuprofile'.",
+            ),
+        )
+        .arg(
+          Arg::new("profile-dir")
+            .long("profile-dir")
+            .requires("profile")
+            .require_equals(true)
+            .value_name("DIR")
+            .value_hint(ValueHint::DirPath)
+            .help("Directory to write benchmark profiles to. Defaults to 'bench_profile/'."),
+        )
+        .arg(
+          Arg::new("warmup-time")
+            .long("warmup-time")
+            .require_equals(true)
+            .value_name("MS")
+            .value_parser(value_parser!(u64))
+            .help("Run each benchmark for MS milliseconds before measuring it. Defaults to 10."),
+        )
+        .arg(
+          Arg::new("min-iterations")
+            .long("min-iterations")
+            .require_equals(true)
+            .value_name("N")
+            .value_parser(value_parser!(NonZeroU64))
+            .help("Measure at least N iterations of each benchmark. Defaults to 10."),
+        )
+        .arg(
+          Arg::new("max-time")
+            .long("max-time")
+            .require_equals(true)
+            .value_name("MS")
+            .value_parser(value_parser!(u64))
+            .help("Measure each benchmark for at least MS milliseconds. Defaults to 500.")
+            .long_help(
+              "Measure each benchmark for at least MS milliseconds, which gives noisy
+benchmarks a longer measurement window. A benchmark is measured until both
+this time has passed and the minimum iterations ran. Benchmarks can
+override this with the `maxTime` option. Defaults to 500.",
+            ),
+        )
+        .arg(
+          Arg::new("save-baseline")
+            .long("save-baseline")
+            .require_equals(true)
+            .value_name("NAME")
+            .value_parser(parse_bench_baseline_name)
+            .help("Save the results as a baseline with the given name to compare later runs with"),
+        )
+        .arg(
+          Arg::new("compare")
+            .long("compare")
+            .require_equals(true)
+            .value_name("NAME")
+            .value_parser(parse_bench_baseline_name)
+            .help("Compare the results with a baseline saved with --save-baseline")
+            .long_help(
+              "Compare the results with a baseline saved with --save-baseline.
+The run fails when a benchmark is significantly slower than its
+baseline by more than the --regression-threshold percentage.",
+            ),
+        )
+        .arg(
+          Arg::new("regression-threshold")
+            .long("regression-threshold")
+            .require_equals(true)
+            .value_name("PERCENT")
+            .requires("compare")
+            .value_parser(value_parser!(u32))
+            .help("The percentage a benchmark may be slower than its baseline before failing the run. Defaults to 10."),
+        )
+        .arg(
+          Arg::new("against")
+            .long("against")
+            .require_equals(true)
+            .value_name("DENO")
+            .value_hint(ValueHint::ExecutablePath)
+            .conflicts_with_all(["json", "json-path", "watch"])
+            .help("Also run the benchmarks with another Deno executable and compare the results")
+            .long_help(
+              "Also run the benchmarks with another Deno executable and print
+the results of both executables side by side, for example to validate
+an upgrade of Deno. The other executable writes its results with
+--json-path, so it has to support that flag:
+
+  deno bench --against=/usr/local/bin/deno-1.45 src/",
+            ),
+        )
         .arg(watch_arg(false))
         .arg(watch_exclude_arg())
         .arg(no_clear_screen_arg())
@@ -1452,6 +1891,48 @@ If no output file is given, the output is written to standard output:
             .value_hint(ValueHint::FilePath),
         )
         .arg(Arg::new("out_file").value_hint(ValueHint::FilePath))
+        .arg(
+          Arg::new("sourcemap")
+            .long("sourcemap")
+            .value_name("MODE")
+            .help("Emit a source map for the bundle")
+            .long_help(
+              "Emits a source map for the bundle, overriding the sourceMap and
+    inlineSourceMap compiler options. With 'external' the source map is
+    written to a .map file next to the output file, which is referenced by a
+    sourceMappingURL comment.",
+            )
+            .value_parser(["external", "inline", "none"])
+            .require_equals(true),
+        )
+        .arg(
+          Arg::new("format")
+            .long("format")
+            .value_name("FORMAT")
+            .help("Module format of the bundle")
+            .long_help(
+              "The module format of the bundle. 'iife' bundles into a classic
+    script, whose exports can be assigned to a global variable with
+    --global-name, and 'cjs' bundles into a CommonJS module.",
+            )
+            .value_parser(["esm", "iife", "cjs"])
+            .default_value("esm")
+            .require_equals(true),
+        )
+        .arg(
+          Arg::new("global-name")
+            .long("global-name")
+            .value_name("NAME")
+            .help("Assign the exports of an IIFE bundle to a global variable")
+            .require_equals(true),
+        )
+        .arg(
+          Arg::new("no-sources-content")
+            .long("no-sources-content")
+            .help("Do not embed the source code in the source map")
+            .requires("sourcemap")
+            .action(ArgAction::SetTrue),
+        )
         .arg(watch_arg(false))
         .arg(watch_exclude_arg())
         .arg(no_clear_screen_arg())
@@ -1491,6 +1972,10 @@ fn check_subcommand() -> Command {
 
   deno check jsr:@std/http/file-server
 
+Type-check again whenever one of the local modules changes:
+
+  deno check --watch main.ts
+
 Unless --reload is specified, this command will not re-download already cached dependencies.",
       )
     .defer(|cmd| compile_args_without_check_args(cmd).arg(
@@ -1515,6 +2000,9 @@ Unless --reload is specified, this command will not re-download already cached d
           .required(true)
           .value_hint(ValueHint::FilePath),
       )
+      .arg(watch_arg(false))
+      .arg(watch_exclude_arg())
+      .arg(no_clear_screen_arg())
     )
 }
 
@@ -1541,6 +2029,9 @@ Cross-compiling to different target architectures is supported using the
 `--target` flag. On the first invocation with deno will download proper
 binary and cache it in $DENO_DIR. The aarch64-apple-darwin target is not
 supported in canary.
+
+When targeting Windows, the icon and the version information of the
+executable can be set with the `--icon` and `--exe-metadata` flags.
 ",
     )
     .defer(|cmd| {
@@ -1553,8 +2044,11 @@ supported in canary.
           .long_help(
             "Includes an additional module in the compiled executable's module
     graph. Use this flag if a dynamically imported module or a web worker main
-    module fails to load in the executable. This flag can be passed multiple
-    times, to include multiple additional modules.",
+    module fails to load in the executable. Passing a directory includes all
+    the modules in it. Workers spawned with
+    `new Worker(new URL(\"./worker.ts\", import.meta.url))` are included
+    automatically. This flag can be passed multiple times, to include multiple
+    additional modules.",
           )
           .action(ArgAction::Append)
           .value_hint(ValueHint::FilePath),
@@ -1583,8 +2077,33 @@ supported in canary.
         Arg::new("no-terminal")
           .long("no-terminal")
           .help("Hide terminal on Windows")
+          .long_help(
+            "Marks the executable as a GUI application on Windows, so that no
+    terminal window is opened when it is started. Its output is discarded
+    unless it is redirected when the executable is started from a terminal.",
+          )
           .action(ArgAction::SetTrue),
       )
+      .arg(
+        Arg::new("icon")
+          .long("icon")
+          .value_name("PATH")
+          .help("Set the icon of the executable on Windows (.ico)")
+          .value_hint(ValueHint::FilePath),
+      )
+      .arg(
+        Arg::new("exe-metadata")
+          .long("exe-metadata")
+          .value_name("PATH")
+          .help("Set the version information of the executable on Windows")
+          .long_help(
+            "Sets the version information of the executable on Windows from a
+    JSON file with the optional keys productName, productVersion,
+    fileDescription, fileVersion, companyName, legalCopyright,
+    originalFilename, internalName and comments.",
+          )
+          .value_hint(ValueHint::FilePath),
+      )
       .arg(executable_ext_arg())
       .arg(env_file_arg())
       .arg(script_arg().required(true).trailing_var_arg(true))
@@ -1641,9 +2160,17 @@ Write a report using the lcov format:
 
   deno coverage --lcov --output=cov.lcov cov_profile/
 
-Generate html reports from lcov:
+Write a report using the HTML format to the html_cov/ directory:
 
-  genhtml -o html_cov cov.lcov
+  deno coverage --html=html_cov cov_profile/
+
+Write a report using the Cobertura XML format:
+
+  deno coverage --cobertura=coverage.xml cov_profile/
+
+Fail when less than 80% of the lines or 60% of the branches are covered:
+
+  deno coverage --check --lines=80 --branches=60 cov_profile/
 ",
     )
     .defer(|cmd| {
@@ -1700,10 +2227,17 @@ Generate html reports from lcov:
         .arg(
           Arg::new("html")
             .long("html")
-            .help(
-              "Output coverage report in HTML format in the given directory",
+            .num_args(0..=1)
+            .require_equals(true)
+            .value_name("DIR")
+            .value_parser(value_parser!(String))
+            .help("Output coverage report in HTML format in the given directory")
+            .long_help(
+              "Writes the coverage report in HTML format to the given directory.
+    If no directory is specified then the report is written to the 'html/'
+    directory in the coverage directory.",
             )
-            .action(ArgAction::SetTrue),
+            .value_hint(ValueHint::DirPath),
         )
         .arg(
           Arg::new("detailed")
@@ -1711,6 +2245,50 @@ Generate html reports from lcov:
             .help("Output coverage report in detailed format in the terminal.")
             .action(ArgAction::SetTrue),
         )
+        .arg(
+          Arg::new("cobertura")
+            .long("cobertura")
+            .num_args(0..=1)
+            .require_equals(true)
+            .value_name("FILE")
+            .value_parser(value_parser!(String))
+            .conflicts_with_all(["lcov", "html", "detailed"])
+            .help("Output coverage report in Cobertura XML format")
+            .long_help(
+              "Exports the coverage report in Cobertura XML format to the given file.
+    If no file is specified then the report is written to stdout.",
+            )
+            .value_hint(ValueHint::FilePath),
+        )
+        .arg(
+          Arg::new("check")
+            .long("check")
+            .help("Fail when the coverage is below the thresholds")
+            .long_help(
+              "Exits with a non-zero code when the coverage is below the thresholds
+of --lines and --branches, or of the \"test.coverage\" key of the
+configuration file.",
+            )
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("lines")
+            .long("lines")
+            .require_equals(true)
+            .requires("check")
+            .value_name("PERCENT")
+            .value_parser(value_parser!(u8).range(0..=100))
+            .help("The minimum percentage of covered lines with --check"),
+        )
+        .arg(
+          Arg::new("branches")
+            .long("branches")
+            .require_equals(true)
+            .requires("check")
+            .value_name("PERCENT")
+            .value_parser(value_parser!(u8).range(0..=100))
+            .help("The minimum percentage of covered branches with --check"),
+        )
         .arg(
           Arg::new("files")
             .num_args(0..)
@@ -1748,6 +2326,19 @@ Lint a module for documentation diagnostics:
 
     deno doc --lint ./path/to/module.ts
 
+Generate markdown documentation with a file for each module:
+
+    deno doc --output-format=markdown --output=./docs/ ./path/to/module.ts
+
+Hide the symbols tagged with @internal:
+
+    deno doc --exclude-tag=internal ./path/to/module.ts
+
+Also write a search index with the name, kind, summary and location of
+each symbol for client-side search:
+
+    deno doc --html --search-index=./search.json ./path/to/module.ts
+
 Target a specific symbol:
 
     deno doc ./path/to/module.ts MyClass.someField
@@ -1778,6 +2369,15 @@ Show documentation for runtime built-ins:
             .action(ArgAction::SetTrue)
             .conflicts_with("json")
         )
+        .arg(
+          Arg::new("output-format")
+            .long("output-format")
+            .help("Output documentation as markdown files")
+            .value_parser(["markdown"])
+            .require_equals(true)
+            .conflicts_with("json")
+            .conflicts_with("html")
+        )
         .arg(
           Arg::new("name")
             .long("name")
@@ -1788,7 +2388,7 @@ Show documentation for runtime built-ins:
         .arg(
           Arg::new("output")
             .long("output")
-            .help("Directory for HTML documentation output")
+            .help("Directory for HTML or markdown documentation output")
             .action(ArgAction::Set)
             .require_equals(true)
             .value_hint(ValueHint::DirPath)
@@ -1807,7 +2407,8 @@ Show documentation for runtime built-ins:
             .required(false)
             .conflicts_with("json")
             .conflicts_with("lint")
-            .conflicts_with("html"),
+            .conflicts_with("html")
+            .conflicts_with("output-format"),
         )
         .arg(
           Arg::new("lint")
@@ -1815,6 +2416,33 @@ Show documentation for runtime built-ins:
             .help("Output documentation diagnostics.")
             .action(ArgAction::SetTrue),
         )
+        .arg(
+          Arg::new("search-index")
+            .long("search-index")
+            .help("Write a JSON search index of the documented symbols to this file")
+            .value_name("FILE")
+            .require_equals(true)
+            .value_hint(ValueHint::FilePath)
+            .value_parser(value_parser!(String)),
+        )
+        .arg(
+          Arg::new("include-tag")
+            .long("include-tag")
+            .help("Only document the symbols with one of these JSDoc tags")
+            .value_name("TAG")
+            .action(ArgAction::Append)
+            .require_equals(true)
+            .use_value_delimiter(true),
+        )
+        .arg(
+          Arg::new("exclude-tag")
+            .long("exclude-tag")
+            .help("Don't document the symbols and members with one of these JSDoc tags")
+            .value_name("TAG")
+            .action(ArgAction::Append)
+            .require_equals(true)
+            .use_value_delimiter(true),
+        )
         // TODO(nayeemrmn): Make `--builtin` a proper option. Blocked by
         // https://github.com/clap-rs/clap/issues/1794. Currently `--builtin` is
         // just a possible value of `source_file` so leading hyphens must be
@@ -1881,12 +2509,16 @@ fn fmt_subcommand() -> Command {
   Command::new("fmt")
     .about("Format source files")
     .long_about(
-      "Auto-format JavaScript, TypeScript, Markdown, and JSON files.
+      "Auto-format JavaScript, TypeScript, Markdown, JSON, CSS, SCSS, LESS, and YAML files.
 
   deno fmt
   deno fmt myfile1.ts myfile2.ts
   deno fmt --check
 
+Formatting CSS, SCSS, LESS and YAML files is unstable and needs to be enabled:
+
+  deno fmt --unstable-css --unstable-yaml
+
 Format stdin and write to stdout:
 
   cat file.ts | deno fmt -
@@ -1909,6 +2541,33 @@ Ignore formatting a file by adding an ignore comment at the top of the file:
             .help("Check if the source files are formatted")
             .num_args(0),
         )
+        .arg(
+          Arg::new("diff-style")
+            .long("diff-style")
+            .require_equals(true)
+            .value_name("STYLE")
+            .value_parser(["pretty", "patch"])
+            .requires("check")
+            .help("How to show the changes to unformatted files")
+            .long_help(
+              "How to show the changes to unformatted files when checking. \
+'patch' prints a unified diff of all the unformatted files to stdout, \
+which can be applied with `git apply`. Defaults to 'pretty'.",
+            ),
+        )
+        .arg(
+          Arg::new("diff-output")
+            .long("diff-output")
+            .require_equals(true)
+            .value_name("FILE")
+            .value_hint(ValueHint::FilePath)
+            .requires("check")
+            .help("Write a patch of the changes to unformatted files to a file")
+            .long_help(
+              "Write a unified diff of the changes to unformatted files to the \
+file instead of printing it to stdout. Implies --diff-style=patch.",
+            ),
+        )
         .arg(
           Arg::new("ext")
             .long("ext")
@@ -1916,7 +2575,8 @@ Ignore formatting a file by adding an ignore comment at the top of the file:
             // prefer using ts for formatting instead of js because ts works in more scenarios
             .default_value("ts")
             .value_parser([
-              "ts", "tsx", "js", "jsx", "md", "json", "jsonc", "ipynb",
+              "ts", "tsx", "js", "jsx", "md", "json", "jsonc", "ipynb", "css",
+              "scss", "less", "yml", "yaml",
             ]),
         )
         .arg(
@@ -1928,6 +2588,37 @@ Ignore formatting a file by adding an ignore comment at the top of the file:
             .help("Ignore formatting particular source files")
             .value_hint(ValueHint::AnyPath),
         )
+        .arg(gitignore_arg())
+        .arg(
+          Arg::new("changed")
+            .long("changed")
+            .num_args(0..=1)
+            .require_equals(true)
+            .default_missing_value("HEAD")
+            .value_name("REF")
+            .help("Only format files changed since the git ref (defaults to HEAD)")
+            .long_help(
+              "Only format the files that were modified since the provided \
+git ref, along with untracked files that aren't ignored by git. The \
+files still need to be in the configured include/exclude set. Defaults \
+to HEAD, which formats the uncommitted changes.",
+            ),
+        )
+        .arg(
+          Arg::new("range")
+            .long("range")
+            .require_equals(true)
+            .value_name("START:END")
+            .value_parser(fmt_range_parser)
+            .conflicts_with_all(["changed", "watch"])
+            .help("Only format the provided lines of a single file")
+            .long_help(
+              "Only apply the formatting changes that are within the lines \
+from START to END, which are 1-indexed and inclusive. In JavaScript and \
+TypeScript files the range is widened to the statements it overlaps. \
+Requires a single file or stdin.",
+            ),
+        )
         .arg(
           Arg::new("files")
             .num_args(1..)
@@ -1993,6 +2684,18 @@ Ignore formatting a file by adding an ignore comment at the top of the file:
               "Don't use semicolons except where necessary. Defaults to false.",
             ),
         )
+        .arg(
+          Arg::new("unstable-css")
+            .long("unstable-css")
+            .help("Enable formatting CSS, SCSS and LESS files")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("unstable-yaml")
+            .long("unstable-yaml")
+            .help("Enable formatting YAML files")
+            .action(ArgAction::SetTrue),
+        )
     })
 }
 
@@ -2029,10 +2732,18 @@ Without any additional arguments, 'deno info' shows:
 
 DENO_DIR: Directory containing Deno-managed files.
 Remote modules cache: Subdirectory containing downloaded remote modules.
-TypeScript compiler cache: Subdirectory containing TS compiler output.",
-      )
-    .defer(|cmd| cmd
-      .arg(Arg::new("file").required(false).value_hint(ValueHint::FilePath))
+TypeScript compiler cache: Subdirectory containing TS compiler output.
+
+Find the dependencies that can be removed from the import map and lockfile:
+
+  deno info --unused main.ts
+
+Show what imports a module or npm package in the graph of a file:
+
+  deno info --why=npm:chalk main.ts",
+      )
+    .defer(|cmd| cmd
+      .arg(Arg::new("file").required(false).value_hint(ValueHint::FilePath))
       .arg(reload_arg().requires("file"))
       .arg(ca_file_arg())
       .arg(
@@ -2056,6 +2767,27 @@ TypeScript compiler cache: Subdirectory containing TS compiler output.",
           .long("json")
           .help("UNSTABLE: Outputs the information in JSON format")
           .action(ArgAction::SetTrue),
+      )
+      .arg(
+        Arg::new("unused")
+          .long("unused")
+          .help("Show import map entries and lockfile packages that aren't used by the module graph")
+          .long_help(
+            "Show the import map entries and lockfile packages that aren't used by
+the module graph of the provided file or, when no file is provided, of the
+exports of the config file.",
+          )
+          .action(ArgAction::SetTrue),
+      )
+      .arg(
+        Arg::new("why")
+          .long("why")
+          .help("Show an inverted tree of what imports a module or npm package")
+          .value_name("SPECIFIER")
+          .require_equals(true)
+          .requires("file")
+          .conflicts_with("unused")
+          .value_hint(ValueHint::AnyPath),
       ))
 }
 
@@ -2301,7 +3033,9 @@ rule name:
   // deno-lint-ignore no-explicit-any
   // deno-lint-ignore require-await no-empty
 
-Names of rules to ignore must be specified after ignore comment.
+Names of rules to ignore must be specified after ignore comment. Ignore
+comments that no longer suppress any diagnostic are reported and removed by
+--fix.
 
 Ignore linting a file by adding an ignore comment at the top of the file:
 
@@ -2316,12 +3050,27 @@ Ignore linting a file by adding an ignore comment at the top of the file:
             .help("Fix any linting errors for rules that support it")
             .action(ArgAction::SetTrue),
         )
+        .arg(
+          Arg::new("fix-dry-run")
+            .long("fix-dry-run")
+            .help("Show the fixes --fix would apply without writing them")
+            .conflicts_with("fix")
+            .action(ArgAction::SetTrue),
+        )
         .arg(
           Arg::new("rules")
             .long("rules")
             .help("List available rules")
             .action(ArgAction::SetTrue),
         )
+        .arg(
+          Arg::new("explain")
+            .long("explain")
+            .require_equals(true)
+            .value_name("RULE")
+            .help("Show the documentation of a rule along with examples")
+            .conflicts_with_all(["rules", "fix", "fix-dry-run", "files"]),
+        )
         .arg(
           Arg::new("rules-tags")
             .long("rules-tags")
@@ -2360,6 +3109,7 @@ Ignore linting a file by adding an ignore comment at the top of the file:
             .help("Ignore linting particular source files")
             .value_hint(ValueHint::AnyPath),
         )
+        .arg(gitignore_arg())
         .arg(
           Arg::new("json")
             .long("json")
@@ -2373,6 +3123,36 @@ Ignore linting a file by adding an ignore comment at the top of the file:
             .action(ArgAction::SetTrue)
             .conflicts_with("json"),
         )
+        .arg(
+          Arg::new("output")
+            .long("output")
+            .require_equals(true)
+            .value_parser(["pretty", "json", "compact", "sarif"])
+            .help("Output lint result in the given format")
+            .conflicts_with_all(["json", "compact"]),
+        )
+        .arg(
+          Arg::new("max-warnings")
+            .long("max-warnings")
+            .require_equals(true)
+            .value_name("N")
+            .value_parser(value_parser!(usize))
+            .help("Fail when more than N warnings are reported"),
+        )
+        .arg(
+          Arg::new("baseline")
+            .long("baseline")
+            .require_equals(true)
+            .value_name("FILE")
+            .help("Only report diagnostics that aren't in the baseline file (defaults to deno-lint-baseline.json next to the config file)")
+            .value_hint(ValueHint::FilePath),
+        )
+        .arg(
+          Arg::new("write-baseline")
+            .long("write-baseline")
+            .help("Write the current diagnostics to the baseline file")
+            .action(ArgAction::SetTrue),
+        )
         .arg(
           Arg::new("files")
             .num_args(1..)
@@ -2554,6 +3334,7 @@ Directory arguments are expanded to all contained files matching the glob
         .help("Ignore files")
         .value_hint(ValueHint::AnyPath),
     )
+    .arg(gitignore_arg())
     .arg(
       Arg::new("no-run")
         .long("no-run")
@@ -2605,11 +3386,55 @@ Directory arguments are expanded to all contained files matching the glob
       Arg::new("shuffle")
         .long("shuffle")
         .value_name("NUMBER")
-        .help("Shuffle the order in which the tests are run")
+        .help("Shuffle the order in which the test modules and tests are run. The seed is random unless NUMBER is provided, and it's printed when the tests fail to reproduce the order.")
         .num_args(0..=1)
         .require_equals(true)
         .value_parser(value_parser!(u64)),
     )
+    .arg(
+      Arg::new("retries")
+        .long("retries")
+        .value_name("N")
+        .require_equals(true)
+        .help("Run failing tests again up to N times. Tests that pass on a retry are reported as flaky.")
+        .value_parser(value_parser!(usize)),
+    )
+    .arg(
+      Arg::new("timeout")
+        .long("timeout")
+        .value_name("MS")
+        .require_equals(true)
+        .help("Fail tests that run for longer than MS milliseconds, unless they set their own timeout")
+        .value_parser(value_parser!(u64).range(1..)),
+    )
+    .arg(
+      Arg::new("update-snapshots")
+        .long("update-snapshots")
+        .help("Write the snapshots of TestContext.assertSnapshot() that are missing or don't match instead of failing")
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("list")
+        .long("list")
+        .help("List the tests that would run, after applying the filters, without running them")
+        .conflicts_with("watch")
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("json")
+        .long("json")
+        .help("Output the list of tests in JSON format")
+        .requires("list")
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("report-slow")
+        .long("report-slow")
+        .value_name("N")
+        .require_equals(true)
+        .help("Print the N slowest tests and steps with their durations after the summary. The junit reporter always includes the durations.")
+        .value_parser(value_parser!(NonZeroUsize)),
+    )
     .arg(
       Arg::new("coverage")
         .long("coverage")
@@ -2638,9 +3463,23 @@ Directory arguments are expanded to all contained files matching the glob
         .num_args(0..=1)
         .value_parser(value_parser!(NonZeroUsize)),
     )
+    .arg(
+      Arg::new("isolate-processes")
+        .long("isolate-processes")
+        .help("Run each test module in a separate process, isolating their globals, environment variables and crashes. Combine with --parallel to run several processes at a time.")
+        .conflicts_with("watch")
+        .action(ArgAction::SetTrue),
+    )
+    .arg(
+      Arg::new("child-process-module")
+        .long("child-process-module")
+        .help("Run only the given test module and write its test events to stdout. Used by --isolate-processes.")
+        .hide(true)
+        .require_equals(true),
+    )
     .arg(
       Arg::new("files")
-        .help("List of file names to run")
+        .help("List of file names to run. Append :LINE to a file name to only run the test at that line")
         .num_args(0..)
         .action(ArgAction::Append)
         .value_hint(ValueHint::AnyPath),
@@ -2656,9 +3495,10 @@ Directory arguments are expanded to all contained files matching the glob
     .arg(
       Arg::new("junit-path")
         .long("junit-path")
+        .visible_alias("output")
         .value_name("PATH")
         .value_hint(ValueHint::FilePath)
-        .help("Write a JUnit XML test report to PATH. Use '-' to write to stdout which is the default when PATH is not provided.")
+        .help("Write a JUnit XML test report to PATH. Use '-' to write to stdout which is the default when PATH is not provided. With --reporter=junit, the report is only written to PATH.")
     )
     .arg(
       Arg::new("reporter")
@@ -2666,6 +3506,22 @@ Directory arguments are expanded to all contained files matching the glob
         .help("Select reporter to use. Default to 'pretty'.")
         .value_parser(["pretty", "dot", "junit", "tap"])
     )
+    .arg(
+      Arg::new("shard")
+        .long("shard")
+        .value_name("INDEX/COUNT")
+        .require_equals(true)
+        .help("Only run the test modules of one shard, ex. --shard=3/8 runs the third of eight shards. The modules are split the same way on every machine.")
+        .value_parser(test_shard_parser)
+        .conflicts_with("watch"),
+    )
+    .arg(
+      Arg::new("shard-tests")
+        .long("shard-tests")
+        .help("Split the individual tests across the shards instead of the test modules")
+        .requires("shard")
+        .action(ArgAction::SetTrue),
+    )
     .arg(env_file_arg())
   )
 }
@@ -2696,7 +3552,14 @@ and is used to replace the current executable.
 If you want to not replace the current Deno executable but instead download an
 update to a different location, use the --output flag
 
-  deno upgrade --output $HOME/my_deno",
+  deno upgrade --output $HOME/my_deno
+
+To stage an upgrade for machines without network access, download the release
+archives of one or more targets into a directory with the --download-only flag.
+The archives are verified against the checksums published with the release:
+
+  deno upgrade --download-only --output ./deno_releases --version 1.43.0 \\
+    --target x86_64-unknown-linux-gnu --target x86_64-pc-windows-msvc",
     )
     .hide(cfg!(not(feature = "upgrade")))
     .defer(|cmd| {
@@ -2713,6 +3576,26 @@ update to a different location, use the --output flag
             .value_parser(value_parser!(String))
             .value_hint(ValueHint::FilePath),
         )
+        .arg(
+          Arg::new("download-only")
+            .long("download-only")
+            .help("Download the release archives into the --output directory without replacing the current executable")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("target")
+            .long("target")
+            .help("Target to download the release archive of (defaults to the current target)")
+            .requires("download-only")
+            .action(ArgAction::Append)
+            .value_parser([
+              "x86_64-unknown-linux-gnu",
+              "aarch64-unknown-linux-gnu",
+              "x86_64-pc-windows-msvc",
+              "x86_64-apple-darwin",
+              "aarch64-apple-darwin",
+            ]),
+        )
         .arg(
           Arg::new("dry-run")
             .long("dry-run")
@@ -2751,14 +3634,35 @@ maps remote specifiers to the downloaded files.
 
 Remote modules and multiple modules may also be specified:
 
-  deno vendor main.ts test.deps.ts jsr:@std/path",
+  deno vendor main.ts test.deps.ts jsr:@std/path
+
+Merge the generated mappings into the \"imports\" and \"scopes\" of the
+configuration file instead of referencing the generated import map:
+
+  deno vendor --merge-config main.ts
+
+Update an existing output directory by re-fetching the remote modules and
+removing the files that are no longer vendored. Vendored files that were
+changed locally (detected with the checksums in the lock file) are kept:
+
+  deno vendor --update --prune main.ts
+
+Only vendor the modules of some hosts or scopes, while the other remote
+modules stay remote:
+
+  deno vendor --only=https://deno.land/x/oak@v12.6.1/ main.ts
+
+Check that the vendored files match the checksums in the lock file, for
+example in CI:
+
+  deno vendor --check",
       )
     .defer(|cmd| cmd
       .arg(
         Arg::new("specifiers")
           .num_args(1..)
           .action(ArgAction::Append)
-          .required(true),
+          .required_unless_present("check"),
       )
       .arg(
         Arg::new("output")
@@ -2776,6 +3680,64 @@ Remote modules and multiple modules may also be specified:
           )
           .action(ArgAction::SetTrue),
       )
+      .arg(
+        Arg::new("trash")
+          .long("trash")
+          .help(
+            "Move the existing output directory to the trash instead of overwriting its files",
+          )
+          .requires("force")
+          .action(ArgAction::SetTrue),
+      )
+      .arg(
+        Arg::new("merge-config")
+          .long("merge-config")
+          .help(
+            "Merge the generated import map into the \"imports\" and \"scopes\" of the configuration file",
+          )
+          .action(ArgAction::SetTrue),
+      )
+      .arg(
+        Arg::new("update")
+          .long("update")
+          .help(
+            "Re-fetch the remote modules and update the changed files in the existing output directory",
+          )
+          .conflicts_with("trash")
+          .action(ArgAction::SetTrue),
+      )
+      .arg(
+        Arg::new("prune")
+          .long("prune")
+          .help(
+            "Remove the files that are no longer vendored from the existing output directory",
+          )
+          .conflicts_with("trash")
+          .action(ArgAction::SetTrue),
+      )
+      .arg(
+        Arg::new("only")
+          .long("only")
+          .help("Only vendor the remote modules starting with this URL prefix. Can be used multiple times")
+          .value_name("URL_PREFIX")
+          .require_equals(true)
+          .action(ArgAction::Append)
+          .value_hint(ValueHint::Url),
+      )
+      .arg(
+        Arg::new("check")
+          .long("check")
+          .help("Check that the files of the existing output directory match the checksums in the lock file")
+          .conflicts_with_all([
+            "specifiers",
+            "force",
+            "merge-config",
+            "update",
+            "prune",
+            "only",
+          ])
+          .action(ArgAction::SetTrue),
+      )
       .arg(no_config_arg())
       .arg(config_arg())
       .arg(import_map_arg())
@@ -3332,6 +4294,18 @@ fn cached_only_arg() -> Arg {
     .help("Require that remote dependencies are already cached")
 }
 
+fn gitignore_arg() -> Arg {
+  Arg::new("gitignore")
+    .long("gitignore")
+    .help("Skip files ignored by .gitignore files")
+    .long_help(
+      "Skip the files that are ignored by the .gitignore files in the \
+directories being searched, including nested .gitignore files. Files \
+explicitly passed on the command line are not skipped.",
+    )
+    .action(ArgAction::SetTrue)
+}
+
 /// Used for subcommands that operate on executable scripts only.
 /// `deno fmt` has its own `--ext` arg because its possible values differ.
 /// If --ext is not provided and the script doesn't have a file extension,
@@ -3655,6 +4629,12 @@ fn add_parse_inner(
   AddFlags { packages }
 }
 
+fn remove_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.subcommand = DenoSubcommand::Remove(RemoveFlags {
+    packages: matches.remove_many::<String>("packages").unwrap().collect(),
+  });
+}
+
 fn bench_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.type_check_mode = TypeCheckMode::Local;
 
@@ -3664,7 +4644,8 @@ fn bench_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   // interactive prompts, unless done by user code
   flags.permissions.no_prompt = true;
 
-  let json = matches.get_flag("json");
+  let json_path = matches.remove_one::<String>("json-path");
+  let json = matches.get_flag("json") || json_path.is_some();
 
   let ignore = match matches.remove_many::<String>("ignore") {
     Some(f) => f.collect(),
@@ -3672,6 +4653,7 @@ fn bench_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   };
 
   let filter = matches.remove_one::<String>("filter");
+  let group = matches.remove_one::<String>("group");
 
   if matches.contains_id("script_arg") {
     flags
@@ -3687,15 +4669,60 @@ fn bench_parse(flags: &mut Flags, matches: &mut ArgMatches) {
 
   let no_run = matches.get_flag("no-run");
 
+  let profile = matches.remove_one::<String>("profile").map(|profile| {
+    match profile.as_str() {
+      "cpuprofile" => BenchProfileFormat::CpuProfile,
+      "flamegraph" => BenchProfileFormat::Flamegraph,
+      _ => unreachable!(),
+    }
+  });
+  let profile_dir = matches.remove_one::<String>("profile-dir");
+  let warmup_time = matches.remove_one::<u64>("warmup-time");
+  let min_iterations = matches.remove_one::<NonZeroU64>("min-iterations");
+  let max_time = matches.remove_one::<u64>("max-time");
+  let save_baseline = matches.remove_one::<String>("save-baseline");
+  let compare_baseline = matches.remove_one::<String>("compare");
+  let regression_threshold = matches.remove_one::<u32>("regression-threshold");
+  let against = matches.remove_one::<String>("against");
+
   flags.subcommand = DenoSubcommand::Bench(BenchFlags {
-    files: FileFlags { include, ignore },
+    files: FileFlags {
+      include,
+      ignore,
+      gitignore: false,
+    },
     filter,
+    group,
     json,
+    json_path,
     no_run,
+    profile,
+    profile_dir,
+    warmup_time,
+    min_iterations,
+    max_time,
+    save_baseline,
+    compare_baseline,
+    regression_threshold,
+    against,
     watch: watch_arg_parse(matches),
   });
 }
 
+/// Validates the name of a bench baseline, which is used as a file name.
+fn parse_bench_baseline_name(name: &str) -> Result<String, String> {
+  let is_valid = !name.is_empty()
+    && name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    && !name.starts_with('.');
+  if is_valid {
+    Ok(name.to_string())
+  } else {
+    Err("must only contain letters, digits, '-', '_' and '.', and not start with '.'".to_string())
+  }
+}
+
 fn bundle_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.type_check_mode = TypeCheckMode::Local;
 
@@ -3711,12 +4738,34 @@ fn bundle_parse(flags: &mut Flags, matches: &mut ArgMatches) {
       None
     };
 
+  let sourcemap =
+    matches
+      .remove_one::<String>("sourcemap")
+      .map(|sourcemap| match sourcemap.as_str() {
+        "external" => BundleSourceMap::External,
+        "inline" => BundleSourceMap::Inline,
+        "none" => BundleSourceMap::None,
+        _ => unreachable!(),
+      });
+  let no_sources_content = matches.get_flag("no-sources-content");
+  let format = match matches.remove_one::<String>("format").unwrap().as_str() {
+    "esm" => BundleFormat::Esm,
+    "iife" => BundleFormat::Iife,
+    "cjs" => BundleFormat::Cjs,
+    _ => unreachable!(),
+  };
+  let global_name = matches.remove_one::<String>("global-name");
+
   ext_arg_parse(flags, matches);
 
   flags.subcommand = DenoSubcommand::Bundle(BundleFlags {
     source_file,
     out_file,
     watch: watch_arg_parse(matches),
+    sourcemap,
+    no_sources_content,
+    format,
+    global_name,
   });
 }
 
@@ -3733,7 +4782,10 @@ fn check_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   if matches.get_flag("all") || matches.get_flag("remote") {
     flags.type_check_mode = TypeCheckMode::All;
   }
-  flags.subcommand = DenoSubcommand::Check(CheckFlags { files });
+  flags.subcommand = DenoSubcommand::Check(CheckFlags {
+    files,
+    watch: watch_arg_parse(matches),
+  });
 }
 
 fn compile_parse(flags: &mut Flags, matches: &mut ArgMatches) {
@@ -3746,6 +4798,8 @@ fn compile_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   let output = matches.remove_one::<String>("output");
   let target = matches.remove_one::<String>("target");
   let no_terminal = matches.get_flag("no-terminal");
+  let icon = matches.remove_one::<String>("icon");
+  let exe_metadata = matches.remove_one::<String>("exe-metadata");
   let include = match matches.remove_many::<String>("include") {
     Some(f) => f.collect(),
     None => vec![],
@@ -3759,6 +4813,8 @@ fn compile_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     target,
     no_terminal,
     include,
+    icon,
+    exe_metadata,
   });
 }
 
@@ -3808,25 +4864,33 @@ fn coverage_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     Some(f) => f.collect(),
     None => vec![],
   };
+  let mut output = matches.remove_one::<String>("output");
   let r#type = if matches.get_flag("lcov") {
     CoverageType::Lcov
-  } else if matches.get_flag("html") {
+  } else if matches.contains_id("html") {
+    output = matches.remove_one::<String>("html");
     CoverageType::Html
   } else if matches.get_flag("detailed") {
     CoverageType::Detailed
+  } else if matches.contains_id("cobertura") {
+    output = matches.remove_one::<String>("cobertura");
+    CoverageType::Cobertura
   } else {
     CoverageType::Summary
   };
-  let output = matches.remove_one::<String>("output");
   flags.subcommand = DenoSubcommand::Coverage(CoverageFlags {
     files: FileFlags {
       include: files,
       ignore,
+      gitignore: false,
     },
     output,
     include,
     exclude,
     r#type,
+    check: matches.get_flag("check"),
+    line_threshold: matches.remove_one::<u8>("lines"),
+    branch_threshold: matches.remove_one::<u8>("branches"),
   });
 }
 
@@ -3860,23 +4924,43 @@ fn doc_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   let lint = matches.get_flag("lint");
   let json = matches.get_flag("json");
   let filter = matches.remove_one::<String>("filter");
+  let search_index = matches.remove_one::<String>("search-index");
+  let include_tags = matches
+    .remove_many::<String>("include-tag")
+    .map(|tags| tags.collect())
+    .unwrap_or_default();
+  let exclude_tags = matches
+    .remove_many::<String>("exclude-tag")
+    .map(|tags| tags.collect())
+    .unwrap_or_default();
+  let output = matches
+    .remove_one::<String>("output")
+    .unwrap_or(String::from("./docs/"));
   let html = if matches.get_flag("html") {
     let name = matches.remove_one::<String>("name");
-    let output = matches
-      .remove_one::<String>("output")
-      .unwrap_or(String::from("./docs/"));
-    Some(DocHtmlFlag { name, output })
+    Some(DocHtmlFlag {
+      name,
+      output: output.clone(),
+    })
   } else {
     None
   };
+  let markdown = match matches.remove_one::<String>("output-format") {
+    Some(format) if format == "markdown" => Some(DocMarkdownFlag { output }),
+    _ => None,
+  };
 
   flags.subcommand = DenoSubcommand::Doc(DocFlags {
     source_files,
     json,
     lint,
     html,
+    markdown,
     filter,
     private,
+    search_index,
+    include_tags,
+    exclude_tags,
   });
 }
 
@@ -3928,16 +5012,32 @@ fn fmt_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   let single_quote = matches.remove_one::<bool>("single-quote");
   let prose_wrap = matches.remove_one::<String>("prose-wrap");
   let no_semicolons = matches.remove_one::<bool>("no-semicolons");
+  let diff_output = matches.remove_one::<String>("diff-output");
+  let diff_style = match matches.remove_one::<String>("diff-style").as_deref() {
+    Some("patch") => FmtDiffStyle::Patch,
+    _ if diff_output.is_some() => FmtDiffStyle::Patch,
+    _ => FmtDiffStyle::Pretty,
+  };
 
   flags.subcommand = DenoSubcommand::Fmt(FmtFlags {
     check: matches.get_flag("check"),
-    files: FileFlags { include, ignore },
+    files: FileFlags {
+      include,
+      ignore,
+      gitignore: matches.get_flag("gitignore"),
+    },
     use_tabs,
     line_width,
     indent_width,
     single_quote,
     prose_wrap,
     no_semicolons,
+    changed: matches.remove_one::<String>("changed"),
+    range: matches.remove_one::<FmtRange>("range"),
+    diff_style,
+    diff_output,
+    unstable_css: matches.get_flag("unstable-css"),
+    unstable_yaml: matches.get_flag("unstable-yaml"),
     watch: watch_arg_parse(matches),
   });
 }
@@ -3962,6 +5062,8 @@ fn info_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.subcommand = DenoSubcommand::Info(InfoFlags {
     file: matches.remove_one::<String>("file"),
     json,
+    unused: matches.get_flag("unused"),
+    why: matches.remove_one::<String>("why"),
   });
 }
 
@@ -4059,14 +5161,21 @@ fn lint_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     files: FileFlags {
       include: files,
       ignore,
+      gitignore: matches.get_flag("gitignore"),
     },
     fix,
+    fix_dry_run: matches.get_flag("fix-dry-run"),
+    explain: matches.remove_one::<String>("explain"),
     rules,
     maybe_rules_tags,
     maybe_rules_include,
     maybe_rules_exclude,
     json,
     compact,
+    output: matches.remove_one::<String>("output"),
+    max_warnings: matches.remove_one::<usize>("max-warnings"),
+    baseline: matches.remove_one::<String>("baseline"),
+    write_baseline: matches.get_flag("write-baseline"),
     watch: watch_arg_parse(matches),
   });
 }
@@ -4289,9 +5398,20 @@ fn test_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   } else {
     None
   };
+  let child_process_module =
+    matches.remove_one::<String>("child-process-module");
 
+  let mut lines = Vec::new();
   let include = if let Some(files) = matches.remove_many::<String>("files") {
-    files.collect()
+    files
+      .map(|file| match split_test_line(&file) {
+        Some((path, line)) => {
+          lines.push((path.to_string(), line));
+          path.to_string()
+        }
+        None => file,
+      })
+      .collect()
   } else {
     Vec::new()
   };
@@ -4320,7 +5440,11 @@ fn test_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     doc,
     coverage_dir: matches.remove_one::<String>("coverage"),
     fail_fast,
-    files: FileFlags { include, ignore },
+    files: FileFlags {
+      include,
+      ignore,
+      gitignore: matches.get_flag("gitignore"),
+    },
     filter,
     shuffle,
     allow_none,
@@ -4329,9 +5453,42 @@ fn test_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     watch: watch_arg_parse(matches),
     reporter,
     junit_path,
+    shard: matches.remove_one::<TestShard>("shard"),
+    shard_tests: matches.get_flag("shard-tests"),
+    retries: matches.remove_one::<usize>("retries").unwrap_or(0),
+    timeout: matches.remove_one::<u64>("timeout"),
+    update_snapshots: matches.get_flag("update-snapshots"),
+    isolate_processes: matches.get_flag("isolate-processes"),
+    child_process_module,
+    list: matches.get_flag("list"),
+    json: matches.get_flag("json"),
+    report_slow: matches.remove_one::<NonZeroUsize>("report-slow"),
+    lines,
   });
 }
 
+/// Splits a `path:line` argument of `deno test` into the path and the line.
+/// Only a trailing `:<digits>` after the file name of the path is a line, so
+/// the port of a URL like `http://localhost:4545` or the drive of a Windows
+/// path isn't mistaken for one.
+fn split_test_line(arg: &str) -> Option<(&str, u32)> {
+  let (path, line) = arg.rsplit_once(':')?;
+  if line.is_empty() || !line.bytes().all(|b| b.is_ascii_digit()) {
+    return None;
+  }
+  let line = line.parse::<u32>().ok().filter(|line| *line > 0)?;
+  let file_name_start = match path.split_once("://") {
+    // the file name of a URL comes after the host
+    Some((_, rest)) => path.len() - rest.len() + rest.find('/')? + 1,
+    None => path.rfind(['/', '\\']).map(|i| i + 1).unwrap_or(0),
+  };
+  let file_name = &path[file_name_start..];
+  // a single letter is the drive of a Windows path, like `C:1`
+  let is_drive =
+    file_name.len() == 1 && file_name.bytes().all(|b| b.is_ascii_alphabetic());
+  (!file_name.is_empty() && !is_drive).then_some((path, line))
+}
+
 fn types_parse(flags: &mut Flags, _matches: &mut ArgMatches) {
   flags.subcommand = DenoSubcommand::Types;
 }
@@ -4344,12 +5501,19 @@ fn upgrade_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   let canary = matches.get_flag("canary");
   let version = matches.remove_one::<String>("version");
   let output = matches.remove_one::<String>("output");
+  let download_only = matches.get_flag("download-only");
+  let targets = matches
+    .remove_many::<String>("target")
+    .map(|targets| targets.collect())
+    .unwrap_or_default();
   flags.subcommand = DenoSubcommand::Upgrade(UpgradeFlags {
     dry_run,
     force,
     canary,
     version,
     output,
+    download_only,
+    targets,
   });
 }
 
@@ -4361,6 +5525,11 @@ fn vendor_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   node_modules_and_vendor_dir_arg_parse(flags, matches);
   reload_arg_parse(flags, matches);
 
+  let update = matches.get_flag("update");
+  if update && flags.cache_blocklist.is_empty() {
+    flags.reload = true;
+  }
+
   flags.subcommand = DenoSubcommand::Vendor(VendorFlags {
     specifiers: matches
       .remove_many::<String>("specifiers")
@@ -4368,6 +5537,15 @@ fn vendor_parse(flags: &mut Flags, matches: &mut ArgMatches) {
       .unwrap_or_default(),
     output_path: matches.remove_one::<String>("output"),
     force: matches.get_flag("force"),
+    trash: matches.get_flag("trash"),
+    merge_config: matches.get_flag("merge-config"),
+    update,
+    prune: matches.get_flag("prune"),
+    only: matches
+      .remove_many::<String>("only")
+      .map(|p| p.collect())
+      .unwrap_or_default(),
+    check: matches.get_flag("check"),
   });
 }
 
@@ -4827,6 +6005,8 @@ mod tests {
           canary: false,
           version: None,
           output: None,
+          download_only: false,
+          targets: vec![],
         }),
         ..Flags::default()
       }
@@ -4845,10 +6025,50 @@ mod tests {
           canary: false,
           version: None,
           output: Some(String::from("example.txt")),
+          download_only: false,
+          targets: vec![],
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn upgrade_download_only() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "upgrade",
+      "--download-only",
+      "--output",
+      "releases",
+      "--target",
+      "x86_64-unknown-linux-gnu",
+      "--target",
+      "aarch64-apple-darwin"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
+          force: false,
+          dry_run: false,
+          canary: false,
+          version: None,
+          output: Some(String::from("releases")),
+          download_only: true,
+          targets: svec!["x86_64-unknown-linux-gnu", "aarch64-apple-darwin"],
         }),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "upgrade",
+      "--target",
+      "x86_64-unknown-linux-gnu"
+    ]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -5520,6 +6740,7 @@ mod tests {
           files: FileFlags {
             include: vec!["script_1.ts".to_string(), "script_2.ts".to_string()],
             ignore: vec![],
+            gitignore: false,
           },
           use_tabs: None,
           line_width: None,
@@ -5527,6 +6748,12 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          changed: None,
+          range: None,
+          diff_style: FmtDiffStyle::Pretty,
+          diff_output: None,
+          unstable_css: false,
+          unstable_yaml: false,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
@@ -5543,6 +6770,7 @@ mod tests {
           files: FileFlags {
             include: vec![],
             ignore: vec![],
+            gitignore: false,
           },
           use_tabs: None,
           line_width: None,
@@ -5550,6 +6778,12 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          changed: None,
+          range: None,
+          diff_style: FmtDiffStyle::Pretty,
+          diff_output: None,
+          unstable_css: false,
+          unstable_yaml: false,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
@@ -5566,6 +6800,7 @@ mod tests {
           files: FileFlags {
             include: vec![],
             ignore: vec![],
+            gitignore: false,
           },
           use_tabs: None,
           line_width: None,
@@ -5573,6 +6808,12 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          changed: None,
+          range: None,
+          diff_style: FmtDiffStyle::Pretty,
+          diff_output: None,
+          unstable_css: false,
+          unstable_yaml: false,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
@@ -5589,6 +6830,7 @@ mod tests {
           files: FileFlags {
             include: vec![],
             ignore: vec![],
+            gitignore: false,
           },
           use_tabs: None,
           line_width: None,
@@ -5596,6 +6838,12 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          changed: None,
+          range: None,
+          diff_style: FmtDiffStyle::Pretty,
+          diff_output: None,
+          unstable_css: false,
+          unstable_yaml: false,
           watch: Some(Default::default()),
         }),
         ext: Some("ts".to_string()),
@@ -5613,6 +6861,7 @@ mod tests {
           files: FileFlags {
             include: vec![],
             ignore: vec![],
+            gitignore: false,
           },
           use_tabs: None,
           line_width: None,
@@ -5620,6 +6869,12 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          changed: None,
+          range: None,
+          diff_style: FmtDiffStyle::Pretty,
+          diff_output: None,
+          unstable_css: false,
+          unstable_yaml: false,
           watch: Some(WatchFlags {
             hmr: false,
             no_clear_screen: true,
@@ -5647,6 +6902,7 @@ mod tests {
           files: FileFlags {
             include: vec!["foo.ts".to_string()],
             ignore: vec!["bar.js".to_string()],
+            gitignore: false,
           },
           use_tabs: None,
           line_width: None,
@@ -5654,6 +6910,12 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          changed: None,
+          range: None,
+          diff_style: FmtDiffStyle::Pretty,
+          diff_output: None,
+          unstable_css: false,
+          unstable_yaml: false,
           watch: Some(Default::default()),
         }),
         ext: Some("ts".to_string()),
@@ -5670,6 +6932,7 @@ mod tests {
           files: FileFlags {
             include: vec![],
             ignore: vec![],
+            gitignore: false,
           },
           use_tabs: None,
           line_width: None,
@@ -5677,6 +6940,12 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          changed: None,
+          range: None,
+          diff_style: FmtDiffStyle::Pretty,
+          diff_output: None,
+          unstable_css: false,
+          unstable_yaml: false,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
@@ -5701,6 +6970,7 @@ mod tests {
           files: FileFlags {
             include: vec!["foo.ts".to_string()],
             ignore: vec![],
+            gitignore: false,
           },
           use_tabs: None,
           line_width: None,
@@ -5708,6 +6978,12 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          changed: None,
+          range: None,
+          diff_style: FmtDiffStyle::Pretty,
+          diff_output: None,
+          unstable_css: false,
+          unstable_yaml: false,
           watch: Some(Default::default()),
         }),
         config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
@@ -5737,6 +7013,7 @@ mod tests {
           files: FileFlags {
             include: vec![],
             ignore: vec![],
+            gitignore: false,
           },
           use_tabs: Some(true),
           line_width: Some(NonZeroU32::new(60).unwrap()),
@@ -5744,6 +7021,12 @@ mod tests {
           single_quote: Some(true),
           prose_wrap: Some("never".to_string()),
           no_semicolons: Some(true),
+          changed: None,
+          range: None,
+          diff_style: FmtDiffStyle::Pretty,
+          diff_output: None,
+          unstable_css: false,
+          unstable_yaml: false,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
@@ -5767,6 +7050,7 @@ mod tests {
           files: FileFlags {
             include: vec![],
             ignore: vec![],
+            gitignore: false,
           },
           use_tabs: Some(false),
           line_width: None,
@@ -5774,6 +7058,12 @@ mod tests {
           single_quote: Some(false),
           prose_wrap: None,
           no_semicolons: Some(false),
+          changed: None,
+          range: None,
+          diff_style: FmtDiffStyle::Pretty,
+          diff_output: None,
+          unstable_css: false,
+          unstable_yaml: false,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
@@ -5782,6 +7072,113 @@ mod tests {
     );
   }
 
+  #[test]
+  fn gitignore() {
+    for subcommand in ["fmt", "lint", "test"] {
+      let flags =
+        flags_from_vec(svec!["deno", subcommand, "--gitignore"]).unwrap();
+      let files = match flags.subcommand {
+        DenoSubcommand::Fmt(flags) => flags.files,
+        DenoSubcommand::Lint(flags) => flags.files,
+        DenoSubcommand::Test(flags) => flags.files,
+        _ => unreachable!(),
+      };
+      assert!(files.gitignore, "{subcommand}");
+    }
+  }
+
+  #[test]
+  fn fmt_changed() {
+    for (args, expected) in [
+      (svec!["deno", "fmt"], None),
+      (svec!["deno", "fmt", "--changed"], Some("HEAD")),
+      (
+        svec!["deno", "fmt", "--changed=origin/main", "src"],
+        Some("origin/main"),
+      ),
+    ] {
+      let flags = flags_from_vec(args).unwrap();
+      let DenoSubcommand::Fmt(fmt_flags) = flags.subcommand else {
+        unreachable!();
+      };
+      assert_eq!(fmt_flags.changed.as_deref(), expected);
+    }
+  }
+
+  #[test]
+  fn fmt_range() {
+    let flags =
+      flags_from_vec(svec!["deno", "fmt", "--range=120:180", "file.ts"])
+        .unwrap();
+    let DenoSubcommand::Fmt(fmt_flags) = flags.subcommand else {
+      unreachable!();
+    };
+    assert_eq!(
+      fmt_flags.range,
+      Some(FmtRange {
+        start_line: 120,
+        end_line: 180,
+      })
+    );
+
+    for range in ["120", "0:1", "5:4", "a:b"] {
+      let r = flags_from_vec(svec![
+        "deno",
+        "fmt",
+        format!("--range={range}"),
+        "file.ts"
+      ]);
+      assert!(r.is_err(), "{range}");
+    }
+  }
+
+  #[test]
+  fn fmt_diff_style() {
+    for (args, expected_style, expected_output) in [
+      (svec!["deno", "fmt", "--check"], FmtDiffStyle::Pretty, None),
+      (
+        svec!["deno", "fmt", "--check", "--diff-style=patch"],
+        FmtDiffStyle::Patch,
+        None,
+      ),
+      (
+        svec!["deno", "fmt", "--check", "--diff-output=fmt.patch"],
+        FmtDiffStyle::Patch,
+        Some("fmt.patch"),
+      ),
+    ] {
+      let flags = flags_from_vec(args).unwrap();
+      let DenoSubcommand::Fmt(fmt_flags) = flags.subcommand else {
+        unreachable!();
+      };
+      assert_eq!(fmt_flags.diff_style, expected_style);
+      assert_eq!(fmt_flags.diff_output.as_deref(), expected_output);
+    }
+
+    // only applies when checking
+    let r = flags_from_vec(svec!["deno", "fmt", "--diff-style=patch"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn fmt_unstable_formatters() {
+    let flags = flags_from_vec(svec!["deno", "fmt"]).unwrap();
+    let DenoSubcommand::Fmt(fmt_flags) = flags.subcommand else {
+      unreachable!();
+    };
+    assert!(!fmt_flags.unstable_css);
+    assert!(!fmt_flags.unstable_yaml);
+
+    let flags =
+      flags_from_vec(svec!["deno", "fmt", "--unstable-css", "--unstable-yaml"])
+        .unwrap();
+    let DenoSubcommand::Fmt(fmt_flags) = flags.subcommand else {
+      unreachable!();
+    };
+    assert!(fmt_flags.unstable_css);
+    assert!(fmt_flags.unstable_yaml);
+  }
+
   #[test]
   fn lint() {
     let r = flags_from_vec(svec!["deno", "lint", "script_1.ts", "script_2.ts"]);
@@ -5792,14 +7189,21 @@ mod tests {
           files: FileFlags {
             include: vec!["script_1.ts".to_string(), "script_2.ts".to_string(),],
             ignore: vec![],
+            gitignore: false,
           },
           fix: false,
+          fix_dry_run: false,
+          explain: None,
           rules: false,
           maybe_rules_tags: None,
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: false,
           compact: false,
+          output: None,
+          max_warnings: None,
+          baseline: None,
+          write_baseline: false,
           watch: Default::default(),
         }),
         ..Flags::default()
@@ -5820,14 +7224,21 @@ mod tests {
           files: FileFlags {
             include: vec!["script_1.ts".to_string(), "script_2.ts".to_string()],
             ignore: vec![],
+            gitignore: false,
           },
           fix: false,
+          fix_dry_run: false,
+          explain: None,
           rules: false,
           maybe_rules_tags: None,
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: false,
           compact: false,
+          output: None,
+          max_warnings: None,
+          baseline: None,
+          write_baseline: false,
           watch: Some(Default::default()),
         }),
         ..Flags::default()
@@ -5849,14 +7260,21 @@ mod tests {
           files: FileFlags {
             include: vec!["script_1.ts".to_string(), "script_2.ts".to_string()],
             ignore: vec![],
+            gitignore: false,
           },
           fix: false,
+          fix_dry_run: false,
+          explain: None,
           rules: false,
           maybe_rules_tags: None,
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: false,
           compact: false,
+          output: None,
+          max_warnings: None,
+          baseline: None,
+          write_baseline: false,
           watch: Some(WatchFlags {
             hmr: false,
             no_clear_screen: true,
@@ -5880,21 +7298,28 @@ mod tests {
           files: FileFlags {
             include: vec![],
             ignore: vec!["script_1.ts".to_string(), "script_2.ts".to_string()],
+            gitignore: false,
           },
           fix: true,
+          fix_dry_run: false,
+          explain: None,
           rules: false,
           maybe_rules_tags: None,
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: false,
           compact: false,
+          output: None,
+          max_warnings: None,
+          baseline: None,
+          write_baseline: false,
           watch: Default::default(),
         }),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "lint", "--rules"]);
+    let r = flags_from_vec(svec!["deno", "lint", "--fix-dry-run"]);
     assert_eq!(
       r.unwrap(),
       Flags {
@@ -5902,26 +7327,31 @@ mod tests {
           files: FileFlags {
             include: vec![],
             ignore: vec![],
+            gitignore: false,
           },
           fix: false,
-          rules: true,
+          fix_dry_run: true,
+          explain: None,
+          rules: false,
           maybe_rules_tags: None,
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: false,
           compact: false,
+          output: None,
+          max_warnings: None,
+          baseline: None,
+          write_baseline: false,
           watch: Default::default(),
         }),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec![
-      "deno",
-      "lint",
-      "--rules",
-      "--rules-tags=recommended"
-    ]);
+    let r = flags_from_vec(svec!["deno", "lint", "--fix", "--fix-dry-run"]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec!["deno", "lint", "--rules"]);
     assert_eq!(
       r.unwrap(),
       Flags {
@@ -5929,14 +7359,21 @@ mod tests {
           files: FileFlags {
             include: vec![],
             ignore: vec![],
+            gitignore: false,
           },
           fix: false,
+          fix_dry_run: false,
+          explain: None,
           rules: true,
-          maybe_rules_tags: Some(svec!["recommended"]),
+          maybe_rules_tags: None,
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: false,
           compact: false,
+          output: None,
+          max_warnings: None,
+          baseline: None,
+          write_baseline: false,
           watch: Default::default(),
         }),
         ..Flags::default()
@@ -5946,9 +7383,8 @@ mod tests {
     let r = flags_from_vec(svec![
       "deno",
       "lint",
-      "--rules-tags=",
-      "--rules-include=ban-untagged-todo,no-undef",
-      "--rules-exclude=no-const-assign"
+      "--rules",
+      "--rules-tags=recommended"
     ]);
     assert_eq!(
       r.unwrap(),
@@ -5957,14 +7393,56 @@ mod tests {
           files: FileFlags {
             include: vec![],
             ignore: vec![],
+            gitignore: false,
           },
           fix: false,
-          rules: false,
-          maybe_rules_tags: Some(svec![""]),
+          fix_dry_run: false,
+          explain: None,
+          rules: true,
+          maybe_rules_tags: Some(svec!["recommended"]),
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          compact: false,
+          output: None,
+          max_warnings: None,
+          baseline: None,
+          write_baseline: false,
+          watch: Default::default(),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "lint",
+      "--rules-tags=",
+      "--rules-include=ban-untagged-todo,no-undef",
+      "--rules-exclude=no-const-assign"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+            gitignore: false,
+          },
+          fix: false,
+          fix_dry_run: false,
+          explain: None,
+          rules: false,
+          maybe_rules_tags: Some(svec![""]),
           maybe_rules_include: Some(svec!["ban-untagged-todo", "no-undef"]),
           maybe_rules_exclude: Some(svec!["no-const-assign"]),
           json: false,
           compact: false,
+          output: None,
+          max_warnings: None,
+          baseline: None,
+          write_baseline: false,
           watch: Default::default(),
         }),
         ..Flags::default()
@@ -5979,14 +7457,21 @@ mod tests {
           files: FileFlags {
             include: vec!["script_1.ts".to_string()],
             ignore: vec![],
+            gitignore: false,
           },
           fix: false,
+          fix_dry_run: false,
+          explain: None,
           rules: false,
           maybe_rules_tags: None,
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: true,
           compact: false,
+          output: None,
+          max_warnings: None,
+          baseline: None,
+          write_baseline: false,
           watch: Default::default(),
         }),
         ..Flags::default()
@@ -6008,14 +7493,21 @@ mod tests {
           files: FileFlags {
             include: vec!["script_1.ts".to_string()],
             ignore: vec![],
+            gitignore: false,
           },
           fix: false,
+          fix_dry_run: false,
+          explain: None,
           rules: false,
           maybe_rules_tags: None,
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: true,
           compact: false,
+          output: None,
+          max_warnings: None,
+          baseline: None,
+          write_baseline: false,
           watch: Default::default(),
         }),
         config_flag: ConfigFlag::Path("Deno.jsonc".to_string()),
@@ -6038,20 +7530,156 @@ mod tests {
           files: FileFlags {
             include: vec!["script_1.ts".to_string()],
             ignore: vec![],
+            gitignore: false,
           },
           fix: false,
+          fix_dry_run: false,
+          explain: None,
           rules: false,
           maybe_rules_tags: None,
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: false,
           compact: true,
+          output: None,
+          max_warnings: None,
+          baseline: None,
+          write_baseline: false,
           watch: Default::default(),
         }),
         config_flag: ConfigFlag::Path("Deno.jsonc".to_string()),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "lint", "--output=sarif"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+            gitignore: false,
+          },
+          fix: false,
+          fix_dry_run: false,
+          explain: None,
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          compact: false,
+          output: Some("sarif".to_string()),
+          max_warnings: None,
+          baseline: None,
+          write_baseline: false,
+          watch: Default::default(),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "lint", "--max-warnings=10"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+            gitignore: false,
+          },
+          fix: false,
+          fix_dry_run: false,
+          explain: None,
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          compact: false,
+          output: None,
+          max_warnings: Some(10),
+          baseline: None,
+          write_baseline: false,
+          watch: Default::default(),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "lint",
+      "--write-baseline",
+      "--baseline=lint-baseline.json"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+            gitignore: false,
+          },
+          fix: false,
+          fix_dry_run: false,
+          explain: None,
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          compact: false,
+          output: None,
+          max_warnings: None,
+          baseline: Some("lint-baseline.json".to_string()),
+          write_baseline: true,
+          watch: Default::default(),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "lint", "--explain=no-var", "--json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+            gitignore: false,
+          },
+          fix: false,
+          fix_dry_run: false,
+          explain: Some("no-var".to_string()),
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: true,
+          compact: false,
+          output: None,
+          max_warnings: None,
+          baseline: None,
+          write_baseline: false,
+          watch: Default::default(),
+        }),
+        ..Flags::default()
+      }
+    );
+    let r =
+      flags_from_vec(svec!["deno", "lint", "--explain=no-var", "--rules"]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec!["deno", "lint", "--output=sarif", "--json"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "lint", "--output=xml"]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -6088,6 +7716,7 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Check(CheckFlags {
           files: svec!["script.ts"],
+          watch: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -6101,6 +7730,7 @@ mod tests {
         Flags {
           subcommand: DenoSubcommand::Check(CheckFlags {
             files: svec!["script.ts"],
+            watch: None,
           }),
           type_check_mode: TypeCheckMode::All,
           ..Flags::default()
@@ -6121,6 +7751,33 @@ mod tests {
     }
   }
 
+  #[test]
+  fn check_watch() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "check",
+      "--watch",
+      "--no-clear-screen",
+      "--watch-exclude=foo",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Check(CheckFlags {
+          files: svec!["script.ts"],
+          watch: Some(WatchFlags {
+            hmr: false,
+            no_clear_screen: true,
+            exclude: svec!["foo"],
+          }),
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn info() {
     let r = flags_from_vec(svec!["deno", "info", "script.ts"]);
@@ -6128,6 +7785,8 @@ mod tests {
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
+          unused: false,
+          why: None,
           json: false,
           file: Some("script.ts".to_string()),
         }),
@@ -6140,6 +7799,8 @@ mod tests {
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
+          unused: false,
+          why: None,
           json: false,
           file: Some("script.ts".to_string()),
         }),
@@ -6153,6 +7814,8 @@ mod tests {
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
+          unused: false,
+          why: None,
           json: true,
           file: Some("script.ts".to_string()),
         }),
@@ -6165,6 +7828,8 @@ mod tests {
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
+          unused: false,
+          why: None,
           json: false,
           file: None
         }),
@@ -6177,6 +7842,8 @@ mod tests {
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
+          unused: false,
+          why: None,
           json: true,
           file: None
         }),
@@ -6196,6 +7863,8 @@ mod tests {
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
+          unused: false,
+          why: None,
           json: false,
           file: None
         }),
@@ -6207,6 +7876,58 @@ mod tests {
     );
   }
 
+  #[test]
+  fn info_unused() {
+    let r = flags_from_vec(svec!["deno", "info", "--unused"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          file: None,
+          unused: true,
+          why: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "info", "--unused", "--json", "main.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: true,
+          file: Some("main.ts".to_string()),
+          unused: true,
+          why: None,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn info_why() {
+    let r = flags_from_vec(svec!["deno", "info", "--why=npm:chalk", "main.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          file: Some("main.ts".to_string()),
+          unused: false,
+          why: Some("npm:chalk".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "info", "--why=npm:chalk"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn tsconfig() {
     let r =
@@ -7039,6 +8760,10 @@ mod tests {
           source_file: "source.ts".to_string(),
           out_file: None,
           watch: Default::default(),
+          sourcemap: None,
+          no_sources_content: false,
+          format: BundleFormat::Esm,
+          global_name: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -7064,6 +8789,10 @@ mod tests {
           source_file: "source.ts".to_string(),
           out_file: Some("bundle.js".to_string()),
           watch: Default::default(),
+          sourcemap: None,
+          no_sources_content: false,
+          format: BundleFormat::Esm,
+          global_name: None,
         }),
         permissions: PermissionFlags {
           allow_write: Some(vec![]),
@@ -7077,6 +8806,73 @@ mod tests {
     );
   }
 
+  #[test]
+  fn bundle_with_sourcemap() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "bundle",
+      "--sourcemap=external",
+      "--no-sources-content",
+      "source.ts",
+      "bundle.js"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bundle(BundleFlags {
+          source_file: "source.ts".to_string(),
+          out_file: Some("bundle.js".to_string()),
+          watch: Default::default(),
+          sourcemap: Some(BundleSourceMap::External),
+          no_sources_content: true,
+          format: BundleFormat::Esm,
+          global_name: None,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        permissions: PermissionFlags {
+          allow_write: Some(vec![]),
+          ..Default::default()
+        },
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "bundle",
+      "--no-sources-content",
+      "source.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn bundle_with_format() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "bundle",
+      "--format=iife",
+      "--global-name=Lib",
+      "source.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bundle(BundleFlags {
+          source_file: "source.ts".to_string(),
+          out_file: None,
+          watch: Default::default(),
+          sourcemap: None,
+          no_sources_content: false,
+          format: BundleFormat::Iife,
+          global_name: Some("Lib".to_string()),
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn bundle_with_output() {
     let r = flags_from_vec(svec!["deno", "bundle", "source.ts", "bundle.js"]);
@@ -7087,6 +8883,10 @@ mod tests {
           source_file: "source.ts".to_string(),
           out_file: Some("bundle.js".to_string()),
           watch: Default::default(),
+          sourcemap: None,
+          no_sources_content: false,
+          format: BundleFormat::Esm,
+          global_name: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         permissions: PermissionFlags {
@@ -7114,6 +8914,10 @@ mod tests {
           source_file: "source.ts".to_string(),
           out_file: None,
           watch: Default::default(),
+          sourcemap: None,
+          no_sources_content: false,
+          format: BundleFormat::Esm,
+          global_name: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         lock_write: true,
@@ -7134,6 +8938,10 @@ mod tests {
           source_file: "source.ts".to_string(),
           out_file: None,
           watch: Default::default(),
+          sourcemap: None,
+          no_sources_content: false,
+          format: BundleFormat::Esm,
+          global_name: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -7152,6 +8960,10 @@ mod tests {
           source_file: "script.ts".to_string(),
           out_file: None,
           watch: Default::default(),
+          sourcemap: None,
+          no_sources_content: false,
+          format: BundleFormat::Esm,
+          global_name: None,
         }),
         type_check_mode: TypeCheckMode::None,
         ..Flags::default()
@@ -7169,6 +8981,10 @@ mod tests {
           source_file: "source.ts".to_string(),
           out_file: None,
           watch: Some(Default::default()),
+          sourcemap: None,
+          no_sources_content: false,
+          format: BundleFormat::Esm,
+          global_name: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -7196,6 +9012,10 @@ mod tests {
             no_clear_screen: true,
             exclude: vec![],
           }),
+          sourcemap: None,
+          no_sources_content: false,
+          format: BundleFormat::Esm,
+          global_name: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -7236,6 +9056,8 @@ mod tests {
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
+          unused: false,
+          why: None,
           file: Some("script.ts".to_string()),
           json: false,
         }),
@@ -7283,6 +9105,10 @@ mod tests {
           html: None,
           lint: false,
           filter: None,
+          search_index: None,
+          markdown: None,
+          include_tags: vec![],
+          exclude_tags: vec![],
         }),
         import_map_path: Some("import_map.json".to_owned()),
         ..Flags::default()
@@ -7537,27 +9363,48 @@ mod tests {
   }
 
   #[test]
-  fn quiet() {
-    let r = flags_from_vec(svec!["deno", "run", "-q", "script.ts"]);
+  fn log_format() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--log-format=json", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
-        log_level: Some(Level::Error),
+        log_format: LogFormat::Json,
         code_cache_enabled: true,
         ..Flags::default()
       }
     );
+
+    let r =
+      flags_from_vec(svec!["deno", "run", "--log-format=xml", "script.ts"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn completions() {
-    let r = flags_from_vec(svec!["deno", "completions", "zsh"]).unwrap();
-
-    match r.subcommand {
-      DenoSubcommand::Completions(CompletionsFlags { buf }) => {
+  fn quiet() {
+    let r = flags_from_vec(svec!["deno", "run", "-q", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        log_level: Some(Level::Error),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn completions() {
+    let r = flags_from_vec(svec!["deno", "completions", "zsh"]).unwrap();
+
+    match r.subcommand {
+      DenoSubcommand::Completions(CompletionsFlags { buf }) => {
         assert!(!buf.is_empty())
       }
       _ => unreachable!(),
@@ -8170,6 +10017,7 @@ mod tests {
           files: FileFlags {
             include: vec!["dir1/".to_string(), "dir2/".to_string()],
             ignore: vec![],
+            gitignore: false,
           },
           shuffle: None,
           concurrent_jobs: None,
@@ -8178,6 +10026,17 @@ mod tests {
           watch: Default::default(),
           reporter: Default::default(),
           junit_path: None,
+          shard: None,
+          shard_tests: false,
+          retries: 0,
+          timeout: None,
+          update_snapshots: false,
+          isolate_processes: false,
+          child_process_module: None,
+          list: false,
+          json: false,
+          report_slow: None,
+          lines: vec![],
         }),
         unstable_config: UnstableConfig {
           legacy_flag_enabled: true,
@@ -8258,12 +10117,24 @@ mod tests {
           files: FileFlags {
             include: vec![],
             ignore: vec![],
+            gitignore: false,
           },
           concurrent_jobs: Some(NonZeroUsize::new(4).unwrap()),
           trace_leaks: false,
           coverage_dir: None,
           watch: Default::default(),
           junit_path: None,
+          shard: None,
+          shard_tests: false,
+          retries: 0,
+          timeout: None,
+          update_snapshots: false,
+          isolate_processes: false,
+          child_process_module: None,
+          list: false,
+          json: false,
+          report_slow: None,
+          lines: vec![],
         }),
         type_check_mode: TypeCheckMode::Local,
         permissions: PermissionFlags {
@@ -8278,6 +10149,36 @@ mod tests {
     assert!(r.is_err());
   }
 
+  #[test]
+  fn test_isolate_processes() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--isolate-processes",
+      "--child-process-module=file:///a_test.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          isolate_processes: true,
+          child_process_module: Some("file:///a_test.ts".to_string()),
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "test", "--isolate-processes", "--watch"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn test_with_fail_fast() {
     let r = flags_from_vec(svec!["deno", "test", "--fail-fast=3"]);
@@ -8294,6 +10195,7 @@ mod tests {
           files: FileFlags {
             include: vec![],
             ignore: vec![],
+            gitignore: false,
           },
           concurrent_jobs: None,
           trace_leaks: false,
@@ -8301,6 +10203,17 @@ mod tests {
           watch: Default::default(),
           reporter: Default::default(),
           junit_path: None,
+          shard: None,
+          shard_tests: false,
+          retries: 0,
+          timeout: None,
+          update_snapshots: false,
+          isolate_processes: false,
+          child_process_module: None,
+          list: false,
+          json: false,
+          report_slow: None,
+          lines: vec![],
         }),
         type_check_mode: TypeCheckMode::Local,
         permissions: PermissionFlags {
@@ -8335,6 +10248,7 @@ mod tests {
           files: FileFlags {
             include: vec![],
             ignore: vec![],
+            gitignore: false,
           },
           concurrent_jobs: None,
           trace_leaks: false,
@@ -8342,6 +10256,17 @@ mod tests {
           watch: Default::default(),
           reporter: Default::default(),
           junit_path: None,
+          shard: None,
+          shard_tests: false,
+          retries: 0,
+          timeout: None,
+          update_snapshots: false,
+          isolate_processes: false,
+          child_process_module: None,
+          list: false,
+          json: false,
+          report_slow: None,
+          lines: vec![],
         }),
         permissions: PermissionFlags {
           no_prompt: true,
@@ -8450,10 +10375,235 @@ mod tests {
       }
     );
 
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--reporter=junit",
+      "--output=report.xml"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          reporter: TestReporterConfig::Junit,
+          junit_path: Some("report.xml".to_string()),
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
     let r = flags_from_vec(svec!["deno", "test", "--junit-path"]);
     assert!(r.is_err());
   }
 
+  #[test]
+  fn test_shard() {
+    let r =
+      flags_from_vec(svec!["deno", "test", "--shard=3/8", "--shard-tests"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          shard: Some(TestShard { index: 3, count: 8 }),
+          shard_tests: true,
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    for shard in ["0/8", "9/8", "3", "a/8"] {
+      let r = flags_from_vec(svec!["deno", "test", format!("--shard={shard}")]);
+      assert!(r.is_err(), "{shard}");
+    }
+    let r = flags_from_vec(svec!["deno", "test", "--shard-tests"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_retries() {
+    let r = flags_from_vec(svec!["deno", "test", "--retries=2"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          retries: 2,
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--retries=-1"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_timeout() {
+    let r = flags_from_vec(svec!["deno", "test", "--timeout=500"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          timeout: Some(500),
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--timeout=0"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_list() {
+    let r = flags_from_vec(svec!["deno", "test", "--list", "--json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          list: true,
+          json: true,
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--json"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_report_slow() {
+    let r = flags_from_vec(svec!["deno", "test", "--report-slow=5"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          report_slow: Some(NonZeroUsize::new(5).unwrap()),
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--report-slow=0"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_split_test_line() {
+    assert_eq!(split_test_line("a_test.ts:42"), Some(("a_test.ts", 42)));
+    assert_eq!(
+      split_test_line("C:\\dir\\a_test.ts:7"),
+      Some(("C:\\dir\\a_test.ts", 7))
+    );
+    assert_eq!(
+      split_test_line("http://localhost:4545/a_test.ts:3"),
+      Some(("http://localhost:4545/a_test.ts", 3))
+    );
+    assert_eq!(split_test_line("a_test.ts"), None);
+    assert_eq!(split_test_line("a_test.ts:0"), None);
+    assert_eq!(split_test_line("a_test.ts:+1"), None);
+    assert_eq!(split_test_line("a_test.ts:"), None);
+    assert_eq!(split_test_line(":1"), None);
+    assert_eq!(split_test_line("dir/:1"), None);
+    assert_eq!(split_test_line("C:1"), None);
+    assert_eq!(split_test_line("http://localhost:4545"), None);
+    assert_eq!(split_test_line("http://localhost:4545/"), None);
+    assert_eq!(split_test_line("http://localhost:4545/a_test.ts"), None);
+  }
+
+  #[test]
+  fn test_with_lines() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "a_test.ts:42",
+      "dir/b_test.ts",
+      "C:\\c_test.ts:7"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          files: FileFlags {
+            include: vec![
+              "a_test.ts".to_string(),
+              "dir/b_test.ts".to_string(),
+              "C:\\c_test.ts".to_string(),
+            ],
+            ..Default::default()
+          },
+          lines: vec![
+            ("a_test.ts".to_string(), 42),
+            ("C:\\c_test.ts".to_string(), 7),
+          ],
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn test_update_snapshots() {
+    let r = flags_from_vec(svec!["deno", "test", "--update-snapshots"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          update_snapshots: true,
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn test_shuffle() {
     let r = flags_from_vec(svec!["deno", "test", "--shuffle=1"]);
@@ -8470,6 +10620,7 @@ mod tests {
           files: FileFlags {
             include: vec![],
             ignore: vec![],
+            gitignore: false,
           },
           concurrent_jobs: None,
           trace_leaks: false,
@@ -8477,6 +10628,17 @@ mod tests {
           watch: Default::default(),
           reporter: Default::default(),
           junit_path: None,
+          shard: None,
+          shard_tests: false,
+          retries: 0,
+          timeout: None,
+          update_snapshots: false,
+          isolate_processes: false,
+          child_process_module: None,
+          list: false,
+          json: false,
+          report_slow: None,
+          lines: vec![],
         }),
         permissions: PermissionFlags {
           no_prompt: true,
@@ -8504,6 +10666,7 @@ mod tests {
           files: FileFlags {
             include: vec![],
             ignore: vec![],
+            gitignore: false,
           },
           concurrent_jobs: None,
           trace_leaks: false,
@@ -8511,6 +10674,17 @@ mod tests {
           watch: Some(Default::default()),
           reporter: Default::default(),
           junit_path: None,
+          shard: None,
+          shard_tests: false,
+          retries: 0,
+          timeout: None,
+          update_snapshots: false,
+          isolate_processes: false,
+          child_process_module: None,
+          list: false,
+          json: false,
+          report_slow: None,
+          lines: vec![],
         }),
         permissions: PermissionFlags {
           no_prompt: true,
@@ -8537,6 +10711,7 @@ mod tests {
           files: FileFlags {
             include: vec!["./".to_string()],
             ignore: vec![],
+            gitignore: false,
           },
           concurrent_jobs: None,
           trace_leaks: false,
@@ -8544,6 +10719,17 @@ mod tests {
           watch: Some(Default::default()),
           reporter: Default::default(),
           junit_path: None,
+          shard: None,
+          shard_tests: false,
+          retries: 0,
+          timeout: None,
+          update_snapshots: false,
+          isolate_processes: false,
+          child_process_module: None,
+          list: false,
+          json: false,
+          report_slow: None,
+          lines: vec![],
         }),
         permissions: PermissionFlags {
           no_prompt: true,
@@ -8572,6 +10758,7 @@ mod tests {
           files: FileFlags {
             include: vec![],
             ignore: vec![],
+            gitignore: false,
           },
           concurrent_jobs: None,
           trace_leaks: false,
@@ -8583,6 +10770,17 @@ mod tests {
           }),
           reporter: Default::default(),
           junit_path: None,
+          shard: None,
+          shard_tests: false,
+          retries: 0,
+          timeout: None,
+          update_snapshots: false,
+          isolate_processes: false,
+          child_process_module: None,
+          list: false,
+          json: false,
+          report_slow: None,
+          lines: vec![],
         }),
         type_check_mode: TypeCheckMode::Local,
         permissions: PermissionFlags {
@@ -8630,6 +10828,10 @@ mod tests {
           source_file: "source.ts".to_string(),
           out_file: None,
           watch: Default::default(),
+          sourcemap: None,
+          no_sources_content: false,
+          format: BundleFormat::Esm,
+          global_name: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         ca_data: Some(CaData::File("example.crt".to_owned())),
@@ -8650,6 +10852,8 @@ mod tests {
           canary: false,
           version: None,
           output: None,
+          download_only: false,
+          targets: vec![],
         }),
         ca_data: Some(CaData::File("example.crt".to_owned())),
         ..Flags::default()
@@ -8692,6 +10896,8 @@ mod tests {
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
+          unused: false,
+          why: None,
           json: false,
           file: Some("https://example.com".to_string()),
         }),
@@ -8714,6 +10920,10 @@ mod tests {
           lint: false,
           source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
           filter: None,
+          search_index: None,
+          markdown: None,
+          include_tags: vec![],
+          exclude_tags: vec![],
         }),
         ..Flags::default()
       }
@@ -8742,6 +10952,10 @@ mod tests {
           }),
           source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
           filter: None,
+          search_index: None,
+          markdown: None,
+          include_tags: vec![],
+          exclude_tags: vec![],
         }),
         ..Flags::default()
       }
@@ -8769,6 +10983,10 @@ mod tests {
           lint: true,
           source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
           filter: None,
+          search_index: None,
+          markdown: None,
+          include_tags: vec![],
+          exclude_tags: vec![],
         }),
         ..Flags::default()
       }
@@ -8781,9 +10999,9 @@ mod tests {
     let r = flags_from_vec(svec![
       "deno",
       "doc",
-      "--filter",
-      "SomeClass.someField",
-      "path/to/module.ts",
+      "--output-format=markdown",
+      "--output=./wiki",
+      "path/to/module.ts"
     ]);
     assert_eq!(
       r.unwrap(),
@@ -8793,16 +11011,35 @@ mod tests {
           json: false,
           html: None,
           lint: false,
-          source_files: DocSourceFileFlag::Paths(vec![
-            "path/to/module.ts".to_string()
-          ]),
-          filter: Some("SomeClass.someField".to_string()),
+          source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
+          filter: None,
+          search_index: None,
+          markdown: Some(DocMarkdownFlag {
+            output: String::from("./wiki"),
+          }),
+          include_tags: vec![],
+          exclude_tags: vec![],
         }),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "doc"]);
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--output-format=markdown",
+      "--html",
+      "path/to/module.ts"
+    ]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--include-tag=public,stable",
+      "--exclude-tag=internal",
+      "path/to/module.ts"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
@@ -8811,8 +11048,12 @@ mod tests {
           json: false,
           html: None,
           lint: false,
-          source_files: Default::default(),
+          source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
           filter: None,
+          search_index: None,
+          markdown: None,
+          include_tags: svec!["public", "stable"],
+          exclude_tags: svec!["internal"],
         }),
         ..Flags::default()
       }
@@ -8821,21 +11062,102 @@ mod tests {
     let r = flags_from_vec(svec![
       "deno",
       "doc",
-      "--filter",
-      "Deno.Listener",
-      "--builtin"
+      "--html",
+      "--search-index=./search.json",
+      "path/to/module.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Doc(DocFlags {
           private: false,
-          lint: false,
           json: false,
-          html: None,
-          source_files: DocSourceFileFlag::Builtin,
-          filter: Some("Deno.Listener".to_string()),
-        }),
+          html: Some(DocHtmlFlag {
+            name: None,
+            output: String::from("./docs/"),
+          }),
+          lint: false,
+          source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
+          filter: None,
+          search_index: Some("./search.json".to_string()),
+          markdown: None,
+          include_tags: vec![],
+          exclude_tags: vec![],
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--filter",
+      "SomeClass.someField",
+      "path/to/module.ts",
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          html: None,
+          lint: false,
+          source_files: DocSourceFileFlag::Paths(vec![
+            "path/to/module.ts".to_string()
+          ]),
+          filter: Some("SomeClass.someField".to_string()),
+          search_index: None,
+          markdown: None,
+          include_tags: vec![],
+          exclude_tags: vec![],
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "doc"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          html: None,
+          lint: false,
+          source_files: Default::default(),
+          filter: None,
+          search_index: None,
+          markdown: None,
+          include_tags: vec![],
+          exclude_tags: vec![],
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--filter",
+      "Deno.Listener",
+      "--builtin"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          lint: false,
+          json: false,
+          html: None,
+          source_files: DocSourceFileFlag::Builtin,
+          filter: Some("Deno.Listener".to_string()),
+          search_index: None,
+          markdown: None,
+          include_tags: vec![],
+          exclude_tags: vec![],
+        }),
         ..Flags::default()
       }
     );
@@ -8858,6 +11180,10 @@ mod tests {
           html: None,
           source_files: DocSourceFileFlag::Paths(svec!["path/to/module.js"]),
           filter: None,
+          search_index: None,
+          markdown: None,
+          include_tags: vec![],
+          exclude_tags: vec![],
         }),
         no_npm: true,
         no_remote: true,
@@ -8884,6 +11210,10 @@ mod tests {
             "path/to/module2.js".to_string()
           ]),
           filter: None,
+          search_index: None,
+          markdown: None,
+          include_tags: vec![],
+          exclude_tags: vec![],
         }),
         ..Flags::default()
       }
@@ -8909,6 +11239,10 @@ mod tests {
             "path/to/module2.js".to_string()
           ]),
           filter: None,
+          search_index: None,
+          markdown: None,
+          include_tags: vec![],
+          exclude_tags: vec![],
         }),
         ..Flags::default()
       }
@@ -8937,6 +11271,10 @@ mod tests {
             "path/to/module2.js".to_string()
           ]),
           filter: None,
+          search_index: None,
+          markdown: None,
+          include_tags: vec![],
+          exclude_tags: vec![],
         }),
         ..Flags::default()
       }
@@ -9010,7 +11348,9 @@ mod tests {
           args: vec![],
           target: None,
           no_terminal: false,
-          include: vec![]
+          include: vec![],
+          icon: None,
+          exe_metadata: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -9032,7 +11372,9 @@ mod tests {
           args: svec!["foo", "bar", "-p", "8080"],
           target: None,
           no_terminal: true,
-          include: vec![]
+          include: vec![],
+          icon: None,
+          exe_metadata: None,
         }),
         import_map_path: Some("import_map.json".to_string()),
         no_remote: true,
@@ -9058,6 +11400,37 @@ mod tests {
     );
   }
 
+  #[test]
+  fn compile_with_windows_resources() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--target",
+      "x86_64-pc-windows-msvc",
+      "--icon",
+      "app.ico",
+      "--exe-metadata=meta.json",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "main.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: Some("x86_64-pc-windows-msvc".to_string()),
+          no_terminal: false,
+          include: vec![],
+          icon: Some("app.ico".to_string()),
+          exe_metadata: Some("meta.json".to_string()),
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn coverage() {
     let r = flags_from_vec(svec!["deno", "coverage", "foo.json"]);
@@ -9068,6 +11441,7 @@ mod tests {
           files: FileFlags {
             include: vec!["foo.json".to_string()],
             ignore: vec![],
+            gitignore: false,
           },
           include: vec![r"^file:".to_string()],
           exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
@@ -9094,15 +11468,171 @@ mod tests {
           files: FileFlags {
             include: vec!["foo.json".to_string()],
             ignore: vec![],
+            gitignore: false,
           },
           include: vec![r"^file:".to_string()],
           exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
           r#type: CoverageType::Lcov,
           output: Some(String::from("foo.lcov")),
+          check: false,
+          line_threshold: None,
+          branch_threshold: None,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn coverage_with_cobertura() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "coverage",
+      "--cobertura=coverage.xml",
+      "foo.json"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
+          files: FileFlags {
+            include: vec!["foo.json".to_string()],
+            ignore: vec![],
+            gitignore: false,
+          },
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          r#type: CoverageType::Cobertura,
+          output: Some(String::from("coverage.xml")),
+          check: false,
+          line_threshold: None,
+          branch_threshold: None,
         }),
         ..Flags::default()
       }
     );
+
+    let r =
+      flags_from_vec(svec!["deno", "coverage", "--cobertura", "foo.json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
+          files: FileFlags {
+            include: vec!["foo.json".to_string()],
+            ignore: vec![],
+            gitignore: false,
+          },
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          r#type: CoverageType::Cobertura,
+          output: None,
+          check: false,
+          line_threshold: None,
+          branch_threshold: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "coverage",
+      "--lcov",
+      "--cobertura=coverage.xml",
+      "foo.json"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn coverage_with_html() {
+    let r =
+      flags_from_vec(svec!["deno", "coverage", "--html=html_cov", "foo.json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
+          files: FileFlags {
+            include: vec!["foo.json".to_string()],
+            ignore: vec![],
+            gitignore: false,
+          },
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          r#type: CoverageType::Html,
+          output: Some(String::from("html_cov")),
+          check: false,
+          line_threshold: None,
+          branch_threshold: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "coverage", "--html", "foo.json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
+          files: FileFlags {
+            include: vec!["foo.json".to_string()],
+            ignore: vec![],
+            gitignore: false,
+          },
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          r#type: CoverageType::Html,
+          output: None,
+          check: false,
+          line_threshold: None,
+          branch_threshold: None,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn coverage_with_check() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "coverage",
+      "--check",
+      "--lines=80",
+      "--branches=60",
+      "foo.json"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
+          files: FileFlags {
+            include: vec!["foo.json".to_string()],
+            ignore: vec![],
+            gitignore: false,
+          },
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          check: true,
+          line_threshold: Some(80),
+          branch_threshold: Some(60),
+          ..CoverageFlags::default()
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "coverage", "--lines=80", "foo.json"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "coverage",
+      "--check",
+      "--lines=101",
+      "foo.json"
+    ]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -9115,6 +11645,7 @@ mod tests {
           files: FileFlags {
             include: vec!["coverage".to_string()],
             ignore: vec![],
+            gitignore: false,
           },
           include: vec![r"^file:".to_string()],
           exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
@@ -9184,7 +11715,13 @@ mod tests {
         subcommand: DenoSubcommand::Vendor(VendorFlags {
           specifiers: svec!["mod.ts"],
           force: false,
+          trash: false,
           output_path: None,
+          merge_config: false,
+          update: false,
+          prune: false,
+          only: vec![],
+          check: false,
         }),
         ..Flags::default()
       }
@@ -9203,6 +11740,8 @@ mod tests {
       "--lock",
       "lock.json",
       "--force",
+      "--trash",
+      "--merge-config",
       "--output",
       "out_dir",
       "--reload",
@@ -9215,7 +11754,13 @@ mod tests {
         subcommand: DenoSubcommand::Vendor(VendorFlags {
           specifiers: svec!["mod.ts", "deps.test.ts"],
           force: true,
+          trash: true,
           output_path: Some(String::from("out_dir")),
+          merge_config: true,
+          update: false,
+          prune: false,
+          only: vec![],
+          check: false,
         }),
         config_flag: ConfigFlag::Path("deno.json".to_owned()),
         import_map_path: Some("import_map.json".to_string()),
@@ -9226,6 +11771,92 @@ mod tests {
     );
   }
 
+  #[test]
+  fn vendor_update_prune() {
+    let r =
+      flags_from_vec(svec!["deno", "vendor", "--update", "--prune", "mod.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Vendor(VendorFlags {
+          specifiers: svec!["mod.ts"],
+          force: false,
+          trash: false,
+          output_path: None,
+          merge_config: false,
+          update: true,
+          prune: true,
+          only: vec![],
+          check: false,
+        }),
+        reload: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno", "vendor", "--update", "--force", "--trash", "mod.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn vendor_check() {
+    let r =
+      flags_from_vec(svec!["deno", "vendor", "--check", "--lock=lock.json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Vendor(VendorFlags {
+          specifiers: vec![],
+          force: false,
+          trash: false,
+          output_path: None,
+          merge_config: false,
+          update: false,
+          prune: false,
+          only: vec![],
+          check: true,
+        }),
+        lock: Some(String::from("lock.json")),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "vendor", "--check", "mod.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "vendor"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn vendor_only() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "vendor",
+      "--only=https://deno.land/x/oak/",
+      "--only=https://jsr.io/@std/",
+      "mod.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Vendor(VendorFlags {
+          specifiers: svec!["mod.ts"],
+          force: false,
+          trash: false,
+          output_path: None,
+          merge_config: false,
+          update: false,
+          prune: false,
+          only: svec!["https://deno.land/x/oak/", "https://jsr.io/@std/"],
+          check: false,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn task_subcommand() {
     let r = flags_from_vec(svec!["deno", "task", "build", "hello", "world",]);
@@ -9460,12 +12091,24 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Bench(BenchFlags {
           filter: Some("- foo".to_string()),
+          group: None,
           json: true,
+          json_path: None,
           no_run: true,
           files: FileFlags {
             include: vec!["dir1/".to_string(), "dir2/".to_string()],
             ignore: vec![],
+            gitignore: false,
           },
+          profile: None,
+          profile_dir: None,
+          warmup_time: None,
+          min_iterations: None,
+          max_time: None,
+          save_baseline: None,
+          compare_baseline: None,
+          regression_threshold: None,
+          against: None,
           watch: Default::default(),
         }),
         unstable_config: UnstableConfig {
@@ -9495,12 +12138,24 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Bench(BenchFlags {
           filter: None,
+          group: None,
           json: false,
+          json_path: None,
           no_run: false,
           files: FileFlags {
             include: vec![],
             ignore: vec![],
+            gitignore: false,
           },
+          profile: None,
+          profile_dir: None,
+          warmup_time: None,
+          min_iterations: None,
+          max_time: None,
+          save_baseline: None,
+          compare_baseline: None,
+          regression_threshold: None,
+          against: None,
           watch: Some(Default::default()),
         }),
         permissions: PermissionFlags {
@@ -9513,6 +12168,185 @@ mod tests {
     );
   }
 
+  #[test]
+  fn bench_profile() {
+    let r = flags_from_vec(svec!["deno", "bench", "--profile"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bench(BenchFlags {
+          profile: Some(BenchProfileFormat::CpuProfile),
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--profile=flamegraph",
+      "--profile-dir=profiles"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bench(BenchFlags {
+          profile: Some(BenchProfileFormat::Flamegraph),
+          profile_dir: Some("profiles".to_string()),
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "bench", "--profile=perf"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "bench", "--profile-dir=profiles"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn bench_group() {
+    let r = flags_from_vec(svec!["deno", "bench", "--group", "sorting"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bench(BenchFlags {
+          group: Some("sorting".to_string()),
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn bench_budget() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--warmup-time=100",
+      "--min-iterations=50",
+      "--max-time=2000"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bench(BenchFlags {
+          warmup_time: Some(100),
+          min_iterations: NonZeroU64::new(50),
+          max_time: Some(2000),
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn bench_baseline() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--save-baseline=feature",
+      "--compare=main",
+      "--regression-threshold=5"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bench(BenchFlags {
+          save_baseline: Some("feature".to_string()),
+          compare_baseline: Some("main".to_string()),
+          regression_threshold: Some(5),
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "bench", "--save-baseline=../main"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "bench", "--regression-threshold=5"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn bench_against() {
+    let r =
+      flags_from_vec(svec!["deno", "bench", "--against=/usr/bin/deno-old"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bench(BenchFlags {
+          against: Some("/usr/bin/deno-old".to_string()),
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--json",
+      "--against=/usr/bin/deno-old"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn bench_json_path() {
+    let r = flags_from_vec(svec!["deno", "bench", "--json-path=report.json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bench(BenchFlags {
+          json: true,
+          json_path: Some("report.json".to_string()),
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn run_with_check() {
     let r = flags_from_vec(svec!["deno", "run", "--check", "script.ts",]);
@@ -9738,4 +12572,21 @@ mod tests {
       }
     );
   }
+
+  #[test]
+  fn remove_subcommand() {
+    let r = flags_from_vec(svec!["deno", "remove"]);
+    r.unwrap_err();
+
+    let r = flags_from_vec(svec!["deno", "remove", "@david/which", "hello"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Remove(RemoveFlags {
+          packages: svec!["@david/which", "hello"],
+        }),
+        ..Flags::default()
+      }
+    );
+  }
 }
@@ -5,6 +5,7 @@ use std::path::PathBuf;
 use deno_core::error::AnyError;
 
 use crate::args::ConfigFile;
+use crate::util::fs::durable_atomic_write_file;
 use crate::Flags;
 
 use super::DenoSubcommand;
@@ -45,3 +46,20 @@ pub fn discover(
   let lockfile = Lockfile::new(filename, flags.lock_write)?;
   Ok(Some(lockfile))
 }
+
+/// Writes the lockfile to the file system when its content changed.
+///
+/// Unlike `Lockfile::write`, which truncates the file before writing to it,
+/// this replaces the file atomically and flushes it to disk, so a crash
+/// never leaves a partially written lockfile behind.
+pub fn write_lockfile(lockfile: &Lockfile) -> Result<(), AnyError> {
+  if !lockfile.has_content_changed && !lockfile.overwrite {
+    return Ok(());
+  }
+  durable_atomic_write_file(
+    &lockfile.filename,
+    lockfile.as_json_string(),
+    0o644,
+  )?;
+  Ok(())
+}
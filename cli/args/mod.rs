@@ -6,7 +6,9 @@ mod flags_net;
 mod import_map;
 mod lockfile;
 pub mod package_json;
+mod permissions;
 
+use self::deno_json::TasksConfigWithOptions;
 pub use self::import_map::resolve_import_map;
 use self::package_json::PackageJsonDeps;
 use ::import_map::ImportMap;
@@ -32,9 +34,11 @@ pub use deno_config::TsConfigType;
 pub use deno_config::TsTypeLib;
 pub use deno_config::WorkspaceConfig;
 pub use flags::*;
+pub use lockfile::write_lockfile;
 pub use lockfile::Lockfile;
 pub use lockfile::LockfileError;
 pub use package_json::PackageJsonDepsProvider;
+pub use permissions::plugin_permissions;
 
 use deno_ast::ModuleSpecifier;
 use deno_core::anyhow::bail;
@@ -58,11 +62,13 @@ use once_cell::sync::OnceCell;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::env;
 use std::io::BufReader;
 use std::io::Cursor;
 use std::net::SocketAddr;
 use std::num::NonZeroU16;
+use std::num::NonZeroU64;
 use std::num::NonZeroUsize;
 use std::path::Path;
 use std::path::PathBuf;
@@ -72,8 +78,11 @@ use thiserror::Error;
 use crate::args::import_map::enhance_import_map_value_with_workspace_members;
 use crate::file_fetcher::FileFetcher;
 use crate::util::fs::canonicalize_path_maybe_not_exists;
+use crate::util::fs::exclude_path_or_patterns;
+use crate::util::fs::include_path_or_patterns;
 use crate::version;
 
+use deno_config::glob::PathKind;
 use deno_config::glob::PathOrPatternSet;
 use deno_config::FmtConfig;
 use deno_config::LintConfig;
@@ -114,6 +123,11 @@ pub static DENO_DISABLE_PEDANTIC_NODE_WARNINGS: Lazy<bool> = Lazy::new(|| {
 pub static DENO_FUTURE: Lazy<bool> =
   Lazy::new(|| std::env::var("DENO_FUTURE").ok().is_some());
 
+/// Move directories in `node_modules` that get replaced to the trash
+/// instead of deleting them.
+pub static DENO_NPM_TRASH: Lazy<bool> =
+  Lazy::new(|| std::env::var("DENO_NPM_TRASH").ok().is_some());
+
 pub fn jsr_url() -> &'static Url {
   static JSR_URL: Lazy<Url> = Lazy::new(|| {
     let env_var_name = "JSR_URL";
@@ -243,12 +257,55 @@ impl CacheSetting {
   }
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BenchProfileOptions {
+  pub format: BenchProfileFormat,
+  pub dir: PathBuf,
+  /// The directory that the paths of local bench modules are made relative
+  /// to in the profile file names.
+  pub root: PathBuf,
+}
+
+/// How long each benchmark is warmed up and measured for, which the
+/// benchmarks can override.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchBudgetOptions {
+  /// The warmup time in milliseconds.
+  pub warmup_time: u64,
+  /// At least one iteration is measured so the statistics are defined.
+  pub min_iterations: NonZeroU64,
+  /// The minimum measurement time in milliseconds.
+  pub max_time: u64,
+}
+
+impl Default for BenchBudgetOptions {
+  fn default() -> Self {
+    Self {
+      warmup_time: 10,
+      min_iterations: NonZeroU64::new(10).unwrap(),
+      max_time: 500,
+    }
+  }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BenchOptions {
   pub files: FilePatterns,
   pub filter: Option<String>,
+  pub group: Option<String>,
   pub json: bool,
+  /// The file the JSON report is written to instead of stdout.
+  pub json_path: Option<PathBuf>,
   pub no_run: bool,
+  pub profile: Option<BenchProfileOptions>,
+  pub budget: BenchBudgetOptions,
+  pub save_baseline: Option<String>,
+  pub compare_baseline: Option<String>,
+  /// The percentage a benchmark may get slower than its baseline.
+  pub regression_threshold: u32,
+  /// Another Deno executable to run the benchmarks with for comparison.
+  pub against: Option<PathBuf>,
 }
 
 impl BenchOptions {
@@ -265,8 +322,34 @@ impl BenchOptions {
         initial_cwd,
       )?,
       filter: bench_flags.filter,
+      group: bench_flags.group,
       json: bench_flags.json,
+      json_path: bench_flags.json_path.map(|path| initial_cwd.join(path)),
       no_run: bench_flags.no_run,
+      profile: bench_flags.profile.map(|format| BenchProfileOptions {
+        format,
+        dir: initial_cwd.join(
+          bench_flags
+            .profile_dir
+            .as_deref()
+            .unwrap_or("bench_profile"),
+        ),
+        root: initial_cwd.to_path_buf(),
+      }),
+      budget: {
+        let defaults = BenchBudgetOptions::default();
+        BenchBudgetOptions {
+          warmup_time: bench_flags.warmup_time.unwrap_or(defaults.warmup_time),
+          min_iterations: bench_flags
+            .min_iterations
+            .unwrap_or(defaults.min_iterations),
+          max_time: bench_flags.max_time.unwrap_or(defaults.max_time),
+        }
+      },
+      save_baseline: bench_flags.save_baseline,
+      compare_baseline: bench_flags.compare_baseline,
+      regression_threshold: bench_flags.regression_threshold.unwrap_or(10),
+      against: bench_flags.against.map(PathBuf::from),
     })
   }
 }
@@ -276,6 +359,60 @@ pub struct FmtOptions {
   pub check: bool,
   pub options: FmtOptionsConfig,
   pub files: FilePatterns,
+  /// Skip the files ignored by `.gitignore` files.
+  pub gitignore: bool,
+  pub overrides: Vec<FmtOverride>,
+  pub plugins: Vec<FmtPluginConfig>,
+  /// Sort and group the imports at the top of JS and TS files.
+  pub sort_imports: bool,
+  /// Languages of the markdown code blocks that aren't formatted.
+  pub skip_code_block_languages: Vec<String>,
+  pub unstable: UnstableFmtOptions,
+  /// Only format the files changed since this git ref.
+  pub changed: Option<String>,
+  /// Only apply the formatting changes within these lines.
+  pub range: Option<FmtRange>,
+  pub diff_style: FmtDiffStyle,
+  /// Write the patch to this file instead of stdout.
+  pub diff_output: Option<PathBuf>,
+}
+
+/// Overrides, plugins, import sorting and skipped code block languages
+/// from the `fmt` configuration, plus the formatters enabled by `unstable`.
+#[derive(Clone, Debug, Default)]
+pub struct FmtConfigExtras {
+  pub overrides: Vec<FmtOverride>,
+  pub plugins: Vec<FmtPluginConfig>,
+  pub sort_imports: bool,
+  pub skip_code_block_languages: Vec<String>,
+  pub unstable: UnstableFmtOptions,
+}
+
+/// The formatters of `deno fmt` that need to be opted into, either with a
+/// flag or with the `unstable` configuration.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct UnstableFmtOptions {
+  /// Format CSS, SCSS and LESS files (`--unstable-css` or `fmt-css`).
+  pub css: bool,
+  /// Format YAML files, along with YAML front matter and code blocks in
+  /// markdown (`--unstable-yaml` or `fmt-yaml`).
+  pub yaml: bool,
+}
+
+/// A dprint Wasm plugin from `fmt.plugins`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FmtPluginConfig {
+  pub specifier: ModuleSpecifier,
+  /// Plugin specific configuration passed to the plugin as-is.
+  pub options: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Options from `fmt.overrides` used instead of the base options
+/// for the files matching the patterns.
+#[derive(Clone, Debug)]
+pub struct FmtOverride {
+  pub files: FilePatterns,
+  pub options: FmtOptionsConfig,
 }
 
 impl Default for FmtOptions {
@@ -290,19 +427,64 @@ impl FmtOptions {
       check: false,
       options: FmtOptionsConfig::default(),
       files: FilePatterns::new_with_base(base),
+      gitignore: false,
+      overrides: Vec::new(),
+      plugins: Vec::new(),
+      sort_imports: false,
+      skip_code_block_languages: Vec::new(),
+      unstable: UnstableFmtOptions::default(),
+      changed: None,
+      range: None,
+      diff_style: FmtDiffStyle::Pretty,
+      diff_output: None,
     }
   }
 
   pub fn resolve(
     maybe_fmt_config: Option<FmtConfig>,
+    fmt_config_extras: FmtConfigExtras,
     maybe_fmt_flags: Option<FmtFlags>,
     initial_cwd: &Path,
   ) -> Result<Self, AnyError> {
     let (maybe_config_options, maybe_config_files) =
       maybe_fmt_config.map(|c| (c.options, c.files)).unzip();
 
+    // flags take precedence over the overrides, which take
+    // precedence over the base options in the config file
+    let overrides = fmt_config_extras
+      .overrides
+      .into_iter()
+      .map(|o| FmtOverride {
+        files: o.files,
+        options: resolve_fmt_options(
+          maybe_fmt_flags.as_ref(),
+          Some(merge_fmt_options(maybe_config_options.as_ref(), o.options)),
+        ),
+      })
+      .collect();
+    let unstable = UnstableFmtOptions {
+      css: fmt_config_extras.unstable.css
+        || maybe_fmt_flags.as_ref().is_some_and(|f| f.unstable_css),
+      yaml: fmt_config_extras.unstable.yaml
+        || maybe_fmt_flags.as_ref().is_some_and(|f| f.unstable_yaml),
+    };
+
     Ok(Self {
       check: maybe_fmt_flags.as_ref().map(|f| f.check).unwrap_or(false),
+      changed: maybe_fmt_flags.as_ref().and_then(|f| f.changed.clone()),
+      range: maybe_fmt_flags.as_ref().and_then(|f| f.range),
+      diff_style: maybe_fmt_flags
+        .as_ref()
+        .map(|f| f.diff_style)
+        .unwrap_or_default(),
+      diff_output: maybe_fmt_flags
+        .as_ref()
+        .and_then(|f| f.diff_output.as_ref())
+        .map(|path| initial_cwd.join(path)),
+      gitignore: maybe_fmt_flags
+        .as_ref()
+        .map(|f| f.files.gitignore)
+        .unwrap_or(false),
       options: resolve_fmt_options(
         maybe_fmt_flags.as_ref(),
         maybe_config_options,
@@ -312,8 +494,43 @@ impl FmtOptions {
         maybe_fmt_flags.map(|f| f.files),
         initial_cwd,
       )?,
+      overrides,
+      plugins: fmt_config_extras.plugins,
+      sort_imports: fmt_config_extras.sort_imports,
+      skip_code_block_languages: fmt_config_extras.skip_code_block_languages,
+      unstable,
     })
   }
+
+  /// Gets the options for formatting the file at the provided path. The
+  /// last override matching the path wins.
+  pub fn options_for_path(&self, path: &Path) -> &FmtOptionsConfig {
+    self
+      .overrides
+      .iter()
+      .rev()
+      .find(|o| o.files.matches_path(path, PathKind::File))
+      .map(|o| &o.options)
+      .unwrap_or(&self.options)
+  }
+}
+
+/// Fills in the options that aren't set by an override with the base options.
+fn merge_fmt_options(
+  base: Option<&FmtOptionsConfig>,
+  options: FmtOptionsConfig,
+) -> FmtOptionsConfig {
+  let Some(base) = base else {
+    return options;
+  };
+  FmtOptionsConfig {
+    use_tabs: options.use_tabs.or(base.use_tabs),
+    line_width: options.line_width.or(base.line_width),
+    indent_width: options.indent_width.or(base.indent_width),
+    single_quote: options.single_quote.or(base.single_quote),
+    prose_wrap: options.prose_wrap.or(base.prose_wrap),
+    semi_colons: options.semi_colons.or(base.semi_colons),
+  }
 }
 
 fn resolve_fmt_options(
@@ -360,6 +577,8 @@ fn resolve_fmt_options(
 #[derive(Clone)]
 pub struct TestOptions {
   pub files: FilePatterns,
+  /// Skip the files ignored by `.gitignore` files.
+  pub gitignore: bool,
   pub doc: bool,
   pub no_run: bool,
   pub fail_fast: Option<NonZeroUsize>,
@@ -370,17 +589,51 @@ pub struct TestOptions {
   pub trace_leaks: bool,
   pub reporter: TestReporterConfig,
   pub junit_path: Option<String>,
+  pub shard: Option<TestShard>,
+  /// Split the individual tests across the shards instead of the modules.
+  pub shard_tests: bool,
+  pub retries: usize,
+  /// The default timeout of the tests in milliseconds.
+  pub timeout: Option<u64>,
+  pub update_snapshots: bool,
+  pub child_process_module: Option<String>,
+  pub list: bool,
+  pub json: bool,
+  pub report_slow: Option<NonZeroUsize>,
+  /// The lines of the `path:line` arguments of each module.
+  pub lines: HashMap<ModuleSpecifier, Vec<u32>>,
+  /// The module from `test.setup` that runs once before all the tests, and
+  /// again before every rerun in watch mode.
+  pub setup: Option<ModuleSpecifier>,
+  /// The module from `test.teardown` that runs once after all the tests, and
+  /// again after every rerun in watch mode.
+  pub teardown: Option<ModuleSpecifier>,
+}
+
+/// The `test.setup` and `test.teardown` modules, resolved relative to the
+/// configuration file.
+#[derive(Clone, Debug, Default)]
+pub struct TestConfigExtras {
+  pub setup: Option<ModuleSpecifier>,
+  pub teardown: Option<ModuleSpecifier>,
 }
 
 impl TestOptions {
   pub fn resolve(
     maybe_test_config: Option<TestConfig>,
+    test_config_extras: TestConfigExtras,
     maybe_test_flags: Option<TestFlags>,
     initial_cwd: &Path,
   ) -> Result<Self, AnyError> {
     let test_flags = maybe_test_flags.unwrap_or_default();
+    let mut lines = HashMap::<ModuleSpecifier, Vec<u32>>::new();
+    for (path, line) in &test_flags.lines {
+      let specifier = resolve_url_or_path(path, initial_cwd)?;
+      lines.entry(specifier).or_default().push(*line);
+    }
 
     Ok(Self {
+      gitignore: test_flags.files.gitignore,
       files: resolve_files(
         maybe_test_config.map(|c| c.files),
         Some(test_flags.files),
@@ -398,24 +651,127 @@ impl TestOptions {
       trace_leaks: test_flags.trace_leaks,
       reporter: test_flags.reporter,
       junit_path: test_flags.junit_path,
+      shard: test_flags.shard,
+      shard_tests: test_flags.shard_tests,
+      retries: test_flags.retries,
+      timeout: test_flags.timeout,
+      update_snapshots: test_flags.update_snapshots,
+      child_process_module: test_flags.child_process_module,
+      list: test_flags.list,
+      json: test_flags.json,
+      report_slow: test_flags.report_slow,
+      lines,
+      setup: test_config_extras.setup,
+      teardown: test_config_extras.teardown,
     })
   }
 }
 
+/// The minimum coverage percentages of `deno coverage --check`, which are
+/// set with flags or with the `test.coverage` key of the configuration file.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CoverageThresholds {
+  pub lines: Option<u8>,
+  pub branches: Option<u8>,
+}
+
+impl CoverageThresholds {
+  /// Resolves the thresholds, where the flags take precedence over the
+  /// configuration file.
+  pub fn resolve(
+    config_thresholds: CoverageThresholds,
+    coverage_flags: &CoverageFlags,
+  ) -> Self {
+    Self {
+      lines: coverage_flags.line_threshold.or(config_thresholds.lines),
+      branches: coverage_flags
+        .branch_threshold
+        .or(config_thresholds.branches),
+    }
+  }
+}
+
+/// The `test.coverage` configuration, which deno_config doesn't understand.
+#[derive(Clone, Debug, Default)]
+pub struct CoverageConfig {
+  pub thresholds: CoverageThresholds,
+  /// The files that are collected by `deno test --coverage` and reported by
+  /// `deno coverage`.
+  pub files: Option<FilePatterns>,
+}
+
 #[derive(Clone, Default, Debug)]
 pub enum LintReporterKind {
   #[default]
   Pretty,
   Json,
   Compact,
+  Sarif,
+}
+
+impl LintReporterKind {
+  fn from_name(name: &str) -> Option<Self> {
+    match name {
+      "pretty" => Some(Self::Pretty),
+      "json" => Some(Self::Json),
+      "compact" => Some(Self::Compact),
+      "sarif" => Some(Self::Sarif),
+      _ => None,
+    }
+  }
 }
 
 #[derive(Clone, Debug)]
 pub struct LintOptions {
   pub rules: LintRulesConfig,
   pub files: FilePatterns,
+  /// Skip the files ignored by `.gitignore` files.
+  pub gitignore: bool,
+  /// Descend into symlinked directories while collecting the files.
+  pub follow_symlinks: bool,
+  /// How many directories below the base of the files to descend into.
+  pub max_depth: Option<usize>,
   pub reporter_kind: LintReporterKind,
   pub fix: bool,
+  /// Show the fixes that `fix` would apply instead of writing them.
+  pub fix_dry_run: bool,
+  pub overrides: Vec<LintOverride>,
+  pub plugins: Vec<ModuleSpecifier>,
+  /// Rules whose diagnostics are reported as warnings, which don't cause
+  /// linting to fail unless there are more than `max_warnings`.
+  pub warn_rules: HashSet<String>,
+  pub max_warnings: Option<usize>,
+  /// The baseline file from `--baseline`, otherwise the default one next to
+  /// the config file is used.
+  pub baseline: Option<PathBuf>,
+  pub write_baseline: bool,
+}
+
+/// Lint settings from the configuration file that `LintConfig` has no
+/// field for, such as rule severities and the file walking options.
+#[derive(Clone, Debug, Default)]
+pub struct LintConfigExtras {
+  pub overrides: Vec<LintOverride>,
+  /// JavaScript or Wasm modules from `lint.plugins` providing more rules.
+  pub plugins: Vec<ModuleSpecifier>,
+  /// Rules configured with the "warn" severity in `lint.rules`.
+  pub warn_rules: Vec<String>,
+  /// Skip the files ignored by `.gitignore` files like `--gitignore` does.
+  pub use_gitignore: bool,
+  /// Descend into symlinked directories, like symlinked workspace packages.
+  pub follow_symlinks: bool,
+  /// Limit on how deep to descend into directories.
+  pub max_depth: Option<usize>,
+}
+
+/// Rules from `lint.overrides` used instead of the base rules
+/// for the files matching the patterns.
+#[derive(Clone, Debug)]
+pub struct LintOverride {
+  pub files: FilePatterns,
+  pub rules: LintRulesConfig,
+  /// Rules whose diagnostics are reported as warnings in these files.
+  pub warn_rules: HashSet<String>,
 }
 
 impl Default for LintOptions {
@@ -429,17 +785,46 @@ impl LintOptions {
     Self {
       rules: Default::default(),
       files: FilePatterns::new_with_base(base),
+      gitignore: false,
+      follow_symlinks: false,
+      max_depth: None,
       reporter_kind: Default::default(),
       fix: false,
+      fix_dry_run: false,
+      overrides: Vec::new(),
+      plugins: Vec::new(),
+      warn_rules: HashSet::new(),
+      max_warnings: None,
+      baseline: None,
+      write_baseline: false,
     }
   }
 
   pub fn resolve(
     maybe_lint_config: Option<LintConfig>,
+    lint_config_extras: LintConfigExtras,
     maybe_lint_flags: Option<LintFlags>,
     initial_cwd: &Path,
   ) -> Result<Self, AnyError> {
     let fix = maybe_lint_flags.as_ref().map(|f| f.fix).unwrap_or(false);
+    let fix_dry_run = maybe_lint_flags
+      .as_ref()
+      .map(|f| f.fix_dry_run)
+      .unwrap_or(false);
+    let max_warnings = maybe_lint_flags.as_ref().and_then(|f| f.max_warnings);
+    let baseline = maybe_lint_flags
+      .as_ref()
+      .and_then(|f| f.baseline.as_ref())
+      .map(|path| initial_cwd.join(path));
+    let write_baseline = maybe_lint_flags
+      .as_ref()
+      .map(|f| f.write_baseline)
+      .unwrap_or(false);
+    let gitignore = lint_config_extras.use_gitignore
+      || maybe_lint_flags
+        .as_ref()
+        .map(|f| f.files.gitignore)
+        .unwrap_or(false);
     let mut maybe_reporter_kind =
       maybe_lint_flags.as_ref().and_then(|lint_flags| {
         if lint_flags.json {
@@ -447,7 +832,10 @@ impl LintOptions {
         } else if lint_flags.compact {
           Some(LintReporterKind::Compact)
         } else {
-          None
+          lint_flags
+            .output
+            .as_deref()
+            .and_then(LintReporterKind::from_name)
         }
       });
 
@@ -455,12 +843,10 @@ impl LintOptions {
       // Flag not set, so try to get lint reporter from the config file.
       if let Some(lint_config) = &maybe_lint_config {
         maybe_reporter_kind = match lint_config.report.as_deref() {
-          Some("json") => Some(LintReporterKind::Json),
-          Some("compact") => Some(LintReporterKind::Compact),
-          Some("pretty") => Some(LintReporterKind::Pretty),
-          Some(_) => {
-            bail!("Invalid lint report type in config file")
-          }
+          Some(report) => match LintReporterKind::from_name(report) {
+            Some(kind) => Some(kind),
+            None => bail!("Invalid lint report type in config file"),
+          },
           None => None,
         }
       }
@@ -484,6 +870,34 @@ impl LintOptions {
 
     let (maybe_config_files, maybe_config_rules) =
       maybe_lint_config.map(|c| (c.files, c.rules)).unzip();
+    // flags take precedence over the overrides, which take
+    // precedence over the base rules in the config file
+    let overrides = lint_config_extras
+      .overrides
+      .into_iter()
+      .map(|o| LintOverride {
+        files: o.files,
+        // the base severities apply to the rules the override doesn't set
+        warn_rules: lint_config_extras
+          .warn_rules
+          .iter()
+          .filter(|rule| {
+            ![&o.rules.include, &o.rules.exclude]
+              .into_iter()
+              .flatten()
+              .any(|rules| rules.contains(rule))
+          })
+          .cloned()
+          .chain(o.warn_rules)
+          .collect(),
+        rules: resolve_lint_rules_options(
+          Some(merge_lint_rules(maybe_config_rules.as_ref(), o.rules)),
+          maybe_rules_tags.clone(),
+          maybe_rules_include.clone(),
+          maybe_rules_exclude.clone(),
+        ),
+      })
+      .collect();
     Ok(Self {
       reporter_kind: maybe_reporter_kind.unwrap_or_default(),
       files: resolve_files(
@@ -497,9 +911,44 @@ impl LintOptions {
         maybe_rules_include,
         maybe_rules_exclude,
       ),
+      gitignore,
+      follow_symlinks: lint_config_extras.follow_symlinks,
+      max_depth: lint_config_extras.max_depth,
       fix,
+      fix_dry_run,
+      overrides,
+      plugins: lint_config_extras.plugins,
+      warn_rules: lint_config_extras.warn_rules.into_iter().collect(),
+      max_warnings,
+      baseline,
+      write_baseline,
     })
   }
+
+  /// Gets the index of the override whose rules are used for the file at
+  /// the provided path. The last override matching the path wins.
+  pub fn override_index_for_path(&self, path: &Path) -> Option<usize> {
+    self
+      .overrides
+      .iter()
+      .rposition(|o| o.files.matches_path(path, PathKind::File))
+  }
+}
+
+/// Fills in the rule options that aren't set by an override with the base
+/// rules.
+fn merge_lint_rules(
+  base: Option<&LintRulesConfig>,
+  rules: LintRulesConfig,
+) -> LintRulesConfig {
+  let Some(base) = base else {
+    return rules;
+  };
+  LintRulesConfig {
+    tags: rules.tags.or_else(|| base.tags.clone()),
+    include: rules.include.or_else(|| base.include.clone()),
+    exclude: rules.exclude.or_else(|| base.exclude.clone()),
+  }
 }
 
 fn resolve_lint_rules_options(
@@ -529,6 +978,15 @@ fn resolve_lint_rules_options(
   }
 }
 
+/// Options of a task that is defined as an object in the config file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TaskOptions {
+  /// Directory to run the task in, resolved relative to the config file.
+  pub cwd: Option<PathBuf>,
+  /// Environment variables to set when running the task.
+  pub env: IndexMap<String, String>,
+}
+
 /// Discover `package.json` file. If `maybe_stop_at` is provided, we will stop
 /// crawling up the directory tree at that path.
 fn discover_package_json(
@@ -710,6 +1168,7 @@ static NPM_PROCESS_STATE: Lazy<Option<NpmProcessState>> = Lazy::new(|| {
 #[derive(Default, Clone)]
 struct CliOptionOverrides {
   import_map_specifier: Option<Option<ModuleSpecifier>>,
+  script_import_map: Option<(ModuleSpecifier, serde_json::Value)>,
 }
 
 /// Holds the resolved options of many sources used by subcommands
@@ -1016,6 +1475,14 @@ impl CliOptions {
       return Ok(None);
     }
 
+    if let Some((base_url, value)) = &self.overrides.script_import_map {
+      return import_map::import_map_from_value(
+        base_url.clone(),
+        value.clone(),
+      )
+      .map(Some);
+    }
+
     let import_map_specifier = self.resolve_specified_import_map_specifier()?;
     resolve_import_map(
       import_map_specifier.as_ref(),
@@ -1154,6 +1621,28 @@ impl CliOptions {
     self.overrides.import_map_specifier = Some(path);
   }
 
+  /// Sets the import map declared in the metadata of a script, which is
+  /// resolved relative to the script.
+  pub fn set_script_import_map(
+    &mut self,
+    script_specifier: ModuleSpecifier,
+    value: serde_json::Value,
+  ) {
+    self.overrides.script_import_map = Some((script_specifier, value));
+  }
+
+  /// Overrides the "imports" and "scopes" of the config file.
+  pub fn set_config_file_imports(
+    &mut self,
+    imports: Option<serde_json::Value>,
+    scopes: Option<serde_json::Value>,
+  ) {
+    if let Some(config_file) = &mut self.maybe_config_file {
+      config_file.json.imports = imports;
+      config_file.json.scopes = scopes;
+    }
+  }
+
   pub fn has_node_modules_dir(&self) -> bool {
     self.maybe_node_modules_folder.is_some()
   }
@@ -1249,9 +1738,9 @@ impl CliOptions {
 
   pub fn resolve_tasks_config(
     &self,
-  ) -> Result<IndexMap<String, deno_config::Task>, AnyError> {
+  ) -> Result<TasksConfigWithOptions, AnyError> {
     if let Some(config_file) = &self.maybe_config_file {
-      config_file.resolve_tasks_config()
+      deno_json::to_tasks_config_with_options(config_file)
     } else if self.maybe_package_json.is_some() {
       Ok(Default::default())
     } else {
@@ -1320,24 +1809,36 @@ impl CliOptions {
     &self,
     fmt_flags: FmtFlags,
   ) -> Result<FmtOptions, AnyError> {
-    let maybe_fmt_config = if let Some(config_file) = &self.maybe_config_file {
-      config_file.to_fmt_config()?
-    } else {
-      None
-    };
-    FmtOptions::resolve(maybe_fmt_config, Some(fmt_flags), &self.initial_cwd)
+    let (maybe_fmt_config, fmt_config_extras) =
+      if let Some(config_file) = &self.maybe_config_file {
+        deno_json::to_fmt_config_with_extras(config_file)?
+      } else {
+        (None, Default::default())
+      };
+    FmtOptions::resolve(
+      maybe_fmt_config,
+      fmt_config_extras,
+      Some(fmt_flags),
+      &self.initial_cwd,
+    )
   }
 
   pub fn resolve_lint_options(
     &self,
     lint_flags: LintFlags,
   ) -> Result<LintOptions, AnyError> {
-    let maybe_lint_config = if let Some(config_file) = &self.maybe_config_file {
-      config_file.to_lint_config()?
-    } else {
-      None
-    };
-    LintOptions::resolve(maybe_lint_config, Some(lint_flags), &self.initial_cwd)
+    let (maybe_lint_config, lint_config_extras) =
+      if let Some(config_file) = &self.maybe_config_file {
+        deno_json::to_lint_config_with_extras(config_file)?
+      } else {
+        (None, Default::default())
+      };
+    LintOptions::resolve(
+      maybe_lint_config,
+      lint_config_extras,
+      Some(lint_flags),
+      &self.initial_cwd,
+    )
   }
 
   pub fn resolve_config_excludes(&self) -> Result<PathOrPatternSet, AnyError> {
@@ -1354,12 +1855,18 @@ impl CliOptions {
     &self,
     test_flags: TestFlags,
   ) -> Result<TestOptions, AnyError> {
-    let maybe_test_config = if let Some(config_file) = &self.maybe_config_file {
-      config_file.to_test_config()?
-    } else {
-      None
-    };
-    TestOptions::resolve(maybe_test_config, Some(test_flags), &self.initial_cwd)
+    let (maybe_test_config, test_config_extras) =
+      if let Some(config_file) = &self.maybe_config_file {
+        deno_json::to_test_config_with_extras(config_file)?
+      } else {
+        (None, Default::default())
+      };
+    TestOptions::resolve(
+      maybe_test_config,
+      test_config_extras,
+      Some(test_flags),
+      &self.initial_cwd,
+    )
   }
 
   pub fn resolve_bench_options(
@@ -1379,6 +1886,14 @@ impl CliOptions {
     )
   }
 
+  pub fn resolve_coverage_config(&self) -> Result<CoverageConfig, AnyError> {
+    if let Some(config_file) = &self.maybe_config_file {
+      deno_json::to_coverage_config(config_file)
+    } else {
+      Ok(Default::default())
+    }
+  }
+
   pub fn resolve_deno_graph_workspace_members(
     &self,
   ) -> Result<Vec<deno_graph::WorkspaceMember>, AnyError> {
@@ -1829,17 +2344,11 @@ fn resolve_files(
   if let Some(file_flags) = maybe_file_flags {
     if !file_flags.include.is_empty() {
       maybe_files_config.include =
-        Some(PathOrPatternSet::from_include_relative_path_or_patterns(
-          initial_cwd,
-          &file_flags.include,
-        )?);
+        Some(include_path_or_patterns(initial_cwd, &file_flags.include)?);
     }
     if !file_flags.ignore.is_empty() {
       maybe_files_config.exclude =
-        PathOrPatternSet::from_exclude_relative_path_or_patterns(
-          initial_cwd,
-          &file_flags.ignore,
-        )?;
+        exclude_path_or_patterns(initial_cwd, &file_flags.ignore)?;
     }
   }
   Ok(maybe_files_config)
@@ -1865,6 +2374,7 @@ pub fn npm_pkg_req_ref_to_binary_command(
 #[cfg(test)]
 mod test {
   use crate::util::fs::FileCollector;
+  use deno_config::glob::PathOrPattern;
 
   use super::*;
   use pretty_assertions::assert_eq;
@@ -1896,6 +2406,493 @@ mod test {
     assert_eq!(actual, Some(expected_specifier));
   }
 
+  #[test]
+  fn resolve_fmt_options_with_overrides() {
+    let config_text = r#"{
+      "fmt": {
+        "indentWidth": 2,
+        "singleQuote": true,
+        "overrides": [{
+          "include": ["legacy/**"],
+          "indentWidth": 4,
+          "lineWidth": 120
+        }, {
+          "include": ["legacy/new/"],
+          "indentWidth": 8
+        }]
+      }
+    }"#;
+    let cwd = std::env::current_dir().unwrap();
+    let config_file = ConfigFile::new(
+      config_text,
+      ModuleSpecifier::from_file_path(cwd.join("deno.json")).unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let (maybe_fmt_config, fmt_config_extras) =
+      deno_json::to_fmt_config_with_extras(&config_file).unwrap();
+    let fmt_options = FmtOptions::resolve(
+      maybe_fmt_config,
+      fmt_config_extras,
+      Some(FmtFlags {
+        check: false,
+        files: FileFlags::default(),
+        use_tabs: None,
+        line_width: Some(std::num::NonZeroU32::new(100).unwrap()),
+        indent_width: None,
+        single_quote: None,
+        prose_wrap: None,
+        no_semicolons: None,
+        changed: None,
+        range: None,
+        diff_style: FmtDiffStyle::Pretty,
+        diff_output: None,
+        unstable_css: false,
+        unstable_yaml: false,
+        watch: None,
+      }),
+      &cwd,
+    )
+    .unwrap();
+
+    let options = fmt_options.options_for_path(&cwd.join("src/mod.ts"));
+    assert_eq!(options.indent_width, Some(2));
+    assert_eq!(options.line_width, Some(100));
+    assert_eq!(options.single_quote, Some(true));
+    let options = fmt_options.options_for_path(&cwd.join("legacy/mod.ts"));
+    assert_eq!(options.indent_width, Some(4));
+    assert_eq!(options.line_width, Some(100));
+    assert_eq!(options.single_quote, Some(true));
+    let options = fmt_options.options_for_path(&cwd.join("legacy/new/a.ts"));
+    assert_eq!(options.indent_width, Some(8));
+  }
+
+  #[test]
+  fn resolve_lint_options_with_overrides() {
+    let config_text = r#"{
+      "lint": {
+        "rules": {
+          "tags": ["recommended"],
+          "exclude": ["no-unused-vars"]
+        },
+        "overrides": [{
+          "include": ["generated/"],
+          "exclude": ["generated/keep.ts"],
+          "rules": { "exclude": ["no-explicit-any"] }
+        }]
+      }
+    }"#;
+    let cwd = std::env::current_dir().unwrap();
+    let config_file = ConfigFile::new(
+      config_text,
+      ModuleSpecifier::from_file_path(cwd.join("deno.json")).unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let (maybe_lint_config, lint_config_extras) =
+      deno_json::to_lint_config_with_extras(&config_file).unwrap();
+    let lint_options =
+      LintOptions::resolve(maybe_lint_config, lint_config_extras, None, &cwd)
+        .unwrap();
+
+    assert_eq!(
+      lint_options.override_index_for_path(&cwd.join("src/mod.ts")),
+      None
+    );
+    assert_eq!(
+      lint_options.override_index_for_path(&cwd.join("generated/keep.ts")),
+      None
+    );
+    assert_eq!(
+      lint_options.override_index_for_path(&cwd.join("generated/mod.ts")),
+      Some(0)
+    );
+    assert_eq!(
+      lint_options.overrides[0].rules,
+      LintRulesConfig {
+        tags: Some(vec!["recommended".to_string()]),
+        include: None,
+        exclude: Some(vec!["no-explicit-any".to_string()]),
+      }
+    );
+  }
+
+  #[test]
+  fn resolve_lint_options_with_use_gitignore() {
+    let config_file = ConfigFile::new(
+      r#"{ "lint": { "useGitignore": true } }"#,
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let (maybe_lint_config, lint_config_extras) =
+      deno_json::to_lint_config_with_extras(&config_file).unwrap();
+    assert!(lint_config_extras.use_gitignore);
+    let cwd = std::env::current_dir().unwrap();
+    let lint_options =
+      LintOptions::resolve(maybe_lint_config, lint_config_extras, None, &cwd)
+        .unwrap();
+    assert!(lint_options.gitignore);
+
+    let config_file = ConfigFile::new(
+      r#"{ "lint": { "useGitignore": "yes" } }"#,
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let err = deno_json::to_lint_config_with_extras(&config_file).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Expected \"lint.useGitignore\" to be a boolean."
+    );
+  }
+
+  #[test]
+  fn resolve_lint_options_with_follow_symlinks_and_max_depth() {
+    let config_file = ConfigFile::new(
+      r#"{ "lint": { "followSymlinks": true, "maxDepth": 3 } }"#,
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let (maybe_lint_config, lint_config_extras) =
+      deno_json::to_lint_config_with_extras(&config_file).unwrap();
+    let cwd = std::env::current_dir().unwrap();
+    let lint_options =
+      LintOptions::resolve(maybe_lint_config, lint_config_extras, None, &cwd)
+        .unwrap();
+    assert!(lint_options.follow_symlinks);
+    assert_eq!(lint_options.max_depth, Some(3));
+
+    let config_file = ConfigFile::new(
+      r#"{ "lint": { "maxDepth": -1 } }"#,
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let err = deno_json::to_lint_config_with_extras(&config_file).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Expected \"lint.maxDepth\" to be a non-negative integer."
+    );
+  }
+
+  #[test]
+  fn resolve_test_options_with_setup_and_teardown() {
+    let config_file = ConfigFile::new(
+      r#"{
+        "test": {
+          "include": ["src/"],
+          "setup": "./test/setup.ts",
+          "teardown": "https://deno.land/x/teardown.ts"
+        }
+      }"#,
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let (maybe_test_config, test_config_extras) =
+      deno_json::to_test_config_with_extras(&config_file).unwrap();
+    assert!(maybe_test_config.is_some());
+    assert_eq!(
+      test_config_extras.setup.unwrap().as_str(),
+      "file:///deno/test/setup.ts"
+    );
+    assert_eq!(
+      test_config_extras.teardown.unwrap().as_str(),
+      "https://deno.land/x/teardown.ts"
+    );
+
+    let config_file = ConfigFile::new(
+      r#"{ "test": { "setup": ["./setup.ts"] } }"#,
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let err = deno_json::to_test_config_with_extras(&config_file).unwrap_err();
+    assert_eq!(err.to_string(), "Expected \"test.setup\" to be a string.");
+  }
+
+  #[test]
+  fn resolve_test_options_with_non_file_specifiers() {
+    let config_file = ConfigFile::new(
+      r#"{
+        "test": {
+          "include": ["src/", "npm:chalk@5", "data:,export {}"],
+          "exclude": ["npm:chalk@4"]
+        }
+      }"#,
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let (maybe_test_config, _) =
+      deno_json::to_test_config_with_extras(&config_file).unwrap();
+    let files = maybe_test_config.unwrap().files;
+    let include = files.include.unwrap().into_path_or_patterns();
+    assert_eq!(include.len(), 3);
+    assert!(matches!(&include[0], PathOrPattern::Path(_)));
+    assert_eq!(
+      include[1],
+      PathOrPattern::RemoteUrl(ModuleSpecifier::parse("npm:chalk@5").unwrap())
+    );
+    assert_eq!(
+      include[2],
+      PathOrPattern::RemoteUrl(
+        ModuleSpecifier::parse("data:,export {}").unwrap()
+      )
+    );
+    assert!(files.exclude.inner().contains(&PathOrPattern::RemoteUrl(
+      ModuleSpecifier::parse("npm:chalk@4").unwrap()
+    )));
+  }
+
+  #[test]
+  fn resolve_lint_options_with_rule_severities() {
+    let config_text = r#"{
+      "lint": {
+        "rules": {
+          "tags": ["recommended"],
+          "exclude": ["no-unused-vars"],
+          "no-explicit-any": "warn",
+          "eqeqeq": "error",
+          "no-empty": "off"
+        }
+      }
+    }"#;
+    let config_file = ConfigFile::new(
+      config_text,
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let (maybe_lint_config, lint_config_extras) =
+      deno_json::to_lint_config_with_extras(&config_file).unwrap();
+    assert_eq!(
+      maybe_lint_config.unwrap().rules,
+      LintRulesConfig {
+        tags: Some(vec!["recommended".to_string()]),
+        include: Some(vec![
+          "no-explicit-any".to_string(),
+          "eqeqeq".to_string()
+        ]),
+        exclude: Some(vec![
+          "no-unused-vars".to_string(),
+          "no-empty".to_string()
+        ]),
+      }
+    );
+    assert_eq!(lint_config_extras.warn_rules, vec!["no-explicit-any"]);
+
+    let config_file = ConfigFile::new(
+      r#"{ "lint": { "rules": { "eqeqeq": "warning" } } }"#,
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let err = deno_json::to_lint_config_with_extras(&config_file).unwrap_err();
+    assert_eq!(
+      format!("{err:#}"),
+      "Failed to parse \"lint.rules\" configuration: Expected the severity of rule \"eqeqeq\" to be \"error\", \"warn\" or \"off\"."
+    );
+  }
+
+  #[test]
+  fn resolve_lint_options_with_override_rule_severities() {
+    let config_text = r#"{
+      "lint": {
+        "rules": {
+          "no-explicit-any": "warn",
+          "no-empty": "warn"
+        },
+        "overrides": [{
+          "include": ["generated/"],
+          "rules": {
+            "no-explicit-any": "error",
+            "eqeqeq": "warn"
+          }
+        }]
+      }
+    }"#;
+    let cwd = std::env::current_dir().unwrap();
+    let config_file = ConfigFile::new(
+      config_text,
+      ModuleSpecifier::from_file_path(cwd.join("deno.json")).unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let (maybe_lint_config, lint_config_extras) =
+      deno_json::to_lint_config_with_extras(&config_file).unwrap();
+    let lint_options =
+      LintOptions::resolve(maybe_lint_config, lint_config_extras, None, &cwd)
+        .unwrap();
+
+    assert_eq!(
+      lint_options.overrides[0].rules.include,
+      Some(vec!["no-explicit-any".to_string(), "eqeqeq".to_string()])
+    );
+    assert_eq!(
+      lint_options.overrides[0].warn_rules,
+      HashSet::from(["no-empty".to_string(), "eqeqeq".to_string()])
+    );
+  }
+
+  #[test]
+  fn resolve_lint_options_with_plugins() {
+    let config_text = r#"{
+      "lint": {
+        "plugins": ["./lint/rules.js", "https://example.com/rules.wasm"]
+      }
+    }"#;
+    let config_file = ConfigFile::new(
+      config_text,
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let (_, lint_config_extras) =
+      deno_json::to_lint_config_with_extras(&config_file).unwrap();
+    assert_eq!(
+      lint_config_extras.plugins,
+      vec![
+        ModuleSpecifier::parse("file:///deno/lint/rules.js").unwrap(),
+        ModuleSpecifier::parse("https://example.com/rules.wasm").unwrap(),
+      ]
+    );
+
+    let config_file = ConfigFile::new(
+      r#"{ "lint": { "plugins": [{}] } }"#,
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let err = deno_json::to_lint_config_with_extras(&config_file).unwrap_err();
+    assert_eq!(
+      format!("{err:#}"),
+      "Failed to parse \"lint.plugins\" configuration: Expected plugin 0 to be a string."
+    );
+  }
+
+  #[test]
+  fn resolve_fmt_options_with_plugins() {
+    let config_text = r#"{
+      "fmt": {
+        "lineWidth": 100,
+        "sortImports": true,
+        "plugins": [
+          "https://plugins.dprint.dev/toml-0.6.1.wasm",
+          { "specifier": "./plugins/sql.wasm", "options": { "uppercase": true } }
+        ]
+      }
+    }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(
+      config_text,
+      config_specifier,
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let (maybe_fmt_config, fmt_config_extras) =
+      deno_json::to_fmt_config_with_extras(&config_file).unwrap();
+    assert_eq!(maybe_fmt_config.unwrap().options.line_width, Some(100));
+    assert!(fmt_config_extras.sort_imports);
+    assert_eq!(
+      fmt_config_extras.plugins,
+      vec![
+        FmtPluginConfig {
+          specifier: ModuleSpecifier::parse(
+            "https://plugins.dprint.dev/toml-0.6.1.wasm"
+          )
+          .unwrap(),
+          options: Default::default(),
+        },
+        FmtPluginConfig {
+          specifier: ModuleSpecifier::parse("file:///deno/plugins/sql.wasm")
+            .unwrap(),
+          options: serde_json::json!({ "uppercase": true })
+            .as_object()
+            .unwrap()
+            .clone(),
+        },
+      ]
+    );
+
+    let config_file = ConfigFile::new(
+      r#"{ "fmt": { "plugins": [{ "options": {} }] } }"#,
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let err = deno_json::to_fmt_config_with_extras(&config_file).unwrap_err();
+    assert_eq!(
+      format!("{err:#}"),
+      "Failed to parse \"fmt.plugins\" configuration: Missing \"specifier\" in plugin 0."
+    );
+  }
+
+  #[test]
+  fn resolve_tasks_config_with_options() {
+    let config_text = r#"{
+      "tasks": {
+        "build": "deno run build.ts",
+        "serve": {
+          "command": "deno run -A main.ts",
+          "cwd": "./server",
+          "env": { "PORT": "8080" }
+        }
+      }
+    }"#;
+    let cwd = std::env::current_dir().unwrap();
+    let config_file = ConfigFile::new(
+      config_text,
+      ModuleSpecifier::from_file_path(cwd.join("deno.json")).unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let (tasks, tasks_options) =
+      deno_json::to_tasks_config_with_options(&config_file).unwrap();
+    let definitions = tasks
+      .iter()
+      .map(|(name, task)| match task {
+        deno_config::Task::Definition(definition)
+        | deno_config::Task::Commented { definition, .. } => {
+          (name.as_str(), definition.as_str())
+        }
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(
+      definitions,
+      vec![
+        ("build", "deno run build.ts"),
+        ("serve", "deno run -A main.ts")
+      ]
+    );
+    assert_eq!(
+      tasks_options,
+      HashMap::from([(
+        "serve".to_string(),
+        TaskOptions {
+          cwd: Some(cwd.join("./server")),
+          env: IndexMap::from([("PORT".to_string(), "8080".to_string())]),
+        }
+      )])
+    );
+
+    let config_file = ConfigFile::new(
+      r#"{ "tasks": { "serve": { "cwd": "./server" } } }"#,
+      ModuleSpecifier::from_file_path(cwd.join("deno.json")).unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let err =
+      deno_json::to_tasks_config_with_options(&config_file).unwrap_err();
+    assert_eq!(
+      format!("{err:#}"),
+      "Failed to parse task 'serve'.: Missing \"command\"."
+    );
+  }
+
   #[test]
   fn resolve_import_map_none() {
     let config_text = r#"{}"#;
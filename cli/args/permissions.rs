@@ -0,0 +1,47 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::ModuleSpecifier;
+use deno_runtime::fs_util::specifier_to_file_path;
+use deno_runtime::permissions::Permissions;
+use deno_runtime::permissions::PermissionsContainer;
+use deno_runtime::permissions::PermissionsOptions;
+
+use super::jsr_url;
+
+/// Gets the permissions for loading the plugins declared in the config
+/// file, which only allow reading the directories of the local plugins
+/// and connecting to the hosts of the remote ones.
+pub fn plugin_permissions(
+  specifiers: &[ModuleSpecifier],
+) -> Result<PermissionsContainer, AnyError> {
+  let mut allow_read = Vec::new();
+  let mut allow_net = Vec::new();
+  for specifier in specifiers {
+    let url = match specifier.scheme() {
+      "file" => {
+        let path = specifier_to_file_path(specifier)?;
+        allow_read.push(path.parent().unwrap_or(&path).to_path_buf());
+        continue;
+      }
+      "jsr" => jsr_url(),
+      "http" | "https" => specifier,
+      _ => bail!("Unsupported plugin specifier '{}'.", specifier),
+    };
+    if let Some(host) = url.host_str() {
+      allow_net.push(match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+      });
+    }
+  }
+  Ok(PermissionsContainer::new(Permissions::from_options(
+    &PermissionsOptions {
+      allow_read: Some(allow_read),
+      allow_net: Some(allow_net),
+      prompt: false,
+      ..Default::default()
+    },
+  )?))
+}
@@ -161,6 +161,11 @@ impl DenoDir {
     }
   }
 
+  /// Folder used for the baselines saved by `deno bench --save-baseline`.
+  pub fn bench_baselines_folder_path(&self) -> PathBuf {
+    self.root.join("bench_baselines")
+  }
+
   /// Folder path used for downloading new versions of deno.
   pub fn dl_folder_path(&self) -> PathBuf {
     self.root.join("dl")
@@ -8,6 +8,7 @@ use crate::file_fetcher::FileFetcher;
 use crate::file_fetcher::FileOrRedirect;
 use crate::npm::CliNpmResolver;
 use crate::util::fs::atomic_write_file;
+use crate::util::fs::durable_atomic_write_file;
 
 use deno_ast::MediaType;
 use deno_core::futures;
@@ -73,7 +74,13 @@ impl deno_cache_dir::DenoCacheEnv for RealDenoCacheEnv {
     path: &Path,
     bytes: &[u8],
   ) -> std::io::Result<()> {
-    atomic_write_file(path, bytes, CACHE_PERM)
+    // the cached content is unusable without its metadata, so make sure a
+    // crash doesn't leave it empty
+    if path.to_string_lossy().ends_with(".metadata.json") {
+      durable_atomic_write_file(path, bytes, CACHE_PERM)
+    } else {
+      atomic_write_file(path, bytes, CACHE_PERM)
+    }
   }
 
   fn modified(&self, path: &Path) -> std::io::Result<Option<SystemTime>> {
@@ -514,6 +514,34 @@ pub struct TakePreciseCoverageResponse {
   pub timestamp: f64,
 }
 
+/// <https://chromedevtools.github.io/devtools-protocol/tot/Profiler/#type-ProfileNode>
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileNode {
+  pub id: i64,
+  pub call_frame: CallFrame,
+  pub hit_count: Option<i64>,
+  pub children: Option<Vec<i64>>,
+}
+
+/// <https://chromedevtools.github.io/devtools-protocol/tot/Profiler/#type-Profile>
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+  pub nodes: Vec<ProfileNode>,
+  pub start_time: f64,
+  pub end_time: f64,
+  pub samples: Option<Vec<i64>>,
+  pub time_deltas: Option<Vec<i64>>,
+}
+
+/// <https://chromedevtools.github.io/devtools-protocol/tot/Profiler/#method-stop>
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopProfileResponse {
+  pub profile: Profile,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Notification {
   pub method: String,
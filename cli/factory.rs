@@ -96,6 +96,15 @@ impl CliFactoryBuilder {
     self.build_from_flags(flags)
   }
 
+  pub fn build_from_cli_options_for_watcher(
+    mut self,
+    options: Arc<CliOptions>,
+    watcher_communicator: Arc<WatcherCommunicator>,
+  ) -> CliFactory {
+    self.watcher_communicator = Some(watcher_communicator);
+    self.build_from_cli_options(options)
+  }
+
   pub fn build_from_cli_options(self, options: Arc<CliOptions>) -> CliFactory {
     CliFactory {
       watcher_communicator: self.watcher_communicator,
@@ -624,6 +633,7 @@ impl CliFactory {
           self.module_graph_builder().await?.clone(),
           self.node_resolver().await?.clone(),
           self.npm_resolver().await?.clone(),
+          self.text_only_progress_bar().clone(),
         )))
       })
       .await
@@ -853,9 +863,14 @@ impl CliFactory {
     let create_coverage_collector =
       if let Some(coverage_dir) = self.options.coverage_dir() {
         let coverage_dir = PathBuf::from(coverage_dir);
+        let coverage_files = self.options.resolve_coverage_config()?.files;
         let fn_: crate::worker::CreateCoverageCollectorCb =
           Box::new(move |session| {
-            Box::new(CoverageCollector::new(coverage_dir.clone(), session))
+            Box::new(CoverageCollector::new(
+              coverage_dir.clone(),
+              coverage_files.clone(),
+              session,
+            ))
           });
         Some(fn_)
       } else {
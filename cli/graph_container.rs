@@ -59,6 +59,13 @@ impl MainModuleGraphContainer {
     }
   }
 
+  /// Replaces the graph with an empty one, so the next load starts over
+  /// while still using the caches of the factory.
+  pub fn clear(&self) {
+    *self.inner.write() =
+      Arc::new(ModuleGraph::new(self.cli_options.graph_kind()));
+  }
+
   pub async fn check_specifiers(
     &self,
     specifiers: &[ModuleSpecifier],
@@ -16,10 +16,12 @@ use crate::resolver::CliGraphResolver;
 use crate::resolver::SloppyImportsResolver;
 use crate::tools::check;
 use crate::tools::check::TypeChecker;
+use crate::util::checksum;
 use crate::util::file_watcher::WatcherCommunicator;
 use crate::util::fs::canonicalize_path;
 use deno_runtime::fs_util::specifier_to_file_path;
 
+use deno_ast::MediaType;
 use deno_config::WorkspaceMemberConfig;
 use deno_core::anyhow::bail;
 use deno_core::error::custom_error;
@@ -41,16 +43,23 @@ use deno_runtime::deno_node;
 use deno_runtime::permissions::PermissionsContainer;
 use deno_semver::package::PackageNv;
 use deno_semver::package::PackageReq;
+use import_map::ImportMap;
 use import_map::ImportMapError;
 use std::collections::HashSet;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 #[derive(Clone, Copy)]
-pub struct GraphValidOptions {
+pub struct GraphValidOptions<'a> {
   pub check_js: bool,
   pub follow_type_only: bool,
   pub is_vendoring: bool,
+  /// Import map used to suggest fixes for unresolved bare specifiers.
+  pub maybe_import_map: Option<&'a ImportMap>,
+  /// Folder of the cached npm registry information, whose package names
+  /// are suggested for unresolved bare specifiers.
+  pub maybe_npm_registry_dir: Option<&'a Path>,
 }
 
 /// Check if `roots` and their deps are available. Returns `Ok(())` if
@@ -66,6 +75,11 @@ pub fn graph_valid(
   roots: &[ModuleSpecifier],
   options: GraphValidOptions,
 ) -> Result<(), AnyError> {
+  let bare_specifier_candidates = BareSpecifierCandidates::new(
+    graph,
+    options.maybe_import_map,
+    options.maybe_npm_registry_dir,
+  );
   let mut errors = graph
     .walk(
       roots,
@@ -87,12 +101,18 @@ pub fn graph_valid(
       };
       let mut message = match &error {
         ModuleGraphError::ResolutionError(resolution_error) => {
-          enhanced_resolution_error_message(resolution_error)
+          enhanced_resolution_error_message_with_candidates(
+            resolution_error,
+            &bare_specifier_candidates,
+          )
         }
         ModuleGraphError::TypesResolutionError(resolution_error) => {
           format!(
             "Failed resolving types. {}",
-            enhanced_resolution_error_message(resolution_error)
+            enhanced_resolution_error_message_with_candidates(
+              resolution_error,
+              &bare_specifier_candidates,
+            )
           )
         }
         ModuleGraphError::ModuleError(e) => {
@@ -189,6 +209,102 @@ pub fn graph_lock_or_exit(graph: &ModuleGraph, lockfile: &mut Lockfile) {
   }
 }
 
+/// Checks that each hop of the redirects matches the one pinned in the
+/// lockfile, then adds any redirects not yet in the lockfile.
+fn lock_redirects<'a>(
+  redirects: impl Iterator<Item = (&'a ModuleSpecifier, &'a ModuleSpecifier)>,
+  lockfile: &mut Lockfile,
+) -> Result<(), AnyError> {
+  for (from, to) in redirects {
+    if !lockfile.overwrite {
+      if let Some(locked_to) = lockfile.content.redirects.get(from.as_str()) {
+        check_locked_redirect(from, to.as_str(), locked_to, lockfile)?;
+      }
+    }
+    lockfile.insert_redirect(from.to_string(), to.to_string());
+  }
+  Ok(())
+}
+
+/// Pins the redirects of the graph to the ones in the lockfile, which fails
+/// when the graph already has a different destination for one of them.
+fn pin_locked_redirects(
+  graph: &mut ModuleGraph,
+  lockfile: &Lockfile,
+) -> Result<(), AnyError> {
+  for (from, locked_to) in &lockfile.content.redirects {
+    let Ok(from) = ModuleSpecifier::parse(from) else {
+      continue;
+    };
+    let Ok(to) = ModuleSpecifier::parse(locked_to) else {
+      continue;
+    };
+    if matches!(from.scheme(), "file" | "npm" | "jsr") {
+      continue;
+    }
+    match graph.redirects.get(&from) {
+      Some(graph_to) => {
+        check_locked_redirect(&from, graph_to.as_str(), locked_to, lockfile)?
+      }
+      None => {
+        graph.redirects.insert(from, to);
+      }
+    }
+  }
+  Ok(())
+}
+
+fn check_locked_redirect(
+  from: &ModuleSpecifier,
+  to: &str,
+  locked_to: &str,
+  lockfile: &Lockfile,
+) -> Result<(), AnyError> {
+  if locked_to != to {
+    bail!(
+      concat!(
+        "The redirect destination does not match the one in the lock file.\n",
+        "  Specifier: {}\n",
+        "  Lock file: {}\n",
+        "  - {}\n",
+        "  + {}\n",
+        "Run with --lock-write to update the lock file.",
+      ),
+      from,
+      lockfile.filename.display(),
+      locked_to,
+      to,
+    );
+  }
+  Ok(())
+}
+
+/// Checks a remote plugin that isn't part of a module graph, like a Wasm
+/// plugin, against the lockfile.
+///
+/// The lockfile only stores checksums of text, so the plugin is locked
+/// with the checksum of its bytes.
+pub fn check_plugin_lock(
+  specifier: &ModuleSpecifier,
+  source: &[u8],
+  lockfile: &mut Lockfile,
+) -> Result<(), AnyError> {
+  if !lockfile
+    .check_or_insert_remote(specifier.as_str(), &checksum::gen(&[source]))
+  {
+    bail!(
+      concat!(
+        "The source code is invalid, as it does not match the expected hash in the lock file.\n",
+        "  Specifier: {}\n",
+        "  Lock file: {}",
+      ),
+      specifier,
+      lockfile.filename.display(),
+    );
+  }
+  Ok(())
+}
+
 pub struct CreateGraphOptions<'a> {
   pub graph_kind: GraphKind,
   pub roots: Vec<ModuleSpecifier>,
@@ -286,10 +402,20 @@ impl ModuleGraphCreator {
     options: CreateGraphOptions<'_>,
   ) -> Result<ModuleGraph, AnyError> {
     let mut graph = ModuleGraph::new(options.graph_kind);
+    self.add_graph_roots(&mut graph, options).await?;
+    Ok(graph)
+  }
 
+  /// Adds the roots and their dependencies to an existing graph, which only
+  /// loads the modules that aren't in the graph yet.
+  pub async fn add_graph_roots(
+    &self,
+    graph: &mut ModuleGraph,
+    options: CreateGraphOptions<'_>,
+  ) -> Result<(), AnyError> {
     self
       .module_graph_builder
-      .build_graph_with_npm_resolution(&mut graph, options)
+      .build_graph_with_npm_resolution(graph, options)
       .await?;
 
     if let Some(npm_resolver) = self.npm_resolver.as_managed() {
@@ -298,7 +424,7 @@ impl ModuleGraphCreator {
       }
     }
 
-    Ok(graph)
+    Ok(())
   }
 
   pub async fn create_graph_and_maybe_check(
@@ -315,7 +441,14 @@ impl ModuleGraphCreator {
         loader: None,
       })
       .await?;
+    self.check_graph(graph).await
+  }
 
+  /// Validates the graph and type checks it when type checking is enabled.
+  pub async fn check_graph(
+    &self,
+    graph: ModuleGraph,
+  ) -> Result<Arc<deno_graph::ModuleGraph>, AnyError> {
     self.graph_valid(&graph)?;
     if let Some(lockfile) = &self.lockfile {
       graph_lock_or_exit(&graph, &mut lockfile.lock());
@@ -479,19 +612,12 @@ impl ModuleGraphBuilder {
       }
     }
 
-    // add the lockfile redirects to the graph if it's the first time executing
-    if graph.redirects.is_empty() {
-      if let Some(lockfile) = &self.lockfile {
-        let lockfile = lockfile.lock();
-        for (from, to) in &lockfile.content.redirects {
-          if let Ok(from) = ModuleSpecifier::parse(from) {
-            if let Ok(to) = ModuleSpecifier::parse(to) {
-              if !matches!(from.scheme(), "file" | "npm" | "jsr") {
-                graph.redirects.insert(from, to);
-              }
-            }
-          }
-        }
+    // pin the redirects to the ones in the lockfile, unless the
+    // lockfile is being rewritten
+    if let Some(lockfile) = &self.lockfile {
+      let lockfile = lockfile.lock();
+      if !lockfile.overwrite {
+        pin_locked_redirects(graph, &lockfile)?;
       }
     }
 
@@ -530,16 +656,14 @@ impl ModuleGraphBuilder {
 
     graph.build(roots, loader, options).await;
 
-    // add the redirects in the graph to the lockfile
+    // verify the redirects in the graph against the lockfile and add
+    // any new ones
     if !graph.redirects.is_empty() {
       if let Some(lockfile) = &self.lockfile {
         let graph_redirects = graph.redirects.iter().filter(|(from, _)| {
           !matches!(from.scheme(), "npm" | "file" | "deno")
         });
-        let mut lockfile = lockfile.lock();
-        for (from, to) in graph_redirects {
-          lockfile.insert_redirect(from.to_string(), to.to_string());
-        }
+        lock_redirects(graph_redirects, &mut lockfile.lock())?;
       }
     }
 
@@ -656,6 +780,10 @@ impl ModuleGraphBuilder {
     graph: &ModuleGraph,
     roots: &[ModuleSpecifier],
   ) -> Result<(), AnyError> {
+    let maybe_npm_registry_dir = self
+      .npm_resolver
+      .as_managed()
+      .map(|npm| npm.registry_folder_in_global_cache(npm.registry_base_url()));
     graph_valid(
       graph,
       self.fs.clone(),
@@ -664,6 +792,8 @@ impl ModuleGraphBuilder {
         is_vendoring: false,
         follow_type_only: self.options.type_check_mode().is_true(),
         check_js: self.options.check_js(),
+        maybe_import_map: self.resolver.maybe_import_map().map(|m| m.as_ref()),
+        maybe_npm_registry_dir: maybe_npm_registry_dir.as_deref(),
       },
     )
   }
@@ -701,6 +831,26 @@ pub fn enhanced_resolution_error_message(error: &ResolutionError) -> String {
   message
 }
 
+/// Adds more explanatory information to a resolution error, including
+/// suggestions from the provided candidates for an unresolved bare specifier.
+pub fn enhanced_resolution_error_message_with_candidates(
+  error: &ResolutionError,
+  candidates: &BareSpecifierCandidates,
+) -> String {
+  let mut message = enhanced_resolution_error_message(error);
+
+  if get_resolution_error_bare_node_specifier(error).is_none() {
+    if let Some(specifier) = get_resolution_error_bare_specifier(error) {
+      for suggestion in candidates.suggestions(specifier) {
+        message.push('\n');
+        message.push_str(&suggestion);
+      }
+    }
+  }
+
+  message
+}
+
 pub fn enhanced_module_error_message(
   fs: Arc<dyn FileSystem>,
   error: &ModuleError,
@@ -759,6 +909,219 @@ fn get_resolution_error_bare_specifier(
   }
 }
 
+/// Names known to the current project that an unresolved bare specifier
+/// is compared against in order to suggest a fix.
+#[derive(Debug, Default)]
+pub struct BareSpecifierCandidates {
+  /// Raw keys and values of the top level "imports" of the import map.
+  import_map_entries: Vec<(String, Option<String>)>,
+  /// Packages already used in the graph (ex. `("npm", "lodash-es", "4.17.21")`).
+  packages: Vec<(&'static str, String, String)>,
+  /// Folder of the cached npm registry information.
+  npm_registry_dir: Option<PathBuf>,
+  /// Names of the packages in `npm_registry_dir`, which are only read once
+  /// a specifier couldn't be resolved.
+  npm_registry_names: once_cell::unsync::OnceCell<Vec<String>>,
+}
+
+impl BareSpecifierCandidates {
+  pub fn new(
+    graph: &ModuleGraph,
+    maybe_import_map: Option<&ImportMap>,
+    maybe_npm_registry_dir: Option<&Path>,
+  ) -> Self {
+    let import_map_entries = maybe_import_map
+      .map(|import_map| {
+        import_map
+          .imports()
+          .entries()
+          .map(|entry| {
+            (
+              entry.raw_key.to_string(),
+              entry.raw_value.map(|v| v.to_string()),
+            )
+          })
+          .collect()
+      })
+      .unwrap_or_default();
+    let mut packages = graph
+      .packages
+      .mappings()
+      .map(|(_, nv)| ("jsr", nv.name.clone(), nv.version.to_string()))
+      .chain(
+        graph
+          .npm_packages
+          .iter()
+          .map(|nv| ("npm", nv.name.clone(), nv.version.to_string())),
+      )
+      .collect::<Vec<_>>();
+    packages.sort();
+    packages.dedup();
+    Self {
+      import_map_entries,
+      packages,
+      npm_registry_dir: maybe_npm_registry_dir.map(ToOwned::to_owned),
+      npm_registry_names: Default::default(),
+    }
+  }
+
+  fn npm_registry_names(&self) -> &[String] {
+    self.npm_registry_names.get_or_init(|| {
+      self
+        .npm_registry_dir
+        .as_deref()
+        .map(read_cached_npm_package_names)
+        .unwrap_or_default()
+    })
+  }
+
+  /// Gets the suggestions to show for a bare specifier that could not be
+  /// resolved, from most to least specific.
+  pub fn suggestions(&self, specifier: &str) -> Vec<String> {
+    let (name, sub_path) = split_bare_specifier(specifier);
+    let mut suggestions = Vec::new();
+
+    // the package is mapped, but not its sub paths
+    if !sub_path.is_empty() {
+      let mapped_value = self
+        .import_map_entries
+        .iter()
+        .find(|(key, _)| key == name)
+        .and_then(|(_, value)| value.as_ref());
+      let has_sub_path_entry = self
+        .import_map_entries
+        .iter()
+        .any(|(key, _)| key.strip_suffix('/') == Some(name));
+      if let (Some(value), false) = (mapped_value, has_sub_path_entry) {
+        suggestions.push(format!(
+          "Add \"{name}/\": \"{}/\" to the \"imports\" to import sub paths of \"{name}\".",
+          value.trim_end_matches('/'),
+        ));
+        return suggestions;
+      }
+    }
+
+    if let Some(key) = closest_match(
+      name,
+      self
+        .import_map_entries
+        .iter()
+        .map(|(key, _)| key.trim_end_matches('/')),
+    ) {
+      suggestions.push(format!(
+        "Did you mean \"{key}{sub_path}\"? It is mapped in the import map."
+      ));
+    }
+
+    if let Some((scheme, package_name, version)) = closest_match(
+      name,
+      self.packages.iter().map(|(_, name, _)| name.as_str()),
+    )
+    .and_then(|package_name| {
+      self
+        .packages
+        .iter()
+        .find(|(_, name, _)| name == package_name)
+    }) {
+      suggestions.push(format!(
+        "Did you mean \"{scheme}:{package_name}{sub_path}\"? Alternatively, add \"{name}\": \"{scheme}:{package_name}@^{version}\" to the \"imports\" of your config file."
+      ));
+    } else if let Some(package_name) = is_possible_npm_package_name(name)
+      .then(|| {
+        closest_match(
+          name,
+          self.npm_registry_names().iter().map(String::as_str),
+        )
+      })
+      .flatten()
+    {
+      suggestions.push(format!(
+        "Did you mean \"npm:{package_name}{sub_path}\"? It is a package from the npm registry. Alternatively, add \"{name}\": \"npm:{package_name}\" to the \"imports\" of your config file (ex. run \"deno add npm:{package_name}\")."
+      ));
+    } else if suggestions.is_empty() && is_possible_npm_package_name(name) {
+      suggestions.push(format!(
+        "If you want to use a package from npm, did you mean \"npm:{specifier}\"? Alternatively, add \"{name}\": \"npm:{name}\" to the \"imports\" of your config file (ex. run \"deno add npm:{name}\")."
+      ));
+    }
+
+    suggestions
+  }
+}
+
+/// Splits a bare specifier into its package name and sub path
+/// (ex. `@scope/pkg/sub` -> `("@scope/pkg", "/sub")`).
+fn split_bare_specifier(specifier: &str) -> (&str, &str) {
+  let name_segments = if specifier.starts_with('@') { 2 } else { 1 };
+  let name_len = specifier
+    .match_indices('/')
+    .nth(name_segments - 1)
+    .map(|(index, _)| index)
+    .unwrap_or(specifier.len());
+  specifier.split_at(name_len)
+}
+
+fn is_possible_npm_package_name(name: &str) -> bool {
+  !name.is_empty()
+    && !name.starts_with('.')
+    // probably a local file missing its "./" prefix
+    && MediaType::from_path(Path::new(name)) == MediaType::Unknown
+    && name.chars().all(|c| {
+      c.is_ascii_lowercase()
+        || c.is_ascii_digit()
+        || matches!(c, '-' | '_' | '.' | '@' | '/' | '~')
+    })
+}
+
+/// Gets the names of the npm packages whose registry information is in
+/// the cache. Mixed case names are stored encoded, so they're skipped.
+fn read_cached_npm_package_names(registry_dir: &Path) -> Vec<String> {
+  fn has_registry_info(dir: &Path) -> bool {
+    dir.join("registry.json").is_file()
+  }
+
+  let mut names = Vec::new();
+  let Ok(entries) = std::fs::read_dir(registry_dir) else {
+    return names;
+  };
+  for entry in entries.flatten() {
+    let name = entry.file_name().to_string_lossy().to_string();
+    if name.starts_with('@') {
+      let Ok(scoped_entries) = std::fs::read_dir(entry.path()) else {
+        continue;
+      };
+      for scoped_entry in scoped_entries.flatten() {
+        if has_registry_info(&scoped_entry.path()) {
+          names.push(format!(
+            "{}/{}",
+            name,
+            scoped_entry.file_name().to_string_lossy()
+          ));
+        }
+      }
+    } else if !name.starts_with(['_', '.']) && has_registry_info(&entry.path())
+    {
+      names.push(name);
+    }
+  }
+  names.sort();
+  names
+}
+
+/// Finds the candidate that is an exact match or the closest one within
+/// a small number of edits.
+fn closest_match<'a>(
+  name: &str,
+  candidates: impl Iterator<Item = &'a str>,
+) -> Option<&'a str> {
+  let max_distance = std::cmp::max(1, name.chars().count() / 3);
+  candidates
+    .filter(|candidate| !candidate.is_empty())
+    .map(|candidate| (strsim::levenshtein(name, candidate), candidate))
+    .filter(|(distance, _)| *distance <= max_distance)
+    .min_by_key(|(distance, _)| *distance)
+    .map(|(_, candidate)| candidate)
+}
+
 /// Gets if any of the specified root's "file:" dependents are in the
 /// provided changed set.
 pub fn has_graph_root_local_dependent_changed(
@@ -949,6 +1312,121 @@ mod test {
     }
   }
 
+  #[test]
+  fn lock_redirects_verifies_pinned_destination() {
+    let temp_dir = test_util::TempDir::new();
+    let lockfile_path = temp_dir.path().join("deno.lock").to_path_buf();
+    let from = ModuleSpecifier::parse("https://deno.land/x/mod.ts").unwrap();
+    let to =
+      ModuleSpecifier::parse("https://deno.land/x/mod@1.0.0/mod.ts").unwrap();
+    let changed_to =
+      ModuleSpecifier::parse("https://deno.land/x/mod@2.0.0/mod.ts").unwrap();
+
+    let mut lockfile = Lockfile::new(lockfile_path, false).unwrap();
+    lock_redirects([(&from, &to)].into_iter(), &mut lockfile).unwrap();
+    assert_eq!(
+      lockfile.content.redirects.get(from.as_str()),
+      Some(&to.to_string())
+    );
+    // same destination is fine
+    lock_redirects([(&from, &to)].into_iter(), &mut lockfile).unwrap();
+
+    let err = lock_redirects([(&from, &changed_to)].into_iter(), &mut lockfile)
+      .unwrap_err()
+      .to_string();
+    assert!(err.contains("does not match the one in the lock file"));
+    assert!(err.contains("  - https://deno.land/x/mod@1.0.0/mod.ts\n"));
+    assert!(err.contains("  + https://deno.land/x/mod@2.0.0/mod.ts\n"));
+
+    // a graph that already has a different destination can't be pinned
+    let mut graph = ModuleGraph::new(GraphKind::All);
+    graph.redirects.insert(from.clone(), changed_to.clone());
+    let err = pin_locked_redirects(&mut graph, &lockfile)
+      .unwrap_err()
+      .to_string();
+    assert!(err.contains("does not match the one in the lock file"));
+    let mut graph = ModuleGraph::new(GraphKind::All);
+    pin_locked_redirects(&mut graph, &lockfile).unwrap();
+    assert_eq!(graph.redirects.get(&from), Some(&to));
+
+    // overwriting the lockfile accepts the new destination
+    lockfile.overwrite = true;
+    lock_redirects([(&from, &changed_to)].into_iter(), &mut lockfile).unwrap();
+    assert_eq!(
+      lockfile.content.redirects.get(from.as_str()),
+      Some(&changed_to.to_string())
+    );
+  }
+
+  #[test]
+  fn test_split_bare_specifier() {
+    assert_eq!(split_bare_specifier("lodash"), ("lodash", ""));
+    assert_eq!(split_bare_specifier("lodash/fp"), ("lodash", "/fp"));
+    assert_eq!(split_bare_specifier("@std/path"), ("@std/path", ""));
+    assert_eq!(
+      split_bare_specifier("@std/path/posix"),
+      ("@std/path", "/posix")
+    );
+  }
+
+  #[test]
+  fn bare_specifier_suggestions() {
+    let temp_dir = test_util::TempDir::new();
+    for name in ["chalk", "express", "@types/node", "_mixed_case"] {
+      let package_dir = temp_dir.path().join(name);
+      package_dir.create_dir_all();
+      package_dir.join("registry.json").write("{}");
+    }
+    let candidates = BareSpecifierCandidates {
+      import_map_entries: vec![
+        ("preact".to_string(), Some("npm:preact@10".to_string())),
+        ("utils".to_string(), Some("./utils.ts".to_string())),
+        ("$std/".to_string(), Some("jsr:/@std/".to_string())),
+      ],
+      packages: vec![
+        ("jsr", "@std/assert".to_string(), "0.221.0".to_string()),
+        ("npm", "lodash-es".to_string(), "4.17.21".to_string()),
+      ],
+      npm_registry_dir: Some(temp_dir.path().to_path_buf()),
+      ..Default::default()
+    };
+    let cases = [
+      (
+        "preact/hooks",
+        vec!["Add \"preact/\": \"npm:preact@10/\" to the \"imports\" to import sub paths of \"preact\"."],
+      ),
+      (
+        "util",
+        vec!["Did you mean \"utils\"? It is mapped in the import map."],
+      ),
+      (
+        "lodash-es/map",
+        vec!["Did you mean \"npm:lodash-es/map\"? Alternatively, add \"lodash-es\": \"npm:lodash-es@^4.17.21\" to the \"imports\" of your config file."],
+      ),
+      (
+        "@std/asert",
+        vec!["Did you mean \"jsr:@std/assert\"? Alternatively, add \"@std/asert\": \"jsr:@std/assert@^0.221.0\" to the \"imports\" of your config file."],
+      ),
+      (
+        "expres",
+        vec!["Did you mean \"npm:express\"? It is a package from the npm registry. Alternatively, add \"expres\": \"npm:express\" to the \"imports\" of your config file (ex. run \"deno add npm:express\")."],
+      ),
+      (
+        "@types/nod",
+        vec!["Did you mean \"npm:@types/node\"? It is a package from the npm registry. Alternatively, add \"@types/nod\": \"npm:@types/node\" to the \"imports\" of your config file (ex. run \"deno add npm:@types/node\")."],
+      ),
+      (
+        "left-pad",
+        vec!["If you want to use a package from npm, did you mean \"npm:left-pad\"? Alternatively, add \"left-pad\": \"npm:left-pad\" to the \"imports\" of your config file (ex. run \"deno add npm:left-pad\")."],
+      ),
+      ("Not A Package", vec![]),
+      ("bad-module.ts", vec![]),
+    ];
+    for (specifier, expected) in cases {
+      assert_eq!(candidates.suggestions(specifier), expected, "{specifier}");
+    }
+  }
+
   #[test]
   fn bare_specifier_node_resolution_error() {
     let cases = vec![("process", Some("process")), ("other", None)];
@@ -1,4 +1,5 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+use crate::util::fs::write_file_from_stream;
 use crate::util::progress_bar::UpdateGuard;
 use crate::version::get_user_agent;
 
@@ -18,6 +19,7 @@ use deno_runtime::deno_fetch::reqwest::Response;
 use deno_runtime::deno_fetch::CreateHttpClientOptions;
 use deno_runtime::deno_tls::RootCertStoreProvider;
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::SystemTime;
@@ -309,11 +311,50 @@ impl HttpClient {
     self.inner_download(url, Some(progress_guard)).await
   }
 
+  /// Downloads the response body straight to the file at the provided path
+  /// without holding it in memory. Returns `false` when it wasn't found.
+  pub async fn download_to_file_with_progress<U: reqwest::IntoUrl>(
+    &self,
+    url: U,
+    file_path: &Path,
+    progress_guard: &UpdateGuard,
+  ) -> Result<bool, AnyError> {
+    let Some(response) = self.get_success_response(url).await? else {
+      return Ok(false);
+    };
+    if let Some(total_size) = response.content_length() {
+      progress_guard.set_total_size(total_size);
+    }
+    let mut current_size = 0;
+    let stream = response.bytes_stream().inspect(|item| {
+      if let Ok(bytes) = item {
+        current_size += bytes.len() as u64;
+        progress_guard.set_position(current_size);
+      }
+    });
+    write_file_from_stream(file_path, stream, 0o644).await?;
+    Ok(true)
+  }
+
   async fn inner_download<U: reqwest::IntoUrl>(
     &self,
     url: U,
     progress_guard: Option<&UpdateGuard>,
   ) -> Result<Option<Vec<u8>>, AnyError> {
+    let Some(response) = self.get_success_response(url).await? else {
+      return Ok(None);
+    };
+    get_response_body_with_progress(response, progress_guard)
+      .await
+      .map(Some)
+  }
+
+  /// Gets the response, returning `None` when it wasn't found and erroring
+  /// for other unsuccessful statuses.
+  async fn get_success_response<U: reqwest::IntoUrl>(
+    &self,
+    url: U,
+  ) -> Result<Option<Response>, AnyError> {
     let response = self.get_redirected_response(url).await?;
 
     if response.status() == 404 {
@@ -331,9 +372,7 @@ impl HttpClient {
       );
     }
 
-    get_response_body_with_progress(response, progress_guard)
-      .await
-      .map(Some)
+    Ok(Some(response))
   }
 
   pub async fn get_redirected_response<U: reqwest::IntoUrl>(
@@ -11,6 +11,8 @@ import { Console } from "ext:deno_console/01_console.js";
 import { setExitHandler } from "ext:runtime/30_os.js";
 const {
   op_register_bench,
+  op_bench_collects_samples,
+  op_bench_get_budget,
   op_bench_get_origin,
   op_dispatch_bench_event,
   op_bench_now,
@@ -19,6 +21,8 @@ const {
   ArrayPrototypePush,
   Error,
   MathCeil,
+  MathSqrt,
+  NumberIsFinite,
   SymbolToStringTag,
   TypeError,
 } = primordials;
@@ -40,6 +44,14 @@ const registerBenchIdRetBufU8 = new Uint8Array(registerBenchIdRetBuf.buffer);
 
 // As long as we're using one isolate per test, we can cache the origin since it won't change
 let cachedOrigin = undefined;
+/** The default budget of the benches from the flags of `deno bench`.
+ * @type {{ warmupTime: number, minIterations: number, maxTime: number } | undefined} */
+let cachedBudget = undefined;
+/** Whether the raw samples are reported, which is only done for `--json`.
+ * @type {boolean | undefined} */
+let cachedCollectsSamples = undefined;
+
+const BUDGET_OPTIONS = ["warmupTime", "minIterations", "maxTime"];
 
 // Main bench function provided by Deno.
 function bench(
@@ -162,6 +174,23 @@ function bench(
     benchDesc = { ...defaults, ...nameOrFnOrOptions, fn, name };
   }
 
+  for (let i = 0; i < BUDGET_OPTIONS.length; i++) {
+    const option = BUDGET_OPTIONS[i];
+    const value = benchDesc[option];
+    // at least one iteration is measured so the statistics are defined
+    const min = option === "minIterations" ? 1 : 0;
+    if (
+      value !== undefined &&
+      (typeof value !== "number" || !NumberIsFinite(value) || value < min)
+    ) {
+      throw new TypeError(
+        min === 0
+          ? `Expected '${option}' of the bench to be a non-negative number`
+          : `Expected '${option}' of the bench to be a number of at least ${min}`,
+      );
+    }
+  }
+
   const AsyncFunction = (async () => {}).constructor;
   benchDesc.async = AsyncFunction === benchDesc.fn.constructor;
   benchDesc.fn = wrapBenchmark(benchDesc);
@@ -200,21 +229,40 @@ function benchStats(
   max,
   all,
 ) {
+  const mean = avg / n;
+  const p25 = all[MathCeil(n * (25 / 100)) - 1];
+  const p75 = all[MathCeil(n * (75 / 100)) - 1];
+  // Tukey's fences
+  const lowFence = p25 - 1.5 * (p75 - p25);
+  const highFence = p75 + 1.5 * (p75 - p25);
+  let variance = 0;
+  let lowOutliers = 0;
+  let highOutliers = 0;
+  for (let i = 0; i < n; i++) {
+    variance += (all[i] - mean) ** 2;
+    if (all[i] < lowFence) lowOutliers++;
+    else if (all[i] > highFence) highOutliers++;
+  }
   return {
     n,
     min,
     max,
-    p75: all[MathCeil(n * (75 / 100)) - 1],
+    p50: all[MathCeil(n * (50 / 100)) - 1],
+    p75,
     p99: all[MathCeil(n * (99 / 100)) - 1],
     p995: all[MathCeil(n * (99.5 / 100)) - 1],
     p999: all[MathCeil(n * (99.9 / 100)) - 1],
+    stddev: MathSqrt(variance / n),
+    lowOutliers,
+    highOutliers,
+    samples: collectsSamples() ? all : undefined,
     avg: !highPrecision ? (avg / n) : MathCeil(avg / n),
     highPrecision,
     usedExplicitTimers,
   };
 }
 
-async function benchMeasure(timeBudget, fn, async, context) {
+async function benchMeasure(benchBudget, fn, async, context) {
   let n = 0;
   let avg = 0;
   let wavg = 0;
@@ -227,7 +275,7 @@ async function benchMeasure(timeBudget, fn, async, context) {
   // warmup step
   let c = 0;
   let iterations = 20;
-  let budget = 10 * 1e6;
+  let budget = benchBudget.warmupTime * 1e6;
 
   if (!async) {
     while (budget > 0 || iterations-- > 0) {
@@ -273,8 +321,8 @@ async function benchMeasure(timeBudget, fn, async, context) {
 
   // measure step
   if (wavg > lowPrecisionThresholdInNs) {
-    let iterations = 10;
-    let budget = timeBudget * 1e6;
+    let iterations = benchBudget.minIterations;
+    let budget = benchBudget.maxTime * 1e6;
 
     if (!async) {
       while (budget > 0 || iterations-- > 0) {
@@ -326,8 +374,8 @@ async function benchMeasure(timeBudget, fn, async, context) {
   } else {
     context.start = function start() {};
     context.end = function end() {};
-    let iterations = 10;
-    let budget = timeBudget * 1e6;
+    let iterations = benchBudget.minIterations;
+    let budget = benchBudget.maxTime * 1e6;
 
     if (!async) {
       while (budget > 0 || iterations-- > 0) {
@@ -376,6 +424,13 @@ async function benchMeasure(timeBudget, fn, async, context) {
   );
 }
 
+function collectsSamples() {
+  if (cachedCollectsSamples === undefined) {
+    cachedCollectsSamples = op_bench_collects_samples();
+  }
+  return cachedCollectsSamples;
+}
+
 /** @param desc {BenchDescription} */
 function createBenchContext(desc) {
   return {
@@ -435,14 +490,16 @@ function wrapBenchmark(desc) {
         });
       }
 
-      const benchTimeInMs = 500;
+      if (cachedBudget === undefined) {
+        cachedBudget = op_bench_get_budget();
+      }
+      const budget = {
+        warmupTime: desc.warmupTime ?? cachedBudget.warmupTime,
+        minIterations: desc.minIterations ?? cachedBudget.minIterations,
+        maxTime: desc.maxTime ?? cachedBudget.maxTime,
+      };
       const context = createBenchContext(desc);
-      const stats = await benchMeasure(
-        benchTimeInMs,
-        fn,
-        desc.async,
-        context,
-      );
+      const stats = await benchMeasure(budget, fn, desc.async, context);
 
       return { ok: stats };
     } catch (error) {
@@ -7,6 +7,7 @@ import { escapeName, withPermissions } from "ext:cli/40_test_common.js";
 const {
   op_register_test_step,
   op_register_test,
+  op_test_assert_snapshot,
   op_test_event_step_result_failed,
   op_test_event_step_result_ignored,
   op_test_event_step_result_ok,
@@ -21,12 +22,17 @@ const {
   Map,
   MapPrototypeGet,
   MapPrototypeSet,
+  NumberIsInteger,
+  Promise,
   SafeArrayIterator,
+  SafePromiseRace,
   SymbolToStringTag,
   TypeError,
 } = primordials;
 
 import { setExitHandler } from "ext:runtime/30_os.js";
+import { inspect } from "ext:deno_console/01_console.js";
+import { clearTimeout, setTimeout } from "ext:deno_web/02_timers.js";
 
 /**
  * @typedef {{
@@ -41,6 +47,8 @@ import { setExitHandler } from "ext:runtime/30_os.js";
  *   sanitizeResources: boolean,
  *   sanitizeExit: boolean,
  *   permissions: PermissionOptions,
+ *   retries: number | null,
+ *   timeout: number | null,
  * }} TestDescription
  *
  * @typedef {{
@@ -57,6 +65,7 @@ import { setExitHandler } from "ext:runtime/30_os.js";
  *   sanitizeOps: boolean,
  *   sanitizeResources: boolean,
  *   sanitizeExit: boolean,
+ *   timeout: number | null,
  * }} TestStepDescription
  *
  * @typedef {{
@@ -110,6 +119,10 @@ function assertExit(fn, isTest) {
 
 function wrapOuter(fn, desc) {
   return async function outerWrapped() {
+    // the test runs again when it's retried, so start with a fresh state
+    const state = MapPrototypeGet(testStates, desc.id);
+    state.children = [];
+    state.completed = false;
     try {
       if (desc.ignore) {
         return "ignored";
@@ -211,6 +224,8 @@ function testInner(
     sanitizeResources: true,
     sanitizeExit: true,
     permissions: null,
+    retries: null,
+    timeout: null,
   };
 
   if (typeof nameOrFnOrOptions === "string") {
@@ -288,6 +303,14 @@ function testInner(
   // Delete this prop in case the user passed it. It's used to detect steps.
   delete testDesc.parent;
 
+  if (
+    testDesc.retries != null &&
+    !(NumberIsInteger(testDesc.retries) && testDesc.retries >= 0)
+  ) {
+    throw new TypeError("The 'retries' option must be a non-negative integer");
+  }
+  assertTimeout(testDesc.timeout);
+
   if (cachedOrigin == undefined) {
     cachedOrigin = op_test_get_origin();
   }
@@ -306,6 +329,8 @@ function testInner(
     testDesc.location.fileName,
     testDesc.location.lineNumber,
     testDesc.location.columnNumber,
+    testDesc.retries ?? -1,
+    testDesc.timeout ?? -1,
     registerTestIdRetBufU8,
   );
   testDesc.id = registerTestIdRetBuf[0];
@@ -345,6 +370,52 @@ function getFullName(desc) {
   return desc.name;
 }
 
+function assertTimeout(timeout) {
+  if (timeout != null && !(NumberIsInteger(timeout) && timeout > 0)) {
+    throw new TypeError("The 'timeout' option must be a positive integer");
+  }
+}
+
+/**
+ * Resolves to a failed result when the step doesn't complete in time. The
+ * timeout of a test is enforced by the test runner instead, which can also
+ * stop a test that blocks the thread.
+ * @param promise {Promise<any>}
+ * @param timeout {number}
+ */
+async function withStepTimeout(promise, timeout) {
+  let timerId;
+  const timedOut = new Promise((resolve) => {
+    timerId = setTimeout(
+      () => resolve({ failed: { timedOut: [timeout, []] } }),
+      timeout,
+    );
+  });
+  try {
+    return await SafePromiseRace([promise, timedOut]);
+  } finally {
+    clearTimeout(timerId);
+  }
+}
+
+/**
+ * Serializes a value for its snapshot, which must be stable across runs and
+ * readable in a diff.
+ * @param value {unknown}
+ */
+function serializeSnapshot(value) {
+  return inspect(value, {
+    depth: Infinity,
+    sorted: true,
+    trailingComma: true,
+    compact: false,
+    iterableLimit: Infinity,
+    strAbbreviateSize: Infinity,
+    breakLength: Infinity,
+    escapeSequences: false,
+  });
+}
+
 function usesSanitizer(desc) {
   return desc.sanitizeResources || desc.sanitizeOps || desc.sanitizeExit;
 }
@@ -380,6 +451,8 @@ function createTestContext(desc) {
     rootId = desc.id;
     rootName = desc.name;
   }
+  // the number of snapshots asserted for each name
+  const snapshotCounts = new Map();
   return {
     [SymbolToStringTag]: "TestContext",
     /**
@@ -394,6 +467,24 @@ function createTestContext(desc) {
      * File Uri of the test code.
      */
     origin: desc.origin,
+    /**
+     * @param actual {unknown}
+     * @param options {{ name?: string } | undefined}
+     */
+    assertSnapshot(actual, options = {}) {
+      const baseName = options.name ?? getFullName(desc);
+      const count = (MapPrototypeGet(snapshotCounts, baseName) ?? 0) + 1;
+      MapPrototypeSet(snapshotCounts, baseName, count);
+      const message = op_test_assert_snapshot(
+        `${baseName} ${count}`,
+        serializeSnapshot(actual),
+      );
+      if (message !== null) {
+        const error = new Error(message);
+        error.name = "AssertionError";
+        throw error;
+      }
+    },
     /**
      * @param nameOrFnOrOptions {string | TestStepDefinition | ((t: TestContext) => void | Promise<void>)}
      * @param maybeFn {((t: TestContext) => void | Promise<void>) | undefined}
@@ -436,6 +527,7 @@ function createTestContext(desc) {
         );
       }
       stepDesc.ignore ??= false;
+      assertTimeout(stepDesc.timeout);
       stepDesc.sanitizeOps ??= desc.sanitizeOps;
       stepDesc.sanitizeResources ??= desc.sanitizeResources;
       stepDesc.sanitizeExit ??= desc.sanitizeExit;
@@ -472,7 +564,9 @@ function createTestContext(desc) {
 
       op_test_event_step_wait(stepDesc.id);
       const earlier = DateNow();
-      const result = await stepDesc.fn(stepDesc);
+      const result = stepDesc.timeout == null
+        ? await stepDesc.fn(stepDesc)
+        : await withStepTimeout(stepDesc.fn(stepDesc), stepDesc.timeout);
       const elapsed = DateNow() - earlier;
       state.failed = !!result.failed;
       stepReportResult(stepDesc, result, elapsed);
@@ -9,6 +9,9 @@ use super::tsc;
 
 use crate::args::jsr_url;
 use crate::tools::lint::create_linter;
+use crate::tools::lint::jsx_a11y;
+use crate::tools::lint::remove_ignore_diagnostics;
+use crate::tools::lint::ConfiguredRules;
 use deno_runtime::fs_util::specifier_to_file_path;
 
 use deno_ast::SourceRange;
@@ -23,7 +26,6 @@ use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::ModuleSpecifier;
 use deno_lint::diagnostic::LintDiagnostic;
-use deno_lint::rules::LintRule;
 use deno_runtime::deno_node::NpmResolver;
 use deno_runtime::deno_node::PathClean;
 use deno_semver::jsr::JsrPackageNvReference;
@@ -168,10 +170,16 @@ fn as_lsp_range(
 
 pub fn get_lint_references(
   parsed_source: &deno_ast::ParsedSource,
-  lint_rules: Vec<&'static dyn LintRule>,
+  lint_rules: &ConfiguredRules,
 ) -> Result<Vec<Reference>, AnyError> {
-  let linter = create_linter(lint_rules);
-  let lint_diagnostics = linter.lint_with_ast(parsed_source);
+  let linter = create_linter(lint_rules.rules.clone());
+  let mut lint_diagnostics = linter.lint_with_ast(parsed_source);
+  remove_ignore_diagnostics(
+    &mut lint_diagnostics,
+    &jsx_a11y::JsxA11yRule::ALL.map(|rule| rule.code()),
+  );
+  let jsx_a11y_diagnostics =
+    jsx_a11y::collect_jsx_a11y_diagnostics(parsed_source, &lint_rules.jsx_a11y);
 
   Ok(
     lint_diagnostics
@@ -199,6 +207,15 @@ pub fn get_lint_references(
             .collect(),
         },
       })
+      .chain(jsx_a11y_diagnostics.into_iter().map(|d| Reference {
+        range: as_lsp_range(d.range, &d.text_info),
+        category: Category::Lint {
+          message: d.message,
+          code: d.code.into_owned(),
+          hint: d.hint.map(|hint| hint.into_owned()),
+          quick_fixes: Vec::new(),
+        },
+      }))
       .collect(),
   )
 }
@@ -666,6 +683,11 @@ pub fn ts_changes_to_edit(
   }))
 }
 
+/// The kind of the source action sorting the imports of a module the way
+/// `deno fmt` does with `"sortImports": true`.
+pub const SOURCE_SORT_IMPORTS: lsp::CodeActionKind =
+  lsp::CodeActionKind::new("source.sortImports");
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CodeActionData {
@@ -1213,6 +1235,53 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_get_lint_references_jsx_a11y() {
+    let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: ModuleSpecifier::parse("file:///a.tsx").unwrap(),
+      text_info: SourceTextInfo::new(
+        "<img src='a.png' />;\n// deno-lint-ignore jsx-a11y-alt-text\n<img />;\n"
+          .into(),
+      ),
+      media_type: deno_ast::MediaType::Tsx,
+      capture_tokens: true,
+      scope_analysis: false,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    let lint_rules = ConfiguredRules {
+      rules: Vec::new(),
+      no_slow_types: false,
+      import_extensions: None,
+      jsx_a11y: vec![jsx_a11y::JsxA11yRule::AltText],
+    };
+    let references = get_lint_references(&parsed_source, &lint_rules).unwrap();
+    assert_eq!(
+      references,
+      vec![Reference {
+        range: Range {
+          start: Position {
+            line: 0,
+            character: 0,
+          },
+          end: Position {
+            line: 0,
+            character: 19,
+          },
+        },
+        category: Category::Lint {
+          message: "<img> elements must have an `alt` prop".to_string(),
+          code: "jsx-a11y-alt-text".to_string(),
+          hint: Some(
+            "add an `alt` prop describing the image, or `alt=\"\"` when the image is decorative"
+              .to_string()
+          ),
+          quick_fixes: Vec::new(),
+        },
+      }]
+    );
+  }
+
   #[test]
   fn test_try_reverse_map_package_json_exports() {
     let exports = json!({
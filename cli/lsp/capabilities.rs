@@ -8,6 +8,7 @@
 use deno_core::serde_json::json;
 use tower_lsp::lsp_types::*;
 
+use super::analysis::SOURCE_SORT_IMPORTS;
 use super::refactor::ALL_KNOWN_REFACTOR_ACTION_KINDS;
 use super::semantic_tokens::get_legend;
 
@@ -27,6 +28,7 @@ fn code_action_capabilities(
           .iter()
           .map(|action| action.kind.clone()),
       );
+      code_action_kinds.push(SOURCE_SORT_IMPORTS);
 
       CodeActionProviderCapability::Options(CodeActionOptions {
         code_action_kinds: Some(code_action_kinds),
@@ -109,7 +111,7 @@ pub fn server_capabilities(
       resolve_provider: Some(true),
     }),
     document_formatting_provider: Some(OneOf::Left(true)),
-    document_range_formatting_provider: None,
+    document_range_formatting_provider: Some(OneOf::Left(true)),
     document_on_type_formatting_provider: None,
     selection_range_provider: Some(SelectionRangeProviderCapability::Simple(
       true,
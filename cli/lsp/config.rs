@@ -1,6 +1,7 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use super::logging::lsp_log;
+use crate::args::deno_json;
 use crate::args::ConfigFile;
 use crate::args::FmtOptions;
 use crate::args::LintOptions;
@@ -1084,6 +1085,8 @@ pub struct ConfigData {
   pub fmt_options: Arc<FmtOptions>,
   pub lint_options: Arc<LintOptions>,
   pub lint_rules: Arc<ConfiguredRules>,
+  /// The rules of each entry in `lint.overrides`, in the same order.
+  pub lint_override_rules: Arc<Vec<Arc<ConfiguredRules>>>,
   pub ts_config: Arc<LspTsConfig>,
   pub byonm: bool,
   pub node_modules_dir: Option<PathBuf>,
@@ -1189,14 +1192,13 @@ impl ConfigData {
       config_file
         .as_ref()
         .and_then(|config_file| {
-          config_file
-            .to_fmt_config()
-            .and_then(|o| {
+          deno_json::to_fmt_config_with_extras(config_file)
+            .and_then(|(o, extras)| {
               let base_path = config_file
                 .specifier
                 .to_file_path()
                 .map_err(|_| anyhow!("Invalid base path."))?;
-              FmtOptions::resolve(o, None, &base_path)
+              FmtOptions::resolve(o, extras, None, &base_path)
             })
             .inspect_err(|err| {
               lsp_warn!("  Couldn't read formatter configuration: {}", err)
@@ -1216,35 +1218,47 @@ impl ConfigData {
         lint_options_rules = Some((
           parent_data.lint_options.clone(),
           parent_data.lint_rules.clone(),
+          parent_data.lint_override_rules.clone(),
         ))
       }
     }
-    let (lint_options, lint_rules) = lint_options_rules.unwrap_or_else(|| {
-      let lint_options = config_file
-        .as_ref()
-        .and_then(|config_file| {
-          config_file
-            .to_lint_config()
-            .and_then(|o| {
-              let base_path = config_file
-                .specifier
-                .to_file_path()
-                .map_err(|_| anyhow!("Invalid base path."))?;
-              LintOptions::resolve(o, None, &base_path)
-            })
-            .inspect_err(|err| {
-              lsp_warn!("  Couldn't read lint configuration: {}", err)
-            })
-            .ok()
-        })
-        .map(Arc::new)
-        .unwrap_or_default();
-      let lint_rules = Arc::new(get_configured_rules(
-        lint_options.rules.clone(),
-        config_file.as_ref(),
-      ));
-      (lint_options, lint_rules)
-    });
+    let (lint_options, lint_rules, lint_override_rules) = lint_options_rules
+      .unwrap_or_else(|| {
+        let lint_options = config_file
+          .as_ref()
+          .and_then(|config_file| {
+            deno_json::to_lint_config_with_extras(config_file)
+              .and_then(|(o, extras)| {
+                let base_path = config_file
+                  .specifier
+                  .to_file_path()
+                  .map_err(|_| anyhow!("Invalid base path."))?;
+                LintOptions::resolve(o, extras, None, &base_path)
+              })
+              .inspect_err(|err| {
+                lsp_warn!("  Couldn't read lint configuration: {}", err)
+              })
+              .ok()
+          })
+          .map(Arc::new)
+          .unwrap_or_default();
+        let lint_rules = Arc::new(get_configured_rules(
+          lint_options.rules.clone(),
+          config_file.as_ref(),
+        ));
+        // resolved once here rather than for every linted document
+        let lint_override_rules = lint_options
+          .overrides
+          .iter()
+          .map(|o| {
+            Arc::new(get_configured_rules(
+              o.rules.clone(),
+              config_file.as_ref(),
+            ))
+          })
+          .collect();
+        (lint_options, lint_rules, Arc::new(lint_override_rules))
+      });
 
     let ts_config = LspTsConfig::new(config_file.as_ref());
 
@@ -1423,6 +1437,7 @@ impl ConfigData {
       fmt_options,
       lint_options,
       lint_rules,
+      lint_override_rules,
       ts_config: Arc::new(ts_config),
       byonm,
       node_modules_dir,
@@ -1434,6 +1449,21 @@ impl ConfigData {
       watched_files,
     }
   }
+
+  /// Gets the lint rules for the document, which are those of the last
+  /// entry in `lint.overrides` matching it, if any.
+  pub fn lint_rules_for_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Arc<ConfiguredRules> {
+    let maybe_override_index = specifier_to_file_path(specifier)
+      .ok()
+      .and_then(|path| self.lint_options.override_index_for_path(&path));
+    match maybe_override_index {
+      Some(index) => self.lint_override_rules[index].clone(),
+      None => self.lint_rules.clone(),
+    }
+  }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -20,6 +20,7 @@ use crate::graph_util::enhanced_resolution_error_message;
 use crate::lsp::lsp_custom::DiagnosticBatchNotificationParams;
 use crate::resolver::SloppyImportsResolution;
 use crate::resolver::SloppyImportsResolver;
+use crate::tools::lint::ConfiguredRules;
 use crate::util::path::to_percent_decoded_str;
 
 use deno_ast::MediaType;
@@ -38,7 +39,6 @@ use deno_graph::source::ResolutionMode;
 use deno_graph::Resolution;
 use deno_graph::ResolutionError;
 use deno_graph::SpecifierError;
-use deno_lint::rules::LintRule;
 use deno_runtime::deno_fs;
 use deno_runtime::deno_node;
 use deno_runtime::tokio_util::create_basic_runtime;
@@ -808,7 +808,12 @@ fn generate_lint_diagnostics(
       .tree
       .scope_for_specifier(specifier)
       .and_then(|s| config_data_by_scope.get(s))
-      .map(|d| (d.lint_options.clone(), d.lint_rules.clone()))
+      .map(|d| {
+        (
+          d.lint_options.clone(),
+          d.lint_rules_for_specifier(specifier),
+        )
+      })
       .unwrap_or_default();
     diagnostics_vec.push(DiagnosticRecord {
       specifier: specifier.clone(),
@@ -817,7 +822,7 @@ fn generate_lint_diagnostics(
         diagnostics: generate_document_lint_diagnostics(
           &document,
           &lint_options,
-          lint_rules.rules.clone(),
+          &lint_rules,
         ),
       },
     });
@@ -828,7 +833,7 @@ fn generate_lint_diagnostics(
 fn generate_document_lint_diagnostics(
   document: &Document,
   lint_options: &LintOptions,
-  lint_rules: Vec<&'static dyn LintRule>,
+  lint_rules: &ConfiguredRules,
 ) -> Vec<lsp::Diagnostic> {
   if !lint_options.files.matches_specifier(document.specifier()) {
     return Vec::new();
@@ -42,6 +42,7 @@ use super::analysis::ts_changes_to_edit;
 use super::analysis::CodeActionCollection;
 use super::analysis::CodeActionData;
 use super::analysis::TsResponseImportMapper;
+use super::analysis::SOURCE_SORT_IMPORTS;
 use super::cache::LspCache;
 use super::capabilities;
 use super::client::Client;
@@ -84,6 +85,7 @@ use super::tsc::GetCompletionDetailsArgs;
 use super::tsc::TsServer;
 use super::urls;
 use crate::args::get_root_cert_store;
+use crate::args::write_lockfile;
 use crate::args::CaData;
 use crate::args::CacheSetting;
 use crate::args::CliOptions;
@@ -98,6 +100,8 @@ use crate::lsp::tsc::file_text_changes_to_workspace_edit;
 use crate::lsp::urls::LspUrlKind;
 use crate::tools::fmt::format_file;
 use crate::tools::fmt::format_parsed_source;
+use crate::tools::fmt::sort_imports::sort_imports;
+use crate::tools::fmt::FormatFileOptions;
 use crate::tools::upgrade::check_for_upgrades_for_lsp;
 use crate::tools::upgrade::upgrade_check_enabled;
 use crate::util::fs::remove_dir_all_if_exists;
@@ -249,6 +253,8 @@ impl LanguageServer {
           is_vendoring: false,
           follow_type_only: true,
           check_js: false,
+          maybe_import_map: None,
+          maybe_npm_registry_dir: None,
         },
       )?;
 
@@ -256,7 +262,7 @@ impl LanguageServer {
       // found after caching
       if let Some(lockfile) = cli_options.maybe_lockfile() {
         let lockfile = lockfile.lock();
-        if let Err(err) = lockfile.write() {
+        if let Err(err) = write_lockfile(&lockfile) {
           lsp_warn!("Error writing lockfile: {:#}", err);
         }
       }
@@ -1228,9 +1234,35 @@ impl Inner {
     &self,
     params: DocumentFormattingParams,
   ) -> LspResult<Option<Vec<TextEdit>>> {
-    let mut specifier = self
-      .url_map
-      .normalize_url(&params.text_document.uri, LspUrlKind::File);
+    self
+      .format_document(&params.text_document.uri, None, || {
+        self.performance.mark_with_args("lsp.formatting", &params)
+      })
+      .await
+  }
+
+  async fn range_formatting(
+    &self,
+    params: DocumentRangeFormattingParams,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    self
+      .format_document(&params.text_document.uri, Some(params.range), || {
+        self
+          .performance
+          .mark_with_args("lsp.range_formatting", &params)
+      })
+      .await
+  }
+
+  /// Formats the document, only keeping the edits that touch the range
+  /// when one is provided.
+  async fn format_document(
+    &self,
+    uri: &Url,
+    maybe_range: Option<Range>,
+    mark: impl FnOnce() -> PerformanceMark,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    let mut specifier = self.url_map.normalize_url(uri, LspUrlKind::File);
     // skip formatting any files ignored by the config file
     if !self
       .config
@@ -1248,43 +1280,59 @@ impl Inner {
     // Detect vendored paths. Vendor file URLs will normalize to their remote
     // counterparts, but for formatting we want to favour the file URL.
     // TODO(nayeemrmn): Implement `Document::file_resource_path()` or similar.
-    if specifier.scheme() != "file"
-      && params.text_document.uri.scheme() == "file"
-    {
-      specifier = params.text_document.uri.clone();
+    if specifier.scheme() != "file" && uri.scheme() == "file" {
+      specifier = uri.clone();
     }
     let file_path = specifier_to_file_path(&specifier).map_err(|err| {
       error!("{:#}", err);
       LspError::invalid_request()
     })?;
-    let mark = self.performance.mark_with_args("lsp.formatting", &params);
+    let mark = mark();
 
     // spawn a blocking task to allow doing other work while this is occurring
     let text_edits = deno_core::unsync::spawn_blocking({
-      let fmt_options = self
-        .config
-        .tree
-        .fmt_options_for_specifier(&specifier)
-        .options
-        .clone();
+      let all_fmt_options =
+        self.config.tree.fmt_options_for_specifier(&specifier);
+      let fmt_options = all_fmt_options.options_for_path(&file_path).clone();
+      let should_sort_imports = all_fmt_options.sort_imports;
+      let file_options = FormatFileOptions {
+        skip_code_block_languages: all_fmt_options
+          .skip_code_block_languages
+          .clone(),
+        unstable: all_fmt_options.unstable,
+      };
       let document = document.clone();
       move || {
-        let format_result = match document.maybe_parsed_source() {
-          Some(Ok(parsed_source)) => {
-            format_parsed_source(&parsed_source, &fmt_options)
-          }
-          Some(Err(err)) => Err(anyhow!("{:#}", err)),
-          None => {
-            // the file path is only used to determine what formatter should
-            // be used to format the file, so give the filepath an extension
-            // that matches what the user selected as the language
-            let file_path = document
-              .maybe_language_id()
-              .and_then(|id| id.as_extension())
-              .map(|ext| file_path.with_extension(ext))
-              .unwrap_or(file_path);
-            // it's not a js/ts file, so attempt to format its contents
-            format_file(&file_path, &document.content(), &fmt_options)
+        let sorted_text = should_sort_imports
+          .then(|| sort_imports(&file_path, &document.content()))
+          .flatten();
+        let format_result = if let Some(sorted_text) = sorted_text {
+          // the parsed source is stale once the imports were sorted
+          format_file(&file_path, &sorted_text, &fmt_options, &file_options)
+            .map(|formatted_text| formatted_text.or(Some(sorted_text)))
+        } else {
+          match document.maybe_parsed_source() {
+            Some(Ok(parsed_source)) => {
+              format_parsed_source(&parsed_source, &fmt_options)
+            }
+            Some(Err(err)) => Err(anyhow!("{:#}", err)),
+            None => {
+              // the file path is only used to determine what formatter should
+              // be used to format the file, so give the filepath an extension
+              // that matches what the user selected as the language
+              let file_path = document
+                .maybe_language_id()
+                .and_then(|id| id.as_extension())
+                .map(|ext| file_path.with_extension(ext))
+                .unwrap_or(file_path);
+              // it's not a js/ts file, so attempt to format its contents
+              format_file(
+                &file_path,
+                &document.content(),
+                &fmt_options,
+                &file_options,
+              )
+            }
           }
         };
         match format_result {
@@ -1305,7 +1353,16 @@ impl Inner {
     .unwrap();
 
     self.performance.measure(mark);
-    if let Some(text_edits) = text_edits {
+    if let Some(mut text_edits) = text_edits {
+      if let Some(range) = maybe_range {
+        text_edits.retain(|edit| {
+          if edit.range.start == edit.range.end {
+            range.start <= edit.range.start && edit.range.start <= range.end
+          } else {
+            edit.range.start < range.end && edit.range.end > range.start
+          }
+        });
+      }
       if text_edits.is_empty() {
         Ok(None)
       } else {
@@ -1614,6 +1671,27 @@ impl Inner {
         .map(CodeActionOrCommand::CodeAction),
     );
 
+    // Source, which clients only show when explicitly requested
+    let wants_sort_imports = params.context.only.as_ref().is_some_and(|only| {
+      only.iter().any(|kind| {
+        let kind = kind.as_str();
+        SOURCE_SORT_IMPORTS.as_str() == kind
+          || SOURCE_SORT_IMPORTS
+            .as_str()
+            .strip_prefix(kind)
+            .is_some_and(|rest| rest.starts_with('.'))
+      })
+    });
+    if wants_sort_imports {
+      if let Some(action) = self.sort_imports_action(
+        &specifier,
+        &params.text_document.uri,
+        &asset_or_doc,
+      ) {
+        all_actions.push(CodeActionOrCommand::CodeAction(action));
+      }
+    }
+
     let code_action_disabled_support =
       self.config.client_capabilities.code_action_disabled_support;
     let actions: Vec<CodeActionOrCommand> = all_actions.into_iter().filter(|ca| {
@@ -1630,6 +1708,29 @@ impl Inner {
     Ok(response)
   }
 
+  fn sort_imports_action(
+    &self,
+    specifier: &ModuleSpecifier,
+    uri: &Url,
+    asset_or_doc: &AssetOrDocument,
+  ) -> Option<CodeAction> {
+    let document = asset_or_doc.document()?;
+    let file_path = specifier_to_file_path(specifier).ok()?;
+    let content = document.content();
+    let sorted_text = sort_imports(&file_path, &content)?;
+    let edits =
+      text::get_edits(&content, &sorted_text, document.line_index().as_ref());
+    Some(CodeAction {
+      title: "Sort imports".to_string(),
+      kind: Some(SOURCE_SORT_IMPORTS),
+      edit: Some(WorkspaceEdit {
+        changes: Some(HashMap::from([(uri.clone(), edits)])),
+        ..Default::default()
+      }),
+      ..Default::default()
+    })
+  }
+
   async fn code_action_resolve(
     &self,
     params: CodeAction,
@@ -3073,6 +3174,13 @@ impl tower_lsp::LanguageServer for LanguageServer {
     self.0.read().await.formatting(params).await
   }
 
+  async fn range_formatting(
+    &self,
+    params: DocumentRangeFormattingParams,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    self.0.read().await.range_formatting(params).await
+  }
+
   async fn hover(&self, params: HoverParams) -> LspResult<Option<Hover>> {
     self.0.read().await.hover(params).await
   }
@@ -3321,7 +3429,10 @@ impl Inner {
     for config_file in self.config.tree.config_files() {
       if let Some(tasks) = json!(&config_file.json.tasks).as_object() {
         for (name, value) in tasks {
-          let Some(command) = value.as_str() else {
+          let Some(command) = value
+            .as_str()
+            .or_else(|| value.get("command").and_then(|c| c.as_str()))
+          else {
             continue;
           };
           result.push(TaskDefinition {
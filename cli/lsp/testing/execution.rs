@@ -123,6 +123,20 @@ struct LspTestFilter {
 }
 
 impl LspTestFilter {
+  /// Gets the 1-based lines of the included tests, when all of them were
+  /// registered statically at a known location.
+  fn lines(&self) -> Option<Vec<u32>> {
+    self
+      .include
+      .as_ref()?
+      .values()
+      .map(|test| {
+        let range = test.range.filter(|_| !test.is_dynamic)?;
+        Some(range.start.line + 1)
+      })
+      .collect()
+  }
+
   fn as_ids(&self, test_module: &TestModule) -> Vec<String> {
     let ids: Vec<String> = if let Some(include) = &self.include {
       include.keys().cloned().collect()
@@ -231,6 +245,19 @@ impl TestRun {
     )
     .await?;
 
+    // the tests of the code lenses are selected by their lines, like with
+    // `deno test path:line`, so tests with the same name aren't run too
+    let lines = self
+      .filters
+      .iter()
+      .filter_map(|(specifier, filter)| {
+        let lines = filter.lines()?;
+        Some((specifier.clone(), lines))
+      })
+      .collect::<HashMap<_, _>>();
+    let mut lines =
+      test::resolve_test_lines(factory.file_fetcher()?, lines).await?;
+
     let (concurrent_jobs, fail_fast) = if let DenoSubcommand::Test(test_flags) =
       factory.cli_options().sub_command()
     {
@@ -271,10 +298,13 @@ impl TestRun {
       let worker_sender = test_event_sender_factory.worker();
       let fail_fast_tracker = fail_fast_tracker.clone();
       let lsp_filter = self.filters.get(&specifier);
+      let test_lines = lines
+        .remove(&specifier)
+        .map(|lines| HashMap::from([(specifier.clone(), lines)]));
       let filter = test::TestFilter {
         substring: None,
         regex: None,
-        include: lsp_filter.and_then(|f| {
+        include: lsp_filter.filter(|_| test_lines.is_none()).and_then(|f| {
           f.include
             .as_ref()
             .map(|i| i.values().map(|t| t.name.clone()).collect())
@@ -304,6 +334,12 @@ impl TestRun {
               filter,
               shuffle: None,
               trace_leaks: false,
+              shard: None,
+              retries: 0,
+              timeout: None,
+              update_snapshots: false,
+              list: false,
+              lines: test_lines.unwrap_or_default(),
             },
           ))
         }
@@ -357,7 +393,9 @@ impl TestRun {
               if tests_with_result.insert(id) {
                 let description = tests.read().get(&id).unwrap().clone();
                 match &result {
-                  test::TestResult::Ok => summary.passed += 1,
+                  test::TestResult::Ok | test::TestResult::Flaky(_) => {
+                    summary.passed += 1
+                  }
                   test::TestResult::Ignored => summary.ignored += 1,
                   test::TestResult::Failed(error) => {
                     summary.failed += 1;
@@ -372,6 +410,7 @@ impl TestRun {
                 reporter.report_result(&description, &result, elapsed);
               }
             }
+            test::TestEvent::Retry(_) => {}
             test::TestEvent::UncaughtError(origin, error) => {
               reporter.report_uncaught_error(&origin, &error);
               summary.failed += 1;
@@ -632,7 +671,7 @@ impl LspTestReporter {
   ) {
     self.current_test = None;
     match result {
-      test::TestResult::Ok => {
+      test::TestResult::Ok | test::TestResult::Flaky(_) => {
         let desc = self.tests.get(&desc.id).unwrap();
         self.progress(lsp_custom::TestRunProgressMessage::Passed {
           test: desc.as_test_identifier(&self.tests),
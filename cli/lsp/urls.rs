@@ -1,6 +1,7 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use crate::cache::LocalLspHttpCache;
+use crate::util::path::specifier_case_key;
 
 use deno_ast::MediaType;
 use deno_core::error::AnyError;
@@ -122,13 +123,39 @@ impl std::fmt::Display for LspClientUrl {
   }
 }
 
+const MAX_FILE_SPECIFIERS_BY_CASE_KEY: usize = 10_000;
+
 #[derive(Debug, Default)]
 struct LspUrlMapInner {
   specifier_to_url: HashMap<ModuleSpecifier, LspClientUrl>,
   url_to_specifier: HashMap<Url, ModuleSpecifier>,
+  /// The first file specifier seen for each case key, so that urls only
+  /// differing in case map to the same module on case insensitive file
+  /// systems.
+  file_specifiers_by_case_key: HashMap<String, ModuleSpecifier>,
 }
 
 impl LspUrlMapInner {
+  fn get_or_insert_case_insensitive_file_specifier(
+    &mut self,
+    specifier: ModuleSpecifier,
+  ) -> ModuleSpecifier {
+    let Some(key) = specifier_case_key(&specifier) else {
+      return specifier;
+    };
+    // start over when full, which only affects urls that differ in case
+    // from the ones seen before
+    if self.file_specifiers_by_case_key.len() >= MAX_FILE_SPECIFIERS_BY_CASE_KEY
+    {
+      self.file_specifiers_by_case_key.clear();
+    }
+    self
+      .file_specifiers_by_case_key
+      .entry(key)
+      .or_insert(specifier)
+      .clone()
+  }
+
   fn put(&mut self, specifier: ModuleSpecifier, url: LspClientUrl) {
     self
       .url_to_specifier
@@ -246,7 +273,9 @@ impl LspUrlMap {
     } else if let Some(s) = from_deno_url(url) {
       specifier = Some(s);
     }
-    let specifier = specifier.unwrap_or_else(|| url.clone());
+    let specifier = inner.get_or_insert_case_insensitive_file_specifier(
+      specifier.unwrap_or_else(|| url.clone()),
+    );
     inner.put(specifier.clone(), LspClientUrl(url.clone()));
     specifier
   }
@@ -279,6 +308,7 @@ fn file_like_to_file_specifier(specifier: &Url) -> Option<Url> {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::util::path::is_case_insensitive_fs;
   use deno_core::resolve_url;
 
   #[test]
@@ -387,6 +417,24 @@ mod tests {
     assert_eq!(actual, expected);
   }
 
+  #[test]
+  fn test_normalize_url_case() {
+    let temp_dir = test_util::TempDir::new();
+    temp_dir.create_dir_all("Deno");
+    let map = LspUrlMap::default();
+    let first =
+      Url::from_file_path(temp_dir.path().join("Deno/Mod.ts")).unwrap();
+    let second =
+      Url::from_file_path(temp_dir.path().join("deno/mod.ts")).unwrap();
+    let first_specifier = map.normalize_url(&first, LspUrlKind::File);
+    let second_specifier = map.normalize_url(&second, LspUrlKind::File);
+    if is_case_insensitive_fs(temp_dir.path().join("Deno").as_path()) {
+      assert_eq!(second_specifier, first_specifier);
+    } else {
+      assert_eq!(second_specifier, second);
+    }
+  }
+
   #[cfg(not(windows))]
   #[test]
   fn test_normalize_percent_encoded_path() {
@@ -123,12 +123,7 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
       emitter.cache_module_emits(&main_graph_container.graph())
     }),
     DenoSubcommand::Check(check_flags) => spawn_subcommand(async move {
-      let factory = CliFactory::from_flags(flags)?;
-      let main_graph_container =
-        factory.main_module_graph_container().await?;
-      main_graph_container
-        .load_and_type_check_files(&check_flags.files)
-        .await
+      tools::check::check(flags, check_flags).await
     }),
     DenoSubcommand::Compile(compile_flags) => spawn_subcommand(async {
       tools::compile::compile(flags, compile_flags).await
@@ -168,6 +163,8 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
           lint_flags.maybe_rules_tags,
         );
         Ok(())
+      } else if let Some(rule) = &lint_flags.explain {
+        tools::lint::print_rule_explanation(rule, lint_flags.json)
       } else {
         tools::lint::lint(flags, lint_flags).await
       }
@@ -233,6 +230,9 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
     DenoSubcommand::Publish(publish_flags) => spawn_subcommand(async {
       tools::registry::publish(flags, publish_flags).await
     }),
+    DenoSubcommand::Remove(remove_flags) => spawn_subcommand(async {
+      tools::registry::remove(flags, remove_flags).await
+    }),
   };
 
   handle.await?
@@ -412,7 +412,7 @@ fn resolve_flags_and_init(
 
   init_v8_flags(&default_v8_flags, &flags.v8_flags, get_v8_flags_from_env());
   deno_core::JsRuntime::init_platform(None);
-  util::logger::init(flags.log_level);
+  util::logger::init(flags.log_level, flags.log_format);
 
   Ok(flags)
 }
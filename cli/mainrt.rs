@@ -71,6 +71,9 @@ fn unwrap_or_exit<T>(result: Result<T, AnyError>) -> T {
 }
 
 fn main() {
+  // executables compiled with `--no-terminal` have no stdio handles on
+  // Windows unless they were started with redirected output
+  util::windows::ensure_stdio_open();
   let args: Vec<_> = env::args_os().collect();
   let current_exe_path = current_exe().unwrap();
   let standalone =
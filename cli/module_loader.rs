@@ -8,6 +8,7 @@ use std::str;
 use std::sync::Arc;
 
 use crate::args::jsr_url;
+use crate::args::write_lockfile;
 use crate::args::CliOptions;
 use crate::args::DenoSubcommand;
 use crate::args::TsTypeLib;
@@ -178,7 +179,7 @@ impl ModuleLoadPreparer {
       // validate the integrity of all the modules
       graph_lock_or_exit(graph, &mut lockfile);
       // update it with anything new
-      lockfile.write().context("Failed writing lockfile.")?;
+      write_lockfile(&lockfile).context("Failed writing lockfile.")?;
     }
 
     drop(_pb_clear_guard);
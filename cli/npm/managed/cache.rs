@@ -22,6 +22,7 @@ use crate::args::CacheSetting;
 use crate::http_util::HttpClient;
 use crate::npm::NpmCacheDir;
 use crate::util::fs::hard_link_dir_recursive;
+use crate::util::path::get_atomic_file_path;
 use crate::util::progress_bar::ProgressBar;
 
 use super::tarball::verify_and_extract_tarball;
@@ -118,13 +119,19 @@ impl NpmCache {
       bail!("Tarball URL was empty.");
     }
 
+    // stream the tarball to a sibling temp file so large packages aren't
+    // held in memory while they're verified and extracted
+    if let Some(parent) = package_folder.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    let tarball_path = get_atomic_file_path(&package_folder);
     let guard = self.progress_bar.update(&dist.tarball);
-    let maybe_bytes = self
+    let found = self
       .http_client
-      .download_with_progress(&dist.tarball, &guard)
-      .await?;
-    match maybe_bytes {
-      Some(bytes) => {
+      .download_to_file_with_progress(&dist.tarball, &tarball_path, &guard)
+      .await;
+    match found {
+      Ok(true) => {
         let extraction_mode = if should_use_cache || !package_folder_exists {
           TarballExtractionMode::SiblingTempDir
         } else {
@@ -140,20 +147,30 @@ impl NpmCache {
         };
         let dist = dist.clone();
         let package_nv = package_nv.clone();
-        deno_core::unsync::spawn_blocking(move || {
-          verify_and_extract_tarball(
-            &package_nv,
-            &bytes,
-            &dist,
-            &package_folder,
-            extraction_mode,
-          )
+        let result = deno_core::unsync::spawn_blocking({
+          let tarball_path = tarball_path.clone();
+          move || {
+            verify_and_extract_tarball(
+              &package_nv,
+              &tarball_path,
+              &dist,
+              &package_folder,
+              extraction_mode,
+            )
+          }
         })
-        .await?
+        .await;
+        let _ = fs::remove_file(&tarball_path);
+        result?
       }
-      None => {
+      Ok(false) => {
+        let _ = fs::remove_file(&tarball_path);
         bail!("Could not find npm package tarball at: {}", dist.tarball);
       }
+      Err(err) => {
+        let _ = fs::remove_file(&tarball_path);
+        Err(err)
+      }
     }
   }
 
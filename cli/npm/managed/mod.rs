@@ -32,6 +32,7 @@ use crate::args::PackageJsonDepsProvider;
 use crate::cache::FastInsecureHasher;
 use crate::util::fs::canonicalize_path_maybe_not_exists_with_fs;
 use crate::util::progress_bar::ProgressBar;
+use crate::util::progress_bar::ProgressBarStyle;
 
 use self::cache::NpmCache;
 use self::installer::PackageJsonDepsInstaller;
@@ -179,7 +180,8 @@ fn create_cache(options: &CliNpmResolverManagedCreateOptions) -> Arc<NpmCache> {
     options.cache_setting.clone(),
     options.fs.clone(),
     options.http_client.clone(),
-    options.text_only_progress_bar.clone(),
+    // tarballs are downloaded concurrently, so show each on its own line
+    ProgressBar::new(ProgressBarStyle::MultiBars),
   ))
 }
 
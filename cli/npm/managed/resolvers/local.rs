@@ -11,10 +11,12 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::args::DENO_NPM_TRASH;
 use crate::cache::CACHE_PERM;
 use crate::npm::cache_dir::mixed_case_package_name_decode;
-use crate::util::fs::atomic_write_file;
 use crate::util::fs::canonicalize_path_maybe_not_exists_with_fs;
+use crate::util::fs::durable_atomic_write_file;
+use crate::util::fs::remove_dir_all_to_trash;
 use crate::util::fs::symlink_dir;
 use crate::util::fs::LaxSingleProcessFsFlag;
 use crate::util::progress_bar::ProgressBar;
@@ -273,7 +275,7 @@ async fn sync_resolution_with_fs(
     // similar message used by cargo build
     "waiting for file lock on node_modules directory",
   )
-  .await;
+  .await?;
 
   // load this after we get the directory lock
   let mut setup_cache =
@@ -535,7 +537,7 @@ impl SetupCache {
     }
 
     bincode::serialize(&self.current).ok().and_then(|data| {
-      atomic_write_file(&self.file_path, data, CACHE_PERM).ok()
+      durable_atomic_write_file(&self.file_path, data, CACHE_PERM).ok()
     });
     true
   }
@@ -660,7 +662,15 @@ fn symlink_package_dir(
   }
 
   // need to delete the previous symlink before creating a new one
-  let _ignore = fs::remove_dir_all(new_path);
+  let is_dir = fs::symlink_metadata(new_path)
+    .map(|metadata| metadata.is_dir())
+    .unwrap_or(false);
+  if is_dir && *DENO_NPM_TRASH {
+    // not a symlink, so it was installed by something else
+    remove_dir_all_to_trash(new_path)?;
+  } else {
+    let _ignore = fs::remove_dir_all(new_path);
+  }
 
   #[cfg(windows)]
   return junction_or_symlink_dir(old_path, new_path);
@@ -2,7 +2,9 @@
 
 use std::collections::HashSet;
 use std::fs;
+use std::io::BufReader;
 use std::io::ErrorKind;
+use std::io::Read;
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -18,6 +20,9 @@ use flate2::read::GzDecoder;
 use tar::Archive;
 use tar::EntryType;
 
+use crate::util::checksum;
+use crate::util::checksum::ChecksumAlgorithm;
+use crate::util::checksum::ChecksumHasher;
 use crate::util::path::get_atomic_dir_path;
 
 #[derive(Debug, Copy, Clone)]
@@ -32,20 +37,29 @@ pub enum TarballExtractionMode {
   SiblingTempDir,
 }
 
+/// Verifies and extracts the downloaded tarball at the provided path. The
+/// tarball is read in chunks, so it's never fully loaded into memory.
 pub fn verify_and_extract_tarball(
   package_nv: &PackageNv,
-  data: &[u8],
+  tarball_path: &Path,
   dist_info: &NpmPackageVersionDistInfo,
   output_folder: &Path,
   extraction_mode: TarballExtractionMode,
 ) -> Result<(), AnyError> {
-  verify_tarball_integrity(package_nv, data, &dist_info.integrity())?;
+  verify_tarball_integrity(
+    package_nv,
+    BufReader::new(fs::File::open(tarball_path)?),
+    &dist_info.integrity(),
+  )?;
 
+  let open_tarball = || fs::File::open(tarball_path).map(BufReader::new);
   match extraction_mode {
-    TarballExtractionMode::Overwrite => extract_tarball(data, output_folder),
+    TarballExtractionMode::Overwrite => {
+      extract_tarball(open_tarball()?, output_folder)
+    }
     TarballExtractionMode::SiblingTempDir => {
       let temp_dir = get_atomic_dir_path(output_folder);
-      extract_tarball(data, &temp_dir)?;
+      extract_tarball(open_tarball()?, &temp_dir)?;
       rename_with_retries(&temp_dir, output_folder)
         .map_err(AnyError::from)
         .context("Failed moving extracted tarball to final destination.")
@@ -92,35 +106,31 @@ fn rename_with_retries(
 
 fn verify_tarball_integrity(
   package: &PackageNv,
-  data: &[u8],
+  mut reader: impl Read,
   npm_integrity: &NpmPackageVersionDistInfoIntegrity,
 ) -> Result<(), AnyError> {
-  use ring::digest::Context;
   let (tarball_checksum, expected_checksum) = match npm_integrity {
     NpmPackageVersionDistInfoIntegrity::Integrity {
       algorithm,
       base64_hash,
     } => {
       let algo = match *algorithm {
-        "sha512" => &ring::digest::SHA512,
-        "sha1" => &ring::digest::SHA1_FOR_LEGACY_USE_ONLY,
+        "sha512" => ChecksumAlgorithm::Sha512,
+        "sha1" => ChecksumAlgorithm::Sha1,
         hash_kind => bail!(
           "Not implemented hash function for {}: {}",
           package,
           hash_kind
         ),
       };
-      let mut hash_ctx = Context::new(algo);
-      hash_ctx.update(data);
-      let digest = hash_ctx.finish();
-      let tarball_checksum = BASE64_STANDARD.encode(digest.as_ref());
+      let mut hasher = ChecksumHasher::new(algo);
+      std::io::copy(&mut reader, &mut hasher)?;
+      let tarball_checksum = BASE64_STANDARD.encode(hasher.finish_bytes());
       (tarball_checksum, base64_hash)
     }
     NpmPackageVersionDistInfoIntegrity::LegacySha1Hex(hex) => {
-      let mut hash_ctx = Context::new(&ring::digest::SHA1_FOR_LEGACY_USE_ONLY);
-      hash_ctx.update(data);
-      let digest = hash_ctx.finish();
-      let tarball_checksum = faster_hex::hex_string(digest.as_ref());
+      let tarball_checksum =
+        checksum::gen_from_reader(ChecksumAlgorithm::Sha1, reader)?;
       (tarball_checksum, hex)
     }
     NpmPackageVersionDistInfoIntegrity::UnknownIntegrity(integrity) => {
@@ -143,10 +153,13 @@ fn verify_tarball_integrity(
   Ok(())
 }
 
-fn extract_tarball(data: &[u8], output_folder: &Path) -> Result<(), AnyError> {
+fn extract_tarball(
+  reader: impl Read,
+  output_folder: &Path,
+) -> Result<(), AnyError> {
   fs::create_dir_all(output_folder)?;
   let output_folder = fs::canonicalize(output_folder)?;
-  let tar = GzDecoder::new(data);
+  let tar = GzDecoder::new(reader);
   let mut archive = Archive::new(tar);
   archive.set_overwrite(true);
   archive.set_preserve_permissions(true);
@@ -224,7 +237,7 @@ mod test {
     assert_eq!(
       verify_tarball_integrity(
         &package,
-        &Vec::new(),
+        std::io::empty(),
         &NpmPackageVersionDistInfoIntegrity::UnknownIntegrity("test")
       )
       .unwrap_err()
@@ -234,7 +247,7 @@ mod test {
     assert_eq!(
       verify_tarball_integrity(
         &package,
-        &Vec::new(),
+        std::io::empty(),
         &NpmPackageVersionDistInfoIntegrity::Integrity {
           algorithm: "notimplemented",
           base64_hash: "test"
@@ -247,7 +260,7 @@ mod test {
     assert_eq!(
       verify_tarball_integrity(
         &package,
-        &Vec::new(),
+        std::io::empty(),
         &NpmPackageVersionDistInfoIntegrity::Integrity {
           algorithm: "sha1",
           base64_hash: "test"
@@ -263,7 +276,7 @@ mod test {
     assert_eq!(
       verify_tarball_integrity(
         &package,
-        &Vec::new(),
+        std::io::empty(),
         &NpmPackageVersionDistInfoIntegrity::Integrity {
           algorithm: "sha512",
           base64_hash: "test"
@@ -275,7 +288,7 @@ mod test {
     );
     assert!(verify_tarball_integrity(
       &package,
-      &Vec::new(),
+      std::io::empty(),
       &NpmPackageVersionDistInfoIntegrity::Integrity {
         algorithm: "sha512",
         base64_hash: actual_checksum,
@@ -286,7 +299,7 @@ mod test {
     assert_eq!(
       verify_tarball_integrity(
         &package,
-        &Vec::new(),
+        std::io::empty(),
         &NpmPackageVersionDistInfoIntegrity::LegacySha1Hex("test"),
       )
       .unwrap_err()
@@ -295,7 +308,7 @@ mod test {
     );
     assert!(verify_tarball_integrity(
       &package,
-      &Vec::new(),
+      std::io::empty(),
       &NpmPackageVersionDistInfoIntegrity::LegacySha1Hex(actual_hex),
     )
     .is_ok());
@@ -17,6 +17,7 @@ use deno_runtime::permissions::PermissionsContainer;
 use tokio::sync::mpsc::UnboundedSender;
 use uuid::Uuid;
 
+use crate::args::BenchBudgetOptions;
 use crate::tools::bench::BenchDescription;
 use crate::tools::bench::BenchEvent;
 
@@ -30,25 +31,45 @@ deno_core::extension!(deno_bench,
     op_pledge_test_permissions,
     op_restore_test_permissions,
     op_register_bench,
+    op_bench_collects_samples,
+    op_bench_get_budget,
     op_bench_get_origin,
     op_dispatch_bench_event,
     op_bench_now,
   ],
   options = {
     sender: UnboundedSender<BenchEvent>,
+    budget: BenchBudgetOptions,
+    collect_samples: bool,
   },
   state = |state, options| {
     state.put(options.sender);
+    state.put(options.budget);
+    state.put(BenchCollectSamples(options.collect_samples));
     state.put(BenchContainer::default());
   },
 );
 
+/// Whether the raw samples of the benches are reported.
+struct BenchCollectSamples(bool);
+
+#[op2(fast)]
+fn op_bench_collects_samples(state: &mut OpState) -> bool {
+  state.borrow::<BenchCollectSamples>().0
+}
+
 #[op2]
 #[string]
 fn op_bench_get_origin(state: &mut OpState) -> String {
   state.borrow::<ModuleSpecifier>().to_string()
 }
 
+#[op2]
+#[serde]
+fn op_bench_get_budget(state: &mut OpState) -> BenchBudgetOptions {
+  *state.borrow::<BenchBudgetOptions>()
+}
+
 #[derive(Clone)]
 struct PermissionsHolder(Uuid, PermissionsContainer);
 
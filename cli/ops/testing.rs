@@ -1,5 +1,6 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use crate::tools::test::SnapshotStore;
 use crate::tools::test::TestContainer;
 use crate::tools::test::TestDescription;
 use crate::tools::test::TestEvent;
@@ -31,6 +32,7 @@ deno_core::extension!(deno_test,
     op_register_test,
     op_register_test_step,
     op_test_get_origin,
+    op_test_assert_snapshot,
     op_test_event_step_wait,
     op_test_event_step_result_ok,
     op_test_event_step_result_ignored,
@@ -116,6 +118,8 @@ fn op_register_test(
   #[string] file_name: String,
   #[smi] line_number: u32,
   #[smi] column_number: u32,
+  #[smi] retries: i32,
+  #[smi] timeout: i32,
   #[buffer] ret_buf: &mut [u8],
 ) -> Result<(), AnyError> {
   if ret_buf.len() != 4 {
@@ -133,6 +137,9 @@ fn op_register_test(
     only,
     sanitize_ops,
     sanitize_resources,
+    // a negative value is passed when an option isn't set
+    retries: usize::try_from(retries).ok(),
+    timeout: u64::try_from(timeout).ok(),
     origin: origin.clone(),
     location: TestLocation {
       file_name,
@@ -152,6 +159,23 @@ fn op_test_get_origin(state: &mut OpState) -> String {
   state.borrow::<ModuleSpecifier>().to_string()
 }
 
+/// Returns the message of the assertion error when the value doesn't match
+/// its snapshot.
+#[op2]
+#[string]
+fn op_test_assert_snapshot(
+  state: &mut OpState,
+  #[string] name: &str,
+  #[string] actual: &str,
+) -> Result<Option<String>, AnyError> {
+  let Some(snapshot_store) = state.try_borrow_mut::<SnapshotStore>() else {
+    return Err(generic_error(
+      "Snapshots are only supported when running `deno test`.",
+    ));
+  };
+  snapshot_store.assert(name, actual)
+}
+
 #[op2(fast)]
 #[smi]
 #[allow(clippy::too_many_arguments)]
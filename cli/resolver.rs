@@ -427,6 +427,10 @@ impl MappedSpecifierResolver {
       Ok(MappedResolution::None)
     }
   }
+
+  pub fn maybe_import_map(&self) -> Option<&Arc<ImportMap>> {
+    self.maybe_import_map.as_ref()
+  }
 }
 
 /// A resolver that takes care of resolution, taking into account loaded
@@ -503,6 +507,10 @@ impl CliGraphResolver {
     self
   }
 
+  pub fn maybe_import_map(&self) -> Option<&Arc<ImportMap>> {
+    self.mapped_specifier_resolver.maybe_import_map()
+  }
+
   pub fn found_package_json_dep(&self) -> bool {
     self.found_package_json_dep_flag.is_raised()
   }
@@ -13,14 +13,14 @@ use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
+use std::time::Duration;
 
 use deno_ast::ModuleSpecifier;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
 use deno_core::futures::io::AllowStdIo;
-use deno_core::futures::AsyncReadExt;
-use deno_core::futures::AsyncSeekExt;
+use deno_core::futures::stream;
 use deno_core::serde_json;
 use deno_core::url::Url;
 use deno_npm::NpmSystemInfo;
@@ -43,6 +43,11 @@ use crate::file_fetcher::FileFetcher;
 use crate::http_util::HttpClient;
 use crate::npm::CliNpmResolver;
 use crate::npm::InnerCliNpmResolverRef;
+use crate::util::checksum;
+use crate::util::checksum::ChecksumAlgorithm;
+use crate::util::fs::write_file_from_stream;
+use crate::util::fs::LaxSingleProcessFsFlag;
+use crate::util::path::get_atomic_file_path;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::ProgressBarStyle;
 
@@ -50,8 +55,16 @@ use super::virtual_fs::FileBackedVfs;
 use super::virtual_fs::VfsBuilder;
 use super::virtual_fs::VfsRoot;
 use super::virtual_fs::VirtualDirectory;
+use super::windows_resources::set_windows_resources;
+use super::windows_resources::ExeMetadata;
 
 const MAGIC_TRAILER: &[u8; 8] = b"d3n0l4nd";
+/// The magic number at the start of a zstd frame, which tells a compressed
+/// eszip archive apart from an uncompressed one.
+const ZSTD_MAGIC: &[u8; 4] = &[0x28, 0xb5, 0x2f, 0xfd];
+/// Higher levels barely make the archives of large npm graphs smaller, but
+/// take much longer to compress.
+const ESZIP_COMPRESSION_LEVEL: i32 = 9;
 
 #[derive(Serialize, Deserialize)]
 enum SerializablePackageJsonDepValueParseError {
@@ -175,29 +188,22 @@ pub fn load_npm_vfs(root_dir_path: PathBuf) -> Result<FileBackedVfs, AnyError> {
   Ok(FileBackedVfs::new(file, fs_root))
 }
 
-fn write_binary_bytes(
-  writer: &mut impl Write,
+/// Writes the executable to the output path. The data blocks are written
+/// one after another instead of being concatenated in memory first.
+async fn write_binary_bytes(
+  output_path: &Path,
   original_bin: Vec<u8>,
   metadata: &Metadata,
   eszip: eszip::EszipV2,
   npm_vfs: Option<&VirtualDirectory>,
-  npm_files: &Vec<Vec<u8>>,
+  npm_files: Vec<Vec<u8>>,
 ) -> Result<(), AnyError> {
   let metadata = serde_json::to_string(metadata)?.as_bytes().to_vec();
   let npm_vfs = serde_json::to_string(&npm_vfs)?.as_bytes().to_vec();
-  let eszip_archive = eszip.into_bytes();
-
-  writer.write_all(&original_bin)?;
-  writer.write_all(&eszip_archive)?;
-  writer.write_all(&metadata)?;
-  writer.write_all(&npm_vfs)?;
-  for file in npm_files {
-    writer.write_all(file)?;
-  }
+  let eszip_archive = compress_eszip_archive(eszip.into_bytes())?;
 
-  // write the trailer, which includes the positions
-  // of the data blocks in the file
-  writer.write_all(&{
+  // the trailer includes the positions of the data blocks in the file
+  let trailer = {
     let eszip_pos = original_bin.len() as u64;
     let metadata_pos = eszip_pos + (eszip_archive.len() as u64);
     let npm_vfs_pos = metadata_pos + (metadata.len() as u64);
@@ -209,11 +215,93 @@ fn write_binary_bytes(
       npm_files_pos,
     }
     .as_bytes()
-  })?;
+  };
 
+  let chunks = [original_bin, eszip_archive, metadata, npm_vfs]
+    .into_iter()
+    .chain(npm_files)
+    .chain([trailer])
+    .map(Ok::<_, AnyError>);
+  write_file_from_stream(output_path, stream::iter(chunks), 0o777).await?;
   Ok(())
 }
 
+/// Verifies the downloaded archive of the runtime binary against the
+/// checksum published next to it. The archive is hashed as it's read from
+/// disk instead of being loaded into memory.
+async fn verify_base_binary_archive(
+  client: &HttpClient,
+  download_url: &str,
+  archive_path: &Path,
+) -> Result<(), AnyError> {
+  let expected_checksum = match client
+    .download_text(format!("{download_url}.sha256sum"))
+    .await
+  {
+    Ok(text) => match checksum::parse_checksum_file(&text) {
+      Some(checksum) => checksum,
+      None => bail!("Failed parsing the published checksum of {download_url}"),
+    },
+    Err(err) => {
+      // not every build publishes checksums for its runtime binaries
+      log::debug!("No checksum was published for {}: {:#}", download_url, err);
+      return Ok(());
+    }
+  };
+  let actual_checksum = checksum::gen_from_reader(
+    ChecksumAlgorithm::Sha256,
+    std::io::BufReader::new(std::fs::File::open(archive_path)?),
+  )?;
+  if actual_checksum != expected_checksum {
+    bail!(
+      concat!(
+        "The checksum of {} did not match the published checksum.\n",
+        "  Expected: {}\n",
+        "  Actual: {}",
+      ),
+      download_url,
+      expected_checksum,
+      actual_checksum,
+    );
+  }
+  Ok(())
+}
+
+/// Compresses the eszip archive with zstd, keeping it uncompressed when that
+/// doesn't make it smaller, e.g. for a tiny program.
+fn compress_eszip_archive(archive: Vec<u8>) -> Result<Vec<u8>, AnyError> {
+  let compressed = zstd::bulk::compress(&archive, ESZIP_COMPRESSION_LEVEL)
+    .context("Failed to compress eszip archive")?;
+  if compressed.len() < archive.len() {
+    Ok(compressed)
+  } else {
+    Ok(archive)
+  }
+}
+
+/// Gets a reader of the eszip archive that starts at the current position of
+/// the file, which decompresses the archive while it's read when it was
+/// compressed.
+fn eszip_archive_reader<'a>(
+  file: &'a mut std::fs::File,
+  trailer: &Trailer,
+) -> Result<Box<dyn Read + Send + 'a>, AnyError> {
+  let mut magic = [0; ZSTD_MAGIC.len()];
+  let is_compressed = trailer.eszip_len() >= magic.len() as u64 && {
+    file.read_exact(&mut magic)?;
+    file.seek(SeekFrom::Start(trailer.eszip_pos))?;
+    &magic == ZSTD_MAGIC
+  };
+  let archive = file.take(trailer.eszip_len());
+  if is_compressed {
+    let decoder = zstd::stream::read::Decoder::new(archive)
+      .context("Failed to decompress eszip archive")?;
+    Ok(Box::new(decoder))
+  } else {
+    Ok(Box::new(archive))
+  }
+}
+
 pub fn is_standalone_binary(exe_path: &Path) -> bool {
   let Ok(mut output_file) = std::fs::File::open(exe_path) else {
     return false;
@@ -261,26 +349,23 @@ pub fn extract_standalone(
   let cli_args = cli_args.into_owned();
   // If we have an eszip, read it out
   Ok(Some(async move {
-    let bufreader =
-      deno_core::futures::io::BufReader::new(AllowStdIo::new(file));
+    let bufreader = deno_core::futures::io::BufReader::new(AllowStdIo::new(
+      eszip_archive_reader(&mut file, &trailer)?,
+    ));
 
     let (eszip, loader) = eszip::EszipV2::parse(bufreader)
       .await
       .context("Failed to parse eszip header")?;
 
-    let mut bufreader =
-      loader.await.context("Failed to parse eszip archive")?;
+    drop(loader.await.context("Failed to parse eszip archive")?);
 
-    bufreader
-      .seek(SeekFrom::Start(trailer.metadata_pos))
-      .await?;
+    file.seek(SeekFrom::Start(trailer.metadata_pos))?;
 
     let mut metadata = String::new();
 
-    bufreader
+    (&mut file)
       .take(trailer.metadata_len())
       .read_to_string(&mut metadata)
-      .await
       .context("Failed to read metadata from the current executable")?;
 
     let mut metadata: Metadata = serde_json::from_str(&metadata).unwrap();
@@ -324,6 +409,10 @@ impl Trailer {
     }))
   }
 
+  pub fn eszip_len(&self) -> u64 {
+    self.metadata_pos - self.eszip_pos
+  }
+
   pub fn metadata_len(&self) -> u64 {
     self.npm_vfs_pos - self.metadata_pos
   }
@@ -351,6 +440,43 @@ fn u64_from_bytes(arr: &[u8]) -> Result<u64, AnyError> {
   Ok(u64::from_be_bytes(*fixed_arr))
 }
 
+/// Held while downloading into the download directory of `deno compile`.
+fn download_lock_path(download_directory: &Path) -> PathBuf {
+  download_directory.join(".download.lock")
+}
+
+/// Removes the runtime binaries of the target that were downloaded for
+/// other canary versions, since every canary commit downloads its own.
+async fn remove_stale_canary_binaries(
+  download_directory: &Path,
+  binary_name: &str,
+) {
+  // skip it while another process is downloading, because the binary it's
+  // downloading would look stale
+  let Some(_download_lock) =
+    LaxSingleProcessFsFlag::try_lock(download_lock_path(download_directory))
+      .await
+  else {
+    return;
+  };
+  let canary_dir = download_directory.join("canary");
+  let Ok(entries) = std::fs::read_dir(canary_dir) else {
+    return;
+  };
+  for entry in entries.flatten() {
+    if entry.file_name() == crate::version::GIT_COMMIT_HASH {
+      continue;
+    }
+    let stale_path = entry.path().join(binary_name);
+    if stale_path.exists() {
+      log::debug!("Removing stale {}", stale_path.display());
+      let _ = std::fs::remove_file(&stale_path);
+      // only removes the directory when no other target is left in it
+      let _ = std::fs::remove_dir(entry.path());
+    }
+  }
+}
+
 pub fn unpack_into_dir(
   exe_name: &str,
   archive_name: &str,
@@ -447,7 +573,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
 
   pub async fn write_bin(
     &self,
-    writer: &mut impl Write,
+    output_path: &Path,
     eszip: eszip::EszipV2,
     module_specifier: &ModuleSpecifier,
     compile_flags: &CompileFlags,
@@ -467,9 +593,38 @@ impl<'a> DenoCompileBinaryWriter<'a> {
       set_windows_binary_to_gui(&mut original_binary)?;
     }
 
+    if compile_flags.icon.is_some() || compile_flags.exe_metadata.is_some() {
+      let target = compile_flags.resolve_target();
+      if !target.contains("windows") {
+        bail!(
+          "The `--icon` and `--exe-metadata` flags are only available when targeting Windows (current: {})",
+          target,
+        )
+      }
+      let initial_cwd = cli_options.initial_cwd();
+      let icon = compile_flags
+        .icon
+        .as_ref()
+        .map(|path| {
+          std::fs::read(initial_cwd.join(path))
+            .with_context(|| format!("Failed reading icon '{}'.", path))
+        })
+        .transpose()?;
+      let metadata = compile_flags
+        .exe_metadata
+        .as_ref()
+        .map(|path| read_exe_metadata(&initial_cwd.join(path)))
+        .transpose()?;
+      original_binary = set_windows_resources(
+        &original_binary,
+        icon.as_deref(),
+        metadata.as_ref(),
+      )?;
+    }
+
     self
       .write_standalone_binary(
-        writer,
+        output_path,
         original_binary,
         eszip,
         module_specifier,
@@ -505,21 +660,47 @@ impl<'a> DenoCompileBinaryWriter<'a> {
     let download_directory = self.deno_dir.dl_folder_path();
     let binary_path = download_directory.join(&binary_path_suffix);
 
-    if !binary_path.exists() {
+    let is_cached = binary_path.exists();
+    if !is_cached {
       self
         .download_base_binary(&download_directory, &binary_path_suffix)
         .await?;
+      if crate::version::is_canary() {
+        remove_stale_canary_binaries(&download_directory, &binary_name).await;
+      }
     }
 
-    let archive_data = std::fs::read(binary_path)?;
-    let temp_dir = tempfile::TempDir::new()?;
-    let base_binary_path = unpack_into_dir(
-      "denort",
-      &binary_name,
-      archive_data,
-      target.contains("windows"),
-      &temp_dir,
-    )?;
+    let mut temp_dir = tempfile::TempDir::new()?;
+    let unpack = |temp_dir: &tempfile::TempDir| {
+      let archive_data = std::fs::read(&binary_path)?;
+      unpack_into_dir(
+        "denort",
+        &binary_name,
+        archive_data,
+        target.contains("windows"),
+        temp_dir,
+      )
+    };
+    let base_binary_path = match unpack(&temp_dir) {
+      Ok(path) => path,
+      Err(err) if is_cached => {
+        // the cached archive is corrupted, such as by a full disk, so it's
+        // downloaded again
+        log::debug!(
+          "Failed unpacking cached {}, downloading it again: {:#}",
+          binary_path.display(),
+          err
+        );
+        std::fs::remove_file(&binary_path)?;
+        self
+          .download_base_binary(&download_directory, &binary_path_suffix)
+          .await?;
+        // start over in an empty directory
+        temp_dir = tempfile::TempDir::new()?;
+        unpack(&temp_dir)?
+      }
+      Err(err) => return Err(err),
+    };
     let base_binary = std::fs::read(base_binary_path)?;
     drop(temp_dir); // delete the temp dir
     Ok(base_binary)
@@ -531,27 +712,55 @@ impl<'a> DenoCompileBinaryWriter<'a> {
     binary_path_suffix: &str,
   ) -> Result<(), AnyError> {
     let download_url = format!("https://dl.deno.land/{binary_path_suffix}");
-    let maybe_bytes = {
+    std::fs::create_dir_all(output_directory)?;
+    let output_path = output_directory.join(binary_path_suffix);
+    std::fs::create_dir_all(output_path.parent().unwrap())?;
+    // wait for another process that's downloading, but not indefinitely
+    let _download_lock = LaxSingleProcessFsFlag::lock_with_timeout(
+      download_lock_path(output_directory),
+      "waiting for file lock on the deno compile download directory",
+      Duration::from_secs(5 * 60),
+    )
+    .await?;
+    if output_path.exists() {
+      // downloaded by the process that held the lock
+      return Ok(());
+    }
+    // download to a temporary file so an interrupted download isn't
+    // mistaken for a cached binary
+    let temp_path = get_atomic_file_path(&output_path);
+    let found = {
       let progress_bars = ProgressBar::new(ProgressBarStyle::DownloadBars);
       let progress = progress_bars.update(&download_url);
 
       self
         .client
-        .download_with_progress(download_url, &progress)
-        .await?
+        .download_to_file_with_progress(
+          download_url.as_str(),
+          &temp_path,
+          &progress,
+        )
+        .await
     };
-    let bytes = match maybe_bytes {
-      Some(bytes) => bytes,
-      None => {
+    match found {
+      Ok(true) => {}
+      Ok(false) => {
+        let _ = std::fs::remove_file(&temp_path);
         log::info!("Download could not be found, aborting");
         std::process::exit(1)
       }
-    };
-
-    std::fs::create_dir_all(output_directory)?;
-    let output_path = output_directory.join(binary_path_suffix);
-    std::fs::create_dir_all(output_path.parent().unwrap())?;
-    tokio::fs::write(output_path, bytes).await?;
+      Err(err) => {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(err);
+      }
+    }
+    if let Err(err) =
+      verify_base_binary_archive(self.client, &download_url, &temp_path).await
+    {
+      let _ = std::fs::remove_file(&temp_path);
+      return Err(err);
+    }
+    std::fs::rename(&temp_path, &output_path)?;
     Ok(())
   }
 
@@ -559,7 +768,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
   /// and magic trailer to the currently executing binary.
   async fn write_standalone_binary(
     &self,
-    writer: &mut impl Write,
+    output_path: &Path,
     original_bin: Vec<u8>,
     mut eszip: eszip::EszipV2,
     entrypoint: &ModuleSpecifier,
@@ -644,13 +853,14 @@ impl<'a> DenoCompileBinaryWriter<'a> {
     };
 
     write_binary_bytes(
-      writer,
+      output_path,
       original_bin,
       &metadata,
       eszip,
       npm_vfs.as_ref(),
-      &npm_files,
+      npm_files,
     )
+    .await
   }
 
   fn build_vfs(&self) -> Result<VfsBuilder, AnyError> {
@@ -704,6 +914,15 @@ impl<'a> DenoCompileBinaryWriter<'a> {
   }
 }
 
+fn read_exe_metadata(path: &Path) -> Result<ExeMetadata, AnyError> {
+  let text = std::fs::read_to_string(path).with_context(|| {
+    format!("Failed reading executable metadata '{}'.", path.display())
+  })?;
+  serde_json::from_str(&text).with_context(|| {
+    format!("Failed parsing executable metadata '{}'.", path.display())
+  })
+}
+
 /// This function sets the subsystem field in the PE header to 2 (GUI subsystem)
 /// For more information about the PE header: https://learn.microsoft.com/en-us/windows/win32/debug/pe-format
 fn set_windows_binary_to_gui(bin: &mut [u8]) -> Result<(), AnyError> {
@@ -61,6 +61,7 @@ use std::sync::Arc;
 pub mod binary;
 mod file_system;
 mod virtual_fs;
+mod windows_resources;
 
 pub use binary::extract_standalone;
 pub use binary::is_standalone_binary;
@@ -182,7 +182,11 @@ impl VfsBuilder {
 
   fn add_file(&mut self, path: &Path, data: Vec<u8>) -> Result<(), AnyError> {
     log::debug!("Adding file '{}'", path.display());
-    let checksum = util::checksum::gen(&[&data]);
+    // only used to find duplicate files while building
+    let checksum = util::checksum::gen_with_algorithm(
+      util::checksum::ChecksumAlgorithm::Blake3,
+      &[&data],
+    );
     let offset = if let Some(offset) = self.file_offsets.get(&checksum) {
       // duplicate file, reuse an old offset
       *offset
@@ -0,0 +1,1050 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Sets the icon and the version information of a Windows executable for
+//! `deno compile --icon` and `--exe-metadata`. The resources of the base
+//! binary are read, changed and written to a new resource section that is
+//! appended to the executable.
+//!
+//! For more information about the resource section: https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#the-rsrc-section
+
+use std::collections::BTreeMap;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use serde::Deserialize;
+
+use crate::colors;
+
+const RT_ICON: u16 = 3;
+const RT_GROUP_ICON: u16 = 14;
+const RT_VERSION: u16 = 16;
+/// English (United States), used when the base binary has no resource of the
+/// type yet.
+const DEFAULT_LANGUAGE: u16 = 0x0409;
+/// The code page of the version strings, which is UTF-16.
+const VERSION_CODE_PAGE: u16 = 1200;
+
+const RESOURCE_DIRECTORY_INDEX: usize = 2;
+const SECURITY_DIRECTORY_INDEX: usize = 4;
+const SECTION_HEADER_SIZE: usize = 40;
+/// IMAGE_SCN_CNT_INITIALIZED_DATA | IMAGE_SCN_MEM_READ
+const RESOURCE_SECTION_CHARACTERISTICS: u32 = 0x4000_0040;
+/// Marks a name string or a subdirectory in a resource directory entry.
+const HIGH_BIT: u32 = 0x8000_0000;
+
+/// The version information of `--exe-metadata`, which is read from a JSON
+/// file.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ExeMetadata {
+  pub product_name: Option<String>,
+  pub product_version: Option<String>,
+  pub file_description: Option<String>,
+  pub file_version: Option<String>,
+  pub company_name: Option<String>,
+  pub legal_copyright: Option<String>,
+  pub original_filename: Option<String>,
+  pub internal_name: Option<String>,
+  pub comments: Option<String>,
+}
+
+/// Returns the executable with the icon of the `.ico` file and the version
+/// information replaced.
+pub fn set_windows_resources(
+  bin: &[u8],
+  icon: Option<&[u8]>,
+  metadata: Option<&ExeMetadata>,
+) -> Result<Vec<u8>, AnyError> {
+  let headers = PeHeaders::parse(bin)?;
+  let mut resources = read_resources(bin, &headers)?;
+  if let Some(icon) = icon {
+    set_icon(&mut resources, icon)?;
+  }
+  if let Some(metadata) = metadata {
+    set_version_info(&mut resources, metadata)?;
+  }
+  append_resource_section(bin, &headers, &resources)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum ResourceName {
+  // the entries with a name come before the ones with an id
+  Name(Vec<u16>),
+  Id(u16),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ResourceData {
+  data: Vec<u8>,
+  code_page: u32,
+}
+
+type Languages = BTreeMap<ResourceName, ResourceData>;
+/// The resources by type, name and language.
+type Resources = BTreeMap<ResourceName, BTreeMap<ResourceName, Languages>>;
+
+struct Section {
+  virtual_size: u32,
+  virtual_address: u32,
+  raw_size: u32,
+  raw_offset: u32,
+}
+
+struct PeHeaders {
+  coff_offset: usize,
+  optional_offset: usize,
+  data_directories_offset: usize,
+  data_directories_count: usize,
+  section_table_offset: usize,
+  section_alignment: u32,
+  file_alignment: u32,
+  size_of_headers: u32,
+  sections: Vec<Section>,
+}
+
+impl PeHeaders {
+  fn parse(bin: &[u8]) -> Result<Self, AnyError> {
+    if bin.get(0..2) != Some(b"MZ") {
+      bail!("The base binary is not a Windows executable");
+    }
+    let pe_offset = read_u32(bin, 60)? as usize;
+    if bin.get(pe_offset..pe_offset + 4) != Some(b"PE\0\0") {
+      bail!("The base binary is not a Windows executable");
+    }
+    let coff_offset = pe_offset + 4;
+    let section_count = read_u16(bin, coff_offset + 2)? as usize;
+    let optional_header_size = read_u16(bin, coff_offset + 16)? as usize;
+    let optional_offset = coff_offset + 20;
+    // PE32 or PE32+, which have different offsets for the data directories
+    let (data_directories_offset, data_directories_count) =
+      match read_u16(bin, optional_offset)? {
+        0x10b => (optional_offset + 96, read_u32(bin, optional_offset + 92)?),
+        0x20b => (optional_offset + 112, read_u32(bin, optional_offset + 108)?),
+        _ => bail!("Could not find a matching magic field in the PE header"),
+      };
+    let section_table_offset = optional_offset + optional_header_size;
+    let sections = (0..section_count)
+      .map(|i| {
+        let offset = section_table_offset + i * SECTION_HEADER_SIZE;
+        Ok(Section {
+          virtual_size: read_u32(bin, offset + 8)?,
+          virtual_address: read_u32(bin, offset + 12)?,
+          raw_size: read_u32(bin, offset + 16)?,
+          raw_offset: read_u32(bin, offset + 20)?,
+        })
+      })
+      .collect::<Result<Vec<_>, AnyError>>()?;
+    Ok(Self {
+      coff_offset,
+      optional_offset,
+      data_directories_offset,
+      data_directories_count: data_directories_count as usize,
+      section_table_offset,
+      section_alignment: read_u32(bin, optional_offset + 32)?,
+      file_alignment: read_u32(bin, optional_offset + 36)?,
+      size_of_headers: read_u32(bin, optional_offset + 60)?,
+      sections,
+    })
+  }
+
+  /// Gets the address and the size of a data directory.
+  fn data_directory(
+    &self,
+    bin: &[u8],
+    index: usize,
+  ) -> Result<(u32, u32), AnyError> {
+    if index >= self.data_directories_count {
+      return Ok((0, 0));
+    }
+    let offset = self.data_directories_offset + index * 8;
+    Ok((read_u32(bin, offset)?, read_u32(bin, offset + 4)?))
+  }
+
+  fn rva_to_offset(&self, rva: u32) -> Option<usize> {
+    self.sections.iter().find_map(|section| {
+      let offset = rva.checked_sub(section.virtual_address)?;
+      (offset < section.raw_size)
+        .then(|| section.raw_offset as usize + offset as usize)
+    })
+  }
+}
+
+fn read_resources(
+  bin: &[u8],
+  headers: &PeHeaders,
+) -> Result<Resources, AnyError> {
+  let mut resources = Resources::new();
+  let (rva, size) = headers.data_directory(bin, RESOURCE_DIRECTORY_INDEX)?;
+  if rva == 0 || size == 0 {
+    return Ok(resources);
+  }
+  let base = headers.rva_to_offset(rva).context(
+    "The resource directory is not in any section of the base binary",
+  )?;
+  for (type_name, type_entry) in read_directory(bin, base, 0)? {
+    let names = resources.entry(type_name).or_default();
+    for (name, name_entry) in
+      read_directory(bin, base, subdirectory_offset(type_entry)?)?
+    {
+      let languages = names.entry(name).or_default();
+      for (language, entry) in
+        read_directory(bin, base, subdirectory_offset(name_entry)?)?
+      {
+        if entry & HIGH_BIT != 0 {
+          bail!("Unexpected resource directory in the base binary");
+        }
+        let entry = base + entry as usize;
+        let data_rva = read_u32(bin, entry)?;
+        let data_size = read_u32(bin, entry + 4)? as usize;
+        let data = headers
+          .rva_to_offset(data_rva)
+          .and_then(|offset| bin.get(offset..offset + data_size))
+          .context("A resource is not in any section of the base binary")?;
+        languages.insert(
+          language,
+          ResourceData {
+            data: data.to_vec(),
+            code_page: read_u32(bin, entry + 8)?,
+          },
+        );
+      }
+    }
+  }
+  Ok(resources)
+}
+
+/// Reads the names of the entries of a resource directory together with the
+/// offsets of their subdirectories or data entries.
+fn read_directory(
+  bin: &[u8],
+  base: usize,
+  offset: usize,
+) -> Result<Vec<(ResourceName, u32)>, AnyError> {
+  let directory = base + offset;
+  let count = read_u16(bin, directory + 12)? as usize
+    + read_u16(bin, directory + 14)? as usize;
+  (0..count)
+    .map(|i| {
+      let entry = directory + 16 + i * 8;
+      let name = read_u32(bin, entry)?;
+      let name = if name & HIGH_BIT != 0 {
+        let string = base + (name & !HIGH_BIT) as usize;
+        let len = read_u16(bin, string)? as usize;
+        ResourceName::Name(
+          (0..len)
+            .map(|i| read_u16(bin, string + 2 + i * 2))
+            .collect::<Result<_, _>>()?,
+        )
+      } else {
+        ResourceName::Id(name as u16)
+      };
+      Ok((name, read_u32(bin, entry + 4)?))
+    })
+    .collect()
+}
+
+fn subdirectory_offset(entry: u32) -> Result<usize, AnyError> {
+  if entry & HIGH_BIT == 0 {
+    bail!("Unexpected resource data entry in the base binary");
+  }
+  Ok((entry & !HIGH_BIT) as usize)
+}
+
+/// Gets the language of the first resource of the type, so that a replaced
+/// resource keeps its language.
+fn first_language(resources: &Resources, resource_type: u16) -> u16 {
+  resources
+    .get(&ResourceName::Id(resource_type))
+    .and_then(|names| names.values().next())
+    .and_then(|languages| languages.keys().next())
+    .and_then(|language| match language {
+      ResourceName::Id(id) => Some(*id),
+      ResourceName::Name(_) => None,
+    })
+    .unwrap_or(DEFAULT_LANGUAGE)
+}
+
+fn single_resource(language: u16, data: Vec<u8>) -> Languages {
+  BTreeMap::from([(
+    ResourceName::Id(language),
+    ResourceData { data, code_page: 0 },
+  )])
+}
+
+/// Replaces the icons with the images of an `.ico` file, which are stored
+/// as separate icon resources referenced by a group icon resource.
+fn set_icon(resources: &mut Resources, icon: &[u8]) -> Result<(), AnyError> {
+  if read_u16(icon, 0)? != 0 || read_u16(icon, 2)? != 1 {
+    bail!("The icon is not an .ico file");
+  }
+  let count = read_u16(icon, 4)? as usize;
+  if count == 0 {
+    bail!("The icon file contains no images");
+  }
+  let language = first_language(resources, RT_GROUP_ICON);
+  let mut images = BTreeMap::new();
+  // the group icon has the header of the .ico file with the offsets of the
+  // images replaced by the ids of their resources
+  let mut group = icon[0..6].to_vec();
+  for i in 0..count {
+    let entry = 6 + i * 16;
+    let size = read_u32(icon, entry + 8)? as usize;
+    let offset = read_u32(icon, entry + 12)? as usize;
+    let image = icon
+      .get(offset..offset + size)
+      .context("The icon file is truncated")?;
+    let id = (i + 1) as u16;
+    images.insert(
+      ResourceName::Id(id),
+      single_resource(language, image.to_vec()),
+    );
+    group.extend_from_slice(&icon[entry..entry + 12]);
+    group.extend_from_slice(&id.to_le_bytes());
+  }
+  resources.insert(ResourceName::Id(RT_ICON), images);
+  resources.insert(
+    ResourceName::Id(RT_GROUP_ICON),
+    BTreeMap::from([(ResourceName::Id(1), single_resource(language, group))]),
+  );
+  Ok(())
+}
+
+fn set_version_info(
+  resources: &mut Resources,
+  metadata: &ExeMetadata,
+) -> Result<(), AnyError> {
+  let language = first_language(resources, RT_VERSION);
+  let version_info = build_version_info(metadata, language)?;
+  resources.insert(
+    ResourceName::Id(RT_VERSION),
+    BTreeMap::from([(
+      ResourceName::Id(1),
+      single_resource(language, version_info),
+    )]),
+  );
+  Ok(())
+}
+
+/// Parses a version like `1.2.3` into the four numbers of a Windows version.
+/// A pre-release or build suffix is ignored.
+fn parse_version(version: &str) -> Result<[u16; 4], AnyError> {
+  let invalid = || {
+    format!(
+      "Invalid version '{version}' in the executable metadata. Expected up to four numbers separated by dots."
+    )
+  };
+  let numbers = version.split(['-', '+']).next().unwrap_or_default();
+  let mut parts = [0; 4];
+  for (i, number) in numbers.split('.').enumerate() {
+    if i >= parts.len() {
+      bail!(invalid());
+    }
+    parts[i] = number.parse().with_context(invalid)?;
+  }
+  Ok(parts)
+}
+
+enum VersionValue<'a> {
+  None,
+  Binary(Vec<u8>),
+  Text(&'a str),
+}
+
+/// Builds the VS_VERSIONINFO structure of the version resource.
+///
+/// See https://learn.microsoft.com/en-us/windows/win32/menurc/vs-versioninfo
+fn build_version_info(
+  metadata: &ExeMetadata,
+  language: u16,
+) -> Result<Vec<u8>, AnyError> {
+  let file_version = metadata
+    .file_version
+    .as_deref()
+    .or(metadata.product_version.as_deref());
+  let product_version = metadata
+    .product_version
+    .as_deref()
+    .or(metadata.file_version.as_deref());
+  let [file_major, file_minor, file_patch, file_build] = file_version
+    .map(parse_version)
+    .transpose()?
+    .unwrap_or_default();
+  let [product_major, product_minor, product_patch, product_build] =
+    product_version
+      .map(parse_version)
+      .transpose()?
+      .unwrap_or_default();
+
+  // VS_FIXEDFILEINFO
+  let fixed_file_info = [
+    0xfeef04bd,
+    0x00010000,
+    (file_major as u32) << 16 | file_minor as u32,
+    (file_patch as u32) << 16 | file_build as u32,
+    (product_major as u32) << 16 | product_minor as u32,
+    (product_patch as u32) << 16 | product_build as u32,
+    // VS_FFI_FILEFLAGSMASK
+    0x3f,
+    0,
+    // VOS_NT_WINDOWS32
+    0x40004,
+    // VFT_APP
+    1,
+    0,
+    0,
+    0,
+  ]
+  .iter()
+  .flat_map(|value: &u32| value.to_le_bytes())
+  .collect::<Vec<_>>();
+
+  let strings = [
+    ("Comments", metadata.comments.as_deref()),
+    ("CompanyName", metadata.company_name.as_deref()),
+    ("FileDescription", metadata.file_description.as_deref()),
+    ("FileVersion", file_version),
+    ("InternalName", metadata.internal_name.as_deref()),
+    ("LegalCopyright", metadata.legal_copyright.as_deref()),
+    ("OriginalFilename", metadata.original_filename.as_deref()),
+    ("ProductName", metadata.product_name.as_deref()),
+    ("ProductVersion", product_version),
+  ]
+  .into_iter()
+  .filter_map(|(key, value)| {
+    Some(version_node(key, VersionValue::Text(value?), &[]))
+  })
+  .collect::<Result<Vec<_>, _>>()?;
+  let string_table = version_node(
+    &format!("{language:04x}{VERSION_CODE_PAGE:04x}"),
+    VersionValue::None,
+    &strings,
+  )?;
+  let string_file_info =
+    version_node("StringFileInfo", VersionValue::None, &[string_table])?;
+  let translation = version_node(
+    "Translation",
+    VersionValue::Binary(
+      [language.to_le_bytes(), VERSION_CODE_PAGE.to_le_bytes()].concat(),
+    ),
+    &[],
+  )?;
+  let var_file_info =
+    version_node("VarFileInfo", VersionValue::None, &[translation])?;
+  version_node(
+    "VS_VERSION_INFO",
+    VersionValue::Binary(fixed_file_info),
+    &[string_file_info, var_file_info],
+  )
+}
+
+/// Builds a structure of the version information, which all start with their
+/// length, the length of their value, their type and their key, followed by
+/// their value and children aligned to 32 bits.
+fn version_node(
+  key: &str,
+  value: VersionValue,
+  children: &[Vec<u8>],
+) -> Result<Vec<u8>, AnyError> {
+  let mut node = vec![0; 6];
+  push_utf16(&mut node, key);
+  pad_to_u32(&mut node);
+  let (value_len, value_type) = match value {
+    VersionValue::None => (0, 1),
+    VersionValue::Binary(bytes) => {
+      node.extend_from_slice(&bytes);
+      (bytes.len(), 0)
+    }
+    VersionValue::Text(text) => {
+      let start = node.len();
+      push_utf16(&mut node, text);
+      // the length of text is in words
+      ((node.len() - start) / 2, 1)
+    }
+  };
+  for child in children {
+    pad_to_u32(&mut node);
+    node.extend_from_slice(child);
+  }
+  // the lengths are 16 bits, which limits the size of the metadata
+  let (Ok(len), Ok(value_len)) =
+    (u16::try_from(node.len()), u16::try_from(value_len))
+  else {
+    bail!(
+      "The executable metadata is too long to be stored as version information"
+    );
+  };
+  write_u16(&mut node, 0, len);
+  write_u16(&mut node, 2, value_len);
+  write_u16(&mut node, 4, value_type);
+  Ok(node)
+}
+
+/// Pushes a null terminated UTF-16 string.
+fn push_utf16(buf: &mut Vec<u8>, text: &str) {
+  for unit in text.encode_utf16().chain([0]) {
+    buf.extend_from_slice(&unit.to_le_bytes());
+  }
+}
+
+fn pad_to_u32(buf: &mut Vec<u8>) {
+  buf.resize(align(buf.len(), 4), 0);
+}
+
+fn directory_size(entries: usize) -> usize {
+  16 + entries * 8
+}
+
+/// Builds the resource section at the relative virtual address, laid out as
+/// the directories breadth first, the data entries, the name strings and the
+/// data of the resources.
+fn build_resource_section(resources: &Resources, section_rva: u32) -> Vec<u8> {
+  let all_languages = || resources.values().flat_map(|names| names.values());
+  let all_data = || all_languages().flat_map(|languages| languages.values());
+
+  let mut offset = directory_size(resources.len());
+  let mut type_directories = Vec::with_capacity(resources.len());
+  for names in resources.values() {
+    type_directories.push(offset);
+    offset += directory_size(names.len());
+  }
+  let mut name_directories = Vec::new();
+  for languages in all_languages() {
+    name_directories.push(offset);
+    offset += directory_size(languages.len());
+  }
+  let data_entries_offset = offset;
+  offset += all_data().count() * 16;
+  let mut strings = BTreeMap::new();
+  let all_names = resources
+    .iter()
+    .flat_map(|(type_name, names)| {
+      [type_name].into_iter().chain(names.iter().flat_map(
+        |(name, languages)| [name].into_iter().chain(languages.keys()),
+      ))
+    })
+    .filter_map(|name| match name {
+      ResourceName::Name(name) => Some(name),
+      ResourceName::Id(_) => None,
+    });
+  for name in all_names {
+    if !strings.contains_key(name) {
+      strings.insert(name, offset);
+      offset += 2 + name.len() * 2;
+    }
+  }
+  offset = align(offset, 8);
+  let mut data_offsets = Vec::new();
+  for resource in all_data() {
+    data_offsets.push(offset);
+    offset = align(offset + resource.data.len(), 8);
+  }
+
+  let mut section = vec![0; offset];
+  write_directory(
+    &mut section,
+    0,
+    &strings,
+    resources.keys().zip(
+      type_directories
+        .iter()
+        .map(|offset| HIGH_BIT | *offset as u32),
+    ),
+  );
+  let mut name_directories_iter = name_directories.iter();
+  for (names, type_directory) in resources.values().zip(&type_directories) {
+    write_directory(
+      &mut section,
+      *type_directory,
+      &strings,
+      names.keys().zip(
+        name_directories_iter
+          .by_ref()
+          .map(|offset| HIGH_BIT | *offset as u32),
+      ),
+    );
+  }
+  let mut data_entries = (0..).map(|i| (data_entries_offset + i * 16) as u32);
+  for (languages, name_directory) in all_languages().zip(&name_directories) {
+    write_directory(
+      &mut section,
+      *name_directory,
+      &strings,
+      languages.keys().zip(data_entries.by_ref()),
+    );
+  }
+  for (i, (resource, data_offset)) in all_data().zip(&data_offsets).enumerate()
+  {
+    let entry = data_entries_offset + i * 16;
+    write_u32(&mut section, entry, section_rva + *data_offset as u32);
+    write_u32(&mut section, entry + 4, resource.data.len() as u32);
+    write_u32(&mut section, entry + 8, resource.code_page);
+    section[*data_offset..*data_offset + resource.data.len()]
+      .copy_from_slice(&resource.data);
+  }
+  for (name, offset) in &strings {
+    write_u16(&mut section, *offset, name.len() as u16);
+    for (i, unit) in name.iter().enumerate() {
+      write_u16(&mut section, offset + 2 + i * 2, *unit);
+    }
+  }
+  section
+}
+
+fn write_directory<'a>(
+  section: &mut [u8],
+  offset: usize,
+  strings: &BTreeMap<&Vec<u16>, usize>,
+  entries: impl Iterator<Item = (&'a ResourceName, u32)>,
+) {
+  let entries = entries.collect::<Vec<_>>();
+  let named = entries
+    .iter()
+    .filter(|(name, _)| matches!(name, ResourceName::Name(_)))
+    .count();
+  write_u16(section, offset + 12, named as u16);
+  write_u16(section, offset + 14, (entries.len() - named) as u16);
+  for (i, (name, target)) in entries.into_iter().enumerate() {
+    let entry = offset + 16 + i * 8;
+    let name = match name {
+      ResourceName::Name(name) => HIGH_BIT | strings[name] as u32,
+      ResourceName::Id(id) => *id as u32,
+    };
+    write_u32(section, entry, name);
+    write_u32(section, entry + 4, target);
+  }
+}
+
+/// Appends the resources as a new section and points the resource directory
+/// to it. The previous resource section is left unused, since other sections
+/// usually follow it.
+fn append_resource_section(
+  bin: &[u8],
+  headers: &PeHeaders,
+  resources: &Resources,
+) -> Result<Vec<u8>, AnyError> {
+  let header_offset =
+    headers.section_table_offset + headers.sections.len() * SECTION_HEADER_SIZE;
+  let first_section_offset = headers
+    .sections
+    .iter()
+    .map(|section| section.raw_offset)
+    .filter(|offset| *offset > 0)
+    .min()
+    .unwrap_or(headers.size_of_headers)
+    .min(headers.size_of_headers) as usize;
+  let has_room = header_offset + SECTION_HEADER_SIZE <= first_section_offset
+    && bin[header_offset..header_offset + SECTION_HEADER_SIZE]
+      .iter()
+      .all(|byte| *byte == 0);
+  if !has_room {
+    bail!("The base binary has no room for another section header");
+  }
+
+  // the signature would be invalid after the change, so it's removed
+  let (signature_offset, signature_size) =
+    headers.data_directory(bin, SECURITY_DIRECTORY_INDEX)?;
+  let mut end = bin.len();
+  if signature_size > 0 {
+    log::warn!(
+      "{} The signature of the base binary was removed since it's invalid after setting the icon or the metadata. Sign the executable again if needed.",
+      colors::yellow("Warning"),
+    );
+    if signature_offset as usize + signature_size as usize >= bin.len() {
+      end = signature_offset as usize;
+    }
+  }
+
+  let virtual_address = align(
+    headers
+      .sections
+      .iter()
+      .map(|section| {
+        section.virtual_address as usize
+          + section.virtual_size.max(section.raw_size) as usize
+      })
+      .max()
+      .unwrap_or(headers.size_of_headers as usize),
+    headers.section_alignment as usize,
+  );
+  let section = build_resource_section(resources, virtual_address as u32);
+  let raw_offset = align(end, headers.file_alignment as usize);
+  let raw_size = align(section.len(), headers.file_alignment as usize);
+
+  let mut output = Vec::with_capacity(raw_offset + raw_size);
+  output.extend_from_slice(&bin[..end]);
+  output.resize(raw_offset, 0);
+  output.extend_from_slice(&section);
+  output.resize(raw_offset + raw_size, 0);
+
+  output[header_offset..header_offset + 8].copy_from_slice(b".rsrc\0\0\0");
+  write_u32(&mut output, header_offset + 8, section.len() as u32);
+  write_u32(&mut output, header_offset + 12, virtual_address as u32);
+  write_u32(&mut output, header_offset + 16, raw_size as u32);
+  write_u32(&mut output, header_offset + 20, raw_offset as u32);
+  write_u32(
+    &mut output,
+    header_offset + 36,
+    RESOURCE_SECTION_CHARACTERISTICS,
+  );
+  write_u16(
+    &mut output,
+    headers.coff_offset + 2,
+    (headers.sections.len() + 1) as u16,
+  );
+  // SizeOfInitializedData
+  let initialized_data_size = read_u32(&output, headers.optional_offset + 8)?;
+  write_u32(
+    &mut output,
+    headers.optional_offset + 8,
+    initialized_data_size + raw_size as u32,
+  );
+  // SizeOfImage
+  write_u32(
+    &mut output,
+    headers.optional_offset + 56,
+    align(
+      virtual_address + section.len(),
+      headers.section_alignment as usize,
+    ) as u32,
+  );
+  let resource_directory =
+    headers.data_directories_offset + RESOURCE_DIRECTORY_INDEX * 8;
+  write_u32(&mut output, resource_directory, virtual_address as u32);
+  write_u32(&mut output, resource_directory + 4, section.len() as u32);
+  if signature_size > 0 {
+    let security_directory =
+      headers.data_directories_offset + SECURITY_DIRECTORY_INDEX * 8;
+    write_u32(&mut output, security_directory, 0);
+    write_u32(&mut output, security_directory + 4, 0);
+  }
+  Ok(output)
+}
+
+fn align(value: usize, alignment: usize) -> usize {
+  if alignment == 0 {
+    return value;
+  }
+  value.div_ceil(alignment) * alignment
+}
+
+fn read_u16(bin: &[u8], offset: usize) -> Result<u16, AnyError> {
+  let bytes = bin
+    .get(offset..offset + 2)
+    .context("Unexpected end of the file")?;
+  Ok(u16::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u32(bin: &[u8], offset: usize) -> Result<u32, AnyError> {
+  let bytes = bin
+    .get(offset..offset + 4)
+    .context("Unexpected end of the file")?;
+  Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn write_u16(buf: &mut [u8], offset: usize, value: u16) {
+  buf[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+}
+
+fn write_u32(buf: &mut [u8], offset: usize, value: u32) {
+  buf[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn parses_versions() {
+    assert_eq!(parse_version("1.2.3").unwrap(), [1, 2, 3, 0]);
+    assert_eq!(parse_version("1.2.3.4").unwrap(), [1, 2, 3, 4]);
+    assert_eq!(parse_version("2.0.0-beta.1").unwrap(), [2, 0, 0, 0]);
+    assert!(parse_version("1.2.3.4.5").is_err());
+    assert!(parse_version("one").is_err());
+  }
+
+  #[test]
+  fn builds_and_reads_resource_section() {
+    let mut resources = Resources::new();
+    resources.insert(
+      ResourceName::Id(RT_VERSION),
+      BTreeMap::from([(ResourceName::Id(1), single_resource(1033, vec![1]))]),
+    );
+    resources.insert(
+      ResourceName::Name("CUSTOM".encode_utf16().collect()),
+      BTreeMap::from([(
+        ResourceName::Name("DATA".encode_utf16().collect()),
+        single_resource(0, vec![1, 2, 3]),
+      )]),
+    );
+    set_icon(
+      &mut resources,
+      &[
+        0, 0, 1, 0, 1, 0, // header
+        16, 16, 0, 0, 1, 0, 32, 0, 2, 0, 0, 0, 22, 0, 0, 0, // entry
+        7, 8, // image
+      ],
+    )
+    .unwrap();
+    assert_eq!(first_language(&resources, RT_GROUP_ICON), DEFAULT_LANGUAGE);
+
+    let section_rva = 0x3000;
+    let section = build_resource_section(&resources, section_rva);
+    let headers = PeHeaders {
+      coff_offset: 0,
+      optional_offset: 0,
+      data_directories_offset: 0,
+      data_directories_count: 0,
+      section_table_offset: 0,
+      section_alignment: 0x1000,
+      file_alignment: 0x200,
+      size_of_headers: 0,
+      sections: vec![Section {
+        virtual_size: section.len() as u32,
+        virtual_address: section_rva,
+        raw_size: section.len() as u32,
+        raw_offset: 0,
+      }],
+    };
+    let mut read = Resources::new();
+    for (type_name, type_entry) in read_directory(&section, 0, 0).unwrap() {
+      let names = read.entry(type_name).or_default();
+      for (name, name_entry) in
+        read_directory(&section, 0, subdirectory_offset(type_entry).unwrap())
+          .unwrap()
+      {
+        for (language, entry) in
+          read_directory(&section, 0, subdirectory_offset(name_entry).unwrap())
+            .unwrap()
+        {
+          let entry = entry as usize;
+          let offset = headers
+            .rva_to_offset(read_u32(&section, entry).unwrap())
+            .unwrap();
+          let size = read_u32(&section, entry + 4).unwrap() as usize;
+          names.entry(name.clone()).or_default().insert(
+            language,
+            ResourceData {
+              data: section[offset..offset + size].to_vec(),
+              code_page: 0,
+            },
+          );
+        }
+      }
+    }
+    assert_eq!(read, resources);
+    assert_eq!(
+      read[&ResourceName::Id(RT_ICON)][&ResourceName::Id(1)]
+        [&ResourceName::Id(DEFAULT_LANGUAGE)]
+        .data,
+      vec![7, 8]
+    );
+    assert_eq!(
+      read[&ResourceName::Id(RT_GROUP_ICON)][&ResourceName::Id(1)]
+        [&ResourceName::Id(DEFAULT_LANGUAGE)]
+        .data,
+      vec![0, 0, 1, 0, 1, 0, 16, 16, 0, 0, 1, 0, 32, 0, 2, 0, 0, 0, 1, 0]
+    );
+  }
+
+  #[test]
+  fn builds_version_info() {
+    let version_info = build_version_info(
+      &ExeMetadata {
+        product_name: Some("App".to_string()),
+        product_version: Some("1.2.3".to_string()),
+        ..Default::default()
+      },
+      DEFAULT_LANGUAGE,
+    )
+    .unwrap();
+    assert_eq!(
+      read_u16(&version_info, 0).unwrap() as usize,
+      version_info.len()
+    );
+    // the fixed file info follows the key
+    assert_eq!(read_u32(&version_info, 40).unwrap(), 0xfeef04bd);
+    // the file version defaults to the product version
+    assert_eq!(read_u32(&version_info, 48).unwrap(), 1 << 16 | 2);
+    assert_eq!(read_u32(&version_info, 52).unwrap(), 3 << 16);
+    let text = String::from_utf16_lossy(
+      &version_info
+        .chunks(2)
+        .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+        .collect::<Vec<_>>(),
+    );
+    assert!(text.contains("040904b0"));
+    assert!(text.contains("ProductName\0\0App\0"));
+    assert!(text.contains(concat!("FileVersion\0\0", "1.2.3\0")));
+  }
+
+  /// Writes a 64-bit executable with a code section, a resource section and
+  /// a signature like the base binaries of `deno compile`.
+  fn write_executable(resources: &Resources, signature: &[u8]) -> Vec<u8> {
+    use object::pe;
+    use object::write::pe::NtHeaders;
+    use object::write::pe::Writer;
+
+    let code = [0xc3; 16];
+    let resource_section_len = build_resource_section(resources, 0).len();
+    let mut bin = Vec::new();
+    let mut writer = Writer::new(true, 0x1000, 0x200, &mut bin);
+    writer.reserve_dos_header_and_stub();
+    writer.reserve_nt_headers(pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
+    writer.reserve_section_headers(2);
+    let text = writer.reserve_text_section(code.len() as u32);
+    let rsrc = writer.reserve_rsrc_section(resource_section_len as u32);
+    writer.reserve_certificate_table(signature.len() as u32);
+    writer.write_dos_header_and_stub().unwrap();
+    writer.write_nt_headers(NtHeaders {
+      machine: pe::IMAGE_FILE_MACHINE_AMD64,
+      time_date_stamp: 0,
+      characteristics: pe::IMAGE_FILE_EXECUTABLE_IMAGE
+        | pe::IMAGE_FILE_LARGE_ADDRESS_AWARE,
+      major_linker_version: 14,
+      minor_linker_version: 0,
+      address_of_entry_point: text.virtual_address,
+      image_base: 0x1_4000_0000,
+      major_operating_system_version: 6,
+      minor_operating_system_version: 0,
+      major_image_version: 0,
+      minor_image_version: 0,
+      major_subsystem_version: 6,
+      minor_subsystem_version: 0,
+      subsystem: pe::IMAGE_SUBSYSTEM_WINDOWS_CUI,
+      dll_characteristics: 0,
+      size_of_stack_reserve: 0x10_0000,
+      size_of_stack_commit: 0x1000,
+      size_of_heap_reserve: 0x10_0000,
+      size_of_heap_commit: 0x1000,
+    });
+    writer.write_section_headers();
+    writer.write_section(text.file_offset, &code);
+    writer.write_section(
+      rsrc.file_offset,
+      &build_resource_section(resources, rsrc.virtual_address),
+    );
+    writer.write_certificate_table(signature);
+    bin
+  }
+
+  /// Reads the language and the data of a resource with object's parser.
+  fn read_resource(
+    bin: &[u8],
+    resource_type: u16,
+    name: u16,
+  ) -> Option<(u16, Vec<u8>)> {
+    use object::read::pe::PeFile64;
+    use object::LittleEndian as LE;
+
+    let file = PeFile64::parse(bin).unwrap();
+    let sections = file.section_table();
+    let directory = file
+      .data_directories()
+      .resource_directory(bin, &sections)
+      .unwrap()?;
+    let find_table = |table: object::read::pe::ResourceDirectoryTable,
+                      id: u16| {
+      table
+        .entries
+        .iter()
+        .find(|entry| entry.name_or_id().id() == Some(id))
+        .and_then(|entry| entry.data(directory).unwrap().table())
+    };
+    let names = find_table(directory.root().unwrap(), resource_type)?;
+    let languages = find_table(names, name)?;
+    let entry = languages.entries.first()?;
+    let data = entry.data(directory).unwrap().data()?;
+    let bytes = sections.pe_data_at(bin, data.offset_to_data.get(LE))?;
+    Some((
+      entry.name_or_id().id()?,
+      bytes[..data.size.get(LE) as usize].to_vec(),
+    ))
+  }
+
+  #[test]
+  fn sets_resources_of_executable() {
+    use object::read::pe::PeFile64;
+
+    let mut resources = Resources::new();
+    // a German version resource, whose language is kept
+    resources.insert(
+      ResourceName::Id(RT_VERSION),
+      BTreeMap::from([(ResourceName::Id(1), single_resource(0x0407, vec![1]))]),
+    );
+    let signature = [0x5a; 24];
+    let base = write_executable(&resources, &signature);
+    assert_eq!(read_resource(&base, RT_VERSION, 1).unwrap().0, 0x0407);
+
+    let icon = [
+      0, 0, 1, 0, 1, 0, // header
+      16, 16, 0, 0, 1, 0, 32, 0, 2, 0, 0, 0, 22, 0, 0, 0, // entry
+      7, 8, // image
+    ];
+    let bin = set_windows_resources(
+      &base,
+      Some(&icon),
+      Some(&ExeMetadata {
+        product_name: Some("App".to_string()),
+        product_version: Some("1.2.3".to_string()),
+        ..Default::default()
+      }),
+    )
+    .unwrap();
+
+    let (language, version_info) = read_resource(&bin, RT_VERSION, 1).unwrap();
+    assert_eq!(language, 0x0407);
+    assert_eq!(
+      version_info,
+      build_version_info(
+        &ExeMetadata {
+          product_name: Some("App".to_string()),
+          product_version: Some("1.2.3".to_string()),
+          ..Default::default()
+        },
+        0x0407
+      )
+      .unwrap()
+    );
+    assert_eq!(
+      read_resource(&bin, RT_ICON, 1).unwrap(),
+      (DEFAULT_LANGUAGE, vec![7, 8])
+    );
+    assert!(read_resource(&bin, RT_GROUP_ICON, 1).is_some());
+
+    let file = PeFile64::parse(&*bin).unwrap();
+    // the code is unchanged
+    let text = file.section_table().section(1).unwrap();
+    assert_eq!(
+      file
+        .section_table()
+        .pe_data_at(&*bin, text.virtual_address.get(object::LittleEndian))
+        .unwrap()[..16],
+      [0xc3; 16]
+    );
+    // the signature is removed since it's invalid
+    assert!(file
+      .data_directories()
+      .get(object::pe::IMAGE_DIRECTORY_ENTRY_SECURITY)
+      .is_none());
+    assert!(!bin
+      .windows(signature.len())
+      .any(|window| window == signature));
+  }
+
+  #[test]
+  fn errors_on_too_long_metadata() {
+    let error = build_version_info(
+      &ExeMetadata {
+        comments: Some("a".repeat(u16::MAX as usize)),
+        ..Default::default()
+      },
+      DEFAULT_LANGUAGE,
+    )
+    .unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "The executable metadata is too long to be stored as version information"
+    );
+  }
+}
@@ -0,0 +1,267 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Runs the benchmarks with another Deno executable for
+//! `deno bench --against=DENO` and prints the results of both executables
+//! side by side. The other executable runs the same benchmarks with
+//! `--json-path`, whose report is parsed for its results.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Stdio;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use serde::Deserialize;
+
+use super::mitata::fmt_duration;
+use super::BenchReport;
+use crate::args::BenchFlags;
+use crate::args::Flags;
+use crate::colors;
+use crate::version::get_user_agent;
+
+/// The parts of the JSON report of `deno bench --json-path` that are
+/// compared.
+#[derive(Debug, Deserialize)]
+struct OtherReport {
+  runtime: String,
+  benches: Vec<OtherBench>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OtherBench {
+  origin: String,
+  group: Option<String>,
+  name: String,
+  /// The results are `{ "ok": stats }` or `{ "failed": error }`.
+  results: Vec<serde_json::Value>,
+}
+
+impl OtherReport {
+  fn find_avg(
+    &self,
+    origin: &str,
+    group: &Option<String>,
+    name: &str,
+  ) -> Option<f64> {
+    self
+      .benches
+      .iter()
+      .find(|bench| {
+        bench.origin == origin && &bench.group == group && bench.name == name
+      })
+      .and_then(|bench| {
+        bench
+          .results
+          .iter()
+          .find_map(|result| result.get("ok")?.get("avg")?.as_f64())
+      })
+  }
+}
+
+/// The other executable and the arguments it runs the benchmarks with.
+#[derive(Debug, Clone)]
+pub struct BenchAgainstOptions {
+  pub executable: PathBuf,
+  pub args: Vec<String>,
+}
+
+/// Runs the benchmarks with the other executable and prints its results next
+/// to the results of this run.
+pub fn compare_against(
+  report: &BenchReport,
+  options: &BenchAgainstOptions,
+) -> Result<(), AnyError> {
+  let executable = &options.executable;
+  log::info!(
+    "{} benchmarks with {}",
+    colors::green("Running"),
+    executable.display()
+  );
+  // the report is written to its own file since the benchmarks may write to
+  // stdout as well
+  let temp_dir = tempfile::TempDir::new()?;
+  let json_path = temp_dir.path().join("report.json");
+  let status = Command::new(executable)
+    .arg("bench")
+    .arg(format!("--json-path={}", json_path.display()))
+    .args(&options.args)
+    .stdin(Stdio::null())
+    .stdout(Stdio::null())
+    .stderr(Stdio::inherit())
+    .status()
+    .with_context(|| {
+      format!("Failed running benchmarks with '{}'.", executable.display())
+    })?;
+  if !status.success() {
+    bail!("Bench failed with '{}' ({}).", executable.display(), status);
+  }
+  let other = std::fs::read_to_string(&json_path)
+    .map_err(AnyError::from)
+    .and_then(|json| Ok(serde_json::from_str::<OtherReport>(&json)?))
+    .with_context(|| {
+      format!(
+        "Failed reading the bench results of '{}'.",
+        executable.display()
+      )
+    })?;
+  print_comparison(report, &other, executable);
+  Ok(())
+}
+
+/// Gets the arguments the other executable runs the same benchmarks with,
+/// without the flags that would write files or compare the results a second
+/// time.
+pub fn child_args(flags: &Flags, bench_flags: &BenchFlags) -> Vec<String> {
+  let mut args = flags.to_runtime_args();
+  // the benchmarks were already type checked by this process
+  args.push("--no-check".to_string());
+  if let Some(filter) = &bench_flags.filter {
+    args.push(format!("--filter={filter}"));
+  }
+  if let Some(group) = &bench_flags.group {
+    args.push(format!("--group={group}"));
+  }
+  if let Some(warmup_time) = bench_flags.warmup_time {
+    args.push(format!("--warmup-time={warmup_time}"));
+  }
+  if let Some(min_iterations) = bench_flags.min_iterations {
+    args.push(format!("--min-iterations={min_iterations}"));
+  }
+  if let Some(max_time) = bench_flags.max_time {
+    args.push(format!("--max-time={max_time}"));
+  }
+  if !bench_flags.files.ignore.is_empty() {
+    args.push(format!("--ignore={}", bench_flags.files.ignore.join(",")));
+  }
+  args.extend(bench_flags.files.include.iter().cloned());
+  args.push("--".to_string());
+  args.extend(flags.argv.iter().cloned());
+  args
+}
+
+#[allow(clippy::print_stdout)]
+fn print_comparison(
+  report: &BenchReport,
+  other: &OtherReport,
+  executable: &Path,
+) {
+  println!(
+    "{}",
+    colors::gray(format!(
+      "compared with {} ({})",
+      executable.display(),
+      other.runtime
+    ))
+  );
+  let measurements = report
+    .measurements
+    .iter()
+    .filter(|(desc, _)| !desc.warmup)
+    .collect::<Vec<_>>();
+  let name_width = measurements
+    .iter()
+    .map(|(desc, _)| desc.name.chars().count())
+    .chain(["benchmark".len()])
+    .max()
+    .unwrap_or(0);
+  println!(
+    "{:<name_width$}  {:>12}  {:>12}",
+    "benchmark",
+    get_user_agent(),
+    executable
+      .file_name()
+      .map(|name| name.to_string_lossy())
+      .unwrap_or_default()
+  );
+  let mut origin = None;
+  for (desc, stats) in measurements {
+    if origin != Some(&desc.origin) {
+      println!("{}", colors::gray(&desc.origin));
+      origin = Some(&desc.origin);
+    }
+    let Some(other_avg) = other.find_avg(&desc.origin, &desc.group, &desc.name)
+    else {
+      println!(
+        "{:<name_width$}  {:>12}  {:>12}",
+        desc.name,
+        fmt_duration(stats.avg),
+        "-"
+      );
+      continue;
+    };
+    let ratio = if stats.avg <= other_avg {
+      colors::green(format!("{:.2}x faster", other_avg / stats.avg))
+    } else {
+      colors::red(format!("{:.2}x slower", stats.avg / other_avg))
+    };
+    println!(
+      "{:<name_width$}  {:>12}  {:>12}  {}",
+      desc.name,
+      fmt_duration(stats.avg),
+      fmt_duration(other_avg),
+      ratio
+    );
+  }
+  println!();
+}
+
+#[cfg(test)]
+mod test {
+  use std::ffi::OsString;
+
+  use super::*;
+  use crate::args::flags_from_vec;
+  use crate::args::DenoSubcommand;
+
+  #[test]
+  fn child_args_from_flags() {
+    let flags = flags_from_vec(
+      [
+        "deno",
+        "bench",
+        "--against=/usr/bin/deno-old",
+        "--compare=main",
+        "--profile",
+        "--profile-dir=profiles",
+        "--group=url",
+        "-A",
+        "--min-iterations=5",
+        "a_bench.ts",
+        "--",
+        "--against=x",
+      ]
+      .into_iter()
+      .map(OsString::from)
+      .collect(),
+    )
+    .unwrap();
+    let DenoSubcommand::Bench(bench_flags) = &flags.subcommand else {
+      unreachable!()
+    };
+    assert_eq!(
+      child_args(&flags, bench_flags),
+      vec![
+        "--allow-all",
+        "--no-check",
+        "--group=url",
+        "--min-iterations=5",
+        "a_bench.ts",
+        "--",
+        "--against=x"
+      ]
+    );
+  }
+
+  #[test]
+  fn finds_avg_of_other_report() {
+    let json = "{\n  \"runtime\": \"Deno/1.44.0 x86_64-unknown-linux-gnu\",\n  \"benches\": [\n    {\n      \"origin\": \"file:///a_bench.ts\",\n      \"group\": null,\n      \"name\": \"a\",\n      \"baseline\": false,\n      \"results\": [{ \"ok\": { \"n\": 1, \"avg\": 5.0 } }]\n    }\n  ]\n}\n";
+    let report = serde_json::from_str::<OtherReport>(json).unwrap();
+    assert_eq!(report.runtime, "Deno/1.44.0 x86_64-unknown-linux-gnu");
+    assert_eq!(report.find_avg("file:///a_bench.ts", &None, "a"), Some(5.0));
+    assert_eq!(report.find_avg("file:///a_bench.ts", &None, "b"), None);
+  }
+}
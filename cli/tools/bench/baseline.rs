@@ -0,0 +1,279 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Saves the results of `deno bench --save-baseline=NAME` under DENO_DIR and
+//! compares the results of later runs with them with `--compare=NAME`.
+
+use std::path::PathBuf;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::url::Url;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::mitata::fmt_duration;
+use super::BenchReport;
+use crate::colors;
+use crate::util::fs::atomic_write_file;
+use crate::version::get_user_agent;
+
+#[derive(Debug, Clone)]
+pub struct BenchBaselineOptions {
+  /// The directory of the baseline files.
+  pub dir: PathBuf,
+  /// The origins of the benchmarks are stored relative to the cwd so that
+  /// the baselines can be compared across checkouts of a project.
+  pub cwd: Url,
+  pub save: Option<String>,
+  pub compare: Option<String>,
+  pub regression_threshold: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Baseline {
+  runtime: String,
+  benches: Vec<BaselineBench>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BaselineBench {
+  origin: String,
+  group: Option<String>,
+  name: String,
+  n: u64,
+  avg: f64,
+  stddev: f64,
+}
+
+impl BaselineBench {
+  fn is_same_bench(&self, other: &BaselineBench) -> bool {
+    self.origin == other.origin
+      && self.group == other.group
+      && self.name == other.name
+  }
+}
+
+struct BenchComparison<'a> {
+  baseline: &'a BaselineBench,
+  current: &'a BaselineBench,
+  /// The change of the average time in percent.
+  delta: f64,
+  /// Whether the difference is larger than the noise of the measurements.
+  significant: bool,
+}
+
+impl<'a> BenchComparison<'a> {
+  fn new(baseline: &'a BaselineBench, current: &'a BaselineBench) -> Self {
+    let difference = current.avg - baseline.avg;
+    // about two standard errors of the difference of the means, which is a
+    // confidence of 95% for normally distributed times
+    let standard_error = (baseline.stddev.powi(2) / baseline.n.max(1) as f64
+      + current.stddev.powi(2) / current.n.max(1) as f64)
+      .sqrt();
+    Self {
+      baseline,
+      current,
+      delta: if baseline.avg > 0.0 {
+        difference / baseline.avg * 100.0
+      } else {
+        0.0
+      },
+      significant: difference.abs() > 2.0 * standard_error,
+    }
+  }
+
+  fn is_regression(&self, threshold: u32) -> bool {
+    self.significant && self.delta > threshold as f64
+  }
+}
+
+/// Compares the results with the baseline of `--compare` and saves them as
+/// the baseline of `--save-baseline`, failing when a benchmark regressed.
+pub fn compare_and_save_baseline(
+  report: &BenchReport,
+  options: &BenchBaselineOptions,
+  print_comparison: bool,
+) -> Result<(), AnyError> {
+  let benches = baseline_benches(report, &options.cwd);
+  let mut regressions = 0;
+  if let Some(name) = &options.compare {
+    let baseline = read_baseline(options, name)?;
+    let comparisons = benches
+      .iter()
+      .filter_map(|current| {
+        let baseline = baseline
+          .benches
+          .iter()
+          .find(|baseline| baseline.is_same_bench(current))?;
+        Some(BenchComparison::new(baseline, current))
+      })
+      .collect::<Vec<_>>();
+    regressions = comparisons
+      .iter()
+      .filter(|c| c.is_regression(options.regression_threshold))
+      .count();
+    if print_comparison {
+      print_comparisons(name, &comparisons, options.regression_threshold);
+    }
+  }
+  if let Some(name) = &options.save {
+    let path = baseline_path(options, name);
+    std::fs::create_dir_all(&options.dir)?;
+    let baseline = Baseline {
+      runtime: format!("{} {}", get_user_agent(), env!("TARGET")),
+      benches,
+    };
+    atomic_write_file(&path, serde_json::to_string_pretty(&baseline)?, 0o644)
+      .with_context(|| {
+      format!("Failed writing bench baseline '{}'.", path.display())
+    })?;
+    log::info!("Saved bench baseline \"{}\".", name);
+  }
+  if regressions > 0 {
+    bail!(
+      "Bench failed because {} {} slower than baseline \"{}\" by more than {}%",
+      regressions,
+      if regressions == 1 {
+        "benchmark is"
+      } else {
+        "benchmarks are"
+      },
+      options.compare.as_deref().unwrap_or_default(),
+      options.regression_threshold,
+    );
+  }
+  Ok(())
+}
+
+fn baseline_path(options: &BenchBaselineOptions, name: &str) -> PathBuf {
+  options.dir.join(format!("{name}.json"))
+}
+
+fn read_baseline(
+  options: &BenchBaselineOptions,
+  name: &str,
+) -> Result<Baseline, AnyError> {
+  let path = baseline_path(options, name);
+  let text = match std::fs::read_to_string(&path) {
+    Ok(text) => text,
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+      bail!(
+        "Bench baseline \"{}\" was not found. Save it with --save-baseline={} first.",
+        name,
+        name
+      );
+    }
+    Err(err) => {
+      return Err(err).with_context(|| {
+        format!("Failed reading bench baseline '{}'.", path.display())
+      })
+    }
+  };
+  serde_json::from_str(&text).with_context(|| {
+    format!("Failed parsing bench baseline '{}'.", path.display())
+  })
+}
+
+fn baseline_benches(report: &BenchReport, cwd: &Url) -> Vec<BaselineBench> {
+  report
+    .measurements
+    .iter()
+    .filter(|(desc, _)| !desc.warmup)
+    .map(|(desc, stats)| BaselineBench {
+      origin: Url::parse(&desc.origin)
+        .ok()
+        .and_then(|origin| cwd.make_relative(&origin))
+        .unwrap_or_else(|| desc.origin.clone()),
+      group: desc.group.clone(),
+      name: desc.name.clone(),
+      n: stats.n,
+      avg: stats.avg,
+      stddev: stats.stddev,
+    })
+    .collect()
+}
+
+#[allow(clippy::print_stdout)]
+fn print_comparisons(
+  name: &str,
+  comparisons: &[BenchComparison],
+  threshold: u32,
+) {
+  println!(
+    "{}",
+    colors::gray(format!("compared with baseline \"{name}\""))
+  );
+  if comparisons.is_empty() {
+    println!("{}\n", colors::gray("no benchmarks of the baseline ran"));
+    return;
+  }
+  let name_width = comparisons
+    .iter()
+    .map(|c| c.current.name.chars().count())
+    .max()
+    .unwrap_or(0);
+  for comparison in comparisons {
+    let delta = format!("{:+.1}%", comparison.delta);
+    let delta = if comparison.is_regression(threshold) {
+      colors::red(format!("{delta} regression")).to_string()
+    } else if !comparison.significant {
+      colors::gray(format!("{delta} (not significant)")).to_string()
+    } else if comparison.delta < 0.0 {
+      colors::green(delta).to_string()
+    } else {
+      colors::yellow(delta).to_string()
+    };
+    println!(
+      "{:<name_width$}  {} -> {}  {}",
+      comparison.current.name,
+      fmt_duration(comparison.baseline.avg),
+      fmt_duration(comparison.current.avg),
+      delta,
+    );
+  }
+  println!();
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn bench(n: u64, avg: f64, stddev: f64) -> BaselineBench {
+    BaselineBench {
+      origin: "a_bench.ts".to_string(),
+      group: None,
+      name: "a".to_string(),
+      n,
+      avg,
+      stddev,
+    }
+  }
+
+  #[test]
+  fn compares_benches() {
+    let baseline = bench(1000, 100.0, 10.0);
+
+    let current = bench(1000, 120.0, 10.0);
+    let comparison = BenchComparison::new(&baseline, &current);
+    assert_eq!(comparison.delta.round(), 20.0);
+    assert!(comparison.significant);
+    assert!(comparison.is_regression(10));
+    assert!(!comparison.is_regression(25));
+
+    // faster benches are never regressions
+    let current = bench(1000, 80.0, 10.0);
+    let comparison = BenchComparison::new(&baseline, &current);
+    assert!(comparison.significant);
+    assert!(!comparison.is_regression(10));
+
+    // a difference within the noise of a few iterations isn't significant
+    let current = bench(4, 120.0, 50.0);
+    let comparison = BenchComparison::new(&baseline, &current);
+    assert!(!comparison.significant);
+    assert!(!comparison.is_regression(10));
+  }
+}
@@ -1,6 +1,9 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::BenchBudgetOptions;
 use crate::args::BenchFlags;
+use crate::args::BenchOptions;
+use crate::args::BenchProfileOptions;
 use crate::args::CliOptions;
 use crate::args::Flags;
 use crate::colors;
@@ -24,10 +27,12 @@ use deno_core::error::AnyError;
 use deno_core::error::JsError;
 use deno_core::futures::future;
 use deno_core::futures::stream;
+use deno_core::futures::FutureExt;
 use deno_core::futures::StreamExt;
 use deno_core::serde_v8;
 use deno_core::unsync::spawn;
 use deno_core::unsync::spawn_blocking;
+use deno_core::url::Url;
 use deno_core::v8;
 use deno_core::ModuleSpecifier;
 use deno_core::PollEventLoopOptions;
@@ -42,14 +47,24 @@ use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashSet;
 use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc::unbounded_channel;
 use tokio::sync::mpsc::UnboundedSender;
 
+mod against;
+mod baseline;
 mod mitata;
+mod profile;
 mod reporters;
 
+use against::child_args;
+use against::compare_against;
+use against::BenchAgainstOptions;
+use baseline::compare_and_save_baseline;
+use baseline::BenchBaselineOptions;
+use profile::BenchProfiler;
 use reporters::BenchReporter;
 use reporters::ConsoleReporter;
 use reporters::JsonReporter;
@@ -57,8 +72,16 @@ use reporters::JsonReporter;
 #[derive(Debug, Clone)]
 struct BenchSpecifierOptions {
   filter: TestFilter,
+  /// Only run the benchmarks of this group with `--group`.
+  group: Option<String>,
   json: bool,
+  json_path: Option<PathBuf>,
   log_level: Option<log::Level>,
+  profile: Option<BenchProfileOptions>,
+  budget: BenchBudgetOptions,
+  baseline: Option<BenchBaselineOptions>,
+  /// Another Deno executable to run the benchmarks with for comparison.
+  against: Option<BenchAgainstOptions>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
@@ -115,12 +138,21 @@ pub struct BenchStats {
   pub min: f64,
   pub max: f64,
   pub avg: f64,
+  pub p50: f64,
   pub p75: f64,
   pub p99: f64,
   pub p995: f64,
   pub p999: f64,
+  /// The standard deviation of the iteration times.
+  pub stddev: f64,
+  /// The number of iteration times below and above Tukey's fences.
+  pub low_outliers: u64,
+  pub high_outliers: u64,
   pub high_precision: bool,
   pub used_explicit_timers: bool,
+  /// The sorted iteration times, which are only collected for `--json`.
+  #[serde(default)]
+  pub samples: Vec<f64>,
 }
 
 impl BenchReport {
@@ -137,9 +169,10 @@ impl BenchReport {
 fn create_reporter(
   show_output: bool,
   json: bool,
+  json_path: Option<PathBuf>,
 ) -> Box<dyn BenchReporter + Send> {
   if json {
-    return Box::new(JsonReporter::new());
+    return Box::new(JsonReporter::new(json_path));
   }
   Box::new(ConsoleReporter::new(show_output))
 }
@@ -150,14 +183,14 @@ async fn bench_specifier(
   permissions: Permissions,
   specifier: ModuleSpecifier,
   sender: UnboundedSender<BenchEvent>,
-  filter: TestFilter,
+  options: BenchSpecifierOptions,
 ) -> Result<(), AnyError> {
   match bench_specifier_inner(
     worker_factory,
     permissions,
     specifier.clone(),
     &sender,
-    filter,
+    options,
   )
   .await
   {
@@ -182,14 +215,19 @@ async fn bench_specifier_inner(
   permissions: Permissions,
   specifier: ModuleSpecifier,
   sender: &UnboundedSender<BenchEvent>,
-  filter: TestFilter,
+  options: BenchSpecifierOptions,
 ) -> Result<(), AnyError> {
   let mut worker = worker_factory
     .create_custom_worker(
       WorkerExecutionMode::Bench,
       specifier.clone(),
       PermissionsContainer::new(permissions),
-      vec![ops::bench::deno_bench::init_ops(sender.clone())],
+      vec![ops::bench::deno_bench::init_ops(
+        sender.clone(),
+        options.budget,
+        // the samples are only reported by `--json`
+        options.json,
+      )],
       Default::default(),
     )
     .await?;
@@ -199,6 +237,22 @@ async fn bench_specifier_inner(
 
   let mut worker = worker.into_main_worker();
 
+  let mut maybe_profiler = match options.profile {
+    Some(options) => {
+      let mut profiler =
+        BenchProfiler::new(options, worker.create_inspector_session());
+      worker
+        .js_runtime
+        .with_event_loop_future(
+          profiler.enable().boxed_local(),
+          PollEventLoopOptions::default(),
+        )
+        .await?;
+      Some(profiler)
+    }
+    None => None,
+  };
+
   // Ensure that there are no pending exceptions before we start running tests
   worker.run_up_to_duration(Duration::from_millis(0)).await?;
 
@@ -215,7 +269,12 @@ async fn bench_specifier_inner(
   let benchmarks = if used_only { only } else { no_only };
   let mut benchmarks = benchmarks
     .into_iter()
-    .filter(|(d, _)| d.warmup || filter.includes(&d.name) && !d.ignore)
+    .filter(|(d, _)| {
+      d.warmup
+        || options.filter.includes(&d.name)
+          && !d.ignore
+          && (options.group.is_none() || d.group == options.group)
+    })
     .collect::<Vec<_>>();
   let mut groups = IndexSet::<Option<String>>::new();
   // make sure ungrouped benchmarks are placed above grouped
@@ -238,11 +297,31 @@ async fn bench_specifier_inner(
   }))?;
   for (desc, function) in benchmarks {
     sender.send(BenchEvent::Wait(desc.id))?;
+    // warmup benches only exist to prime the JIT, so they aren't profiled
+    let should_profile = !desc.warmup;
+    if let Some(profiler) = maybe_profiler.as_mut().filter(|_| should_profile) {
+      worker
+        .js_runtime
+        .with_event_loop_future(
+          profiler.start().boxed_local(),
+          PollEventLoopOptions::default(),
+        )
+        .await?;
+    }
     let call = worker.js_runtime.call(&function);
     let result = worker
       .js_runtime
       .with_event_loop_promise(call, PollEventLoopOptions::default())
       .await?;
+    if let Some(profiler) = maybe_profiler.as_mut().filter(|_| should_profile) {
+      worker
+        .js_runtime
+        .with_event_loop_future(
+          profiler.stop(&desc).boxed_local(),
+          PollEventLoopOptions::default(),
+        )
+        .await?;
+    }
     let scope = &mut worker.js_runtime.handle_scope();
     let result = v8::Local::new(scope, result);
     let result = serde_v8::from_v8::<BenchResult>(scope, result)?;
@@ -285,7 +364,7 @@ async fn bench_specifiers(
         permissions,
         specifier,
         sender,
-        options.filter,
+        options,
       );
       create_and_run_current_thread(future)
     })
@@ -299,8 +378,11 @@ async fn bench_specifiers(
     spawn(async move {
       let mut used_only = false;
       let mut report = BenchReport::new();
-      let mut reporter =
-        create_reporter(log_level != Some(Level::Error), options.json);
+      let mut reporter = create_reporter(
+        log_level != Some(Level::Error),
+        options.json,
+        options.json_path.clone(),
+      );
       let mut benches = IndexMap::new();
 
       while let Some(event) = receiver.recv().await {
@@ -361,6 +443,14 @@ async fn bench_specifiers(
         return Err(generic_error("Bench failed"));
       }
 
+      if let Some(baseline) = &options.baseline {
+        compare_and_save_baseline(&report, baseline, !options.json)?;
+      }
+
+      if let Some(against) = &options.against {
+        compare_against(&report, against)?;
+      }
+
       Ok(())
     })
   };
@@ -406,6 +496,10 @@ pub async fn run_benchmarks(
   flags: Flags,
   bench_flags: BenchFlags,
 ) -> Result<(), AnyError> {
+  let against_args = bench_flags
+    .against
+    .is_some()
+    .then(|| child_args(&flags, &bench_flags));
   let cli_options = CliOptions::from_flags(flags)?;
   let bench_options = cli_options.resolve_bench_options(bench_flags)?;
   let factory = CliFactory::from_cli_options(Arc::new(cli_options));
@@ -416,9 +510,11 @@ pub async fn run_benchmarks(
   let permissions =
     Permissions::from_options(&cli_options.permissions_options()?)?;
 
+  let baseline = resolve_baseline_options(&factory, &bench_options)?;
   let specifiers = collect_specifiers(
     bench_options.files,
     cli_options.vendor_dir_path().map(ToOwned::to_owned),
+    false,
     is_supported_bench_path,
   )?;
 
@@ -442,15 +538,57 @@ pub async fn run_benchmarks(
     specifiers,
     BenchSpecifierOptions {
       filter: TestFilter::from_flag(&bench_options.filter),
+      group: bench_options.group.clone(),
       json: bench_options.json,
+      json_path: bench_options.json_path.clone(),
       log_level,
+      profile: bench_options.profile.clone(),
+      budget: bench_options.budget,
+      baseline,
+      against: bench_options
+        .against
+        .clone()
+        .zip(against_args)
+        .map(|(executable, args)| BenchAgainstOptions { executable, args }),
     },
   )
   .await?;
 
+  if let Some(profile) = &bench_options.profile {
+    log::info!(
+      "Bench profiles have been written to {}",
+      profile.dir.display()
+    );
+  }
+
   Ok(())
 }
 
+fn resolve_baseline_options(
+  factory: &CliFactory,
+  bench_options: &BenchOptions,
+) -> Result<Option<BenchBaselineOptions>, AnyError> {
+  if bench_options.save_baseline.is_none()
+    && bench_options.compare_baseline.is_none()
+  {
+    return Ok(None);
+  }
+  let initial_cwd = factory.cli_options().initial_cwd();
+  let cwd = Url::from_directory_path(initial_cwd).map_err(|_| {
+    generic_error(format!(
+      "Unable to construct URL from the path of cwd: {}",
+      initial_cwd.to_string_lossy(),
+    ))
+  })?;
+  Ok(Some(BenchBaselineOptions {
+    dir: factory.deno_dir()?.bench_baselines_folder_path(),
+    cwd,
+    save: bench_options.save_baseline.clone(),
+    compare: bench_options.compare_baseline.clone(),
+    regression_threshold: bench_options.regression_threshold,
+  }))
+}
+
 // TODO(bartlomieju): heavy duplication of code with `cli/tools/test.rs`
 pub async fn run_benchmarks_with_watch(
   flags: Flags,
@@ -488,6 +626,7 @@ pub async fn run_benchmarks_with_watch(
         let bench_modules = collect_specifiers(
           bench_options.files.clone(),
           cli_options.vendor_dir_path().map(ToOwned::to_owned),
+          false,
           is_supported_bench_path,
         )?;
 
@@ -526,9 +665,11 @@ pub async fn run_benchmarks_with_watch(
 
         // todo(dsherret): why are we collecting specifiers twice in a row?
         // Seems like a perf bug.
+        let baseline = resolve_baseline_options(&factory, &bench_options)?;
         let specifiers = collect_specifiers(
           bench_options.files,
           cli_options.vendor_dir_path().map(ToOwned::to_owned),
+          false,
           is_supported_bench_path,
         )?
         .into_iter()
@@ -552,8 +693,14 @@ pub async fn run_benchmarks_with_watch(
           specifiers,
           BenchSpecifierOptions {
             filter: TestFilter::from_flag(&bench_options.filter),
+            group: bench_options.group.clone(),
             json: bench_options.json,
+            json_path: bench_options.json_path.clone(),
             log_level,
+            baseline,
+            budget: bench_options.budget,
+            profile: bench_options.profile,
+            against: None,
           },
         )
         .await?;
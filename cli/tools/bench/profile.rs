@@ -0,0 +1,274 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use super::BenchDescription;
+use crate::args::BenchProfileFormat;
+use crate::args::BenchProfileOptions;
+use crate::cdp;
+
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::LocalInspectorSession;
+use deno_core::ModuleSpecifier;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Records a V8 CPU profile around each benchmark and writes it to the
+/// profile directory.
+pub struct BenchProfiler {
+  options: BenchProfileOptions,
+  session: LocalInspectorSession,
+}
+
+impl BenchProfiler {
+  pub fn new(
+    options: BenchProfileOptions,
+    session: LocalInspectorSession,
+  ) -> Self {
+    Self { options, session }
+  }
+
+  pub async fn enable(&mut self) -> Result<(), AnyError> {
+    fs::create_dir_all(&self.options.dir)?;
+    self
+      .session
+      .post_message::<()>("Profiler.enable", None)
+      .await?;
+    Ok(())
+  }
+
+  pub async fn start(&mut self) -> Result<(), AnyError> {
+    self
+      .session
+      .post_message::<()>("Profiler.start", None)
+      .await?;
+    Ok(())
+  }
+
+  /// Stops the running profile and writes it out for the given bench.
+  pub async fn stop(
+    &mut self,
+    desc: &BenchDescription,
+  ) -> Result<PathBuf, AnyError> {
+    let mut return_value = self
+      .session
+      .post_message::<()>("Profiler.stop", None)
+      .await?;
+    let file_stem = profile_file_stem(desc, &self.options.root);
+    let (file_path, contents) = match self.options.format {
+      BenchProfileFormat::CpuProfile => {
+        let profile = return_value["profile"].take();
+        (
+          self.options.dir.join(format!("{file_stem}.cpuprofile")),
+          serde_json::to_string(&profile)?,
+        )
+      }
+      BenchProfileFormat::Flamegraph => {
+        let response: cdp::StopProfileResponse =
+          serde_json::from_value(return_value)?;
+        (
+          self.options.dir.join(format!("{file_stem}.folded")),
+          fold_stacks(&response.profile),
+        )
+      }
+    };
+    fs::write(&file_path, contents)?;
+    Ok(file_path)
+  }
+}
+
+/// Creates a file name for the profile of a bench that is unique within
+/// a bench run and safe to use on all platforms.
+fn profile_file_stem(desc: &BenchDescription, root: &Path) -> String {
+  let mut parts = vec![module_path(&desc.origin, root)];
+  if let Some(group) = &desc.group {
+    parts.push(group.clone());
+  }
+  parts.push(desc.name.clone());
+  parts
+    .join("-")
+    .chars()
+    .map(|c| {
+      if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+        c
+      } else {
+        '_'
+      }
+    })
+    .collect()
+}
+
+/// Gets the path of a bench module without its extension, which is relative
+/// to the root for local modules and starts with the host for remote ones,
+/// so that benches with the same name in different modules don't overwrite
+/// each other's profiles.
+fn module_path(origin: &str, root: &Path) -> String {
+  let Ok(url) = ModuleSpecifier::parse(origin) else {
+    return origin.to_string();
+  };
+  let path = match url.to_file_path() {
+    Ok(path) => match path.strip_prefix(root) {
+      Ok(relative_path) => relative_path.to_path_buf(),
+      Err(_) => path,
+    },
+    Err(()) => PathBuf::from(format!(
+      "{}{}",
+      url.host_str().unwrap_or_default(),
+      url.path()
+    )),
+  };
+  path
+    .with_extension("")
+    .to_string_lossy()
+    .trim_start_matches(['/', '\\'])
+    .to_string()
+}
+
+/// Converts a V8 CPU profile into the "folded stacks" format understood by
+/// flamegraph tools (ex. `inferno-flamegraph` or `flamegraph.pl`), which is
+/// one line per unique stack with the frames separated by `;` followed by
+/// the number of samples.
+fn fold_stacks(profile: &cdp::Profile) -> String {
+  let nodes = profile
+    .nodes
+    .iter()
+    .map(|node| (node.id, node))
+    .collect::<HashMap<_, _>>();
+  let mut parents = HashMap::new();
+  for node in &profile.nodes {
+    for child in node.children.iter().flatten() {
+      parents.insert(*child, node.id);
+    }
+  }
+
+  let mut sample_counts = HashMap::<i64, i64>::new();
+  match &profile.samples {
+    Some(samples) => {
+      for id in samples {
+        *sample_counts.entry(*id).or_default() += 1;
+      }
+    }
+    None => {
+      for node in &profile.nodes {
+        sample_counts.insert(node.id, node.hit_count.unwrap_or(0));
+      }
+    }
+  }
+
+  let mut lines = Vec::new();
+  for node in &profile.nodes {
+    let count = sample_counts.get(&node.id).copied().unwrap_or(0);
+    if count == 0 {
+      continue;
+    }
+    let mut frames = Vec::new();
+    let mut current = Some(node.id);
+    while let Some(id) = current {
+      let call_frame = &nodes[&id].call_frame;
+      if call_frame.function_name != "(root)" {
+        frames.push(frame_name(call_frame));
+      }
+      current = parents.get(&id).copied();
+    }
+    if frames.is_empty() {
+      continue;
+    }
+    frames.reverse();
+    lines.push(format!("{} {}", frames.join(";"), count));
+  }
+  lines.sort();
+
+  let mut output = lines.join("\n");
+  output.push('\n');
+  output
+}
+
+fn frame_name(call_frame: &cdp::CallFrame) -> String {
+  let name = if call_frame.function_name.is_empty() {
+    "(anonymous)"
+  } else {
+    call_frame.function_name.as_str()
+  };
+  if call_frame.url.is_empty() {
+    name.to_string()
+  } else {
+    format!(
+      "{} ({}:{})",
+      name,
+      call_frame.url,
+      call_frame.line_number + 1
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use deno_core::serde_json::json;
+
+  fn call_frame(name: &str, url: &str, line: u64) -> serde_json::Value {
+    json!({
+      "functionName": name,
+      "scriptId": "1",
+      "url": url,
+      "lineNumber": line,
+      "columnNumber": 0,
+    })
+  }
+
+  #[test]
+  fn test_fold_stacks() {
+    let profile: cdp::Profile = serde_json::from_value(json!({
+      "nodes": [
+        { "id": 1, "callFrame": call_frame("(root)", "", 0), "children": [2, 4] },
+        { "id": 2, "callFrame": call_frame("", "file:///a.ts", 0), "children": [3] },
+        { "id": 3, "callFrame": call_frame("foo", "file:///a.ts", 4) },
+        { "id": 4, "callFrame": call_frame("(garbage collector)", "", 0) },
+      ],
+      "startTime": 0.0,
+      "endTime": 10.0,
+      "samples": [3, 3, 2, 4, 3, 1],
+      "timeDeltas": [1, 1, 1, 1, 1, 1],
+    }))
+    .unwrap();
+    assert_eq!(
+      fold_stacks(&profile),
+      concat!(
+        "(anonymous) (file:///a.ts:1) 1\n",
+        "(anonymous) (file:///a.ts:1);foo (file:///a.ts:5) 3\n",
+        "(garbage collector) 1\n",
+      )
+    );
+  }
+
+  #[test]
+  fn test_profile_file_stem() {
+    let root = std::env::temp_dir();
+    let mut desc = BenchDescription {
+      id: 0,
+      name: "parse url/with spaces".to_string(),
+      origin: ModuleSpecifier::from_file_path(
+        root.join("bench").join("url_bench.ts"),
+      )
+      .unwrap()
+      .to_string(),
+      baseline: false,
+      group: Some("url".to_string()),
+      ignore: false,
+      only: false,
+      warmup: false,
+    };
+    assert_eq!(
+      profile_file_stem(&desc, &root),
+      "bench_url_bench-url-parse_url_with_spaces"
+    );
+
+    desc.origin = "https://deno.land/x/url/bench.ts".to_string();
+    desc.group = None;
+    assert_eq!(
+      profile_file_stem(&desc, &root),
+      "deno.land_x_url_bench-parse_url_with_spaces"
+    );
+  }
+}
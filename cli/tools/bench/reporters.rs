@@ -1,8 +1,11 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::anyhow::Context;
+use deno_core::serde_json;
 use serde::Serialize;
 
 use super::*;
+use crate::util::fs::atomic_write_file;
 
 pub trait BenchReporter {
   fn report_group_summary(&mut self);
@@ -15,18 +18,45 @@ pub trait BenchReporter {
   fn report_uncaught_error(&mut self, origin: &str, error: Box<JsError>);
 }
 
+/// The version of the schema of the JSON output, which is increased when
+/// fields are removed or change their meaning.
+const JSON_SCHEMA_VERSION: u32 = 1;
+
 #[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 struct JsonReporterOutput {
+  version: u32,
   runtime: String,
   cpu: String,
+  environment: JsonReporterEnvironment,
   benches: Vec<JsonReporterBench>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonReporterEnvironment {
+  deno: &'static str,
+  v8: &'static str,
+  typescript: &'static str,
+  target: &'static str,
+  os: &'static str,
+  arch: &'static str,
+}
+
 impl Default for JsonReporterOutput {
   fn default() -> Self {
     Self {
+      version: JSON_SCHEMA_VERSION,
       runtime: format!("{} {}", get_user_agent(), env!("TARGET")),
       cpu: mitata::cpu::name(),
+      environment: JsonReporterEnvironment {
+        deno: crate::version::deno(),
+        v8: deno_core::v8_version(),
+        typescript: crate::version::TYPESCRIPT,
+        target: env!("TARGET"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+      },
       benches: vec![],
     }
   }
@@ -41,12 +71,19 @@ struct JsonReporterBench {
   results: Vec<BenchResult>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct JsonReporter(JsonReporterOutput);
+#[derive(Debug)]
+pub struct JsonReporter {
+  output: JsonReporterOutput,
+  /// The file the report is written to instead of stdout.
+  path: Option<PathBuf>,
+}
 
 impl JsonReporter {
-  pub fn new() -> Self {
-    Self(Default::default())
+  pub fn new(path: Option<PathBuf>) -> Self {
+    Self {
+      output: Default::default(),
+      path,
+    }
   }
 }
 
@@ -57,7 +94,17 @@ impl BenchReporter for JsonReporter {
   fn report_plan(&mut self, _plan: &BenchPlan) {}
 
   fn report_end(&mut self, _report: &BenchReport) {
-    match write_json_to_stdout(self) {
+    let result = match &self.path {
+      Some(path) => serde_json::to_string_pretty(&self.output)
+        .map_err(AnyError::from)
+        .and_then(|json| {
+          atomic_write_file(path, json, 0o644).with_context(|| {
+            format!("Failed writing bench report '{}'.", path.display())
+          })
+        }),
+      None => write_json_to_stdout(&self.output),
+    };
+    match result {
       Ok(_) => (),
       Err(e) => println!("{}", e),
     }
@@ -74,7 +121,7 @@ impl BenchReporter for JsonReporter {
       return;
     }
 
-    let maybe_bench = self.0.benches.iter_mut().find(|bench| {
+    let maybe_bench = self.output.benches.iter_mut().find(|bench| {
       bench.origin == desc.origin
         && bench.group == desc.group
         && bench.name == desc.name
@@ -84,7 +131,7 @@ impl BenchReporter for JsonReporter {
     if let Some(bench) = maybe_bench {
       bench.results.push(result.clone());
     } else {
-      self.0.benches.push(JsonReporterBench {
+      self.output.benches.push(JsonReporterBench {
         origin: desc.origin.clone(),
         group: desc.group.clone(),
         name: desc.name.clone(),
@@ -1,15 +1,24 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Instant;
 
+use deno_ast::SourceMapOption;
+use deno_core::anyhow::bail;
 use deno_core::error::AnyError;
 use deno_graph::Module;
 use deno_terminal::colors;
 
 use crate::args::BundleFlags;
+use crate::args::BundleFormat;
+use crate::args::BundleSourceMap;
 use crate::args::CliOptions;
 use crate::args::Flags;
 use crate::args::TsConfigType;
+use crate::cache::FastInsecureHasher;
 use crate::factory::CliFactory;
 use crate::factory::CliFactoryBuilder;
 use crate::graph_util::error_for_any_npm_specifier;
@@ -26,22 +35,48 @@ pub async fn bundle(
   );
 
   if let Some(watch_flags) = &bundle_flags.watch {
+    let last_bundle = Rc::new(RefCell::new(BundledModules::default()));
     util::file_watcher::watch_func(
       flags,
       util::file_watcher::PrintConfig::new(
         "Bundle",
         !watch_flags.no_clear_screen,
       ),
-      move |flags, watcher_communicator, _changed_paths| {
+      move |flags, watcher_communicator, changed_paths| {
         let bundle_flags = bundle_flags.clone();
+        let last_bundle = last_bundle.clone();
         Ok(async move {
+          if let Some(changed_paths) = changed_paths {
+            let last_bundle = last_bundle.borrow();
+            if last_bundle.has_same_sources(&changed_paths) {
+              // the watcher is recreated for every run
+              let _ = watcher_communicator
+                .watch_paths(last_bundle.paths_to_watch.clone());
+              log::info!(
+                "{}",
+                colors::gray("The changed files have the same content. Skipping the rebuild.")
+              );
+              return Ok(());
+            }
+          }
+
+          let started = Instant::now();
           let factory = CliFactoryBuilder::new().build_from_flags_for_watcher(
             flags,
             watcher_communicator.clone(),
           )?;
           let cli_options = factory.cli_options();
-          let _ = watcher_communicator.watch_paths(cli_options.watch_paths());
-          bundle_action(factory, &bundle_flags).await?;
+          let config_paths = cli_options.watch_paths();
+          let _ = watcher_communicator.watch_paths(config_paths.clone());
+          let mut bundled_modules =
+            bundle_action(factory, &bundle_flags).await?;
+          bundled_modules.paths_to_watch.extend(config_paths);
+          *last_bundle.borrow_mut() = bundled_modules;
+          log::info!(
+            "{} in {}",
+            colors::green("Finished"),
+            display::human_elapsed(started.elapsed().as_millis())
+          );
 
           Ok(())
         })
@@ -56,10 +91,37 @@ pub async fn bundle(
   Ok(())
 }
 
+/// The local modules of the last bundle of `deno bundle --watch`, whose
+/// sources are hashed to skip rebuilding when a file is saved without
+/// changing it.
+#[derive(Debug, Default)]
+struct BundledModules {
+  paths_to_watch: Vec<PathBuf>,
+  source_hashes: HashMap<PathBuf, u64>,
+}
+
+impl BundledModules {
+  fn has_same_sources(&self, changed_paths: &[PathBuf]) -> bool {
+    !changed_paths.is_empty()
+      && changed_paths.iter().all(|path| {
+        let Some(hash) = self.source_hashes.get(path) else {
+          return false;
+        };
+        std::fs::read_to_string(path)
+          .is_ok_and(|source| hash_source(&source) == *hash)
+      })
+  }
+}
+
+fn hash_source(source: &str) -> u64 {
+  let source = source.strip_prefix('\u{FEFF}').unwrap_or(source);
+  FastInsecureHasher::new().write_str(source).finish()
+}
+
 async fn bundle_action(
   factory: CliFactory,
   bundle_flags: &BundleFlags,
-) -> Result<(), AnyError> {
+) -> Result<BundledModules, AnyError> {
   let cli_options = factory.cli_options();
   let module_specifier = cli_options.resolve_main_module()?;
   log::debug!(">>>>> bundle START");
@@ -88,17 +150,79 @@ async fn bundle_action(
   {
     paths_to_watch.push(import_map_path);
   }
+  let source_hashes = graph
+    .modules()
+    .filter_map(|module| {
+      let (specifier, source) = match module {
+        Module::Js(m) => (&m.specifier, &m.source),
+        Module::Json(m) => (&m.specifier, &m.source),
+        Module::Node(_) | Module::Npm(_) | Module::External(_) => return None,
+      };
+      let path = specifier.to_file_path().ok()?;
+      Some((path, hash_source(source)))
+    })
+    .collect();
 
   // at the moment, we don't support npm specifiers in deno bundle, so show an error
   error_for_any_npm_specifier(&graph)?;
 
-  let bundle_output = bundle_module_graph(graph.as_ref(), cli_options)?;
+  if bundle_flags.out_file.is_none()
+    && bundle_flags.sourcemap == Some(BundleSourceMap::External)
+  {
+    bail!("An output file is required to write an external source map.");
+  }
+  if let Some(global_name) = &bundle_flags.global_name {
+    if bundle_flags.format != BundleFormat::Iife {
+      bail!("--global-name can only be used with --format=iife.");
+    }
+    if !lazy_regex::regex!(r"^[A-Za-z_$][\w$]*$").is_match(global_name) {
+      bail!(
+        "--global-name must be a JavaScript identifier: {}",
+        global_name
+      );
+    }
+  }
+
+  let mut bundle_output =
+    bundle_module_graph(graph.as_ref(), cli_options, bundle_flags)?;
   log::debug!(">>>>> bundle END");
+  match (bundle_flags.format, &bundle_flags.global_name) {
+    (BundleFormat::Iife, Some(global_name)) => {
+      bundle_output.code = assign_bundle_exports(
+        &bundle_output.code,
+        &format!("var {global_name} = "),
+      );
+    }
+    (BundleFormat::Cjs, _) => {
+      bundle_output.code =
+        assign_bundle_exports(&bundle_output.code, "module.exports = ");
+    }
+    _ => {}
+  }
   let out_file = &bundle_flags.out_file;
 
   if let Some(out_file) = out_file {
     let out_file = cli_options.initial_cwd().join(out_file);
-    let output_bytes = bundle_output.code.as_bytes();
+    let ext = if let Some(curr_ext) = out_file.extension() {
+      format!("{}.map", curr_ext.to_string_lossy())
+    } else {
+      "map".to_string()
+    };
+    let map_out_file = out_file.with_extension(ext);
+    let mut code = bundle_output.code;
+    let has_source_mapping_url = code
+      .trim_end()
+      .lines()
+      .last()
+      .is_some_and(|line| line.starts_with("//# sourceMappingURL="));
+    if bundle_output.maybe_map.is_some() && !has_source_mapping_url {
+      let map_file_name = map_out_file.file_name().unwrap().to_string_lossy();
+      if !code.ends_with('\n') {
+        code.push('\n');
+      }
+      code.push_str(&format!("//# sourceMappingURL={map_file_name}\n"));
+    }
+    let output_bytes = code.as_bytes();
     let output_len = output_bytes.len();
     util::fs::write_file(&out_file, output_bytes, 0o644)?;
     log::info!(
@@ -110,12 +234,6 @@ async fn bundle_action(
     if let Some(bundle_map) = bundle_output.maybe_map {
       let map_bytes = bundle_map.as_bytes();
       let map_len = map_bytes.len();
-      let ext = if let Some(curr_ext) = out_file.extension() {
-        format!("{}.map", curr_ext.to_string_lossy())
-      } else {
-        "map".to_string()
-      };
-      let map_out_file = out_file.with_extension(ext);
       util::fs::write_file(&map_out_file, map_bytes, 0o644)?;
       log::info!(
         "{} {:?} ({})",
@@ -130,12 +248,16 @@ async fn bundle_action(
       println!("{}", bundle_output.code);
     }
   }
-  Ok(())
+  Ok(BundledModules {
+    paths_to_watch,
+    source_hashes,
+  })
 }
 
 fn bundle_module_graph(
   graph: &deno_graph::ModuleGraph,
   cli_options: &CliOptions,
+  bundle_flags: &BundleFlags,
 ) -> Result<deno_emit::BundleEmit, AnyError> {
   log::info!("{} {}", colors::green("Bundle"), graph.roots[0]);
 
@@ -147,18 +269,65 @@ fn bundle_module_graph(
     }
   }
 
-  let (transpile_options, emit_options) =
+  let (transpile_options, mut emit_options) =
     crate::args::ts_config_to_transpile_and_emit_options(
       ts_config_result.ts_config,
     )?;
+  if let Some(sourcemap) = bundle_flags.sourcemap {
+    emit_options.source_map = match sourcemap {
+      BundleSourceMap::External => SourceMapOption::Separate,
+      BundleSourceMap::Inline => SourceMapOption::Inline,
+      BundleSourceMap::None => SourceMapOption::None,
+    };
+    emit_options.inline_sources = !bundle_flags.no_sources_content;
+  }
   deno_emit::bundle_graph(
     graph,
     deno_emit::BundleOptions {
       minify: false,
-      bundle_type: deno_emit::BundleType::Module,
+      bundle_type: match bundle_flags.format {
+        BundleFormat::Esm => deno_emit::BundleType::Module,
+        // the IIFE of a classic bundle returns the exports of the module
+        BundleFormat::Iife | BundleFormat::Cjs => {
+          deno_emit::BundleType::Classic
+        }
+      },
       emit_options,
       emit_ignore_directives: true,
       transpile_options,
     },
   )
 }
+
+/// Assigns the exports returned by the IIFE of a classic bundle with the
+/// assignment, e.g. `module.exports = `. The IIFE follows the comments at the
+/// start of the bundle and the assignment is inserted on the same line, so
+/// that the lines of the source map stay the same.
+fn assign_bundle_exports(code: &str, assignment: &str) -> String {
+  let mut offset = 0;
+  for line in code.split_inclusive('\n') {
+    let trimmed = line.trim_start();
+    if !trimmed.is_empty() && !trimmed.starts_with("//") {
+      offset += line.len() - trimmed.len();
+      break;
+    }
+    offset += line.len();
+  }
+  format!("{}{}{}", &code[..offset], assignment, &code[offset..])
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn assigns_bundle_exports() {
+    assert_eq!(
+      assign_bundle_exports(
+        "// deno-fmt-ignore-file\n\n(function() {\n    return {};\n})();\n",
+        "module.exports = "
+      ),
+      "// deno-fmt-ignore-file\n\nmodule.exports = (function() {\n    return {};\n})();\n"
+    );
+  }
+}
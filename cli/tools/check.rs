@@ -1,7 +1,10 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::cell::RefCell;
 use std::collections::HashSet;
 use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::rc::Rc;
 use std::sync::Arc;
 
 use deno_ast::MediaType;
@@ -14,7 +17,9 @@ use deno_terminal::colors;
 use once_cell::sync::Lazy;
 use regex::Regex;
 
+use crate::args::CheckFlags;
 use crate::args::CliOptions;
+use crate::args::Flags;
 use crate::args::TsConfig;
 use crate::args::TsConfigType;
 use crate::args::TsTypeLib;
@@ -22,13 +27,125 @@ use crate::args::TypeCheckMode;
 use crate::cache::Caches;
 use crate::cache::FastInsecureHasher;
 use crate::cache::TypeCheckCache;
+use crate::factory::CliFactory;
+use crate::factory::CliFactoryBuilder;
+use crate::graph_container::ModuleGraphContainer;
 use crate::graph_util::BuildFastCheckGraphOptions;
 use crate::graph_util::ModuleGraphBuilder;
 use crate::npm::CliNpmResolver;
 use crate::tsc;
 use crate::tsc::Diagnostics;
+use crate::util::file_watcher;
+use crate::util::progress_bar::ProgressBar;
 use crate::version;
 
+/// Type checks the provided files. In watch mode the factory, with its
+/// caches, is kept across runs and only rebuilt when the configuration
+/// changes. A changed module starts a new module graph, which is built from
+/// those warm caches, and saving a file without changing it is skipped.
+pub async fn check(
+  flags: Flags,
+  check_flags: CheckFlags,
+) -> Result<(), AnyError> {
+  let Some(watch_flags) = &check_flags.watch else {
+    let factory = CliFactory::from_flags(flags)?;
+    let main_graph_container = factory.main_module_graph_container().await?;
+    return main_graph_container
+      .load_and_type_check_files(&check_flags.files)
+      .await;
+  };
+
+  let last_factory: Rc<RefCell<Option<Rc<CliFactory>>>> = Default::default();
+  file_watcher::watch_func(
+    flags,
+    file_watcher::PrintConfig::new("Check", !watch_flags.no_clear_screen),
+    move |flags, watcher_communicator, changed_paths| {
+      let check_flags = check_flags.clone();
+      let last_factory = last_factory.clone();
+      Ok(async move {
+        let maybe_factory = last_factory.borrow().clone();
+        let factory = match (maybe_factory, &changed_paths) {
+          // the configuration is only read when the factory is built
+          (Some(factory), Some(changed_paths))
+            if !has_changed_config(factory.cli_options(), changed_paths) =>
+          {
+            factory
+          }
+          _ => {
+            let factory =
+              Rc::new(CliFactoryBuilder::new().build_from_flags_for_watcher(
+                flags,
+                watcher_communicator.clone(),
+              )?);
+            *last_factory.borrow_mut() = Some(factory.clone());
+            factory
+          }
+        };
+        let cli_options = factory.cli_options();
+        let _ = watcher_communicator.watch_paths(cli_options.watch_paths());
+        let main_graph_container =
+          factory.main_module_graph_container().await?;
+        if let Some(changed_paths) = &changed_paths {
+          let graph = main_graph_container.graph();
+          if has_same_sources(&graph, changed_paths) {
+            // the watcher is recreated for every run
+            let _ = watcher_communicator.watch_paths(local_paths(&graph));
+            log::info!(
+              "{}",
+              colors::gray(
+                "The changed files have the same content. Skipping the check."
+              )
+            );
+            return Ok(());
+          }
+          // deno_graph can't evict a module, so start over with a new graph
+          main_graph_container.clear();
+        }
+        main_graph_container
+          .load_and_type_check_files(&check_flags.files)
+          .await
+      })
+    },
+  )
+  .await
+}
+
+fn has_changed_config(
+  cli_options: &CliOptions,
+  changed_paths: &[PathBuf],
+) -> bool {
+  let config_paths = cli_options.watch_paths();
+  changed_paths.iter().any(|path| config_paths.contains(path))
+}
+
+/// Gets if all the changed files are modules of the graph that still have
+/// the source they were loaded with, such as when a file is saved without
+/// changing it.
+fn has_same_sources(graph: &ModuleGraph, changed_paths: &[PathBuf]) -> bool {
+  !changed_paths.is_empty()
+    && changed_paths.iter().all(|path| {
+      let Ok(specifier) = ModuleSpecifier::from_file_path(path) else {
+        return false;
+      };
+      let source = match graph.get(&specifier) {
+        Some(Module::Js(module)) => &module.source,
+        Some(Module::Json(module)) => &module.source,
+        _ => return false,
+      };
+      std::fs::read_to_string(path).is_ok_and(|text| {
+        text.strip_prefix('\u{FEFF}').unwrap_or(&text) == source.as_ref()
+      })
+    })
+}
+
+fn local_paths(graph: &ModuleGraph) -> Vec<PathBuf> {
+  graph
+    .specifiers()
+    .filter(|(specifier, _)| specifier.scheme() == "file")
+    .filter_map(|(specifier, _)| specifier.to_file_path().ok())
+    .collect()
+}
+
 /// Options for performing a check of a module graph. Note that the decision to
 /// emit or not is determined by the `ts_config` settings.
 pub struct CheckOptions {
@@ -54,6 +171,7 @@ pub struct TypeChecker {
   module_graph_builder: Arc<ModuleGraphBuilder>,
   node_resolver: Arc<NodeResolver>,
   npm_resolver: Arc<dyn CliNpmResolver>,
+  progress_bar: ProgressBar,
 }
 
 impl TypeChecker {
@@ -63,6 +181,7 @@ impl TypeChecker {
     module_graph_builder: Arc<ModuleGraphBuilder>,
     node_resolver: Arc<NodeResolver>,
     npm_resolver: Arc<dyn CliNpmResolver>,
+    progress_bar: ProgressBar,
   ) -> Self {
     Self {
       caches,
@@ -70,6 +189,7 @@ impl TypeChecker {
       module_graph_builder,
       node_resolver,
       npm_resolver,
+      progress_bar,
     }
   }
 
@@ -198,6 +318,7 @@ impl TypeChecker {
       maybe_tsbuildinfo,
       root_names,
       check_mode: type_check_mode,
+      maybe_progress_bar: Some(self.progress_bar.clone()),
     })?;
 
     let mut diagnostics = response.diagnostics.filter(|d| {
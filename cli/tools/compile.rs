@@ -2,15 +2,28 @@
 
 use crate::args::CompileFlags;
 use crate::args::Flags;
+use crate::cache::ParsedSourceCache;
 use crate::factory::CliFactory;
+use crate::graph_util::CreateGraphOptions;
 use crate::standalone::is_standalone_binary;
+use crate::util::fs::collect_specifiers;
+use crate::util::path::is_script_ext;
+use deno_ast::swc::ast;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_config::glob::FilePatterns;
+use deno_config::glob::PathOrPattern;
+use deno_config::glob::PathOrPatternSet;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::resolve_url_or_path;
+use deno_core::ModuleSpecifier;
 use deno_graph::GraphKind;
+use deno_graph::ModuleGraph;
 use deno_terminal::colors;
+use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -27,11 +40,18 @@ pub async fn compile(
   let parsed_source_cache = factory.parsed_source_cache();
   let binary_writer = factory.create_compile_binary_writer().await?;
   let module_specifier = cli_options.resolve_main_module()?;
-  let module_roots = {
+  let mut module_roots = {
     let mut vec = Vec::with_capacity(compile_flags.include.len() + 1);
     vec.push(module_specifier.clone());
     for side_module in &compile_flags.include {
-      vec.push(resolve_url_or_path(side_module, cli_options.initial_cwd())?);
+      let specifier =
+        resolve_url_or_path(side_module, cli_options.initial_cwd())?;
+      match specifier.to_file_path() {
+        Ok(path) if path.is_dir() => {
+          vec.extend(collect_included_dir(path)?);
+        }
+        _ => vec.push(specifier),
+      }
     }
     vec
   };
@@ -54,12 +74,45 @@ pub async fn compile(
   )
   .await?;
 
-  let graph = Arc::try_unwrap(
+  let graph_kind = cli_options.type_check_mode().as_graph_kind();
+  let mut graph = module_graph_creator
+    .create_graph_with_options(CreateGraphOptions {
+      graph_kind,
+      roots: module_roots.clone(),
+      is_dynamic: false,
+      loader: None,
+    })
+    .await?;
+  // the workers may spawn workers of their own, so repeat until no new
+  // entrypoints are found
+  let mut scanned_modules = HashSet::new();
+  loop {
+    let worker_entrypoints = find_worker_entrypoints(
+      &graph,
+      parsed_source_cache,
+      &mut scanned_modules,
+    )?;
+    if worker_entrypoints.is_empty() {
+      break;
+    }
+    for entrypoint in &worker_entrypoints {
+      log::debug!("Including worker entrypoint {}", entrypoint);
+    }
+    module_roots.extend(worker_entrypoints.iter().cloned());
     module_graph_creator
-      .create_graph_and_maybe_check(module_roots.clone())
-      .await?,
-  )
-  .unwrap();
+      .add_graph_roots(
+        &mut graph,
+        CreateGraphOptions {
+          graph_kind,
+          roots: worker_entrypoints,
+          is_dynamic: false,
+          loader: None,
+        },
+      )
+      .await?;
+  }
+  let graph =
+    Arc::try_unwrap(module_graph_creator.check_graph(graph).await?).unwrap();
   let graph = if cli_options.type_check_mode().is_true() {
     // In this case, the previous graph creation did type checking, which will
     // create a module graph with types information in it. We don't want to
@@ -93,11 +146,9 @@ pub async fn compile(
   );
   validate_output_path(&output_path)?;
 
-  let mut file = std::fs::File::create(&output_path)
-    .with_context(|| format!("Opening file '{}'", output_path.display()))?;
   let write_result = binary_writer
     .write_bin(
-      &mut file,
+      &output_path,
       eszip,
       &module_specifier,
       &compile_flags,
@@ -105,22 +156,124 @@ pub async fn compile(
     )
     .await
     .with_context(|| format!("Writing {}", output_path.display()));
-  drop(file);
   if let Err(err) = write_result {
     // errored, so attempt to remove the output path
     let _ = std::fs::remove_file(output_path);
     return Err(err);
   }
 
-  // set it as executable
-  #[cfg(unix)]
-  {
-    use std::os::unix::fs::PermissionsExt;
-    let perms = std::fs::Permissions::from_mode(0o777);
-    std::fs::set_permissions(output_path, perms)?;
+  Ok(())
+}
+
+/// Finds the local worker entrypoints spawned by the modules of the graph
+/// that aren't in the graph yet. Workers aren't imported, so they would be
+/// missing from the executable otherwise. Each module is only scanned once.
+fn find_worker_entrypoints(
+  graph: &ModuleGraph,
+  parsed_source_cache: &ParsedSourceCache,
+  scanned_modules: &mut HashSet<ModuleSpecifier>,
+) -> Result<Vec<ModuleSpecifier>, AnyError> {
+  let mut entrypoints = Vec::new();
+  for module in graph.modules() {
+    let deno_graph::Module::Js(module) = module else {
+      continue;
+    };
+    if module.specifier.scheme() != "file"
+      || !scanned_modules.insert(module.specifier.clone())
+    {
+      continue;
+    }
+    let parsed_source =
+      parsed_source_cache.get_parsed_source_from_js_module(module)?;
+    let mut collector = WorkerUrlCollector::default();
+    parsed_source.module().visit_with(&mut collector);
+    for url in collector.urls {
+      let Ok(specifier) = module.specifier.join(&url) else {
+        continue;
+      };
+      // a missing worker fails when it's spawned, like it does when the
+      // module isn't compiled
+      let is_file = specifier
+        .to_file_path()
+        .map(|path| path.is_file())
+        .unwrap_or(false);
+      if is_file
+        && graph.get(&specifier).is_none()
+        && !entrypoints.contains(&specifier)
+      {
+        entrypoints.push(specifier);
+      }
+    }
   }
+  Ok(entrypoints)
+}
 
-  Ok(())
+/// Collects the URLs of `new Worker(new URL("./worker.ts", import.meta.url))`,
+/// which is how a worker entrypoint relative to the module is usually
+/// specified.
+#[derive(Default)]
+struct WorkerUrlCollector {
+  urls: Vec<String>,
+}
+
+impl Visit for WorkerUrlCollector {
+  fn visit_new_expr(&mut self, node: &ast::NewExpr) {
+    if let Some(url) = worker_url(node) {
+      self.urls.push(url);
+    }
+    node.visit_children_with(self);
+  }
+}
+
+fn worker_url(node: &ast::NewExpr) -> Option<String> {
+  if !is_ident(&node.callee, "Worker") {
+    return None;
+  }
+  let ast::Expr::New(url) = &*node.args.as_ref()?.first()?.expr else {
+    return None;
+  };
+  if !is_ident(&url.callee, "URL") {
+    return None;
+  }
+  let [specifier, base] = url.args.as_deref()? else {
+    return None;
+  };
+  let ast::Expr::Lit(ast::Lit::Str(specifier)) = &*specifier.expr else {
+    return None;
+  };
+  let ast::Expr::Member(base) = &*base.expr else {
+    return None;
+  };
+  let is_import_meta = matches!(
+    &*base.obj,
+    ast::Expr::MetaProp(meta) if meta.kind == ast::MetaPropKind::ImportMeta
+  );
+  let is_url = matches!(
+    &base.prop,
+    ast::MemberProp::Ident(prop) if &*prop.sym == "url"
+  );
+  (is_import_meta && is_url).then(|| specifier.value.to_string())
+}
+
+fn is_ident(expr: &ast::Expr, name: &str) -> bool {
+  matches!(expr, ast::Expr::Ident(ident) if &*ident.sym == name)
+}
+
+/// Collects the modules in a directory passed to `--include`, so that any of
+/// them can be imported dynamically in the executable.
+fn collect_included_dir(
+  dir: PathBuf,
+) -> Result<Vec<ModuleSpecifier>, AnyError> {
+  collect_specifiers(
+    FilePatterns {
+      base: dir.clone(),
+      include: Some(PathOrPatternSet::new(vec![PathOrPattern::Path(dir)])),
+      exclude: PathOrPatternSet::new(vec![]),
+    },
+    None,
+    false,
+    |entry| is_script_ext(entry.path),
+  )
 }
 
 /// This function writes out a final binary to specified path. If output path
@@ -245,6 +398,8 @@ mod test {
         target: Some("x86_64-unknown-linux-gnu".to_string()),
         no_terminal: false,
         include: vec![],
+        icon: None,
+        exe_metadata: None,
       },
       &std::env::current_dir().unwrap(),
     )
@@ -267,6 +422,8 @@ mod test {
         target: Some("x86_64-pc-windows-msvc".to_string()),
         include: vec![],
         no_terminal: false,
+        icon: None,
+        exe_metadata: None,
       },
       &std::env::current_dir().unwrap(),
     )
@@ -301,4 +458,30 @@ mod test {
     run_test("C:\\my-exe.0.1.2", Some("windows"), "C:\\my-exe.0.1.2.exe");
     run_test("my-exe-0.1.2", Some("linux"), "my-exe-0.1.2");
   }
+
+  #[test]
+  fn collects_worker_urls() {
+    let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: ModuleSpecifier::parse("file:///main.ts").unwrap(),
+      text_info: deno_ast::SourceTextInfo::from_string(
+        r#"
+// new Worker(new URL("./comment.ts", import.meta.url));
+const text = 'new Worker(new URL("./string.ts", import.meta.url))';
+new Worker(new URL("./worker.ts", import.meta.url), { type: "module" });
+new Worker(new URL('./nested/worker.js', import.meta.url));
+new Worker(new URL("./other.ts", location.href));
+new Worker("./string_url.ts");
+"#
+        .to_string(),
+      ),
+      media_type: deno_ast::MediaType::TypeScript,
+      capture_tokens: false,
+      scope_analysis: false,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    let mut collector = WorkerUrlCollector::default();
+    parsed_source.module().visit_with(&mut collector);
+    assert_eq!(collector.urls, vec!["./worker.ts", "./nested/worker.js"]);
+  }
 }
@@ -2,6 +2,8 @@
 
 use crate::args::CliOptions;
 use crate::args::CoverageFlags;
+use crate::args::CoverageThresholds;
+use crate::args::CoverageType;
 use crate::args::FileFlags;
 use crate::args::Flags;
 use crate::cdp;
@@ -27,6 +29,7 @@ use deno_core::url::Url;
 use deno_core::LocalInspectorSession;
 use deno_core::ModuleCodeString;
 use regex::Regex;
+use std::collections::HashMap;
 use std::fs;
 use std::fs::File;
 use std::io::BufWriter;
@@ -44,6 +47,8 @@ use merge::ProcessCoverage;
 
 pub struct CoverageCollector {
   pub dir: PathBuf,
+  /// The files of the `test.coverage` configuration to collect.
+  files: Option<FilePatterns>,
   session: LocalInspectorSession,
 }
 
@@ -75,6 +80,9 @@ impl crate::worker::CoverageCollector for CoverageCollector {
       {
         continue;
       }
+      if !is_included_by_config(self.files.as_ref(), &script_coverage.url) {
+        continue;
+      }
 
       let filename = format!("{}.json", Uuid::new_v4());
       let filepath = self.dir.join(filename);
@@ -99,8 +107,16 @@ impl crate::worker::CoverageCollector for CoverageCollector {
 }
 
 impl CoverageCollector {
-  pub fn new(dir: PathBuf, session: LocalInspectorSession) -> Self {
-    Self { dir, session }
+  pub fn new(
+    dir: PathBuf,
+    files: Option<FilePatterns>,
+    session: LocalInspectorSession,
+  ) -> Self {
+    Self {
+      dir,
+      files,
+      session,
+    }
   }
 
   async fn enable_debugger(&mut self) -> Result<(), AnyError> {
@@ -240,9 +256,31 @@ fn generate_coverage_report(
     });
   }
 
-  for (block_number, function) in script_coverage.functions.iter().enumerate() {
-    let block_hits = function.ranges[0].count;
-    for (branch_number, range) in function.ranges[1..].iter().enumerate() {
+  // The block ranges of V8 are branches of the range that encloses them, so
+  // the ranges with the same enclosing range are reported as the branches of
+  // one block. The ranges of a function are sorted by their start offset, so
+  // the enclosing range of a range always comes before it.
+  let mut block_count = 0;
+  for function in &script_coverage.functions {
+    let ranges = &function.ranges;
+    // the block number and the number of branches for each enclosing range
+    let mut blocks = HashMap::<usize, (usize, usize)>::new();
+    for (index, range) in ranges.iter().enumerate().skip(1) {
+      let enclosing_index = (0..index)
+        .rev()
+        .find(|i| {
+          ranges[*i].start_char_offset <= range.start_char_offset
+            && ranges[*i].end_char_offset >= range.end_char_offset
+        })
+        .unwrap_or(0);
+      let (block_number, branch_count) =
+        blocks.entry(enclosing_index).or_insert_with(|| {
+          block_count += 1;
+          (block_count - 1, 0)
+        });
+      let branch_number = *branch_count;
+      *branch_count += 1;
+
       let line_index =
         range_to_src_line_index(range, &text_lines, &maybe_source_map);
 
@@ -251,11 +289,7 @@ fn generate_coverage_report(
       // Block number and branch number are gcc internal IDs for the branch. Taken is either '-'
       // if the basic block containing the branch was never executed or a number indicating how
       // often that branch was taken.
-      //
-      // However with the data we get from v8 coverage profiles it seems we can't actually hit
-      // this as appears it won't consider any nested branches it hasn't seen but its here for
-      // the sake of accuracy.
-      let taken = if block_hits > 0 {
+      let taken = if ranges[enclosing_index].count > 0 {
         Some(range.count)
       } else {
         None
@@ -263,7 +297,7 @@ fn generate_coverage_report(
 
       coverage_report.branches.push(BranchCoverageItem {
         line_index,
-        block_number,
+        block_number: *block_number,
         branch_number,
         taken,
         is_hit: range.count > 0,
@@ -424,10 +458,23 @@ fn collect_coverages(
   Ok(coverages)
 }
 
+/// Checks whether the script matches the include and exclude globs of the
+/// `test.coverage` configuration, which only apply to local files.
+fn is_included_by_config(files: Option<&FilePatterns>, url: &str) -> bool {
+  let Some(files) = files else {
+    return true;
+  };
+  match Url::parse(url) {
+    Ok(url) if url.scheme() == "file" => files.matches_specifier(&url),
+    _ => true,
+  }
+}
+
 fn filter_coverages(
   coverages: Vec<cdp::ScriptCoverage>,
   include: Vec<String>,
   exclude: Vec<String>,
+  files: Option<&FilePatterns>,
   npm_resolver: &dyn CliNpmResolver,
 ) -> Vec<cdp::ScriptCoverage> {
   let include: Vec<Regex> =
@@ -452,7 +499,10 @@ fn filter_coverages(
       let is_included = include.iter().any(|p| p.is_match(&e.url));
       let is_excluded = exclude.iter().any(|p| p.is_match(&e.url));
 
-      (include.is_empty() || is_included) && !is_excluded && !is_internal
+      (include.is_empty() || is_included)
+        && !is_excluded
+        && !is_internal
+        && is_included_by_config(files, &e.url)
     })
     .collect::<Vec<cdp::ScriptCoverage>>()
 }
@@ -471,6 +521,20 @@ pub async fn cover_files(
   let cli_options = factory.cli_options();
   let emitter = factory.emitter()?;
 
+  let coverage_config = cli_options.resolve_coverage_config()?;
+  let maybe_thresholds = if coverage_flags.check {
+    let thresholds =
+      CoverageThresholds::resolve(coverage_config.thresholds, &coverage_flags);
+    if thresholds == CoverageThresholds::default() {
+      return Err(generic_error(
+        "No coverage thresholds were set. Pass --lines or --branches, or set them under \"test.coverage\" in the configuration file.",
+      ));
+    }
+    Some(thresholds)
+  } else {
+    None
+  };
+
   assert!(!coverage_flags.files.include.is_empty());
 
   // Use the first include path as the default output path.
@@ -489,6 +553,7 @@ pub async fn cover_files(
     script_coverages,
     coverage_flags.include,
     coverage_flags.exclude,
+    coverage_config.files.as_ref(),
     npm_resolver.as_ref(),
   );
   if script_coverages.is_empty() {
@@ -507,9 +572,14 @@ pub async fn cover_files(
     vec![]
   };
 
-  let mut reporter = reporter::create(coverage_flags.r#type);
+  let mut reporter = reporter::create(coverage_flags.r#type.clone());
+  let mut totals = CoverageTotals::default();
 
   let out_mode = match coverage_flags.output {
+    // the html report is a directory, which is created by the reporter
+    Some(ref path) if coverage_flags.r#type == CoverageType::Html => {
+      Some(PathBuf::from(path))
+    }
     Some(ref path) => match File::create(path) {
       Ok(_) => Some(PathBuf::from(path)),
       Err(e) => {
@@ -584,10 +654,76 @@ pub async fn cover_files(
 
     if !coverage_report.found_lines.is_empty() {
       reporter.report(&coverage_report, &original_source)?;
+      totals.add(&coverage_report);
     }
   }
 
   reporter.done(&coverage_root);
 
+  if let Some(thresholds) = maybe_thresholds {
+    check_thresholds(&thresholds, &totals)?;
+  }
+
+  Ok(())
+}
+
+/// The covered and total lines and branches of all the reported files.
+#[derive(Debug, Default)]
+struct CoverageTotals {
+  line_hit: usize,
+  line_total: usize,
+  branch_hit: usize,
+  branch_total: usize,
+}
+
+impl CoverageTotals {
+  fn add(&mut self, report: &CoverageReport) {
+    self.line_hit += report
+      .found_lines
+      .iter()
+      .filter(|(_, count)| *count > 0)
+      .count();
+    self.line_total += report.found_lines.len();
+    self.branch_hit += report.branches.iter().filter(|b| b.is_hit).count();
+    self.branch_total += report.branches.len();
+  }
+}
+
+/// Fails when the coverage of `deno coverage --check` is below a threshold.
+fn check_thresholds(
+  thresholds: &CoverageThresholds,
+  totals: &CoverageTotals,
+) -> Result<(), AnyError> {
+  let checks = [
+    ("line", thresholds.lines, totals.line_hit, totals.line_total),
+    (
+      "branch",
+      thresholds.branches,
+      totals.branch_hit,
+      totals.branch_total,
+    ),
+  ];
+  let mut failures = Vec::new();
+  for (kind, threshold, hit, total) in checks {
+    let Some(threshold) = threshold else {
+      continue;
+    };
+    let percent = if total == 0 {
+      100.0
+    } else {
+      hit as f64 * 100.0 / total as f64
+    };
+    if percent < threshold as f64 {
+      failures.push(format!(
+        "{kind} coverage {percent:.1}% is below the threshold of {threshold}%"
+      ));
+    }
+  }
+  if !failures.is_empty() {
+    return Err(generic_error(format!(
+      "Coverage check failed: {}",
+      failures.join(", ")
+    )));
+  }
   Ok(())
 }
@@ -34,6 +34,7 @@ pub fn create(kind: CoverageType) -> Box<dyn CoverageReporter + Send> {
     CoverageType::Lcov => Box::new(LcovCoverageReporter::new()),
     CoverageType::Detailed => Box::new(DetailedCoverageReporter::new()),
     CoverageType::Html => Box::new(HtmlCoverageReporter::new()),
+    CoverageType::Cobertura => Box::new(CoberturaCoverageReporter::new()),
   }
 }
 
@@ -400,10 +401,15 @@ impl CoverageReporter for HtmlCoverageReporter {
   fn done(&mut self, coverage_root: &Path) {
     let summary = self.collect_summary(&self.file_reports);
     let now = crate::util::time::utc_now().to_rfc2822();
+    let html_root = self
+      .file_reports
+      .first()
+      .and_then(|(report, _)| report.output.clone())
+      .unwrap_or_else(|| coverage_root.join("html"));
 
     for (node, stats) in &summary {
       let report_path =
-        self.get_report_path(coverage_root, node, stats.file_text.is_none());
+        self.get_report_path(&html_root, node, stats.file_text.is_none());
       let main_content = if let Some(file_text) = &stats.file_text {
         self.create_html_code_table(file_text, stats.report.unwrap())
       } else {
@@ -415,14 +421,9 @@ impl CoverageReporter for HtmlCoverageReporter {
       fs::write(report_path, html).unwrap();
     }
 
-    let root_report = Url::from_file_path(
-      coverage_root
-        .join("html")
-        .join("index.html")
-        .canonicalize()
-        .unwrap(),
-    )
-    .unwrap();
+    let root_report =
+      Url::from_file_path(html_root.join("index.html").canonicalize().unwrap())
+        .unwrap();
 
     log::info!("HTML coverage report has been generated at {}", root_report);
   }
@@ -438,20 +439,17 @@ impl HtmlCoverageReporter {
   /// Gets the report path for a single file
   pub fn get_report_path(
     &self,
-    coverage_root: &Path,
+    html_root: &Path,
     node: &str,
     is_dir: bool,
   ) -> PathBuf {
     if is_dir {
       // e.g. /path/to/coverage/html/src/index.html
-      coverage_root.join("html").join(node).join("index.html")
+      html_root.join(node).join("index.html")
     } else {
       // e.g. /path/to/coverage/html/src/main.ts.html
-      Path::new(&format!(
-        "{}.html",
-        coverage_root.join("html").join(node).to_str().unwrap()
-      ))
-      .to_path_buf()
+      Path::new(&format!("{}.html", html_root.join(node).to_str().unwrap()))
+        .to_path_buf()
     }
   }
 
@@ -682,3 +680,246 @@ impl HtmlCoverageReporter {
     )
   }
 }
+
+struct CoberturaCoverageReporter {
+  file_reports: Vec<(CoverageReport, String)>,
+}
+
+impl CoverageReporter for CoberturaCoverageReporter {
+  fn report(
+    &mut self,
+    report: &CoverageReport,
+    text: &str,
+  ) -> Result<(), AnyError> {
+    self.file_reports.push((report.clone(), text.to_string()));
+    Ok(())
+  }
+
+  fn done(&mut self, _coverage_root: &Path) {
+    let Some(first_report) = self.file_reports.first() else {
+      return;
+    };
+    let output = first_report.0.output.clone();
+    let xml = self.create_xml();
+
+    // pipes output to stdout if no file is specified
+    let out_mode: Result<Box<dyn Write>, Error> = match output {
+      Some(ref path) => {
+        File::create(path).map(|f| Box::new(f) as Box<dyn Write>)
+      }
+      None => Ok(Box::new(io::stdout())),
+    };
+    out_mode
+      .and_then(|mut out_writer| out_writer.write_all(xml.as_bytes()))
+      .unwrap();
+  }
+}
+
+impl CoberturaCoverageReporter {
+  pub fn new() -> CoberturaCoverageReporter {
+    CoberturaCoverageReporter {
+      file_reports: Vec::new(),
+    }
+  }
+
+  /// Creates the whole Cobertura XML document.
+  pub fn create_xml(&self) -> String {
+    let urls = self.file_reports.iter().map(|rep| &rep.0.url).collect();
+    let root = util::find_root(urls)
+      .and_then(|root| root.to_file_path().ok())
+      .unwrap_or_default();
+
+    // Cobertura groups classes (files) by package (directory)
+    let mut packages: Vec<(String, Vec<&CoverageReport>)> = Vec::new();
+    for (report, _) in &self.file_reports {
+      let package_name = self
+        .relative_path(&root, report)
+        .rsplit_once('/')
+        .map(|(dir, _)| dir.replace('/', "."))
+        .unwrap_or_default();
+      match packages.iter_mut().find(|(name, _)| *name == package_name) {
+        Some((_, reports)) => reports.push(report),
+        None => packages.push((package_name, vec![report])),
+      }
+    }
+    packages.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let all_reports = self.file_reports.iter().map(|(r, _)| r);
+    let (line_hit, line_total, branch_hit, branch_total) =
+      count_coverage(all_reports);
+    let line_rate = calc_rate(line_hit, line_total);
+    let branch_rate = calc_rate(branch_hit, branch_total);
+    let timestamp = crate::util::time::utc_now().timestamp_millis();
+    let version = env!("CARGO_PKG_VERSION");
+    let source = escape_xml(&root.to_string_lossy());
+
+    let packages = packages
+      .iter()
+      .map(|(name, reports)| self.create_package_xml(&root, name, reports))
+      .collect::<Vec<_>>()
+      .join("");
+
+    format!(
+      "<?xml version=\"1.0\" ?>
+<!DOCTYPE coverage SYSTEM \"http://cobertura.sourceforge.net/xml/coverage-04.dtd\">
+<coverage line-rate=\"{line_rate:.4}\" branch-rate=\"{branch_rate:.4}\" lines-covered=\"{line_hit}\" lines-valid=\"{line_total}\" branches-covered=\"{branch_hit}\" branches-valid=\"{branch_total}\" complexity=\"0\" version=\"deno {version}\" timestamp=\"{timestamp}\">
+  <sources>
+    <source>{source}</source>
+  </sources>
+  <packages>
+{packages}  </packages>
+</coverage>
+"
+    )
+  }
+
+  /// Creates <package> element for the files of a single directory.
+  fn create_package_xml(
+    &self,
+    root: &Path,
+    name: &str,
+    reports: &[&CoverageReport],
+  ) -> String {
+    let (line_hit, line_total, branch_hit, branch_total) =
+      count_coverage(reports.iter().copied());
+    let line_rate = calc_rate(line_hit, line_total);
+    let branch_rate = calc_rate(branch_hit, branch_total);
+    let classes = reports
+      .iter()
+      .map(|report| self.create_class_xml(root, report))
+      .collect::<Vec<_>>()
+      .join("");
+    let name = escape_xml(name);
+    format!(
+      "    <package name=\"{name}\" line-rate=\"{line_rate:.4}\" branch-rate=\"{branch_rate:.4}\" complexity=\"0\">
+      <classes>
+{classes}      </classes>
+    </package>
+"
+    )
+  }
+
+  /// Creates <class> element for a single file.
+  fn create_class_xml(&self, root: &Path, report: &CoverageReport) -> String {
+    let (line_hit, line_total, branch_hit, branch_total) =
+      count_coverage(std::iter::once(report));
+    let line_rate = calc_rate(line_hit, line_total);
+    let branch_rate = calc_rate(branch_hit, branch_total);
+    let filename = self.relative_path(root, report);
+    let name = filename
+      .rsplit_once('/')
+      .map(|(_, name)| name)
+      .unwrap_or(&filename);
+
+    let methods = report
+      .named_functions
+      .iter()
+      .map(|function| {
+        let hits = function.execution_count;
+        let line_rate = if hits > 0 { 1 } else { 0 };
+        format!(
+          "            <method name=\"{name}\" signature=\"\" line-rate=\"{line_rate}\" branch-rate=\"0\" complexity=\"0\">
+              <lines>
+                <line number=\"{line}\" hits=\"{hits}\"/>
+              </lines>
+            </method>
+",
+          name = escape_xml(&function.name),
+          line = function.line_index + 1,
+        )
+      })
+      .collect::<Vec<_>>()
+      .join("");
+
+    let lines = report
+      .found_lines
+      .iter()
+      .map(|(index, hits)| {
+        let branches = report
+          .branches
+          .iter()
+          .filter(|b| b.line_index == *index)
+          .collect::<Vec<_>>();
+        if branches.is_empty() {
+          format!(
+            "            <line number=\"{}\" hits=\"{hits}\" branch=\"false\"/>\n",
+            index + 1
+          )
+        } else {
+          let covered = branches.iter().filter(|b| b.is_hit).count();
+          let total = branches.len();
+          let percent = covered * 100 / total;
+          format!(
+            "            <line number=\"{}\" hits=\"{hits}\" branch=\"true\" condition-coverage=\"{percent}% ({covered}/{total})\"/>\n",
+            index + 1
+          )
+        }
+      })
+      .collect::<Vec<_>>()
+      .join("");
+
+    format!(
+      "        <class name=\"{name}\" filename=\"{filename}\" line-rate=\"{line_rate:.4}\" branch-rate=\"{branch_rate:.4}\" complexity=\"0\">
+          <methods>
+{methods}          </methods>
+          <lines>
+{lines}          </lines>
+        </class>
+",
+      name = escape_xml(name),
+      filename = escape_xml(&filename),
+    )
+  }
+
+  /// Gets the path of the report relative to the common root, always
+  /// separated by forward slashes.
+  fn relative_path(&self, root: &Path, report: &CoverageReport) -> String {
+    report
+      .url
+      .to_file_path()
+      .ok()
+      .and_then(|path| {
+        path
+          .strip_prefix(root)
+          .ok()
+          .map(|p| p.to_string_lossy().replace('\\', "/"))
+      })
+      .unwrap_or_else(|| report.url.to_string())
+  }
+}
+
+/// Returns (line hit, line total, branch hit, branch total) for the given
+/// reports.
+fn count_coverage<'a>(
+  reports: impl Iterator<Item = &'a CoverageReport>,
+) -> (usize, usize, usize, usize) {
+  let mut counts = (0, 0, 0, 0);
+  for report in reports {
+    counts.0 += report
+      .found_lines
+      .iter()
+      .filter(|(_, count)| *count > 0)
+      .count();
+    counts.1 += report.found_lines.len();
+    counts.2 += report.branches.iter().filter(|b| b.is_hit).count();
+    counts.3 += report.branches.len();
+  }
+  counts
+}
+
+fn calc_rate(hit: usize, total: usize) -> f32 {
+  if total == 0 {
+    1.0
+  } else {
+    hit as f32 / total as f32
+  }
+}
+
+fn escape_xml(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+    .replace('\'', "&apos;")
+}
@@ -2,6 +2,7 @@
 
 use crate::args::DocFlags;
 use crate::args::DocHtmlFlag;
+use crate::args::DocMarkdownFlag;
 use crate::args::DocSourceFileFlag;
 use crate::args::Flags;
 use crate::colors;
@@ -11,12 +12,13 @@ use crate::factory::CliFactory;
 use crate::graph_util::graph_lock_or_exit;
 use crate::tsc::get_types_declaration_file_text;
 use crate::util::fs::collect_specifiers;
+use crate::util::fs::include_path_or_patterns;
 use deno_ast::diagnostics::Diagnostic;
 use deno_config::glob::FilePatterns;
-use deno_config::glob::PathOrPatternSet;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
+use deno_core::serde_json;
 use deno_doc as doc;
 use deno_doc::html::UrlResolveKind;
 use deno_graph::source::NullFileSystem;
@@ -24,10 +26,20 @@ use deno_graph::GraphKind;
 use deno_graph::ModuleAnalyzer;
 use deno_graph::ModuleParser;
 use deno_graph::ModuleSpecifier;
+use deno_runtime::deno_node::NodeResolution;
+use deno_runtime::deno_node::NodeResolutionMode;
+use deno_runtime::permissions::PermissionsContainer;
+use deno_semver::npm::NpmPackageReqReference;
+use deno_semver::Version;
 use doc::html::ShortPath;
 use doc::DocDiagnostic;
 use indexmap::IndexMap;
+use regex::Captures;
+use serde::Serialize;
 use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
 use std::rc::Rc;
 
 async fn generate_doc_nodes_for_builtin_types(
@@ -90,7 +102,7 @@ pub async fn doc(flags: Flags, doc_flags: DocFlags) -> Result<(), AnyError> {
   let capturing_parser = parsed_source_cache.as_capturing_parser();
   let analyzer = module_info_cache.as_module_analyzer(&capturing_parser);
 
-  let doc_nodes_by_url = match doc_flags.source_files {
+  let mut doc_nodes_by_url = match doc_flags.source_files {
     DocSourceFileFlag::Builtin => {
       generate_doc_nodes_for_builtin_types(
         doc_flags.clone(),
@@ -106,19 +118,34 @@ pub async fn doc(flags: Flags, doc_flags: DocFlags) -> Result<(), AnyError> {
       let module_specifiers = collect_specifiers(
         FilePatterns {
           base: cli_options.initial_cwd().to_path_buf(),
-          include: Some(
-            PathOrPatternSet::from_include_relative_path_or_patterns(
-              cli_options.initial_cwd(),
-              source_files,
-            )?,
-          ),
+          include: Some(include_path_or_patterns(
+            cli_options.initial_cwd(),
+            source_files,
+          )?),
           exclude: Default::default(),
         },
         cli_options.vendor_dir_path().map(ToOwned::to_owned),
+        false,
         |_| true,
       )?;
+      // npm packages are documented from their types
+      let npm_types_specifiers = resolve_npm_types_specifiers(
+        &factory,
+        &module_specifiers,
+        cli_options.initial_cwd(),
+      )
+      .await?;
+      let graph_roots = module_specifiers
+        .iter()
+        .map(|specifier| {
+          npm_types_specifiers
+            .get(specifier)
+            .unwrap_or(specifier)
+            .clone()
+        })
+        .collect::<Vec<_>>();
       let graph = module_graph_creator
-        .create_graph(GraphKind::TypesOnly, module_specifiers.clone())
+        .create_graph(GraphKind::TypesOnly, graph_roots.clone())
         .await?;
 
       if let Some(lockfile) = maybe_lockfile {
@@ -137,20 +164,44 @@ pub async fn doc(flags: Flags, doc_flags: DocFlags) -> Result<(), AnyError> {
       let mut doc_nodes_by_url =
         IndexMap::with_capacity(module_specifiers.len());
 
-      for module_specifier in module_specifiers {
-        let nodes = doc_parser.parse_with_reexports(&module_specifier)?;
+      for (module_specifier, graph_root) in
+        module_specifiers.into_iter().zip(graph_roots)
+      {
+        let nodes = doc_parser.parse_with_reexports(&graph_root)?;
         doc_nodes_by_url.insert(module_specifier, nodes);
       }
 
       if doc_flags.lint {
         let diagnostics = doc_parser.take_diagnostics();
-        check_diagnostics(&diagnostics)?;
+        let mut param_diagnostics = Vec::new();
+        collect_param_description_diagnostics(
+          &mut param_diagnostics,
+          doc_nodes_by_url.values().flatten(),
+        );
+        check_diagnostics(&diagnostics, &param_diagnostics)?;
       }
 
       doc_nodes_by_url
     }
   };
 
+  if !doc_flags.include_tags.is_empty() || !doc_flags.exclude_tags.is_empty() {
+    let tag_filter = TagFilter {
+      include: &doc_flags.include_tags,
+      exclude: &doc_flags.exclude_tags,
+    };
+    for doc_nodes in doc_nodes_by_url.values_mut() {
+      tag_filter.filter(doc_nodes);
+    }
+  }
+
+  if let Some(search_index) = &doc_flags.search_index {
+    write_search_index(
+      &cli_options.initial_cwd().join(search_index),
+      &doc_nodes_by_url,
+    )?;
+  }
+
   if let Some(html_options) = &doc_flags.html {
     let deno_ns = if doc_flags.source_files != DocSourceFileFlag::Builtin {
       let deno_ns = generate_doc_nodes_for_builtin_types(
@@ -186,6 +237,12 @@ pub async fn doc(flags: Flags, doc_flags: DocFlags) -> Result<(), AnyError> {
     };
 
     generate_docs_directory(doc_nodes_by_url, html_options, deno_ns)
+  } else if let Some(markdown_options) = &doc_flags.markdown {
+    generate_markdown_directory(
+      &doc_nodes_by_url,
+      markdown_options,
+      cli_options.initial_cwd(),
+    )
   } else {
     let modules_len = doc_nodes_by_url.len();
     let doc_nodes =
@@ -207,6 +264,246 @@ pub async fn doc(flags: Flags, doc_flags: DocFlags) -> Result<(), AnyError> {
   }
 }
 
+/// Filters the documented symbols by their JSDoc tags. The included tags
+/// only apply to the exported symbols, while the excluded tags also apply to
+/// the members of classes, interfaces and namespaces.
+struct TagFilter<'a> {
+  include: &'a [String],
+  exclude: &'a [String],
+}
+
+impl TagFilter<'_> {
+  fn filter(&self, doc_nodes: &mut Vec<doc::DocNode>) {
+    doc_nodes.retain(|doc_node| {
+      // imports and the module documentation aren't symbols
+      matches!(
+        doc_node.kind,
+        doc::DocNodeKind::Import | doc::DocNodeKind::ModuleDoc
+      ) || (!has_any_tag(&doc_node.js_doc, self.exclude)
+        && (self.include.is_empty()
+          || has_any_tag(&doc_node.js_doc, self.include)))
+    });
+    for doc_node in doc_nodes.iter_mut() {
+      self.filter_members(doc_node);
+    }
+  }
+
+  fn filter_members(&self, doc_node: &mut doc::DocNode) {
+    let exclude = self.exclude;
+    if let Some(class_def) = &mut doc_node.class_def {
+      class_def
+        .constructors
+        .retain(|constructor| !has_any_tag(&constructor.js_doc, exclude));
+      class_def
+        .properties
+        .retain(|property| !has_any_tag(&property.js_doc, exclude));
+      class_def
+        .methods
+        .retain(|method| !has_any_tag(&method.js_doc, exclude));
+    }
+    if let Some(interface_def) = &mut doc_node.interface_def {
+      interface_def
+        .properties
+        .retain(|property| !has_any_tag(&property.js_doc, exclude));
+      interface_def
+        .methods
+        .retain(|method| !has_any_tag(&method.js_doc, exclude));
+    }
+    if let Some(namespace_def) = &mut doc_node.namespace_def {
+      namespace_def
+        .elements
+        .retain(|element| !has_any_tag(&element.js_doc, exclude));
+      for element in namespace_def.elements.iter_mut() {
+        self.filter_members(std::sync::Arc::make_mut(element));
+      }
+    }
+  }
+}
+
+fn has_any_tag(js_doc: &doc::js_doc::JsDoc, tags: &[String]) -> bool {
+  js_doc.tags.iter().any(|tag| {
+    let names = js_doc_tag_names(tag);
+    tags
+      .iter()
+      .any(|tag| names.contains(&tag.trim_start_matches('@')))
+  })
+}
+
+/// Gets the names that a JSDoc tag is written with, since deno_doc parses
+/// some tags with several names (ex. `@return` and `@returns`).
+fn js_doc_tag_names(tag: &doc::js_doc::JsDocTag) -> Vec<&str> {
+  use doc::js_doc::JsDocTag;
+
+  match tag {
+    JsDocTag::Callback { .. } => vec!["callback"],
+    JsDocTag::Category { .. } => vec!["category"],
+    JsDocTag::Constructor => vec!["constructor", "class"],
+    JsDocTag::Default { .. } => vec!["default"],
+    JsDocTag::Deprecated { .. } => vec!["deprecated"],
+    JsDocTag::Enum { .. } => vec!["enum"],
+    JsDocTag::Example { .. } => vec!["example"],
+    JsDocTag::Experimental => vec!["experimental"],
+    JsDocTag::Extends { .. } => vec!["extends", "augments"],
+    JsDocTag::Ignore => vec!["ignore"],
+    JsDocTag::Internal => vec!["internal"],
+    JsDocTag::Module => vec!["module"],
+    JsDocTag::Param { .. } => vec!["param", "arg", "argument"],
+    JsDocTag::Public => vec!["public"],
+    JsDocTag::Private => vec!["private"],
+    JsDocTag::Property { .. } => vec!["property", "prop"],
+    JsDocTag::Protected => vec!["protected"],
+    JsDocTag::ReadOnly => vec!["readonly"],
+    JsDocTag::Return { .. } => vec!["return", "returns"],
+    JsDocTag::Tags { .. } => vec!["tags"],
+    JsDocTag::Template { .. } => vec!["template", "typeparam", "typeParam"],
+    JsDocTag::This { .. } => vec!["this"],
+    JsDocTag::Throws { .. } => vec!["throws", "exception"],
+    JsDocTag::TypeDef { .. } => vec!["typedef"],
+    JsDocTag::TypeRef { .. } => vec!["type"],
+    JsDocTag::See { .. } => vec!["see"],
+    JsDocTag::Since { .. } => vec!["since"],
+    JsDocTag::Unsupported { value } => value
+      .trim_start_matches('@')
+      .split_whitespace()
+      .next()
+      .into_iter()
+      .collect(),
+  }
+}
+
+/// Resolves the declaration files of the `npm:` specifiers to document. When
+/// a package doesn't have types, the types of its DefinitelyTyped package
+/// (ex. `npm:@types/express@4` for `npm:express@4`) are used.
+async fn resolve_npm_types_specifiers(
+  factory: &CliFactory,
+  module_specifiers: &[ModuleSpecifier],
+  cwd: &Path,
+) -> Result<HashMap<ModuleSpecifier, ModuleSpecifier>, AnyError> {
+  let npm_req_refs = module_specifiers
+    .iter()
+    .filter_map(|specifier| {
+      let req_ref = NpmPackageReqReference::from_specifier(specifier).ok()?;
+      Some((specifier, req_ref))
+    })
+    .collect::<Vec<_>>();
+  if npm_req_refs.is_empty() {
+    return Ok(HashMap::new());
+  }
+
+  let module_graph_creator = factory.module_graph_creator().await?;
+  let npm_resolver = factory.npm_resolver().await?;
+  let node_resolver = factory.node_resolver().await?;
+  let referrer = ModuleSpecifier::from_directory_path(cwd).unwrap();
+  let resolve_types = |req_ref: &NpmPackageReqReference| {
+    let package_folder = npm_resolver
+      .resolve_pkg_folder_from_deno_module_req(req_ref.req(), &referrer)
+      .ok()?;
+    let resolution = node_resolver
+      .resolve_package_subpath_from_deno_module(
+        &package_folder,
+        req_ref.sub_path(),
+        &referrer,
+        NodeResolutionMode::Types,
+        &PermissionsContainer::allow_all(),
+      )
+      .ok()??;
+    let (specifier, media_type) =
+      NodeResolution::into_specifier_and_media_type(Some(resolution));
+    media_type.is_declaration().then_some(specifier)
+  };
+  let resolve_version = |req_ref: &NpmPackageReqReference| {
+    let package_folder = npm_resolver
+      .resolve_pkg_folder_from_deno_module_req(req_ref.req(), &referrer)
+      .ok()?;
+    let package_json = node_resolver
+      .get_closest_package_json_from_path(
+        &package_folder.join("package.json"),
+        &PermissionsContainer::allow_all(),
+      )
+      .ok()??;
+    Version::parse_standard(package_json.version.as_ref()?).ok()
+  };
+
+  // building the graph installs the npm packages
+  module_graph_creator
+    .create_graph(
+      GraphKind::TypesOnly,
+      npm_req_refs
+        .iter()
+        .map(|(specifier, _)| (*specifier).clone())
+        .collect(),
+    )
+    .await?;
+  let mut types_specifiers = HashMap::with_capacity(npm_req_refs.len());
+  let mut untyped_req_refs = Vec::new();
+  for (specifier, req_ref) in &npm_req_refs {
+    match resolve_types(req_ref) {
+      Some(types_specifier) => {
+        types_specifiers.insert((*specifier).clone(), types_specifier);
+      }
+      None => {
+        let types_specifier =
+          definitely_typed_specifier(req_ref, resolve_version(req_ref))?;
+        let types_req_ref =
+          NpmPackageReqReference::from_specifier(&types_specifier)?;
+        untyped_req_refs.push((*specifier, types_specifier, types_req_ref));
+      }
+    }
+  }
+  if untyped_req_refs.is_empty() {
+    return Ok(types_specifiers);
+  }
+
+  module_graph_creator
+    .create_graph(
+      GraphKind::TypesOnly,
+      untyped_req_refs
+        .iter()
+        .map(|(_, types_specifier, _)| types_specifier.clone())
+        .collect(),
+    )
+    .await?;
+  for (specifier, definitely_typed_specifier, req_ref) in untyped_req_refs {
+    let Some(types_specifier) = resolve_types(&req_ref) else {
+      bail!(
+        "Could not find the types of {}. Neither the package nor {} has declaration files.",
+        specifier,
+        definitely_typed_specifier
+      );
+    };
+    types_specifiers.insert(specifier.clone(), types_specifier);
+  }
+  Ok(types_specifiers)
+}
+
+/// Gets the DefinitelyTyped package of an npm package, where `@scope/name`
+/// maps to `@types/scope__name`. The versions of the DefinitelyTyped packages
+/// only follow the major version of the package (or the minor version of
+/// `0.x` packages), so only that part of the resolved version is used.
+fn definitely_typed_specifier(
+  req_ref: &NpmPackageReqReference,
+  maybe_version: Option<Version>,
+) -> Result<ModuleSpecifier, AnyError> {
+  let name = req_ref
+    .req()
+    .name
+    .trim_start_matches('@')
+    .replacen('/', "__", 1);
+  let mut text = format!("npm:@types/{}", name);
+  match maybe_version {
+    Some(version) if version.major > 0 => {
+      text.push_str(&format!("@{}", version.major))
+    }
+    Some(version) => text.push_str(&format!("@0.{}", version.minor)),
+    None => {}
+  }
+  if let Some(sub_path) = req_ref.sub_path() {
+    text.push('/');
+    text.push_str(sub_path);
+  }
+  Ok(ModuleSpecifier::parse(&text)?)
+}
+
 struct DocResolver {
   deno_ns: std::collections::HashSet<Vec<String>>,
 }
@@ -303,6 +600,328 @@ fn generate_docs_directory(
   Ok(())
 }
 
+fn generate_markdown_directory(
+  doc_nodes_by_url: &IndexMap<ModuleSpecifier, Vec<doc::DocNode>>,
+  markdown_options: &DocMarkdownFlag,
+  cwd: &Path,
+) -> Result<(), AnyError> {
+  let path = cwd.join(&markdown_options.output);
+  let files = generate_markdown(doc_nodes_by_url, cwd);
+
+  let _ = std::fs::remove_dir_all(&path);
+  std::fs::create_dir(&path)
+    .with_context(|| format!("Failed to create directory {:?}", path))?;
+
+  let no_of_files = files.len();
+  for (name, content) in files {
+    let this_path = path.join(name);
+    let prefix = this_path.parent().with_context(|| {
+      format!("Failed to get parent path for {:?}", this_path)
+    })?;
+    std::fs::create_dir_all(prefix)
+      .with_context(|| format!("Failed to create directory {:?}", prefix))?;
+    std::fs::write(&this_path, content)
+      .with_context(|| format!("Failed to write file {:?}", this_path))?;
+  }
+
+  log::info!(
+    "{}",
+    colors::green(format!(
+      "Written {} files to {:?}",
+      no_of_files, markdown_options.output
+    ))
+  );
+  Ok(())
+}
+
+/// Generates an `index.md` file that links to a markdown file for each
+/// module. The symbols of a module have an anchor and `{@link}` references
+/// to documented symbols link to it.
+fn generate_markdown(
+  doc_nodes_by_url: &IndexMap<ModuleSpecifier, Vec<doc::DocNode>>,
+  cwd: &Path,
+) -> IndexMap<String, String> {
+  let file_names = doc_nodes_by_url
+    .keys()
+    .map(|specifier| markdown_file_name(specifier, cwd))
+    .collect::<Vec<_>>();
+  let mut symbol_files = HashMap::new();
+  for (file_name, doc_nodes) in file_names.iter().zip(doc_nodes_by_url.values())
+  {
+    visit_symbols(&[], doc_nodes, &mut |name, _| {
+      symbol_files
+        .entry(name)
+        .or_insert_with(|| file_name.clone());
+    });
+  }
+
+  let mut files = IndexMap::with_capacity(file_names.len() + 1);
+  let mut index = String::from("# Modules\n\n");
+  for (file_name, doc_nodes) in file_names.iter().zip(doc_nodes_by_url.values())
+  {
+    let title = file_name.strip_suffix(".md").unwrap_or(file_name);
+    index.push_str(&format!("- [{}]({})\n", title, file_name));
+    let content =
+      render_markdown_module(title, file_name, doc_nodes, &symbol_files);
+    files.insert(file_name.clone(), content);
+  }
+  files.insert("index.md".to_string(), index);
+  files
+}
+
+fn markdown_file_name(specifier: &ModuleSpecifier, cwd: &Path) -> String {
+  let path = match specifier.to_file_path() {
+    Ok(path) => match path.strip_prefix(cwd) {
+      Ok(relative_path) => relative_path.to_string_lossy().replace('\\', "/"),
+      Err(_) => specifier.path().to_string(),
+    },
+    Err(_) => format!(
+      "{}{}",
+      specifier.host_str().unwrap_or_default(),
+      specifier.path()
+    ),
+  };
+  format!("{}.md", path.trim_start_matches('/'))
+}
+
+fn render_markdown_module(
+  title: &str,
+  file_name: &str,
+  doc_nodes: &[doc::DocNode],
+  symbol_files: &HashMap<String, String>,
+) -> String {
+  let mut text = format!("# {}\n\n", title);
+  let module_doc = doc_nodes
+    .iter()
+    .find(|doc_node| doc_node.kind == doc::DocNodeKind::ModuleDoc)
+    .and_then(|doc_node| doc_node.js_doc.doc.as_deref());
+  if let Some(module_doc) = module_doc {
+    text.push_str(&replace_doc_links(
+      module_doc.trim(),
+      file_name,
+      symbol_files,
+    ));
+    text.push_str("\n\n");
+  }
+
+  let mut symbols = Vec::new();
+  visit_symbols(&[], doc_nodes, &mut |name, doc_node| {
+    symbols.push((name, doc_node));
+  });
+  if symbols.is_empty() {
+    return text;
+  }
+
+  text.push_str("## Index\n\n");
+  let mut listed = HashSet::new();
+  for (name, doc_node) in &symbols {
+    if !listed.insert(name.as_str()) {
+      continue;
+    }
+    text.push_str(&format!("- [`{}`](#{})", name, name));
+    if let Some(summary) = doc_node.js_doc.doc.as_deref().and_then(doc_summary)
+    {
+      text.push_str(": ");
+      text.push_str(&replace_doc_links(&summary, file_name, symbol_files));
+    }
+    text.push('\n');
+  }
+
+  // overloads share the anchor of the first declaration
+  let mut anchored = HashSet::new();
+  for (name, doc_node) in &symbols {
+    text.push('\n');
+    if anchored.insert(name.as_str()) {
+      text.push_str(&format!("<a id=\"{}\"></a>\n\n", name));
+    }
+    text.push_str(&format!("## {}\n\n", name));
+    text.push_str(&format!(
+      "```ts\n{}\n```\n",
+      markdown_signature(name, doc_node)
+    ));
+    if let Some(doc) = doc_node.js_doc.doc.as_deref() {
+      text.push('\n');
+      text.push_str(&replace_doc_links(doc.trim(), file_name, symbol_files));
+      text.push('\n');
+    }
+  }
+  text
+}
+
+fn markdown_signature(name: &str, doc_node: &doc::DocNode) -> String {
+  fn join<T: std::fmt::Display>(items: impl IntoIterator<Item = T>) -> String {
+    items
+      .into_iter()
+      .map(|item| item.to_string())
+      .collect::<Vec<_>>()
+      .join(", ")
+  }
+  fn type_params<T: std::fmt::Display>(
+    items: impl IntoIterator<Item = T>,
+  ) -> String {
+    let type_params = join(items);
+    if type_params.is_empty() {
+      type_params
+    } else {
+      format!("<{}>", type_params)
+    }
+  }
+
+  if let Some(function_def) = &doc_node.function_def {
+    return format!(
+      "{}function {}{}({}){}",
+      if function_def.is_async { "async " } else { "" },
+      name,
+      type_params(function_def.type_params.iter()),
+      join(function_def.params.iter()),
+      function_def
+        .return_type
+        .as_ref()
+        .map(|return_type| format!(": {}", return_type))
+        .unwrap_or_default()
+    );
+  }
+  if let Some(variable_def) = &doc_node.variable_def {
+    return match &variable_def.ts_type {
+      Some(ts_type) => format!("{} {}: {}", variable_def.kind, name, ts_type),
+      None => format!("{} {}", variable_def.kind, name),
+    };
+  }
+  if let Some(type_alias_def) = &doc_node.type_alias_def {
+    return format!(
+      "type {}{} = {}",
+      name,
+      type_params(type_alias_def.type_params.iter()),
+      type_alias_def.ts_type
+    );
+  }
+  let keyword = match doc_node.kind {
+    doc::DocNodeKind::Class => "class",
+    doc::DocNodeKind::Enum => "enum",
+    doc::DocNodeKind::Interface => "interface",
+    doc::DocNodeKind::Namespace => "namespace",
+    _ => "",
+  };
+  format!("{} {}", keyword, name).trim_start().to_string()
+}
+
+/// Replaces `{@link}` references with markdown links to the documented
+/// symbols, relative to the markdown file `file_name`. References to
+/// symbols that aren't documented are formatted as code.
+fn replace_doc_links(
+  doc: &str,
+  file_name: &str,
+  symbol_files: &HashMap<String, String>,
+) -> String {
+  lazy_regex::regex!(r"\{@link(?:code|plain)?\s+([^\s|}]+)\s*\|?\s*([^}]*)\}")
+    .replace_all(doc, |c: &Captures| {
+      let target = &c[1];
+      let text = match c[2].trim() {
+        "" => target,
+        text => text,
+      };
+      if target.contains("://") {
+        format!("[{}]({})", text, target)
+      } else if let Some(target_file) = symbol_files.get(target) {
+        if target_file == file_name {
+          format!("[{}](#{})", text, target)
+        } else {
+          format!(
+            "[{}]({}{}#{})",
+            text,
+            "../".repeat(file_name.matches('/').count()),
+            target_file,
+            target
+          )
+        }
+      } else {
+        format!("`{}`", text)
+      }
+    })
+    .into_owned()
+}
+
+#[derive(Serialize)]
+struct SearchIndexEntry {
+  name: String,
+  kind: doc::DocNodeKind,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  summary: Option<String>,
+  location: doc::Location,
+}
+
+fn write_search_index(
+  path: &Path,
+  doc_nodes_by_url: &IndexMap<ModuleSpecifier, Vec<doc::DocNode>>,
+) -> Result<(), AnyError> {
+  let mut entries = Vec::new();
+  for doc_nodes in doc_nodes_by_url.values() {
+    visit_symbols(&[], doc_nodes, &mut |name, doc_node| {
+      entries.push(SearchIndexEntry {
+        name,
+        kind: doc_node.kind,
+        summary: doc_node.js_doc.doc.as_deref().and_then(doc_summary),
+        location: doc_node.location.clone(),
+      });
+    });
+  }
+  let text = serde_json::to_string(&entries)?;
+  std::fs::write(path, text)
+    .with_context(|| format!("Failed to write file {:?}", path))?;
+  log::info!(
+    "{}",
+    colors::green(format!(
+      "Written search index of {} symbols to {:?}",
+      entries.len(),
+      path
+    ))
+  );
+  Ok(())
+}
+
+/// Visits the documented symbols, including the members of namespaces, with
+/// their namespace qualified names.
+fn visit_symbols<'a>(
+  ns_qualifiers: &[&str],
+  doc_nodes: impl IntoIterator<Item = &'a doc::DocNode>,
+  visit: &mut impl FnMut(String, &'a doc::DocNode),
+) {
+  for doc_node in doc_nodes {
+    if matches!(
+      doc_node.kind,
+      doc::DocNodeKind::Import | doc::DocNodeKind::ModuleDoc
+    ) {
+      continue;
+    }
+    let mut qualifiers = ns_qualifiers.to_vec();
+    qualifiers.push(&doc_node.name);
+    visit(qualifiers.join("."), doc_node);
+    if let Some(namespace_def) = &doc_node.namespace_def {
+      // collected so that the recursion doesn't instantiate a new iterator
+      // type for every level of namespaces
+      let elements: Vec<&'a doc::DocNode> =
+        namespace_def.elements.iter().map(|n| &**n).collect();
+      visit_symbols(&qualifiers, elements, visit);
+    }
+  }
+}
+
+/// Gets the first paragraph of a JSDoc comment.
+fn doc_summary(doc: &str) -> Option<String> {
+  let summary = doc
+    .trim()
+    .lines()
+    .map(str::trim)
+    .take_while(|line| !line.is_empty())
+    .collect::<Vec<_>>()
+    .join(" ");
+  if summary.is_empty() {
+    None
+  } else {
+    Some(summary)
+  }
+}
+
 fn print_docs_to_stdout(
   doc_flags: DocFlags,
   mut doc_nodes: Vec<deno_doc::DocNode>,
@@ -327,8 +946,54 @@ fn print_docs_to_stdout(
   write_to_stdout_ignore_sigpipe(details.as_bytes()).map_err(AnyError::from)
 }
 
-fn check_diagnostics(diagnostics: &[DocDiagnostic]) -> Result<(), AnyError> {
-  if diagnostics.is_empty() {
+/// A `@param` tag without a description, which the documentation
+/// diagnostics of deno_doc don't report.
+struct ParamDescriptionDiagnostic {
+  location: doc::Location,
+  name: String,
+}
+
+fn collect_param_description_diagnostics<'a>(
+  diagnostics: &mut Vec<ParamDescriptionDiagnostic>,
+  doc_nodes: impl IntoIterator<Item = &'a doc::DocNode>,
+) {
+  let mut check_js_doc = |js_doc: &doc::js_doc::JsDoc,
+                          location: &doc::Location| {
+    for tag in js_doc.tags.iter() {
+      if let doc::js_doc::JsDocTag::Param { name, doc, .. } = tag {
+        if doc.as_deref().map_or(true, |doc| doc.trim().is_empty()) {
+          diagnostics.push(ParamDescriptionDiagnostic {
+            location: location.clone(),
+            name: name.to_string(),
+          });
+        }
+      }
+    }
+  };
+  visit_symbols(&[], doc_nodes, &mut |_, doc_node| {
+    check_js_doc(&doc_node.js_doc, &doc_node.location);
+    if let Some(class_def) = &doc_node.class_def {
+      for constructor in class_def.constructors.iter() {
+        check_js_doc(&constructor.js_doc, &constructor.location);
+      }
+      for method in class_def.methods.iter() {
+        check_js_doc(&method.js_doc, &method.location);
+      }
+    }
+    if let Some(interface_def) = &doc_node.interface_def {
+      for method in interface_def.methods.iter() {
+        check_js_doc(&method.js_doc, &method.location);
+      }
+    }
+  });
+}
+
+fn check_diagnostics(
+  diagnostics: &[DocDiagnostic],
+  param_diagnostics: &[ParamDescriptionDiagnostic],
+) -> Result<(), AnyError> {
+  let diagnostics_len = diagnostics.len() + param_diagnostics.len();
+  if diagnostics_len == 0 {
     return Ok(());
   }
 
@@ -354,9 +1019,211 @@ fn check_diagnostics(diagnostics: &[DocDiagnostic]) -> Result<(), AnyError> {
       }
     }
   }
+  for diagnostic in param_diagnostics {
+    log::error!(
+      "{}: @param tag for '{}' is missing a description\n --> {}:{}:{}\n\n",
+      colors::red_bold("error[missing-param-description]"),
+      diagnostic.name,
+      diagnostic.location.filename,
+      diagnostic.location.line,
+      diagnostic.location.col + 1,
+    );
+  }
   bail!(
     "Found {} documentation lint error{}.",
-    colors::bold(diagnostics.len().to_string()),
-    if diagnostics.len() == 1 { "" } else { "s" }
+    colors::bold(diagnostics_len.to_string()),
+    if diagnostics_len == 1 { "" } else { "s" }
   );
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn replaces_doc_links() {
+    let symbol_files = HashMap::from([
+      ("add".to_string(), "src/math.ts.md".to_string()),
+      ("Point".to_string(), "src/point.ts.md".to_string()),
+    ]);
+    assert_eq!(
+      replace_doc_links(
+        "Uses {@link add}, {@linkcode Point | points} and {@link missing}.",
+        "src/math.ts.md",
+        &symbol_files,
+      ),
+      "Uses [add](#add), [points](../src/point.ts.md#Point) and `missing`."
+    );
+    assert_eq!(
+      replace_doc_links(
+        "See {@link https://deno.land the site}.",
+        "index.md",
+        &symbol_files,
+      ),
+      "See [the site](https://deno.land)."
+    );
+  }
+
+  #[test]
+  fn gets_markdown_file_name() {
+    let cwd = std::env::current_dir().unwrap();
+    let specifier =
+      ModuleSpecifier::from_file_path(cwd.join("src/mod.ts")).unwrap();
+    assert_eq!(markdown_file_name(&specifier, &cwd), "src/mod.ts.md");
+    let specifier =
+      ModuleSpecifier::parse("https://deno.land/x/mod/mod.ts").unwrap();
+    assert_eq!(
+      markdown_file_name(&specifier, &cwd),
+      "deno.land/x/mod/mod.ts.md"
+    );
+  }
+
+  #[test]
+  fn gets_definitely_typed_specifier() {
+    let get = |text: &str, version: Option<&str>| {
+      definitely_typed_specifier(
+        &NpmPackageReqReference::from_str(text).unwrap(),
+        version.map(|version| Version::parse_standard(version).unwrap()),
+      )
+      .unwrap()
+      .to_string()
+    };
+    assert_eq!(
+      get("npm:express@^4.18.2", Some("4.19.2")),
+      "npm:@types/express@4"
+    );
+    assert_eq!(
+      get("npm:@babel/core@7/sub", Some("7.24.5")),
+      "npm:@types/babel__core@7/sub"
+    );
+    assert_eq!(
+      get("npm:nanoid@0.3.1", Some("0.3.1")),
+      "npm:@types/nanoid@0.3"
+    );
+    assert_eq!(get("npm:express", None), "npm:@types/express");
+  }
+
+  #[test]
+  fn gets_js_doc_tag_names() {
+    assert_eq!(
+      js_doc_tag_names(&doc::js_doc::JsDocTag::Unsupported {
+        value: "@beta some reason".into(),
+      }),
+      vec!["beta"]
+    );
+    assert_eq!(
+      js_doc_tag_names(&doc::js_doc::JsDocTag::Return {
+        type_ref: None,
+        doc: None,
+      }),
+      vec!["return", "returns"]
+    );
+  }
+
+  #[tokio::test]
+  async fn filters_doc_nodes_by_tags() {
+    let specifier = ModuleSpecifier::parse("file:///mod.ts").unwrap();
+    let loader = deno_graph::source::MemoryLoader::new(
+      vec![(
+        specifier.to_string(),
+        deno_graph::source::Source::Module {
+          specifier: specifier.to_string(),
+          content: r#"
+/** @returns the sum */
+export function add(a: number, b: number): number {
+  return a + b;
+}
+/** @internal */
+export function secret() {}
+/** @arg value the logged value */
+export function log(value: string) {}
+export class Point {
+  /** @beta */
+  x = 0;
+  y = 0;
+}
+"#
+          .to_string(),
+          maybe_headers: None,
+        },
+      )],
+      Vec::new(),
+    );
+    let analyzer = deno_graph::CapturingModuleAnalyzer::default();
+    let mut graph = deno_graph::ModuleGraph::new(GraphKind::TypesOnly);
+    graph
+      .build(
+        vec![specifier.clone()],
+        &loader,
+        deno_graph::BuildOptions {
+          imports: Vec::new(),
+          is_dynamic: false,
+          passthrough_jsr_specifiers: false,
+          workspace_members: &[],
+          executor: Default::default(),
+          file_system: &NullFileSystem,
+          jsr_url_provider: Default::default(),
+          module_analyzer: &analyzer,
+          npm_resolver: None,
+          reporter: None,
+          resolver: None,
+        },
+      )
+      .await;
+    let doc_parser = doc::DocParser::new(
+      &graph,
+      &analyzer,
+      doc::DocParserOptions {
+        diagnostics: false,
+        private: false,
+      },
+    )
+    .unwrap();
+    let doc_nodes = doc_parser.parse_module(&specifier).unwrap().definitions;
+    let filter = |include: &[&str], exclude: &[&str]| {
+      let include = include.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+      let exclude = exclude.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+      let mut doc_nodes = doc_nodes.clone();
+      TagFilter {
+        include: &include,
+        exclude: &exclude,
+      }
+      .filter(&mut doc_nodes);
+      doc_nodes
+    };
+    let names = |doc_nodes: &[doc::DocNode]| {
+      let mut names = doc_nodes
+        .iter()
+        .map(|doc_node| doc_node.name.as_str())
+        .collect::<Vec<_>>();
+      names.sort();
+      names.join(",")
+    };
+
+    let doc_nodes = filter(&[], &["returns", "@beta"]);
+    assert_eq!(names(&doc_nodes), "Point,log,secret");
+    let point = doc_nodes.iter().find(|n| n.name == "Point").unwrap();
+    let properties = &point.class_def.as_ref().unwrap().properties;
+    assert_eq!(properties.len(), 1);
+    assert_eq!(properties[0].name, "y");
+
+    // `@arg` is a `@param` tag
+    assert_eq!(names(&filter(&["param"], &[])), "log");
+    assert_eq!(names(&filter(&["@internal", "return"], &[])), "add,secret");
+    assert_eq!(names(&filter(&["internal"], &["internal"])), "");
+  }
+
+  #[test]
+  fn gets_doc_summary() {
+    assert_eq!(doc_summary(""), None);
+    assert_eq!(doc_summary("  \n"), None);
+    assert_eq!(
+      doc_summary("Adds two numbers.\n\n@example add(1, 2)"),
+      Some("Adds two numbers.".to_string())
+    );
+    assert_eq!(
+      doc_summary("\nA summary that\n  spans lines.\n\nDetails."),
+      Some("A summary that spans lines.".to_string())
+    );
+  }
+}
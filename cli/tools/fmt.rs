@@ -9,19 +9,29 @@
 
 use crate::args::CliOptions;
 use crate::args::Flags;
+use crate::args::FmtDiffStyle;
 use crate::args::FmtFlags;
 use crate::args::FmtOptions;
 use crate::args::FmtOptionsConfig;
+use crate::args::FmtRange;
 use crate::args::ProseWrap;
+use crate::args::UnstableFmtOptions;
 use crate::colors;
 use crate::factory::CliFactory;
 use crate::util::diff::diff;
+use crate::util::diff::unified_diff;
+use crate::util::diff::DiffGranularity;
+use crate::util::diff::DiffOptions;
 use crate::util::file_watcher;
 use crate::util::fs::canonicalize_path;
 use crate::util::fs::FileCollector;
+use crate::util::git;
 use crate::util::path::get_extension;
+use deno_ast::MediaType;
+use deno_ast::ModuleSpecifier;
 use deno_ast::ParsedSource;
-use deno_config::glob::FilePatterns;
+use deno_ast::SourceRangedForSpanned;
+use deno_ast::SourceTextInfo;
 use deno_core::anyhow::anyhow;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
@@ -33,6 +43,7 @@ use deno_core::unsync::spawn_blocking;
 use log::debug;
 use log::info;
 use log::warn;
+use std::collections::HashSet;
 use std::fs;
 use std::io::stdin;
 use std::io::stdout;
@@ -46,13 +57,21 @@ use std::sync::Arc;
 
 use crate::cache::IncrementalCache;
 
+mod plugins;
+pub mod sort_imports;
+
+use plugins::FmtPluginHost;
+
 /// Format JavaScript/TypeScript files.
 pub async fn format(flags: Flags, fmt_flags: FmtFlags) -> Result<(), AnyError> {
   if fmt_flags.is_stdin() {
-    let cli_options = CliOptions::from_flags(flags)?;
+    let factory = CliFactory::from_flags(flags)?;
+    let cli_options = factory.cli_options();
     let fmt_options = cli_options.resolve_fmt_options(fmt_flags)?;
+    let formatter = create_file_formatter(&factory, &fmt_options).await?;
     return format_stdin(
       fmt_options,
+      &formatter,
       cli_options
         .ext_flag()
         .as_ref()
@@ -71,7 +90,8 @@ pub async fn format(flags: Flags, fmt_flags: FmtFlags) -> Result<(), AnyError> {
           let factory = CliFactory::from_flags(flags)?;
           let cli_options = factory.cli_options();
           let fmt_options = cli_options.resolve_fmt_options(fmt_flags)?;
-          let files = collect_fmt_files(cli_options, fmt_options.files.clone())
+          let formatter = create_file_formatter(&factory, &fmt_options).await?;
+          let files = collect_fmt_files(cli_options, &fmt_options, &formatter)
             .and_then(|files| {
               if files.is_empty() {
                 Err(generic_error("No target files found."))
@@ -105,7 +125,7 @@ pub async fn format(flags: Flags, fmt_flags: FmtFlags) -> Result<(), AnyError> {
           } else {
             files
           };
-          format_files(factory, fmt_options, refmt_files).await?;
+          format_files(factory, fmt_options, formatter, refmt_files).await?;
 
           Ok(())
         })
@@ -116,7 +136,8 @@ pub async fn format(flags: Flags, fmt_flags: FmtFlags) -> Result<(), AnyError> {
     let factory = CliFactory::from_flags(flags)?;
     let cli_options = factory.cli_options();
     let fmt_options = cli_options.resolve_fmt_options(fmt_flags)?;
-    let files = collect_fmt_files(cli_options, fmt_options.files.clone())
+    let formatter = create_file_formatter(&factory, &fmt_options).await?;
+    let files = collect_fmt_files(cli_options, &fmt_options, &formatter)
       .and_then(|files| {
         if files.is_empty() {
           Err(generic_error("No target files found."))
@@ -124,30 +145,111 @@ pub async fn format(flags: Flags, fmt_flags: FmtFlags) -> Result<(), AnyError> {
           Ok(files)
         }
       })?;
-    format_files(factory, fmt_options, files).await?;
+    format_files(factory, fmt_options, formatter, files).await?;
   }
 
   Ok(())
 }
 
+async fn create_file_formatter(
+  factory: &CliFactory,
+  fmt_options: &FmtOptions,
+) -> Result<FileFormatter, AnyError> {
+  let plugins = if fmt_options.plugins.is_empty() {
+    None
+  } else {
+    let plugins = FmtPluginHost::load(
+      factory.file_fetcher()?,
+      factory.maybe_lockfile().as_ref(),
+      &fmt_options.plugins,
+      &fmt_options.options,
+    )
+    .await?;
+    Some(Arc::new(plugins))
+  };
+  Ok(FileFormatter {
+    plugins,
+    sort_imports: fmt_options.sort_imports,
+    file_options: FormatFileOptions {
+      skip_code_block_languages: fmt_options.skip_code_block_languages.clone(),
+      unstable: fmt_options.unstable,
+    },
+  })
+}
+
 async fn format_files(
   factory: CliFactory,
   fmt_options: FmtOptions,
+  formatter: FileFormatter,
   paths: Vec<PathBuf>,
 ) -> Result<(), AnyError> {
+  if let Some(range) = fmt_options.range {
+    if paths.len() != 1 {
+      bail!(
+        "--range requires a single file, but {} files were found.",
+        paths.len()
+      );
+    }
+    return format_file_range(&paths[0], &fmt_options, &formatter, range);
+  }
+
   let caches = factory.caches()?;
   let check = fmt_options.check;
+  // the override patterns are part of the state so that changing which
+  // files an override applies to causes them to be formatted again
+  let overrides_state = fmt_options
+    .overrides
+    .iter()
+    .map(|o| (format!("{:?}", o.files), &o.options))
+    .collect::<Vec<_>>();
+  // the plugins are keyed by the checksum of their Wasm module because
+  // the file behind a specifier might change between runs
+  let plugins_state = fmt_options
+    .plugins
+    .iter()
+    .zip(formatter.plugins.iter().flat_map(|p| p.wasm_checksums()))
+    .map(|(p, checksum)| (checksum, &p.options))
+    .collect::<Vec<_>>();
   let incremental_cache = Arc::new(IncrementalCache::new(
     caches.fmt_incremental_cache_db(),
-    &fmt_options.options,
+    &(
+      &fmt_options.options,
+      overrides_state,
+      plugins_state,
+      fmt_options.sort_imports,
+      &fmt_options.skip_code_block_languages,
+      // changes how the YAML front matter of markdown files is formatted
+      fmt_options.unstable.yaml,
+    ),
     &paths,
   ));
   if check {
-    check_source_files(paths, fmt_options.options, incremental_cache.clone())
-      .await?;
+    // the paths in patches are relative to the directory of the config
+    // file, so the patch applies from the project root wherever deno fmt
+    // was run from
+    let cli_options = factory.cli_options();
+    let patch_root = cli_options
+      .maybe_config_file()
+      .as_ref()
+      .and_then(|c| c.specifier.to_file_path().ok())
+      .and_then(|p| p.parent().map(ToOwned::to_owned))
+      .unwrap_or_else(|| cli_options.initial_cwd().to_path_buf());
+    check_source_files(
+      paths,
+      fmt_options,
+      formatter,
+      incremental_cache.clone(),
+      patch_root,
+    )
+    .await?;
   } else {
-    format_source_files(paths, fmt_options.options, incremental_cache.clone())
-      .await?;
+    format_source_files(
+      paths,
+      fmt_options,
+      formatter,
+      incremental_cache.clone(),
+    )
+    .await?;
   }
   incremental_cache.wait_completion().await;
   Ok(())
@@ -155,28 +257,57 @@ async fn format_files(
 
 fn collect_fmt_files(
   cli_options: &CliOptions,
-  files: FilePatterns,
+  fmt_options: &FmtOptions,
+  formatter: &FileFormatter,
 ) -> Result<Vec<PathBuf>, AnyError> {
-  FileCollector::new(|e| is_supported_ext_fmt(e.path))
+  let files = FileCollector::new(|e| formatter.can_format(e.path))
     .ignore_git_folder()
     .ignore_node_modules()
     .set_vendor_folder(cli_options.vendor_dir_path().map(ToOwned::to_owned))
-    .collect_file_patterns(files)
+    .set_use_gitignore(fmt_options.gitignore)
+    .collect_file_patterns(fmt_options.files.clone())?;
+  let Some(git_ref) = &fmt_options.changed else {
+    return Ok(files);
+  };
+  // paths from git are relative to the canonicalized repository root
+  let changed_files = git::changed_files(cli_options.initial_cwd(), git_ref)?
+    .into_iter()
+    .filter_map(|path| canonicalize_path(&path).ok())
+    .collect::<HashSet<_>>();
+  Ok(
+    files
+      .into_iter()
+      .filter(|path| {
+        canonicalize_path(path).is_ok_and(|path| changed_files.contains(&path))
+      })
+      .collect(),
+  )
 }
 
-/// Formats markdown (using <https://github.com/dprint/dprint-plugin-markdown>) and its code blocks
-/// (ts/tsx, js/jsx).
+/// Formats markdown (using <https://github.com/dprint/dprint-plugin-markdown>), its code blocks
+/// (ts/tsx, js/jsx, json, yaml) and its YAML front matter. YAML is only
+/// formatted when the unstable YAML formatter was opted into.
 fn format_markdown(
   file_text: &str,
   fmt_options: &FmtOptionsConfig,
+  file_options: &FormatFileOptions,
 ) -> Result<Option<String>, AnyError> {
   let markdown_config = get_resolved_markdown_config(fmt_options);
-  dprint_plugin_markdown::format_text(
+  let formatted_text = dprint_plugin_markdown::format_text(
     file_text,
     &markdown_config,
     move |tag, text, line_width| {
       let tag = tag.to_lowercase();
-      if matches!(
+      if file_options
+        .skip_code_block_languages
+        .iter()
+        .any(|language| {
+          code_block_language(&language.to_lowercase())
+            == code_block_language(&tag)
+        })
+      {
+        Ok(None)
+      } else if matches!(
         tag.as_str(),
         "ts"
           | "tsx"
@@ -215,13 +346,68 @@ fn format_markdown(
             &codeblock_config,
           )
         }
+      } else if matches!(tag.as_str(), "yml" | "yaml")
+        && file_options.unstable.yaml
+      {
+        let mut yaml_config = get_resolved_yaml_config(fmt_options);
+        yaml_config.layout.print_width = line_width as usize;
+        format_yaml_with_config(text, &yaml_config)
+      } else if matches!(tag.as_str(), "md" | "markdown") {
+        let fmt_options = FmtOptionsConfig {
+          line_width: Some(line_width),
+          ..fmt_options.clone()
+        };
+        format_markdown(text, &fmt_options, file_options)
       } else {
         Ok(None)
       }
     },
+  )?;
+  if !file_options.unstable.yaml {
+    return Ok(formatted_text);
+  }
+  // the markdown formatter keeps the front matter as is
+  let text = formatted_text.as_deref().unwrap_or(file_text);
+  match format_yaml_front_matter(text, fmt_options)? {
+    Some(text) => Ok(Some(text)),
+    None => Ok(formatted_text),
+  }
+}
+
+/// Formats the YAML front matter at the start of a markdown file, which is
+/// delimited by `---` lines.
+fn format_yaml_front_matter(
+  file_text: &str,
+  fmt_options: &FmtOptionsConfig,
+) -> Result<Option<String>, AnyError> {
+  let Some(rest) = file_text.strip_prefix("---\n") else {
+    return Ok(None);
+  };
+  let Some(end) = rest
+    .find("\n---\n")
+    .or_else(|| rest.strip_suffix("\n---").map(|yaml| yaml.len()))
+  else {
+    return Ok(None);
+  };
+  let (yaml, after) = rest.split_at(end + 1);
+  Ok(
+    format_yaml(yaml, fmt_options)?
+      .map(|yaml| format!("---\n{}{}", yaml, after)),
   )
 }
 
+/// Gets the language of a code block, where the aliases of a language
+/// resolve to the same name.
+fn code_block_language(tag: &str) -> &str {
+  match tag {
+    "javascript" => "js",
+    "typescript" => "ts",
+    "yml" => "yaml",
+    "markdown" => "md",
+    tag => tag,
+  }
+}
+
 /// Formats JSON and JSONC using the rules provided by .deno()
 /// of configuration builder of <https://github.com/dprint/dprint-plugin-json>.
 /// See <https://github.com/dprint/dprint-plugin-json/blob/cfa1052dbfa0b54eb3d814318034cdc514c813d7/src/configuration/builder.rs#L87> for configuration.
@@ -234,23 +420,85 @@ pub fn format_json(
   dprint_plugin_json::format_text(file_path, file_text, &config)
 }
 
-/// Formats a single TS, TSX, JS, JSX, JSONC, JSON, MD, or IPYNB file.
+/// Formats YAML using <https://github.com/g-plane/pretty_yaml>.
+fn format_yaml(
+  file_text: &str,
+  fmt_options: &FmtOptionsConfig,
+) -> Result<Option<String>, AnyError> {
+  format_yaml_with_config(file_text, &get_resolved_yaml_config(fmt_options))
+}
+
+fn format_yaml_with_config(
+  file_text: &str,
+  config: &pretty_yaml::config::FormatOptions,
+) -> Result<Option<String>, AnyError> {
+  let formatted_text = pretty_yaml::format_text(file_text, config)
+    .map_err(|err| anyhow!("{}", err))?;
+  Ok(if formatted_text == file_text {
+    None
+  } else {
+    Some(formatted_text)
+  })
+}
+
+/// Formats CSS, SCSS and LESS using <https://github.com/g-plane/malva>.
+fn format_css(
+  file_path: &Path,
+  file_text: &str,
+  fmt_options: &FmtOptionsConfig,
+) -> Result<Option<String>, AnyError> {
+  let syntax = match get_extension(file_path).as_deref() {
+    Some("scss") => malva::Syntax::Scss,
+    Some("less") => malva::Syntax::Less,
+    _ => malva::Syntax::Css,
+  };
+  let formatted_text = malva::format_text(
+    file_text,
+    syntax,
+    &get_resolved_malva_config(fmt_options),
+  )?;
+  Ok(if formatted_text == file_text {
+    None
+  } else {
+    Some(formatted_text)
+  })
+}
+
+/// Options of [`format_file`] that aren't part of the dprint configuration.
+#[derive(Clone, Debug, Default)]
+pub struct FormatFileOptions {
+  /// Languages of the markdown code blocks that aren't formatted.
+  pub skip_code_block_languages: Vec<String>,
+  pub unstable: UnstableFmtOptions,
+}
+
+/// Formats a single TS, TSX, JS, JSX, JSONC, JSON, MD, IPYNB, CSS, SCSS,
+/// LESS or YAML file.
 pub fn format_file(
   file_path: &Path,
   file_text: &str,
   fmt_options: &FmtOptionsConfig,
+  file_options: &FormatFileOptions,
 ) -> Result<Option<String>, AnyError> {
   let ext = get_extension(file_path).unwrap_or_default();
 
   match ext.as_str() {
     "md" | "mkd" | "mkdn" | "mdwn" | "mdown" | "markdown" => {
-      format_markdown(file_text, fmt_options)
+      format_markdown(file_text, fmt_options, file_options)
     }
     "json" | "jsonc" => format_json(file_path, file_text, fmt_options),
+    "css" | "scss" | "less" if file_options.unstable.css => {
+      format_css(file_path, file_text, fmt_options)
+    }
+    "yml" | "yaml" if file_options.unstable.yaml => {
+      format_yaml(file_text, fmt_options)
+    }
+    // left as is unless the unstable formatter was opted into
+    "css" | "scss" | "less" | "yml" | "yaml" => Ok(None),
     "ipynb" => dprint_plugin_jupyter::format_text(
       file_text,
       |file_path: &Path, file_text: String| {
-        format_file(file_path, &file_text, fmt_options)
+        format_file(file_path, &file_text, fmt_options, file_options)
       },
     ),
     _ => {
@@ -260,6 +508,62 @@ pub fn format_file(
   }
 }
 
+/// Formats files for `deno fmt`, which besides the formatting options
+/// depends on the loaded plugins and whether imports are sorted.
+#[derive(Clone)]
+struct FileFormatter {
+  plugins: Option<Arc<FmtPluginHost>>,
+  sort_imports: bool,
+  file_options: FormatFileOptions,
+}
+
+impl FileFormatter {
+  /// Whether the file is formatted by a built-in formatter or a plugin.
+  fn can_format(&self, file_path: &Path) -> bool {
+    is_supported_ext_fmt(file_path, self.file_options.unstable)
+      || self
+        .plugins
+        .as_ref()
+        .is_some_and(|p| p.plugin_for_path(file_path).is_some())
+  }
+
+  fn format(
+    &self,
+    file_path: &Path,
+    file_text: &str,
+    fmt_options: &FmtOptionsConfig,
+  ) -> Result<Option<String>, AnyError> {
+    if self.sort_imports {
+      if let Some(sorted_text) =
+        sort_imports::sort_imports(file_path, file_text)
+      {
+        let formatted_text =
+          self.format_sorted(file_path, &sorted_text, fmt_options)?;
+        return Ok(Some(formatted_text.unwrap_or(sorted_text)));
+      }
+    }
+    self.format_sorted(file_path, file_text, fmt_options)
+  }
+
+  /// Formats the file with the built-in formatters, or with the plugin
+  /// handling the file when it's not supported by them.
+  fn format_sorted(
+    &self,
+    file_path: &Path,
+    file_text: &str,
+    fmt_options: &FmtOptionsConfig,
+  ) -> Result<Option<String>, AnyError> {
+    if !is_supported_ext_fmt(file_path, self.file_options.unstable) {
+      if let Some(plugins) = &self.plugins {
+        if let Some(index) = plugins.plugin_for_path(file_path) {
+          return plugins.format_file(index, file_path, file_text, fmt_options);
+        }
+      }
+    }
+    format_file(file_path, file_text, fmt_options, &self.file_options)
+  }
+}
+
 pub fn format_parsed_source(
   parsed_source: &ParsedSource,
   fmt_options: &FmtOptionsConfig,
@@ -272,11 +576,17 @@ pub fn format_parsed_source(
 
 async fn check_source_files(
   paths: Vec<PathBuf>,
-  fmt_options: FmtOptionsConfig,
+  fmt_options: FmtOptions,
+  formatter: FileFormatter,
   incremental_cache: Arc<IncrementalCache>,
+  patch_root: PathBuf,
 ) -> Result<(), AnyError> {
   let not_formatted_files_count = Arc::new(AtomicUsize::new(0));
   let checked_files_count = Arc::new(AtomicUsize::new(0));
+  let diff_style = fmt_options.diff_style;
+  let diff_output = fmt_options.diff_output.clone();
+  // the patch of each unformatted file for `--diff-style=patch`
+  let patches = Arc::new(Mutex::new(Vec::new()));
 
   // prevent threads outputting at the same time
   let output_lock = Arc::new(Mutex::new(0));
@@ -284,6 +594,7 @@ async fn check_source_files(
   run_parallelized(paths, {
     let not_formatted_files_count = not_formatted_files_count.clone();
     let checked_files_count = checked_files_count.clone();
+    let patches = patches.clone();
     move |file_path| {
       checked_files_count.fetch_add(1, Ordering::Relaxed);
       let file_text = read_file_contents(&file_path)?.text;
@@ -293,11 +604,29 @@ async fn check_source_files(
         return Ok(());
       }
 
-      match format_file(&file_path, &file_text, &fmt_options) {
+      let file_fmt_options = fmt_options.options_for_path(&file_path);
+      match formatter.format(&file_path, &file_text, file_fmt_options) {
+        Ok(Some(formatted_text)) if diff_style == FmtDiffStyle::Patch => {
+          not_formatted_files_count.fetch_add(1, Ordering::Relaxed);
+          let file_name = file_path
+            .strip_prefix(&patch_root)
+            .unwrap_or(&file_path)
+            .to_string_lossy()
+            .replace('\\', "/");
+          let patch = unified_diff(&file_name, &file_text, &formatted_text, 3);
+          patches.lock().push((file_path, patch));
+        }
         Ok(Some(formatted_text)) => {
           not_formatted_files_count.fetch_add(1, Ordering::Relaxed);
           let _g = output_lock.lock();
-          let diff = diff(&file_text, &formatted_text);
+          let diff = diff(
+            &file_text,
+            &formatted_text,
+            &DiffOptions {
+              granularity: DiffGranularity::Word,
+              context_lines: 0,
+            },
+          );
           info!("");
           info!("{} {}:", colors::bold("from"), file_path.display());
           info!("{}", diff);
@@ -335,6 +664,24 @@ async fn check_source_files(
   })
   .await?;
 
+  if diff_style == FmtDiffStyle::Patch {
+    // sorted so that the patch doesn't depend on the order files were checked
+    let mut patches = std::mem::take(&mut *patches.lock());
+    patches.sort_by(|a, b| a.0.cmp(&b.0));
+    let patch = patches
+      .into_iter()
+      .map(|(_, patch)| patch)
+      .collect::<String>();
+    // always write the file so that a previous patch isn't left behind
+    if let Some(diff_output) = &diff_output {
+      fs::write(diff_output, &patch).with_context(|| {
+        format!("Failed writing patch to '{}'.", diff_output.display())
+      })?;
+    } else {
+      stdout().write_all(patch.as_bytes())?;
+    }
+  }
+
   let not_formatted_files_count =
     not_formatted_files_count.load(Ordering::Relaxed);
   let checked_files_count = checked_files_count.load(Ordering::Relaxed);
@@ -353,7 +700,8 @@ async fn check_source_files(
 
 async fn format_source_files(
   paths: Vec<PathBuf>,
-  fmt_options: FmtOptionsConfig,
+  fmt_options: FmtOptions,
+  formatter: FileFormatter,
   incremental_cache: Arc<IncrementalCache>,
 ) -> Result<(), AnyError> {
   let formatted_files_count = Arc::new(AtomicUsize::new(0));
@@ -375,8 +723,10 @@ async fn format_source_files(
       match format_ensure_stable(
         &file_path,
         &file_contents.text,
-        &fmt_options,
-        format_file,
+        fmt_options.options_for_path(&file_path),
+        |file_path, file_text, fmt_options| {
+          formatter.format(file_path, file_text, fmt_options)
+        },
       ) {
         Ok(Some(formatted_text)) => {
           incremental_cache.update_file(&file_path, &formatted_text);
@@ -422,6 +772,207 @@ async fn format_source_files(
   Ok(())
 }
 
+/// Formats the lines of a single file. The incremental cache isn't used
+/// because the rest of the file might not be formatted.
+fn format_file_range(
+  file_path: &Path,
+  fmt_options: &FmtOptions,
+  formatter: &FileFormatter,
+  range: FmtRange,
+) -> Result<(), AnyError> {
+  let file_contents = read_file_contents(file_path)?;
+  let file_fmt_options = fmt_options.options_for_path(file_path);
+  let maybe_formatted_text = format_ensure_stable(
+    file_path,
+    &file_contents.text,
+    file_fmt_options,
+    |file_path, file_text, fmt_options| {
+      formatter.format(file_path, file_text, fmt_options)
+    },
+  )?
+  .map(|formatted_text| {
+    apply_formatting_in_statements(
+      formatter,
+      file_path,
+      &file_contents.text,
+      &formatted_text,
+      file_fmt_options,
+      range,
+    )
+  })
+  .transpose()?
+  .flatten();
+
+  if let Some(formatted_text) = maybe_formatted_text {
+    if fmt_options.check {
+      let diff = diff(
+        &file_contents.text,
+        &formatted_text,
+        &DiffOptions {
+          granularity: DiffGranularity::Word,
+          context_lines: 0,
+        },
+      );
+      info!("");
+      info!("{} {}:", colors::bold("from"), file_path.display());
+      info!("{}", diff);
+      bail!("Found 1 not formatted file in 1 file");
+    }
+    write_file_contents(
+      file_path,
+      FileContents {
+        had_bom: file_contents.had_bom,
+        text: formatted_text,
+      },
+    )?;
+    info!("{}", file_path.to_string_lossy());
+  }
+  info!("Checked 1 file");
+  Ok(())
+}
+
+/// Applies the formatting changes within the range after widening it to
+/// the statements it overlaps. The result is parsed again because only
+/// applying part of the changes could still break the code, for example
+/// when the range ends within a template literal.
+fn apply_formatting_in_statements(
+  formatter: &FileFormatter,
+  file_path: &Path,
+  file_text: &str,
+  formatted_text: &str,
+  fmt_options: &FmtOptionsConfig,
+  range: FmtRange,
+) -> Result<Option<String>, AnyError> {
+  let range = widen_range_to_statements(file_path, file_text, range);
+  let Some(result) =
+    apply_formatting_in_range(file_text, formatted_text, range)
+  else {
+    return Ok(None);
+  };
+  formatter
+    .format(file_path, &result, fmt_options)
+    .with_context(|| {
+      format!(
+        "Formatting lines {}-{} of {} produced invalid code",
+        range.start_line,
+        range.end_line,
+        file_path.display()
+      )
+    })?;
+  Ok(Some(result))
+}
+
+/// Widens the range to cover the lines of the top level statements of a
+/// JS or TS file that it overlaps, so that a statement is formatted as a
+/// whole or not at all. Other files keep the range as-is.
+fn widen_range_to_statements(
+  file_path: &Path,
+  file_text: &str,
+  mut range: FmtRange,
+) -> FmtRange {
+  let media_type = MediaType::from_path(file_path);
+  if !matches!(
+    media_type,
+    MediaType::JavaScript
+      | MediaType::Jsx
+      | MediaType::Mjs
+      | MediaType::Cjs
+      | MediaType::TypeScript
+      | MediaType::Mts
+      | MediaType::Cts
+      | MediaType::Dts
+      | MediaType::Dmts
+      | MediaType::Dcts
+      | MediaType::Tsx
+  ) {
+    return range;
+  }
+  let specifier = ModuleSpecifier::from_file_path(file_path)
+    .unwrap_or_else(|_| ModuleSpecifier::parse("file:///mod.ts").unwrap());
+  let Ok(parsed_source) = deno_ast::parse_module(deno_ast::ParseParams {
+    specifier,
+    text_info: SourceTextInfo::new(file_text.into()),
+    media_type,
+    capture_tokens: false,
+    scope_analysis: false,
+    maybe_syntax: None,
+  }) else {
+    // the formatter already reported the error
+    return range;
+  };
+  let text_info = parsed_source.text_info();
+  for item in &parsed_source.module().body {
+    let item_range = item.range();
+    let start_line = text_info.line_index(item_range.start) + 1;
+    let end_line = text_info.line_index(item_range.end) + 1;
+    if start_line <= range.end_line && end_line >= range.start_line {
+      range.start_line = range.start_line.min(start_line);
+      range.end_line = range.end_line.max(end_line);
+    }
+  }
+  range
+}
+
+/// Applies the changes from formatting the file that are within the range
+/// of lines to the original text, leaving the rest of the file as-is.
+fn apply_formatting_in_range(
+  file_text: &str,
+  formatted_text: &str,
+  range: FmtRange,
+) -> Option<String> {
+  let mut start = file_text.len();
+  let mut end = file_text.len();
+  let mut offset = 0;
+  for (i, line) in file_text.split_inclusive('\n').enumerate() {
+    if i + 1 == range.start_line {
+      start = offset;
+    }
+    offset += line.len();
+    if i + 1 == range.end_line {
+      end = offset;
+      break;
+    }
+  }
+  let is_insert_in_range =
+    |pos: usize| start <= pos && (pos < end || end == file_text.len());
+
+  let mut result = String::with_capacity(formatted_text.len());
+  let mut pos = 0;
+  let mut chunks = dissimilar::diff(file_text, formatted_text).into_iter();
+  while let Some(chunk) = chunks.next() {
+    match chunk {
+      dissimilar::Chunk::Equal(text) => {
+        result.push_str(text);
+        pos += text.len();
+      }
+      dissimilar::Chunk::Delete(text) => {
+        let delete_end = pos + text.len();
+        let is_in_range = pos < end && delete_end > start;
+        // an insert following a delete is a replacement, which is
+        // applied as a whole or not at all
+        let mut replacement = None;
+        let rest = chunks.as_slice();
+        if let Some(dissimilar::Chunk::Insert(insert)) = rest.first() {
+          replacement = Some(*insert);
+          chunks.next();
+        }
+        if is_in_range {
+          result.push_str(replacement.unwrap_or(""));
+        } else {
+          result.push_str(text);
+        }
+        pos = delete_end;
+      }
+      dissimilar::Chunk::Insert(text) => {
+        if is_insert_in_range(pos) {
+          result.push_str(text);
+        }
+      }
+    }
+  }
+  (result != file_text).then_some(result)
+}
+
 /// When storing any formatted text in the incremental cache, we want
 /// to ensure that anything stored when formatted will have itself as
 /// the output as well. This is to prevent "double format" issues where
@@ -487,13 +1038,41 @@ fn format_ensure_stable(
 /// Format stdin and write result to stdout.
 /// Treats input as set by `--ext` flag.
 /// Compatible with `--check` flag.
-fn format_stdin(fmt_options: FmtOptions, ext: &str) -> Result<(), AnyError> {
+fn format_stdin(
+  fmt_options: FmtOptions,
+  formatter: &FileFormatter,
+  ext: &str,
+) -> Result<(), AnyError> {
   let mut source = String::new();
   if stdin().read_to_string(&mut source).is_err() {
     bail!("Failed to read from stdin");
   }
   let file_path = PathBuf::from(format!("_stdin.{ext}"));
-  let formatted_text = format_file(&file_path, &source, &fmt_options.options)?;
+  if !formatter.can_format(&file_path) {
+    let feature = match ext {
+      "yml" | "yaml" => "yaml",
+      _ => "css",
+    };
+    bail!(
+      "Formatting {} is unstable. Enable it with --unstable-{feature} or by adding \"fmt-{feature}\" to the \"unstable\" configuration.",
+      ext.to_uppercase()
+    );
+  }
+  let mut formatted_text =
+    formatter.format(&file_path, &source, &fmt_options.options)?;
+  if let Some(range) = fmt_options.range {
+    formatted_text = match formatted_text {
+      Some(formatted_text) => apply_formatting_in_statements(
+        formatter,
+        &file_path,
+        &source,
+        &formatted_text,
+        &fmt_options.options,
+        range,
+      )?,
+      None => None,
+    };
+  }
   if fmt_options.check {
     #[allow(clippy::print_stdout)]
     if formatted_text.is_some() {
@@ -602,6 +1181,40 @@ fn get_resolved_json_config(
   builder.build()
 }
 
+fn get_resolved_malva_config(
+  options: &FmtOptionsConfig,
+) -> malva::config::FormatOptions {
+  let mut config = malva::config::FormatOptions::default();
+  config.layout.print_width = options.line_width.unwrap_or(80) as usize;
+  config.layout.indent_width = options.indent_width.unwrap_or(2) as usize;
+  config.layout.use_tabs = options.use_tabs.unwrap_or(false);
+  config.layout.line_break = malva::config::LineBreak::Lf;
+  config.language.quotes = if options.single_quote == Some(true) {
+    malva::config::Quotes::PreferSingle
+  } else {
+    malva::config::Quotes::PreferDouble
+  };
+  config.language.ignore_comment_directive = "deno-fmt-ignore".to_string();
+  config
+}
+
+fn get_resolved_yaml_config(
+  options: &FmtOptionsConfig,
+) -> pretty_yaml::config::FormatOptions {
+  let mut config = pretty_yaml::config::FormatOptions::default();
+  config.layout.print_width = options.line_width.unwrap_or(80) as usize;
+  // tabs are not allowed for indentation in YAML, so `useTabs` is ignored
+  config.layout.indent_width = options.indent_width.unwrap_or(2) as usize;
+  config.layout.line_break = pretty_yaml::config::LineBreak::Lf;
+  config.language.quotes = if options.single_quote == Some(true) {
+    pretty_yaml::config::Quotes::PreferSingle
+  } else {
+    pretty_yaml::config::Quotes::PreferDouble
+  };
+  config.language.ignore_comment_directive = "deno-fmt-ignore".to_string();
+  config
+}
+
 struct FileContents {
   text: String,
   had_bom: bool,
@@ -679,9 +1292,14 @@ where
 }
 
 /// This function is similar to is_supported_ext but adds additional extensions
-/// supported by `deno fmt`.
-fn is_supported_ext_fmt(path: &Path) -> bool {
+/// supported by `deno fmt`, including the unstable ones that were opted into.
+fn is_supported_ext_fmt(path: &Path, unstable: UnstableFmtOptions) -> bool {
   get_extension(path).is_some_and(|ext| {
+    match ext.as_str() {
+      "css" | "scss" | "less" => return unstable.css,
+      "yml" | "yaml" => return unstable.yaml,
+      _ => {}
+    }
     matches!(
       ext.as_str(),
       "ts"
@@ -711,30 +1329,61 @@ mod test {
 
   #[test]
   fn test_is_supported_ext_fmt() {
-    assert!(!is_supported_ext_fmt(Path::new("tests/subdir/redirects")));
-    assert!(is_supported_ext_fmt(Path::new("README.md")));
-    assert!(is_supported_ext_fmt(Path::new("readme.MD")));
-    assert!(is_supported_ext_fmt(Path::new("readme.mkd")));
-    assert!(is_supported_ext_fmt(Path::new("readme.mkdn")));
-    assert!(is_supported_ext_fmt(Path::new("readme.mdwn")));
-    assert!(is_supported_ext_fmt(Path::new("readme.mdown")));
-    assert!(is_supported_ext_fmt(Path::new("readme.markdown")));
-    assert!(is_supported_ext_fmt(Path::new("lib/typescript.d.ts")));
-    assert!(is_supported_ext_fmt(Path::new("testdata/run/001_hello.js")));
-    assert!(is_supported_ext_fmt(Path::new("testdata/run/002_hello.ts")));
-    assert!(is_supported_ext_fmt(Path::new("foo.jsx")));
-    assert!(is_supported_ext_fmt(Path::new("foo.tsx")));
-    assert!(is_supported_ext_fmt(Path::new("foo.TS")));
-    assert!(is_supported_ext_fmt(Path::new("foo.TSX")));
-    assert!(is_supported_ext_fmt(Path::new("foo.JS")));
-    assert!(is_supported_ext_fmt(Path::new("foo.JSX")));
-    assert!(is_supported_ext_fmt(Path::new("foo.mjs")));
-    assert!(!is_supported_ext_fmt(Path::new("foo.mjsx")));
-    assert!(is_supported_ext_fmt(Path::new("foo.jsonc")));
-    assert!(is_supported_ext_fmt(Path::new("foo.JSONC")));
-    assert!(is_supported_ext_fmt(Path::new("foo.json")));
-    assert!(is_supported_ext_fmt(Path::new("foo.JsON")));
-    assert!(is_supported_ext_fmt(Path::new("foo.ipynb")));
+    let unstable = UnstableFmtOptions {
+      css: true,
+      yaml: true,
+    };
+    assert!(!is_supported_ext_fmt(
+      Path::new("tests/subdir/redirects"),
+      unstable
+    ));
+    assert!(is_supported_ext_fmt(Path::new("README.md"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("readme.MD"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("readme.mkd"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("readme.mkdn"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("readme.mdwn"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("readme.mdown"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("readme.markdown"), unstable));
+    assert!(is_supported_ext_fmt(
+      Path::new("lib/typescript.d.ts"),
+      unstable
+    ));
+    assert!(is_supported_ext_fmt(
+      Path::new("testdata/run/001_hello.js"),
+      unstable
+    ));
+    assert!(is_supported_ext_fmt(
+      Path::new("testdata/run/002_hello.ts"),
+      unstable
+    ));
+    assert!(is_supported_ext_fmt(Path::new("foo.jsx"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("foo.tsx"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("foo.TS"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("foo.TSX"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("foo.JS"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("foo.JSX"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("foo.mjs"), unstable));
+    assert!(!is_supported_ext_fmt(Path::new("foo.mjsx"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("foo.jsonc"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("foo.JSONC"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("foo.json"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("foo.JsON"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("foo.ipynb"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("foo.css"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("foo.SCSS"), unstable));
+    assert!(is_supported_ext_fmt(Path::new("foo.less"), unstable));
+    assert!(!is_supported_ext_fmt(Path::new("foo.sass"), unstable));
+    assert!(is_supported_ext_fmt(
+      Path::new(".github/workflows/ci.yml"),
+      unstable
+    ));
+    assert!(is_supported_ext_fmt(Path::new("foo.YAML"), unstable));
+    // the unstable formatters need to be opted into
+    let stable = UnstableFmtOptions::default();
+    assert!(is_supported_ext_fmt(Path::new("foo.ts"), stable));
+    assert!(!is_supported_ext_fmt(Path::new("foo.css"), stable));
+    assert!(!is_supported_ext_fmt(Path::new("foo.less"), stable));
+    assert!(!is_supported_ext_fmt(Path::new("foo.yml"), stable));
   }
 
   #[test]
@@ -810,6 +1459,7 @@ mod test {
         single_quote: Some(true),
         ..Default::default()
       },
+      &Default::default(),
     )
     .unwrap()
     .unwrap();
@@ -819,4 +1469,219 @@ mod test {
       "console.log(\"there's\");\nconsole.log('hi');\nconsole.log('bye');\n",
     );
   }
+
+  #[test]
+  fn test_format_css() {
+    let fmt_options = FmtOptionsConfig {
+      indent_width: Some(4),
+      ..Default::default()
+    };
+    let file_text = "a{color:red;&:hover{content:'x'}}\n";
+    // not formatted without opting into the unstable formatter
+    assert_eq!(
+      format_file(
+        &PathBuf::from("styles.scss"),
+        file_text,
+        &fmt_options,
+        &Default::default(),
+      )
+      .unwrap(),
+      None,
+    );
+    let file_text = format_file(
+      &PathBuf::from("styles.scss"),
+      file_text,
+      &fmt_options,
+      &FormatFileOptions {
+        unstable: UnstableFmtOptions {
+          css: true,
+          ..Default::default()
+        },
+        ..Default::default()
+      },
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+      file_text,
+      "a {\n    color: red;\n    &:hover {\n        content: \"x\";\n    }\n}\n",
+    );
+  }
+
+  fn unstable_yaml_options() -> FormatFileOptions {
+    FormatFileOptions {
+      unstable: UnstableFmtOptions {
+        yaml: true,
+        ..Default::default()
+      },
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn test_format_yaml() {
+    let file_text = "name:   'ci'\non:\n    push:\n      branches: [ main ]\n";
+    // not formatted without opting into the unstable formatter
+    assert_eq!(
+      format_file(
+        &PathBuf::from("config.yml"),
+        file_text,
+        &Default::default(),
+        &Default::default(),
+      )
+      .unwrap(),
+      None,
+    );
+    let file_text = format_file(
+      &PathBuf::from("config.yml"),
+      file_text,
+      &Default::default(),
+      &unstable_yaml_options(),
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+      file_text,
+      "name: \"ci\"\non:\n  push:\n    branches: [main]\n",
+    );
+  }
+
+  #[test]
+  fn test_format_markdown_yaml_front_matter() {
+    let file_text = format_file(
+      &PathBuf::from("README.md"),
+      "---\ntitle:    'Hello'\n---\n\n# Hello\n",
+      &Default::default(),
+      &unstable_yaml_options(),
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(file_text, "---\ntitle: \"Hello\"\n---\n\n# Hello\n");
+  }
+
+  #[test]
+  fn test_format_markdown_code_blocks() {
+    let file_text = concat!(
+      "```json\n{\"a\":1}\n```\n\n",
+      "````md\n#   Title\n```yml\nkey:   'value'\n```\n````\n",
+    );
+    assert_eq!(
+      format_file(
+        &PathBuf::from("README.md"),
+        file_text,
+        &Default::default(),
+        &unstable_yaml_options(),
+      )
+      .unwrap()
+      .unwrap(),
+      concat!(
+        "```json\n{ \"a\": 1 }\n```\n\n",
+        "````md\n# Title\n\n```yml\nkey: \"value\"\n```\n````\n",
+      ),
+    );
+    // languages are matched along with their aliases
+    assert_eq!(
+      format_file(
+        &PathBuf::from("README.md"),
+        file_text,
+        &Default::default(),
+        &FormatFileOptions {
+          skip_code_block_languages: vec![
+            "JSON".to_string(),
+            "yaml".to_string()
+          ],
+          ..unstable_yaml_options()
+        },
+      )
+      .unwrap()
+      .unwrap(),
+      concat!(
+        "```json\n{\"a\":1}\n```\n\n",
+        "````md\n# Title\n\n```yml\nkey:   'value'\n```\n````\n",
+      ),
+    );
+  }
+
+  #[test]
+  fn test_apply_formatting_in_range() {
+    let file_text = "const a  =  1;\nconst b  =  2;\nconst c  =  3;\n";
+    let formatted_text = "const a = 1;\nconst b = 2;\nconst c = 3;\n";
+    let range = |start_line, end_line| FmtRange {
+      start_line,
+      end_line,
+    };
+    assert_eq!(
+      apply_formatting_in_range(file_text, formatted_text, range(2, 2))
+        .unwrap(),
+      "const a  =  1;\nconst b = 2;\nconst c  =  3;\n",
+    );
+    assert_eq!(
+      apply_formatting_in_range(file_text, formatted_text, range(2, 3))
+        .unwrap(),
+      "const a  =  1;\nconst b = 2;\nconst c = 3;\n",
+    );
+    assert_eq!(
+      apply_formatting_in_range(file_text, formatted_text, range(1, 10))
+        .unwrap(),
+      formatted_text,
+    );
+    assert_eq!(
+      apply_formatting_in_range(formatted_text, formatted_text, range(1, 1)),
+      None,
+    );
+  }
+
+  #[test]
+  fn test_apply_formatting_in_statements() {
+    let formatter = FileFormatter {
+      plugins: None,
+      sort_imports: false,
+      file_options: Default::default(),
+    };
+    let file_path = PathBuf::from("mod.ts");
+    let file_text =
+      "const a  =  1;\nfunction f( ) {\n  return  [\n    1,2 ];\n}\n";
+    let formatted_text = format_file(
+      &file_path,
+      file_text,
+      &Default::default(),
+      &Default::default(),
+    )
+    .unwrap()
+    .unwrap();
+    // the range only covers part of the function, which is formatted whole
+    assert_eq!(
+      apply_formatting_in_statements(
+        &formatter,
+        &file_path,
+        file_text,
+        &formatted_text,
+        &Default::default(),
+        FmtRange {
+          start_line: 4,
+          end_line: 4,
+        },
+      )
+      .unwrap()
+      .unwrap(),
+      format!(
+        "const a  =  1;\n{}",
+        formatted_text.strip_prefix("const a = 1;\n").unwrap()
+      ),
+    );
+    assert_eq!(
+      widen_range_to_statements(
+        &file_path,
+        file_text,
+        FmtRange {
+          start_line: 1,
+          end_line: 1,
+        },
+      ),
+      FmtRange {
+        start_line: 1,
+        end_line: 1,
+      },
+    );
+  }
 }
@@ -0,0 +1,203 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+// Runs dprint Wasm plugins (plugin schema version 4) for `deno fmt`.
+//
+// The plugins are instantiated in an isolate without any of the Deno APIs and
+// only receive the host functions of the dprint plugin protocol, so they can't
+// access the file system, the network or the environment.
+
+"use strict";
+
+((globalThis) => {
+  const core = globalThis.Deno.core;
+  const ops = core.ops;
+  /** @type {Plugin[]} */
+  const plugins = [];
+
+  /**
+   * @typedef Plugin
+   * @property {WebAssembly.Exports} exports
+   * @property {Uint8Array} hostBuffer
+   * @property {Record<string, unknown>} options
+   * @property {Map<string, number>} configIds
+   */
+
+  /**
+   * Provides the imports of the module. Anything other than the dprint
+   * host functions is stubbed out.
+   * @param {WebAssembly.Module} module
+   * @param {Plugin} plugin
+   */
+  function createImports(module, plugin) {
+    const imports = {};
+    for (const { module: moduleName, name, kind } of WebAssembly.Module
+      .imports(module)) {
+      if (kind !== "function") {
+        throw new Error(`Unsupported import: ${moduleName}.${name} (${kind})`);
+      }
+      imports[moduleName] ??= {};
+      imports[moduleName][name] = moduleName === "dprint"
+        ? createHostFunction(name, plugin)
+        : // ENOSYS for wasi and a no-op for anything else
+          () => (moduleName.startsWith("wasi") ? 52 : 0);
+    }
+    return imports;
+  }
+
+  /**
+   * @param {string} name
+   * @param {Plugin} plugin
+   */
+  function createHostFunction(name, plugin) {
+    switch (name) {
+      case "host_write_buffer":
+        return (ptr) => {
+          new Uint8Array(
+            plugin.exports.memory.buffer,
+            ptr,
+            plugin.hostBuffer.length,
+          ).set(plugin.hostBuffer);
+        };
+      case "host_format":
+        // formatting embedded code with other plugins is not supported,
+        // so report that the text didn't change
+        return () => 0;
+      case "host_get_formatted_text":
+      case "host_get_error_text":
+        return () => {
+          plugin.hostBuffer = new Uint8Array();
+          return 0;
+        };
+      default:
+        return () => 0;
+    }
+  }
+
+  /**
+   * @param {Plugin} plugin
+   * @param {Uint8Array} bytes
+   */
+  function writeSharedBytes(plugin, bytes) {
+    const ptr = plugin.exports.clear_shared_bytes(bytes.length);
+    new Uint8Array(plugin.exports.memory.buffer, ptr, bytes.length).set(bytes);
+  }
+
+  /**
+   * @param {Plugin} plugin
+   * @param {number} length
+   */
+  function readSharedText(plugin, length) {
+    const ptr = plugin.exports.get_shared_bytes_ptr();
+    return core.decode(
+      new Uint8Array(plugin.exports.memory.buffer, ptr, length).slice(),
+    );
+  }
+
+  /**
+   * Registers the configuration with the plugin the first time it's used
+   * and provides its id.
+   * @param {Plugin} plugin
+   * @param {Record<string, unknown>} global
+   */
+  function getConfigId(plugin, global) {
+    const key = JSON.stringify(global);
+    let configId = plugin.configIds.get(key);
+    if (configId == null) {
+      configId = plugin.configIds.size + 1;
+      writeSharedBytes(
+        plugin,
+        core.encode(JSON.stringify({ plugin: plugin.options, global })),
+      );
+      plugin.exports.register_config(configId);
+      const diagnostics = JSON.parse(
+        readSharedText(plugin, plugin.exports.get_config_diagnostics(configId)),
+      );
+      if (diagnostics.length > 0) {
+        throw new Error(
+          diagnostics
+            .map((d) => `${d.propertyName}: ${d.message}`)
+            .join("\n"),
+        );
+      }
+      plugin.configIds.set(key, configId);
+    }
+    return configId;
+  }
+
+  /**
+   * @param {{ options: Record<string, unknown> }[]} configs
+   * @param {Record<string, unknown>} global
+   */
+  function loadPlugins(configs, global) {
+    return configs.map(({ options }, index) => {
+      const module = new WebAssembly.Module(ops.op_fmt_plugin_bytes(index));
+      /** @type {Plugin} */
+      const plugin = {
+        exports: null,
+        hostBuffer: new Uint8Array(),
+        options,
+        configIds: new Map(),
+      };
+      plugin.exports =
+        new WebAssembly.Instance(module, createImports(module, plugin)).exports;
+      if (typeof plugin.exports.dprint_plugin_version_4 !== "function") {
+        throw new Error(
+          "Unsupported plugin. Only dprint plugins using schema version 4 are supported.",
+        );
+      }
+      plugins.push(plugin);
+
+      const info = JSON.parse(
+        readSharedText(plugin, plugin.exports.get_plugin_info()),
+      );
+      const configId = getConfigId(plugin, global);
+      const fileMatching =
+        typeof plugin.exports.get_config_file_matching === "function"
+          ? JSON.parse(
+            readSharedText(
+              plugin,
+              plugin.exports.get_config_file_matching(configId),
+            ),
+          )
+          : info;
+      return {
+        name: info.name,
+        version: info.version,
+        fileExtensions: fileMatching.fileExtensions ?? [],
+        fileNames: fileMatching.fileNames ?? [],
+      };
+    });
+  }
+
+  /**
+   * @param {{
+   *   plugin: number,
+   *   global: Record<string, unknown>,
+   *   filePath: string,
+   *   fileText: string,
+   * }} request
+   * @returns {string | null}
+   */
+  function formatText({ plugin: index, global, filePath, fileText }) {
+    const plugin = plugins[index];
+    const configId = getConfigId(plugin, global);
+    writeSharedBytes(plugin, core.encode(filePath));
+    plugin.exports.set_file_path();
+    writeSharedBytes(plugin, core.encode(fileText));
+    switch (plugin.exports.format(configId)) {
+      case 0:
+        return null;
+      case 1:
+        return readSharedText(plugin, plugin.exports.get_formatted_text());
+      case 2:
+        throw new Error(
+          readSharedText(plugin, plugin.exports.get_error_text()),
+        );
+      default:
+        throw new Error("Unexpected format result from the plugin.");
+    }
+  }
+
+  globalThis.loadPlugins = loadPlugins;
+  globalThis.formatText = formatText;
+})(globalThis);
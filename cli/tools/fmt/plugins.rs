@@ -0,0 +1,278 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Support for formatting files with the dprint Wasm plugins declared in
+//! `fmt.plugins`, for languages `deno fmt` doesn't format itself.
+//!
+//! The plugins are fetched with the file fetcher, so remote plugins are
+//! cached in the DENO_DIR and checked against the lockfile, but only with
+//! access to the directories of the local plugins and the hosts of the
+//! remote ones. They run in a dedicated V8 isolate that only provides the
+//! host functions of the dprint plugin protocol.
+
+use std::path::Path;
+use std::sync::mpsc;
+use std::sync::Arc;
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::futures::channel::oneshot;
+use deno_core::located_script_name;
+use deno_core::op2;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde::de::DeserializeOwned;
+use deno_core::serde::Deserialize;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_v8;
+use deno_core::v8;
+use deno_core::JsRuntime;
+use deno_core::OpState;
+use deno_core::RuntimeOptions;
+use deno_core::ToJsBuffer;
+
+use crate::args::plugin_permissions;
+use crate::args::FmtOptionsConfig;
+use crate::args::FmtPluginConfig;
+use crate::args::Lockfile;
+use crate::file_fetcher::FileFetcher;
+use crate::graph_util::check_plugin_lock;
+use crate::util::checksum;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FmtPluginInfo {
+  name: String,
+  version: String,
+  file_extensions: Vec<String>,
+  file_names: Vec<String>,
+}
+
+impl FmtPluginInfo {
+  fn matches_path(&self, path: &Path) -> bool {
+    let Some(file_name) = path.file_name() else {
+      return false;
+    };
+    let file_name = file_name.to_string_lossy().to_lowercase();
+    self
+      .file_names
+      .iter()
+      .any(|name| name.to_lowercase() == file_name)
+      || self.file_extensions.iter().any(|ext| {
+        file_name
+          .strip_suffix(&ext.to_lowercase())
+          .is_some_and(|stem| stem.ends_with('.'))
+      })
+  }
+}
+
+struct FormatRequest {
+  /// The request serialized as a JS expression.
+  json: String,
+  response: mpsc::Sender<Result<Option<String>, AnyError>>,
+}
+
+/// Wasm bytes of the plugins, which are handed over to the isolate once.
+struct FmtPluginWasmModules(Vec<Option<Arc<[u8]>>>);
+
+#[op2]
+#[serde]
+fn op_fmt_plugin_bytes(state: &mut OpState, #[smi] index: u32) -> ToJsBuffer {
+  let modules = state.borrow_mut::<FmtPluginWasmModules>();
+  let bytes = modules.0[index as usize].take().unwrap();
+  ToJsBuffer::from(bytes.to_vec())
+}
+
+deno_core::extension!(deno_fmt_plugins,
+  ops = [op_fmt_plugin_bytes],
+  options = {
+    wasm_modules: Vec<Arc<[u8]>>,
+  },
+  state = |state, options| {
+    state.put(FmtPluginWasmModules(
+      options.wasm_modules.into_iter().map(Some).collect(),
+    ));
+  },
+);
+
+/// Runs the loaded plugins on a dedicated thread, because an isolate can't
+/// be shared between the threads formatting the files.
+pub struct FmtPluginHost {
+  plugins: Vec<FmtPluginInfo>,
+  wasm_checksums: Vec<String>,
+  sender: mpsc::Sender<FormatRequest>,
+}
+
+impl FmtPluginHost {
+  pub async fn load(
+    file_fetcher: &FileFetcher,
+    maybe_lockfile: Option<&Arc<Mutex<Lockfile>>>,
+    configs: &[FmtPluginConfig],
+    options: &FmtOptionsConfig,
+  ) -> Result<Self, AnyError> {
+    let permissions = plugin_permissions(
+      &configs
+        .iter()
+        .map(|c| c.specifier.clone())
+        .collect::<Vec<_>>(),
+    )?;
+    let mut wasm_modules = Vec::with_capacity(configs.len());
+    let mut wasm_checksums = Vec::with_capacity(configs.len());
+    for config in configs {
+      let file = file_fetcher
+        .fetch(&config.specifier, &permissions)
+        .await
+        .with_context(|| {
+          format!("Failed fetching fmt plugin '{}'.", config.specifier)
+        })?;
+      if let Some(lockfile) = maybe_lockfile {
+        check_plugin_lock(&file.specifier, &file.source, &mut lockfile.lock())?;
+      }
+      wasm_checksums.push(checksum::gen(&[&file.source]));
+      wasm_modules.push(file.source);
+    }
+    let load_request = format!(
+      "globalThis.loadPlugins({}, {})",
+      serde_json::to_string(
+        &configs
+          .iter()
+          .map(|c| json!({ "options": c.options }))
+          .collect::<Vec<_>>()
+      )?,
+      global_config(options),
+    );
+
+    let (init_sender, init_receiver) = oneshot::channel();
+    let (sender, receiver) = mpsc::channel::<FormatRequest>();
+    std::thread::spawn(move || {
+      let mut runtime = JsRuntime::new(RuntimeOptions {
+        extensions: vec![deno_fmt_plugins::init_ops(wasm_modules)],
+        ..Default::default()
+      });
+      let init_result = runtime
+        .execute_script(
+          located_script_name!(),
+          include_str!("plugin_host.js").to_string(),
+        )
+        .map_err(AnyError::from)
+        .and_then(|_| {
+          execute::<Vec<FmtPluginInfo>>(&mut runtime, load_request)
+        });
+      let is_ok = init_result.is_ok();
+      if init_sender.send(init_result).is_err() || !is_ok {
+        return;
+      }
+      // runs until the host is dropped
+      for request in receiver {
+        let result = execute::<Option<String>>(&mut runtime, request.json);
+        let _ = request.response.send(result);
+      }
+    });
+
+    let plugins = init_receiver
+      .await?
+      .context("Failed loading fmt plugins.")?;
+    for plugin in &plugins {
+      log::debug!("Loaded fmt plugin {} {}", plugin.name, plugin.version);
+    }
+    Ok(Self {
+      plugins,
+      wasm_checksums,
+      sender,
+    })
+  }
+
+  /// Checksums of the Wasm modules of the plugins, in the order of the
+  /// configuration, so that a plugin that was rebuilt or updated causes
+  /// the files to be formatted again.
+  pub fn wasm_checksums(&self) -> &[String] {
+    &self.wasm_checksums
+  }
+
+  /// Gets the index of the plugin formatting the file, if any.
+  pub fn plugin_for_path(&self, path: &Path) -> Option<usize> {
+    self.plugins.iter().position(|p| p.matches_path(path))
+  }
+
+  pub fn format_file(
+    &self,
+    plugin_index: usize,
+    file_path: &Path,
+    file_text: &str,
+    options: &FmtOptionsConfig,
+  ) -> Result<Option<String>, AnyError> {
+    let request = json!({
+      "plugin": plugin_index,
+      "global": global_config(options),
+      "filePath": file_path.to_string_lossy(),
+      "fileText": file_text,
+    });
+    let (response_sender, response_receiver) = mpsc::channel();
+    self.sender.send(FormatRequest {
+      json: format!("globalThis.formatText({request})"),
+      response: response_sender,
+    })?;
+    response_receiver.recv()?.with_context(|| {
+      format!("Error in fmt plugin '{}'", self.plugins[plugin_index].name)
+    })
+  }
+}
+
+/// Gets the dprint global configuration, which the plugins use as the
+/// defaults of their own options.
+fn global_config(options: &FmtOptionsConfig) -> serde_json::Value {
+  json!({
+    "lineWidth": options.line_width.unwrap_or(80),
+    "indentWidth": options.indent_width.unwrap_or(2),
+    "useTabs": options.use_tabs.unwrap_or(false),
+    "newLineKind": "lf",
+  })
+}
+
+fn execute<T: DeserializeOwned>(
+  runtime: &mut JsRuntime,
+  source: String,
+) -> Result<T, AnyError> {
+  let value = runtime.execute_script(located_script_name!(), source)?;
+  let scope = &mut runtime.handle_scope();
+  let local = v8::Local::new(scope, value);
+  Ok(serde_v8::from_v8::<T>(scope, local)?)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_plugin_matches_path() {
+    let plugin = FmtPluginInfo {
+      name: "dprint-plugin-sql".to_string(),
+      version: "0.1.0".to_string(),
+      file_extensions: vec!["sql".to_string(), "pg.sql".to_string()],
+      file_names: vec!["Schemafile".to_string()],
+    };
+    assert!(plugin.matches_path(Path::new("/db/query.sql")));
+    assert!(plugin.matches_path(Path::new("/db/query.SQL")));
+    assert!(plugin.matches_path(Path::new("/db/query.pg.sql")));
+    assert!(plugin.matches_path(Path::new("/db/schemafile")));
+    assert!(!plugin.matches_path(Path::new("/db/nosql")));
+    assert!(!plugin.matches_path(Path::new("/db/query.ts")));
+  }
+
+  #[test]
+  fn test_global_config() {
+    let options = FmtOptionsConfig {
+      line_width: Some(100),
+      use_tabs: Some(true),
+      ..Default::default()
+    };
+    assert_eq!(
+      global_config(&options),
+      json!({
+        "lineWidth": 100,
+        "indentWidth": 2,
+        "useTabs": true,
+        "newLineKind": "lf",
+      })
+    );
+  }
+}
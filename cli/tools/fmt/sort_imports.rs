@@ -0,0 +1,305 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Sorting and grouping of the import declarations at the top of a module,
+//! which is enabled with `"fmt": { "sortImports": true }`.
+//!
+//! The imports are grouped as std, remote, npm and relative imports, with a
+//! blank line between the groups, and sorted by specifier within a group.
+//! Side effect imports (ex. `import "./polyfill.ts";`) are kept in place
+//! because the order they're evaluated in might matter.
+
+use std::path::Path;
+
+use deno_ast::swc::ast;
+use deno_ast::MediaType;
+use deno_ast::ModuleSpecifier;
+use deno_ast::SourceRangedForSpanned;
+use deno_ast::SourceTextInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ImportGroup {
+  Std,
+  Remote,
+  Npm,
+  Relative,
+}
+
+impl ImportGroup {
+  pub fn from_specifier(specifier: &str) -> Self {
+    if specifier.starts_with("./")
+      || specifier.starts_with("../")
+      || specifier.starts_with('/')
+      || specifier.starts_with("file:")
+    {
+      ImportGroup::Relative
+    } else if specifier.starts_with("npm:") {
+      ImportGroup::Npm
+    } else if specifier.starts_with("node:")
+      || specifier.starts_with("@std/")
+      || specifier.starts_with("jsr:@std/")
+      || specifier.starts_with("jsr:/@std/")
+      || specifier.starts_with("https://deno.land/std@")
+      || specifier.starts_with("https://deno.land/std/")
+    {
+      ImportGroup::Std
+    } else {
+      ImportGroup::Remote
+    }
+  }
+}
+
+struct ImportChunk<'a> {
+  specifier: String,
+  /// The text of the import, including the comments above it.
+  text: &'a str,
+  is_side_effect: bool,
+}
+
+/// Sorts the imports at the top of a JS or TS file. Returns `None` when
+/// nothing changed or the file couldn't be parsed, in which case the
+/// formatter reports the error.
+pub fn sort_imports(file_path: &Path, file_text: &str) -> Option<String> {
+  let media_type = MediaType::from_path(file_path);
+  if !matches!(
+    media_type,
+    MediaType::JavaScript
+      | MediaType::Jsx
+      | MediaType::Mjs
+      | MediaType::Cjs
+      | MediaType::TypeScript
+      | MediaType::Mts
+      | MediaType::Cts
+      | MediaType::Dts
+      | MediaType::Dmts
+      | MediaType::Dcts
+      | MediaType::Tsx
+  ) {
+    return None;
+  }
+  let specifier = ModuleSpecifier::from_file_path(file_path)
+    .unwrap_or_else(|_| ModuleSpecifier::parse("file:///mod.ts").unwrap());
+  let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+    specifier,
+    text_info: SourceTextInfo::new(file_text.into()),
+    media_type,
+    capture_tokens: false,
+    scope_analysis: false,
+    maybe_syntax: None,
+  })
+  .ok()?;
+  let text_info = parsed_source.text_info();
+  let text = text_info.text_str();
+  let text_start = text_info.range().start;
+
+  let imports = parsed_source
+    .module()
+    .body
+    .iter()
+    .map_while(|item| match item {
+      ast::ModuleItem::ModuleDecl(ast::ModuleDecl::Import(import)) => {
+        Some(import)
+      }
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+  if imports.len() < 2 {
+    return None;
+  }
+
+  // each import owns the lines from the end of the previous import to the
+  // end of its own line, so the comments above an import move with it
+  let block_start = imports[0].range().as_byte_range(text_start).start;
+  let mut chunk_start = block_start;
+  let mut chunks = Vec::with_capacity(imports.len());
+  for import in &imports {
+    let end = import.range().as_byte_range(text_start).end;
+    let line_end = text[end..]
+      .find('\n')
+      .map(|i| end + i)
+      .unwrap_or(text.len());
+    let rest_of_line = text[end..line_end].trim();
+    if !rest_of_line.is_empty() && !rest_of_line.starts_with("//") {
+      // another statement on the same line
+      return None;
+    }
+    chunks.push(ImportChunk {
+      specifier: import.src.value.to_string(),
+      text: trim_blank_lines(&text[chunk_start..line_end]),
+      is_side_effect: import.specifiers.is_empty() && !import.type_only,
+    });
+    chunk_start = line_end;
+  }
+  let block_end = chunk_start;
+
+  let mut blocks = Vec::new();
+  let mut groups: Vec<Vec<ImportChunk>> = Vec::new();
+  for chunk in chunks {
+    if chunk.is_side_effect {
+      blocks.extend(groups_to_blocks(std::mem::take(&mut groups)));
+      blocks.push(chunk.text.to_string());
+      continue;
+    }
+    let group = ImportGroup::from_specifier(&chunk.specifier) as usize;
+    if groups.len() <= group {
+      groups.resize_with(group + 1, Vec::new);
+    }
+    groups[group].push(chunk);
+  }
+  blocks.extend(groups_to_blocks(groups));
+
+  let mut new_text = String::with_capacity(text.len());
+  new_text.push_str(&text[..block_start]);
+  new_text.push_str(&blocks.join("\n\n"));
+  new_text.push_str(&text[block_end..]);
+  (new_text != text).then_some(new_text)
+}
+
+fn groups_to_blocks(groups: Vec<Vec<ImportChunk>>) -> Vec<String> {
+  groups
+    .into_iter()
+    .filter(|group| !group.is_empty())
+    .map(|mut group| {
+      group.sort_by(|a, b| {
+        a.specifier
+          .cmp(&b.specifier)
+          .then_with(|| a.text.cmp(b.text))
+      });
+      group
+        .iter()
+        .map(|chunk| chunk.text)
+        .collect::<Vec<_>>()
+        .join("\n")
+    })
+    .collect()
+}
+
+/// Removes the blank lines before and after the text.
+fn trim_blank_lines(text: &str) -> &str {
+  let text = text.trim_end();
+  let mut start = 0;
+  for line in text.split_inclusive('\n') {
+    if !line.trim().is_empty() {
+      break;
+    }
+    start += line.len();
+  }
+  &text[start..]
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn sort(text: &str) -> Option<String> {
+    sort_imports(Path::new("/project/mod.ts"), text)
+  }
+
+  #[test]
+  fn test_import_group() {
+    assert_eq!(
+      ImportGroup::from_specifier("jsr:@std/assert"),
+      ImportGroup::Std
+    );
+    assert_eq!(ImportGroup::from_specifier("@std/path"), ImportGroup::Std);
+    assert_eq!(ImportGroup::from_specifier("node:fs"), ImportGroup::Std);
+    assert_eq!(
+      ImportGroup::from_specifier("https://deno.land/std@0.224.0/fs/mod.ts"),
+      ImportGroup::Std
+    );
+    assert_eq!(
+      ImportGroup::from_specifier("jsr:@oak/oak"),
+      ImportGroup::Remote
+    );
+    assert_eq!(
+      ImportGroup::from_specifier("https://esm.sh/preact"),
+      ImportGroup::Remote
+    );
+    assert_eq!(ImportGroup::from_specifier("npm:chalk@5"), ImportGroup::Npm);
+    assert_eq!(ImportGroup::from_specifier("./a.ts"), ImportGroup::Relative);
+    assert_eq!(
+      ImportGroup::from_specifier("../b.ts"),
+      ImportGroup::Relative
+    );
+  }
+
+  #[test]
+  fn sorts_and_groups_imports() {
+    let text = concat!(
+      "// Copyright header\n",
+      "\n",
+      "import { b } from \"./b.ts\";\n",
+      "import chalk from \"npm:chalk@5\";\n",
+      "// the assertions\n",
+      "import { assertEquals } from \"jsr:@std/assert\";\n",
+      "import { a } from \"./a.ts\";\n",
+      "\n",
+      "import { Application } from \"jsr:@oak/oak\";\n",
+      "import type { Foo } from \"./types.ts\";\n",
+      "\n",
+      "console.log(a, b);\n",
+    );
+    assert_eq!(
+      sort(text).unwrap(),
+      concat!(
+        "// Copyright header\n",
+        "\n",
+        "// the assertions\n",
+        "import { assertEquals } from \"jsr:@std/assert\";\n",
+        "\n",
+        "import { Application } from \"jsr:@oak/oak\";\n",
+        "\n",
+        "import chalk from \"npm:chalk@5\";\n",
+        "\n",
+        "import { a } from \"./a.ts\";\n",
+        "import { b } from \"./b.ts\";\n",
+        "import type { Foo } from \"./types.ts\";\n",
+        "\n",
+        "console.log(a, b);\n",
+      )
+    );
+  }
+
+  #[test]
+  fn keeps_side_effect_imports_in_place() {
+    let text = concat!(
+      "import { b } from \"./b.ts\";\n",
+      "import { a } from \"./a.ts\";\n",
+      "import \"./polyfill.ts\";\n",
+      "import { d } from \"./d.ts\";\n",
+      "import { c } from \"./c.ts\";\n",
+    );
+    assert_eq!(
+      sort(text).unwrap(),
+      concat!(
+        "import { a } from \"./a.ts\";\n",
+        "import { b } from \"./b.ts\";\n",
+        "\n",
+        "import \"./polyfill.ts\";\n",
+        "\n",
+        "import { c } from \"./c.ts\";\n",
+        "import { d } from \"./d.ts\";\n",
+      )
+    );
+  }
+
+  #[test]
+  fn skips_sorted_and_unsupported_files() {
+    let text = concat!(
+      "import { a } from \"./a.ts\";\n",
+      "import { b } from \"./b.ts\";\n",
+    );
+    assert_eq!(sort(text), None);
+    assert_eq!(
+      sort_imports(Path::new("/project/README.md"), "# Hello\n"),
+      None
+    );
+    assert_eq!(sort("import { from \"./a.ts\";\n"), None);
+    // another statement on the line of an import
+    assert_eq!(
+      sort(
+        "import { b } from \"./b.ts\"; b();\nimport { a } from \"./a.ts\";\n"
+      ),
+      None
+    );
+  }
+}
@@ -1,9 +1,11 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
 use std::fmt::Write;
+use std::path::Path;
 
 use deno_ast::ModuleSpecifier;
 use deno_core::anyhow::bail;
@@ -21,11 +23,16 @@ use deno_graph::Resolution;
 use deno_npm::resolution::NpmResolutionSnapshot;
 use deno_npm::NpmPackageId;
 use deno_npm::NpmResolutionPackage;
+use deno_semver::jsr::JsrPackageReqReference;
 use deno_semver::npm::NpmPackageNvReference;
 use deno_semver::npm::NpmPackageReqReference;
 use deno_semver::package::PackageNv;
+use deno_semver::package::PackageReq;
 use deno_terminal::colors;
+use serde::Serialize;
 
+use crate::args::jsr_url;
+use crate::args::write_lockfile;
 use crate::args::Flags;
 use crate::args::InfoFlags;
 use crate::display;
@@ -38,6 +45,9 @@ use crate::util::checksum;
 pub async fn info(flags: Flags, info_flags: InfoFlags) -> Result<(), AnyError> {
   let factory = CliFactory::from_flags(flags)?;
   let cli_options = factory.cli_options();
+  if info_flags.unused {
+    return print_unused_dependencies(&factory, info_flags).await;
+  }
   if let Some(specifier) = info_flags.file {
     let module_graph_builder = factory.module_graph_builder().await?;
     let module_graph_creator = factory.module_graph_creator().await?;
@@ -74,7 +84,17 @@ pub async fn info(flags: Flags, info_flags: InfoFlags) -> Result<(), AnyError> {
       // validate the integrity of all the modules
       graph_lock_or_exit(&graph, &mut lockfile);
       // update it with anything new
-      lockfile.write().context("Failed writing lockfile.")?;
+      write_lockfile(&lockfile).context("Failed writing lockfile.")?;
+    }
+
+    if let Some(why) = &info_flags.why {
+      return print_importers(
+        &graph,
+        npm_resolver.as_ref(),
+        why,
+        cli_options.initial_cwd(),
+        info_flags.json,
+      );
     }
 
     if info_flags.json {
@@ -172,6 +192,423 @@ fn print_cache_info(
   }
 }
 
+/// Import map entries and lockfile packages that aren't used by the
+/// module graph.
+#[derive(Debug, Default, Serialize)]
+struct UnusedDependencies {
+  imports: Vec<String>,
+  packages: Vec<String>,
+}
+
+async fn print_unused_dependencies(
+  factory: &CliFactory,
+  info_flags: InfoFlags,
+) -> Result<(), AnyError> {
+  let cli_options = factory.cli_options();
+  let roots = match &info_flags.file {
+    Some(file) => vec![resolve_url_or_path(file, cli_options.initial_cwd())?],
+    None => {
+      let Some(config_file) = cli_options.maybe_config_file() else {
+        bail!("Provide a file or use a config file with \"exports\" to find unused dependencies.");
+      };
+      let exports = config_file.to_exports_config()?.into_map();
+      if exports.is_empty() {
+        bail!("Provide a file or add \"exports\" to the config file to find unused dependencies.");
+      }
+      exports
+        .values()
+        .map(|path| config_file.specifier.join(path))
+        .collect::<Result<Vec<_>, _>>()?
+    }
+  };
+
+  let module_graph_creator = factory.module_graph_creator().await?;
+  let graph = module_graph_creator
+    .create_graph(GraphKind::All, roots)
+    .await?;
+  module_graph_creator.graph_valid(&graph)?;
+
+  let import_map_keys = match factory.maybe_import_map().await? {
+    Some(import_map) => {
+      import_map.imports().keys().map(|k| k.to_string()).collect()
+    }
+    None => Vec::new(),
+  };
+  let lockfile_specifiers = match factory.maybe_lockfile() {
+    Some(lockfile) => lockfile
+      .lock()
+      .content
+      .packages
+      .specifiers
+      .keys()
+      .cloned()
+      .collect(),
+    None => Vec::new(),
+  };
+  let unused =
+    find_unused_dependencies(&graph, import_map_keys, lockfile_specifiers);
+
+  if info_flags.json {
+    return display::write_json_to_stdout(&unused);
+  }
+  let mut output = String::new();
+  if unused.imports.is_empty() && unused.packages.is_empty() {
+    writeln!(output, "No unused dependencies found.")?;
+  }
+  if !unused.imports.is_empty() {
+    writeln!(output, "{}", colors::bold("Unused import map entries:"))?;
+    for key in &unused.imports {
+      writeln!(output, "  {}", key)?;
+    }
+  }
+  if !unused.packages.is_empty() {
+    writeln!(output, "{}", colors::bold("Unused lockfile packages:"))?;
+    for specifier in &unused.packages {
+      writeln!(output, "  {}", specifier)?;
+    }
+  }
+  display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
+  Ok(())
+}
+
+fn find_unused_dependencies(
+  graph: &ModuleGraph,
+  import_map_keys: Vec<String>,
+  lockfile_specifiers: Vec<String>,
+) -> UnusedDependencies {
+  // the text of every specifier in the graph and the module it's in
+  let mut raw_specifiers = Vec::new();
+  let mut used_npm_reqs = HashSet::new();
+  let mut used_jsr_reqs = HashSet::new();
+  let mut visit_resolved = |specifier: &ModuleSpecifier| {
+    if let Ok(req_ref) = NpmPackageReqReference::from_specifier(specifier) {
+      used_npm_reqs.insert(req_ref.into_inner().req);
+    } else if let Ok(req_ref) =
+      JsrPackageReqReference::from_specifier(specifier)
+    {
+      used_jsr_reqs.insert(req_ref.into_inner().req);
+    }
+  };
+  for module in graph.modules() {
+    let Some(module) = module.js() else {
+      continue;
+    };
+    for (text, dep) in &module.dependencies {
+      raw_specifiers.push((text.as_str(), &module.specifier));
+      for resolution in [&dep.maybe_code, &dep.maybe_type] {
+        if let Some(specifier) = resolution.maybe_specifier() {
+          visit_resolved(specifier);
+        }
+      }
+    }
+    if let Some(types_dep) = &module.maybe_types_dependency {
+      raw_specifiers.push((types_dep.specifier.as_str(), &module.specifier));
+      if let Some(specifier) = types_dep.dependency.maybe_specifier() {
+        visit_resolved(specifier);
+      }
+    }
+  }
+  for (req, _) in graph.packages.mappings() {
+    used_jsr_reqs.insert(req.clone());
+  }
+
+  let imports = import_map_keys
+    .into_iter()
+    .filter(|key| {
+      !raw_specifiers
+        .iter()
+        .any(|(text, referrer)| import_map_key_matches(key, text, referrer))
+    })
+    .collect();
+  let packages = lockfile_specifiers
+    .into_iter()
+    .filter(|specifier| {
+      if let Some(req) = specifier.strip_prefix("npm:") {
+        PackageReq::from_str(req)
+          .map(|req| !used_npm_reqs.contains(&req))
+          .unwrap_or(false)
+      } else if let Some(req) = specifier.strip_prefix("jsr:") {
+        PackageReq::from_str(req)
+          .map(|req| !used_jsr_reqs.contains(&req))
+          .unwrap_or(false)
+      } else {
+        false
+      }
+    })
+    .collect();
+  UnusedDependencies { imports, packages }
+}
+
+/// Gets if the import map key would be used when resolving the specifier.
+fn import_map_key_matches(
+  key: &str,
+  specifier: &str,
+  referrer: &ModuleSpecifier,
+) -> bool {
+  let matches = |specifier: &str| {
+    if key.ends_with('/') {
+      specifier.starts_with(key)
+    } else {
+      specifier == key
+    }
+  };
+  if matches(specifier) {
+    return true;
+  }
+  // keys for relative and absolute specifiers are stored as urls
+  let is_url_like = specifier.starts_with("./")
+    || specifier.starts_with("../")
+    || specifier.starts_with('/');
+  is_url_like
+    && referrer
+      .join(specifier)
+      .map(|url| matches(url.as_str()))
+      .unwrap_or(false)
+}
+
+/// Prints the modules and npm packages that match the `--why` specifier
+/// with an inverted tree of what imports them, like `cargo tree -i`. The
+/// importers of a module are only listed the first time it's printed and
+/// it's marked with `(*)` after that.
+fn print_importers(
+  graph: &ModuleGraph,
+  npm_resolver: &dyn CliNpmResolver,
+  why: &str,
+  cwd: &Path,
+  json: bool,
+) -> Result<(), AnyError> {
+  let npm_snapshot = npm_resolver
+    .as_managed()
+    .map(|npm_resolver| npm_resolver.snapshot());
+  let import_graph = ImportGraph::build(graph, npm_snapshot.as_ref());
+  let is_target = why_target_matcher(graph, why, cwd)?;
+  let root = import_graph.module_label(&graph.roots[0]);
+  let importers = Importers::find(&import_graph.imports, &root, is_target);
+
+  if json {
+    return display::write_json_to_stdout(&importers);
+  }
+  if importers.targets.is_empty() {
+    bail!("{} is not imported by {}.", why, root);
+  }
+  let mut output = String::new();
+  let mut expanded = HashSet::new();
+  for (i, target) in importers.targets.iter().enumerate() {
+    if i > 0 {
+      writeln!(output)?;
+    }
+    print_tree_node(&importers.tree_node(target, &mut expanded), &mut output)?;
+  }
+  display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
+  Ok(())
+}
+
+/// The targets of `--why` with what imports them, which only includes the
+/// modules and npm packages that are imported by the root and lead to a
+/// target.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct Importers {
+  targets: Vec<String>,
+  importers: BTreeMap<String, Vec<String>>,
+}
+
+impl Importers {
+  fn find(
+    imports: &HashMap<String, Vec<String>>,
+    root: &str,
+    is_target: impl Fn(&str) -> bool,
+  ) -> Self {
+    let mut reachable = HashSet::from([root]);
+    let mut pending = vec![root];
+    while let Some(label) = pending.pop() {
+      for import in imports.get(label).into_iter().flatten() {
+        if reachable.insert(import.as_str()) {
+          pending.push(import.as_str());
+        }
+      }
+    }
+    let mut importers_by_label: HashMap<&str, Vec<&str>> = HashMap::new();
+    for importer in &reachable {
+      for import in imports.get(*importer).into_iter().flatten() {
+        importers_by_label
+          .entry(import.as_str())
+          .or_default()
+          .push(importer);
+      }
+    }
+
+    let mut targets = reachable
+      .iter()
+      .copied()
+      .filter(|label| is_target(label))
+      .collect::<Vec<_>>();
+    targets.sort();
+    let mut leads_to_target = targets.iter().copied().collect::<HashSet<_>>();
+    let mut pending = targets.clone();
+    let mut importers = BTreeMap::new();
+    while let Some(label) = pending.pop() {
+      let Some(label_importers) = importers_by_label.get(label) else {
+        continue;
+      };
+      for importer in label_importers {
+        if leads_to_target.insert(importer) {
+          pending.push(importer);
+        }
+      }
+      let mut label_importers = label_importers
+        .iter()
+        .map(|importer| importer.to_string())
+        .collect::<Vec<_>>();
+      label_importers.sort();
+      importers.insert(label.to_string(), label_importers);
+    }
+    Self {
+      targets: targets.into_iter().map(ToOwned::to_owned).collect(),
+      importers,
+    }
+  }
+
+  fn tree_node<'a>(
+    &'a self,
+    label: &'a str,
+    expanded: &mut HashSet<&'a str>,
+  ) -> TreeNode {
+    let Some(importers) = self.importers.get(label) else {
+      return TreeNode::from_text(label.to_string());
+    };
+    if !expanded.insert(label) {
+      return TreeNode::from_text(format!("{} {}", label, colors::gray("(*)")));
+    }
+    TreeNode {
+      text: label.to_string(),
+      children: importers
+        .iter()
+        .map(|importer| self.tree_node(importer, expanded))
+        .collect(),
+    }
+  }
+}
+
+/// The modules and npm packages of a module graph with what they import,
+/// where npm packages are labeled with their resolved `npm:name@version`.
+struct ImportGraph<'a> {
+  graph: &'a ModuleGraph,
+  npm_snapshot: Option<&'a NpmResolutionSnapshot>,
+  imports: HashMap<String, Vec<String>>,
+}
+
+impl<'a> ImportGraph<'a> {
+  fn build(
+    graph: &'a ModuleGraph,
+    npm_snapshot: Option<&'a NpmResolutionSnapshot>,
+  ) -> Self {
+    let mut import_graph = Self {
+      graph,
+      npm_snapshot,
+      imports: HashMap::new(),
+    };
+    for module in graph.modules() {
+      let Some(module) = module.js() else {
+        continue;
+      };
+      let mut imports = Vec::new();
+      let resolutions = module
+        .dependencies
+        .values()
+        .flat_map(|dep| [&dep.maybe_code, &dep.maybe_type])
+        .chain(
+          module
+            .maybe_types_dependency
+            .as_ref()
+            .map(|types_dep| &types_dep.dependency),
+        );
+      for resolution in resolutions {
+        if let Some(specifier) = resolution.maybe_specifier() {
+          let label = import_graph.module_label(specifier);
+          if !imports.contains(&label) {
+            imports.push(label);
+          }
+        }
+      }
+      import_graph
+        .imports
+        .insert(module.specifier.to_string(), imports);
+    }
+    if let Some(npm_snapshot) = npm_snapshot {
+      for package in npm_snapshot.all_packages_for_every_system() {
+        let mut imports = package
+          .dependencies
+          .values()
+          .map(|id| format!("npm:{}", id.nv))
+          .collect::<Vec<_>>();
+        imports.sort();
+        imports.dedup();
+        import_graph
+          .imports
+          .insert(format!("npm:{}", package.id.nv), imports);
+      }
+    }
+    import_graph
+  }
+
+  fn module_label(&self, specifier: &ModuleSpecifier) -> String {
+    let specifier = self.graph.resolve(specifier);
+    let Some(Module::Npm(module)) = self.graph.get(&specifier) else {
+      return specifier.to_string();
+    };
+    let nv = module.nv_reference.nv();
+    match self
+      .npm_snapshot
+      .and_then(|snapshot| snapshot.resolve_package_from_deno_module(nv).ok())
+    {
+      Some(package) => format!("npm:{}", package.id.nv),
+      None => format!("npm:{}", nv),
+    }
+  }
+}
+
+type WhyTargetMatcher = Box<dyn Fn(&str) -> bool>;
+
+/// Gets a function that tells if a label of the import graph is the target
+/// of `--why`. npm and jsr specifiers match any version of the package that
+/// satisfies their version requirement.
+fn why_target_matcher(
+  graph: &ModuleGraph,
+  why: &str,
+  cwd: &Path,
+) -> Result<WhyTargetMatcher, AnyError> {
+  if let Ok(req_ref) = NpmPackageReqReference::from_str(why) {
+    let req = req_ref.into_inner().req;
+    return Ok(Box::new(move |label| {
+      label
+        .strip_prefix("npm:")
+        .and_then(|nv| PackageNv::from_str(nv).ok())
+        .is_some_and(|nv| {
+          nv.name == req.name && req.version_req.matches(&nv.version)
+        })
+    }));
+  }
+  if let Ok(req_ref) = JsrPackageReqReference::from_str(why) {
+    let req = req_ref.into_inner().req;
+    let package_urls = graph
+      .packages
+      .mappings()
+      .filter(|(mapped_req, nv)| {
+        mapped_req.name == req.name && req.version_req.matches(&nv.version)
+      })
+      .map(|(_, nv)| format!("{}{}/{}/", jsr_url(), nv.name, nv.version))
+      .collect::<Vec<_>>();
+    return Ok(Box::new(move |label| {
+      package_urls
+        .iter()
+        .any(|url| label.starts_with(url.as_str()))
+    }));
+  }
+  let specifier = resolve_url_or_path(why, cwd)?;
+  let specifier = graph.resolve(&specifier).to_string();
+  Ok(Box::new(move |label| label == specifier))
+}
+
 fn add_npm_packages_to_json(
   json: &mut serde_json::Value,
   npm_resolver: &dyn CliNpmResolver,
@@ -735,3 +1172,57 @@ fn maybe_size_to_text(maybe_size: Option<u64>) -> String {
   ))
   .to_string()
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn finds_importers_of_diamonds() {
+    // 40 diamonds in a row, which have 2^40 import chains
+    let mut imports = HashMap::new();
+    for i in 0..40 {
+      imports.insert(format!("{i}"), vec![format!("{i}a"), format!("{i}b")]);
+      imports.insert(format!("{i}a"), vec![format!("{}", i + 1)]);
+      imports.insert(format!("{i}b"), vec![format!("{}", i + 1)]);
+    }
+    imports.insert("unused".to_string(), vec!["40".to_string()]);
+    let importers = Importers::find(&imports, "0", |label| label == "40");
+    assert_eq!(importers.targets, vec!["40"]);
+    assert_eq!(importers.importers.len(), 120);
+    assert_eq!(importers.importers["40"], vec!["39a", "39b"]);
+    assert_eq!(importers.importers["39a"], vec!["39"]);
+
+    let mut output = String::new();
+    let mut expanded = HashSet::new();
+    print_tree_node(&importers.tree_node("40", &mut expanded), &mut output)
+      .unwrap();
+    // each level prints its importers once: `Na`, `N`, `Nb` and `N (*)`
+    assert_eq!(output.lines().count(), 1 + 40 * 4);
+    assert!(output.lines().nth(2).unwrap().ends_with("39"));
+  }
+
+  #[test]
+  fn finds_importers_in_cycles() {
+    let imports = HashMap::from([
+      ("main".to_string(), vec!["a".to_string()]),
+      ("a".to_string(), vec!["b".to_string()]),
+      ("b".to_string(), vec!["a".to_string(), "c".to_string()]),
+    ]);
+    let importers = Importers::find(&imports, "main", |label| label == "c");
+    let mut output = String::new();
+    let mut expanded = HashSet::new();
+    print_tree_node(&importers.tree_node("c", &mut expanded), &mut output)
+      .unwrap();
+    assert_eq!(
+      test_util::strip_ansi_codes(&output),
+      concat!(
+        "c\n",
+        "└─┬ b\n",
+        "  └─┬ a\n",
+        "    ├── b (*)\n",
+        "    └── main\n",
+      )
+    );
+  }
+}
@@ -2,6 +2,7 @@
 
 use crate::args::Flags;
 use crate::args::JupyterFlags;
+use crate::args::LogFormat;
 use crate::ops;
 use crate::tools::jupyter::server::StdioMsg;
 use crate::tools::repl;
@@ -54,7 +55,7 @@ pub async fn kernel(
 
   // This env var might be set by notebook
   if std::env::var("DEBUG").is_ok() {
-    logger::init(Some(log::Level::Debug));
+    logger::init(Some(log::Level::Debug), LogFormat::Text);
   }
 
   let factory = CliFactory::from_flags(flags)?;
@@ -0,0 +1,203 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Baseline of the existing diagnostics written by
+//! `deno lint --write-baseline`. The diagnostics in the baseline are left out
+//! of later runs, so only new diagnostics cause linting to fail.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_ast::diagnostics::Diagnostic;
+use deno_ast::ModuleSpecifier;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
+use deno_core::url::Url;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::LintOrCliDiagnostic;
+use crate::util::fs::atomic_write_file;
+
+pub const BASELINE_FILE_NAME: &str = "deno-lint-baseline.json";
+const BASELINE_VERSION: u32 = 1;
+
+/// The number of diagnostics of each rule by the path of the file relative
+/// to the directory of the baseline.
+type BaselineCounts = BTreeMap<String, BTreeMap<String, usize>>;
+
+#[derive(Serialize, Deserialize)]
+struct BaselineFile {
+  version: u32,
+  diagnostics: BaselineCounts,
+}
+
+pub struct LintBaseline {
+  path: PathBuf,
+  base_url: Option<Url>,
+  write: bool,
+  counts: Mutex<BaselineCounts>,
+}
+
+impl LintBaseline {
+  /// Loads the baseline at the path, or creates an empty one that records
+  /// the diagnostics when `write` is set. Returns `None` when there's no
+  /// baseline to use.
+  pub fn load(path: PathBuf, write: bool) -> Result<Option<Self>, AnyError> {
+    let counts = if write {
+      BaselineCounts::new()
+    } else {
+      match std::fs::read_to_string(&path) {
+        Ok(text) => parse_baseline(&text).with_context(|| {
+          format!("Failed to parse lint baseline '{}'", path.display())
+        })?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+          return Ok(None)
+        }
+        Err(err) => {
+          return Err(err).with_context(|| {
+            format!("Failed to read lint baseline '{}'", path.display())
+          })
+        }
+      }
+    };
+    let base_url = path
+      .parent()
+      .and_then(|dir| Url::from_directory_path(dir).ok());
+    Ok(Some(Self {
+      path,
+      base_url,
+      write,
+      counts: Mutex::new(counts),
+    }))
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Takes the diagnostic out of the baseline, returning whether it was in
+  /// it. When writing the baseline, the diagnostic is recorded instead and
+  /// this always returns true.
+  pub fn take(&self, d: &LintOrCliDiagnostic) -> bool {
+    let file_key = self.file_key(d.specifier());
+    let code = d.code();
+    let mut counts = self.counts.lock();
+    if self.write {
+      *counts
+        .entry(file_key)
+        .or_default()
+        .entry(code.to_string())
+        .or_default() += 1;
+      return true;
+    }
+    match counts
+      .get_mut(&file_key)
+      .and_then(|rules| rules.get_mut(code.as_ref()))
+    {
+      Some(count) if *count > 0 => {
+        *count -= 1;
+        true
+      }
+      _ => false,
+    }
+  }
+
+  /// Writes the recorded diagnostics to the baseline file, returning how
+  /// many were written.
+  pub fn write(&self) -> Result<usize, AnyError> {
+    let file = BaselineFile {
+      version: BASELINE_VERSION,
+      diagnostics: self.counts.lock().clone(),
+    };
+    let count = file.diagnostics.values().flat_map(|r| r.values()).sum();
+    let mut text = serde_json::to_string_pretty(&file)?;
+    text.push('\n');
+    atomic_write_file(&self.path, text, 0o644).with_context(|| {
+      format!("Failed to write lint baseline '{}'", self.path.display())
+    })?;
+    Ok(count)
+  }
+
+  /// Gets the path of the file relative to the directory of the baseline
+  /// so that the baseline can be shared between checkouts.
+  fn file_key(&self, specifier: &ModuleSpecifier) -> String {
+    self
+      .base_url
+      .as_ref()
+      .filter(|base| specifier.as_str().starts_with(base.as_str()))
+      .and_then(|base| base.make_relative(specifier))
+      .unwrap_or_else(|| specifier.to_string())
+  }
+}
+
+fn parse_baseline(text: &str) -> Result<BaselineCounts, AnyError> {
+  let file: BaselineFile = serde_json::from_str(text)?;
+  if file.version != BASELINE_VERSION {
+    bail!(
+      "Unsupported version {}. Run `deno lint --write-baseline` to write it again.",
+      file.version
+    );
+  }
+  Ok(file.diagnostics)
+}
+
+#[cfg(test)]
+mod test {
+  use test_util::TempDir;
+
+  use super::*;
+  use crate::tools::lint::import_extensions::test_diagnostic;
+
+  #[test]
+  fn write_and_take() {
+    let temp_dir = TempDir::new();
+    let path = temp_dir.path().join(BASELINE_FILE_NAME).to_path_buf();
+    assert!(LintBaseline::load(path.clone(), false).unwrap().is_none());
+
+    let main = temp_dir.uri().join("src/main.ts").unwrap();
+    let other = temp_dir.uri().join("other.ts").unwrap();
+    let main_diagnostic = test_diagnostic(&main, None);
+    let other_diagnostic = test_diagnostic(&other, None);
+    let main_d = LintOrCliDiagnostic::ImportExtensions(&main_diagnostic);
+    let other_d = LintOrCliDiagnostic::ImportExtensions(&other_diagnostic);
+
+    let baseline = LintBaseline::load(path.clone(), true).unwrap().unwrap();
+    assert!(baseline.take(&main_d));
+    assert!(baseline.take(&main_d));
+    assert_eq!(baseline.write().unwrap(), 2);
+    assert_eq!(
+      serde_json::from_str::<serde_json::Value>(
+        &std::fs::read_to_string(&path).unwrap()
+      )
+      .unwrap(),
+      serde_json::json!({
+        "version": 1,
+        "diagnostics": {
+          "src/main.ts": { "import-extensions": 2 },
+        },
+      })
+    );
+
+    let baseline = LintBaseline::load(path, false).unwrap().unwrap();
+    assert!(!baseline.take(&other_d));
+    assert!(baseline.take(&main_d));
+    assert!(baseline.take(&main_d));
+    // a new diagnostic in a file of the baseline
+    assert!(!baseline.take(&main_d));
+  }
+
+  #[test]
+  fn unsupported_version() {
+    let err = parse_baseline(r#"{ "version": 2, "diagnostics": {} }"#)
+      .err()
+      .unwrap();
+    assert_eq!(
+      err.to_string(),
+      "Unsupported version 2. Run `deno lint --write-baseline` to write it again."
+    );
+  }
+}
@@ -0,0 +1,388 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! CLI specific lint rules that enforce a consistent style for the file
+//! extensions of relative import specifiers.
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use deno_ast::diagnostics::Diagnostic;
+use deno_ast::diagnostics::DiagnosticLevel;
+use deno_ast::diagnostics::DiagnosticLocation;
+use deno_ast::diagnostics::DiagnosticSnippet;
+use deno_ast::diagnostics::DiagnosticSnippetHighlight;
+use deno_ast::diagnostics::DiagnosticSnippetHighlightStyle;
+use deno_ast::diagnostics::DiagnosticSourcePos;
+use deno_ast::diagnostics::DiagnosticSourceRange;
+use deno_ast::MediaType;
+use deno_ast::ModuleSpecifier;
+use deno_ast::ParsedSource;
+use deno_ast::SourceRange;
+use deno_ast::SourceTextInfo;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_graph::source::ResolutionMode;
+use deno_graph::DependencyDescriptor;
+use deno_graph::ParserModuleAnalyzer;
+use deno_lint::diagnostic::LintDiagnostic;
+use deno_lint::linter::LintFileOptions;
+use deno_lint::linter::Linter;
+use deno_runtime::deno_fs::RealFs;
+
+use crate::resolver::SloppyImportsResolution;
+use crate::resolver::SloppyImportsResolver;
+
+pub const IMPORT_EXTENSIONS_CODE: &str = "import-extensions";
+pub const NO_IMPORT_EXTENSIONS_CODE: &str = "no-import-extensions";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportExtensionsStyle {
+  /// Relative imports must include the file extension (ex. `./mod.ts`).
+  Always,
+  /// Relative imports must not include the file extension (ex. `./mod`).
+  Never,
+}
+
+impl ImportExtensionsStyle {
+  pub fn code(&self) -> &'static str {
+    match self {
+      ImportExtensionsStyle::Always => IMPORT_EXTENSIONS_CODE,
+      ImportExtensionsStyle::Never => NO_IMPORT_EXTENSIONS_CODE,
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportExtensionsDiagnostic {
+  pub style: ImportExtensionsStyle,
+  pub specifier: ModuleSpecifier,
+  pub text_info: SourceTextInfo,
+  /// The range of the import specifier text, excluding the quotes.
+  pub range: SourceRange,
+  /// The text the import specifier should be replaced with, when
+  /// it could be determined.
+  pub fixed_import_specifier: Option<String>,
+}
+
+impl Diagnostic for ImportExtensionsDiagnostic {
+  fn level(&self) -> DiagnosticLevel {
+    DiagnosticLevel::Error
+  }
+
+  fn code(&self) -> Cow<'_, str> {
+    Cow::Borrowed(self.style.code())
+  }
+
+  fn message(&self) -> Cow<'_, str> {
+    Cow::Borrowed(match self.style {
+      ImportExtensionsStyle::Always => {
+        "relative import is missing a file extension"
+      }
+      ImportExtensionsStyle::Never => {
+        "relative import should not include a file extension"
+      }
+    })
+  }
+
+  fn location(&self) -> DiagnosticLocation {
+    DiagnosticLocation::ModulePosition {
+      specifier: Cow::Borrowed(&self.specifier),
+      text_info: Cow::Borrowed(&self.text_info),
+      source_pos: DiagnosticSourcePos::SourcePos(self.range.start),
+    }
+  }
+
+  fn snippet(&self) -> Option<DiagnosticSnippet<'_>> {
+    Some(DiagnosticSnippet {
+      source: Cow::Borrowed(&self.text_info),
+      highlight: DiagnosticSnippetHighlight {
+        style: DiagnosticSnippetHighlightStyle::Error,
+        range: DiagnosticSourceRange {
+          start: DiagnosticSourcePos::SourcePos(self.range.start),
+          end: DiagnosticSourcePos::SourcePos(self.range.end),
+        },
+        description: Some("the specifier".into()),
+      },
+    })
+  }
+
+  fn hint(&self) -> Option<Cow<'_, str>> {
+    Some(match &self.fixed_import_specifier {
+      Some(fixed) => Cow::Owned(format!("change the specifier to '{fixed}'")),
+      None => Cow::Borrowed(match self.style {
+        ImportExtensionsStyle::Always => {
+          "add the file extension of the imported module"
+        }
+        ImportExtensionsStyle::Never => "remove the file extension",
+      }),
+    })
+  }
+
+  fn snippet_fixed(&self) -> Option<DiagnosticSnippet<'_>> {
+    None
+  }
+
+  fn info(&self) -> Cow<'_, [Cow<'_, str>]> {
+    Cow::Borrowed(&[Cow::Borrowed(
+      "mixing import styles breaks when code is shared between Deno and bundler based toolchains",
+    )])
+  }
+
+  fn docs_url(&self) -> Option<Cow<'_, str>> {
+    None
+  }
+}
+
+/// Collects the relative imports of the module that don't follow the
+/// provided style.
+pub fn collect_import_extensions_diagnostics(
+  specifier: &ModuleSpecifier,
+  parsed_source: &ParsedSource,
+  style: ImportExtensionsStyle,
+  sloppy_imports_resolver: &SloppyImportsResolver,
+) -> Vec<ImportExtensionsDiagnostic> {
+  let module_info = ParserModuleAnalyzer::module_info(parsed_source);
+  let text_info = parsed_source.text_info();
+  let mut diagnostics = Vec::new();
+  for dep in &module_info.dependencies {
+    let DependencyDescriptor::Static(dep) = dep else {
+      continue;
+    };
+    let is_relative =
+      dep.specifier.starts_with("./") || dep.specifier.starts_with("../");
+    if !is_relative {
+      continue;
+    }
+    let Ok(resolved) = specifier.join(&dep.specifier) else {
+      continue;
+    };
+    let media_type = MediaType::from_specifier(&resolved);
+    let fixed_import_specifier = match style {
+      ImportExtensionsStyle::Always => {
+        if media_type != MediaType::Unknown {
+          continue;
+        }
+        match sloppy_imports_resolver
+          .resolve(&resolved, ResolutionMode::Execution)
+        {
+          SloppyImportsResolution::NoExtension(resolved) => {
+            let media_type = MediaType::from_specifier(&resolved);
+            Some(format!("{}{}", dep.specifier, media_type.as_ts_extension()))
+          }
+          SloppyImportsResolution::Directory(resolved) => {
+            resolved.path().rsplit_once('/').map(|(_, file_name)| {
+              format!("{}/{}", dep.specifier.trim_end_matches('/'), file_name)
+            })
+          }
+          SloppyImportsResolution::None(_)
+          | SloppyImportsResolution::JsToTs(_) => None,
+        }
+      }
+      ImportExtensionsStyle::Never => match media_type {
+        MediaType::TypeScript
+        | MediaType::Tsx
+        | MediaType::Mts
+        | MediaType::Cts
+        | MediaType::JavaScript
+        | MediaType::Jsx
+        | MediaType::Mjs
+        | MediaType::Cjs => dep
+          .specifier
+          .strip_suffix(media_type.as_ts_extension())
+          .map(|s| s.to_string()),
+        // declaration files, json, wasm, etc. can't be imported
+        // without their extension
+        _ => continue,
+      },
+    };
+
+    let range = dep.specifier_range.as_source_range(text_info);
+    let text =
+      &text_info.text_str()[range.as_byte_range(text_info.range().start)];
+    let range =
+      if text.len() >= 2 && (text.starts_with('"') || text.starts_with('\'')) {
+        let start = range.start + 1;
+        SourceRange::new(start, start + (text.len() - 2))
+      } else {
+        range
+      };
+    diagnostics.push(ImportExtensionsDiagnostic {
+      style,
+      specifier: specifier.clone(),
+      text_info: text_info.clone(),
+      range,
+      fixed_import_specifier,
+    });
+  }
+  diagnostics
+}
+
+/// Checks the relative imports of an already linted file. When `fix` is
+/// set, the fixable import specifiers are rewritten and the file is linted
+/// again so the returned lint diagnostics match the new text. Writing the
+/// returned source to disk is left to the caller.
+pub fn lint_file_import_extensions(
+  linter: &Linter,
+  specifier: &ModuleSpecifier,
+  style: ImportExtensionsStyle,
+  fix: bool,
+  source: ParsedSource,
+  lint_diagnostics: Vec<LintDiagnostic>,
+) -> Result<
+  (
+    ParsedSource,
+    Vec<LintDiagnostic>,
+    Vec<ImportExtensionsDiagnostic>,
+  ),
+  AnyError,
+> {
+  let sloppy_imports_resolver =
+    SloppyImportsResolver::new_without_stat_cache(Arc::new(RealFs));
+  let diagnostics = collect_import_extensions_diagnostics(
+    specifier,
+    &source,
+    style,
+    &sloppy_imports_resolver,
+  );
+  if !fix {
+    return Ok((source, lint_diagnostics, diagnostics));
+  }
+
+  let file_start = source.text_info().range().start;
+  let text_changes = diagnostics
+    .iter()
+    .filter_map(|d| {
+      Some(deno_ast::TextChange {
+        range: d.range.as_byte_range(file_start),
+        new_text: d.fixed_import_specifier.clone()?,
+      })
+    })
+    .collect::<Vec<_>>();
+  if text_changes.is_empty() {
+    return Ok((source, lint_diagnostics, diagnostics));
+  }
+
+  let new_text =
+    deno_ast::apply_text_changes(source.text_info().text_str(), text_changes);
+  let (source, lint_diagnostics) = linter
+    .lint_file(LintFileOptions {
+      specifier: specifier.clone(),
+      media_type: MediaType::from_specifier(specifier),
+      source_code: new_text,
+    })
+    .context(
+      "An applied lint fix caused a syntax error. Please report this bug.",
+    )?;
+  let diagnostics = collect_import_extensions_diagnostics(
+    specifier,
+    &source,
+    style,
+    &sloppy_imports_resolver,
+  );
+  Ok((source, lint_diagnostics, diagnostics))
+}
+
+/// Creates the diagnostic for the import of `./a` in a file only containing
+/// `import "./a";`, which is shared by the tests of the lint reporters.
+#[cfg(test)]
+pub(super) fn test_diagnostic(
+  specifier: &ModuleSpecifier,
+  fixed_import_specifier: Option<&str>,
+) -> ImportExtensionsDiagnostic {
+  let text_info = SourceTextInfo::new("import \"./a\";\n".into());
+  let start = text_info.range().start;
+  ImportExtensionsDiagnostic {
+    style: ImportExtensionsStyle::Always,
+    specifier: specifier.clone(),
+    text_info,
+    range: SourceRange::new(start + 8, start + 11),
+    fixed_import_specifier: fixed_import_specifier.map(|s| s.to_string()),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use test_util::TempDir;
+
+  fn collect(
+    temp_dir: &TempDir,
+    source: &str,
+    style: ImportExtensionsStyle,
+  ) -> Vec<(String, Option<String>)> {
+    let specifier = temp_dir.uri().join("main.ts").unwrap();
+    let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: specifier.clone(),
+      text_info: SourceTextInfo::new(source.into()),
+      media_type: MediaType::TypeScript,
+      capture_tokens: true,
+      scope_analysis: false,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    let resolver =
+      SloppyImportsResolver::new_without_stat_cache(Arc::new(RealFs));
+    collect_import_extensions_diagnostics(
+      &specifier,
+      &parsed_source,
+      style,
+      &resolver,
+    )
+    .into_iter()
+    .map(|d| {
+      let text = &source[d.range.as_byte_range(d.text_info.range().start)];
+      (text.to_string(), d.fixed_import_specifier)
+    })
+    .collect()
+  }
+
+  #[test]
+  fn always_style() {
+    let temp_dir = TempDir::new();
+    temp_dir.write("a.ts", "");
+    temp_dir.create_dir_all("b");
+    temp_dir.write("b/index.ts", "");
+    let diagnostics = collect(
+      &temp_dir,
+      concat!(
+        "import './a';\n",
+        "import './a.ts';\n",
+        "export * from './b';\n",
+        "import './missing';\n",
+        "import 'https://deno.land/x/mod';\n",
+      ),
+      ImportExtensionsStyle::Always,
+    );
+    assert_eq!(
+      diagnostics,
+      vec![
+        ("./a".to_string(), Some("./a.ts".to_string())),
+        ("./b".to_string(), Some("./b/index.ts".to_string())),
+        ("./missing".to_string(), None),
+      ]
+    );
+  }
+
+  #[test]
+  fn never_style() {
+    let temp_dir = TempDir::new();
+    let diagnostics = collect(
+      &temp_dir,
+      concat!(
+        "import './a';\n",
+        "import './a.ts';\n",
+        "import \"../b.js\";\n",
+        "import './types.d.ts';\n",
+        "import data from './data.json' with { type: 'json' };\n",
+      ),
+      ImportExtensionsStyle::Never,
+    );
+    assert_eq!(
+      diagnostics,
+      vec![
+        ("./a.ts".to_string(), Some("./a".to_string())),
+        ("../b.js".to_string(), Some("../b".to_string())),
+      ]
+    );
+  }
+}
@@ -0,0 +1,485 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! CLI specific lint rules that check the accessibility of the JSX in `.jsx`
+//! and `.tsx` files. They're opt-in with the `jsx-a11y` tag.
+
+use std::borrow::Cow;
+
+use deno_ast::swc::ast;
+use deno_ast::swc::visit::noop_visit_type;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::MediaType;
+use deno_ast::ParsedSource;
+use deno_ast::SourceRange;
+use deno_ast::SourceRangedForSpanned;
+
+use super::plugins::IgnoreDirectives;
+use super::rule_diagnostic::RuleDiagnostic;
+
+pub const JSX_A11Y_TAG: &str = "jsx-a11y";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsxA11yRule {
+  /// Images must have alternative text.
+  AltText,
+  /// `aria-*` props must be valid ARIA attributes.
+  AriaProps,
+  /// The `role` prop must be a valid, non-abstract ARIA role.
+  AriaRole,
+  /// Elements that handle clicks must also handle the keyboard.
+  ClickEventsHaveKeyEvents,
+}
+
+impl JsxA11yRule {
+  pub const ALL: [JsxA11yRule; 4] = [
+    JsxA11yRule::AltText,
+    JsxA11yRule::AriaProps,
+    JsxA11yRule::AriaRole,
+    JsxA11yRule::ClickEventsHaveKeyEvents,
+  ];
+
+  pub fn code(&self) -> &'static str {
+    match self {
+      JsxA11yRule::AltText => "jsx-a11y-alt-text",
+      JsxA11yRule::AriaProps => "jsx-a11y-aria-props",
+      JsxA11yRule::AriaRole => "jsx-a11y-aria-role",
+      JsxA11yRule::ClickEventsHaveKeyEvents => {
+        "jsx-a11y-click-events-have-key-events"
+      }
+    }
+  }
+
+  pub fn from_code(code: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|rule| rule.code() == code)
+  }
+
+  fn hint(&self) -> &'static str {
+    match self {
+      JsxA11yRule::AltText => {
+        "add an `alt` prop describing the image, or `alt=\"\"` when the image is decorative"
+      }
+      JsxA11yRule::AriaProps => {
+        "use one of the attributes from the WAI-ARIA specification"
+      }
+      JsxA11yRule::AriaRole => {
+        "use one of the non-abstract roles from the WAI-ARIA specification"
+      }
+      JsxA11yRule::ClickEventsHaveKeyEvents => {
+        "add `onKeyDown`, `onKeyUp` or `onKeyPress`, or use an interactive element like <button>"
+      }
+    }
+  }
+}
+
+/// Runs the enabled rules on the JSX of the file.
+pub fn collect_jsx_a11y_diagnostics(
+  parsed_source: &ParsedSource,
+  rules: &[JsxA11yRule],
+) -> Vec<RuleDiagnostic> {
+  if rules.is_empty()
+    || !matches!(parsed_source.media_type(), MediaType::Jsx | MediaType::Tsx)
+  {
+    return Vec::new();
+  }
+  let text_info = parsed_source.text_info();
+  let mut visitor = JsxA11yVisitor {
+    rules,
+    found: Vec::new(),
+  };
+  parsed_source.program().visit_with(&mut visitor);
+  let ignore_directives = IgnoreDirectives::new(text_info);
+  visitor
+    .found
+    .into_iter()
+    .filter(|(rule, range, _)| {
+      !ignore_directives.is_ignored(text_info, range.start, rule.code())
+    })
+    .map(|(rule, range, message)| RuleDiagnostic {
+      specifier: parsed_source.specifier().clone(),
+      text_info: text_info.clone(),
+      range,
+      code: Cow::Borrowed(rule.code()),
+      message,
+      hint: Some(Cow::Borrowed(rule.hint())),
+    })
+    .collect()
+}
+
+struct JsxA11yVisitor<'a> {
+  rules: &'a [JsxA11yRule],
+  found: Vec<(JsxA11yRule, SourceRange, String)>,
+}
+
+impl<'a> JsxA11yVisitor<'a> {
+  fn report(&mut self, rule: JsxA11yRule, range: SourceRange, message: String) {
+    if self.rules.contains(&rule) {
+      self.found.push((rule, range, message));
+    }
+  }
+
+  fn check_element(&mut self, element: &ast::JSXOpeningElement) {
+    // only check the DOM elements because components can give the props
+    // another meaning
+    let ast::JSXElementName::Ident(name) = &element.name else {
+      return;
+    };
+    let name = name.sym.as_str();
+    if !name.starts_with(|c: char| c.is_ascii_lowercase()) {
+      return;
+    }
+    let props = JsxProps::new(element);
+
+    for (prop_name, range) in &props.names {
+      if prop_name.starts_with("aria-")
+        && !ARIA_ATTRIBUTES.contains(&prop_name.as_str())
+      {
+        self.report(
+          JsxA11yRule::AriaProps,
+          *range,
+          format!("`{prop_name}` is not a valid ARIA attribute"),
+        );
+      }
+    }
+
+    if let Some((Some(roles), range)) = props.get("role") {
+      for role in roles.split_whitespace() {
+        if !ARIA_ROLES.contains(&role) {
+          self.report(
+            JsxA11yRule::AriaRole,
+            range,
+            format!("`{role}` is not a valid ARIA role"),
+          );
+        }
+      }
+    }
+
+    // the props could be passed with the spread
+    if props.has_spread {
+      return;
+    }
+
+    let needs_alt = match name {
+      "img" | "area" => true,
+      "input" => matches!(props.get("type"), Some((Some("image"), _))),
+      _ => false,
+    };
+    if needs_alt && props.get("alt").is_none() {
+      self.report(
+        JsxA11yRule::AltText,
+        element.range(),
+        format!("<{name}> elements must have an `alt` prop"),
+      );
+    }
+
+    if let Some((_, range)) = props.get("onClick") {
+      let is_interactive = INTERACTIVE_ELEMENTS.contains(&name)
+        || (name == "a" && props.get("href").is_some());
+      let is_hidden = props.get("aria-hidden").is_some()
+        || props.get("role").is_some_and(|(role, _)| {
+          matches!(role, Some("presentation" | "none"))
+        });
+      let has_key_events = ["onKeyDown", "onKeyUp", "onKeyPress"]
+        .iter()
+        .any(|name| props.get(name).is_some());
+      if !is_interactive && !is_hidden && !has_key_events {
+        self.report(
+          JsxA11yRule::ClickEventsHaveKeyEvents,
+          range,
+          format!(
+            "`onClick` on <{name}> must be accompanied by a keyboard event handler"
+          ),
+        );
+      }
+    }
+  }
+}
+
+impl<'a> Visit for JsxA11yVisitor<'a> {
+  noop_visit_type!();
+
+  fn visit_jsx_opening_element(&mut self, node: &ast::JSXOpeningElement) {
+    self.check_element(node);
+    node.visit_children_with(self);
+  }
+}
+
+/// The props of a JSX element along with the values of those that are
+/// string literals.
+struct JsxProps<'a> {
+  names: Vec<(String, SourceRange)>,
+  values: Vec<Option<&'a str>>,
+  has_spread: bool,
+}
+
+impl<'a> JsxProps<'a> {
+  fn new(element: &'a ast::JSXOpeningElement) -> Self {
+    let mut props = JsxProps {
+      names: Vec::new(),
+      values: Vec::new(),
+      has_spread: false,
+    };
+    for attr in &element.attrs {
+      let attr = match attr {
+        ast::JSXAttrOrSpread::JSXAttr(attr) => attr,
+        ast::JSXAttrOrSpread::SpreadElement(_) => {
+          props.has_spread = true;
+          continue;
+        }
+      };
+      let ast::JSXAttrName::Ident(name) = &attr.name else {
+        continue;
+      };
+      let value = match &attr.value {
+        Some(ast::JSXAttrValue::Lit(ast::Lit::Str(value))) => {
+          Some(value.value.as_str())
+        }
+        _ => None,
+      };
+      props.names.push((name.sym.to_string(), attr.range()));
+      props.values.push(value);
+    }
+    props
+  }
+
+  /// Gets the string value of the prop, if it's a literal, along with the
+  /// range of the prop.
+  fn get(&self, name: &str) -> Option<(Option<&'a str>, SourceRange)> {
+    let index = self.names.iter().position(|(n, _)| n == name)?;
+    Some((self.values[index], self.names[index].1))
+  }
+}
+
+/// Elements that can be used with the keyboard without any handlers.
+const INTERACTIVE_ELEMENTS: &[&str] = &[
+  "button", "details", "input", "option", "select", "summary", "textarea",
+];
+
+/// The attributes of WAI-ARIA 1.2.
+const ARIA_ATTRIBUTES: &[&str] = &[
+  "aria-activedescendant",
+  "aria-atomic",
+  "aria-autocomplete",
+  "aria-braillelabel",
+  "aria-brailleroledescription",
+  "aria-busy",
+  "aria-checked",
+  "aria-colcount",
+  "aria-colindex",
+  "aria-colindextext",
+  "aria-colspan",
+  "aria-controls",
+  "aria-current",
+  "aria-describedby",
+  "aria-description",
+  "aria-details",
+  "aria-disabled",
+  "aria-dropeffect",
+  "aria-errormessage",
+  "aria-expanded",
+  "aria-flowto",
+  "aria-grabbed",
+  "aria-haspopup",
+  "aria-hidden",
+  "aria-invalid",
+  "aria-keyshortcuts",
+  "aria-label",
+  "aria-labelledby",
+  "aria-level",
+  "aria-live",
+  "aria-modal",
+  "aria-multiline",
+  "aria-multiselectable",
+  "aria-orientation",
+  "aria-owns",
+  "aria-placeholder",
+  "aria-posinset",
+  "aria-pressed",
+  "aria-readonly",
+  "aria-relevant",
+  "aria-required",
+  "aria-roledescription",
+  "aria-rowcount",
+  "aria-rowindex",
+  "aria-rowindextext",
+  "aria-rowspan",
+  "aria-selected",
+  "aria-setsize",
+  "aria-sort",
+  "aria-valuemax",
+  "aria-valuemin",
+  "aria-valuenow",
+  "aria-valuetext",
+];
+
+/// The non-abstract roles of WAI-ARIA 1.2.
+const ARIA_ROLES: &[&str] = &[
+  "alert",
+  "alertdialog",
+  "application",
+  "article",
+  "banner",
+  "blockquote",
+  "button",
+  "caption",
+  "cell",
+  "checkbox",
+  "code",
+  "columnheader",
+  "combobox",
+  "complementary",
+  "contentinfo",
+  "definition",
+  "deletion",
+  "dialog",
+  "directory",
+  "document",
+  "emphasis",
+  "feed",
+  "figure",
+  "form",
+  "generic",
+  "grid",
+  "gridcell",
+  "group",
+  "heading",
+  "img",
+  "insertion",
+  "link",
+  "list",
+  "listbox",
+  "listitem",
+  "log",
+  "main",
+  "marquee",
+  "math",
+  "menu",
+  "menubar",
+  "menuitem",
+  "menuitemcheckbox",
+  "menuitemradio",
+  "meter",
+  "navigation",
+  "none",
+  "note",
+  "option",
+  "paragraph",
+  "presentation",
+  "progressbar",
+  "radio",
+  "radiogroup",
+  "region",
+  "row",
+  "rowgroup",
+  "rowheader",
+  "scrollbar",
+  "search",
+  "searchbox",
+  "separator",
+  "slider",
+  "spinbutton",
+  "status",
+  "strong",
+  "subscript",
+  "superscript",
+  "switch",
+  "tab",
+  "table",
+  "tablist",
+  "tabpanel",
+  "term",
+  "textbox",
+  "time",
+  "timer",
+  "toolbar",
+  "tooltip",
+  "tree",
+  "treegrid",
+  "treeitem",
+];
+
+#[cfg(test)]
+mod test {
+  use deno_ast::ModuleSpecifier;
+  use deno_ast::ParseParams;
+  use deno_ast::SourceTextInfo;
+
+  use super::*;
+
+  fn lint(source: &str) -> Vec<(&'static str, String)> {
+    let parsed_source = deno_ast::parse_module(ParseParams {
+      specifier: ModuleSpecifier::parse("file:///main.tsx").unwrap(),
+      text_info: SourceTextInfo::new(source.into()),
+      media_type: MediaType::Tsx,
+      capture_tokens: true,
+      scope_analysis: false,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    collect_jsx_a11y_diagnostics(&parsed_source, &JsxA11yRule::ALL)
+      .into_iter()
+      .map(|d| (JsxA11yRule::from_code(&d.code).unwrap().code(), d.message))
+      .collect()
+  }
+
+  #[test]
+  fn alt_text() {
+    assert_eq!(
+      lint("<img src='a.png' />;"),
+      vec![(
+        "jsx-a11y-alt-text",
+        "<img> elements must have an `alt` prop".to_string()
+      )]
+    );
+    assert_eq!(lint("<img src='a.png' alt='' />;"), vec![]);
+    assert_eq!(lint("<img {...props} />;"), vec![]);
+    assert_eq!(lint("<input type='text' />;"), vec![]);
+    assert_eq!(lint("<input type='image' />;").len(), 1);
+    // components aren't checked
+    assert_eq!(lint("<Img src='a.png' />;"), vec![]);
+  }
+
+  #[test]
+  fn aria_props_and_roles() {
+    assert_eq!(
+      lint("<div aria-labeledby='a' aria-label='b' role='button foo' />;"),
+      vec![
+        (
+          "jsx-a11y-aria-props",
+          "`aria-labeledby` is not a valid ARIA attribute".to_string()
+        ),
+        (
+          "jsx-a11y-aria-role",
+          "`foo` is not a valid ARIA role".to_string()
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn click_events_have_key_events() {
+    assert_eq!(
+      lint("<div onClick={f} />;"),
+      vec![(
+        "jsx-a11y-click-events-have-key-events",
+        "`onClick` on <div> must be accompanied by a keyboard event handler"
+          .to_string()
+      )]
+    );
+    assert_eq!(lint("<div onClick={f} onKeyDown={f} />;"), vec![]);
+    assert_eq!(lint("<button onClick={f} />;"), vec![]);
+    assert_eq!(lint("<a href='/' onClick={f} />;"), vec![]);
+    assert_eq!(lint("<div onClick={f} aria-hidden />;"), vec![]);
+  }
+
+  #[test]
+  fn ignore_comments() {
+    assert_eq!(
+      lint(
+        "// deno-lint-ignore jsx-a11y-alt-text\n<img src='a.png' />;\n<img />;"
+      )
+      .len(),
+      1
+    );
+  }
+}
@@ -3,12 +3,13 @@
 //! This module provides file linting utilities using
 //! [`deno_lint`](https://github.com/denoland/deno_lint).
 use deno_ast::diagnostics::Diagnostic;
+use deno_ast::diagnostics::DiagnosticLocation;
 use deno_ast::MediaType;
 use deno_ast::ModuleSpecifier;
 use deno_ast::ParsedSource;
 use deno_ast::SourceRange;
 use deno_ast::SourceTextInfo;
-use deno_config::glob::FilePatterns;
+use deno_config::glob::PathKind;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::generic_error;
@@ -32,34 +33,72 @@ use std::io::stdin;
 use std::io::Read;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use crate::args::CliOptions;
+use crate::args::FilePatterns;
 use crate::args::Flags;
 use crate::args::LintFlags;
 use crate::args::LintOptions;
 use crate::args::LintReporterKind;
 use crate::args::LintRulesConfig;
+use crate::cache::FastInsecureHasher;
 use crate::cache::IncrementalCache;
 use crate::colors;
 use crate::factory::CliFactory;
 use crate::tools::fmt::run_parallelized;
+use crate::util::diff::diff;
+use crate::util::diff::DiffGranularity;
+use crate::util::diff::DiffOptions;
 use crate::util::file_watcher;
+use crate::util::fs::atomic_write_file;
 use crate::util::fs::canonicalize_path;
 use crate::util::fs::specifier_from_file_path;
 use crate::util::fs::FileCollector;
 use crate::util::path::is_script_ext;
 use crate::util::sync::AtomicFlag;
 
+mod baseline;
+pub mod import_extensions;
+pub mod jsx_a11y;
 pub mod no_slow_types;
+mod plugins;
+mod rule_diagnostic;
+mod sarif;
+mod unused_ignores;
+mod watch_cache;
+
+use baseline::LintBaseline;
+use baseline::BASELINE_FILE_NAME;
+use import_extensions::ImportExtensionsDiagnostic;
+use import_extensions::ImportExtensionsStyle;
+use jsx_a11y::JsxA11yRule;
+use plugins::LintPluginHost;
+use rule_diagnostic::RuleDiagnostic;
+use sarif::SarifLintReporter;
+use unused_ignores::get_unused_ignore_changes;
+use unused_ignores::is_unused_ignore_fixable;
+use watch_cache::FileDiagnostics;
+use watch_cache::LintWatchCache;
+use watch_cache::LintWatchDelta;
+
+pub use plugins::remove_ignore_diagnostics;
 
 static STDIN_FILE_NAME: &str = "$deno$stdin.ts";
 
-fn create_reporter(kind: LintReporterKind) -> Box<dyn LintReporter + Send> {
+fn create_reporter(
+  kind: LintReporterKind,
+  initial_cwd: &Path,
+) -> Box<dyn LintReporter + Send> {
   match kind {
     LintReporterKind::Pretty => Box::new(PrettyLintReporter::new()),
     LintReporterKind::Json => Box::new(JsonLintReporter::new()),
     LintReporterKind::Compact => Box::new(CompactLintReporter::new()),
+    LintReporterKind::Sarif => {
+      Box::new(SarifLintReporter::new(initial_cwd.to_path_buf()))
+    }
   }
 }
 
@@ -70,24 +109,28 @@ pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
         "Lint watch on standard input is not supported.",
       ));
     }
+    // keeps the results between the runs so that only the files that
+    // changed are linted again
+    let watch_cache = Arc::new(Mutex::new(LintWatchCache::default()));
     file_watcher::watch_func(
       flags,
       file_watcher::PrintConfig::new("Lint", !watch_flags.no_clear_screen),
       move |flags, watcher_communicator, changed_paths| {
         let lint_flags = lint_flags.clone();
+        let watch_cache = watch_cache.clone();
         Ok(async move {
           let factory = CliFactory::from_flags(flags)?;
           let cli_options = factory.cli_options();
           let lint_options = cli_options.resolve_lint_options(lint_flags)?;
-          let files =
-            collect_lint_files(cli_options, lint_options.files.clone())
-              .and_then(|files| {
-                if files.is_empty() {
-                  Err(generic_error("No target files found."))
-                } else {
-                  Ok(files)
-                }
-              })?;
+          let files = collect_lint_files(cli_options, &lint_options).and_then(
+            |files| {
+              if files.is_empty() {
+                Err(generic_error("No target files found."))
+              } else {
+                Ok(files)
+              }
+            },
+          )?;
           _ = watcher_communicator.watch_paths(files.clone());
 
           let lint_paths = if let Some(paths) = changed_paths {
@@ -105,7 +148,8 @@ pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
             files
           };
 
-          lint_files(factory, lint_options, lint_paths).await?;
+          lint_files(factory, lint_options, lint_paths, Some(watch_cache))
+            .await?;
           Ok(())
         })
       },
@@ -116,10 +160,13 @@ pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
     let cli_options = factory.cli_options();
     let is_stdin = lint_flags.is_stdin();
     let lint_options = cli_options.resolve_lint_options(lint_flags)?;
-    let files = &lint_options.files;
     let success = if is_stdin {
+      let severities = RuleSeverities::new(&lint_options, None);
       let reporter_kind = lint_options.reporter_kind;
-      let reporter_lock = Arc::new(Mutex::new(create_reporter(reporter_kind)));
+      let reporter_lock = Arc::new(Mutex::new(create_reporter(
+        reporter_kind,
+        cli_options.initial_cwd(),
+      )));
       let lint_rules = get_config_rules_err_empty(
         lint_options.rules,
         cli_options.maybe_config_file().as_ref(),
@@ -130,11 +177,12 @@ pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
         &file_path.to_string_lossy(),
         r,
         reporter_lock.clone(),
+        &severities,
       );
       reporter_lock.lock().close(1);
-      success
+      success && severities.check_max_warnings(lint_options.max_warnings)
     } else {
-      let target_files = collect_lint_files(cli_options, files.clone())
+      let target_files = collect_lint_files(cli_options, &lint_options)
         .and_then(|files| {
           if files.is_empty() {
             Err(generic_error("No target files found."))
@@ -143,7 +191,7 @@ pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
           }
         })?;
       debug!("Found {} files", target_files.len());
-      lint_files(factory, lint_options, target_files).await?
+      lint_files(factory, lint_options, target_files, None).await?
     };
     if !success {
       std::process::exit(1);
@@ -157,28 +205,96 @@ async fn lint_files(
   factory: CliFactory,
   lint_options: LintOptions,
   paths: Vec<PathBuf>,
+  watch_cache: Option<Arc<Mutex<LintWatchCache>>>,
 ) -> Result<bool, AnyError> {
   let caches = factory.caches()?;
   let maybe_config_file = factory.cli_options().maybe_config_file().as_ref();
   let lint_rules =
-    get_config_rules_err_empty(lint_options.rules, maybe_config_file)?;
+    get_config_rules_err_empty(lint_options.rules.clone(), maybe_config_file)?;
+  let override_rules = lint_options
+    .overrides
+    .iter()
+    .map(|o| get_config_rules_err_empty(o.rules.clone(), maybe_config_file))
+    .collect::<Result<Vec<_>, _>>()?;
+  let plugins = if lint_options.plugins.is_empty() {
+    None
+  } else {
+    let plugins = LintPluginHost::load(&factory, &lint_options.plugins).await?;
+    Some(Arc::new(plugins))
+  };
+  // the override patterns are part of the state so that changing which
+  // files an override applies to causes them to be linted again
+  let overrides_state = lint_options
+    .overrides
+    .iter()
+    .zip(&override_rules)
+    .map(|(o, rules)| {
+      (format!("{:?}", o.files), rules.incremental_cache_state())
+    })
+    .collect::<Vec<_>>();
+  let cache_state = (
+    lint_rules.incremental_cache_state(),
+    overrides_state,
+    plugins.as_ref().map(|p| p.checksum()),
+  );
   let incremental_cache = Arc::new(IncrementalCache::new(
     caches.lint_incremental_cache_db(),
-    &lint_rules.incremental_cache_state(),
+    &cache_state,
     &paths,
   ));
+  // every diagnostic needs to be seen when showing the fixes or writing
+  // the baseline
+  let watch_cache = watch_cache
+    .filter(|_| !lint_options.fix_dry_run && !lint_options.write_baseline);
+  if let Some(watch_cache) = &watch_cache {
+    let sorted = |rules: &HashSet<String>| {
+      let mut rules = rules.iter().cloned().collect::<Vec<_>>();
+      rules.sort();
+      rules
+    };
+    let warn_rules = std::iter::once(&lint_options.warn_rules)
+      .chain(lint_options.overrides.iter().map(|o| &o.warn_rules))
+      .map(sorted)
+      .collect::<Vec<_>>();
+    // the diagnostics of the unchanged files aren't reported again, so
+    // changing how they're reported needs to lint all the files again
+    let state_hash = FastInsecureHasher::hash(
+      serde_json::to_string(&(
+        &cache_state,
+        format!("{:?}", lint_options.reporter_kind),
+        warn_rules,
+      ))
+      .unwrap(),
+    );
+    watch_cache.lock().start_run(state_hash, &paths);
+  }
   let target_files_len = paths.len();
-  let reporter_kind = lint_options.reporter_kind;
+  let reporter_kind = lint_options.reporter_kind.clone();
   // todo(dsherret): abstract away this lock behind a performant interface
-  let reporter_lock =
-    Arc::new(Mutex::new(create_reporter(reporter_kind.clone())));
+  let reporter_lock = Arc::new(Mutex::new(create_reporter(
+    reporter_kind.clone(),
+    factory.cli_options().initial_cwd(),
+  )));
   let has_error = Arc::new(AtomicFlag::default());
+  let baseline_path = lint_options.baseline.clone().unwrap_or_else(|| {
+    maybe_config_file
+      .and_then(|c| c.specifier.to_file_path().ok())
+      .and_then(|p| p.parent().map(ToOwned::to_owned))
+      .unwrap_or_else(|| factory.cli_options().initial_cwd().to_path_buf())
+      .join(BASELINE_FILE_NAME)
+  });
+  let baseline =
+    LintBaseline::load(baseline_path, lint_options.write_baseline)?;
+  let severities = Arc::new(RuleSeverities::new(&lint_options, baseline));
+  let max_warnings = lint_options.max_warnings;
+  let write_baseline = lint_options.write_baseline;
 
   let mut futures = Vec::with_capacity(2);
   if lint_rules.no_slow_types {
     if let Some(config_file) = maybe_config_file {
       let members = config_file.to_workspace_members()?;
       let has_error = has_error.clone();
+      let severities = severities.clone();
       let reporter_lock = reporter_lock.clone();
       let module_graph_creator = factory.module_graph_creator().await?.clone();
       let path_urls = paths
@@ -202,12 +318,11 @@ async fn lint_files(
             &export_urls,
             &graph,
           );
-          if !diagnostics.is_empty() {
-            has_error.raise();
-            let mut reporter = reporter_lock.lock();
-            for diagnostic in &diagnostics {
-              reporter
-                .visit_diagnostic(LintOrCliDiagnostic::FastCheck(diagnostic));
+          let mut reporter = reporter_lock.lock();
+          for diagnostic in &diagnostics {
+            let d = LintOrCliDiagnostic::FastCheck(diagnostic);
+            if severities.report(&mut **reporter, d) {
+              has_error.raise();
             }
           }
         }
@@ -218,23 +333,137 @@ async fn lint_files(
 
   futures.push({
     let has_error = has_error.clone();
-    let linter = create_linter(lint_rules.rules);
+    let severities = severities.clone();
+    // the linter of the base rules followed by those of the overrides,
+    // along with the CLI specific rules and the codes excluded for the
+    // rules of the plugins
+    let linters = Arc::new(
+      std::iter::once((lint_rules, &lint_options.rules))
+        .chain(
+          override_rules
+            .into_iter()
+            .zip(lint_options.overrides.iter().map(|o| &o.rules)),
+        )
+        .map(|(rules, rules_config)| {
+          (
+            create_linter(rules.rules),
+            rules.import_extensions,
+            rules.jsx_a11y,
+            rules_config.exclude.clone().unwrap_or_default(),
+          )
+        })
+        .collect::<Vec<_>>(),
+    );
     let reporter_lock = reporter_lock.clone();
     let incremental_cache = incremental_cache.clone();
-    let fix = lint_options.fix;
+    let watch_cache = watch_cache.clone();
+    let fix_dry_run = lint_options.fix_dry_run;
+    let fix = lint_options.fix || fix_dry_run;
+    // the codes of the rules that deno_lint doesn't run
+    let cli_rule_codes = JsxA11yRule::ALL
+      .iter()
+      .map(|rule| rule.code().to_string())
+      .chain(plugins.iter().flat_map(|p| p.rule_codes().iter().cloned()))
+      .collect::<Vec<_>>();
     deno_core::unsync::spawn(async move {
       run_parallelized(paths, {
         move |file_path| {
           let file_text = fs::read_to_string(&file_path)?;
 
+          // the file didn't change since the previous run, so its
+          // diagnostics are reported again without linting it
+          let maybe_cached = watch_cache.as_ref().and_then(|watch_cache| {
+            watch_cache
+              .lock()
+              .get_file_diagnostics(&file_path, &file_text)
+          });
+          if let Some(mut cached) = maybe_cached {
+            cached.lint.sort_by_key(|d| d.range.start);
+            let mut reporter = reporter_lock.lock();
+            for d in cached.iter() {
+              if severities.report(&mut **reporter, d) {
+                has_error.raise();
+              }
+            }
+            return Ok(());
+          }
+
           // don't bother rechecking this file if it didn't have any diagnostics before
           if incremental_cache.is_file_same(&file_path, &file_text) {
+            if let Some(watch_cache) = &watch_cache {
+              watch_cache.lock().update_file(
+                &file_path,
+                &file_text,
+                Default::default(),
+              );
+            }
             return Ok(());
           }
 
-          let r = lint_file(&linter, &file_path, file_text, fix);
+          let linter_index = lint_options
+            .override_index_for_path(&file_path)
+            .map(|i| i + 1)
+            .unwrap_or(0);
+          let (linter, import_extensions, jsx_a11y_rules, excluded_codes) =
+            &linters[linter_index];
+          let orig_text = if fix { Some(file_text.clone()) } else { None };
+          let r = lint_file(linter, &file_path, file_text, fix);
+          let (r, import_extensions_diagnostics) = match *import_extensions {
+            Some(style) => match r.and_then(|(source, diagnostics)| {
+              import_extensions::lint_file_import_extensions(
+                linter,
+                &specifier_from_file_path(&file_path)?,
+                style,
+                fix,
+                source,
+                diagnostics,
+              )
+            }) {
+              Ok((source, diagnostics, import_extensions_diagnostics)) => {
+                (Ok((source, diagnostics)), import_extensions_diagnostics)
+              }
+              Err(err) => (Err(err), Vec::new()),
+            },
+            None => (r, Vec::new()),
+          };
+          let r = match orig_text {
+            Some(orig_text) => r.and_then(|(source, diagnostics)| {
+              write_fixed_file(
+                &file_path,
+                &orig_text,
+                source.text_info().text_str(),
+                fix_dry_run,
+              )?;
+              Ok((source, diagnostics))
+            }),
+            None => r,
+          };
+          let r = r.and_then(|(source, mut diagnostics)| {
+            // deno_lint doesn't know the codes of the rules it doesn't run
+            remove_ignore_diagnostics(&mut diagnostics, &cli_rule_codes);
+            let mut rule_diagnostics = match &plugins {
+              Some(plugins) => plugins.lint_file(&source, excluded_codes)?,
+              None => Vec::new(),
+            };
+            rule_diagnostics.extend(jsx_a11y::collect_jsx_a11y_diagnostics(
+              &source,
+              jsx_a11y_rules,
+            ));
+            Ok((source, diagnostics, rule_diagnostics))
+          });
+          let (r, rule_diagnostics) = match r {
+            Ok((source, diagnostics, rule_diagnostics)) => {
+              (Ok((source, diagnostics)), rule_diagnostics)
+            }
+            Err(err) => (Err(err), Vec::new()),
+          };
           if let Ok((file_source, file_diagnostics)) = &r {
-            if file_diagnostics.is_empty() {
+            // the file on disk wasn't changed when doing a dry run
+            if file_diagnostics.is_empty()
+              && !fix_dry_run
+              && import_extensions_diagnostics.is_empty()
+              && rule_diagnostics.is_empty()
+            {
               // update the incremental cache if there were no diagnostics
               incremental_cache.update_file(
                 &file_path,
@@ -244,14 +473,44 @@ async fn lint_files(
             }
           }
 
+          if let Some(watch_cache) = &watch_cache {
+            let mut watch_cache = watch_cache.lock();
+            match &r {
+              Ok((file_source, file_diagnostics)) => {
+                watch_cache.update_file(
+                  &file_path,
+                  file_source.text_info().text_str(),
+                  FileDiagnostics {
+                    lint: file_diagnostics.clone(),
+                    import_extensions: import_extensions_diagnostics.clone(),
+                    rule: rule_diagnostics.clone(),
+                  },
+                );
+              }
+              // lint the file again in the next run to report the error
+              Err(_) => watch_cache.remove_file(&file_path),
+            }
+          }
+
           let success = handle_lint_result(
             &file_path.to_string_lossy(),
             r,
             reporter_lock.clone(),
+            &severities,
           );
           if !success {
             has_error.raise();
           }
+          let mut reporter = reporter_lock.lock();
+          let diagnostics = import_extensions_diagnostics
+            .iter()
+            .map(LintOrCliDiagnostic::ImportExtensions)
+            .chain(rule_diagnostics.iter().map(LintOrCliDiagnostic::Rule));
+          for d in diagnostics {
+            if severities.report(&mut **reporter, d) {
+              has_error.raise();
+            }
+          }
 
           Ok(())
         }
@@ -265,18 +524,66 @@ async fn lint_files(
   incremental_cache.wait_completion().await;
   reporter_lock.lock().close(target_files_len);
 
-  Ok(!has_error.is_raised())
+  let maybe_delta = watch_cache.and_then(|c| c.lock().take_delta());
+  if let Some(delta) = maybe_delta {
+    print_watch_delta(&delta, factory.cli_options().initial_cwd());
+  }
+
+  if write_baseline {
+    if let Some(baseline) = &severities.baseline {
+      let count = baseline.write()?;
+      info!(
+        "Wrote {} problem{} to the baseline at {}",
+        count,
+        if count == 1 { "" } else { "s" },
+        baseline.path().display()
+      );
+    }
+  }
+
+  Ok(!has_error.is_raised() && severities.check_max_warnings(max_warnings))
+}
+
+/// Prints how the diagnostics changed since the previous run in watch mode.
+fn print_watch_delta(delta: &LintWatchDelta, initial_cwd: &Path) {
+  if delta.added.is_empty() && delta.resolved.is_empty() {
+    return;
+  }
+  info!(
+    "{} new and {} resolved problems since the previous run",
+    delta.added.len(),
+    delta.resolved.len()
+  );
+  let changes = delta
+    .added
+    .iter()
+    .map(|change| (colors::green("+").to_string(), change))
+    .chain(
+      delta
+        .resolved
+        .iter()
+        .map(|change| (colors::red("-").to_string(), change)),
+    );
+  for (sign, (path, diagnostic)) in changes {
+    let path = path.strip_prefix(initial_cwd).unwrap_or(path);
+    info!("  {} {}: {}", sign, path.display(), diagnostic);
+  }
 }
 
 fn collect_lint_files(
   cli_options: &CliOptions,
-  files: FilePatterns,
+  lint_options: &LintOptions,
 ) -> Result<Vec<PathBuf>, AnyError> {
-  FileCollector::new(|e| is_script_ext(e.path))
+  let mut file_collector = FileCollector::new(|e| is_script_ext(e.path))
     .ignore_git_folder()
     .ignore_node_modules()
     .set_vendor_folder(cli_options.vendor_dir_path().map(ToOwned::to_owned))
-    .collect_file_patterns(files)
+    .set_use_gitignore(lint_options.gitignore)
+    .follow_symlinks(lint_options.follow_symlinks);
+  if let Some(max_depth) = lint_options.max_depth {
+    file_collector = file_collector.max_depth(max_depth);
+  }
+  file_collector.collect_file_patterns(lint_options.files.clone())
 }
 
 #[allow(clippy::print_stdout)]
@@ -323,6 +630,66 @@ pub fn print_rules_list(json: bool, maybe_rules_tags: Option<Vec<String>>) {
   }
 }
 
+/// Prints the documentation of a rule, which includes the rationale along
+/// with examples of valid and invalid code.
+#[allow(clippy::print_stdout)]
+pub fn print_rule_explanation(
+  rule_name: &str,
+  json: bool,
+) -> Result<(), AnyError> {
+  let Some(rule) = rules::get_all_rules()
+    .into_iter()
+    .find(|rule| rule.code() == rule_name)
+  else {
+    bail!(
+      "Unknown lint rule \"{}\". Run `deno lint --rules` to list the available rules.",
+      rule_name
+    );
+  };
+  let docs_url = format!("https://lint.deno.land/#{}", rule.code());
+
+  if json {
+    let json_rule = serde_json::json!({
+      "code": rule.code(),
+      "tags": rule.tags(),
+      "docs": rule.docs(),
+      "docsUrl": docs_url,
+    });
+    let json_str = serde_json::to_string_pretty(&json_rule).unwrap();
+    println!("{json_str}");
+    return Ok(());
+  }
+
+  print!("{}", colors::cyan(rule.code()));
+  if rule.tags().is_empty() {
+    println!();
+  } else {
+    println!(" [{}]", colors::gray(rule.tags().join(", ")));
+  }
+  println!();
+  let docs = rule.docs().trim();
+  if docs.is_empty() {
+    println!("There is no documentation for this rule.");
+  } else {
+    let mut in_code_block = false;
+    for line in docs.lines() {
+      if line.trim_start().starts_with("```") {
+        in_code_block = !in_code_block;
+        println!("{}", colors::gray(line));
+      } else if in_code_block {
+        println!("  {line}");
+      } else if line.starts_with('#') {
+        println!("{}", colors::bold(line.trim_start_matches('#').trim()));
+      } else {
+        println!("{line}");
+      }
+    }
+  }
+  println!();
+  println!("{}", colors::gray(format!("help: {docs_url}")));
+  Ok(())
+}
+
 pub fn create_linter(rules: Vec<&'static dyn LintRule>) -> Linter {
   LinterBuilder::default()
     .ignore_file_directive("deno-lint-ignore-file")
@@ -341,7 +708,7 @@ fn lint_file(
   let media_type = MediaType::from_specifier(&specifier);
 
   if fix {
-    lint_file_and_fix(linter, &specifier, media_type, source_code, file_path)
+    lint_file_and_fix(linter, &specifier, media_type, source_code)
   } else {
     linter
       .lint_file(LintFileOptions {
@@ -358,7 +725,6 @@ fn lint_file_and_fix(
   specifier: &ModuleSpecifier,
   media_type: MediaType,
   source_code: String,
-  file_path: &Path,
 ) -> Result<(ParsedSource, Vec<LintDiagnostic>), deno_core::anyhow::Error> {
   // initial lint
   let (source, diagnostics) = linter.lint_file(LintFileOptions {
@@ -404,15 +770,51 @@ fn lint_file_and_fix(
     }
   }
 
-  if fix_iterations > 0 {
-    // everything looks good and the file still parses, so write it out
-    fs::write(file_path, source.text_info().text_str())
-      .context("Failed writing fix to file.")?;
-  }
-
   Ok((source, diagnostics))
 }
 
+/// Writes the fixed text of a file once everything looks good and the file
+/// still parses. On a dry run, the changes are shown as a diff instead.
+fn write_fixed_file(
+  file_path: &Path,
+  orig_text: &str,
+  fixed_text: &str,
+  dry_run: bool,
+) -> Result<(), AnyError> {
+  if orig_text == fixed_text {
+    return Ok(());
+  }
+  if dry_run {
+    let diff = diff(
+      orig_text,
+      fixed_text,
+      &DiffOptions {
+        granularity: DiffGranularity::Word,
+        context_lines: 0,
+      },
+    );
+    info!(
+      "\n{} {}:\n{}",
+      colors::bold("fix"),
+      file_path.display(),
+      diff
+    );
+    return Ok(());
+  }
+  // write to the target of a symlink instead of replacing the symlink,
+  // and keep the permissions of the file, such as its executable bit
+  let file_path = canonicalize_path(file_path)?;
+  #[cfg(unix)]
+  let mode = {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(&file_path)?.permissions().mode()
+  };
+  #[cfg(not(unix))]
+  let mode = 0o644;
+  atomic_write_file(&file_path, fixed_text, mode)
+    .context("Failed writing fix to file.")
+}
+
 fn apply_lint_fixes_and_relint(
   specifier: &ModuleSpecifier,
   media_type: MediaType,
@@ -453,6 +855,7 @@ fn apply_lint_fixes(
       range: change.range.as_byte_range(file_start),
       new_text: change.new_text.to_string(),
     })
+    .chain(get_unused_ignore_changes(text_info, diagnostics))
     .collect::<Vec<_>>();
   if quick_fixes.is_empty() {
     return None;
@@ -500,6 +903,7 @@ fn handle_lint_result(
   file_path: &str,
   result: Result<(ParsedSource, Vec<LintDiagnostic>), AnyError>,
   reporter_lock: Arc<Mutex<Box<dyn LintReporter + Send>>>,
+  severities: &RuleSeverities,
 ) -> bool {
   let mut reporter = reporter_lock.lock();
 
@@ -509,10 +913,13 @@ fn handle_lint_result(
         std::cmp::Ordering::Equal => a.range.start.cmp(&b.range.start),
         file_order => file_order,
       });
+      let mut success = true;
       for d in &file_diagnostics {
-        reporter.visit_diagnostic(LintOrCliDiagnostic::Lint(d));
+        if severities.report(&mut **reporter, LintOrCliDiagnostic::Lint(d)) {
+          success = false;
+        }
       }
-      file_diagnostics.is_empty()
+      success
     }
     Err(err) => {
       reporter.visit_error(file_path, &err);
@@ -521,10 +928,100 @@ fn handle_lint_result(
   }
 }
 
+/// Reports the diagnostics with the severities of their rules from
+/// `lint.rules` and `lint.overrides`, counting the warnings for
+/// `--max-warnings`. Diagnostics in the baseline aren't reported.
+struct RuleSeverities {
+  warn_rules: HashSet<String>,
+  /// The files of each override with the rules reported as warnings in
+  /// them, where the last override matching a file wins.
+  overrides: Vec<(FilePatterns, HashSet<String>)>,
+  warning_count: AtomicUsize,
+  baseline: Option<LintBaseline>,
+}
+
+impl RuleSeverities {
+  fn new(lint_options: &LintOptions, baseline: Option<LintBaseline>) -> Self {
+    Self {
+      warn_rules: lint_options.warn_rules.clone(),
+      overrides: lint_options
+        .overrides
+        .iter()
+        .map(|o| (o.files.clone(), o.warn_rules.clone()))
+        .collect(),
+      warning_count: AtomicUsize::new(0),
+      baseline,
+    }
+  }
+
+  fn warn_rules_for(&self, d: &LintOrCliDiagnostic) -> &HashSet<String> {
+    if self.overrides.is_empty() {
+      return &self.warn_rules;
+    }
+    let maybe_path = match d.location() {
+      DiagnosticLocation::Path { path } => Some(path),
+      DiagnosticLocation::Module { specifier }
+      | DiagnosticLocation::ModulePosition { specifier, .. } => {
+        specifier.to_file_path().ok()
+      }
+    };
+    maybe_path
+      .and_then(|path| {
+        self
+          .overrides
+          .iter()
+          .rfind(|(files, _)| files.matches_path(&path, PathKind::File))
+      })
+      .map(|(_, warn_rules)| warn_rules)
+      .unwrap_or(&self.warn_rules)
+  }
+
+  /// Reports the diagnostic and returns whether it's an error.
+  fn report(
+    &self,
+    reporter: &mut (dyn LintReporter + Send),
+    d: LintOrCliDiagnostic,
+  ) -> bool {
+    if self.baseline.as_ref().is_some_and(|b| b.take(&d)) {
+      false
+    } else if self.warn_rules_for(&d).contains(d.code().as_ref()) {
+      self.warning_count.fetch_add(1, Ordering::Relaxed);
+      reporter.visit_diagnostic(LintOrCliDiagnostic::Warning(&d));
+      false
+    } else {
+      reporter.visit_diagnostic(d);
+      true
+    }
+  }
+
+  /// Returns false when there were more warnings than allowed.
+  fn check_max_warnings(&self, max_warnings: Option<usize>) -> bool {
+    let warning_count = self.warning_count.load(Ordering::Relaxed);
+    match max_warnings {
+      Some(max_warnings) if warning_count > max_warnings => {
+        log::error!(
+          "{} Found {} warning{}, which is more than the maximum of {} allowed by --max-warnings.",
+          colors::red_bold("error:"),
+          warning_count,
+          if warning_count == 1 { "" } else { "s" },
+          max_warnings,
+        );
+        false
+      }
+      _ => true,
+    }
+  }
+}
+
 #[derive(Clone, Copy)]
 pub enum LintOrCliDiagnostic<'a> {
   Lint(&'a LintDiagnostic),
   FastCheck(&'a FastCheckDiagnostic),
+  ImportExtensions(&'a ImportExtensionsDiagnostic),
+  /// A diagnostic of the rules of the lint plugins or the jsx-a11y rules.
+  Rule(&'a RuleDiagnostic),
+  /// A diagnostic of a rule configured with the "warn" severity.
+  Warning(&'a LintOrCliDiagnostic<'a>),
 }
 
 impl<'a> LintOrCliDiagnostic<'a> {
@@ -532,6 +1029,9 @@ impl<'a> LintOrCliDiagnostic<'a> {
     match self {
       LintOrCliDiagnostic::Lint(d) => &d.specifier,
       LintOrCliDiagnostic::FastCheck(d) => d.specifier(),
+      LintOrCliDiagnostic::ImportExtensions(d) => &d.specifier,
+      LintOrCliDiagnostic::Rule(d) => &d.specifier,
+      LintOrCliDiagnostic::Warning(d) => d.specifier(),
     }
   }
 
@@ -541,6 +1041,22 @@ impl<'a> LintOrCliDiagnostic<'a> {
       LintOrCliDiagnostic::FastCheck(d) => {
         d.range().map(|r| (&r.text_info, r.range))
       }
+      LintOrCliDiagnostic::ImportExtensions(d) => Some((&d.text_info, d.range)),
+      LintOrCliDiagnostic::Rule(d) => Some((&d.text_info, d.range)),
+      LintOrCliDiagnostic::Warning(d) => d.range(),
+    }
+  }
+
+  pub fn is_fixable(&self) -> bool {
+    match self {
+      LintOrCliDiagnostic::Lint(d) => {
+        !d.fixes.is_empty() || is_unused_ignore_fixable(d)
+      }
+      LintOrCliDiagnostic::ImportExtensions(d) => {
+        d.fixed_import_specifier.is_some()
+      }
+      LintOrCliDiagnostic::FastCheck(_) | LintOrCliDiagnostic::Rule(_) => false,
+      LintOrCliDiagnostic::Warning(d) => d.is_fixable(),
     }
   }
 }
@@ -550,6 +1066,11 @@ impl<'a> deno_ast::diagnostics::Diagnostic for LintOrCliDiagnostic<'a> {
     match self {
       LintOrCliDiagnostic::Lint(d) => d.level(),
       LintOrCliDiagnostic::FastCheck(d) => d.level(),
+      LintOrCliDiagnostic::ImportExtensions(d) => d.level(),
+      LintOrCliDiagnostic::Rule(d) => d.level(),
+      LintOrCliDiagnostic::Warning(_) => {
+        deno_ast::diagnostics::DiagnosticLevel::Warning
+      }
     }
   }
 
@@ -557,6 +1078,9 @@ impl<'a> deno_ast::diagnostics::Diagnostic for LintOrCliDiagnostic<'a> {
     match self {
       LintOrCliDiagnostic::Lint(d) => d.code(),
       LintOrCliDiagnostic::FastCheck(_) => Cow::Borrowed("no-slow-types"),
+      LintOrCliDiagnostic::ImportExtensions(d) => d.code(),
+      LintOrCliDiagnostic::Rule(d) => d.code(),
+      LintOrCliDiagnostic::Warning(d) => d.code(),
     }
   }
 
@@ -564,6 +1088,9 @@ impl<'a> deno_ast::diagnostics::Diagnostic for LintOrCliDiagnostic<'a> {
     match self {
       LintOrCliDiagnostic::Lint(d) => d.message(),
       LintOrCliDiagnostic::FastCheck(d) => d.message(),
+      LintOrCliDiagnostic::ImportExtensions(d) => d.message(),
+      LintOrCliDiagnostic::Rule(d) => d.message(),
+      LintOrCliDiagnostic::Warning(d) => d.message(),
     }
   }
 
@@ -571,6 +1098,9 @@ impl<'a> deno_ast::diagnostics::Diagnostic for LintOrCliDiagnostic<'a> {
     match self {
       LintOrCliDiagnostic::Lint(d) => d.location(),
       LintOrCliDiagnostic::FastCheck(d) => d.location(),
+      LintOrCliDiagnostic::ImportExtensions(d) => d.location(),
+      LintOrCliDiagnostic::Rule(d) => d.location(),
+      LintOrCliDiagnostic::Warning(d) => d.location(),
     }
   }
 
@@ -578,6 +1108,9 @@ impl<'a> deno_ast::diagnostics::Diagnostic for LintOrCliDiagnostic<'a> {
     match self {
       LintOrCliDiagnostic::Lint(d) => d.snippet(),
       LintOrCliDiagnostic::FastCheck(d) => d.snippet(),
+      LintOrCliDiagnostic::ImportExtensions(d) => d.snippet(),
+      LintOrCliDiagnostic::Rule(d) => d.snippet(),
+      LintOrCliDiagnostic::Warning(d) => d.snippet(),
     }
   }
 
@@ -585,6 +1118,9 @@ impl<'a> deno_ast::diagnostics::Diagnostic for LintOrCliDiagnostic<'a> {
     match self {
       LintOrCliDiagnostic::Lint(d) => d.hint(),
       LintOrCliDiagnostic::FastCheck(d) => d.hint(),
+      LintOrCliDiagnostic::ImportExtensions(d) => d.hint(),
+      LintOrCliDiagnostic::Rule(d) => d.hint(),
+      LintOrCliDiagnostic::Warning(d) => d.hint(),
     }
   }
 
@@ -594,6 +1130,9 @@ impl<'a> deno_ast::diagnostics::Diagnostic for LintOrCliDiagnostic<'a> {
     match self {
       LintOrCliDiagnostic::Lint(d) => d.snippet_fixed(),
       LintOrCliDiagnostic::FastCheck(d) => d.snippet_fixed(),
+      LintOrCliDiagnostic::ImportExtensions(d) => d.snippet_fixed(),
+      LintOrCliDiagnostic::Rule(d) => d.snippet_fixed(),
+      LintOrCliDiagnostic::Warning(d) => d.snippet_fixed(),
     }
   }
 
@@ -601,6 +1140,9 @@ impl<'a> deno_ast::diagnostics::Diagnostic for LintOrCliDiagnostic<'a> {
     match self {
       LintOrCliDiagnostic::Lint(d) => d.info(),
       LintOrCliDiagnostic::FastCheck(d) => d.info(),
+      LintOrCliDiagnostic::ImportExtensions(d) => d.info(),
+      LintOrCliDiagnostic::Rule(d) => d.info(),
+      LintOrCliDiagnostic::Warning(d) => d.info(),
     }
   }
 
@@ -608,6 +1150,9 @@ impl<'a> deno_ast::diagnostics::Diagnostic for LintOrCliDiagnostic<'a> {
     match self {
       LintOrCliDiagnostic::Lint(d) => d.docs_url(),
       LintOrCliDiagnostic::FastCheck(d) => d.docs_url(),
+      LintOrCliDiagnostic::ImportExtensions(d) => d.docs_url(),
+      LintOrCliDiagnostic::Rule(d) => d.docs_url(),
+      LintOrCliDiagnostic::Warning(d) => d.docs_url(),
     }
   }
 }
@@ -626,6 +1171,7 @@ struct LintError {
 
 struct PrettyLintReporter {
   lint_count: u32,
+  warning_count: u32,
   fixable_diagnostics: u32,
 }
 
@@ -633,6 +1179,7 @@ impl PrettyLintReporter {
   fn new() -> PrettyLintReporter {
     PrettyLintReporter {
       lint_count: 0,
+      warning_count: 0,
       fixable_diagnostics: 0,
     }
   }
@@ -641,10 +1188,11 @@ impl PrettyLintReporter {
 impl LintReporter for PrettyLintReporter {
   fn visit_diagnostic(&mut self, d: LintOrCliDiagnostic) {
     self.lint_count += 1;
-    if let LintOrCliDiagnostic::Lint(d) = d {
-      if !d.fixes.is_empty() {
-        self.fixable_diagnostics += 1;
-      }
+    if matches!(d, LintOrCliDiagnostic::Warning(_)) {
+      self.warning_count += 1;
+    }
+    if d.is_fixable() {
+      self.fixable_diagnostics += 1;
     }
 
     log::error!("{}", d.display());
@@ -656,16 +1204,24 @@ impl LintReporter for PrettyLintReporter {
   }
 
   fn close(&mut self, check_count: usize) {
-    let fixable_suffix = if self.fixable_diagnostics > 0 {
-      colors::gray(format!(" ({} fixable via --fix)", self.fixable_diagnostics))
-        .to_string()
-    } else {
+    let mut notes = Vec::new();
+    match self.warning_count {
+      0 => {}
+      1 => notes.push("1 warning".to_string()),
+      n => notes.push(format!("{n} warnings")),
+    }
+    if self.fixable_diagnostics > 0 {
+      notes.push(format!("{} fixable via --fix", self.fixable_diagnostics));
+    }
+    let suffix = if notes.is_empty() {
       "".to_string()
+    } else {
+      colors::gray(format!(" ({})", notes.join(", "))).to_string()
     };
     match self.lint_count {
-      1 => info!("Found 1 problem{}", fixable_suffix),
+      1 => info!("Found 1 problem{}", suffix),
       n if n > 1 => {
-        info!("Found {} problems{}", self.lint_count, fixable_suffix)
+        info!("Found {} problems{}", self.lint_count, suffix)
       }
       _ => (),
     }
@@ -852,7 +1408,7 @@ fn get_config_rules_err_empty(
   maybe_config_file: Option<&deno_config::ConfigFile>,
 ) -> Result<ConfiguredRules, AnyError> {
   let lint_rules = get_configured_rules(rules, maybe_config_file);
-  if lint_rules.rules.is_empty() {
+  if lint_rules.rules.is_empty() && lint_rules.jsx_a11y.is_empty() {
     bail!("No rules have been configured")
   }
   Ok(lint_rules)
@@ -863,6 +1419,8 @@ pub struct ConfiguredRules {
   pub rules: Vec<&'static dyn LintRule>,
   // cli specific rules
   pub no_slow_types: bool,
+  pub import_extensions: Option<ImportExtensionsStyle>,
+  pub jsx_a11y: Vec<JsxA11yRule>,
 }
 
 impl Default for ConfiguredRules {
@@ -880,6 +1438,10 @@ impl ConfiguredRules {
     if self.no_slow_types {
       names.push("no-slow-types");
     }
+    if let Some(style) = self.import_extensions {
+      names.push(style.code());
+    }
+    names.extend(self.jsx_a11y.iter().map(|rule| rule.code()));
     names
   }
 }
@@ -889,35 +1451,69 @@ pub fn get_configured_rules(
   maybe_config_file: Option<&deno_config::ConfigFile>,
 ) -> ConfiguredRules {
   const NO_SLOW_TYPES_NAME: &str = "no-slow-types";
+  const CLI_RULE_NAMES: [&str; 3] = [
+    NO_SLOW_TYPES_NAME,
+    import_extensions::IMPORT_EXTENSIONS_CODE,
+    import_extensions::NO_IMPORT_EXTENSIONS_CODE,
+  ];
   let implicit_no_slow_types = maybe_config_file
     .map(|c| c.is_package() || !c.json.workspaces.is_empty())
     .unwrap_or(false);
-  let no_slow_types = implicit_no_slow_types
-    && !rules
+  let is_excluded = |name: &str| {
+    rules
       .exclude
       .as_ref()
-      .map(|exclude| exclude.iter().any(|i| i == NO_SLOW_TYPES_NAME))
-      .unwrap_or(false);
+      .map(|exclude| exclude.iter().any(|i| i == name))
+      .unwrap_or(false)
+  };
+  let is_included = |name: &str| {
+    !is_excluded(name)
+      && rules
+        .include
+        .as_ref()
+        .map(|include| include.iter().any(|i| i == name))
+        .unwrap_or(false)
+  };
+  let no_slow_types =
+    implicit_no_slow_types && !is_excluded(NO_SLOW_TYPES_NAME);
+  // the import extension rules are opt-in and mutually exclusive
+  let import_extensions =
+    if is_included(import_extensions::NO_IMPORT_EXTENSIONS_CODE) {
+      Some(ImportExtensionsStyle::Never)
+    } else if is_included(import_extensions::IMPORT_EXTENSIONS_CODE) {
+      Some(ImportExtensionsStyle::Always)
+    } else {
+      None
+    };
+  let tags = rules
+    .tags
+    .unwrap_or_else(|| get_default_tags(maybe_config_file));
+  // the accessibility rules are opt-in with their tag
+  let has_jsx_a11y_tag = tags.iter().any(|t| t == jsx_a11y::JSX_A11Y_TAG);
+  let jsx_a11y = JsxA11yRule::ALL
+    .into_iter()
+    .filter(|rule| {
+      (has_jsx_a11y_tag && !is_excluded(rule.code()))
+        || is_included(rule.code())
+    })
+    .collect();
+  let is_cli_rule = |c: &String| {
+    CLI_RULE_NAMES.contains(&c.as_str()) || JsxA11yRule::from_code(c).is_some()
+  };
   let rules = rules::get_filtered_rules(
+    Some(tags),
+    rules
+      .exclude
+      .map(|exclude| exclude.into_iter().filter(|c| !is_cli_rule(c)).collect()),
     rules
-      .tags
-      .or_else(|| Some(get_default_tags(maybe_config_file))),
-    rules.exclude.map(|exclude| {
-      exclude
-        .into_iter()
-        .filter(|c| c != NO_SLOW_TYPES_NAME)
-        .collect()
-    }),
-    rules.include.map(|include| {
-      include
-        .into_iter()
-        .filter(|c| c != NO_SLOW_TYPES_NAME)
-        .collect()
-    }),
+      .include
+      .map(|include| include.into_iter().filter(|c| !is_cli_rule(c)).collect()),
   );
   ConfiguredRules {
     rules,
     no_slow_types,
+    import_extensions,
+    jsx_a11y,
   }
 }
 
@@ -938,6 +1534,7 @@ mod test {
 
   use super::*;
   use crate::args::LintRulesConfig;
+  use test_util::TempDir;
 
   #[test]
   fn recommended_rules_when_no_tags_in_config() {
@@ -961,4 +1558,95 @@ mod test {
     recommended_rule_names.sort();
     assert_eq!(rule_names, recommended_rule_names);
   }
+
+  #[test]
+  fn import_extensions_rules_are_opt_in() {
+    let rules = get_configured_rules(Default::default(), None);
+    assert_eq!(rules.import_extensions, None);
+
+    let rules_config = LintRulesConfig {
+      exclude: None,
+      include: Some(vec!["import-extensions".to_string()]),
+      tags: None,
+    };
+    let rules = get_configured_rules(rules_config, None);
+    assert_eq!(rules.import_extensions, Some(ImportExtensionsStyle::Always));
+    assert!(!rules.rules.iter().any(|r| r.code() == "import-extensions"));
+
+    let rules_config = LintRulesConfig {
+      exclude: Some(vec!["import-extensions".to_string()]),
+      include: Some(vec![
+        "import-extensions".to_string(),
+        "no-import-extensions".to_string(),
+      ]),
+      tags: None,
+    };
+    let rules = get_configured_rules(rules_config, None);
+    assert_eq!(rules.import_extensions, Some(ImportExtensionsStyle::Never));
+  }
+
+  #[test]
+  fn jsx_a11y_rules_are_enabled_by_tag() {
+    let rules = get_configured_rules(Default::default(), None);
+    assert_eq!(rules.jsx_a11y, vec![]);
+
+    let rules_config = LintRulesConfig {
+      exclude: Some(vec!["jsx-a11y-aria-role".to_string()]),
+      include: None,
+      tags: Some(vec!["recommended".to_string(), "jsx-a11y".to_string()]),
+    };
+    let rules = get_configured_rules(rules_config, None);
+    assert_eq!(
+      rules.jsx_a11y,
+      vec![
+        JsxA11yRule::AltText,
+        JsxA11yRule::AriaProps,
+        JsxA11yRule::ClickEventsHaveKeyEvents,
+      ]
+    );
+    assert_eq!(rules.rules.len(), get_recommended_rules().len());
+
+    let rules_config = LintRulesConfig {
+      exclude: None,
+      include: Some(vec!["jsx-a11y-alt-text".to_string()]),
+      tags: None,
+    };
+    let rules = get_configured_rules(rules_config, None);
+    assert_eq!(rules.jsx_a11y, vec![JsxA11yRule::AltText]);
+  }
+
+  #[test]
+  fn write_fixed_file_through_symlink() {
+    let temp_dir = TempDir::new();
+    temp_dir.write("mod.ts", "let a = 1;\n");
+    temp_dir.symlink_file("mod.ts", "link.ts");
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      fs::set_permissions(
+        temp_dir.path().join("mod.ts"),
+        fs::Permissions::from_mode(0o755),
+      )
+      .unwrap();
+    }
+
+    write_fixed_file(
+      temp_dir.path().join("link.ts").as_path(),
+      "let a = 1;\n",
+      "const a = 1;\n",
+      false,
+    )
+    .unwrap();
+    assert!(fs::symlink_metadata(temp_dir.path().join("link.ts"))
+      .unwrap()
+      .file_type()
+      .is_symlink());
+    assert_eq!(temp_dir.read_to_string("mod.ts"), "const a = 1;\n");
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      let metadata = fs::metadata(temp_dir.path().join("mod.ts")).unwrap();
+      assert_eq!(metadata.permissions().mode() & 0o777, 0o755);
+    }
+  }
 }
@@ -0,0 +1,259 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+// Runs the lint plugins from `lint.plugins` for `deno lint`.
+//
+// A JavaScript plugin is a module with a default export like:
+//
+//   export default {
+//     name: "my-plugin",
+//     rules: {
+//       "no-alert": {
+//         create(context) {
+//           return {
+//             CallExpr(node) {
+//               if (context.getText(node).startsWith("alert(")) {
+//                 context.report({ node, message: "Don't use alert()" });
+//               }
+//             },
+//           };
+//         },
+//       },
+//     },
+//   };
+//
+// The visitor of a rule is called with the nodes of the SWC AST, which look
+// like `{ type: "CallExpr", start, end, children, parent }` where `start` and
+// `end` are offsets in the source text. A `"CallExpr:exit"` visitor is called
+// after the children of the node were visited.
+//
+// A Wasm plugin exports its `memory` along with these functions, where the
+// strings are UTF-8 JSON and a returned `i64` is the pointer of the string in
+// the upper 32 bits and its length in the lower 32 bits:
+//
+//   - `alloc(len: i32) -> i32` allocates the memory for a string passed to it
+//   - `plugin_info() -> i64` returns `{ "name": string, "rules": string[] }`
+//   - `lint(ptr: i32, len: i32) -> i64` receives
+//     `{ "filePath", "sourceText", "ast", "rules" }` with the names of the
+//     enabled rules and returns an array of
+//     `{ "rule", "start", "end", "message", "hint"? }`
+//
+// The plugins are run in an isolate without any of the Deno APIs, so they
+// can't access the file system, the network or the environment.
+
+"use strict";
+
+((globalThis) => {
+  const core = globalThis.Deno.core;
+  const ops = core.ops;
+  /** @type {Plugin[]} */
+  const plugins = [];
+
+  /**
+   * @typedef Plugin
+   * @property {string} name
+   * @property {string[]} rules
+   * @property {(file: LintFile, rules: string[], report: Report) => void} lint
+   */
+
+  /**
+   * @typedef LintFile
+   * @property {string} filePath
+   * @property {string} sourceText
+   * @property {AstNode} ast
+   */
+
+  /**
+   * @typedef AstNode
+   * @property {string} type
+   * @property {number} start
+   * @property {number} end
+   * @property {AstNode[]} children
+   * @property {AstNode | null} parent
+   */
+
+  /**
+   * @callback Report
+   * @param {string} rule
+   * @param {{ start: number, end: number, message: string, hint?: string }} diagnostic
+   */
+
+  /**
+   * @param {string} specifier
+   * @param {Record<string, unknown>} module
+   * @returns {Plugin}
+   */
+  function loadJsPlugin(specifier, module) {
+    const plugin = module.default ?? module;
+    if (typeof plugin?.name !== "string" || typeof plugin.rules !== "object") {
+      throw new Error(
+        `Invalid lint plugin '${specifier}'. Expected a default export with a "name" and "rules".`,
+      );
+    }
+    for (const [name, rule] of Object.entries(plugin.rules)) {
+      if (typeof rule?.create !== "function") {
+        throw new Error(
+          `Invalid rule '${name}' in lint plugin '${specifier}'. Expected a "create" function.`,
+        );
+      }
+    }
+    return {
+      name: plugin.name,
+      rules: Object.keys(plugin.rules),
+      lint(file, rules, report) {
+        const visitors = rules.map((name) =>
+          plugin.rules[name].create({
+            id: `${plugin.name}/${name}`,
+            filePath: file.filePath,
+            sourceText: file.sourceText,
+            getText: (node) => file.sourceText.slice(node.start, node.end),
+            report: ({ node, start, end, message, hint }) => {
+              report(name, {
+                start: start ?? node.start,
+                end: end ?? node.end,
+                message,
+                hint,
+              });
+            },
+          })
+        );
+        walk(file.ast, null, visitors);
+      },
+    };
+  }
+
+  /**
+   * @param {AstNode} node
+   * @param {AstNode | null} parent
+   * @param {Record<string, (node: AstNode) => void>[]} visitors
+   */
+  function walk(node, parent, visitors) {
+    node.parent = parent;
+    for (const visitor of visitors) {
+      visitor[node.type]?.(node);
+    }
+    for (const child of node.children) {
+      walk(child, node, visitors);
+    }
+    for (const visitor of visitors) {
+      visitor[`${node.type}:exit`]?.(node);
+    }
+  }
+
+  /**
+   * @param {string} specifier
+   * @param {number} index
+   * @returns {Plugin}
+   */
+  function loadWasmPlugin(specifier, index) {
+    const module = new WebAssembly.Module(ops.op_lint_plugin_bytes(index));
+    const imports = {};
+    for (const { module: moduleName, name, kind } of WebAssembly.Module
+      .imports(module)) {
+      if (kind !== "function") {
+        throw new Error(`Unsupported import: ${moduleName}.${name} (${kind})`);
+      }
+      imports[moduleName] ??= {};
+      // ENOSYS for wasi and a no-op for anything else
+      imports[moduleName][name] = () =>
+        moduleName.startsWith("wasi") ? 52 : 0;
+    }
+    const exports = new WebAssembly.Instance(module, imports).exports;
+    for (const name of ["memory", "alloc", "plugin_info", "lint"]) {
+      if (!(name in exports)) {
+        throw new Error(
+          `Invalid lint plugin '${specifier}'. Missing the "${name}" export.`,
+        );
+      }
+    }
+    const info = readJson(exports, exports.plugin_info());
+    return {
+      name: info.name,
+      rules: info.rules,
+      lint(file, rules, report) {
+        // the parents would make the AST circular
+        const bytes = core.encode(
+          JSON.stringify(
+            { ...file, rules },
+            (key, value) => (key === "parent" ? undefined : value),
+          ),
+        );
+        const ptr = exports.alloc(bytes.length);
+        new Uint8Array(exports.memory.buffer, ptr, bytes.length).set(bytes);
+        for (const { rule, ...diagnostic } of readJson(
+          exports,
+          exports.lint(ptr, bytes.length),
+        )) {
+          report(rule, diagnostic);
+        }
+      },
+    };
+  }
+
+  /**
+   * @param {WebAssembly.Exports} exports
+   * @param {bigint} value
+   */
+  function readJson(exports, value) {
+    value = BigInt.asUintN(64, BigInt(value));
+    const ptr = Number(value >> 32n);
+    const length = Number(value & 0xffffffffn);
+    return JSON.parse(
+      core.decode(new Uint8Array(exports.memory.buffer, ptr, length).slice()),
+    );
+  }
+
+  /**
+   * @param {{ specifier: string, module?: number, wasm?: number }[]} entries
+   * @param {Record<string, unknown>[]} modules
+   */
+  function registerPlugins(entries, modules) {
+    for (const { specifier, module, wasm } of entries) {
+      plugins.push(
+        wasm != null
+          ? loadWasmPlugin(specifier, wasm)
+          : loadJsPlugin(specifier, modules[module]),
+      );
+    }
+  }
+
+  /** @returns {string[]} */
+  function getRuleCodes() {
+    return plugins.flatMap((plugin) =>
+      plugin.rules.map((rule) => `${plugin.name}/${rule}`)
+    );
+  }
+
+  /**
+   * @param {LintFile & { excluded: string[] }} request
+   */
+  function lintFile({ filePath, sourceText, ast, excluded }) {
+    const diagnostics = [];
+    for (const plugin of plugins) {
+      const rules = plugin.rules.filter((rule) =>
+        !excluded.includes(`${plugin.name}/${rule}`)
+      );
+      if (rules.length === 0) {
+        continue;
+      }
+      plugin.lint({ filePath, sourceText, ast }, rules, (rule, d) => {
+        if (typeof d.start !== "number" || typeof d.end !== "number") {
+          throw new Error(
+            `The rule '${plugin.name}/${rule}' reported a diagnostic without a range.`,
+          );
+        }
+        diagnostics.push({
+          code: `${plugin.name}/${rule}`,
+          start: d.start,
+          end: d.end,
+          message: String(d.message),
+          hint: d.hint == null ? null : String(d.hint),
+        });
+      });
+    }
+    return diagnostics;
+  }
+
+  globalThis.registerPlugins = registerPlugins;
+  globalThis.getRuleCodes = getRuleCodes;
+  globalThis.lintFile = lintFile;
+})(globalThis);
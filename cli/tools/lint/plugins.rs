@@ -0,0 +1,651 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Support for the lint plugins declared in `lint.plugins`, which provide
+//! rules that `deno lint` runs alongside the built-in rules.
+//!
+//! A plugin is either a JavaScript module or a Wasm module implementing the
+//! protocol described in `plugin_host.js`. The rules of a plugin visit the
+//! nodes of the SWC AST of the file and are reported with a code like
+//! `<plugin name>/<rule name>`, which can be used to exclude them in
+//! `lint.rules.exclude` or to ignore them with a `deno-lint-ignore` comment.
+//!
+//! The plugins are loaded like the modules of a program, so remote plugins
+//! are cached in the DENO_DIR and checked against the lockfile, but only
+//! with access to the directories of the local plugins and the hosts of the
+//! remote ones. They run in a dedicated V8 isolate that doesn't provide any
+//! of the Deno APIs.
+
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::sync::Arc;
+
+use deno_ast::view::Node;
+use deno_ast::view::NodeTrait;
+use deno_ast::MediaType;
+use deno_ast::ModuleSpecifier;
+use deno_ast::ParsedSource;
+use deno_ast::SourcePos;
+use deno_ast::SourceRange;
+use deno_ast::SourceRanged;
+use deno_ast::SourceTextInfo;
+use deno_ast::StartSourcePos;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::futures::channel::oneshot;
+use deno_core::located_script_name;
+use deno_core::op2;
+use deno_core::resolve_import;
+use deno_core::serde::de::DeserializeOwned;
+use deno_core::serde::Deserialize;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_v8;
+use deno_core::v8;
+use deno_core::JsRuntime;
+use deno_core::ModuleCodeString;
+use deno_core::ModuleLoadResponse;
+use deno_core::ModuleLoader;
+use deno_core::ModuleSource;
+use deno_core::ModuleSourceCode;
+use deno_core::ModuleType;
+use deno_core::OpState;
+use deno_core::PollEventLoopOptions;
+use deno_core::RequestedModuleType;
+use deno_core::ResolutionKind;
+use deno_core::RuntimeOptions;
+use deno_core::ToJsBuffer;
+use deno_graph::GraphKind;
+use deno_graph::Module;
+use deno_graph::ModuleGraph;
+use deno_lint::diagnostic::LintDiagnostic;
+use deno_runtime::tokio_util::create_and_run_current_thread;
+
+use crate::args::plugin_permissions;
+use crate::factory::CliFactory;
+use crate::graph_util::check_plugin_lock;
+use crate::graph_util::graph_lock_or_exit;
+use crate::util::checksum;
+
+use super::rule_diagnostic::RuleDiagnostic;
+use super::unused_ignores::IgnoreCodeDiagnostic;
+
+/// A diagnostic as reported by the plugin host, with UTF-16 offsets.
+#[derive(Debug, Deserialize)]
+struct PluginDiagnosticResponse {
+  code: String,
+  start: usize,
+  end: usize,
+  message: String,
+  hint: Option<String>,
+}
+
+struct LintRequest {
+  /// The request serialized as a JS expression.
+  json: String,
+  response: mpsc::Sender<Result<Vec<PluginDiagnosticResponse>, AnyError>>,
+}
+
+/// Wasm bytes of the plugins, which are handed over to the isolate once.
+struct LintPluginWasmModules(Vec<Option<Arc<[u8]>>>);
+
+#[op2]
+#[serde]
+fn op_lint_plugin_bytes(state: &mut OpState, #[smi] index: u32) -> ToJsBuffer {
+  let modules = state.borrow_mut::<LintPluginWasmModules>();
+  let bytes = modules.0[index as usize].take().unwrap();
+  ToJsBuffer::from(bytes.to_vec())
+}
+
+deno_core::extension!(deno_lint_plugins,
+  ops = [op_lint_plugin_bytes],
+  options = {
+    wasm_modules: Vec<Arc<[u8]>>,
+  },
+  state = |state, options| {
+    state.put(LintPluginWasmModules(
+      options.wasm_modules.into_iter().map(Some).collect(),
+    ));
+  },
+);
+
+/// Runs the loaded plugins on a dedicated thread, because an isolate can't
+/// be shared between the threads linting the files.
+pub struct LintPluginHost {
+  rule_codes: Vec<String>,
+  checksum: String,
+  sender: mpsc::Sender<LintRequest>,
+}
+
+impl LintPluginHost {
+  /// Loads the plugins with permissions scoped to the plugin specifiers,
+  /// checking the remote ones against the lockfile.
+  ///
+  /// The JavaScript plugins are loaded as a module graph, so they can
+  /// import other modules.
+  pub async fn load(
+    factory: &CliFactory,
+    specifiers: &[ModuleSpecifier],
+  ) -> Result<Self, AnyError> {
+    let permissions = plugin_permissions(specifiers)?;
+    let maybe_lockfile = factory.maybe_lockfile();
+    let mut js_roots = Vec::new();
+    let mut wasm_modules = Vec::new();
+    let mut checksum_parts = Vec::new();
+    let mut entries = Vec::with_capacity(specifiers.len());
+    for specifier in specifiers {
+      if MediaType::from_specifier(specifier) != MediaType::Wasm {
+        entries
+          .push(json!({ "specifier": specifier, "module": js_roots.len() }));
+        js_roots.push(specifier.clone());
+        continue;
+      }
+      let file = factory
+        .file_fetcher()?
+        .fetch(specifier, &permissions)
+        .await
+        .with_context(|| {
+          format!("Failed fetching lint plugin '{specifier}'.")
+        })?;
+      if let Some(lockfile) = maybe_lockfile {
+        check_plugin_lock(&file.specifier, &file.source, &mut lockfile.lock())?;
+      }
+      checksum_parts.push(file.specifier.to_string().into_bytes());
+      checksum_parts.push(file.source.to_vec());
+      entries
+        .push(json!({ "specifier": specifier, "wasm": wasm_modules.len() }));
+      wasm_modules.push(file.source);
+    }
+    let graph = if js_roots.is_empty() {
+      ModuleGraph::new(GraphKind::CodeOnly)
+    } else {
+      let module_graph_builder = factory.module_graph_builder().await?;
+      let mut loader = module_graph_builder.create_fetch_cacher(permissions);
+      let graph = factory
+        .module_graph_creator()
+        .await?
+        .create_graph_with_loader(
+          GraphKind::CodeOnly,
+          js_roots.clone(),
+          &mut loader,
+        )
+        .await
+        .context("Failed loading lint plugins.")?;
+      module_graph_builder.graph_valid(&graph)?;
+      if let Some(lockfile) = maybe_lockfile {
+        graph_lock_or_exit(&graph, &mut lockfile.lock());
+      }
+      graph
+    };
+    for module in graph.modules() {
+      let source = match module {
+        Module::Js(module)
+          if matches!(module.media_type, MediaType::JavaScript | MediaType::Mjs) =>
+        {
+          &module.source
+        }
+        Module::Json(module) => &module.source,
+        _ => bail!(
+          "Unsupported lint plugin module '{}'. Plugins must be JavaScript or Wasm modules.",
+          module.specifier()
+        ),
+      };
+      checksum_parts.push(module.specifier().to_string().into_bytes());
+      checksum_parts.push(source.as_bytes().to_vec());
+    }
+    let main_module_code = main_module_code(&js_roots, &entries)?;
+
+    let (init_sender, init_receiver) = oneshot::channel();
+    let (sender, receiver) = mpsc::channel::<LintRequest>();
+    std::thread::spawn(move || {
+      create_and_run_current_thread(async move {
+        let mut runtime = JsRuntime::new(RuntimeOptions {
+          module_loader: Some(Rc::new(PluginModuleLoader(graph))),
+          extensions: vec![deno_lint_plugins::init_ops(wasm_modules)],
+          ..Default::default()
+        });
+        let init_result = init_plugins(&mut runtime, main_module_code).await;
+        let is_ok = init_result.is_ok();
+        if init_sender.send(init_result).is_err() || !is_ok {
+          return;
+        }
+        // runs until the host is dropped
+        for request in receiver {
+          let result = execute(&mut runtime, request.json);
+          let _ = request.response.send(result);
+        }
+      })
+    });
+
+    let rule_codes = init_receiver
+      .await?
+      .context("Failed loading lint plugins.")?;
+    log::debug!("Loaded lint plugin rules: {}", rule_codes.join(", "));
+    Ok(Self {
+      rule_codes,
+      checksum: checksum::gen(&checksum_parts),
+      sender,
+    })
+  }
+
+  /// Checksum of the plugins and the modules they import, so that a plugin
+  /// that changed causes the files to be linted again.
+  pub fn checksum(&self) -> &str {
+    &self.checksum
+  }
+
+  /// The codes of the rules of the plugins.
+  pub fn rule_codes(&self) -> &[String] {
+    &self.rule_codes
+  }
+
+  /// Runs the rules of the plugins that aren't excluded on the file.
+  pub fn lint_file(
+    &self,
+    parsed_source: &ParsedSource,
+    excluded_codes: &[String],
+  ) -> Result<Vec<RuleDiagnostic>, AnyError> {
+    if self.rule_codes.iter().all(|c| excluded_codes.contains(c)) {
+      return Ok(Vec::new());
+    }
+    let text_info = parsed_source.text_info();
+    let text = text_info.text_str();
+    let offsets = Utf16Offsets::new(text);
+    let ast = parsed_source.with_view(|program| {
+      serialize_node(program.as_node(), text_info.range().start, &offsets)
+    });
+    let request = json!({
+      "filePath": parsed_source.specifier().as_str(),
+      "sourceText": text,
+      "ast": ast,
+      "excluded": excluded_codes,
+    });
+    let (response_sender, response_receiver) = mpsc::channel();
+    self.sender.send(LintRequest {
+      json: format!("globalThis.lintFile({request})"),
+      response: response_sender,
+    })?;
+    let diagnostics = response_receiver.recv()?.with_context(|| {
+      format!(
+        "Error in lint plugin linting '{}'",
+        parsed_source.specifier()
+      )
+    })?;
+    let ignore_directives = IgnoreDirectives::new(text_info);
+    Ok(
+      diagnostics
+        .into_iter()
+        .filter_map(|d| {
+          let start = offsets.to_byte_offset(d.start);
+          let end = offsets.to_byte_offset(d.end).max(start);
+          let range = SourceRange::new(
+            text_info.range().start + start,
+            text_info.range().start + end,
+          );
+          if ignore_directives.is_ignored(text_info, range.start, &d.code) {
+            return None;
+          }
+          Some(RuleDiagnostic {
+            specifier: parsed_source.specifier().clone(),
+            text_info: text_info.clone(),
+            range,
+            code: Cow::Owned(d.code),
+            message: d.message,
+            hint: d.hint.map(Cow::Owned),
+          })
+        })
+        .collect(),
+    )
+  }
+}
+
+/// Gets the code of the module importing the JavaScript plugins, which
+/// registers all the plugins with the plugin host.
+fn main_module_code(
+  js_roots: &[ModuleSpecifier],
+  entries: &[serde_json::Value],
+) -> Result<String, AnyError> {
+  let mut code = String::new();
+  for (i, specifier) in js_roots.iter().enumerate() {
+    code.push_str(&format!(
+      "import * as plugin{i} from {};\n",
+      serde_json::to_string(specifier.as_str())?
+    ));
+  }
+  code.push_str(&format!(
+    "globalThis.registerPlugins({}, [{}]);\n",
+    serde_json::to_string(entries)?,
+    (0..js_roots.len())
+      .map(|i| format!("plugin{i}"))
+      .collect::<Vec<_>>()
+      .join(", "),
+  ));
+  Ok(code)
+}
+
+/// Loads the JavaScript modules of the plugins from their module graph,
+/// resolving the imports the same way the graph did.
+struct PluginModuleLoader(ModuleGraph);
+
+impl ModuleLoader for PluginModuleLoader {
+  fn resolve(
+    &self,
+    specifier: &str,
+    referrer: &str,
+    _kind: ResolutionKind,
+  ) -> Result<ModuleSpecifier, AnyError> {
+    let referrer = ModuleSpecifier::parse(referrer)?;
+    let specifier = match self.0.resolve_dependency(specifier, &referrer, false)
+    {
+      Some(specifier) => specifier,
+      // the main module importing the plugins isn't part of the graph
+      None => resolve_import(specifier, referrer.as_str())?,
+    };
+    Ok(self.0.resolve(&specifier))
+  }
+
+  fn load(
+    &self,
+    specifier: &ModuleSpecifier,
+    _maybe_referrer: Option<&ModuleSpecifier>,
+    _is_dyn_import: bool,
+    _requested_module_type: RequestedModuleType,
+  ) -> ModuleLoadResponse {
+    let (module_type, source) = match self.0.get(specifier) {
+      Some(Module::Js(module)) => (ModuleType::JavaScript, &module.source),
+      Some(Module::Json(module)) => (ModuleType::Json, &module.source),
+      _ => {
+        return ModuleLoadResponse::Sync(Err(generic_error(format!(
+          "Module not found in the lint plugins: {specifier}"
+        ))))
+      }
+    };
+    ModuleLoadResponse::Sync(Ok(ModuleSource::new(
+      module_type,
+      ModuleSourceCode::String(ModuleCodeString::from(source.to_string())),
+      specifier,
+      None,
+    )))
+  }
+}
+
+async fn init_plugins(
+  runtime: &mut JsRuntime,
+  main_module_code: String,
+) -> Result<Vec<String>, AnyError> {
+  runtime.execute_script(
+    located_script_name!(),
+    include_str!("plugin_host.js").to_string(),
+  )?;
+  let main_specifier =
+    ModuleSpecifier::parse("file:///$deno$lint_plugins.js").unwrap();
+  let id = runtime
+    .load_main_es_module_from_code(&main_specifier, main_module_code)
+    .await?;
+  let evaluation = runtime.mod_evaluate(id);
+  runtime
+    .run_event_loop(PollEventLoopOptions::default())
+    .await?;
+  evaluation.await?;
+  execute(runtime, "globalThis.getRuleCodes()".to_string())
+}
+
+fn execute<T: DeserializeOwned>(
+  runtime: &mut JsRuntime,
+  source: String,
+) -> Result<T, AnyError> {
+  let value = runtime.execute_script(located_script_name!(), source)?;
+  let scope = &mut runtime.handle_scope();
+  let local = v8::Local::new(scope, value);
+  Ok(serde_v8::from_v8::<T>(scope, local)?)
+}
+
+/// Serializes a node of the AST and its children, with the range of the
+/// node as UTF-16 offsets so that the plugins can index the source text.
+fn serialize_node(
+  node: Node,
+  text_start: StartSourcePos,
+  offsets: &Utf16Offsets,
+) -> serde_json::Value {
+  let range = node.range().as_byte_range(text_start);
+  json!({
+    "type": format!("{:?}", node.kind()),
+    "start": offsets.to_utf16_offset(range.start),
+    "end": offsets.to_utf16_offset(range.end),
+    "children": node
+      .children()
+      .into_iter()
+      .map(|child| serialize_node(child, text_start, offsets))
+      .collect::<Vec<_>>(),
+  })
+}
+
+/// Maps between the byte offsets of a text and its UTF-16 offsets, which
+/// is how JavaScript indexes strings.
+struct Utf16Offsets {
+  /// The UTF-16 offset of each byte, which is `None` for ASCII text
+  /// because the offsets are the same.
+  utf16_offsets: Option<Vec<usize>>,
+}
+
+impl Utf16Offsets {
+  fn new(text: &str) -> Self {
+    if text.is_ascii() {
+      return Self {
+        utf16_offsets: None,
+      };
+    }
+    let mut utf16_offsets = Vec::with_capacity(text.len() + 1);
+    let mut utf16_offset = 0;
+    for c in text.chars() {
+      for _ in 0..c.len_utf8() {
+        utf16_offsets.push(utf16_offset);
+      }
+      utf16_offset += c.len_utf16();
+    }
+    utf16_offsets.push(utf16_offset);
+    Self {
+      utf16_offsets: Some(utf16_offsets),
+    }
+  }
+
+  fn to_utf16_offset(&self, byte_offset: usize) -> usize {
+    match &self.utf16_offsets {
+      Some(offsets) => offsets[byte_offset.min(offsets.len() - 1)],
+      None => byte_offset,
+    }
+  }
+
+  fn to_byte_offset(&self, utf16_offset: usize) -> usize {
+    match &self.utf16_offsets {
+      Some(offsets) => offsets
+        .partition_point(|o| *o < utf16_offset)
+        .min(offsets.len() - 1),
+      None => utf16_offset,
+    }
+  }
+}
+
+/// Removes the diagnostics deno_lint reports for the `deno-lint-ignore`
+/// comments of rules it doesn't know about, which are the provided codes.
+pub fn remove_ignore_diagnostics(
+  diagnostics: &mut Vec<LintDiagnostic>,
+  codes: &[impl AsRef<str>],
+) {
+  diagnostics.retain(|d| {
+    !IgnoreCodeDiagnostic::from_diagnostic(d)
+      .is_some_and(|ignore| codes.iter().any(|c| c.as_ref() == ignore.code))
+  });
+}
+
+/// The `deno-lint-ignore-file` and `deno-lint-ignore` comments of a file,
+/// which deno_lint only applies to the built-in rules.
+pub(super) struct IgnoreDirectives {
+  /// The codes ignored for the whole file, where an empty list ignores
+  /// all the rules.
+  file_codes: Option<Vec<String>>,
+}
+
+impl IgnoreDirectives {
+  pub fn new(text_info: &SourceTextInfo) -> Self {
+    // the file directive must be in the comments at the top of the file
+    let file_codes = text_info
+      .text_str()
+      .lines()
+      .map(|line| line.trim())
+      .take_while(|line| {
+        line.is_empty() || line.starts_with("//") || line.starts_with("#!")
+      })
+      .find_map(|line| parse_directive(line, "deno-lint-ignore-file"));
+    Self { file_codes }
+  }
+
+  pub fn is_ignored(
+    &self,
+    text_info: &SourceTextInfo,
+    pos: SourcePos,
+    code: &str,
+  ) -> bool {
+    let matches_code =
+      |codes: &Vec<String>| codes.is_empty() || codes.iter().any(|c| c == code);
+    if self.file_codes.as_ref().is_some_and(matches_code) {
+      return true;
+    }
+    let line_index = text_info.line_index(pos);
+    if line_index == 0 {
+      return false;
+    }
+    let previous_line = text_info.line_text(line_index - 1).trim();
+    parse_directive(previous_line, "deno-lint-ignore")
+      .as_ref()
+      .is_some_and(matches_code)
+  }
+}
+
+/// Parses the codes of a directive comment like `// deno-lint-ignore a b`.
+fn parse_directive(line: &str, directive: &str) -> Option<Vec<String>> {
+  let rest = line
+    .strip_prefix("//")?
+    .trim_start()
+    .strip_prefix(directive)?;
+  if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+    // ex. deno-lint-ignore-file when looking for deno-lint-ignore
+    return None;
+  }
+  Some(
+    rest
+      .split_whitespace()
+      .take_while(|code| *code != "--")
+      .map(|code| code.to_string())
+      .collect(),
+  )
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_utf16_offsets() {
+    let offsets = Utf16Offsets::new("const a = 1;");
+    assert_eq!(offsets.to_utf16_offset(6), 6);
+    assert_eq!(offsets.to_byte_offset(6), 6);
+
+    // é is 2 bytes and 1 UTF-16 code unit, 🦕 is 4 bytes and 2 code units
+    let offsets = Utf16Offsets::new("'é🦕' + b");
+    assert_eq!(offsets.to_utf16_offset(1), 1);
+    assert_eq!(offsets.to_utf16_offset(3), 2);
+    assert_eq!(offsets.to_utf16_offset(7), 4);
+    assert_eq!(offsets.to_utf16_offset(12), 9);
+    assert_eq!(offsets.to_byte_offset(2), 3);
+    assert_eq!(offsets.to_byte_offset(4), 7);
+    assert_eq!(offsets.to_byte_offset(9), 12);
+  }
+
+  #[test]
+  fn test_remove_ignore_diagnostics() {
+    let text_info = SourceTextInfo::new(
+      "// deno-lint-ignore my-plugin/a my-plugin/ab no-var\nvar a;\n".into(),
+    );
+    let diagnostic = |code: &str, message: &str| LintDiagnostic {
+      specifier: ModuleSpecifier::parse("file:///a.ts").unwrap(),
+      range: SourceRange::new(text_info.line_start(0), text_info.line_end(0)),
+      text_info: text_info.clone(),
+      message: message.to_string(),
+      code: code.to_string(),
+      hint: None,
+      fixes: Vec::new(),
+    };
+    let mut diagnostics = vec![
+      diagnostic(
+        "ban-unknown-rule-code",
+        "Unknown rule for code \"my-plugin/a\"",
+      ),
+      diagnostic(
+        "ban-unknown-rule-code",
+        "Unknown rule for code \"my-plugin/ab\"",
+      ),
+      diagnostic(
+        "ban-unused-ignore",
+        "Ignore for code \"my-plugin/a\" was not used.",
+      ),
+      diagnostic("no-var", "`var` keyword is not allowed."),
+    ];
+    remove_ignore_diagnostics(&mut diagnostics, &["my-plugin/a"]);
+    assert_eq!(
+      diagnostics
+        .iter()
+        .map(|d| d.message.as_str())
+        .collect::<Vec<_>>(),
+      vec![
+        "Unknown rule for code \"my-plugin/ab\"",
+        "`var` keyword is not allowed.",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_parse_directive() {
+    assert_eq!(
+      parse_directive("// deno-lint-ignore my-plugin/a b", "deno-lint-ignore"),
+      Some(vec!["my-plugin/a".to_string(), "b".to_string()])
+    );
+    assert_eq!(
+      parse_directive("//deno-lint-ignore a -- reason", "deno-lint-ignore"),
+      Some(vec!["a".to_string()])
+    );
+    assert_eq!(
+      parse_directive("// deno-lint-ignore", "deno-lint-ignore"),
+      Some(vec![])
+    );
+    assert_eq!(
+      parse_directive("// deno-lint-ignore-file a", "deno-lint-ignore"),
+      None
+    );
+    assert_eq!(parse_directive("const a = 1;", "deno-lint-ignore"), None);
+  }
+
+  #[test]
+  fn test_ignore_directives() {
+    let text_info = SourceTextInfo::new(
+      concat!(
+        "// deno-lint-ignore-file my-plugin/a\n",
+        "\n",
+        "// deno-lint-ignore my-plugin/b\n",
+        "foo();\n",
+        "bar();\n",
+      )
+      .into(),
+    );
+    let directives = IgnoreDirectives::new(&text_info);
+    let foo_pos = text_info.line_start(3);
+    let bar_pos = text_info.line_start(4);
+    assert!(directives.is_ignored(&text_info, foo_pos, "my-plugin/a"));
+    assert!(directives.is_ignored(&text_info, bar_pos, "my-plugin/a"));
+    assert!(directives.is_ignored(&text_info, foo_pos, "my-plugin/b"));
+    assert!(!directives.is_ignored(&text_info, bar_pos, "my-plugin/b"));
+    assert!(!directives.is_ignored(&text_info, foo_pos, "my-plugin/c"));
+  }
+}
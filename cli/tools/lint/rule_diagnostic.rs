@@ -0,0 +1,79 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::borrow::Cow;
+
+use deno_ast::diagnostics::Diagnostic;
+use deno_ast::diagnostics::DiagnosticLevel;
+use deno_ast::diagnostics::DiagnosticLocation;
+use deno_ast::diagnostics::DiagnosticSnippet;
+use deno_ast::diagnostics::DiagnosticSnippetHighlight;
+use deno_ast::diagnostics::DiagnosticSnippetHighlightStyle;
+use deno_ast::diagnostics::DiagnosticSourcePos;
+use deno_ast::diagnostics::DiagnosticSourceRange;
+use deno_ast::ModuleSpecifier;
+use deno_ast::SourceRange;
+use deno_ast::SourceTextInfo;
+
+/// A diagnostic of a rule that isn't run by deno_lint, like the rules of
+/// the lint plugins and the jsx-a11y rules.
+#[derive(Debug, Clone)]
+pub struct RuleDiagnostic {
+  pub specifier: ModuleSpecifier,
+  pub text_info: SourceTextInfo,
+  pub range: SourceRange,
+  pub code: Cow<'static, str>,
+  pub message: String,
+  pub hint: Option<Cow<'static, str>>,
+}
+
+impl Diagnostic for RuleDiagnostic {
+  fn level(&self) -> DiagnosticLevel {
+    DiagnosticLevel::Error
+  }
+
+  fn code(&self) -> Cow<'_, str> {
+    Cow::Borrowed(&self.code)
+  }
+
+  fn message(&self) -> Cow<'_, str> {
+    Cow::Borrowed(&self.message)
+  }
+
+  fn location(&self) -> DiagnosticLocation {
+    DiagnosticLocation::ModulePosition {
+      specifier: Cow::Borrowed(&self.specifier),
+      text_info: Cow::Borrowed(&self.text_info),
+      source_pos: DiagnosticSourcePos::SourcePos(self.range.start),
+    }
+  }
+
+  fn snippet(&self) -> Option<DiagnosticSnippet<'_>> {
+    Some(DiagnosticSnippet {
+      source: Cow::Borrowed(&self.text_info),
+      highlight: DiagnosticSnippetHighlight {
+        style: DiagnosticSnippetHighlightStyle::Error,
+        range: DiagnosticSourceRange {
+          start: DiagnosticSourcePos::SourcePos(self.range.start),
+          end: DiagnosticSourcePos::SourcePos(self.range.end),
+        },
+        description: None,
+      },
+    })
+  }
+
+  fn hint(&self) -> Option<Cow<'_, str>> {
+    self.hint.as_deref().map(Cow::Borrowed)
+  }
+
+  fn snippet_fixed(&self) -> Option<DiagnosticSnippet<'_>> {
+    None
+  }
+
+  fn info(&self) -> Cow<'_, [Cow<'_, str>]> {
+    Cow::Borrowed(&[])
+  }
+
+  fn docs_url(&self) -> Option<Cow<'_, str>> {
+    None
+  }
+}
@@ -0,0 +1,269 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Reporter for `deno lint --output=sarif`, which prints the diagnostics as
+//! a [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html)
+//! log that can be uploaded to GitHub code scanning and other tools.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use deno_ast::diagnostics::Diagnostic;
+use deno_ast::diagnostics::DiagnosticLevel;
+use deno_ast::ModuleSpecifier;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::url::Url;
+use deno_lint::rules;
+
+use super::LintOrCliDiagnostic;
+use super::LintReporter;
+use crate::version;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+/// The base of the relative URIs of the files, which is the current
+/// working directory.
+const SRCROOT: &str = "%SRCROOT%";
+
+struct SarifRule {
+  id: String,
+  help_uri: Option<String>,
+}
+
+pub struct SarifLintReporter {
+  root_url: Option<Url>,
+  rules: Vec<SarifRule>,
+  rule_indexes: HashMap<String, usize>,
+  results: Vec<serde_json::Value>,
+  notifications: Vec<serde_json::Value>,
+}
+
+impl SarifLintReporter {
+  pub fn new(initial_cwd: PathBuf) -> Self {
+    Self {
+      root_url: Url::from_directory_path(initial_cwd).ok(),
+      rules: Vec::new(),
+      rule_indexes: HashMap::new(),
+      results: Vec::new(),
+      notifications: Vec::new(),
+    }
+  }
+
+  fn rule_index(&mut self, d: &LintOrCliDiagnostic) -> usize {
+    let code = d.code();
+    if let Some(index) = self.rule_indexes.get(code.as_ref()) {
+      return *index;
+    }
+    let index = self.rules.len();
+    self.rules.push(SarifRule {
+      id: code.to_string(),
+      help_uri: d.docs_url().map(|url| url.to_string()),
+    });
+    self.rule_indexes.insert(code.to_string(), index);
+    index
+  }
+
+  /// Gets the artifact location of the file, which is relative to the
+  /// current working directory when the file is in it.
+  fn artifact_location(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> serde_json::Value {
+    let relative = self
+      .root_url
+      .as_ref()
+      .filter(|root| specifier.as_str().starts_with(root.as_str()))
+      .and_then(|root| root.make_relative(specifier));
+    match relative {
+      Some(uri) => json!({ "uri": uri, "uriBaseId": SRCROOT }),
+      None => json!({ "uri": specifier.as_str() }),
+    }
+  }
+
+  fn to_sarif(&self) -> serde_json::Value {
+    let all_rules = rules::get_all_rules();
+    let rules = self
+      .rules
+      .iter()
+      .map(|rule| {
+        let mut descriptor = json!({ "id": rule.id });
+        let docs = all_rules
+          .iter()
+          .find(|r| r.code() == rule.id)
+          .map(|r| r.docs().trim())
+          .filter(|docs| !docs.is_empty());
+        if let Some(docs) = docs {
+          let summary = docs.split("\n\n").next().unwrap_or(docs);
+          descriptor["shortDescription"] =
+            json!({ "text": summary.replace('\n', " ") });
+          descriptor["fullDescription"] = json!({ "text": docs });
+        }
+        if let Some(help_uri) = &rule.help_uri {
+          descriptor["helpUri"] = json!(help_uri);
+        }
+        descriptor
+      })
+      .collect::<Vec<_>>();
+    let mut run = json!({
+      "tool": {
+        "driver": {
+          "name": "deno lint",
+          "informationUri": "https://docs.deno.com/runtime/manual/tools/linter",
+          "version": version::deno(),
+          "rules": rules,
+        },
+      },
+      "columnKind": "unicodeCodePoints",
+      "results": self.results,
+      "invocations": [{
+        "executionSuccessful": self.notifications.is_empty(),
+        "toolExecutionNotifications": self.notifications,
+      }],
+    });
+    if let Some(root_url) = &self.root_url {
+      run["originalUriBaseIds"] =
+        json!({ SRCROOT: { "uri": root_url.as_str() } });
+    }
+    json!({
+      "$schema": SARIF_SCHEMA,
+      "version": "2.1.0",
+      "runs": [run],
+    })
+  }
+}
+
+impl LintReporter for SarifLintReporter {
+  fn visit_diagnostic(&mut self, d: LintOrCliDiagnostic) {
+    let rule_index = self.rule_index(&d);
+    let mut message = d.message().to_string();
+    if let Some(hint) = d.hint() {
+      message.push_str(&format!("\n\nHint: {hint}"));
+    }
+    let mut physical_location = json!({
+      "artifactLocation": self.artifact_location(d.specifier()),
+    });
+    if let Some((text_info, range)) = d.range() {
+      let start = text_info.line_and_column_index(range.start);
+      let end = text_info.line_and_column_index(range.end);
+      // lines and columns are 1-indexed
+      physical_location["region"] = json!({
+        "startLine": start.line_index + 1,
+        "startColumn": start.column_index + 1,
+        "endLine": end.line_index + 1,
+        "endColumn": end.column_index + 1,
+      });
+    }
+    self.results.push(json!({
+      "ruleId": d.code(),
+      "ruleIndex": rule_index,
+      "level": match d.level() {
+        DiagnosticLevel::Warning => "warning",
+        DiagnosticLevel::Error => "error",
+      },
+      "message": { "text": message },
+      "locations": [{ "physicalLocation": physical_location }],
+    }));
+  }
+
+  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
+    let mut notification = json!({
+      "level": "error",
+      "message": { "text": format!("Error linting {file_path}: {err}") },
+    });
+    if let Ok(specifier) = ModuleSpecifier::from_file_path(file_path) {
+      notification["locations"] = json!([{
+        "physicalLocation": {
+          "artifactLocation": self.artifact_location(&specifier),
+        },
+      }]);
+    }
+    self.notifications.push(notification);
+  }
+
+  fn close(&mut self, _check_count: usize) {
+    // sort so that the output is deterministic
+    self.results.sort_by(|a, b| {
+      let key = |r: &serde_json::Value| {
+        let location = &r["locations"][0]["physicalLocation"];
+        (
+          location["artifactLocation"]["uri"]
+            .as_str()
+            .map(String::from),
+          location["region"]["startLine"].as_u64(),
+          location["region"]["startColumn"].as_u64(),
+        )
+      };
+      key(a).cmp(&key(b))
+    });
+    #[allow(clippy::print_stdout)]
+    {
+      println!(
+        "{}",
+        serde_json::to_string_pretty(&self.to_sarif()).unwrap()
+      );
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::tools::lint::import_extensions::test_diagnostic;
+
+  #[test]
+  fn test_sarif_output() {
+    let diagnostic = test_diagnostic(
+      &ModuleSpecifier::parse("file:///project/src/main.ts").unwrap(),
+      Some("./a.ts"),
+    );
+    let mut reporter = SarifLintReporter {
+      root_url: Some(Url::parse("file:///project/").unwrap()),
+      rules: Vec::new(),
+      rule_indexes: HashMap::new(),
+      results: Vec::new(),
+      notifications: Vec::new(),
+    };
+    reporter
+      .visit_diagnostic(LintOrCliDiagnostic::ImportExtensions(&diagnostic));
+    reporter
+      .visit_diagnostic(LintOrCliDiagnostic::ImportExtensions(&diagnostic));
+    let sarif = reporter.to_sarif();
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    assert_eq!(
+      run["tool"]["driver"]["rules"],
+      json!([{ "id": "import-extensions" }])
+    );
+    assert_eq!(
+      run["originalUriBaseIds"],
+      json!({ "%SRCROOT%": { "uri": "file:///project/" } })
+    );
+    assert_eq!(run["results"].as_array().unwrap().len(), 2);
+    assert_eq!(
+      run["results"][0],
+      json!({
+        "ruleId": "import-extensions",
+        "ruleIndex": 0,
+        "level": "error",
+        "message": {
+          "text": "relative import is missing a file extension\n\nHint: change the specifier to './a.ts'",
+        },
+        "locations": [{
+          "physicalLocation": {
+            "artifactLocation": {
+              "uri": "src/main.ts",
+              "uriBaseId": "%SRCROOT%",
+            },
+            "region": {
+              "startLine": 1,
+              "startColumn": 9,
+              "endLine": 1,
+              "endColumn": 12,
+            },
+          },
+        }],
+      })
+    );
+    assert_eq!(run["invocations"][0]["executionSuccessful"], true);
+  }
+}
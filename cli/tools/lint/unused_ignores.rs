@@ -0,0 +1,269 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Fixes for the `ban-unused-ignore` diagnostics, which remove the codes of
+//! `deno-lint-ignore` comments that no longer suppress any diagnostic, along
+//! with the code of the comment that such a diagnostic is about.
+
+use std::collections::BTreeMap;
+
+use deno_ast::SourceRange;
+use deno_ast::SourceRanged;
+use deno_ast::SourceTextInfo;
+use deno_ast::TextChange;
+use deno_lint::diagnostic::LintDiagnostic;
+
+pub const UNUSED_IGNORE_CODE: &str = "ban-unused-ignore";
+pub const UNKNOWN_RULE_CODE: &str = "ban-unknown-rule-code";
+
+const DIRECTIVES: [&str; 2] = ["deno-lint-ignore-file", "deno-lint-ignore"];
+
+/// Gets whether `deno lint --fix` can remove the unused code of the
+/// diagnostic.
+pub fn is_unused_ignore_fixable(d: &LintDiagnostic) -> bool {
+  IgnoreCodeDiagnostic::from_diagnostic(d)
+    .is_some_and(|d| d.kind == IgnoreCodeKind::Unused)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IgnoreCodeKind {
+  /// A `ban-unused-ignore` diagnostic.
+  Unused,
+  /// A `ban-unknown-rule-code` diagnostic.
+  Unknown,
+}
+
+/// A diagnostic deno_lint reports for a code of an ignore comment.
+#[derive(Debug, PartialEq, Eq)]
+pub struct IgnoreCodeDiagnostic<'a> {
+  pub kind: IgnoreCodeKind,
+  pub code: &'a str,
+}
+
+impl<'a> IgnoreCodeDiagnostic<'a> {
+  /// deno_lint only provides the code in the message, like `Ignore for code
+  /// "no-empty" was not used.`, so it's only used when it's one of the codes
+  /// of the comment the diagnostic is reported on.
+  pub fn from_diagnostic(d: &'a LintDiagnostic) -> Option<Self> {
+    let (kind, prefix) = match d.code.as_str() {
+      UNUSED_IGNORE_CODE => (IgnoreCodeKind::Unused, "Ignore for code \""),
+      UNKNOWN_RULE_CODE => {
+        (IgnoreCodeKind::Unknown, "Unknown rule for code \"")
+      }
+      _ => return None,
+    };
+    let (code, _) = d.message.strip_prefix(prefix)?.split_once('"')?;
+    let comment = IgnoreComment::parse(d.range.text_fast(&d.text_info))?;
+    comment.codes.contains(&code).then_some(Self { kind, code })
+  }
+}
+
+/// Gets the changes that remove the unused codes from the ignore comments,
+/// removing the whole comment when none of its codes are used.
+pub fn get_unused_ignore_changes(
+  text_info: &SourceTextInfo,
+  diagnostics: &[LintDiagnostic],
+) -> Vec<TextChange> {
+  // there's a diagnostic for each unused code of a comment
+  let mut unused_codes_by_line = BTreeMap::<usize, Vec<String>>::new();
+  for d in diagnostics {
+    let Some(ignore) = IgnoreCodeDiagnostic::from_diagnostic(d) else {
+      continue;
+    };
+    if ignore.kind == IgnoreCodeKind::Unused {
+      unused_codes_by_line
+        .entry(text_info.line_index(d.range.start))
+        .or_default()
+        .push(ignore.code.to_string());
+    }
+  }
+  get_changes_for_lines(text_info, unused_codes_by_line)
+}
+
+fn get_changes_for_lines(
+  text_info: &SourceTextInfo,
+  unused_codes_by_line: BTreeMap<usize, Vec<String>>,
+) -> Vec<TextChange> {
+  let file_start = text_info.range().start;
+  let text = text_info.text_str();
+  let mut changes = Vec::new();
+  for (line_index, unused_codes) in unused_codes_by_line {
+    let line_range = SourceRange::new(
+      text_info.line_start(line_index),
+      text_info.line_end(line_index),
+    )
+    .as_byte_range(file_start);
+    let line_text = &text[line_range.clone()];
+    let Some(comment_start) = line_text.find("//") else {
+      continue;
+    };
+    let Some(directive) = IgnoreComment::parse(&line_text[comment_start..])
+    else {
+      continue;
+    };
+    let used_codes = directive
+      .codes
+      .iter()
+      .filter(|code| !unused_codes.iter().any(|c| c == *code))
+      .copied()
+      .collect::<Vec<_>>();
+    if used_codes.len() == directive.codes.len() {
+      continue;
+    }
+    let comment_range =
+      line_range.start + comment_start..line_range.start + line_text.len();
+    if !used_codes.is_empty() {
+      changes.push(TextChange {
+        range: comment_range,
+        new_text: directive.to_text(&used_codes),
+      });
+    } else if line_text[..comment_start].trim().is_empty() {
+      // remove the line along with its line break
+      let next_line_start = if line_index + 1 < text_info.lines_count() {
+        text_info
+          .line_start(line_index + 1)
+          .as_byte_index(file_start)
+      } else {
+        text.len()
+      };
+      changes.push(TextChange {
+        range: line_range.start..next_line_start,
+        new_text: String::new(),
+      });
+    } else {
+      // the comment follows some code on the line
+      let code_end = line_text[..comment_start].trim_end().len();
+      changes.push(TextChange {
+        range: line_range.start + code_end..comment_range.end,
+        new_text: String::new(),
+      });
+    }
+  }
+  changes
+}
+
+struct IgnoreComment<'a> {
+  directive: &'static str,
+  codes: Vec<&'a str>,
+  /// The explanation after `--`, which is kept as is.
+  reason: Option<&'a str>,
+}
+
+impl<'a> IgnoreComment<'a> {
+  fn parse(comment: &'a str) -> Option<Self> {
+    let comment = comment.strip_prefix("//")?.trim_start();
+    let directive = DIRECTIVES
+      .into_iter()
+      .find(|directive| comment.starts_with(directive))?;
+    let rest = &comment[directive.len()..];
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+      return None;
+    }
+    let (codes, reason) = match rest.find(" --") {
+      Some(index) => (&rest[..index], Some(rest[index + 1..].trim_end())),
+      None => (rest, None),
+    };
+    Some(Self {
+      directive,
+      codes: codes.split_whitespace().collect(),
+      reason,
+    })
+  }
+
+  fn to_text(&self, codes: &[&str]) -> String {
+    let mut text = format!("// {} {}", self.directive, codes.join(" "));
+    if let Some(reason) = self.reason {
+      text.push(' ');
+      text.push_str(reason);
+    }
+    text
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn fix(text: &str, unused: &[(usize, &str)]) -> String {
+    let text_info = SourceTextInfo::new(text.into());
+    let mut unused_codes_by_line = BTreeMap::<usize, Vec<String>>::new();
+    for (line_index, code) in unused {
+      unused_codes_by_line
+        .entry(*line_index)
+        .or_default()
+        .push(code.to_string());
+    }
+    let changes = get_changes_for_lines(&text_info, unused_codes_by_line);
+    deno_ast::apply_text_changes(text, changes)
+  }
+
+  #[test]
+  fn removes_unused_codes() {
+    assert_eq!(
+      fix(
+        "// deno-lint-ignore no-explicit-any no-empty -- legacy\nlet a;\n",
+        &[(0, "no-empty")],
+      ),
+      "// deno-lint-ignore no-explicit-any -- legacy\nlet a;\n",
+    );
+    assert_eq!(
+      fix(
+        "let a;\n  // deno-lint-ignore no-empty no-debugger\n  debugger;",
+        &[(1, "no-empty"), (1, "no-debugger")],
+      ),
+      "let a;\n  debugger;",
+    );
+    assert_eq!(
+      fix(
+        "// deno-lint-ignore-file no-empty\nlet a; // deno-lint-ignore no-var\n",
+        &[(0, "no-empty"), (1, "no-var")],
+      ),
+      "let a;\n",
+    );
+  }
+
+  #[test]
+  fn ignore_code_diagnostic() {
+    let text_info = SourceTextInfo::new(
+      "// deno-lint-ignore no-empty my-plugin/a\nlet a;\n".into(),
+    );
+    let diagnostic = |code: &str, message: &str| LintDiagnostic {
+      specifier: deno_ast::ModuleSpecifier::parse("file:///a.ts").unwrap(),
+      range: SourceRange::new(text_info.line_start(0), text_info.line_end(0)),
+      text_info: text_info.clone(),
+      message: message.to_string(),
+      code: code.to_string(),
+      hint: None,
+      fixes: Vec::new(),
+    };
+    let unused = diagnostic(
+      UNUSED_IGNORE_CODE,
+      "Ignore for code \"no-empty\" was not used.",
+    );
+    assert_eq!(
+      IgnoreCodeDiagnostic::from_diagnostic(&unused),
+      Some(IgnoreCodeDiagnostic {
+        kind: IgnoreCodeKind::Unused,
+        code: "no-empty",
+      })
+    );
+    assert!(is_unused_ignore_fixable(&unused));
+    let unknown =
+      diagnostic(UNKNOWN_RULE_CODE, "Unknown rule for code \"my-plugin/a\"");
+    assert_eq!(
+      IgnoreCodeDiagnostic::from_diagnostic(&unknown),
+      Some(IgnoreCodeDiagnostic {
+        kind: IgnoreCodeKind::Unknown,
+        code: "my-plugin/a",
+      })
+    );
+    assert!(!is_unused_ignore_fixable(&unknown));
+    // the code isn't one of the codes of the comment
+    let other = diagnostic(
+      UNUSED_IGNORE_CODE,
+      "Ignore for code \"no-var\" was not used.",
+    );
+    assert_eq!(IgnoreCodeDiagnostic::from_diagnostic(&other), None);
+    let other = diagnostic("no-var", "Ignore for code \"no-empty\"");
+    assert_eq!(IgnoreCodeDiagnostic::from_diagnostic(&other), None);
+  }
+}
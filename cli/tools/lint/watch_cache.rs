@@ -0,0 +1,246 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Cache used by `deno lint --watch` to only lint the files that changed
+//! since the previous run and to report how the diagnostics changed.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_ast::diagnostics::Diagnostic;
+use deno_lint::diagnostic::LintDiagnostic;
+
+use super::ImportExtensionsDiagnostic;
+use super::LintOrCliDiagnostic;
+use super::RuleDiagnostic;
+use crate::cache::FastInsecureHasher;
+
+/// The diagnostics of a file, which are reported again in the next runs
+/// while the file doesn't change.
+#[derive(Clone, Default)]
+pub struct FileDiagnostics {
+  pub lint: Vec<LintDiagnostic>,
+  pub import_extensions: Vec<ImportExtensionsDiagnostic>,
+  pub rule: Vec<RuleDiagnostic>,
+}
+
+impl FileDiagnostics {
+  pub fn iter(&self) -> impl Iterator<Item = LintOrCliDiagnostic<'_>> {
+    self
+      .lint
+      .iter()
+      .map(LintOrCliDiagnostic::Lint)
+      .chain(
+        self
+          .import_extensions
+          .iter()
+          .map(LintOrCliDiagnostic::ImportExtensions),
+      )
+      .chain(self.rule.iter().map(LintOrCliDiagnostic::Rule))
+  }
+}
+
+struct CachedFile {
+  source_hash: u64,
+  diagnostics: FileDiagnostics,
+  /// The diagnostics formatted as `message (code)`. Their positions are left
+  /// out so that editing the start of a file doesn't make all the
+  /// diagnostics after it look new.
+  summaries: Vec<String>,
+}
+
+/// The diagnostics that were added and resolved in the files that changed
+/// since the previous run.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct LintWatchDelta {
+  pub added: Vec<(PathBuf, String)>,
+  pub resolved: Vec<(PathBuf, String)>,
+}
+
+#[derive(Default)]
+pub struct LintWatchCache {
+  state_hash: Option<u64>,
+  files: HashMap<PathBuf, CachedFile>,
+  is_incremental_run: bool,
+  delta: LintWatchDelta,
+}
+
+impl LintWatchCache {
+  /// Starts a run that lints the provided paths. The cached files are
+  /// discarded when the state of the configuration changed.
+  pub fn start_run(&mut self, state_hash: u64, paths: &[PathBuf]) {
+    if self.state_hash != Some(state_hash) {
+      self.state_hash = Some(state_hash);
+      self.files.clear();
+    }
+    let paths = paths.iter().collect::<HashSet<_>>();
+    self.files.retain(|path, _| paths.contains(path));
+    self.is_incremental_run = !self.files.is_empty();
+    self.delta = Default::default();
+  }
+
+  /// Gets the diagnostics of the file when it's the same as when it was
+  /// last linted, so that they can be reported without linting it again.
+  pub fn get_file_diagnostics(
+    &self,
+    file_path: &Path,
+    file_text: &str,
+  ) -> Option<FileDiagnostics> {
+    self
+      .files
+      .get(file_path)
+      .filter(|file| file.source_hash == FastInsecureHasher::hash(file_text))
+      .map(|file| file.diagnostics.clone())
+  }
+
+  pub fn update_file(
+    &mut self,
+    file_path: &Path,
+    file_text: &str,
+    diagnostics: FileDiagnostics,
+  ) {
+    let mut summaries = diagnostics
+      .iter()
+      .map(|d| format!("{} ({})", d.message(), d.code()))
+      .collect::<Vec<_>>();
+    summaries.sort();
+    let previous = self.files.insert(
+      file_path.to_path_buf(),
+      CachedFile {
+        source_hash: FastInsecureHasher::hash(file_text),
+        diagnostics,
+        summaries,
+      },
+    );
+    if !self.is_incremental_run {
+      return;
+    }
+    let previous = previous.map(|f| f.summaries).unwrap_or_default();
+    let current = &self.files[file_path].summaries;
+    let (added, resolved) = diff_sorted(current, &previous);
+    self.delta.added.extend(
+      added
+        .into_iter()
+        .map(|d| (file_path.to_path_buf(), d.to_string())),
+    );
+    self.delta.resolved.extend(
+      resolved
+        .into_iter()
+        .map(|d| (file_path.to_path_buf(), d.to_string())),
+    );
+  }
+
+  /// Removes the file so that it's linted again in the next run, which is
+  /// done when linting it failed.
+  pub fn remove_file(&mut self, file_path: &Path) {
+    self.files.remove(file_path);
+  }
+
+  /// Takes the changes of the diagnostics in this run, which is `None` for
+  /// the first run.
+  pub fn take_delta(&mut self) -> Option<LintWatchDelta> {
+    if !self.is_incremental_run {
+      return None;
+    }
+    let mut delta = std::mem::take(&mut self.delta);
+    delta.added.sort();
+    delta.resolved.sort();
+    Some(delta)
+  }
+}
+
+/// Gets the items only in `a` and those only in `b` for two sorted lists,
+/// taking the number of occurrences of each item into account.
+fn diff_sorted<'a>(
+  a: &'a [String],
+  b: &'a [String],
+) -> (Vec<&'a str>, Vec<&'a str>) {
+  let mut only_a = Vec::new();
+  let mut only_b = Vec::new();
+  let (mut i, mut j) = (0, 0);
+  while i < a.len() && j < b.len() {
+    match a[i].cmp(&b[j]) {
+      std::cmp::Ordering::Less => {
+        only_a.push(a[i].as_str());
+        i += 1;
+      }
+      std::cmp::Ordering::Greater => {
+        only_b.push(b[j].as_str());
+        j += 1;
+      }
+      std::cmp::Ordering::Equal => {
+        i += 1;
+        j += 1;
+      }
+    }
+  }
+  only_a.extend(a[i..].iter().map(|s| s.as_str()));
+  only_b.extend(b[j..].iter().map(|s| s.as_str()));
+  (only_a, only_b)
+}
+
+#[cfg(test)]
+mod test {
+  use deno_ast::ModuleSpecifier;
+
+  use super::*;
+  use crate::tools::lint::import_extensions::test_diagnostic;
+
+  #[test]
+  fn diff_sorted_counts_occurrences() {
+    let a = vec!["a".to_string(), "a".to_string(), "c".to_string()];
+    let b = vec!["a".to_string(), "b".to_string()];
+    assert_eq!(diff_sorted(&a, &b), (vec!["a", "c"], vec!["b"]));
+  }
+
+  #[test]
+  fn delta_of_changed_files() {
+    let diagnostic = test_diagnostic(
+      &ModuleSpecifier::parse("file:///project/main.ts").unwrap(),
+      None,
+    );
+    let diagnostics = FileDiagnostics {
+      import_extensions: vec![diagnostic],
+      ..Default::default()
+    };
+    let main = PathBuf::from("/project/main.ts");
+    let other = PathBuf::from("/project/other.ts");
+    let paths = vec![main.clone(), other.clone()];
+
+    let mut cache = LintWatchCache::default();
+    cache.start_run(1, &paths);
+    cache.update_file(&main, "import \"./a\";", diagnostics.clone());
+    cache.update_file(&other, "", Default::default());
+    assert_eq!(cache.take_delta(), None);
+    // the diagnostics of an unchanged file are reported again
+    let cached = cache
+      .get_file_diagnostics(&main, "import \"./a\";")
+      .unwrap();
+    assert_eq!(cached.iter().count(), 1);
+    assert!(cache
+      .get_file_diagnostics(&main, "import \"./a.ts\";")
+      .is_none());
+
+    cache.start_run(1, &paths);
+    cache.update_file(&main, "import \"./a.ts\";", Default::default());
+    cache.update_file(&other, "import \"./a\";", diagnostics);
+    let message = "relative import is missing a file extension \
+      (import-extensions)"
+      .to_string();
+    assert_eq!(
+      cache.take_delta(),
+      Some(LintWatchDelta {
+        added: vec![(other.clone(), message.clone())],
+        resolved: vec![(main.clone(), message)],
+      })
+    );
+
+    // changing the configuration lints all the files again
+    cache.start_run(2, &paths);
+    assert!(cache
+      .get_file_diagnostics(&main, "import \"./a.ts\";")
+      .is_none());
+    assert_eq!(cache.take_delta(), None);
+  }
+}
@@ -1,6 +1,7 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use std::borrow::Cow;
+use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -21,9 +22,12 @@ use indexmap::IndexMap;
 use jsonc_parser::ast::ObjectProp;
 use jsonc_parser::ast::Value;
 
+use crate::args::write_lockfile;
 use crate::args::AddFlags;
 use crate::args::CacheSetting;
+use crate::args::CliOptions;
 use crate::args::Flags;
+use crate::args::RemoveFlags;
 use crate::factory::CliFactory;
 use crate::file_fetcher::FileFetcher;
 use crate::jsr::JsrFetchResolver;
@@ -116,6 +120,22 @@ impl DenoOrPackageJson {
     matches!(self, Self::Npm(..))
   }
 
+  /// Get the preferred config file to operate on given the options.
+  fn from_options(options: &CliOptions) -> Result<Option<Self>, AnyError> {
+    match (options.maybe_config_file(), options.maybe_package_json()) {
+      // when both are present, for now,
+      // default to deno.json
+      (Some(deno), Some(_) | None) => Ok(Some(DenoOrPackageJson::Deno(
+        deno.clone(),
+        DenoConfigFormat::from_specifier(&deno.specifier)?,
+      ))),
+      (None, Some(package_json)) if options.enable_future_features() => {
+        Ok(Some(DenoOrPackageJson::Npm(package_json.clone(), None)))
+      }
+      (None, Some(_) | None) => Ok(None),
+    }
+  }
+
   /// Get the preferred config file to operate on
   /// given the flags. If no config file is present,
   /// creates a `deno.json` file - in this case
@@ -125,20 +145,9 @@ impl DenoOrPackageJson {
     let factory = CliFactory::from_flags(flags.clone())?;
     let options = factory.cli_options().clone();
 
-    match (options.maybe_config_file(), options.maybe_package_json()) {
-      // when both are present, for now,
-      // default to deno.json
-      (Some(deno), Some(_) | None) => Ok((
-        DenoOrPackageJson::Deno(
-          deno.clone(),
-          DenoConfigFormat::from_specifier(&deno.specifier)?,
-        ),
-        factory,
-      )),
-      (None, Some(package_json)) if options.enable_future_features() => {
-        Ok((DenoOrPackageJson::Npm(package_json.clone(), None), factory))
-      }
-      (None, Some(_) | None) => {
+    match Self::from_options(&options)? {
+      Some(config_file) => Ok((config_file, factory)),
+      None => {
         std::fs::write(options.initial_cwd().join("deno.json"), "{}\n")
           .context("Failed to create deno.json file")?;
         log::info!("Created deno.json configuration file.");
@@ -166,7 +175,12 @@ fn package_json_dependency_entry(
   selected: SelectedPackage,
 ) -> (String, String) {
   if let Some(npm_package) = selected.package_name.strip_prefix("npm:") {
-    (npm_package.into(), selected.version_req)
+    if selected.import_name == npm_package {
+      (npm_package.into(), selected.version_req)
+    } else {
+      let version_req = format!("npm:{npm_package}@{}", selected.version_req);
+      (selected.import_name, version_req)
+    }
   } else if let Some(jsr_package) = selected.package_name.strip_prefix("jsr:") {
     let jsr_package = jsr_package.strip_prefix('@').unwrap_or(jsr_package);
     let scope_replaced = jsr_package.replace('/', "__");
@@ -194,6 +208,7 @@ pub async fn add(flags: Flags, add_flags: AddFlags) -> Result<(), AnyError> {
   let mut package_reqs = Vec::with_capacity(add_flags.packages.len());
 
   for package_name in add_flags.packages.iter() {
+    let (alias, package_name) = split_alias(package_name);
     let req = if package_name.starts_with("npm:") {
       let pkg_req = NpmPackageReqReference::from_str(&format!(
         "npm:{}",
@@ -214,7 +229,7 @@ pub async fn add(flags: Flags, add_flags: AddFlags) -> Result<(), AnyError> {
       AddPackageReq::Jsr(pkg_req)
     };
 
-    package_reqs.push(req);
+    package_reqs.push((alias.map(ToOwned::to_owned), req));
   }
 
   let deps_http_cache = cli_factory.global_http_cache()?;
@@ -232,11 +247,12 @@ pub async fn add(flags: Flags, add_flags: AddFlags) -> Result<(), AnyError> {
 
   let package_futures = package_reqs
     .into_iter()
-    .map(move |package_req| {
+    .map(move |(alias, package_req)| {
       find_package_and_select_version_for_req(
         jsr_resolver.clone(),
         npm_resolver.clone(),
         package_req,
+        alias,
       )
       .boxed_local()
     })
@@ -322,18 +338,163 @@ pub async fn add(flags: Flags, add_flags: AddFlags) -> Result<(), AnyError> {
     .await
     .context("Failed to update configuration file")?;
 
-  // TODO(bartlomieju): we should now cache the imports from the deno.json.
-
   // make a new CliFactory to pick up the updated config file
   let cli_factory = CliFactory::from_flags(flags)?;
-  // cache deps
-  if cli_factory.cli_options().enable_future_features() {
-    crate::module_loader::load_top_level_deps(&cli_factory).await?;
+  // cache deps, which also updates the lockfile with the added packages
+  if cli_factory.cli_options().enable_future_features()
+    || cli_factory.maybe_lockfile().is_some()
+  {
+    if let Err(err) =
+      crate::module_loader::load_top_level_deps(&cli_factory).await
+    {
+      // don't leave the config file referencing packages
+      // that aren't in the lockfile
+      tokio::fs::write(&config_file_path, config_file_contents)
+        .await
+        .context("Failed to restore configuration file")?;
+      return Err(err.context(format!(
+        "Failed to cache the added dependencies. The {} file was left unchanged.",
+        config_file.file_name()
+      )));
+    }
   }
 
   Ok(())
 }
 
+pub async fn remove(
+  flags: Flags,
+  remove_flags: RemoveFlags,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags)?;
+  let Some(config_file) =
+    DenoOrPackageJson::from_options(factory.cli_options())?
+  else {
+    bail!("No deno.json or package.json found to remove dependencies from.");
+  };
+
+  let config_specifier = config_file.specifier();
+  if config_specifier.scheme() != "file" {
+    bail!("Can't remove dependencies from a remote configuration file");
+  }
+  let config_file_path = config_specifier.to_file_path().unwrap();
+
+  let mut existing_imports = config_file.existing_imports()?;
+  let mut removed_imports = Vec::new();
+  for package in &remove_flags.packages {
+    let removed_count = removed_imports.len();
+    existing_imports.retain(|name, value| {
+      let is_match = name == package || import_value_matches(value, package);
+      if is_match {
+        removed_imports.push((name.clone(), value.clone()));
+      }
+      !is_match
+    });
+    if removed_imports.len() == removed_count {
+      log::warn!(
+        "{} {} was not found in {}.",
+        crate::colors::yellow("Warning"),
+        package,
+        config_file.file_name()
+      );
+    }
+  }
+  if removed_imports.is_empty() {
+    return Ok(());
+  }
+
+  let config_file_contents =
+    tokio::fs::read_to_string(&config_file_path).await?;
+  let ast = jsonc_parser::parse_to_ast(
+    &config_file_contents,
+    &Default::default(),
+    &Default::default(),
+  )?;
+  let obj = match ast.value {
+    Some(Value::Object(obj)) => obj,
+    _ => bail!("Failed updating config file due to no object."),
+  };
+  let new_text = update_config_file_content(
+    obj,
+    &config_file_contents,
+    generate_imports(existing_imports.clone().into_iter().collect()),
+    config_file.fmt_options(),
+    config_file.imports_key(),
+    config_file.file_name(),
+  );
+  tokio::fs::write(&config_file_path, new_text)
+    .await
+    .context("Failed to update configuration file")?;
+
+  // remove the specifiers that are no longer referenced from the lockfile
+  if let Some(lockfile) = factory.maybe_lockfile() {
+    let remaining_keys = existing_imports
+      .iter()
+      .map(|(name, value)| lockfile_specifier_key(name, value))
+      .collect::<HashSet<_>>();
+    let mut lockfile = lockfile.lock();
+    for (name, value) in &removed_imports {
+      let key = lockfile_specifier_key(name, value);
+      if !remaining_keys.contains(&key)
+        && lockfile.content.packages.specifiers.remove(&key).is_some()
+      {
+        lockfile.has_content_changed = true;
+      }
+    }
+    write_lockfile(&lockfile).context("Failed writing lockfile.")?;
+  }
+
+  for (name, value) in removed_imports {
+    log::info!("Remove {} - {}", crate::colors::green(name), value);
+  }
+
+  Ok(())
+}
+
+/// Splits an `alias@jsr:package` or `alias@npm:package` argument into
+/// the alias and the package.
+fn split_alias(package: &str) -> (Option<&str>, &str) {
+  for prefix in ["@jsr:", "@npm:"] {
+    if let Some(index) = package.find(prefix) {
+      if index > 0 {
+        return (Some(&package[..index]), &package[index + 1..]);
+      }
+    }
+  }
+  (None, package)
+}
+
+/// Gets if the value of an import entry refers to the provided package
+/// (ex. `@std/path`, `jsr:@std/path` or `npm:chalk`).
+fn import_value_matches(value: &str, package: &str) -> bool {
+  let (name, prefix) =
+    if let Ok(req_ref) = JsrPackageReqReference::from_str(value) {
+      (req_ref.into_inner().req.name, "jsr:")
+    } else if let Ok(req_ref) = NpmPackageReqReference::from_str(value) {
+      (req_ref.into_inner().req.name, "npm:")
+    } else {
+      return false;
+    };
+  match package.strip_prefix(prefix) {
+    Some(package) => name == package,
+    None => {
+      !package.starts_with("jsr:")
+        && !package.starts_with("npm:")
+        && name == package
+    }
+  }
+}
+
+/// Gets the key of the lockfile's "specifiers" section for an import entry.
+fn lockfile_specifier_key(name: &str, value: &str) -> String {
+  if value.starts_with("jsr:") || value.starts_with("npm:") {
+    value.to_string()
+  } else {
+    // package.json dependency
+    format!("npm:{name}@{value}")
+  }
+}
+
 struct SelectedPackage {
   import_name: String,
   package_name: String,
@@ -349,6 +510,7 @@ async fn find_package_and_select_version_for_req(
   jsr_resolver: Arc<JsrFetchResolver>,
   npm_resolver: Arc<NpmFetchResolver>,
   add_package_req: AddPackageReq,
+  alias: Option<String>,
 ) -> Result<PackageAndVersion, AnyError> {
   match add_package_req {
     AddPackageReq::Jsr(pkg_ref) => {
@@ -363,7 +525,7 @@ async fn find_package_and_select_version_for_req(
         '^'
       };
       Ok(PackageAndVersion::Selected(SelectedPackage {
-        import_name: req.name.to_string(),
+        import_name: alias.unwrap_or_else(|| req.name.to_string()),
         package_name: jsr_prefixed_name,
         version_req: format!("{}{}", range_symbol, &nv.version),
       }))
@@ -380,7 +542,7 @@ async fn find_package_and_select_version_for_req(
         '^'
       };
       Ok(PackageAndVersion::Selected(SelectedPackage {
-        import_name: req.name.to_string(),
+        import_name: alias.unwrap_or_else(|| req.name.to_string()),
         package_name: npm_prefixed_name,
         version_req: format!("{}{}", range_symbol, &nv.version),
       }))
@@ -1,12 +1,18 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use std::io::Read;
+use std::sync::Arc;
 
+use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
+use deno_core::resolve_url_or_path;
+use deno_core::ModuleSpecifier;
 use deno_runtime::permissions::Permissions;
 use deno_runtime::permissions::PermissionsContainer;
 use deno_runtime::WorkerExecutionMode;
 
+use crate::args::CliOptions;
+use crate::args::DenoSubcommand;
 use crate::args::EvalFlags;
 use crate::args::Flags;
 use crate::args::WatchFlagsWithPaths;
@@ -17,6 +23,10 @@ use crate::util;
 use crate::util::file_watcher::WatcherRestartMode;
 
 pub mod hmr;
+mod script_metadata;
+
+use self::script_metadata::read_script_metadata;
+use self::script_metadata::ScriptMetadata;
 
 pub async fn run_script(
   mode: WorkerExecutionMode,
@@ -34,13 +44,18 @@ To grant permissions, set them before the script argument. For example:
     );
   }
 
+  let maybe_script_metadata = resolve_script_metadata(&flags)?;
+
   if let Some(watch_flags) = watch {
-    return run_with_watch(mode, flags, watch_flags).await;
+    return run_with_watch(mode, flags, watch_flags, maybe_script_metadata)
+      .await;
   }
 
   // TODO(bartlomieju): actually I think it will also fail if there's an import
   // map specified and bare specifier is used on the command line
-  let factory = CliFactory::from_flags(flags)?;
+  let factory = CliFactory::from_cli_options(Arc::new(
+    cli_options_with_script_metadata(flags, maybe_script_metadata.as_ref())?,
+  ));
   let deno_dir = factory.deno_dir()?;
   let http_client = factory.http_client();
   let cli_options = factory.cli_options();
@@ -105,13 +120,63 @@ pub async fn run_from_stdin(flags: Flags) -> Result<i32, AnyError> {
   Ok(exit_code)
 }
 
+/// Reads the metadata declared at the top of the local script being run and
+/// checks it against the flags.
+fn resolve_script_metadata(
+  flags: &Flags,
+) -> Result<Option<(ModuleSpecifier, ScriptMetadata)>, AnyError> {
+  let DenoSubcommand::Run(run_flags) = &flags.subcommand else {
+    return Ok(None);
+  };
+  let cwd = std::env::current_dir().context("Unable to get CWD")?;
+  let script_specifier = resolve_url_or_path(&run_flags.script, &cwd)?;
+  let Some(metadata) = read_script_metadata(&script_specifier)? else {
+    return Ok(None);
+  };
+  script_metadata::check_script_metadata(&metadata, flags, &run_flags.script)?;
+  Ok(Some((script_specifier, metadata)))
+}
+
+/// Creates the options from the flags, using the import map declared by the
+/// script's metadata when the script is not part of a project that has its
+/// own config file or import map.
+fn cli_options_with_script_metadata(
+  flags: Flags,
+  maybe_script_metadata: Option<&(ModuleSpecifier, ScriptMetadata)>,
+) -> Result<CliOptions, AnyError> {
+  let mut cli_options = CliOptions::from_flags(flags)?;
+  let Some((script_specifier, metadata)) = maybe_script_metadata else {
+    return Ok(cli_options);
+  };
+  let Some(import_map) = &metadata.maybe_import_map else {
+    return Ok(cli_options);
+  };
+  if cli_options.maybe_config_file().is_some()
+    || cli_options
+      .resolve_specified_import_map_specifier()?
+      .is_some()
+  {
+    log::warn!(
+      "{} Ignoring the imports in the metadata of '{}' because a config file or import map is used.",
+      crate::colors::yellow("Warning"),
+      script_specifier,
+    );
+  } else {
+    cli_options
+      .set_script_import_map(script_specifier.clone(), import_map.clone());
+  }
+  Ok(cli_options)
+}
+
 // TODO(bartlomieju): this function is not handling `exit_code` set by the runtime
 // code properly.
 async fn run_with_watch(
   mode: WorkerExecutionMode,
   flags: Flags,
   watch_flags: WatchFlagsWithPaths,
+  maybe_script_metadata: Option<(ModuleSpecifier, ScriptMetadata)>,
 ) -> Result<i32, AnyError> {
+  let maybe_script_metadata = Arc::new(maybe_script_metadata);
   util::file_watcher::watch_recv(
     flags,
     util::file_watcher::PrintConfig::new_with_banner(
@@ -121,9 +186,17 @@ async fn run_with_watch(
     ),
     WatcherRestartMode::Automatic,
     move |flags, watcher_communicator, _changed_paths| {
+      let maybe_script_metadata = maybe_script_metadata.clone();
       Ok(async move {
+        let cli_options = cli_options_with_script_metadata(
+          flags,
+          maybe_script_metadata.as_ref().as_ref(),
+        )?;
         let factory = CliFactoryBuilder::new()
-          .build_from_flags_for_watcher(flags, watcher_communicator.clone())?;
+          .build_from_cli_options_for_watcher(
+            Arc::new(cli_options),
+            watcher_communicator.clone(),
+          );
         let cli_options = factory.cli_options();
         let main_module = cli_options.resolve_main_module()?;
 
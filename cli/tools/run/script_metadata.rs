@@ -0,0 +1,284 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Metadata declared in a comment block at the top of a script, which allows
+//! sharing and running single file scripts without a config file:
+//!
+//! ```ts
+//! #!/usr/bin/env -S deno run
+//! /* deno-script
+//! {
+//!   "imports": { "@std/fmt/": "jsr:/@std/fmt@^0.224/" },
+//!   "permissions": ["--allow-net=example.com"],
+//!   "deno": ">=1.43"
+//! }
+//! */
+//! ```
+//!
+//! The declared permissions are never granted by the metadata itself, since
+//! that would let a script bypass the permission model. They're only shown
+//! to the user when no permissions were passed on the command line.
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde::Deserialize;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::ModuleSpecifier;
+use deno_semver::Version;
+use deno_semver::VersionReq;
+
+use crate::args::Flags;
+use crate::colors;
+use crate::version;
+
+const START_MARKER: &str = "/* deno-script";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ScriptMetadataJson {
+  imports: Option<serde_json::Value>,
+  scopes: Option<serde_json::Value>,
+  permissions: Vec<String>,
+  deno: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ScriptMetadata {
+  /// Import map made from the "imports" and "scopes" of the metadata.
+  pub maybe_import_map: Option<serde_json::Value>,
+  /// Permission flags the script needs, which are never granted from the
+  /// metadata and only suggested to the user.
+  pub permissions: Vec<String>,
+  /// Versions of Deno the script supports.
+  pub maybe_deno_version_req: Option<VersionReq>,
+}
+
+/// Extracts the metadata block of the script, which must be the first thing
+/// in the file after an optional shebang.
+pub fn extract_script_metadata(
+  text: &str,
+) -> Result<Option<ScriptMetadata>, AnyError> {
+  let mut text = text.strip_prefix('\u{FEFF}').unwrap_or(text);
+  if text.starts_with("#!") {
+    text = text.split_once('\n').map(|(_, rest)| rest).unwrap_or("");
+  }
+  let Some(rest) = text.trim_start().strip_prefix(START_MARKER) else {
+    return Ok(None);
+  };
+  let Some(rest) = rest
+    .strip_prefix('\n')
+    .or_else(|| rest.strip_prefix("\r\n"))
+  else {
+    return Ok(None);
+  };
+  let Some((json_text, _)) = rest.split_once("*/") else {
+    bail!("Missing the closing \"*/\" of the script metadata.");
+  };
+  let json: ScriptMetadataJson = serde_json::from_str(json_text)
+    .context("Failed parsing the script metadata.")?;
+
+  for permission in &json.permissions {
+    if !is_permission_flag(permission) {
+      bail!(
+        "Invalid permission in the script metadata: {}\n  Only --allow-* and --deny-* flags are supported.",
+        permission
+      );
+    }
+  }
+  let maybe_deno_version_req = match &json.deno {
+    Some(deno) => {
+      Some(VersionReq::parse_from_npm(deno).with_context(|| {
+        format!("Invalid \"deno\" version requirement: {}", deno)
+      })?)
+    }
+    None => None,
+  };
+  let maybe_import_map = if json.imports.is_some() || json.scopes.is_some() {
+    let mut import_map = json!({});
+    if let Some(imports) = json.imports {
+      import_map["imports"] = imports;
+    }
+    if let Some(scopes) = json.scopes {
+      import_map["scopes"] = scopes;
+    }
+    Some(import_map)
+  } else {
+    None
+  };
+  Ok(Some(ScriptMetadata {
+    maybe_import_map,
+    permissions: json.permissions,
+    maybe_deno_version_req,
+  }))
+}
+
+fn is_permission_flag(flag: &str) -> bool {
+  flag == "-A" || flag.starts_with("--allow-") || flag.starts_with("--deny-")
+}
+
+/// Reads the metadata of the script being run when it's a local file.
+pub fn read_script_metadata(
+  script_specifier: &ModuleSpecifier,
+) -> Result<Option<ScriptMetadata>, AnyError> {
+  if script_specifier.scheme() != "file" {
+    return Ok(None);
+  }
+  let Ok(path) = script_specifier.to_file_path() else {
+    return Ok(None);
+  };
+  let Ok(text) = std::fs::read_to_string(&path) else {
+    // let the module loader surface the error
+    return Ok(None);
+  };
+  extract_script_metadata(&text)
+    .with_context(|| format!("Failed reading metadata of '{}'", path.display()))
+}
+
+/// Ensures the running version of Deno satisfies the requirement of the
+/// script and warns about the permissions it declares when none were
+/// provided on the command line. A script can't grant itself permissions.
+pub fn check_script_metadata(
+  metadata: &ScriptMetadata,
+  flags: &Flags,
+  script: &str,
+) -> Result<(), AnyError> {
+  if let Some(version_req) = &metadata.maybe_deno_version_req {
+    if let Ok(deno_version) = Version::parse_from_npm(version::deno()) {
+      if !version_req.matches(&deno_version) {
+        bail!(
+          "The script requires Deno {}, but this is Deno {}. Run `deno upgrade` to update.",
+          version_req,
+          deno_version
+        );
+      }
+    }
+  }
+
+  if let Some(message) = permissions_message(metadata, flags, script) {
+    log::warn!("{}", colors::yellow(message));
+  }
+  Ok(())
+}
+
+fn permissions_message(
+  metadata: &ScriptMetadata,
+  flags: &Flags,
+  script: &str,
+) -> Option<String> {
+  if metadata.permissions.is_empty() || flags.has_permission() {
+    return None;
+  }
+  let permissions = metadata.permissions.join(" ");
+  Some(format!(
+    r#"The script declares permissions that were not granted: {permissions}
+To grant them, set them before the script argument. For example:
+    deno run {permissions} {script}"#
+  ))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn extracts_metadata() {
+    let metadata = extract_script_metadata(concat!(
+      "#!/usr/bin/env -S deno run\n",
+      "/* deno-script\n",
+      "{\n",
+      "  \"imports\": { \"@std/fmt/\": \"jsr:/@std/fmt@^0.224/\" },\n",
+      "  \"permissions\": [\"--allow-net=example.com\"],\n",
+      "  \"deno\": \">=1.40\"\n",
+      "}\n",
+      "*/\n",
+      "console.log(1);\n",
+    ))
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+      metadata.maybe_import_map,
+      Some(json!({
+        "imports": { "@std/fmt/": "jsr:/@std/fmt@^0.224/" },
+      }))
+    );
+    assert_eq!(metadata.permissions, vec!["--allow-net=example.com"]);
+    assert_eq!(
+      metadata.maybe_deno_version_req.unwrap().to_string(),
+      ">=1.40"
+    );
+  }
+
+  #[test]
+  fn ignores_scripts_without_metadata() {
+    assert!(extract_script_metadata("console.log(1);")
+      .unwrap()
+      .is_none());
+    assert!(extract_script_metadata("// deno-script\nconsole.log(1);")
+      .unwrap()
+      .is_none());
+    assert!(
+      extract_script_metadata("console.log(1);\n/* deno-script\n{}\n*/")
+        .unwrap()
+        .is_none()
+    );
+  }
+
+  #[test]
+  fn errors_on_invalid_metadata() {
+    let err = extract_script_metadata("/* deno-script\n{}\n").unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Missing the closing \"*/\" of the script metadata."
+    );
+    let err = extract_script_metadata(
+      "/* deno-script\n{ \"permissions\": [\"--reload\"] }\n*/",
+    )
+    .unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Invalid permission in the script metadata: --reload\n  Only --allow-* and --deny-* flags are supported."
+    );
+    assert!(
+      extract_script_metadata("/* deno-script\n{ \"unknown\": 1 }\n*/")
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn suggests_permissions_when_none_provided() {
+    let metadata = extract_script_metadata(
+      "/* deno-script\n{ \"permissions\": [\"--allow-read\", \"--allow-env=HOME\"] }\n*/",
+    )
+    .unwrap()
+    .unwrap();
+    let mut flags = Flags::default();
+    check_script_metadata(&metadata, &flags, "main.ts").unwrap();
+    assert_eq!(flags.permissions.allow_read, None);
+    assert_eq!(flags.permissions.allow_env, None);
+    assert_eq!(
+      permissions_message(&metadata, &flags, "main.ts").unwrap(),
+      concat!(
+        "The script declares permissions that were not granted: --allow-read --allow-env=HOME\n",
+        "To grant them, set them before the script argument. For example:\n",
+        "    deno run --allow-read --allow-env=HOME main.ts",
+      )
+    );
+
+    flags.permissions.allow_net = Some(vec![]);
+    assert_eq!(permissions_message(&metadata, &flags, "main.ts"), None);
+  }
+
+  #[test]
+  fn errors_on_unsatisfied_deno_version() {
+    let metadata =
+      extract_script_metadata("/* deno-script\n{ \"deno\": \"<1.0.0\" }\n*/")
+        .unwrap()
+        .unwrap();
+    let err = check_script_metadata(&metadata, &Flags::default(), "old.ts")
+      .unwrap_err();
+    assert!(err
+      .to_string()
+      .starts_with("The script requires Deno <1.0.0"));
+  }
+}
@@ -34,7 +34,7 @@ pub async fn execute_script(
 ) -> Result<i32, AnyError> {
   let factory = CliFactory::from_flags(flags)?;
   let cli_options = factory.cli_options();
-  let tasks_config = cli_options.resolve_tasks_config()?;
+  let (tasks_config, tasks_options) = cli_options.resolve_tasks_config()?;
   let maybe_package_json = cli_options.maybe_package_json();
   let package_json_scripts = maybe_package_json
     .as_ref()
@@ -54,7 +54,7 @@ pub async fn execute_script(
   };
   let npm_resolver = factory.npm_resolver().await?;
   let node_resolver = factory.node_resolver().await?;
-  let env_vars = real_env_vars();
+  let mut env_vars = real_env_vars();
 
   if let Some(
     deno_config::Task::Definition(script)
@@ -69,10 +69,23 @@ pub async fn execute_script(
     } else {
       bail!("Only local configuration files are supported")
     };
+    let task_options = tasks_options.get(task_name);
     let cwd = match task_flags.cwd {
       Some(path) => canonicalize_path(&PathBuf::from(path))?,
-      None => config_file_path.parent().unwrap().to_owned(),
+      None => match task_options.and_then(|o| o.cwd.as_ref()) {
+        Some(path) => canonicalize_path(path).with_context(|| {
+          format!(
+            "Failed resolving the \"cwd\" of task '{}': {}",
+            task_name,
+            path.display()
+          )
+        })?,
+        None => config_file_path.parent().unwrap().to_owned(),
+      },
     };
+    if let Some(task_options) = task_options {
+      apply_task_env_vars(&mut env_vars, &task_options.env);
+    }
 
     let custom_commands =
       resolve_custom_commands(npm_resolver.as_ref(), node_resolver)?;
@@ -247,6 +260,20 @@ fn prepend_to_path(env_vars: &mut HashMap<String, String>, value: String) {
   }
 }
 
+fn apply_task_env_vars(
+  env_vars: &mut HashMap<String, String>,
+  task_env: &IndexMap<String, String>,
+) {
+  for (key, value) in task_env {
+    let key = if cfg!(windows) {
+      key.to_uppercase()
+    } else {
+      key.clone()
+    };
+    env_vars.insert(key, value.clone());
+  }
+}
+
 fn real_env_vars() -> HashMap<String, String> {
   std::env::vars()
     .map(|(k, v)| {
@@ -559,6 +586,26 @@ mod test {
     );
   }
 
+  #[test]
+  fn test_apply_task_env_vars() {
+    let mut env_vars =
+      HashMap::from([("PATH".to_string(), "/bin".to_string())]);
+    apply_task_env_vars(
+      &mut env_vars,
+      &IndexMap::from([
+        ("PORT".to_string(), "8080".to_string()),
+        ("PATH".to_string(), "/usr/bin".to_string()),
+      ]),
+    );
+    assert_eq!(
+      env_vars,
+      HashMap::from([
+        ("PATH".to_string(), "/usr/bin".to_string()),
+        ("PORT".to_string(), "8080".to_string()),
+      ])
+    );
+  }
+
   #[test]
   fn test_resolve_execution_path_from_npx_shim() {
     // example shim on unix
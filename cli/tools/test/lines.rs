@@ -0,0 +1,252 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Selects the tests at the lines of `deno test path:line` arguments and of
+//! the "Run Test" code lens of the language server. A test is at a line
+//! when the call that registered it, like `Deno.test(...)`, spans the line.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use deno_ast::swc::ast;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::LineAndColumnIndex;
+use deno_ast::ParseParams;
+use deno_ast::SourceRangedForSpanned;
+use deno_ast::SourceTextInfo;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::ModuleSpecifier;
+use deno_runtime::permissions::PermissionsContainer;
+
+use super::TestDescriptions;
+use super::TestLocation;
+use crate::file_fetcher::FileFetcher;
+
+/// The lines of a test module to run the tests at, along with the calls of
+/// the module that may register those tests.
+#[derive(Debug, Clone, Default)]
+pub struct TestLines {
+  lines: Vec<u32>,
+  calls: Vec<CallLines>,
+}
+
+#[derive(Debug, Clone)]
+struct CallLines {
+  /// The 0-based start and end of the callee, which contain the location of
+  /// the tests registered by the call.
+  callee: (LineAndColumnIndex, LineAndColumnIndex),
+  /// The 1-based lines the call spans.
+  start_line: u32,
+  end_line: u32,
+}
+
+impl TestLines {
+  /// Collects the calls of the module to look up the tests at the lines.
+  pub fn new(
+    text_info: &SourceTextInfo,
+    module: &ast::Module,
+    lines: Vec<u32>,
+  ) -> Self {
+    let mut collector = CallCollector {
+      text_info,
+      calls: Vec::new(),
+    };
+    module.visit_with(&mut collector);
+    Self {
+      lines,
+      calls: collector.calls,
+    }
+  }
+
+  /// Gets the index of the innermost call whose callee contains the
+  /// location of a test.
+  fn call_of(&self, location: &TestLocation) -> Option<usize> {
+    let position = (
+      location.line_number.checked_sub(1)? as usize,
+      location.column_number.checked_sub(1)? as usize,
+    );
+    self
+      .calls
+      .iter()
+      .enumerate()
+      .filter(|(_, call)| {
+        let (start, end) = &call.callee;
+        (start.line_index, start.column_index) <= position
+          && position < (end.line_index, end.column_index)
+      })
+      .min_by_key(|(_, call)| call.end_line - call.start_line)
+      .map(|(index, _)| index)
+  }
+}
+
+struct CallCollector<'a> {
+  text_info: &'a SourceTextInfo,
+  calls: Vec<CallLines>,
+}
+
+impl Visit for CallCollector<'_> {
+  fn visit_call_expr(&mut self, node: &ast::CallExpr) {
+    let callee = node.callee.range();
+    let range = node.range();
+    self.calls.push(CallLines {
+      callee: (
+        self.text_info.line_and_column_index(callee.start),
+        self.text_info.line_and_column_index(callee.end),
+      ),
+      start_line: self.text_info.line_index(range.start) as u32 + 1,
+      end_line: self.text_info.line_index(range.end) as u32 + 1,
+    });
+    node.visit_children_with(self);
+  }
+}
+
+/// Parses the modules of `path:line` arguments to look up their tests.
+pub async fn resolve_test_lines(
+  file_fetcher: &FileFetcher,
+  lines: HashMap<ModuleSpecifier, Vec<u32>>,
+) -> Result<HashMap<ModuleSpecifier, TestLines>, AnyError> {
+  let mut test_lines = HashMap::with_capacity(lines.len());
+  for (specifier, lines) in lines {
+    let file = file_fetcher
+      .fetch(&specifier, &PermissionsContainer::allow_all())
+      .await?
+      .into_text_decoded()?;
+    let parsed_source = deno_ast::parse_module(ParseParams {
+      specifier: specifier.clone(),
+      text_info: SourceTextInfo::new(file.source),
+      media_type: file.media_type,
+      capture_tokens: false,
+      scope_analysis: false,
+      maybe_syntax: None,
+    })?;
+    let lines =
+      TestLines::new(parsed_source.text_info(), parsed_source.module(), lines);
+    test_lines.insert(specifier, lines);
+  }
+  Ok(test_lines)
+}
+
+/// Gets the ids of the tests at the lines. At every line, these are the
+/// tests registered by the innermost call spanning the line, which are
+/// several when a test is registered in a loop.
+pub fn select_tests_at_lines(
+  specifier: &ModuleSpecifier,
+  tests: &TestDescriptions,
+  test_lines: &TestLines,
+) -> Result<HashSet<usize>, AnyError> {
+  let mut tests_by_call = HashMap::<usize, Vec<usize>>::new();
+  for description in tests.tests.values() {
+    // tests may also be registered by the modules imported by this one
+    if description.location.file_name != specifier.as_str() {
+      continue;
+    }
+    if let Some(call) = test_lines.call_of(&description.location) {
+      tests_by_call.entry(call).or_default().push(description.id);
+    }
+  }
+  let mut ids = HashSet::new();
+  for line in &test_lines.lines {
+    let call = tests_by_call
+      .keys()
+      .map(|index| (index, &test_lines.calls[*index]))
+      .filter(|(_, call)| call.start_line <= *line && *line <= call.end_line)
+      .min_by_key(|(_, call)| call.end_line - call.start_line)
+      .map(|(index, _)| index);
+    let Some(call) = call else {
+      bail!("No test found at line {} of {}.", line, specifier);
+    };
+    ids.extend(&tests_by_call[call]);
+  }
+  Ok(ids)
+}
+
+#[cfg(test)]
+mod test {
+  use indexmap::IndexMap;
+
+  use super::*;
+  use crate::tools::test::TestDescription;
+
+  #[test]
+  fn test_select_tests_at_lines() {
+    let specifier =
+      ModuleSpecifier::parse("file:///project/a_test.ts").unwrap();
+    let source = r#"import "./helpers.ts";
+
+Deno.test("first", () => {
+  assertEquals(add(1, 2), 3);
+});
+
+for (const n of [1, 2]) {
+  Deno.test(`loop ${n}`, () => {});
+}
+
+Deno.test({
+  name: "object",
+  fn() {},
+});
+"#;
+    let parsed_source = deno_ast::parse_module(ParseParams {
+      specifier: specifier.clone(),
+      text_info: SourceTextInfo::from_string(source.to_string()),
+      media_type: deno_ast::MediaType::TypeScript,
+      capture_tokens: false,
+      scope_analysis: false,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    let test = |id: usize, file_name: &str, line_number: u32| {
+      let description = TestDescription {
+        id,
+        name: format!("test {id}"),
+        ignore: false,
+        only: false,
+        origin: specifier.to_string(),
+        location: TestLocation {
+          file_name: file_name.to_string(),
+          line_number,
+          // the position of `test` in `Deno.test`
+          column_number: 6,
+        },
+        sanitize_ops: true,
+        sanitize_resources: true,
+        retries: None,
+        timeout: None,
+      };
+      (id, description)
+    };
+    let tests = TestDescriptions {
+      tests: IndexMap::from([
+        test(0, specifier.as_str(), 3),
+        test(1, specifier.as_str(), 8),
+        test(2, specifier.as_str(), 8),
+        // registered by a module imported by the test module
+        test(3, "file:///project/helpers.ts", 11),
+        test(4, specifier.as_str(), 11),
+      ]),
+    };
+    let select = |lines: Vec<u32>| {
+      let test_lines = TestLines::new(
+        parsed_source.text_info(),
+        parsed_source.module(),
+        lines,
+      );
+      select_tests_at_lines(&specifier, &tests, &test_lines).map(|ids| {
+        let mut ids = ids.into_iter().collect::<Vec<_>>();
+        ids.sort();
+        ids
+      })
+    };
+    assert_eq!(select(vec![3]).unwrap(), vec![0]);
+    assert_eq!(select(vec![4, 5]).unwrap(), vec![0]);
+    assert_eq!(select(vec![8]).unwrap(), vec![1, 2]);
+    assert_eq!(select(vec![4, 13]).unwrap(), vec![0, 4]);
+    for line in [1, 6, 7, 15] {
+      assert_eq!(
+        select(vec![line]).unwrap_err().to_string(),
+        format!("No test found at line {line} of {specifier}."),
+      );
+    }
+  }
+}
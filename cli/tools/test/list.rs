@@ -0,0 +1,134 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Prints the tests of `deno test --list`, which are registered by evaluating
+//! the test modules without running any of the tests.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::url::Url;
+use serde::Serialize;
+
+use super::fmt::format_test_error;
+use super::fmt::to_relative_path_or_remote_url;
+use super::TestDescription;
+use super::TestEvent;
+use super::TestEventReceiver;
+use super::TestLocation;
+use crate::colors;
+use crate::display;
+
+#[derive(Debug, Clone)]
+pub struct TestListOptions {
+  pub cwd: Url,
+  pub json: bool,
+  /// The `--filter` that was applied to the tests.
+  pub filter: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TestList<'a> {
+  filter: Option<&'a str>,
+  filtered_out: usize,
+  tests: Vec<ListedTest<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListedTest<'a> {
+  name: &'a str,
+  origin: &'a str,
+  location: &'a TestLocation,
+  ignore: bool,
+  only: bool,
+}
+
+/// Collects the tests registered by the test modules and prints them once
+/// all the modules were evaluated.
+pub async fn list_tests(
+  mut receiver: TestEventReceiver,
+  options: TestListOptions,
+) -> Result<(), AnyError> {
+  // sorted by module since the modules may be evaluated concurrently
+  let mut tests_by_origin = BTreeMap::<String, Vec<TestDescription>>::new();
+  let mut filtered_out = 0;
+  while let Some((_, event)) = receiver.recv().await {
+    match event {
+      TestEvent::Register(descriptions) => {
+        for description in descriptions.tests.values() {
+          tests_by_origin
+            .entry(description.origin.clone())
+            .or_default()
+            .push(description.clone());
+        }
+      }
+      TestEvent::Plan(plan) => {
+        filtered_out += plan.filtered_out;
+      }
+      TestEvent::UncaughtError(origin, error) => {
+        return Err(generic_error(format!(
+          "Uncaught error from {}: {}",
+          to_relative_path_or_remote_url(&options.cwd, &origin),
+          format_test_error(&error)
+        )));
+      }
+      TestEvent::Sigint => {
+        std::process::exit(130);
+      }
+      _ => {}
+    }
+  }
+
+  if options.json {
+    return display::write_json_to_stdout(&TestList {
+      filter: options.filter.as_deref(),
+      filtered_out,
+      tests: tests_by_origin
+        .values()
+        .flatten()
+        .map(|test| ListedTest {
+          name: &test.name,
+          origin: &test.origin,
+          location: &test.location,
+          ignore: test.ignore,
+          only: test.only,
+        })
+        .collect(),
+    });
+  }
+
+  let mut output = String::new();
+  for (origin, tests) in &tests_by_origin {
+    writeln!(
+      output,
+      "{}",
+      to_relative_path_or_remote_url(&options.cwd, origin)
+    )
+    .unwrap();
+    for test in tests {
+      write!(output, "  {}", test.name).unwrap();
+      if test.ignore {
+        write!(output, " {}", colors::yellow("(ignored)")).unwrap();
+      }
+      writeln!(output).unwrap();
+    }
+  }
+  let total = tests_by_origin
+    .values()
+    .map(|tests| tests.len())
+    .sum::<usize>();
+  let inflection = if total == 1 { "test" } else { "tests" };
+  let mut summary = format!("{total} {inflection}");
+  if filtered_out > 0 {
+    write!(summary, " | {filtered_out} filtered out").unwrap();
+  }
+  if let Some(filter) = &options.filter {
+    write!(summary, " | filter: {filter}").unwrap();
+  }
+  writeln!(output, "\n{}", colors::gray(summary)).unwrap();
+  display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
+  Ok(())
+}
@@ -4,6 +4,8 @@ use crate::args::CliOptions;
 use crate::args::Flags;
 use crate::args::TestFlags;
 use crate::args::TestReporterConfig;
+use crate::args::TestShard;
+use crate::cache::FastInsecureHasher;
 use crate::colors;
 use crate::display;
 use crate::factory::CliFactory;
@@ -14,6 +16,7 @@ use crate::graph_container::MainModuleGraphContainer;
 use crate::graph_util::has_graph_root_local_dependent_changed;
 use crate::ops;
 use crate::util::file_watcher;
+use crate::util::fs::canonicalize_path;
 use crate::util::fs::collect_specifiers;
 use crate::util::fs::WalkEntry;
 use crate::util::path::get_extension;
@@ -68,6 +71,7 @@ use rand::seq::SliceRandom;
 use rand::SeedableRng;
 use regex::Regex;
 use serde::Deserialize;
+use serde::Serialize;
 use std::borrow::Cow;
 use std::cell::RefCell;
 use std::collections::BTreeMap;
@@ -79,6 +83,7 @@ use std::future::poll_fn;
 use std::io::Write;
 use std::num::NonZeroUsize;
 use std::path::Path;
+use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
@@ -90,7 +95,12 @@ use tokio::signal;
 
 mod channel;
 pub mod fmt;
+mod lines;
+mod list;
+mod process;
 pub mod reporters;
+mod snapshot;
+mod watchdog;
 
 pub use channel::create_single_test_event_channel;
 pub use channel::create_test_event_channel;
@@ -99,12 +109,23 @@ pub use channel::TestEventSender;
 pub use channel::TestEventWorkerSender;
 use fmt::format_sanitizer_diff;
 pub use fmt::format_test_error;
+pub use lines::resolve_test_lines;
+use lines::select_tests_at_lines;
+pub use lines::TestLines;
+use list::list_tests;
+use list::TestListOptions;
+use process::child_process_args;
+use process::test_specifier_in_process;
+use process::write_process_test_events;
 use reporters::CompoundTestReporter;
 use reporters::DotTestReporter;
 use reporters::JunitTestReporter;
 use reporters::PrettyTestReporter;
+use reporters::SlowTestReporter;
 use reporters::TapTestReporter;
 use reporters::TestReporter;
+pub use snapshot::SnapshotStore;
+use watchdog::TestWatchdog;
 
 /// How many times we're allowed to spin the event loop before considering something a leak.
 const MAX_SANITIZER_LOOP_SPINS: usize = 16;
@@ -201,7 +222,7 @@ impl TestFilter {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct TestLocation {
   pub file_name: String,
@@ -254,7 +275,7 @@ impl<'a> IntoIterator for &'a TestDescriptions {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct TestDescription {
   pub id: usize,
@@ -265,6 +286,11 @@ pub struct TestDescription {
   pub location: TestLocation,
   pub sanitize_ops: bool,
   pub sanitize_resources: bool,
+  /// The number of retries set for the test, which overrides `--retries`.
+  pub retries: Option<usize>,
+  /// The timeout in milliseconds set for the test, which overrides
+  /// `--timeout`.
+  pub timeout: Option<u64>,
 }
 
 /// May represent a failure of a test or test step.
@@ -289,13 +315,14 @@ impl From<&TestDescription> for TestFailureDescription {
 }
 
 #[allow(clippy::derive_partial_eq_without_eq)]
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TestFailure {
   JsError(Box<JsError>),
   FailedSteps(usize),
   IncompleteSteps,
   Leaked(Vec<String>, Vec<String>), // Details, trailer notes
+  TimedOut(u64, Vec<String>),       // Timeout in ms, sampled stack frames
   // The rest are for steps only.
   Incomplete,
   OverlapsWithSanitizers(IndexSet<String>), // Long names of overlapped tests
@@ -329,6 +356,16 @@ impl std::fmt::Display for TestFailure {
         }
         Ok(())
       }
+      TestFailure::TimedOut(timeout, stack) => {
+        write!(f, "Timed out after {timeout}ms.")?;
+        if !stack.is_empty() {
+          write!(f, "\nThe test was stuck at:")?;
+          for frame in stack {
+            write!(f, "\n    at {}", frame)?;
+          }
+        }
+        Ok(())
+      }
       TestFailure::OverlapsWithSanitizers(long_names) => {
         write!(f, "Started test step while another test step with sanitizers was running:")?;
         for long_name in long_names {
@@ -358,6 +395,9 @@ impl TestFailure {
       }
       TestFailure::Incomplete => "Didn't complete before parent".to_string(),
       TestFailure::Leaked(_, _) => "Leaks detected".to_string(),
+      TestFailure::TimedOut(timeout, _) => {
+        format!("Timed out after {timeout}ms")
+      }
       TestFailure::OverlapsWithSanitizers(_) => {
         "Started test step while another test step with sanitizers was running"
           .to_string()
@@ -387,6 +427,9 @@ impl TestFailure {
       TestFailure::IncompleteSteps => {
         Some("due to incomplete steps".to_string())
       }
+      TestFailure::TimedOut(timeout, _) => {
+        Some(format!("timed out after {timeout}ms"))
+      }
       _ => None,
     }
   }
@@ -402,16 +445,19 @@ impl TestFailure {
 }
 
 #[allow(clippy::derive_partial_eq_without_eq)]
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TestResult {
   Ok,
   Ignored,
   Failed(TestFailure),
   Cancelled,
+  /// The test passed on a retry after failing with these failures.
+  #[serde(skip_deserializing)]
+  Flaky(Vec<TestFailure>),
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TestStepDescription {
   pub id: usize,
@@ -425,7 +471,7 @@ pub struct TestStepDescription {
 }
 
 #[allow(clippy::derive_partial_eq_without_eq)]
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TestStepResult {
   Ok,
@@ -433,7 +479,7 @@ pub enum TestStepResult {
   Failed(TestFailure),
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TestPlan {
   pub origin: String,
@@ -442,7 +488,7 @@ pub struct TestPlan {
   pub used_only: bool,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum TestStdioStream {
   Stdout,
   Stderr,
@@ -455,6 +501,9 @@ pub enum TestEvent {
   Wait(usize),
   Output(TestStdioStream, Vec<u8>),
   Result(usize, TestResult, u64),
+  /// The test failed and is run again, so the results of its steps so far
+  /// are discarded.
+  Retry(usize),
   UncaughtError(String, Box<JsError>),
   StepRegister(TestStepDescription),
   StepWait(usize),
@@ -497,6 +546,7 @@ pub struct TestSummary {
   pub ignored_steps: usize,
   pub filtered_out: usize,
   pub measured: usize,
+  pub flaky: usize,
   pub failures: Vec<(TestFailureDescription, TestFailure)>,
   pub uncaught_errors: Vec<(String, Box<JsError>)>,
 }
@@ -511,6 +561,24 @@ struct TestSpecifiersOptions {
   specifier: TestSpecifierOptions,
   reporter: TestReporterConfig,
   junit_path: Option<String>,
+  isolation: TestIsolation,
+  /// Lists the tests instead of reporting their results with `--list`.
+  list: Option<TestListOptions>,
+  report_slow: Option<NonZeroUsize>,
+}
+
+/// How the test modules are isolated from each other.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+enum TestIsolation {
+  /// Each module runs in a worker of this process.
+  #[default]
+  Worker,
+  /// Each module runs in a child process with `--isolate-processes`, which
+  /// is started with these arguments.
+  Process(Arc<Vec<String>>),
+  /// This is the child process of a single module, which writes its test
+  /// events to stdout for the parent.
+  ChildProcess,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -518,6 +586,55 @@ pub struct TestSpecifierOptions {
   pub shuffle: Option<u64>,
   pub filter: TestFilter,
   pub trace_leaks: bool,
+  pub shard: Option<TestShardFilter>,
+  /// The number of times a failing test is run again.
+  pub retries: usize,
+  /// The default timeout of the tests in milliseconds.
+  pub timeout: Option<u64>,
+  /// Whether the snapshots that don't match are written instead of failing.
+  pub update_snapshots: bool,
+  /// Only registers the tests that would run without running them.
+  pub list: bool,
+  /// Only runs the tests at these lines of the modules with `path:line`.
+  pub lines: HashMap<ModuleSpecifier, TestLines>,
+}
+
+/// Selects the tests of a shard when the individual tests are split across
+/// the shards. A test is assigned by a hash of its name and the path of its
+/// module relative to the cwd, so every machine assigns it the same way.
+#[derive(Debug, Clone)]
+pub struct TestShardFilter {
+  pub shard: TestShard,
+  pub cwd: Url,
+}
+
+impl TestShardFilter {
+  fn includes(&self, specifier: &ModuleSpecifier, name: &str) -> bool {
+    let path = self
+      .cwd
+      .make_relative(specifier)
+      .unwrap_or_else(|| specifier.to_string());
+    let hash = FastInsecureHasher::new()
+      .write_str(&path)
+      .write_str(name)
+      .finish();
+    hash % self.shard.count as u64 == (self.shard.index - 1) as u64
+  }
+}
+
+/// Gets the test modules of a shard. The modules are sorted before being
+/// dealt out so that every machine splits them the same way.
+fn select_shard_specifiers(
+  mut specifiers: Vec<(ModuleSpecifier, TestMode)>,
+  shard: &TestShard,
+) -> Vec<(ModuleSpecifier, TestMode)> {
+  specifiers.sort_by(|a, b| a.0.cmp(&b.0));
+  specifiers
+    .into_iter()
+    .enumerate()
+    .filter(|(i, _)| i % shard.count == shard.index - 1)
+    .map(|(_, specifier)| specifier)
+    .collect()
 }
 
 impl TestSummary {
@@ -532,6 +649,7 @@ impl TestSummary {
       ignored_steps: 0,
       filtered_out: 0,
       measured: 0,
+      flaky: 0,
       failures: Vec::new(),
       uncaught_errors: Vec::new(),
     }
@@ -556,7 +674,12 @@ fn get_test_reporter(options: &TestSpecifiersOptions) -> Box<dyn TestReporter> {
       options.cwd.clone(),
     )),
     TestReporterConfig::Junit => {
-      Box::new(JunitTestReporter::new(options.cwd.clone(), "-".to_string()))
+      // the report is only written to the file when one is provided
+      let output_path = options.junit_path.as_deref().unwrap_or("-");
+      Box::new(JunitTestReporter::new(
+        options.cwd.clone(),
+        output_path.to_string(),
+      ))
     }
     TestReporterConfig::Tap => Box::new(TapTestReporter::new(
       options.cwd.clone(),
@@ -564,15 +687,28 @@ fn get_test_reporter(options: &TestSpecifiersOptions) -> Box<dyn TestReporter> {
     )),
   };
 
-  if let Some(junit_path) = &options.junit_path {
-    let junit = Box::new(JunitTestReporter::new(
-      options.cwd.clone(),
-      junit_path.to_string(),
-    ));
-    return Box::new(CompoundTestReporter::new(vec![reporter, junit]));
-  }
+  let is_junit = options.reporter == TestReporterConfig::Junit;
+  let reporter: Box<dyn TestReporter> = match &options.junit_path {
+    // the junit reporter already writes to the path
+    Some(junit_path) if !is_junit => {
+      let junit = Box::new(JunitTestReporter::new(
+        options.cwd.clone(),
+        junit_path.to_string(),
+      ));
+      Box::new(CompoundTestReporter::new(vec![reporter, junit]))
+    }
+    _ => reporter,
+  };
 
-  reporter
+  // the junit and tap reports are machine readable, with the durations of
+  // the tests in the junit report
+  match options.report_slow {
+    Some(count) if !is_junit && options.reporter != TestReporterConfig::Tap => {
+      let slow = Box::new(SlowTestReporter::new(count, options.cwd.clone()));
+      Box::new(CompoundTestReporter::new(vec![reporter, slow]))
+    }
+    _ => reporter,
+  }
 }
 
 async fn configure_main_worker(
@@ -604,6 +740,12 @@ async fn configure_main_worker(
   }
   let res = worker.execute_side_module_possibly_with_npm().await;
   let mut worker = worker.into_main_worker();
+  // the snapshots are only asserted by the tests, which run after this
+  worker
+    .js_runtime
+    .op_state()
+    .borrow_mut()
+    .put(SnapshotStore::new(specifier, options.update_snapshots));
   match res {
     Ok(()) => Ok(()),
     Err(error) => {
@@ -771,7 +913,10 @@ pub async fn run_tests_for_worker(
     std::mem::take(&mut *state_rc.borrow_mut().borrow_mut::<TestContainer>());
 
   let tests: Arc<TestDescriptions> = tests.into();
-  send_test_event(&state_rc, TestEvent::Register(tests.clone()))?;
+  // only the tests that would run are registered when listing them
+  if !options.list {
+    send_test_event(&state_rc, TestEvent::Register(tests.clone()))?;
+  }
   let res = run_tests_for_worker_inner(
     worker,
     specifier,
@@ -781,6 +926,12 @@ pub async fn run_tests_for_worker(
     fail_fast_tracker,
   )
   .await;
+  let res = res.and_then(|()| {
+    match state_rc.borrow_mut().try_borrow_mut::<SnapshotStore>() {
+      Some(snapshot_store) => snapshot_store.save(),
+      None => Ok(()),
+    }
+  });
 
   _ = send_test_event(&state_rc, TestEvent::Completed);
   res
@@ -797,6 +948,12 @@ async fn run_tests_for_worker_inner(
   let unfiltered = tests.len();
   let state_rc = worker.js_runtime.op_state();
 
+  let tests_at_lines = options
+    .lines
+    .get(specifier)
+    .map(|lines| select_tests_at_lines(specifier, &tests, lines))
+    .transpose()?;
+
   // Build the test plan in a single pass
   let mut tests_to_run = Vec::with_capacity(tests.len());
   let mut used_only = false;
@@ -804,6 +961,16 @@ async fn run_tests_for_worker_inner(
     if !options.filter.includes(&d.name) {
       continue;
     }
+    if let Some(ids) = &tests_at_lines {
+      if !ids.contains(&d.id) {
+        continue;
+      }
+    }
+    if let Some(shard) = &options.shard {
+      if !shard.includes(specifier, &d.name) {
+        continue;
+      }
+    }
 
     // If we've seen an "only: true" test, the remaining tests must be "only: true" to be added
     if used_only && !d.only {
@@ -819,6 +986,26 @@ async fn run_tests_for_worker_inner(
     tests_to_run.push((d, f));
   }
 
+  if options.list {
+    let listed_tests = TestDescriptions {
+      tests: tests_to_run
+        .iter()
+        .map(|(d, _)| (d.id, (*d).clone()))
+        .collect(),
+    };
+    send_test_event(&state_rc, TestEvent::Register(Arc::new(listed_tests)))?;
+    send_test_event(
+      &state_rc,
+      TestEvent::Plan(TestPlan {
+        origin: specifier.to_string(),
+        total: tests_to_run.len(),
+        filtered_out: unfiltered - tests_to_run.len(),
+        used_only,
+      }),
+    )?;
+    return Ok(());
+  }
+
   if let Some(seed) = options.shuffle {
     tests_to_run.shuffle(&mut SmallRng::seed_from_u64(seed));
   }
@@ -834,6 +1021,9 @@ async fn run_tests_for_worker_inner(
   )?;
 
   let mut had_uncaught_error = false;
+  let watchdog = (options.timeout.is_some()
+    || tests_to_run.iter().any(|(desc, _)| desc.timeout.is_some()))
+  .then(|| TestWatchdog::new(&mut worker.js_runtime));
   let stats = worker.js_runtime.runtime_activity_stats_factory();
   let ops = worker.js_runtime.op_names();
 
@@ -901,94 +1091,187 @@ async fn run_tests_for_worker_inner(
     }
     send_test_event(&state_rc, TestEvent::Wait(desc.id))?;
 
-    // Poll event loop once, to allow all ops that are already resolved, but haven't
-    // responded to settle.
-    // TODO(mmastrac): we should provide an API to poll the event loop until no further
-    // progress is made.
-    poll_event_loop(worker).await?;
-
-    // We always capture stats, regardless of sanitization state
-    let before = stats.clone().capture(&filter);
-
+    let retries = desc.retries.unwrap_or(options.retries);
+    let timeout = desc.timeout.or(options.timeout);
+    let mut failed_attempts = Vec::new();
     let earlier = Instant::now();
-    let call = worker.js_runtime.call(&function);
-    let result = match worker
-      .js_runtime
-      .with_event_loop_promise(call, PollEventLoopOptions::default())
-      .await
-    {
-      Ok(r) => r,
-      Err(error) => {
-        if error.is::<JsError>() {
-          send_test_event(
-            &state_rc,
-            TestEvent::UncaughtError(
-              specifier.to_string(),
-              Box::new(error.downcast::<JsError>().unwrap()),
-            ),
-          )?;
-          fail_fast_tracker.add_failure();
-          send_test_event(
-            &state_rc,
-            TestEvent::Result(desc.id, TestResult::Cancelled, 0),
-          )?;
-          had_uncaught_error = true;
-          continue;
-        } else {
-          return Err(error);
+    let result = loop {
+      let result = match run_test_attempt(
+        worker,
+        &function,
+        desc,
+        timeout.zip(watchdog.as_ref()),
+        &stats,
+        &filter,
+        &top_level,
+      )
+      .await?
+      {
+        Ok(result) => result,
+        Err(error) => break Err(error),
+      };
+      match result {
+        TestResult::Failed(failure) if failed_attempts.len() < retries => {
+          failed_attempts.push(failure);
+          send_test_event(&state_rc, TestEvent::Retry(desc.id))?;
+        }
+        TestResult::Ok if !failed_attempts.is_empty() => {
+          break Ok(TestResult::Flaky(failed_attempts));
         }
+        result => break Ok(result),
       }
     };
-
-    // Check the result before we check for leaks
-    let result = {
-      let scope = &mut worker.js_runtime.handle_scope();
-      let result = v8::Local::new(scope, result);
-      serde_v8::from_v8::<TestResult>(scope, result)?
-    };
-    if matches!(result, TestResult::Failed(_)) {
-      fail_fast_tracker.add_failure();
-      let elapsed = earlier.elapsed().as_millis();
-      send_test_event(
-        &state_rc,
-        TestEvent::Result(desc.id, result, elapsed as u64),
-      )?;
-      continue;
-    }
-
-    // Await activity stabilization
-    if let Some(diff) = wait_for_activity_to_stabilize(
-      worker,
-      &stats,
-      &filter,
-      &top_level,
-      before,
-      desc.sanitize_ops,
-      desc.sanitize_resources,
-    )
-    .await?
-    {
-      let (formatted, trailer_notes) = format_sanitizer_diff(diff);
-      if !formatted.is_empty() {
-        let failure = TestFailure::Leaked(formatted, trailer_notes);
+    let elapsed = earlier.elapsed().as_millis() as u64;
+    match result {
+      Ok(result) => {
+        if matches!(result, TestResult::Failed(_)) {
+          fail_fast_tracker.add_failure();
+        }
+        send_test_event(
+          &state_rc,
+          TestEvent::Result(desc.id, result, elapsed),
+        )?;
+      }
+      Err(error) => {
+        send_test_event(
+          &state_rc,
+          TestEvent::UncaughtError(specifier.to_string(), Box::new(error)),
+        )?;
         fail_fast_tracker.add_failure();
-        let elapsed = earlier.elapsed().as_millis();
         send_test_event(
           &state_rc,
-          TestEvent::Result(
-            desc.id,
-            TestResult::Failed(failure),
-            elapsed as u64,
-          ),
+          TestEvent::Result(desc.id, TestResult::Cancelled, 0),
         )?;
-        continue;
+        had_uncaught_error = true;
       }
     }
+  }
+  Ok(())
+}
 
-    let elapsed = earlier.elapsed().as_millis();
-    send_test_event(
-      &state_rc,
-      TestEvent::Result(desc.id, result, elapsed as u64),
+/// Runs the test function once and checks it for leaks. An uncaught error
+/// is returned as the inner error since it cancels the remaining tests of
+/// the module instead of failing the test.
+async fn run_test_attempt(
+  worker: &mut MainWorker,
+  function: &v8::Global<v8::Function>,
+  desc: &TestDescription,
+  timeout: Option<(u64, &TestWatchdog)>,
+  stats: &RuntimeActivityStatsFactory,
+  filter: &RuntimeActivityStatsFilter,
+  top_level: &TopLevelSanitizerStats,
+) -> Result<Result<TestResult, JsError>, AnyError> {
+  // Poll event loop once, to allow all ops that are already resolved, but haven't
+  // responded to settle.
+  // TODO(mmastrac): we should provide an API to poll the event loop until no further
+  // progress is made.
+  poll_event_loop(worker).await?;
+
+  // We always capture stats, regardless of sanitization state
+  let before = stats.clone().capture(filter);
+
+  let timer = timeout.map(|(timeout, watchdog)| {
+    (timeout, watchdog.start(Duration::from_millis(timeout)))
+  });
+  let call = worker.js_runtime.call(function);
+  let result = worker
+    .js_runtime
+    .with_event_loop_promise(call, PollEventLoopOptions::default());
+  let result = match timer {
+    Some((timeout, timer)) => {
+      // the timer only fires while the test waits on the event loop, so the
+      // watchdog terminates a test that blocks the thread instead
+      let result = tokio::select! {
+        result = result => Some(result),
+        _ = tokio::time::sleep(Duration::from_millis(timeout)) => None,
+      };
+      match (result, timer.stop()) {
+        (Some(result), None) => result,
+        (_, stack) => {
+          worker.js_runtime.v8_isolate().cancel_terminate_execution();
+          cancel_test_activity(worker, stats, filter, &before)?;
+          let failure =
+            TestFailure::TimedOut(timeout, stack.unwrap_or_default());
+          return Ok(Ok(TestResult::Failed(failure)));
+        }
+      }
+    }
+    None => result.await,
+  };
+  let result = match result {
+    Ok(r) => r,
+    Err(error) => return error.downcast::<JsError>().map(Err),
+  };
+
+  // Check the result before we check for leaks
+  let result = {
+    let scope = &mut worker.js_runtime.handle_scope();
+    let result = v8::Local::new(scope, result);
+    serde_v8::from_v8::<TestResult>(scope, result)?
+  };
+  if matches!(result, TestResult::Failed(_)) {
+    return Ok(Ok(result));
+  }
+
+  // Await activity stabilization
+  if let Some(diff) = wait_for_activity_to_stabilize(
+    worker,
+    stats,
+    filter,
+    top_level,
+    before,
+    desc.sanitize_ops,
+    desc.sanitize_resources,
+  )
+  .await?
+  {
+    let (formatted, trailer_notes) = format_sanitizer_diff(diff);
+    if !formatted.is_empty() {
+      let failure = TestFailure::Leaked(formatted, trailer_notes);
+      return Ok(Ok(TestResult::Failed(failure)));
+    }
+  }
+
+  Ok(Ok(result))
+}
+
+/// Cancels the timers and closes the resources that a test that timed out
+/// started. The test itself can't be stopped, but this keeps what it was
+/// waiting on from running during the next tests.
+fn cancel_test_activity(
+  worker: &mut MainWorker,
+  stats: &RuntimeActivityStatsFactory,
+  filter: &RuntimeActivityStatsFilter,
+  before: &RuntimeActivityStats,
+) -> Result<(), AnyError> {
+  let after = stats.clone().capture(filter);
+  let mut timer_ids = Vec::new();
+  for activity in RuntimeActivityStats::diff(before, &after).appeared {
+    match activity {
+      RuntimeActivity::Resource(rid, ..) => {
+        // the resource may have been closed by the test in the meantime
+        let resource = worker
+          .js_runtime
+          .op_state()
+          .borrow_mut()
+          .resource_table
+          .take_any(rid);
+        if let Ok(resource) = resource {
+          resource.close();
+        }
+      }
+      RuntimeActivity::Timer(id, _) | RuntimeActivity::Interval(id, _) => {
+        timer_ids.push(id);
+      }
+      RuntimeActivity::AsyncOp(..) => {}
+    }
+  }
+  if !timer_ids.is_empty() {
+    worker.js_runtime.execute_script(
+      located_script_name!(),
+      format!(
+        "for (const id of {timer_ids:?}) Deno[Deno.internal].core.cancelTimer(id);"
+      ),
     )?;
   }
   Ok(())
@@ -1360,7 +1643,8 @@ async fn test_specifiers(
   specifiers: Vec<ModuleSpecifier>,
   options: TestSpecifiersOptions,
 ) -> Result<(), AnyError> {
-  let specifiers = if let Some(seed) = options.specifier.shuffle {
+  let shuffle = options.specifier.shuffle;
+  let specifiers = if let Some(seed) = shuffle {
     let mut rng = SmallRng::seed_from_u64(seed);
     let mut specifiers = specifiers;
     specifiers.sort();
@@ -1381,6 +1665,7 @@ async fn test_specifiers(
   HAS_TEST_RUN_SIGINT_HANDLER.store(true, Ordering::Relaxed);
   let reporter = get_test_reporter(&options);
   let fail_fast_tracker = FailFastTracker::new(options.fail_fast);
+  let is_child_process = options.isolation == TestIsolation::ChildProcess;
 
   let join_handles = specifiers.into_iter().map(move |specifier| {
     let worker_factory = worker_factory.clone();
@@ -1388,7 +1673,17 @@ async fn test_specifiers(
     let worker_sender = test_event_sender_factory.worker();
     let fail_fast_tracker = fail_fast_tracker.clone();
     let specifier_options = options.specifier.clone();
+    let isolation = options.isolation.clone();
     spawn_blocking(move || {
+      if let TestIsolation::Process(args) = &isolation {
+        return test_specifier_in_process(
+          specifier,
+          args,
+          worker_sender,
+          fail_fast_tracker,
+          specifier_options.shuffle,
+        );
+      }
       create_and_run_current_thread(test_specifier(
         worker_factory,
         permissions,
@@ -1404,7 +1699,16 @@ async fn test_specifiers(
     .buffer_unordered(concurrent_jobs.get())
     .collect::<Vec<Result<Result<(), AnyError>, tokio::task::JoinError>>>();
 
-  let handler = spawn(async move { report_tests(receiver, reporter).await.0 });
+  let list_options = options.list.clone();
+  let handler = spawn(async move {
+    if is_child_process {
+      return write_process_test_events(receiver).await;
+    }
+    if let Some(list_options) = list_options {
+      return list_tests(receiver, list_options).await;
+    }
+    report_tests(receiver, reporter).await.0
+  });
 
   let (join_results, result) = future::join(join_stream, handler).await;
   sigint_handler_handle.abort();
@@ -1412,7 +1716,16 @@ async fn test_specifiers(
   for join_result in join_results {
     join_result??;
   }
-  result??;
+  let result = result?;
+  if let (Err(_), Some(seed)) = (&result, shuffle) {
+    log::info!(
+      "{}",
+      colors::gray(format!(
+        "The tests were shuffled with seed {seed}. Run with --shuffle={seed} to reproduce their order."
+      ))
+    );
+  }
+  result?;
 
   Ok(())
 }
@@ -1469,6 +1782,9 @@ pub async fn report_tests(
           reporter.report_result(tests.get(&id).unwrap(), &result, elapsed);
         }
       }
+      TestEvent::Retry(id) => {
+        reporter.report_retry(tests.get(&id).unwrap());
+      }
       TestEvent::UncaughtError(origin, error) => {
         failed = true;
         reporter.report_uncaught_error(&origin, error);
@@ -1615,6 +1931,7 @@ fn is_supported_test_ext(path: &Path) -> bool {
 fn collect_specifiers_with_test_mode(
   cli_options: &CliOptions,
   files: FilePatterns,
+  use_gitignore: bool,
   include_inline: &bool,
 ) -> Result<Vec<(ModuleSpecifier, TestMode)>, AnyError> {
   // todo(dsherret): there's no need to collect twice as it's slow
@@ -1622,6 +1939,7 @@ fn collect_specifiers_with_test_mode(
   let module_specifiers = collect_specifiers(
     files.clone(),
     vendor_folder.map(ToOwned::to_owned),
+    use_gitignore,
     is_supported_test_path_predicate,
   )?;
 
@@ -1629,6 +1947,7 @@ fn collect_specifiers_with_test_mode(
     return collect_specifiers(
       files,
       vendor_folder.map(ToOwned::to_owned),
+      use_gitignore,
       |e| is_supported_test_ext(e.path),
     )
     .map(|specifiers| {
@@ -1667,12 +1986,17 @@ async fn fetch_specifiers_with_test_mode(
   cli_options: &CliOptions,
   file_fetcher: &FileFetcher,
   files: FilePatterns,
+  use_gitignore: bool,
   doc: &bool,
 ) -> Result<Vec<(ModuleSpecifier, TestMode)>, AnyError> {
   let mut specifiers_with_mode =
-    collect_specifiers_with_test_mode(cli_options, files, doc)?;
+    collect_specifiers_with_test_mode(cli_options, files, use_gitignore, doc)?;
 
   for (specifier, mode) in &mut specifiers_with_mode {
+    if matches!(specifier.scheme(), "npm" | "jsr") {
+      // packages are resolved when building the module graph
+      continue;
+    }
     let file = file_fetcher
       .fetch(specifier, &PermissionsContainer::allow_all())
       .await?;
@@ -1690,6 +2014,9 @@ pub async fn run_tests(
   flags: Flags,
   test_flags: TestFlags,
 ) -> Result<(), AnyError> {
+  let child_process_args = test_flags
+    .isolate_processes
+    .then(|| Arc::new(child_process_args(&flags, &test_flags)));
   let factory = CliFactory::from_flags(flags)?;
   let cli_options = factory.cli_options();
   let test_options = cli_options.resolve_test_options(test_flags)?;
@@ -1705,6 +2032,7 @@ pub async fn run_tests(
     cli_options,
     file_fetcher,
     test_options.files.clone(),
+    test_options.gitignore,
     &test_options.doc,
   )
   .await?;
@@ -1713,14 +2041,54 @@ pub async fn run_tests(
     return Err(generic_error("No test modules found"));
   }
 
-  let main_graph_container = factory.main_module_graph_container().await?;
+  let isolation = if test_options.child_process_module.is_some() {
+    TestIsolation::ChildProcess
+  } else if let Some(args) = child_process_args {
+    TestIsolation::Process(args)
+  } else {
+    TestIsolation::Worker
+  };
 
-  check_specifiers(
-    file_fetcher,
-    main_graph_container,
-    specifiers_with_mode.clone(),
-  )
-  .await?;
+  let cwd =
+    Url::from_directory_path(cli_options.initial_cwd()).map_err(|_| {
+      generic_error(format!(
+        "Unable to construct URL from the path of cwd: {}",
+        cli_options.initial_cwd().to_string_lossy(),
+      ))
+    })?;
+  let (specifiers_with_mode, shard_filter) = match test_options.shard {
+    Some(shard) if test_options.shard_tests => (
+      specifiers_with_mode,
+      Some(TestShardFilter {
+        shard,
+        cwd: cwd.clone(),
+      }),
+    ),
+    Some(shard) => {
+      (select_shard_specifiers(specifiers_with_mode, &shard), None)
+    }
+    None => (specifiers_with_mode, None),
+  };
+
+  let specifiers_with_mode = match &test_options.child_process_module {
+    Some(module) => specifiers_with_mode
+      .into_iter()
+      .filter(|(specifier, _)| specifier.as_str() == module)
+      .collect(),
+    None => specifiers_with_mode,
+  };
+
+  // the parent of a child process already type checked the modules, and
+  // listing the tests doesn't require type checking
+  if isolation != TestIsolation::ChildProcess && !test_options.list {
+    let main_graph_container = factory.main_module_graph_container().await?;
+    check_specifiers(
+      file_fetcher,
+      main_graph_container,
+      specifiers_with_mode.clone(),
+    )
+    .await?;
+  }
 
   if test_options.no_run {
     return Ok(());
@@ -1728,9 +2096,24 @@ pub async fn run_tests(
 
   let worker_factory =
     Arc::new(factory.create_cli_main_worker_factory().await?);
+  let list_options = test_options.list.then(|| TestListOptions {
+    cwd: cwd.clone(),
+    json: test_options.json,
+    filter: test_options.filter.clone(),
+  });
 
-  test_specifiers(
-    worker_factory,
+  // the parent of a child process runs the setup and teardown modules
+  let run_global_modules =
+    isolation != TestIsolation::ChildProcess && !test_options.list;
+  if let Some(setup) =
+    test_options.setup.as_ref().filter(|_| run_global_modules)
+  {
+    run_global_module(&worker_factory, &permissions, setup, "setup").await?;
+  }
+
+  let lines = resolve_test_lines(file_fetcher, test_options.lines).await?;
+  let result = test_specifiers(
+    worker_factory.clone(),
     &permissions,
     specifiers_with_mode
       .into_iter()
@@ -1740,30 +2123,87 @@ pub async fn run_tests(
       })
       .collect(),
     TestSpecifiersOptions {
-      cwd: Url::from_directory_path(cli_options.initial_cwd()).map_err(
-        |_| {
-          generic_error(format!(
-            "Unable to construct URL from the path of cwd: {}",
-            cli_options.initial_cwd().to_string_lossy(),
-          ))
-        },
-      )?,
+      cwd,
       concurrent_jobs: test_options.concurrent_jobs,
       fail_fast: test_options.fail_fast,
       log_level,
       filter: test_options.filter.is_some(),
       reporter: test_options.reporter,
       junit_path: test_options.junit_path,
+      isolation,
+      list: list_options,
+      report_slow: test_options.report_slow,
       specifier: TestSpecifierOptions {
         filter: TestFilter::from_flag(&test_options.filter),
         shuffle: test_options.shuffle,
         trace_leaks: test_options.trace_leaks,
+        shard: shard_filter,
+        retries: test_options.retries,
+        timeout: test_options.timeout,
+        update_snapshots: test_options.update_snapshots,
+        list: test_options.list,
+        lines,
       },
     },
   )
-  .await?;
+  .await;
 
-  Ok(())
+  // the teardown also runs when tests failed, which were already reported
+  if let Some(teardown) = test_options
+    .teardown
+    .as_ref()
+    .filter(|_| run_global_modules)
+  {
+    run_global_module(&worker_factory, &permissions, teardown, "teardown")
+      .await?;
+  }
+
+  result
+}
+
+/// Runs the `test.setup` or `test.teardown` module of the config file to
+/// completion like `deno run`, aborting the test run when it fails.
+async fn run_global_module(
+  worker_factory: &CliMainWorkerFactory,
+  permissions: &Permissions,
+  specifier: &ModuleSpecifier,
+  kind: &str,
+) -> Result<(), AnyError> {
+  let result = async {
+    let mut worker = worker_factory
+      .create_main_worker(
+        WorkerExecutionMode::Run,
+        specifier.clone(),
+        PermissionsContainer::new(permissions.clone()),
+      )
+      .await?;
+    worker.run().await
+  }
+  .await;
+  let message = match result {
+    Ok(0) => return Ok(()),
+    Ok(exit_code) => format!("exited with code {exit_code}"),
+    Err(err) => match err.downcast_ref::<JsError>() {
+      Some(js_error) => format_test_error(js_error),
+      None => format!("{err:?}"),
+    },
+  };
+  Err(generic_error(format!(
+    "Test {kind} module {specifier} failed: {message}"
+  )))
+}
+
+/// Gets whether the config file or import map changed, which requires
+/// rerunning all the tests in watch mode.
+fn has_config_changed(
+  cli_options: &CliOptions,
+  canonicalized_changed_paths: &HashSet<PathBuf>,
+) -> bool {
+  cli_options.watch_paths().iter().any(|path| {
+    canonicalize_path(path)
+      .map(|path| canonicalized_changed_paths.contains(&path))
+      .unwrap_or(false)
+  })
 }
 
 pub async fn run_tests_with_watch(
@@ -1808,6 +2248,14 @@ pub async fn run_tests_with_watch(
             let _ = watcher_communicator.watch_paths(watch_paths);
           }
         }
+        let global_module_paths = [&test_options.setup, &test_options.teardown]
+          .into_iter()
+          .flatten()
+          .filter_map(|specifier| specifier.to_file_path().ok())
+          .collect::<Vec<_>>();
+        if !global_module_paths.is_empty() {
+          let _ = watcher_communicator.watch_paths(global_module_paths);
+        }
 
         let graph_kind = cli_options.type_check_mode().as_graph_kind();
         let log_level = cli_options.log_level();
@@ -1818,12 +2266,14 @@ pub async fn run_tests_with_watch(
           collect_specifiers(
             test_options.files.clone(),
             cli_options.vendor_dir_path().map(ToOwned::to_owned),
+            test_options.gitignore,
             |e| is_supported_test_ext(e.path),
           )
         } else {
           collect_specifiers(
             test_options.files.clone(),
             cli_options.vendor_dir_path().map(ToOwned::to_owned),
+            test_options.gitignore,
             is_supported_test_path_predicate,
           )
         }?;
@@ -1838,18 +2288,23 @@ pub async fn run_tests_with_watch(
 
         let test_modules_to_reload = if let Some(changed_paths) = changed_paths
         {
-          let mut result = Vec::new();
           let changed_paths = changed_paths.into_iter().collect::<HashSet<_>>();
-          for test_module_specifier in test_modules {
-            if has_graph_root_local_dependent_changed(
-              &graph,
-              test_module_specifier,
-              &changed_paths,
-            ) {
-              result.push(test_module_specifier.clone());
+          if has_config_changed(&cli_options, &changed_paths) {
+            // the resolution of any module may have changed
+            test_modules.clone()
+          } else {
+            let mut result = Vec::new();
+            for test_module_specifier in test_modules {
+              if has_graph_root_local_dependent_changed(
+                &graph,
+                test_module_specifier,
+                &changed_paths,
+              ) {
+                result.push(test_module_specifier.clone());
+              }
             }
+            result
           }
-          result
         } else {
           test_modules.clone()
         };
@@ -1860,9 +2315,18 @@ pub async fn run_tests_with_watch(
           &cli_options,
           file_fetcher,
           test_options.files.clone(),
+          test_options.gitignore,
           &test_options.doc,
         )
-        .await?
+        .await?;
+        // the shard is selected out of all the modules so that a module
+        // stays in the same shard while the others change
+        let specifiers_with_mode = match test_options.shard {
+          Some(shard) if !test_options.shard_tests => {
+            select_shard_specifiers(specifiers_with_mode, &shard)
+          }
+          _ => specifiers_with_mode,
+        }
         .into_iter()
         .filter(|(specifier, _)| test_modules_to_reload.contains(specifier))
         .collect::<Vec<(ModuleSpecifier, TestMode)>>();
@@ -1880,8 +2344,31 @@ pub async fn run_tests_with_watch(
           return Ok(());
         }
 
-        test_specifiers(
-          worker_factory,
+        let cwd = Url::from_directory_path(cli_options.initial_cwd()).map_err(
+          |_| {
+            generic_error(format!(
+              "Unable to construct URL from the path of cwd: {}",
+              cli_options.initial_cwd().to_string_lossy(),
+            ))
+          },
+        )?;
+        let shard_filter = test_options
+          .shard
+          .filter(|_| test_options.shard_tests)
+          .map(|shard| TestShardFilter {
+            shard,
+            cwd: cwd.clone(),
+          });
+        // the setup and teardown run around every rerun of the tests
+        if let Some(setup) = &test_options.setup {
+          run_global_module(&worker_factory, &permissions, setup, "setup")
+            .await?;
+        }
+
+        let lines =
+          resolve_test_lines(file_fetcher, test_options.lines).await?;
+        let result = test_specifiers(
+          worker_factory.clone(),
           &permissions,
           specifiers_with_mode
             .into_iter()
@@ -1891,30 +2378,44 @@ pub async fn run_tests_with_watch(
             })
             .collect(),
           TestSpecifiersOptions {
-            cwd: Url::from_directory_path(cli_options.initial_cwd()).map_err(
-              |_| {
-                generic_error(format!(
-                  "Unable to construct URL from the path of cwd: {}",
-                  cli_options.initial_cwd().to_string_lossy(),
-                ))
-              },
-            )?,
+            cwd,
             concurrent_jobs: test_options.concurrent_jobs,
             fail_fast: test_options.fail_fast,
             log_level,
             filter: test_options.filter.is_some(),
             reporter: test_options.reporter,
             junit_path: test_options.junit_path,
+            // `--isolate-processes` can't be used with `--watch`
+            isolation: TestIsolation::Worker,
+            // `--list` can't be used with `--watch`
+            list: None,
+            report_slow: test_options.report_slow,
             specifier: TestSpecifierOptions {
               filter: TestFilter::from_flag(&test_options.filter),
               shuffle: test_options.shuffle,
               trace_leaks: test_options.trace_leaks,
+              shard: shard_filter,
+              retries: test_options.retries,
+              timeout: test_options.timeout,
+              update_snapshots: test_options.update_snapshots,
+              list: false,
+              lines,
             },
           },
         )
-        .await?;
+        .await;
+
+        if let Some(teardown) = &test_options.teardown {
+          run_global_module(
+            &worker_factory,
+            &permissions,
+            teardown,
+            "teardown",
+          )
+          .await?;
+        }
 
-        Ok(())
+        result
       })
     },
   )
@@ -2021,4 +2522,55 @@ mod inner_test {
     assert!(!is_supported_test_path(Path::new("notatest.js")));
     assert!(!is_supported_test_path(Path::new("NotAtest.ts")));
   }
+
+  #[test]
+  fn test_select_shard_specifiers() {
+    let specifiers = ["d", "a", "c", "e", "b"]
+      .into_iter()
+      .map(|name| {
+        let specifier =
+          ModuleSpecifier::parse(&format!("file:///project/{name}_test.ts"))
+            .unwrap();
+        (specifier, TestMode::Executable)
+      })
+      .collect::<Vec<_>>();
+    let names = |shard: TestShard| {
+      select_shard_specifiers(specifiers.clone(), &shard)
+        .into_iter()
+        .map(|(s, _)| s.path().to_string())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(
+      names(TestShard { index: 1, count: 2 }),
+      vec![
+        "/project/a_test.ts",
+        "/project/c_test.ts",
+        "/project/e_test.ts"
+      ]
+    );
+    assert_eq!(
+      names(TestShard { index: 2, count: 2 }),
+      vec!["/project/b_test.ts", "/project/d_test.ts"]
+    );
+    assert!(names(TestShard { index: 8, count: 8 }).is_empty());
+  }
+
+  #[test]
+  fn test_shard_filter_includes_each_test_once() {
+    let specifier =
+      ModuleSpecifier::parse("file:///project/a_test.ts").unwrap();
+    let filters = (1..=3)
+      .map(|index| TestShardFilter {
+        shard: TestShard { index, count: 3 },
+        cwd: Url::parse("file:///project/").unwrap(),
+      })
+      .collect::<Vec<_>>();
+    for name in ["one", "two", "three", "four"] {
+      let count = filters
+        .iter()
+        .filter(|filter| filter.includes(&specifier, name))
+        .count();
+      assert_eq!(count, 1, "{name}");
+    }
+  }
 }
@@ -0,0 +1,403 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Runs each test module in a child process with `deno test
+//! --isolate-processes`, which isolates the globals, environment variables
+//! and crashes of the modules. The child runs the tests of a single module
+//! and writes its test events to stdout as JSON lines, which the parent
+//! forwards to its reporter.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::error::JsError;
+use deno_core::serde_json;
+use deno_core::ModuleSpecifier;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::args::Flags;
+use crate::args::TestFlags;
+
+use super::FailFastTracker;
+use super::TestDescription;
+use super::TestDescriptions;
+use super::TestEvent;
+use super::TestEventReceiver;
+use super::TestEventWorkerSender;
+use super::TestFailure;
+use super::TestPlan;
+use super::TestResult;
+use super::TestStdioStream;
+use super::TestStepDescription;
+use super::TestStepResult;
+
+/// The ids of the tests of all the child processes, which are assigned in
+/// the order the parent receives them since every child starts from zero.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// The events of `TestEvent` that are sent from a child process.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum ProcessTestEvent {
+  Register(Vec<TestDescription>),
+  Plan(TestPlan),
+  Wait(usize),
+  Output(TestStdioStream, Vec<u8>),
+  Result(usize, TestResult, u64),
+  /// The result of a test that passed on a retry, since the test runner
+  /// doesn't deserialize `TestResult::Flaky`.
+  FlakyResult(usize, Vec<TestFailure>, u64),
+  Retry(usize),
+  UncaughtError(String, Box<JsError>),
+  StepRegister(TestStepDescription),
+  StepWait(usize),
+  StepResult(usize, TestStepResult, u64),
+  Completed,
+}
+
+impl ProcessTestEvent {
+  fn from_test_event(event: TestEvent) -> Option<Self> {
+    let event = match event {
+      TestEvent::Register(descriptions) => {
+        Self::Register(descriptions.tests.values().cloned().collect())
+      }
+      TestEvent::Plan(plan) => Self::Plan(plan),
+      TestEvent::Wait(id) => Self::Wait(id),
+      TestEvent::Output(stream, output) => Self::Output(stream, output),
+      TestEvent::Result(id, TestResult::Flaky(failures), elapsed) => {
+        Self::FlakyResult(id, failures, elapsed)
+      }
+      TestEvent::Result(id, result, elapsed) => {
+        Self::Result(id, result, elapsed)
+      }
+      TestEvent::Retry(id) => Self::Retry(id),
+      TestEvent::UncaughtError(origin, error) => {
+        Self::UncaughtError(origin, error)
+      }
+      TestEvent::StepRegister(description) => Self::StepRegister(description),
+      TestEvent::StepWait(id) => Self::StepWait(id),
+      TestEvent::StepResult(id, result, elapsed) => {
+        Self::StepResult(id, result, elapsed)
+      }
+      TestEvent::Completed => Self::Completed,
+      TestEvent::Sigint | TestEvent::ForceEndReport => return None,
+    };
+    Some(event)
+  }
+}
+
+/// Maps the ids of the tests and steps of a child process to ids that are
+/// unique across the child processes.
+#[derive(Default)]
+struct TestIdMap(HashMap<usize, usize>);
+
+impl TestIdMap {
+  fn get(&mut self, id: usize) -> usize {
+    *self
+      .0
+      .entry(id)
+      .or_insert_with(|| NEXT_ID.fetch_add(1, Ordering::SeqCst))
+  }
+
+  fn map_event(&mut self, event: ProcessTestEvent) -> TestEvent {
+    match event {
+      ProcessTestEvent::Register(descriptions) => {
+        TestEvent::Register(Arc::new(TestDescriptions {
+          tests: descriptions
+            .into_iter()
+            .map(|mut description| {
+              description.id = self.get(description.id);
+              (description.id, description)
+            })
+            .collect(),
+        }))
+      }
+      ProcessTestEvent::Plan(plan) => TestEvent::Plan(plan),
+      ProcessTestEvent::Wait(id) => TestEvent::Wait(self.get(id)),
+      ProcessTestEvent::Output(stream, output) => {
+        TestEvent::Output(stream, output)
+      }
+      ProcessTestEvent::Result(id, result, elapsed) => {
+        TestEvent::Result(self.get(id), result, elapsed)
+      }
+      ProcessTestEvent::FlakyResult(id, failures, elapsed) => {
+        TestEvent::Result(self.get(id), TestResult::Flaky(failures), elapsed)
+      }
+      ProcessTestEvent::Retry(id) => TestEvent::Retry(self.get(id)),
+      ProcessTestEvent::UncaughtError(origin, error) => {
+        TestEvent::UncaughtError(origin, error)
+      }
+      ProcessTestEvent::StepRegister(mut description) => {
+        description.id = self.get(description.id);
+        description.parent_id = self.get(description.parent_id);
+        description.root_id = self.get(description.root_id);
+        TestEvent::StepRegister(description)
+      }
+      ProcessTestEvent::StepWait(id) => TestEvent::StepWait(self.get(id)),
+      ProcessTestEvent::StepResult(id, result, elapsed) => {
+        TestEvent::StepResult(self.get(id), result, elapsed)
+      }
+      ProcessTestEvent::Completed => TestEvent::Completed,
+    }
+  }
+}
+
+/// Writes the test events of the child process to stdout for the parent.
+pub async fn write_process_test_events(
+  mut receiver: TestEventReceiver,
+) -> Result<(), AnyError> {
+  while let Some((_, event)) = receiver.recv().await {
+    if matches!(event, TestEvent::Sigint) {
+      // the parent reports the tests that were cancelled
+      std::process::exit(130);
+    }
+    if let Some(event) = ProcessTestEvent::from_test_event(event) {
+      let mut stdout = std::io::stdout();
+      writeln!(stdout, "{}", serde_json::to_string(&event)?)?;
+      stdout.flush()?;
+    }
+  }
+  Ok(())
+}
+
+/// Runs the tests of the module in a child process, forwarding its events
+/// to the sender. The child is killed once enough tests failed for
+/// `--fail-fast`.
+pub fn test_specifier_in_process(
+  specifier: ModuleSpecifier,
+  args: &[String],
+  mut worker_sender: TestEventWorkerSender,
+  fail_fast_tracker: FailFastTracker,
+  shuffle: Option<u64>,
+) -> Result<(), AnyError> {
+  if fail_fast_tracker.should_stop() {
+    return Ok(());
+  }
+  let mut child = Command::new(std::env::current_exe()?)
+    .arg("test")
+    .arg(format!("--child-process-module={}", specifier.as_str()))
+    // the child would pick another random seed
+    .args(shuffle.map(|seed| format!("--shuffle={seed}")))
+    .args(args)
+    .stdin(Stdio::inherit())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::inherit())
+    .spawn()?;
+  let stdout = BufReader::new(child.stdout.take().unwrap());
+  let mut ids = TestIdMap::default();
+  for line in stdout.lines() {
+    if fail_fast_tracker.should_stop() {
+      child.kill()?;
+      child.wait()?;
+      return Ok(());
+    }
+    let line = line?;
+    let Ok(event) = serde_json::from_str::<ProcessTestEvent>(&line) else {
+      // something other than the test runner wrote to stdout
+      worker_sender
+        .stdout
+        .write_all(format!("{line}\n").as_bytes())?;
+      continue;
+    };
+    match event {
+      ProcessTestEvent::Output(TestStdioStream::Stdout, output) => {
+        worker_sender.stdout.write_all(&output)?;
+      }
+      ProcessTestEvent::Output(TestStdioStream::Stderr, output) => {
+        worker_sender.stderr.write_all(&output)?;
+      }
+      event => {
+        if let ProcessTestEvent::Result(_, TestResult::Failed(_), _) = &event {
+          fail_fast_tracker.add_failure();
+        }
+        worker_sender.sender.send(ids.map_event(event))?;
+      }
+    }
+  }
+  let status = child.wait()?;
+  if !status.success() {
+    bail!("The test process of {} failed with {}.", specifier, status);
+  }
+  Ok(())
+}
+
+/// Gets the arguments of the child processes from the flags of this
+/// process, so that they resolve the same options. The module of the child
+/// and the seed of `--shuffle` are added per child. Type checking,
+/// reloading, reporting, `--fail-fast` and the setup and teardown modules
+/// are left to this process.
+pub fn child_process_args(
+  flags: &Flags,
+  test_flags: &TestFlags,
+) -> Vec<String> {
+  let mut args = flags.to_runtime_args();
+  for (enabled, arg) in [
+    (test_flags.doc, "--doc"),
+    (test_flags.allow_none, "--allow-none"),
+    (test_flags.trace_leaks, "--trace-leaks"),
+    (test_flags.update_snapshots, "--update-snapshots"),
+    (test_flags.files.gitignore, "--gitignore"),
+  ] {
+    if enabled {
+      args.push(arg.to_string());
+    }
+  }
+  if let Some(filter) = &test_flags.filter {
+    args.push(format!("--filter={filter}"));
+  }
+  if let Some(coverage_dir) = &test_flags.coverage_dir {
+    args.push(format!("--coverage={coverage_dir}"));
+  }
+  if test_flags.retries > 0 {
+    args.push(format!("--retries={}", test_flags.retries));
+  }
+  if let Some(timeout) = test_flags.timeout {
+    args.push(format!("--timeout={timeout}"));
+  }
+  // the modules of the shard are selected by this process
+  if let Some(shard) = test_flags.shard.filter(|_| test_flags.shard_tests) {
+    args.push(format!("--shard={}/{}", shard.index, shard.count));
+    args.push("--shard-tests".to_string());
+  }
+  if !test_flags.files.ignore.is_empty() {
+    args.push(format!("--ignore={}", test_flags.files.ignore.join(",")));
+  }
+  // the child collects the same modules and then selects its own
+  let line_paths = test_flags
+    .lines
+    .iter()
+    .map(|(path, _)| path)
+    .collect::<HashSet<_>>();
+  args.extend(
+    test_flags
+      .files
+      .include
+      .iter()
+      .filter(|path| !line_paths.contains(path))
+      .cloned(),
+  );
+  args.extend(
+    test_flags
+      .lines
+      .iter()
+      .map(|(path, line)| format!("{path}:{line}")),
+  );
+  args.push("--".to_string());
+  args.extend(flags.argv.iter().cloned());
+  args
+}
+
+#[cfg(test)]
+mod test {
+  use std::ffi::OsString;
+
+  use super::*;
+  use crate::args::flags_from_vec;
+  use crate::args::DenoSubcommand;
+  use crate::tools::test::TestLocation;
+
+  fn child_args(args: &[&str]) -> Vec<String> {
+    let flags = flags_from_vec(
+      ["deno", "test"]
+        .iter()
+        .chain(args)
+        .map(OsString::from)
+        .collect(),
+    )
+    .unwrap();
+    let DenoSubcommand::Test(test_flags) = &flags.subcommand else {
+      unreachable!()
+    };
+    child_process_args(&flags, test_flags)
+  }
+
+  #[test]
+  fn child_process_args_from_flags() {
+    assert_eq!(
+      child_args(&[
+        "--isolate-processes",
+        "--parallel",
+        "--fail-fast",
+        "--allow-read=.",
+        "--config=deno.jsonc",
+        "--filter",
+        "add",
+        "--check",
+        "--retries=2",
+        "a_test.ts",
+        "b_test.ts:12",
+        "--",
+        "--filter",
+      ]),
+      vec![
+        "--allow-read=.",
+        "--config=deno.jsonc",
+        "--filter=add",
+        "--retries=2",
+        "a_test.ts",
+        "b_test.ts:12",
+        "--",
+        "--filter",
+      ]
+    );
+  }
+
+  #[test]
+  fn child_process_args_only_shard_tests() {
+    assert_eq!(
+      child_args(&["--isolate-processes", "--shard=1/2"]),
+      vec!["--"]
+    );
+    assert_eq!(
+      child_args(&["--isolate-processes", "--shard=1/2", "--shard-tests"]),
+      vec!["--shard=1/2", "--shard-tests", "--"]
+    );
+  }
+
+  #[test]
+  fn maps_ids_of_child_processes() {
+    let step = TestStepDescription {
+      id: 1,
+      name: "step".to_string(),
+      origin: "file:///a_test.ts".to_string(),
+      location: TestLocation {
+        file_name: "file:///a_test.ts".to_string(),
+        line_number: 2,
+        column_number: 11,
+      },
+      level: 1,
+      parent_id: 0,
+      root_id: 0,
+      root_name: "test".to_string(),
+    };
+    let mut first = TestIdMap::default();
+    let mut second = TestIdMap::default();
+    let TestEvent::StepRegister(first_step) =
+      first.map_event(ProcessTestEvent::StepRegister(step.clone()))
+    else {
+      unreachable!()
+    };
+    let TestEvent::StepRegister(second_step) =
+      second.map_event(ProcessTestEvent::StepRegister(step))
+    else {
+      unreachable!()
+    };
+    assert_eq!(first_step.parent_id, first_step.root_id);
+    assert_ne!(first_step.id, second_step.id);
+    assert_ne!(first_step.parent_id, second_step.parent_id);
+    let TestEvent::Wait(id) = first.map_event(ProcessTestEvent::Wait(1)) else {
+      unreachable!()
+    };
+    assert_eq!(id, first_step.id);
+  }
+}
@@ -4,6 +4,60 @@ use super::fmt::format_test_error;
 use super::fmt::to_relative_path_or_remote_url;
 use super::*;
 
+/// The results of the steps of the tests that are running, which are only
+/// added to the summary once the test has a result so that the steps of the
+/// attempts that are retried aren't reported.
+#[derive(Default)]
+pub(super) struct StepResultsBuffer(HashMap<usize, StepResults>);
+
+#[derive(Default)]
+struct StepResults {
+  passed: usize,
+  ignored: usize,
+  failures: Vec<(TestFailureDescription, TestFailure)>,
+}
+
+impl StepResultsBuffer {
+  pub fn add(
+    &mut self,
+    desc: &TestStepDescription,
+    result: &TestStepResult,
+    tests: &IndexMap<usize, TestDescription>,
+    test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    let results = self.0.entry(desc.root_id).or_default();
+    match result {
+      TestStepResult::Ok => results.passed += 1,
+      TestStepResult::Ignored => results.ignored += 1,
+      TestStepResult::Failed(failure) => results.failures.push((
+        TestFailureDescription {
+          id: desc.id,
+          name: format_test_step_ancestry(desc, tests, test_steps),
+          origin: desc.origin.clone(),
+          location: desc.location.clone(),
+        },
+        failure.clone(),
+      )),
+    }
+  }
+
+  /// Drops the results of the steps of a test that is run again.
+  pub fn discard(&mut self, test_id: usize) {
+    self.0.remove(&test_id);
+  }
+
+  /// Adds the results of the steps of a test that finished to the summary.
+  pub fn flush(&mut self, test_id: usize, summary: &mut TestSummary) {
+    let Some(results) = self.0.remove(&test_id) else {
+      return;
+    };
+    summary.passed_steps += results.passed;
+    summary.ignored_steps += results.ignored;
+    summary.failed_steps += results.failures.len();
+    summary.failures.extend(results.failures);
+  }
+}
+
 pub(super) fn format_test_step_ancestry(
   desc: &TestStepDescription,
   tests: &IndexMap<usize, TestDescription>,
@@ -206,6 +260,10 @@ pub(super) fn report_summary(
     .unwrap()
   }
 
+  if summary.flaky > 0 {
+    write!(summary_result, " | {} flaky", summary.flaky).unwrap();
+  }
+
   if summary.measured > 0 {
     write!(summary_result, " | {} measured", summary.measured,).unwrap();
   }
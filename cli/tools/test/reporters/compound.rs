@@ -48,6 +48,12 @@ impl TestReporter for CompoundTestReporter {
     }
   }
 
+  fn report_retry(&mut self, description: &TestDescription) {
+    for reporter in &mut self.test_reporters {
+      reporter.report_retry(description);
+    }
+  }
+
   fn report_uncaught_error(&mut self, origin: &str, error: Box<JsError>) {
     for reporter in &mut self.test_reporters {
       reporter.report_uncaught_error(origin, error.clone());
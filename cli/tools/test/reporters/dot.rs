@@ -9,6 +9,7 @@ pub struct DotTestReporter {
   width: usize,
   cwd: Url,
   summary: TestSummary,
+  step_results: common::StepResultsBuffer,
 }
 
 #[allow(clippy::print_stdout)]
@@ -26,6 +27,7 @@ impl DotTestReporter {
       width: console_width,
       cwd,
       summary: TestSummary::new(),
+      step_results: Default::default(),
     }
   }
 
@@ -59,6 +61,7 @@ impl DotTestReporter {
       TestResult::Ignored => fmt_ignored(),
       TestResult::Failed(_failure) => fmt_failed(),
       TestResult::Cancelled => fmt_cancelled(),
+      TestResult::Flaky(_) => fmt_flaky(),
     };
 
     self.print_status(status);
@@ -81,6 +84,10 @@ fn fmt_cancelled() -> String {
   colors::gray("!").to_string()
 }
 
+fn fmt_flaky() -> String {
+  colors::yellow(".").to_string()
+}
+
 #[allow(clippy::print_stdout)]
 impl TestReporter for DotTestReporter {
   fn report_register(&mut self, _description: &TestDescription) {}
@@ -103,10 +110,15 @@ impl TestReporter for DotTestReporter {
     result: &TestResult,
     _elapsed: u64,
   ) {
+    self.step_results.flush(description.id, &mut self.summary);
     match &result {
       TestResult::Ok => {
         self.summary.passed += 1;
       }
+      TestResult::Flaky(_) => {
+        self.summary.passed += 1;
+        self.summary.flaky += 1;
+      }
       TestResult::Ignored => {
         self.summary.ignored += 1;
       }
@@ -125,6 +137,10 @@ impl TestReporter for DotTestReporter {
     self.print_test_result(result);
   }
 
+  fn report_retry(&mut self, description: &TestDescription) {
+    self.step_results.discard(description.id);
+  }
+
   fn report_uncaught_error(&mut self, origin: &str, error: Box<JsError>) {
     self.summary.failed += 1;
     self
@@ -154,26 +170,7 @@ impl TestReporter for DotTestReporter {
     tests: &IndexMap<usize, TestDescription>,
     test_steps: &IndexMap<usize, TestStepDescription>,
   ) {
-    match &result {
-      TestStepResult::Ok => {
-        self.summary.passed_steps += 1;
-      }
-      TestStepResult::Ignored => {
-        self.summary.ignored_steps += 1;
-      }
-      TestStepResult::Failed(failure) => {
-        self.summary.failed_steps += 1;
-        self.summary.failures.push((
-          TestFailureDescription {
-            id: desc.id,
-            name: common::format_test_step_ancestry(desc, tests, test_steps),
-            origin: desc.origin.clone(),
-            location: desc.location.clone(),
-          },
-          failure.clone(),
-        ))
-      }
-    }
+    self.step_results.add(desc, result, tests, test_steps);
 
     self.print_test_step_result(result);
   }
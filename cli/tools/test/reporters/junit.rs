@@ -45,6 +45,19 @@ impl JunitTestReporter {
         description: None,
         reruns: vec![],
       },
+      TestResult::Flaky(failures) => quick_junit::TestCaseStatus::Success {
+        flaky_runs: failures
+          .iter()
+          .map(|failure| {
+            let mut rerun =
+              quick_junit::TestRerun::new(quick_junit::NonSuccessKind::Failure);
+            rerun
+              .set_message(failure.overview())
+              .set_description(failure.detail());
+            rerun
+          })
+          .collect(),
+      },
     }
   }
 
@@ -115,6 +128,8 @@ impl TestReporter for JunitTestReporter {
     }
   }
 
+  fn report_retry(&mut self, _description: &TestDescription) {}
+
   fn report_uncaught_error(&mut self, _origin: &str, _error: Box<JsError>) {}
 
   fn report_step_register(&mut self, description: &TestStepDescription) {
@@ -7,12 +7,14 @@ mod compound;
 mod dot;
 mod junit;
 mod pretty;
+mod slow;
 mod tap;
 
 pub use compound::CompoundTestReporter;
 pub use dot::DotTestReporter;
 pub use junit::JunitTestReporter;
 pub use pretty::PrettyTestReporter;
+pub use slow::SlowTestReporter;
 pub use tap::TapTestReporter;
 
 pub trait TestReporter {
@@ -26,6 +28,7 @@ pub trait TestReporter {
     result: &TestResult,
     elapsed: u64,
   );
+  fn report_retry(&mut self, description: &TestDescription);
   fn report_uncaught_error(&mut self, origin: &str, error: Box<JsError>);
   fn report_step_register(&mut self, description: &TestStepDescription);
   fn report_step_wait(&mut self, description: &TestStepDescription);
@@ -19,6 +19,7 @@ pub struct PrettyTestReporter {
   child_results_buffer:
     HashMap<usize, IndexMap<usize, (TestStepDescription, TestStepResult, u64)>>,
   summary: TestSummary,
+  step_results: common::StepResultsBuffer,
   writer: Box<dyn std::io::Write>,
 }
 
@@ -44,6 +45,7 @@ impl PrettyTestReporter {
       ended_tests: false,
       child_results_buffer: Default::default(),
       summary: TestSummary::new(),
+      step_results: Default::default(),
       writer: Box::new(std::io::stdout()),
     }
   }
@@ -234,10 +236,15 @@ impl TestReporter for PrettyTestReporter {
     result: &TestResult,
     elapsed: u64,
   ) {
+    self.step_results.flush(description.id, &mut self.summary);
     match &result {
       TestResult::Ok => {
         self.summary.passed += 1;
       }
+      TestResult::Flaky(_) => {
+        self.summary.passed += 1;
+        self.summary.flaky += 1;
+      }
       TestResult::Ignored => {
         self.summary.ignored += 1;
       }
@@ -267,6 +274,7 @@ impl TestReporter for PrettyTestReporter {
       TestResult::Ignored => colors::yellow("ignored").to_string(),
       TestResult::Failed(failure) => failure.format_label(),
       TestResult::Cancelled => colors::gray("cancelled").to_string(),
+      TestResult::Flaky(_) => colors::yellow("flaky").to_string(),
     };
     write!(&mut self.writer, " {}", status).unwrap();
     if let TestResult::Failed(failure) = result {
@@ -274,6 +282,11 @@ impl TestReporter for PrettyTestReporter {
         write!(&mut self.writer, " ({})", inline_summary).unwrap();
       }
     }
+    if let TestResult::Flaky(failures) = result {
+      let retries = failures.len();
+      let plural = if retries == 1 { "retry" } else { "retries" };
+      write!(&mut self.writer, " (passed after {retries} {plural})").unwrap();
+    }
     writeln!(
       &mut self.writer,
       " {}",
@@ -284,6 +297,10 @@ impl TestReporter for PrettyTestReporter {
     self.scope_test_id = None;
   }
 
+  fn report_retry(&mut self, description: &TestDescription) {
+    self.step_results.discard(description.id);
+  }
+
   fn report_uncaught_error(&mut self, origin: &str, error: Box<JsError>) {
     self.summary.failed += 1;
     self
@@ -321,26 +338,7 @@ impl TestReporter for PrettyTestReporter {
     tests: &IndexMap<usize, TestDescription>,
     test_steps: &IndexMap<usize, TestStepDescription>,
   ) {
-    match &result {
-      TestStepResult::Ok => {
-        self.summary.passed_steps += 1;
-      }
-      TestStepResult::Ignored => {
-        self.summary.ignored_steps += 1;
-      }
-      TestStepResult::Failed(failure) => {
-        self.summary.failed_steps += 1;
-        self.summary.failures.push((
-          TestFailureDescription {
-            id: desc.id,
-            name: common::format_test_step_ancestry(desc, tests, test_steps),
-            origin: desc.origin.clone(),
-            location: desc.location.clone(),
-          },
-          failure.clone(),
-        ))
-      }
-    }
+    self.step_results.add(desc, result, tests, test_steps);
 
     if self.parallel {
       self.write_output_end();
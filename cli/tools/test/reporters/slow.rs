@@ -0,0 +1,149 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use super::common;
+use super::*;
+
+/// Prints the slowest tests and steps after the summary with
+/// `--report-slow=N`.
+pub struct SlowTestReporter {
+  count: NonZeroUsize,
+  cwd: Url,
+  /// The names of the finished tests and steps with their durations.
+  durations: Vec<(String, u64)>,
+}
+
+impl SlowTestReporter {
+  pub fn new(count: NonZeroUsize, cwd: Url) -> Self {
+    Self {
+      count,
+      cwd,
+      durations: Vec::new(),
+    }
+  }
+
+  fn slowest(&self) -> Vec<&(String, u64)> {
+    let mut durations = self.durations.iter().collect::<Vec<_>>();
+    // stable so that tests with the same duration keep the order they ran in
+    durations.sort_by(|(_, a), (_, b)| b.cmp(a));
+    durations.truncate(self.count.get());
+    durations
+  }
+}
+
+#[allow(clippy::print_stdout)]
+impl TestReporter for SlowTestReporter {
+  fn report_register(&mut self, _description: &TestDescription) {}
+  fn report_plan(&mut self, _plan: &TestPlan) {}
+  fn report_wait(&mut self, _description: &TestDescription) {}
+  fn report_output(&mut self, _output: &[u8]) {}
+
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    if matches!(result, TestResult::Ignored | TestResult::Cancelled) {
+      return;
+    }
+    self.durations.push((
+      common::format_test_for_summary(&self.cwd, &description.into()),
+      elapsed,
+    ));
+  }
+
+  fn report_retry(&mut self, _description: &TestDescription) {}
+
+  fn report_uncaught_error(&mut self, _origin: &str, _error: Box<JsError>) {}
+  fn report_step_register(&mut self, _description: &TestStepDescription) {}
+  fn report_step_wait(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_result(
+    &mut self,
+    desc: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+    tests: &IndexMap<usize, TestDescription>,
+    test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    if matches!(result, TestStepResult::Ignored) {
+      return;
+    }
+    self.durations.push((
+      common::format_test_step_for_summary(&self.cwd, desc, tests, test_steps),
+      elapsed,
+    ));
+  }
+
+  fn report_summary(
+    &mut self,
+    _elapsed: &Duration,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    let slowest = self.slowest();
+    if slowest.is_empty() {
+      return;
+    }
+    println!("{}", colors::bold("slowest tests"));
+    for (name, elapsed) in slowest {
+      println!(
+        "{} {}",
+        name,
+        colors::gray(format!("({})", display::human_elapsed(*elapsed as u128)))
+      );
+    }
+    println!();
+  }
+
+  fn report_sigint(
+    &mut self,
+    _tests_pending: &HashSet<usize>,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+  }
+
+  fn report_completed(&mut self) {}
+
+  fn flush_report(
+    &mut self,
+    _elapsed: &Duration,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) -> anyhow::Result<()> {
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn keeps_the_slowest_in_order() {
+    let mut reporter = SlowTestReporter::new(
+      NonZeroUsize::new(2).unwrap(),
+      Url::parse("file:///").unwrap(),
+    );
+    reporter.durations = vec![
+      ("a".to_string(), 5),
+      ("b".to_string(), 20),
+      ("c".to_string(), 5),
+      ("d".to_string(), 10),
+    ];
+    assert_eq!(
+      reporter.slowest(),
+      vec![&("b".to_string(), 20), &("d".to_string(), 10)]
+    );
+    reporter.count = NonZeroUsize::new(10).unwrap();
+    assert_eq!(
+      reporter
+        .slowest()
+        .into_iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>(),
+      vec!["b", "d", "a", "c"]
+    );
+  }
+}
@@ -161,7 +161,7 @@ impl TestReporter for TapTestReporter {
     }
 
     let (status, directive) = match result {
-      TestResult::Ok => ("ok", ""),
+      TestResult::Ok | TestResult::Flaky(_) => ("ok", ""),
       TestResult::Ignored => ("ok", " # SKIP"),
       TestResult::Failed(_failure) => ("not ok", ""),
       TestResult::Cancelled => ("not ok", ""),
@@ -181,6 +181,10 @@ impl TestReporter for TapTestReporter {
     }
   }
 
+  fn report_retry(&mut self, description: &TestDescription) {
+    self.step_results.remove(&description.id);
+  }
+
   fn report_uncaught_error(&mut self, _origin: &str, _errorr: Box<JsError>) {}
 
   fn report_step_register(&mut self, _description: &TestStepDescription) {}
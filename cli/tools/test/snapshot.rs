@@ -0,0 +1,251 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Stores the snapshots of `TestContext.assertSnapshot()` in a
+//! `__snapshots__/<file name>.snap` file next to the test module.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::ModuleSpecifier;
+
+use crate::util::diff::diff;
+use crate::util::diff::DiffGranularity;
+use crate::util::diff::DiffOptions;
+use crate::util::fs::atomic_write_file;
+
+const SNAPSHOTS_DIR_NAME: &str = "__snapshots__";
+const HEADER: &str = "// Snapshot file generated by `deno test`.\n";
+
+pub struct SnapshotStore {
+  /// The path of the snapshot file, which is `None` for remote modules.
+  path: Option<PathBuf>,
+  update: bool,
+  /// The snapshots of the file, which is loaded on the first assertion.
+  snapshots: Option<BTreeMap<String, String>>,
+  changed: bool,
+}
+
+impl SnapshotStore {
+  pub fn new(specifier: &ModuleSpecifier, update: bool) -> Self {
+    let path = specifier.to_file_path().ok().and_then(|path| {
+      let file_name = path.file_name()?.to_string_lossy().into_owned();
+      Some(
+        path
+          .parent()?
+          .join(SNAPSHOTS_DIR_NAME)
+          .join(format!("{file_name}.snap")),
+      )
+    });
+    Self {
+      path,
+      update,
+      snapshots: None,
+      changed: false,
+    }
+  }
+
+  /// Compares the serialized value with the stored snapshot, returning the
+  /// message of the assertion error when it doesn't match. The snapshot is
+  /// written instead when updating the snapshots.
+  pub fn assert(
+    &mut self,
+    name: &str,
+    actual: &str,
+  ) -> Result<Option<String>, AnyError> {
+    let Some(path) = &self.path else {
+      bail!("Snapshots are only supported in local test modules.");
+    };
+    if self.snapshots.is_none() {
+      let snapshots = match std::fs::read_to_string(path) {
+        Ok(text) => parse_snapshots(&text).with_context(|| {
+          format!("Failed parsing snapshot file '{}'.", path.display())
+        })?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+          BTreeMap::new()
+        }
+        Err(err) => {
+          return Err(err).with_context(|| {
+            format!("Failed reading snapshot file '{}'.", path.display())
+          })
+        }
+      };
+      self.snapshots = Some(snapshots);
+    }
+    let snapshots = self.snapshots.as_mut().unwrap();
+    match snapshots.get(name) {
+      Some(expected) if expected == actual => Ok(None),
+      _ if self.update => {
+        snapshots.insert(name.to_string(), actual.to_string());
+        self.changed = true;
+        Ok(None)
+      }
+      Some(expected) => {
+        let diff = diff(
+          expected,
+          actual,
+          &DiffOptions {
+            granularity: DiffGranularity::Char,
+            context_lines: 2,
+          },
+        );
+        Ok(Some(format!(
+          "Snapshot \"{name}\" does not match:\n\n{diff}\nRun with --update-snapshots to update it."
+        )))
+      }
+      None => Ok(Some(format!(
+        "Missing snapshot \"{name}\". Run with --update-snapshots to create it."
+      ))),
+    }
+  }
+
+  /// Writes the snapshot file when any of its snapshots were updated.
+  pub fn save(&mut self) -> Result<(), AnyError> {
+    if !self.changed {
+      return Ok(());
+    }
+    let (Some(path), Some(snapshots)) = (&self.path, &self.snapshots) else {
+      return Ok(());
+    };
+    atomic_write_file(path, serialize_snapshots(snapshots), 0o644)
+      .with_context(|| {
+        format!("Failed writing snapshot file '{}'.", path.display())
+      })?;
+    self.changed = false;
+    Ok(())
+  }
+}
+
+/// Serializes the snapshots as template literals, which keeps multi-line
+/// values readable in reviews.
+fn serialize_snapshots(snapshots: &BTreeMap<String, String>) -> String {
+  let mut text = HEADER.to_string();
+  for (name, value) in snapshots {
+    text.push_str(&format!(
+      "\nsnapshot[`{}`] = `\n{}\n`;\n",
+      escape(name),
+      escape(value)
+    ));
+  }
+  text
+}
+
+fn parse_snapshots(text: &str) -> Result<BTreeMap<String, String>, AnyError> {
+  let mut snapshots = BTreeMap::new();
+  let mut rest = text.strip_prefix(HEADER).unwrap_or(text);
+  loop {
+    rest = rest.trim_start();
+    if rest.is_empty() {
+      return Ok(snapshots);
+    }
+    let Some(after_start) = rest.strip_prefix("snapshot[`") else {
+      bail!("Expected a snapshot entry.");
+    };
+    let (name, after_name) = parse_template(after_start)?;
+    let Some(after_assign) = after_name.strip_prefix("] = `\n") else {
+      bail!("Expected the value of snapshot \"{name}\".");
+    };
+    let (value, after_value) = parse_template(after_assign)?;
+    let Some(after_end) = after_value.strip_prefix(';') else {
+      bail!("Expected a semicolon after snapshot \"{name}\".");
+    };
+    let value = value.strip_suffix('\n').unwrap_or(&value).to_string();
+    snapshots.insert(name, value);
+    rest = after_end;
+  }
+}
+
+/// Parses the text of a template literal up to its closing backtick,
+/// returning the unescaped text and the text after the backtick.
+fn parse_template(text: &str) -> Result<(String, &str), AnyError> {
+  let mut value = String::new();
+  let mut chars = text.char_indices();
+  while let Some((index, c)) = chars.next() {
+    match c {
+      '`' => return Ok((value, &text[index + 1..])),
+      '\\' => match chars.next() {
+        Some((_, c)) => value.push(c),
+        None => break,
+      },
+      c => value.push(c),
+    }
+  }
+  bail!("Unterminated template literal.")
+}
+
+fn escape(text: &str) -> String {
+  text
+    .replace('\\', "\\\\")
+    .replace('`', "\\`")
+    .replace("${", "\\${")
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn serializes_and_parses_snapshots() {
+    let mut snapshots = BTreeMap::new();
+    snapshots.insert("basic 1".to_string(), "{ a: 1 }".to_string());
+    snapshots.insert(
+      "`escaped` \\ ${name} 1".to_string(),
+      "[\n  \"`${a}`\",\n  \"\\\\\",\n]".to_string(),
+    );
+    snapshots.insert("empty 1".to_string(), String::new());
+    let text = serialize_snapshots(&snapshots);
+    assert_eq!(parse_snapshots(&text).unwrap(), snapshots);
+  }
+
+  #[test]
+  fn serializes_snapshots() {
+    let mut snapshots = BTreeMap::new();
+    snapshots.insert("basic 1".to_string(), "{\n  a: 1,\n}".to_string());
+    assert_eq!(
+      serialize_snapshots(&snapshots),
+      concat!(
+        "// Snapshot file generated by `deno test`.\n",
+        "\n",
+        "snapshot[`basic 1`] = `\n",
+        "{\n",
+        "  a: 1,\n",
+        "}\n",
+        "`;\n",
+      )
+    );
+  }
+
+  #[test]
+  fn parse_errors() {
+    assert!(parse_snapshots("snapshot[`a`] = `\nvalue").is_err());
+    assert!(parse_snapshots("snapshot[`a`] = 1;").is_err());
+    assert!(parse_snapshots("export const a = 1;").is_err());
+  }
+
+  #[test]
+  fn asserts_snapshots() {
+    let temp_dir = test_util::TempDir::new();
+    let specifier =
+      ModuleSpecifier::from_file_path(temp_dir.path().join("a_test.ts"))
+        .unwrap();
+
+    let mut store = SnapshotStore::new(&specifier, false);
+    let message = store.assert("a 1", "1").unwrap().unwrap();
+    assert!(message.starts_with("Missing snapshot \"a 1\"."));
+    store.save().unwrap();
+    assert!(!temp_dir.path().join("__snapshots__").exists());
+
+    let mut store = SnapshotStore::new(&specifier, true);
+    assert_eq!(store.assert("a 1", "1").unwrap(), None);
+    store.save().unwrap();
+    let snapshot_path = temp_dir.path().join("__snapshots__/a_test.ts.snap");
+    assert!(snapshot_path.exists());
+
+    let mut store = SnapshotStore::new(&specifier, false);
+    assert_eq!(store.assert("a 1", "1").unwrap(), None);
+    let message = store.assert("a 1", "2").unwrap().unwrap();
+    assert!(message.starts_with("Snapshot \"a 1\" does not match:"));
+  }
+}
@@ -0,0 +1,159 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Enforces the timeout of a test that blocks its thread with synchronous
+//! code, which can't be raced with a timer on the event loop.
+
+use std::ffi::c_void;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use deno_core::parking_lot::Mutex;
+use deno_core::v8;
+use deno_core::JsRuntime;
+
+/// The maximum number of stack frames sampled when a test times out.
+const STACK_FRAME_LIMIT: usize = 10;
+
+#[derive(Default)]
+struct WatchdogState {
+  armed: AtomicBool,
+  /// The stack sampled when the test was terminated.
+  stack: Mutex<Option<Vec<String>>>,
+}
+
+/// The context that the stack of a terminated test is sampled in, stored in
+/// a slot of the isolate because interrupts don't enter one.
+struct WatchdogContext(v8::Global<v8::Context>);
+
+/// Terminates the JavaScript execution of the isolate once the timeout of
+/// the running test elapses, sampling the stack of where it was stuck. A
+/// single thread watches all the tests of a worker.
+pub struct TestWatchdog {
+  // dropping this stops the thread of the watchdog
+  sender: mpsc::Sender<Option<(Duration, Arc<WatchdogState>)>>,
+}
+
+impl TestWatchdog {
+  pub fn new(js_runtime: &mut JsRuntime) -> Self {
+    let context = WatchdogContext(js_runtime.main_context());
+    let isolate = js_runtime.v8_isolate();
+    isolate.set_slot(context);
+    let isolate_handle = isolate.thread_safe_handle();
+    let (sender, receiver) =
+      mpsc::channel::<Option<(Duration, Arc<WatchdogState>)>>();
+    std::thread::spawn(move || {
+      let mut pending: Option<(Instant, Arc<WatchdogState>)> = None;
+      loop {
+        let message = match &pending {
+          Some((deadline, _)) => receiver
+            .recv_timeout(deadline.saturating_duration_since(Instant::now())),
+          None => receiver
+            .recv()
+            .map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+        };
+        match message {
+          Ok(Some((timeout, state))) => {
+            pending = Some((Instant::now() + timeout, state));
+          }
+          Ok(None) => pending = None,
+          Err(mpsc::RecvTimeoutError::Timeout) => {
+            let Some((_, state)) = pending.take() else {
+              continue;
+            };
+            let data = Arc::into_raw(state) as *mut c_void;
+            if !isolate_handle.request_interrupt(interrupt_callback, data) {
+              // SAFETY: the isolate was disposed, so the callback won't run
+              // and the reference is still owned here
+              drop(unsafe { Arc::from_raw(data as *const WatchdogState) });
+              return;
+            }
+          }
+          Err(mpsc::RecvTimeoutError::Disconnected) => return,
+        }
+      }
+    });
+    Self { sender }
+  }
+
+  /// Starts watching a test until the returned timer is stopped.
+  pub fn start(&self, timeout: Duration) -> WatchdogTimer {
+    let state = Arc::new(WatchdogState::default());
+    state.armed.store(true, Ordering::SeqCst);
+    // the thread only stops early when the isolate was disposed
+    _ = self.sender.send(Some((timeout, state.clone())));
+    WatchdogTimer {
+      state,
+      sender: self.sender.clone(),
+    }
+  }
+}
+
+/// The timeout of a single test.
+pub struct WatchdogTimer {
+  state: Arc<WatchdogState>,
+  sender: mpsc::Sender<Option<(Duration, Arc<WatchdogState>)>>,
+}
+
+impl WatchdogTimer {
+  /// Stops watching the test, returning the sampled stack when the
+  /// watchdog terminated its execution.
+  pub fn stop(self) -> Option<Vec<String>> {
+    // the interrupt runs on the thread of the isolate, so it can't fire
+    // during a later test once this is unset
+    self.state.armed.store(false, Ordering::SeqCst);
+    _ = self.sender.send(None);
+    self.state.stack.lock().take()
+  }
+}
+
+extern "C" fn interrupt_callback(isolate: &mut v8::Isolate, data: *mut c_void) {
+  // SAFETY: the pointer was created by `Arc::into_raw` in the watchdog
+  let state = unsafe { Arc::from_raw(data as *const WatchdogState) };
+  if !state.armed.swap(false, Ordering::SeqCst) {
+    return;
+  }
+  // SAFETY: interrupts run on the thread of the isolate while it executes
+  let scope = &mut unsafe { v8::CallbackScope::new(isolate) };
+  let scope = &mut v8::HandleScope::new(scope);
+  let Some(context) = scope
+    .get_slot::<WatchdogContext>()
+    .map(|WatchdogContext(context)| context.clone())
+  else {
+    scope.terminate_execution();
+    return;
+  };
+  let context = v8::Local::new(scope, context);
+  let scope = &mut v8::ContextScope::new(scope, context);
+  let mut stack = Vec::new();
+  if let Some(trace) =
+    v8::StackTrace::current_stack_trace(scope, STACK_FRAME_LIMIT)
+  {
+    for index in 0..trace.get_frame_count() {
+      let Some(frame) = trace.get_frame(scope, index) else {
+        continue;
+      };
+      let script_name = frame
+        .get_script_name(scope)
+        .map(|name| name.to_rust_string_lossy(scope))
+        .unwrap_or_else(|| "<anonymous>".to_string());
+      let location = format!(
+        "{}:{}:{}",
+        script_name,
+        frame.get_line_number(),
+        frame.get_column()
+      );
+      stack.push(match frame.get_function_name(scope) {
+        Some(name) if name.length() > 0 => {
+          format!("{} ({})", name.to_rust_string_lossy(scope), location)
+        }
+        _ => location,
+      });
+    }
+  }
+  *state.stack.lock() = Some(stack);
+  scope.terminate_execution();
+}
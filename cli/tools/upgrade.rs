@@ -8,6 +8,7 @@ use crate::colors;
 use crate::factory::CliFactory;
 use crate::http_util::HttpClient;
 use crate::standalone::binary::unpack_into_dir;
+use crate::util::checksum;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::ProgressBarStyle;
 use crate::util::time;
@@ -379,7 +380,10 @@ pub async fn upgrade(
   let output_exe_path =
     full_path_output_flag.as_ref().unwrap_or(&current_exe_path);
 
-  let permissions = if let Ok(metadata) = fs::metadata(output_exe_path) {
+  let permissions = if upgrade_flags.download_only {
+    // the output is a directory of release archives in this case
+    fs::metadata(&current_exe_path)?.permissions()
+  } else if let Ok(metadata) = fs::metadata(output_exe_path) {
     let permissions = metadata.permissions();
     if permissions.readonly() {
       bail!(
@@ -427,6 +431,7 @@ pub async fn upgrade(
       };
 
       if !upgrade_flags.force
+        && !upgrade_flags.download_only
         && full_path_output_flag.is_none()
         && current_is_passed
       {
@@ -461,6 +466,7 @@ pub async fn upgrade(
       };
 
       if !upgrade_flags.force
+        && !upgrade_flags.download_only
         && full_path_output_flag.is_none()
         && current_is_most_recent
       {
@@ -480,17 +486,26 @@ pub async fn upgrade(
     }
   };
 
-  let download_url = if upgrade_flags.canary {
-    format!(
-      "https://dl.deno.land/canary/{}/{}",
-      install_version, *ARCHIVE_NAME
-    )
-  } else {
-    format!(
-      "{}/download/v{}/{}",
-      RELEASE_URL, install_version, *ARCHIVE_NAME
+  if upgrade_flags.download_only {
+    let output_dir = full_path_output_flag
+      .unwrap_or_else(|| factory.cli_options().initial_cwd().to_path_buf());
+    let targets = if upgrade_flags.targets.is_empty() {
+      vec![env!("TARGET").to_string()]
+    } else {
+      upgrade_flags.targets
+    };
+    return download_release_archives(
+      client,
+      &install_version,
+      upgrade_flags.canary,
+      &targets,
+      &output_dir,
     )
-  };
+    .await;
+  }
+
+  let download_url =
+    get_download_url(&install_version, upgrade_flags.canary, &ARCHIVE_NAME);
 
   let archive_data = download_package(client, &download_url)
     .await
@@ -555,6 +570,108 @@ pub async fn upgrade(
   Ok(())
 }
 
+fn get_download_url(
+  install_version: &str,
+  canary: bool,
+  archive_name: &str,
+) -> String {
+  if canary {
+    format!(
+      "https://dl.deno.land/canary/{}/{}",
+      install_version, archive_name
+    )
+  } else {
+    format!(
+      "{}/download/v{}/{}",
+      RELEASE_URL, install_version, archive_name
+    )
+  }
+}
+
+/// Downloads the release archives of the provided targets into the output
+/// directory without replacing the current executable. The archives are
+/// verified against the checksums published with the release and a
+/// `SHA256SUMS` file is written next to them so they can be verified again
+/// once distributed.
+async fn download_release_archives(
+  client: &HttpClient,
+  install_version: &str,
+  canary: bool,
+  targets: &[String],
+  output_dir: &Path,
+) -> Result<(), AnyError> {
+  fs::create_dir_all(output_dir).with_context(|| {
+    format!("Failed creating directory {}", output_dir.display())
+  })?;
+  let mut checksum_lines = Vec::with_capacity(targets.len());
+  for target in targets {
+    let archive_name = format!("deno-{}.zip", target);
+    let download_url = get_download_url(install_version, canary, &archive_name);
+    let archive_data = download_package(client, &download_url)
+      .await
+      .with_context(|| format!("Failed downloading {download_url}. The version you requested may not have been built for {target}."))?;
+
+    let actual_checksum = checksum::gen(&[&archive_data]);
+    match client
+      .download_text(format!("{download_url}.sha256sum"))
+      .await
+    {
+      Ok(text) => {
+        let Some(expected_checksum) = checksum::parse_checksum_file(&text)
+        else {
+          bail!("Failed parsing the published checksum of {archive_name}");
+        };
+        if expected_checksum != actual_checksum {
+          bail!(
+            concat!(
+              "The checksum of {} did not match the published checksum.\n",
+              "  Expected: {}\n",
+              "  Actual: {}",
+            ),
+            archive_name,
+            expected_checksum,
+            actual_checksum,
+          );
+        }
+      }
+      Err(err) => {
+        log::warn!(
+          "{} No checksum was published for {}, so it could not be verified: {:#}",
+          colors::yellow("Warning"),
+          archive_name,
+          err,
+        );
+      }
+    }
+
+    // the executable can only be checked when it's for the current target
+    if target == env!("TARGET") {
+      let temp_dir = tempfile::TempDir::new()?;
+      let exe_path = unpack_into_dir(
+        "deno",
+        &archive_name,
+        archive_data.clone(),
+        cfg!(windows),
+        &temp_dir,
+      )?;
+      check_exe(&exe_path)?;
+    }
+
+    let archive_path = output_dir.join(&archive_name);
+    fs::write(&archive_path, &archive_data)
+      .with_context(|| format!("Failed writing {}", archive_path.display()))?;
+    checksum_lines.push(format!("{}  {}\n", actual_checksum, archive_name));
+  }
+  fs::write(output_dir.join("SHA256SUMS"), checksum_lines.concat())?;
+
+  log::info!(
+    "Downloaded Deno {} to {}",
+    install_version,
+    output_dir.display()
+  );
+  Ok(())
+}
+
 #[derive(Debug, Clone, Copy)]
 enum UpgradeReleaseKind {
   Stable,
@@ -615,7 +732,7 @@ async fn download_package(
 ) -> Result<Vec<u8>, AnyError> {
   log::info!("Downloading {}", &download_url);
   let maybe_bytes = {
-    let progress_bar = ProgressBar::new(ProgressBarStyle::DownloadBars);
+    let progress_bar = ProgressBar::new(ProgressBarStyle::DownloadBytes);
     // provide an empty string here in order to prefer the downloading
     // text above which will stay alive after the progress bars are complete
     let progress = progress_bar.update("");
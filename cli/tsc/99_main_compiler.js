@@ -1008,19 +1008,32 @@ delete Object.prototype.__proto__;
         })
       : undefined;
 
-    if (checkFiles != null) {
-      // When calling program.getSemanticDiagnostics(...) with a source file, we
-      // need to call this code first in order to get it to invalidate cached
-      // diagnostics correctly. This is what program.getSemanticDiagnostics()
-      // does internally when calling without any arguments.
-      const checkFileNames = new Set(checkFiles.map((f) => f.fileName));
-      while (
-        program.getSemanticDiagnosticsOfNextAffectedFile(
-          undefined,
-          /* ignoreSourceFile */ (s) => !checkFileNames.has(s.fileName),
-        )
-      ) {
-        // keep going until there are no more affected files
+    // When calling program.getSemanticDiagnostics(...) with a source file, we
+    // need to call this code first in order to get it to invalidate cached
+    // diagnostics correctly. This is what program.getSemanticDiagnostics()
+    // does internally when calling without any arguments. Doing it here for
+    // all the files also allows reporting the progress of the check.
+    const checkFileNames = checkFiles == null
+      ? undefined
+      : new Set(checkFiles.map((f) => f.fileName));
+    const totalFiles = checkFileNames?.size ??
+      program.getSourceFiles().length;
+    let checkedFiles = 0;
+    let affectedResult;
+    while (
+      (affectedResult = program.getSemanticDiagnosticsOfNextAffectedFile(
+        undefined,
+        /* ignoreSourceFile */ checkFileNames == null
+          ? undefined
+          : (s) => !checkFileNames.has(s.fileName),
+      ))
+    ) {
+      // the affected item is the whole program when everything needs to
+      // be checked, in which case there's no single file to report
+      const fileName = affectedResult.affected?.fileName;
+      if (fileName != null) {
+        checkedFiles = Math.min(checkedFiles + 1, totalFiles);
+        ops.op_check_progress(checkedFiles, totalFiles, fileName);
       }
     }
 
@@ -691,6 +691,22 @@ declare namespace Deno {
      * will be set here. */
     parent?: TestContext;
 
+    /** Assert that the value matches its snapshot, which is stored in a
+     * `__snapshots__` directory next to the test module. The snapshot is
+     * named after the test and the number of the assertion within it, unless
+     * a name is provided.
+     *
+     * Run `deno test --update-snapshots` to write the snapshots that are
+     * missing or don't match.
+     *
+     * ```ts
+     * Deno.test("config", (t) => {
+     *   t.assertSnapshot({ port: 8000, hostname: "localhost" });
+     * });
+     * ```
+     */
+    assertSnapshot(actual: unknown, options?: { name?: string }): void;
+
     /** Run a sub step of the parent test or step. Returns a promise
      * that resolves to a boolean signifying if the step completed successfully.
      *
@@ -797,6 +813,9 @@ declare namespace Deno {
      *
      * Defaults to the parent test or step's value. */
     sanitizeExit?: boolean;
+    /** The number of milliseconds after which the step fails if it didn't
+     * complete. */
+    timeout?: number;
   }
 
   /** @category Testing */
@@ -840,6 +859,16 @@ declare namespace Deno {
      *
      * @default {"inherit"} */
     permissions?: PermissionOptions;
+    /** The number of times the test is run again when it fails. A test that
+     * passes on a retry is reported as flaky.
+     *
+     * Defaults to the value of the `--retries` flag. */
+    retries?: number;
+    /** The number of milliseconds after which the test fails if it didn't
+     * complete.
+     *
+     * Defaults to the value of the `--timeout` flag. */
+    timeout?: number;
   }
 
   /** Register a test which will be run when `deno test` is used on the command
@@ -1253,6 +1282,22 @@ declare namespace Deno {
      * @default {"inherit"}
      */
     permissions?: PermissionOptions;
+    /** The time in milliseconds the bench is run before being measured,
+     * which overrides `deno bench --warmup-time`.
+     *
+     * @default {10} */
+    warmupTime?: number;
+    /** The minimum number of measured iterations of the bench, which must
+     * be at least 1 and overrides `deno bench --min-iterations`.
+     *
+     * @default {10} */
+    minIterations?: number;
+    /** The minimum time in milliseconds the bench is measured for, which
+     * overrides `deno bench --max-time`. The bench is measured until both
+     * this time has passed and the minimum number of iterations ran.
+     *
+     * @default {500} */
+    maxTime?: number;
   }
 
   /**
@@ -7,6 +7,9 @@ use crate::node;
 use crate::npm::CliNpmResolver;
 use crate::util::checksum;
 use crate::util::path::mapped_specifier_for_tsc;
+use crate::util::progress_bar::ProgressBar;
+use crate::util::progress_bar::ProgressMessagePrompt;
+use crate::util::progress_bar::UpdateGuard;
 
 use deno_ast::MediaType;
 use deno_core::anyhow::anyhow;
@@ -116,6 +119,7 @@ fn get_asset_texts_from_new_runtime() -> Result<Vec<AssetText>, AnyError> {
   deno_core::extension!(
     deno_cli_tsc,
     ops = [
+      op_check_progress,
       op_create_hash,
       op_emit,
       op_is_node_file,
@@ -319,6 +323,8 @@ pub struct Request {
   /// program.
   pub root_names: Vec<(ModuleSpecifier, MediaType)>,
   pub check_mode: TypeCheckMode,
+  /// Progress bar to report the files being type checked to.
+  pub maybe_progress_bar: Option<ProgressBar>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -343,6 +349,8 @@ struct State {
   remapped_specifiers: HashMap<String, ModuleSpecifier>,
   root_map: HashMap<String, ModuleSpecifier>,
   current_dir: PathBuf,
+  maybe_progress_bar: Option<ProgressBar>,
+  maybe_progress_guard: Option<UpdateGuard>,
 }
 
 impl Default for State {
@@ -356,11 +364,14 @@ impl Default for State {
       remapped_specifiers: Default::default(),
       root_map: Default::default(),
       current_dir: Default::default(),
+      maybe_progress_bar: Default::default(),
+      maybe_progress_guard: Default::default(),
     }
   }
 }
 
 impl State {
+  #[allow(clippy::too_many_arguments)]
   pub fn new(
     graph: Arc<ModuleGraph>,
     hash_data: u64,
@@ -369,6 +380,7 @@ impl State {
     root_map: HashMap<String, ModuleSpecifier>,
     remapped_specifiers: HashMap<String, ModuleSpecifier>,
     current_dir: PathBuf,
+    maybe_progress_bar: Option<ProgressBar>,
   ) -> Self {
     State {
       hash_data,
@@ -379,6 +391,8 @@ impl State {
       remapped_specifiers,
       root_map,
       current_dir,
+      maybe_progress_bar,
+      maybe_progress_guard: None,
     }
   }
 }
@@ -831,6 +845,33 @@ struct RespondArgs {
   pub stats: Stats,
 }
 
+/// Reports the file that is currently being type checked along with the
+/// number of files checked so far.
+#[op2(fast)]
+fn op_check_progress(
+  state: &mut OpState,
+  #[smi] checked: u32,
+  #[smi] total: u32,
+  #[string] specifier: &str,
+) {
+  let state = state.borrow_mut::<State>();
+  let Some(progress_bar) = &state.maybe_progress_bar else {
+    return;
+  };
+  let specifier = state
+    .remapped_specifiers
+    .get(specifier)
+    .or_else(|| state.root_map.get(specifier))
+    .map(|s| s.as_str())
+    .unwrap_or(specifier);
+  let guard = state.maybe_progress_guard.get_or_insert_with(|| {
+    progress_bar.update_with_prompt(ProgressMessagePrompt::Check, "")
+  });
+  guard.set_message(specifier);
+  guard.set_total_size(total as u64);
+  guard.set_position(checked as u64);
+}
+
 // TODO(bartlomieju): this mechanism is questionable.
 // Can't we use something more efficient here?
 #[op2]
@@ -899,6 +940,7 @@ pub fn exec(request: Request) -> Result<Response, AnyError> {
         std::env::current_dir()
           .context("Unable to get CWD")
           .unwrap(),
+        options.request.maybe_progress_bar,
       ));
     },
   );
@@ -1007,6 +1049,7 @@ mod tests {
       std::env::current_dir()
         .context("Unable to get CWD")
         .unwrap(),
+      None,
     );
     let mut op_state = OpState::new(None);
     op_state.put(state);
@@ -1049,6 +1092,7 @@ mod tests {
       maybe_tsbuildinfo: None,
       root_names: vec![(specifier.clone(), MediaType::TypeScript)],
       check_mode: TypeCheckMode::All,
+      maybe_progress_bar: None,
     };
     exec(request)
   }
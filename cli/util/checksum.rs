@@ -1,15 +1,119 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::io::Read;
+
 use ring::digest::Context;
+use ring::digest::SHA1_FOR_LEGACY_USE_ONLY;
 use ring::digest::SHA256;
+use ring::digest::SHA512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+  /// Only for verifying the legacy integrity of npm packages.
+  Sha1,
+  /// Used for checksums that are persisted or compared with published
+  /// checksums (ex. the lockfile).
+  Sha256,
+  /// Used by the integrity of npm packages.
+  Sha512,
+  /// Much faster, but only suitable for checksums that don't leave the
+  /// process or a cache that can be recreated.
+  Blake3,
+}
+
+/// Incrementally computes a checksum, so large data can be hashed as it's
+/// read instead of loading it fully into memory. This also implements
+/// `std::io::Write` for use with `std::io::copy`.
+pub enum ChecksumHasher {
+  Ring(Box<Context>),
+  Blake3(Box<blake3::Hasher>),
+}
+
+impl ChecksumHasher {
+  pub fn new(algorithm: ChecksumAlgorithm) -> Self {
+    match algorithm {
+      ChecksumAlgorithm::Sha1 => {
+        Self::Ring(Box::new(Context::new(&SHA1_FOR_LEGACY_USE_ONLY)))
+      }
+      ChecksumAlgorithm::Sha256 => Self::Ring(Box::new(Context::new(&SHA256))),
+      ChecksumAlgorithm::Sha512 => Self::Ring(Box::new(Context::new(&SHA512))),
+      ChecksumAlgorithm::Blake3 => Self::Blake3(Default::default()),
+    }
+  }
+
+  pub fn update(&mut self, data: &[u8]) {
+    match self {
+      Self::Ring(ctx) => ctx.update(data),
+      Self::Blake3(hasher) => {
+        hasher.update(data);
+      }
+    }
+  }
+
+  /// Gets the raw bytes of the checksum.
+  pub fn finish_bytes(self) -> Vec<u8> {
+    match self {
+      Self::Ring(ctx) => ctx.finish().as_ref().to_vec(),
+      Self::Blake3(hasher) => hasher.finalize().as_bytes().to_vec(),
+    }
+  }
+
+  /// Gets the hex encoded checksum.
+  pub fn finish(self) -> String {
+    faster_hex::hex_string(&self.finish_bytes())
+  }
+}
+
+impl std::io::Write for ChecksumHasher {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    self.update(buf);
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    Ok(())
+  }
+}
 
 /// Generate a SHA256 checksum of a slice of byte-slice-like things.
 pub fn gen(v: &[impl AsRef<[u8]>]) -> String {
-  let mut ctx = Context::new(&SHA256);
+  gen_with_algorithm(ChecksumAlgorithm::Sha256, v)
+}
+
+/// Generate a checksum of a slice of byte-slice-like things with the
+/// provided algorithm.
+pub fn gen_with_algorithm(
+  algorithm: ChecksumAlgorithm,
+  v: &[impl AsRef<[u8]>],
+) -> String {
+  let mut hasher = ChecksumHasher::new(algorithm);
   for src in v {
-    ctx.update(src.as_ref());
+    hasher.update(src.as_ref());
   }
-  faster_hex::hex_string(ctx.finish().as_ref())
+  hasher.finish()
+}
+
+/// Generate the hex encoded checksum of everything read from the reader,
+/// without loading it into memory.
+pub fn gen_from_reader(
+  algorithm: ChecksumAlgorithm,
+  mut reader: impl Read,
+) -> std::io::Result<String> {
+  let mut hasher = ChecksumHasher::new(algorithm);
+  std::io::copy(&mut reader, &mut hasher)?;
+  Ok(hasher.finish())
+}
+
+/// Gets the hash from the contents of a checksum file published with a
+/// release, which is either in the format output by `sha256sum` or by
+/// PowerShell's `Get-FileHash`.
+pub fn parse_checksum_file(text: &str) -> Option<String> {
+  text
+    .split_whitespace()
+    .find(|word| {
+      word.len() == 64 && word.chars().all(|c| c.is_ascii_hexdigit())
+    })
+    .map(|hash| hash.to_ascii_lowercase())
 }
 
 #[cfg(test)]
@@ -24,4 +128,50 @@ mod tests {
       "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
     );
   }
+
+  #[test]
+  fn test_gen_blake3() {
+    let actual =
+      gen_with_algorithm(ChecksumAlgorithm::Blake3, &["hello", " world"]);
+    assert_eq!(
+      actual,
+      "d74981efa70a0c880b8d8c1985d075dbcbf679b99a5f9914e5aaf96b831a9e24"
+    );
+  }
+
+  #[test]
+  fn test_gen_from_reader() {
+    let data = vec![7u8; 1024 * 1024 + 3];
+    for algorithm in [
+      ChecksumAlgorithm::Sha1,
+      ChecksumAlgorithm::Sha256,
+      ChecksumAlgorithm::Sha512,
+      ChecksumAlgorithm::Blake3,
+    ] {
+      assert_eq!(
+        gen_from_reader(algorithm, data.as_slice()).unwrap(),
+        gen_with_algorithm(algorithm, &[&data]),
+      );
+    }
+  }
+
+  #[test]
+  fn test_parse_checksum_file() {
+    let hash =
+      "3a8d1d2bae4d01bf4a7bf6c1e4aeaa3b9d32e7ecf7b6f0f2a8e0a9c2c9f0e1d2";
+    assert_eq!(
+      parse_checksum_file(&format!(
+        "{hash}  deno-x86_64-unknown-linux-gnu.zip\n"
+      )),
+      Some(hash.to_string())
+    );
+    assert_eq!(
+      parse_checksum_file(&format!(
+        "Algorithm : SHA256\r\nHash      : {}\r\nPath      : deno.zip\r\n",
+        hash.to_ascii_uppercase()
+      )),
+      Some(hash.to_string())
+    );
+    assert_eq!(parse_checksum_file("Not Found"), None);
+  }
 }
@@ -3,12 +3,30 @@
 use crate::colors;
 use dissimilar::diff as difference;
 use dissimilar::Chunk;
+use std::collections::VecDeque;
 use std::fmt::Write as _;
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiffGranularity {
+  /// Highlights the changed characters within a line.
+  #[default]
+  Char,
+  /// Highlights whole words within a line, which is easier to read when
+  /// a small change is made in a long line.
+  Word,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DiffOptions {
+  pub granularity: DiffGranularity,
+  /// Number of unchanged lines to show before and after each change.
+  pub context_lines: usize,
+}
+
 /// Print diff of the same file_path, before and after formatting.
 ///
 /// Diff format is loosely based on GitHub diff formatting.
-pub fn diff(orig_text: &str, edit_text: &str) -> String {
+pub fn diff(orig_text: &str, edit_text: &str, options: &DiffOptions) -> String {
   if orig_text == edit_text {
     return String::new();
   }
@@ -21,7 +39,7 @@ pub fn diff(orig_text: &str, edit_text: &str) -> String {
     return " | Text differed by line endings.\n".to_string();
   }
 
-  DiffBuilder::build(&orig_text, &edit_text)
+  DiffBuilder::build(&orig_text, &edit_text, options)
 }
 
 struct DiffBuilder {
@@ -31,17 +49,29 @@ struct DiffBuilder {
   edit_line: usize,
   orig: String,
   edit: String,
+  /// Text of the current line when it has no changes.
+  unchanged: String,
   has_changes: bool,
+  context_lines: usize,
+  /// Unchanged lines that might be shown before the next change.
+  leading_context: VecDeque<(usize, String)>,
+  /// Number of unchanged lines left to show after the last change.
+  trailing_context: usize,
 }
 
 impl DiffBuilder {
-  pub fn build(orig_text: &str, edit_text: &str) -> String {
+  pub fn build(
+    orig_text: &str,
+    edit_text: &str,
+    options: &DiffOptions,
+  ) -> String {
     let mut diff_builder = DiffBuilder {
       output: String::new(),
       orig_line: 1,
       edit_line: 1,
       orig: String::new(),
       edit: String::new(),
+      unchanged: String::new(),
       has_changes: false,
       line_number_width: {
         let line_count = std::cmp::max(
@@ -50,9 +80,15 @@ impl DiffBuilder {
         );
         line_count.to_string().chars().count()
       },
+      context_lines: options.context_lines,
+      leading_context: VecDeque::with_capacity(options.context_lines),
+      trailing_context: 0,
     };
 
-    let chunks = difference(orig_text, edit_text);
+    let chunks = match options.granularity {
+      DiffGranularity::Char => difference(orig_text, edit_text),
+      DiffGranularity::Word => word_difference(orig_text, edit_text),
+    };
     diff_builder.handle_chunks(chunks);
     diff_builder.output
   }
@@ -88,6 +124,7 @@ impl DiffBuilder {
             }
             self.orig.push_str(&fmt_rem_text(s));
             self.edit.push_str(&fmt_add_text(s));
+            self.unchanged.push_str(s);
           }
         }
       }
@@ -98,18 +135,48 @@ impl DiffBuilder {
 
   fn flush_changes(&mut self) {
     if self.has_changes {
+      for (line_number, text) in std::mem::take(&mut self.leading_context) {
+        self.write_context_line(line_number, &text);
+      }
       self.write_line_diff();
+      self.trailing_context = self.context_lines;
 
       self.orig_line += self.orig.split('\n').count();
       self.edit_line += self.edit.split('\n').count();
       self.has_changes = false;
     } else {
+      if self.trailing_context > 0 {
+        self.trailing_context -= 1;
+        let text = std::mem::take(&mut self.unchanged);
+        self.write_context_line(self.edit_line, &text);
+      } else if self.context_lines > 0 {
+        if self.leading_context.len() == self.context_lines {
+          self.leading_context.pop_front();
+        }
+        self
+          .leading_context
+          .push_back((self.edit_line, std::mem::take(&mut self.unchanged)));
+      }
       self.orig_line += 1;
       self.edit_line += 1;
     }
 
     self.orig.clear();
     self.edit.clear();
+    self.unchanged.clear();
+  }
+
+  fn write_context_line(&mut self, line_number: usize, text: &str) {
+    write!(
+      self.output,
+      "{:width$}{}  {}",
+      line_number,
+      colors::gray(" |"),
+      text,
+      width = self.line_number_width
+    )
+    .unwrap();
+    self.output.push('\n');
   }
 
   fn write_line_diff(&mut self) {
@@ -145,6 +212,319 @@ impl DiffBuilder {
   }
 }
 
+/// Creates a unified diff of the changes that can be applied with
+/// `git apply` or `patch -p1`. The file name is used in the headers with
+/// the `a/` and `b/` prefixes git uses.
+pub fn unified_diff(
+  file_name: &str,
+  orig_text: &str,
+  edit_text: &str,
+  context_lines: usize,
+) -> String {
+  if orig_text == edit_text {
+    return String::new();
+  }
+
+  // the lines with their tag, where the removed lines of each change come
+  // before the added lines like in the output of git
+  let mut lines: Vec<(char, &str)> = Vec::new();
+  let mut added_lines = Vec::new();
+  for chunk in line_difference(orig_text, edit_text) {
+    let (tag, text) = match chunk {
+      Chunk::Equal(text) => (' ', text),
+      Chunk::Delete(text) => ('-', text),
+      Chunk::Insert(text) => ('+', text),
+    };
+    if tag == ' ' {
+      lines.append(&mut added_lines);
+    }
+    for line in text.split_inclusive('\n') {
+      if tag == '+' {
+        added_lines.push((tag, line));
+      } else {
+        lines.push((tag, line));
+      }
+    }
+  }
+  lines.append(&mut added_lines);
+
+  // group the changes that are close to each other into hunks
+  let mut hunks: Vec<(usize, usize)> = Vec::new();
+  for (index, _) in lines.iter().enumerate().filter(|(_, l)| l.0 != ' ') {
+    let start = index.saturating_sub(context_lines);
+    let end = (index + 1 + context_lines).min(lines.len());
+    match hunks.last_mut() {
+      Some(last) if start <= last.1 => last.1 = end,
+      _ => hunks.push((start, end)),
+    }
+  }
+
+  let mut output = format!("--- a/{file_name}\n+++ b/{file_name}\n");
+  let mut orig_line = 0;
+  let mut edit_line = 0;
+  let mut index = 0;
+  for (start, end) in hunks {
+    for (tag, _) in &lines[index..start] {
+      orig_line += usize::from(*tag != '+');
+      edit_line += usize::from(*tag != '-');
+    }
+    let hunk_lines = &lines[start..end];
+    let orig_count = hunk_lines.iter().filter(|l| l.0 != '+').count();
+    let edit_count = hunk_lines.iter().filter(|l| l.0 != '-').count();
+    // an empty range refers to the line before it
+    let orig_start = orig_line + usize::from(orig_count > 0);
+    let edit_start = edit_line + usize::from(edit_count > 0);
+    writeln!(
+      output,
+      "@@ -{orig_start},{orig_count} +{edit_start},{edit_count} @@"
+    )
+    .unwrap();
+    for (tag, line) in hunk_lines {
+      output.push(*tag);
+      output.push_str(line);
+      if !line.ends_with('\n') {
+        output.push_str("\n\\ No newline at end of file\n");
+      }
+    }
+    orig_line += orig_count;
+    edit_line += edit_count;
+    index = end;
+  }
+  output
+}
+
+/// Diffs the text by lines, which is what patches are made of.
+fn line_difference<'a>(
+  orig_text: &'a str,
+  edit_text: &'a str,
+) -> Vec<Chunk<'a>> {
+  token_difference(orig_text, edit_text, |text| {
+    text.split_inclusive('\n').collect()
+  })
+}
+
+/// Diffs the text by words instead of characters, which is easier to
+/// read when a small change is made in a long line.
+fn word_difference<'a>(
+  orig_text: &'a str,
+  edit_text: &'a str,
+) -> Vec<Chunk<'a>> {
+  token_difference(orig_text, edit_text, tokenize_words)
+}
+
+/// Diffs the tokens of the text. The tokens aren't diffed as characters
+/// with `dissimilar`, because its semantic cleanup merges the equal tokens
+/// between nearby changes into the changes.
+fn token_difference<'a>(
+  orig_text: &'a str,
+  edit_text: &'a str,
+  tokenize: impl Fn(&'a str) -> Vec<&'a str>,
+) -> Vec<Chunk<'a>> {
+  let orig_tokens = tokenize(orig_text);
+  let edit_tokens = tokenize(edit_text);
+  let prefix_len = orig_tokens
+    .iter()
+    .zip(&edit_tokens)
+    .take_while(|(a, b)| a == b)
+    .count();
+  let suffix_len = orig_tokens[prefix_len..]
+    .iter()
+    .rev()
+    .zip(edit_tokens[prefix_len..].iter().rev())
+    .take_while(|(a, b)| a == b)
+    .count();
+  let mut ops = vec![TokenOp::Equal; prefix_len];
+  ops.extend(myers_difference(
+    &orig_tokens[prefix_len..orig_tokens.len() - suffix_len],
+    &edit_tokens[prefix_len..edit_tokens.len() - suffix_len],
+  ));
+  ops.extend(std::iter::repeat(TokenOp::Equal).take(suffix_len));
+
+  // map the runs of tokens back to slices of the original text, where the
+  // deleted tokens of a change come before the inserted ones
+  let slice = |text: &'a str, tokens: &[&'a str], start: usize, end: usize| {
+    &text[token_offset(text, tokens, start)..token_offset(text, tokens, end)]
+  };
+  let mut chunks = Vec::new();
+  let mut orig_index = 0;
+  let mut edit_index = 0;
+  let mut ops = ops.into_iter().peekable();
+  while let Some(op) = ops.next() {
+    if op == TokenOp::Equal {
+      let mut len = 1;
+      while ops.next_if_eq(&TokenOp::Equal).is_some() {
+        len += 1;
+      }
+      chunks.push(Chunk::Equal(slice(
+        orig_text,
+        &orig_tokens,
+        orig_index,
+        orig_index + len,
+      )));
+      orig_index += len;
+      edit_index += len;
+      continue;
+    }
+    let mut delete_len = usize::from(op == TokenOp::Delete);
+    let mut insert_len = usize::from(op == TokenOp::Insert);
+    while let Some(op) = ops.next_if(|op| *op != TokenOp::Equal) {
+      match op {
+        TokenOp::Delete => delete_len += 1,
+        _ => insert_len += 1,
+      }
+    }
+    if delete_len > 0 {
+      chunks.push(Chunk::Delete(slice(
+        orig_text,
+        &orig_tokens,
+        orig_index,
+        orig_index + delete_len,
+      )));
+      orig_index += delete_len;
+    }
+    if insert_len > 0 {
+      chunks.push(Chunk::Insert(slice(
+        edit_text,
+        &edit_tokens,
+        edit_index,
+        edit_index + insert_len,
+      )));
+      edit_index += insert_len;
+    }
+  }
+  chunks
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenOp {
+  Equal,
+  Delete,
+  Insert,
+}
+
+/// Finds the shortest edit script between the tokens with the algorithm of
+/// Eugene Myers, returning an operation for each token.
+fn myers_difference(orig: &[&str], edit: &[&str]) -> Vec<TokenOp> {
+  let (n, m) = (orig.len() as isize, edit.len() as isize);
+  // the furthest x reached on each diagonal `k = x - y`, stored for every
+  // number of edits `d` for the diagonals `-d..=d` to backtrack the path
+  let mut trace: Vec<Vec<isize>> = Vec::new();
+  let mut v = vec![0isize; 3];
+  'search: for d in 0..=(n + m) {
+    let mut next = vec![0isize; 2 * d as usize + 3];
+    for k in (-d..=d).step_by(2) {
+      // `v` holds the diagonals `-(d - 1)..=(d - 1)`, offset by `d`
+      let get = |k: isize| v[(k + d) as usize];
+      let mut x = if k == -d || (k != d && get(k - 1) < get(k + 1)) {
+        get(k + 1)
+      } else {
+        get(k - 1) + 1
+      };
+      let mut y = x - k;
+      while x < n && y < m && orig[x as usize] == edit[y as usize] {
+        x += 1;
+        y += 1;
+      }
+      next[(k + d + 1) as usize] = x;
+      if x >= n && y >= m {
+        trace.push(next);
+        break 'search;
+      }
+    }
+    trace.push(next.clone());
+    v = next;
+  }
+
+  let mut ops = Vec::with_capacity((n + m) as usize);
+  let (mut x, mut y) = (n, m);
+  for d in (0..trace.len() as isize).rev() {
+    let k = x - y;
+    let (prev_x, prev_y) = if d == 0 {
+      (0, 0)
+    } else {
+      let prev = &trace[d as usize - 1];
+      let get = |k: isize| prev[(k + d) as usize];
+      let prev_k = if k == -d || (k != d && get(k - 1) < get(k + 1)) {
+        k + 1
+      } else {
+        k - 1
+      };
+      let prev_x = get(prev_k);
+      (prev_x, prev_x - prev_k)
+    };
+    while x > prev_x && y > prev_y {
+      ops.push(TokenOp::Equal);
+      x -= 1;
+      y -= 1;
+    }
+    if d > 0 {
+      ops.push(if x == prev_x {
+        TokenOp::Insert
+      } else {
+        TokenOp::Delete
+      });
+    }
+    x = prev_x;
+    y = prev_y;
+  }
+  ops.reverse();
+  ops
+}
+
+/// Gets the byte offset of the token at the provided index.
+fn token_offset(text: &str, tokens: &[&str], index: usize) -> usize {
+  match tokens.get(index) {
+    Some(token) => token.as_ptr() as usize - text.as_ptr() as usize,
+    None => text.len(),
+  }
+}
+
+/// Splits the text into newlines, runs of other whitespace, words and
+/// single punctuation characters.
+fn tokenize_words(text: &str) -> Vec<&str> {
+  #[derive(PartialEq)]
+  enum Kind {
+    Newline,
+    Whitespace,
+    Word,
+    Other,
+  }
+
+  fn kind(c: char) -> Kind {
+    if c == '\n' {
+      Kind::Newline
+    } else if c.is_whitespace() {
+      Kind::Whitespace
+    } else if c.is_alphanumeric() || c == '_' {
+      Kind::Word
+    } else {
+      Kind::Other
+    }
+  }
+
+  let mut tokens = Vec::new();
+  let mut start = 0;
+  let mut last_kind = None;
+  for (index, c) in text.char_indices() {
+    let kind = kind(c);
+    let continues_token = match &last_kind {
+      Some(last_kind) => {
+        *last_kind == kind && matches!(kind, Kind::Whitespace | Kind::Word)
+      }
+      None => true,
+    };
+    if !continues_token {
+      tokens.push(&text[start..index]);
+      start = index;
+    }
+    last_kind = Some(kind);
+  }
+  if start < text.len() {
+    tokens.push(&text[start..]);
+  }
+  tokens
+}
+
 fn fmt_add() -> String {
   colors::green_bold("+").to_string()
 }
@@ -219,9 +599,121 @@ mod tests {
     run_test("test\n", "test\r\n", " | Text differed by line endings.\n");
   }
 
+  #[test]
+  fn test_context_lines() {
+    let options = DiffOptions {
+      granularity: DiffGranularity::Char,
+      context_lines: 1,
+    };
+    let output = diff("a\nb\nc\nd\ne\nf\ng", "a\nb\nC\nd\ne\nf\nG", &options);
+    assert_eq!(
+      test_util::strip_ansi_codes(&output),
+      concat!(
+        "2 |  b\n", "3 | -c\n", "3 | +C\n", "4 |  d\n", "6 |  f\n", "7 | -g\n",
+        "7 | +G\n",
+      ),
+    );
+  }
+
+  #[test]
+  fn test_word_difference() {
+    let chunks = word_difference(
+      "const value = getValue(a,b);",
+      "const value = getValues(a, b);",
+    );
+    assert_eq!(
+      chunks,
+      vec![
+        Chunk::Equal("const value = "),
+        Chunk::Delete("getValue"),
+        Chunk::Insert("getValues"),
+        Chunk::Equal("(a,"),
+        Chunk::Insert(" "),
+        Chunk::Equal("b);"),
+      ]
+    );
+    // the output is the same as the character diff without colors
+    let options = DiffOptions {
+      granularity: DiffGranularity::Word,
+      context_lines: 0,
+    };
+    assert_eq!(
+      test_util::strip_ansi_codes(&diff(
+        "let a = 1;\nlet b=2;",
+        "let a = 1;\nlet b = 2;\n",
+        &options
+      )),
+      concat!("2 | -let b=2;\n", "2 | +let b = 2;\n", "3 | +\n"),
+    );
+  }
+
+  #[test]
+  fn test_unified_diff() {
+    assert_eq!(unified_diff("mod.ts", "a\n", "a\n", 3), "");
+    assert_eq!(
+      unified_diff(
+        "src/mod.ts",
+        "a\nb\nc\nd\ne\nf\ng\nh\ni\nj",
+        "a\nB\nc\nd\ne\nf\ng\nh\nI\nj\n",
+        1
+      ),
+      concat!(
+        "--- a/src/mod.ts\n",
+        "+++ b/src/mod.ts\n",
+        "@@ -1,3 +1,3 @@\n",
+        " a\n",
+        "-b\n",
+        "+B\n",
+        " c\n",
+        "@@ -8,3 +8,3 @@\n",
+        " h\n",
+        "-i\n",
+        "-j\n",
+        "\\ No newline at end of file\n",
+        "+I\n",
+        "+j\n",
+      )
+    );
+    // nearby changes are merged into one hunk
+    assert_eq!(
+      unified_diff("mod.ts", "a\nb\nc\nd\n", "x\na\nB\nc\nd\n", 1),
+      concat!(
+        "--- a/mod.ts\n",
+        "+++ b/mod.ts\n",
+        "@@ -1,3 +1,4 @@\n",
+        "+x\n",
+        " a\n",
+        "-b\n",
+        "+B\n",
+        " c\n",
+      )
+    );
+    assert_eq!(
+      unified_diff("mod.ts", "", "a\n", 3),
+      concat!(
+        "--- a/mod.ts\n",
+        "+++ b/mod.ts\n",
+        "@@ -0,0 +1,1 @@\n",
+        "+a\n"
+      )
+    );
+  }
+
+  #[test]
+  fn test_tokenize_words() {
+    assert_eq!(
+      tokenize_words("fn  a_b(c)\n\n  x"),
+      vec!["fn", "  ", "a_b", "(", "c", ")", "\n", "\n", "  ", "x"]
+    );
+  }
+
   fn run_test(diff_text1: &str, diff_text2: &str, expected_output: &str) {
     assert_eq!(
-      test_util::strip_ansi_codes(&diff(diff_text1, diff_text2,)),
+      test_util::strip_ansi_codes(&diff(
+        diff_text1,
+        diff_text2,
+        &Default::default()
+      )),
       expected_output,
     );
   }
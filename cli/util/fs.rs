@@ -1,5 +1,7 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::env::current_dir;
 use std::fs::FileType;
@@ -9,17 +11,23 @@ use std::io::ErrorKind;
 use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::Duration;
-use walkdir::WalkDir;
+use tokio::io::AsyncWriteExt;
 
 use deno_config::glob::FilePatterns;
 use deno_config::glob::PathOrPattern;
 use deno_config::glob::PathOrPatternSet;
 use deno_core::anyhow::anyhow;
+use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
+use deno_core::futures::Stream;
+use deno_core::futures::StreamExt;
 pub use deno_core::normalize_path;
+use deno_core::parking_lot::Mutex;
 use deno_core::unsync::spawn_blocking;
 use deno_core::ModuleSpecifier;
 use deno_runtime::deno_fs::FileSystem;
@@ -28,6 +36,9 @@ use deno_runtime::deno_node::PathClean;
 use crate::util::gitignore::DirGitIgnores;
 use crate::util::gitignore::GitIgnoreTree;
 use crate::util::path::get_atomic_file_path;
+use crate::util::path::is_case_insensitive_fs;
+use crate::util::path::path_case_key_with_sensitivity;
+use crate::util::path::paths_equal;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::ProgressBarStyle;
 use crate::util::progress_bar::ProgressMessagePrompt;
@@ -42,33 +53,69 @@ pub fn atomic_write_file<T: AsRef<[u8]>>(
   file_path: &Path,
   data: T,
   mode: u32,
+) -> std::io::Result<()> {
+  atomic_write_file_inner(file_path, data.as_ref(), mode, false)
+}
+
+/// Same as `atomic_write_file`, but also flushes the file and its parent
+/// directory to disk before returning so that the write survives a crash
+/// or power loss. This is slower, so only use it for small files that
+/// are expensive or impossible to recreate.
+pub fn durable_atomic_write_file<T: AsRef<[u8]>>(
+  file_path: &Path,
+  data: T,
+  mode: u32,
+) -> std::io::Result<()> {
+  atomic_write_file_inner(file_path, data.as_ref(), mode, true)
+}
+
+fn atomic_write_file_inner(
+  file_path: &Path,
+  data: &[u8],
+  mode: u32,
+  durable: bool,
 ) -> std::io::Result<()> {
   fn atomic_write_file_raw(
     temp_file_path: &Path,
     file_path: &Path,
     data: &[u8],
     mode: u32,
+    durable: bool,
   ) -> std::io::Result<()> {
     write_file(temp_file_path, data, mode)?;
-    std::fs::rename(temp_file_path, file_path)?;
+    if durable {
+      sync_file(temp_file_path)?;
+    }
+    // the temporary file is in the same directory as the destination, so
+    // this never crosses devices
+    std::fs::rename(to_long_path(temp_file_path), to_long_path(file_path))?;
+    if durable {
+      sync_parent_dir(file_path)?;
+    }
     Ok(())
   }
 
-  fn inner(file_path: &Path, data: &[u8], mode: u32) -> std::io::Result<()> {
+  fn inner(
+    file_path: &Path,
+    data: &[u8],
+    mode: u32,
+    durable: bool,
+  ) -> std::io::Result<()> {
     let temp_file_path = get_atomic_file_path(file_path);
 
     if let Err(write_err) =
-      atomic_write_file_raw(&temp_file_path, file_path, data, mode)
+      atomic_write_file_raw(&temp_file_path, file_path, data, mode, durable)
     {
       if write_err.kind() == ErrorKind::NotFound {
         let parent_dir_path = file_path.parent().unwrap();
-        match std::fs::create_dir_all(parent_dir_path) {
+        match std::fs::create_dir_all(to_long_path(parent_dir_path)) {
           Ok(()) => {
             return atomic_write_file_raw(
               &temp_file_path,
               file_path,
               data,
               mode,
+              durable,
             )
             .map_err(|err| add_file_context_to_err(file_path, err));
           }
@@ -91,19 +138,41 @@ pub fn atomic_write_file<T: AsRef<[u8]>>(
     Ok(())
   }
 
-  inner(file_path, data.as_ref(), mode)
+  inner(file_path, data, mode, durable)
+}
+
+fn sync_file(file_path: &Path) -> std::io::Result<()> {
+  // write access is required to flush a file on Windows
+  OpenOptions::new()
+    .write(true)
+    .open(to_long_path(file_path))?
+    .sync_all()
+}
+
+fn sync_parent_dir(file_path: &Path) -> std::io::Result<()> {
+  #[cfg(unix)]
+  {
+    if let Some(parent) = file_path.parent() {
+      std::fs::File::open(parent)?.sync_all()?;
+    }
+  }
+  // directories can't be opened as files on Windows and NTFS
+  // journals the rename
+  #[cfg(not(unix))]
+  let _ = file_path;
+  Ok(())
 }
 
 /// Creates a std::fs::File handling if the parent does not exist.
 pub fn create_file(file_path: &Path) -> std::io::Result<std::fs::File> {
-  match std::fs::File::create(file_path) {
+  match std::fs::File::create(to_long_path(file_path)) {
     Ok(file) => Ok(file),
     Err(err) => {
       if err.kind() == ErrorKind::NotFound {
         let parent_dir_path = file_path.parent().unwrap();
-        match std::fs::create_dir_all(parent_dir_path) {
+        match std::fs::create_dir_all(to_long_path(parent_dir_path)) {
           Ok(()) => {
-            return std::fs::File::create(file_path)
+            return std::fs::File::create(to_long_path(file_path))
               .map_err(|err| add_file_context_to_err(file_path, err));
           }
           Err(create_err) => {
@@ -154,7 +223,7 @@ pub fn write_file_2<T: AsRef<[u8]>>(
     .append(is_append)
     .truncate(!is_append)
     .create(is_create)
-    .open(filename)?;
+    .open(to_long_path(filename))?;
 
   if update_mode {
     #[cfg(unix)]
@@ -171,6 +240,89 @@ pub fn write_file_2<T: AsRef<[u8]>>(
   file.write_all(data.as_ref())
 }
 
+/// Size of the buffer used when writing streamed data to a file.
+const STREAM_WRITE_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Writes the chunks of the stream to the file as they arrive, so the whole
+/// payload is never held in memory. Returns the number of bytes written.
+pub async fn write_file_from_stream<B: AsRef<[u8]>, E: Into<AnyError>>(
+  file_path: &Path,
+  stream: impl Stream<Item = Result<B, E>>,
+  mode: u32,
+) -> Result<u64, AnyError> {
+  let mut stream = std::pin::pin!(stream);
+  let mut writer = tokio::io::BufWriter::with_capacity(
+    STREAM_WRITE_BUFFER_SIZE,
+    create_file_for_stream(file_path, mode).await?,
+  );
+  let mut written = 0;
+  while let Some(chunk) = stream.next().await {
+    let chunk = chunk.map_err(Into::into)?;
+    writer.write_all(chunk.as_ref()).await?;
+    written += chunk.as_ref().len() as u64;
+  }
+  writer.flush().await?;
+  Ok(written)
+}
+
+async fn create_file_for_stream(
+  file_path: &Path,
+  mode: u32,
+) -> std::io::Result<tokio::fs::File> {
+  let file = tokio::fs::File::create(to_long_path(file_path)).await?;
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    let permissions = PermissionsExt::from_mode(mode & 0o777);
+    file.set_permissions(permissions).await?;
+  }
+  #[cfg(not(unix))]
+  let _ = mode;
+  Ok(file)
+}
+
+/// Paths of this length or longer fail in some Windows APIs unless they use
+/// the `\\?\` verbatim prefix. This is `MAX_PATH` (260) minus the space
+/// `CreateDirectoryW` reserves for an 8.3 file name.
+#[cfg(windows)]
+const WINDOWS_MAX_PATH: usize = 248;
+
+/// Adds the `\\?\` verbatim prefix to long paths on Windows so that file
+/// system operations don't fail for paths longer than `MAX_PATH`
+/// (ex. deep node_modules trees). This is a no-op on other platforms.
+///
+/// Only use the returned path for the file system call. Use the original
+/// path in messages, since the prefix is an implementation detail.
+pub fn to_long_path(path: &Path) -> Cow<Path> {
+  #[cfg(windows)]
+  {
+    if let Some(long_path) = to_windows_long_path(path) {
+      return Cow::Owned(long_path);
+    }
+  }
+  Cow::Borrowed(path)
+}
+
+#[cfg(windows)]
+fn to_windows_long_path(path: &Path) -> Option<PathBuf> {
+  let text = path.to_str()?;
+  if text.len() < WINDOWS_MAX_PATH || text.starts_with(r"\\?\") {
+    return None;
+  }
+  let path = if path.is_absolute() {
+    Cow::Borrowed(path)
+  } else {
+    Cow::Owned(current_dir().ok()?.join(path))
+  };
+  // verbatim paths aren't normalized by Windows
+  let normalized = normalize_path(path);
+  let text = normalized.to_str()?.replace('/', "\\");
+  match text.strip_prefix(r"\\") {
+    Some(unc_path) => Some(PathBuf::from(format!(r"\\?\UNC\{}", unc_path))),
+    None => Some(PathBuf::from(format!(r"\\?\{}", text))),
+  }
+}
+
 /// Similar to `std::fs::canonicalize()` but strips UNC prefixes on Windows.
 pub fn canonicalize_path(path: &Path) -> Result<PathBuf, Error> {
   Ok(deno_core::strip_unc_prefix(path.canonicalize()?))
@@ -227,6 +379,33 @@ fn canonicalize_path_maybe_not_exists_with_custom_fn(
   }
 }
 
+/// Memoizes canonicalized paths for the duration of a single operation,
+/// such as walking a directory tree, which saves the syscalls of resolving
+/// the same paths over and over.
+///
+/// Only paths that exist are stored. Don't keep an instance around longer
+/// than the operation, because symlinks or directories might be replaced
+/// on the file system in the meantime.
+#[derive(Debug, Default)]
+pub struct CanonicalizedPathCache {
+  paths: Mutex<HashMap<PathBuf, PathBuf>>,
+}
+
+impl CanonicalizedPathCache {
+  /// Cached version of `canonicalize_path`.
+  pub fn canonicalize(&self, path: &Path) -> Result<PathBuf, Error> {
+    if let Some(canonicalized_path) = self.paths.lock().get(path) {
+      return Ok(canonicalized_path.clone());
+    }
+    let canonicalized_path = canonicalize_path(path)?;
+    self
+      .paths
+      .lock()
+      .insert(path.to_path_buf(), canonicalized_path.clone());
+    Ok(canonicalized_path)
+  }
+}
+
 pub fn resolve_from_cwd(path: &Path) -> Result<PathBuf, AnyError> {
   let resolved_path = if path.is_absolute() {
     path.to_owned()
@@ -247,6 +426,7 @@ pub struct WalkEntry<'a> {
 }
 
 /// Collects file paths that satisfy the given predicate, by recursively walking `files`.
+/// The returned paths are sorted.
 /// If the walker visits a path that is listed in `ignore`, it skips descending into the directory.
 pub struct FileCollector<TFilter: Fn(WalkEntry) -> bool> {
   file_filter: TFilter,
@@ -254,6 +434,8 @@ pub struct FileCollector<TFilter: Fn(WalkEntry) -> bool> {
   ignore_node_modules: bool,
   vendor_folder: Option<PathBuf>,
   use_gitignore: bool,
+  follow_symlinks: bool,
+  max_depth: Option<usize>,
 }
 
 impl<TFilter: Fn(WalkEntry) -> bool> FileCollector<TFilter> {
@@ -264,6 +446,8 @@ impl<TFilter: Fn(WalkEntry) -> bool> FileCollector<TFilter> {
       ignore_node_modules: false,
       vendor_folder: None,
       use_gitignore: false,
+      follow_symlinks: false,
+      max_depth: None,
     }
   }
 
@@ -287,6 +471,27 @@ impl<TFilter: Fn(WalkEntry) -> bool> FileCollector<TFilter> {
     self
   }
 
+  pub fn set_use_gitignore(mut self, use_gitignore: bool) -> Self {
+    self.use_gitignore = use_gitignore;
+    self
+  }
+
+  /// Descend into symlinked directories and provide symlinked files to
+  /// the filter as the file type of their target. A directory is only
+  /// walked once even when multiple symlinks point to it, which also
+  /// prevents symlink loops.
+  pub fn follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+    self.follow_symlinks = follow_symlinks;
+    self
+  }
+
+  /// Stop descending after the provided number of directories below the
+  /// base of the file patterns. A depth of `0` only collects the base.
+  pub fn max_depth(mut self, max_depth: usize) -> Self {
+    self.max_depth = Some(max_depth);
+    self
+  }
+
   pub fn collect_file_patterns(
     &self,
     file_patterns: FilePatterns,
@@ -347,56 +552,79 @@ impl<TFilter: Fn(WalkEntry) -> bool> FileCollector<TFilter> {
     };
     let mut target_files = Vec::new();
     let mut visited_paths = HashSet::new();
+    let mut visited_real_dirs = HashSet::new();
+    let path_cache = CanonicalizedPathCache::default();
     let file_patterns_by_base = file_patterns.split_by_base();
     for file_patterns in file_patterns_by_base {
       let file = normalize_path(&file_patterns.base);
-      // use an iterator in order to minimize the number of file system operations
-      let mut iterator = WalkDir::new(&file)
-        .follow_links(false) // the default, but be explicit
-        .into_iter();
-      loop {
-        let e = match iterator.next() {
-          None => break,
-          Some(Err(_)) => continue,
-          Some(Ok(entry)) => entry,
-        };
-        let file_type = e.file_type();
-        let is_dir = file_type.is_dir();
-        let path = e.path().to_path_buf();
-        let maybe_gitignore =
-          maybe_git_ignores.as_mut().and_then(|git_ignores| {
-            if is_dir {
-              git_ignores.get_resolved_git_ignore_for_dir(&path)
-            } else {
-              git_ignores.get_resolved_git_ignore_for_file(&path)
-            }
-          });
-        if !is_pattern_matched(
-          maybe_gitignore.as_deref(),
-          &path,
-          is_dir,
-          &file_patterns,
-        ) {
-          if is_dir {
-            iterator.skip_current_dir();
+      // the base is followed when it's a symlink
+      let Ok(metadata) = std::fs::metadata(&file) else {
+        continue;
+      };
+      // paths that only differ in case are the same path on case insensitive
+      // file systems, which might be provided by multiple bases
+      let case_insensitive = is_case_insensitive_fs(&file);
+      // walk one level of the tree at a time so the directories of each
+      // level can be read in parallel
+      let mut pending_entries = vec![(file.clone(), metadata.file_type())];
+      let mut depth = 0;
+      while !pending_entries.is_empty() {
+        let mut dirs_to_read = Vec::new();
+        for (path, file_type) in pending_entries {
+          let is_dir = file_type.is_dir();
+          let maybe_gitignore =
+            maybe_git_ignores.as_mut().and_then(|git_ignores| {
+              if is_dir {
+                git_ignores.get_resolved_git_ignore_for_dir(&path)
+              } else {
+                git_ignores.get_resolved_git_ignore_for_file(&path)
+              }
+            });
+          if !is_pattern_matched(
+            maybe_gitignore.as_deref(),
+            &path,
+            is_dir,
+            &file_patterns,
+          ) {
+            continue;
           }
-        } else if is_dir {
-          // allow the user to opt out of ignoring by explicitly specifying the dir
-          let opt_out_ignore = file == path;
-          let should_ignore_dir = !opt_out_ignore && self.is_ignored_dir(&path);
-          if should_ignore_dir || !visited_paths.insert(path.clone()) {
-            iterator.skip_current_dir();
+          if is_dir {
+            // allow the user to opt out of ignoring by explicitly specifying the dir
+            let opt_out_ignore = file == path;
+            let should_ignore_dir =
+              !opt_out_ignore && self.is_ignored_dir(&path);
+            if !should_ignore_dir
+              && visited_paths.insert(
+                path_case_key_with_sensitivity(&path, case_insensitive)
+                  .into_owned(),
+              )
+              && (!self.follow_symlinks
+                || path_cache
+                  .canonicalize(&path)
+                  .map(|real_path| visited_real_dirs.insert(real_path))
+                  .unwrap_or(false))
+            {
+              dirs_to_read.push(path);
+            }
+          } else if (self.file_filter)(WalkEntry {
+            path: &path,
+            file_type: &file_type,
+            patterns: &file_patterns,
+          }) && visited_paths.insert(
+            path_case_key_with_sensitivity(&path, case_insensitive)
+              .into_owned(),
+          ) {
+            target_files.push(path);
           }
-        } else if (self.file_filter)(WalkEntry {
-          path: &path,
-          file_type: &file_type,
-          patterns: &file_patterns,
-        }) && visited_paths.insert(path.clone())
-        {
-          target_files.push(path);
         }
+        if self.max_depth.map(|max| depth >= max).unwrap_or(false) {
+          break;
+        }
+        pending_entries = read_dirs(&dirs_to_read, self.follow_symlinks);
+        depth += 1;
       }
     }
+    target_files.sort();
     Ok(target_files)
   }
 
@@ -420,17 +648,85 @@ impl<TFilter: Fn(WalkEntry) -> bool> FileCollector<TFilter> {
     self
       .vendor_folder
       .as_ref()
-      .map(|vendor_folder| path == *vendor_folder)
+      .map(|vendor_folder| paths_equal(path, vendor_folder))
       .unwrap_or(false)
   }
 }
 
+/// Reads the entries of the provided directories. The directories are read in
+/// parallel when there are enough of them to make it worthwhile and the entries
+/// are returned in the order of the directories.
+fn read_dirs(
+  dirs: &[PathBuf],
+  follow_symlinks: bool,
+) -> Vec<(PathBuf, FileType)> {
+  const MIN_DIRS_PER_THREAD: usize = 8;
+
+  let read_dir = |dir: &Path| -> Vec<(PathBuf, FileType)> {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+      return Vec::new();
+    };
+    read_dir
+      .filter_map(|entry| {
+        let entry = entry.ok()?;
+        let mut file_type = entry.file_type().ok()?;
+        if follow_symlinks && file_type.is_symlink() {
+          // broken symlinks are provided as symlinks
+          if let Ok(metadata) = std::fs::metadata(entry.path()) {
+            file_type = metadata.file_type();
+          }
+        }
+        Some((entry.path(), file_type))
+      })
+      .collect()
+  };
+
+  let thread_count = std::thread::available_parallelism()
+    .map(|count| count.get())
+    .unwrap_or(1)
+    .min(dirs.len() / MIN_DIRS_PER_THREAD);
+  if thread_count <= 1 {
+    return dirs.iter().flat_map(|dir| read_dir(dir)).collect();
+  }
+
+  // threads take the next unread directory until there are none left
+  let next_index = AtomicUsize::new(0);
+  let mut results = std::thread::scope(|scope| {
+    let handles = (0..thread_count)
+      .map(|_| {
+        scope.spawn(|| {
+          let mut results = Vec::new();
+          loop {
+            let index = next_index.fetch_add(1, Ordering::Relaxed);
+            let Some(dir) = dirs.get(index) else {
+              break;
+            };
+            results.push((index, read_dir(dir)));
+          }
+          results
+        })
+      })
+      .collect::<Vec<_>>();
+    handles
+      .into_iter()
+      .flat_map(|handle| handle.join().unwrap())
+      .collect::<Vec<_>>()
+  });
+  results.sort_by_key(|(index, _)| *index);
+  results
+    .into_iter()
+    .flat_map(|(_, entries)| entries)
+    .collect()
+}
+
 /// Collects module specifiers that satisfy the given predicate as a file path, by recursively walking `include`.
-/// Specifiers that start with http and https are left intact.
-/// Note: This ignores all .git and node_modules folders.
+/// Specifiers that start with http, https, npm, jsr and data are left intact.
+/// Note: This ignores all .git and node_modules folders and, when `use_gitignore`
+/// is set, the files ignored by `.gitignore` files.
 pub fn collect_specifiers(
   mut files: FilePatterns,
   vendor_folder: Option<PathBuf>,
+  use_gitignore: bool,
   predicate: impl Fn(WalkEntry) -> bool,
 ) -> Result<Vec<ModuleSpecifier>, AnyError> {
   let mut prepared = vec![];
@@ -445,7 +741,9 @@ pub fn collect_specifiers(
         PathOrPattern::Path(path) => {
           if path.is_dir() {
             result.push(PathOrPattern::Path(path));
-          } else if !files.exclude.matches_path(&path) {
+          } else if files.exclude.matches_path(&path) {
+            continue;
+          } else {
             let url = specifier_from_file_path(&path)?;
             prepared.push(url);
           }
@@ -455,7 +753,12 @@ pub fn collect_specifiers(
           result.push(PathOrPattern::NegatedPath(path));
         }
         PathOrPattern::RemoteUrl(remote_url) => {
-          prepared.push(remote_url);
+          let is_excluded = files.exclude.inner().iter().any(|p| {
+            matches!(p, PathOrPattern::RemoteUrl(url) if *url == remote_url)
+          });
+          if !is_excluded {
+            prepared.push(remote_url);
+          }
         }
         PathOrPattern::Pattern(pattern) => {
           // add it back
@@ -470,6 +773,7 @@ pub fn collect_specifiers(
     .ignore_git_folder()
     .ignore_node_modules()
     .set_vendor_folder(vendor_folder)
+    .set_use_gitignore(use_gitignore)
     .collect_file_patterns(files)?;
   let mut collected_files_as_urls = collected_files
     .iter()
@@ -482,6 +786,61 @@ pub fn collect_specifiers(
   Ok(prepared)
 }
 
+/// Schemes of the specifiers that can be included in file patterns, which
+/// deno_config would otherwise resolve as paths relative to the base.
+const NON_FILE_SPECIFIER_SCHEMES: [&str; 3] = ["npm:", "jsr:", "data:"];
+
+fn parse_non_file_specifier(
+  entry: &str,
+) -> Option<Result<ModuleSpecifier, AnyError>> {
+  NON_FILE_SPECIFIER_SCHEMES
+    .iter()
+    .any(|scheme| entry.starts_with(scheme))
+    .then(|| {
+      ModuleSpecifier::parse(entry)
+        .with_context(|| format!("Invalid specifier '{}'", entry))
+    })
+}
+
+/// Same as `PathOrPatternSet::from_include_relative_path_or_patterns`, but
+/// keeps `npm:`, `jsr:` and `data:` specifiers as they were written.
+pub fn include_path_or_patterns(
+  base: &Path,
+  entries: &[String],
+) -> Result<PathOrPatternSet, AnyError> {
+  entries
+    .iter()
+    .map(|entry| match parse_non_file_specifier(entry) {
+      Some(specifier) => specifier.map(PathOrPattern::RemoteUrl),
+      None => PathOrPattern::from_relative(base, entry),
+    })
+    .collect::<Result<Vec<_>, _>>()
+    .map(PathOrPatternSet::new)
+}
+
+/// Same as `PathOrPatternSet::from_exclude_relative_path_or_patterns`, but
+/// keeps `npm:`, `jsr:` and `data:` specifiers as they were written.
+pub fn exclude_path_or_patterns(
+  base: &Path,
+  entries: &[String],
+) -> Result<PathOrPatternSet, AnyError> {
+  let mut specifiers = Vec::new();
+  let mut path_or_patterns = Vec::new();
+  for entry in entries {
+    match parse_non_file_specifier(entry) {
+      Some(specifier) => specifiers.push(PathOrPattern::RemoteUrl(specifier?)),
+      None => path_or_patterns.push(entry.clone()),
+    }
+  }
+  let mut result = PathOrPatternSet::from_exclude_relative_path_or_patterns(
+    base,
+    &path_or_patterns,
+  )?
+  .into_path_or_patterns();
+  result.extend(specifiers);
+  Ok(PathOrPatternSet::new(result))
+}
+
 /// Asynchronously removes a directory and all its descendants, but does not error
 /// when the directory does not exist.
 pub async fn remove_dir_all_if_exists(path: &Path) -> std::io::Result<()> {
@@ -492,13 +851,183 @@ pub async fn remove_dir_all_if_exists(path: &Path) -> std::io::Result<()> {
   }
 }
 
+/// Moves a directory to the trash (recycle bin) of the operating system
+/// instead of deleting it, so the user can restore it.
+///
+/// On Linux this follows the FreeDesktop.org trash specification, which only
+/// supports moving the directory when the trash is on the same device.
+pub fn remove_dir_all_to_trash(path: &Path) -> Result<(), AnyError> {
+  let path = canonicalize_path(path)
+    .with_context(|| format!("Failed resolving '{}'", path.display()))?;
+  trash::move_to_trash(&path)
+    .with_context(|| format!("Failed moving '{}' to the trash", path.display()))
+}
+
+#[cfg(target_os = "macos")]
+mod trash {
+  use super::*;
+
+  pub fn move_to_trash(path: &Path) -> Result<(), AnyError> {
+    let Some(home_dir) = std::env::var_os("HOME") else {
+      return Err(anyhow!("Could not resolve the home directory."));
+    };
+    let trash_dir = PathBuf::from(home_dir).join(".Trash");
+    std::fs::create_dir_all(&trash_dir)?;
+    let file_name = path.file_name().unwrap().to_string_lossy();
+    for i in 0.. {
+      let trash_path = if i == 0 {
+        trash_dir.join(&*file_name)
+      } else {
+        trash_dir.join(format!("{} {}", file_name, i))
+      };
+      if !trash_path.exists() {
+        std::fs::rename(path, trash_path)?;
+        break;
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod trash {
+  use super::*;
+  use percent_encoding::utf8_percent_encode;
+  use percent_encoding::AsciiSet;
+  use percent_encoding::NON_ALPHANUMERIC;
+
+  const PATH_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'/')
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+  pub fn move_to_trash(path: &Path) -> Result<(), AnyError> {
+    let data_dir = std::env::var_os("XDG_DATA_HOME")
+      .filter(|dir| !dir.is_empty())
+      .map(PathBuf::from)
+      .or_else(|| {
+        std::env::var_os("HOME")
+          .map(|home| PathBuf::from(home).join(".local/share"))
+      });
+    let Some(data_dir) = data_dir else {
+      return Err(anyhow!("Could not resolve the home directory."));
+    };
+    let trash_dir = data_dir.join("Trash");
+    let files_dir = trash_dir.join("files");
+    let info_dir = trash_dir.join("info");
+    std::fs::create_dir_all(&files_dir)?;
+    std::fs::create_dir_all(&info_dir)?;
+
+    let file_name = path.file_name().unwrap().to_string_lossy();
+    let info_text = format!(
+      "[Trash Info]\nPath={}\nDeletionDate={}\n",
+      utf8_percent_encode(&path.to_string_lossy(), PATH_ENCODE_SET),
+      local_deletion_date(),
+    );
+    for i in 0.. {
+      let trash_name = if i == 0 {
+        file_name.to_string()
+      } else {
+        format!("{}.{}", file_name, i)
+      };
+      // the info file is created first to reserve the name
+      let info_path = info_dir.join(format!("{}.trashinfo", trash_name));
+      let mut info_file = match OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&info_path)
+      {
+        Ok(file) => file,
+        Err(err) if err.kind() == ErrorKind::AlreadyExists => continue,
+        Err(err) => return Err(err.into()),
+      };
+      let result = info_file
+        .write_all(info_text.as_bytes())
+        .and_then(|_| std::fs::rename(path, files_dir.join(&trash_name)));
+      if let Err(err) = result {
+        drop(info_file);
+        let _ = std::fs::remove_file(&info_path);
+        return Err(err.into());
+      }
+      break;
+    }
+    Ok(())
+  }
+
+  /// The current local time in the format of the trash specification,
+  /// which has no time zone. This uses libc because the chrono "clock"
+  /// feature isn't enabled.
+  fn local_deletion_date() -> String {
+    // SAFETY: `localtime_r` only writes to the provided `tm`
+    unsafe {
+      let now = libc::time(std::ptr::null_mut());
+      let mut tm = std::mem::zeroed::<libc::tm>();
+      if libc::localtime_r(&now, &mut tm).is_null() {
+        return crate::util::time::utc_now()
+          .format("%Y-%m-%dT%H:%M:%S")
+          .to_string();
+      }
+      format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+      )
+    }
+  }
+}
+
+#[cfg(windows)]
+mod trash {
+  use super::*;
+  use std::os::windows::ffi::OsStrExt;
+  use winapi::um::shellapi::SHFileOperationW;
+  use winapi::um::shellapi::FOF_ALLOWUNDO;
+  use winapi::um::shellapi::FOF_NOCONFIRMATION;
+  use winapi::um::shellapi::FOF_NOERRORUI;
+  use winapi::um::shellapi::FOF_SILENT;
+  use winapi::um::shellapi::FO_DELETE;
+  use winapi::um::shellapi::SHFILEOPSTRUCTW;
+
+  pub fn move_to_trash(path: &Path) -> Result<(), AnyError> {
+    // the list of paths is double null terminated
+    let mut from = path.as_os_str().encode_wide().collect::<Vec<_>>();
+    from.extend([0, 0]);
+    let mut operation = SHFILEOPSTRUCTW {
+      hwnd: std::ptr::null_mut(),
+      wFunc: FO_DELETE as _,
+      pFrom: from.as_ptr(),
+      pTo: std::ptr::null(),
+      fFlags: (FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_NOERRORUI | FOF_SILENT)
+        as _,
+      fAnyOperationsAborted: 0,
+      hNameMappings: std::ptr::null_mut(),
+      lpszProgressTitle: std::ptr::null(),
+    };
+    // SAFETY: winapi call
+    let result = unsafe { SHFileOperationW(&mut operation) };
+    if result != 0 {
+      return Err(anyhow!("Error code {}", result));
+    }
+    if operation.fAnyOperationsAborted != 0 {
+      return Err(anyhow!("The operation was aborted."));
+    }
+    Ok(())
+  }
+}
+
 /// Copies a directory to another directory.
 ///
 /// Note: Does not handle symlinks.
 pub fn copy_dir_recursive(from: &Path, to: &Path) -> Result<(), AnyError> {
-  std::fs::create_dir_all(to)
+  std::fs::create_dir_all(to_long_path(to))
     .with_context(|| format!("Creating {}", to.display()))?;
-  let read_dir = std::fs::read_dir(from)
+  let read_dir = std::fs::read_dir(to_long_path(from))
     .with_context(|| format!("Reading {}", from.display()))?;
 
   for entry in read_dir {
@@ -512,7 +1041,7 @@ pub fn copy_dir_recursive(from: &Path, to: &Path) -> Result<(), AnyError> {
         format!("Dir {} to {}", new_from.display(), new_to.display())
       })?;
     } else if file_type.is_file() {
-      std::fs::copy(&new_from, &new_to).with_context(|| {
+      clone_or_copy_file(&new_from, &new_to).with_context(|| {
         format!("Copying {} to {}", new_from.display(), new_to.display())
       })?;
     }
@@ -521,13 +1050,54 @@ pub fn copy_dir_recursive(from: &Path, to: &Path) -> Result<(), AnyError> {
   Ok(())
 }
 
+/// Copies a file, sharing its data with the original file (copy-on-write)
+/// when the file system supports it and copying the bytes otherwise.
+///
+/// On Linux this clones the file on Btrfs, XFS and other file systems
+/// supporting `FICLONE`. On macOS, `std::fs::copy` already clones files
+/// on APFS.
+pub fn clone_or_copy_file(from: &Path, to: &Path) -> std::io::Result<()> {
+  #[cfg(target_os = "linux")]
+  {
+    if clone_file(from, to).is_ok() {
+      return Ok(());
+    }
+  }
+  std::fs::copy(to_long_path(from), to_long_path(to))?;
+  Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn clone_file(from: &Path, to: &Path) -> std::io::Result<()> {
+  use std::os::unix::io::AsRawFd;
+
+  // _IOW(0x94, 9, int)
+  const FICLONE: u32 = 0x40049409;
+
+  let from_file = std::fs::File::open(from)?;
+  let permissions = from_file.metadata()?.permissions();
+  let to_file = std::fs::File::create(to)?;
+  // SAFETY: both file descriptors are open for the duration of the call
+  let result = unsafe {
+    libc::ioctl(to_file.as_raw_fd(), FICLONE as _, from_file.as_raw_fd())
+  };
+  if result != 0 {
+    let err = Error::last_os_error();
+    drop(to_file);
+    let _ = std::fs::remove_file(to);
+    return Err(err);
+  }
+  to_file.set_permissions(permissions)?;
+  Ok(())
+}
+
 /// Hardlinks the files in one directory to another directory.
 ///
 /// Note: Does not handle symlinks.
 pub fn hard_link_dir_recursive(from: &Path, to: &Path) -> Result<(), AnyError> {
-  std::fs::create_dir_all(to)
+  std::fs::create_dir_all(to_long_path(to))
     .with_context(|| format!("Creating {}", to.display()))?;
-  let read_dir = std::fs::read_dir(from)
+  let read_dir = std::fs::read_dir(to_long_path(from))
     .with_context(|| format!("Reading {}", from.display()))?;
 
   for entry in read_dir {
@@ -546,9 +1116,11 @@ pub fn hard_link_dir_recursive(from: &Path, to: &Path) -> Result<(), AnyError> {
       // a way to hard link with overwriting in Rust, but maybe there is some
       // way with platform specific code. The workaround here is to handle
       // scenarios where something else might create or remove files.
-      if let Err(err) = std::fs::hard_link(&new_from, &new_to) {
+      if let Err(err) =
+        std::fs::hard_link(to_long_path(&new_from), to_long_path(&new_to))
+      {
         if err.kind() == ErrorKind::AlreadyExists {
-          if let Err(err) = std::fs::remove_file(&new_to) {
+          if let Err(err) = std::fs::remove_file(to_long_path(&new_to)) {
             if err.kind() == ErrorKind::NotFound {
               // Assume another process/thread created this hard link to the file we are wanting
               // to remove then sleep a little bit to let the other process/thread move ahead
@@ -567,7 +1139,9 @@ pub fn hard_link_dir_recursive(from: &Path, to: &Path) -> Result<(), AnyError> {
 
           // Always attempt to recreate the hardlink. In contention scenarios, the other process
           // might have been killed or exited after removing the file, but before creating the hardlink
-          if let Err(err) = std::fs::hard_link(&new_from, &new_to) {
+          if let Err(err) =
+            std::fs::hard_link(to_long_path(&new_from), to_long_path(&new_to))
+          {
             // Assume another process/thread created this hard link to the file we are wanting
             // to now create then sleep a little bit to let the other process/thread move ahead
             // faster to reduce contention.
@@ -673,7 +1247,38 @@ pub struct LaxSingleProcessFsFlag(
 );
 
 impl LaxSingleProcessFsFlag {
-  pub async fn lock(file_path: PathBuf, long_wait_message: &str) -> Self {
+  /// Waits for the flag to be acquired. Processes waiting on the same flag
+  /// acquire it in the order they started waiting.
+  pub async fn lock(
+    file_path: PathBuf,
+    long_wait_message: &str,
+  ) -> Result<Self, AnyError> {
+    Self::lock_inner(file_path, Some(long_wait_message), None).await
+  }
+
+  /// Same as `lock`, but gives up and errors when the flag couldn't be
+  /// acquired within the provided duration.
+  pub async fn lock_with_timeout(
+    file_path: PathBuf,
+    long_wait_message: &str,
+    timeout: Duration,
+  ) -> Result<Self, AnyError> {
+    Self::lock_inner(file_path, Some(long_wait_message), Some(timeout)).await
+  }
+
+  /// Attempts to acquire the flag once without waiting. Returns `None` when
+  /// it's held by another process or other processes are waiting for it.
+  pub async fn try_lock(file_path: PathBuf) -> Option<Self> {
+    Self::lock_inner(file_path, None, Some(Duration::ZERO))
+      .await
+      .ok()
+  }
+
+  async fn lock_inner(
+    file_path: PathBuf,
+    long_wait_message: Option<&str>,
+    timeout: Option<Duration>,
+  ) -> Result<Self, AnyError> {
     log::debug!("Acquiring file lock at {}", file_path.display());
     use fs3::FileExt;
     let last_updated_path = file_path.with_extension("lock.poll");
@@ -687,13 +1292,23 @@ impl LaxSingleProcessFsFlag {
 
     match open_result {
       Ok(fs_file) => {
+        let mut queue_entry =
+          LaxFsFlagQueueEntry::create(&file_path.with_extension("lock.queue"));
         let mut pb_update_guard = None;
         let mut error_count = 0;
         while error_count < 10 {
-          let lock_result = fs_file.try_lock_exclusive();
+          let is_turn = queue_entry
+            .as_ref()
+            .map(|entry| entry.is_first())
+            .unwrap_or(true);
+          let lock_result = if is_turn {
+            Some(fs_file.try_lock_exclusive())
+          } else {
+            None
+          };
           let poll_file_update_ms = 100;
           match lock_result {
-            Ok(_) => {
+            Some(Ok(_)) => {
               log::debug!("Acquired file lock at {}", file_path.display());
               let _ignore = std::fs::write(&last_updated_path, "");
               let token = Arc::new(tokio_util::sync::CancellationToken::new());
@@ -723,28 +1338,48 @@ impl LaxSingleProcessFsFlag {
                 }
               });
 
-              return Self(Some(LaxSingleProcessFsFlagInner {
+              return Ok(Self(Some(LaxSingleProcessFsFlagInner {
                 file_path,
                 fs_file,
                 finished_token: token,
-              }));
+              })));
             }
-            Err(_) => {
+            Some(Err(_)) | None => {
+              if let Some(timeout) = timeout {
+                if start_instant.elapsed() >= timeout {
+                  bail!(
+                    "Timed out acquiring file lock at {}",
+                    file_path.display()
+                  );
+                }
+              }
+
               // show a message if it's been a while
-              if pb_update_guard.is_none()
-                && start_instant.elapsed().as_millis() > 1_000
-              {
-                let pb = ProgressBar::new(ProgressBarStyle::TextOnly);
-                let guard = pb.update_with_prompt(
-                  ProgressMessagePrompt::Blocking,
-                  long_wait_message,
-                );
-                pb_update_guard = Some((guard, pb));
+              if let Some(long_wait_message) = long_wait_message {
+                if pb_update_guard.is_none()
+                  && start_instant.elapsed().as_millis() > 1_000
+                {
+                  let pb = ProgressBar::new(ProgressBarStyle::TextOnly);
+                  let guard = pb.update_with_prompt(
+                    ProgressMessagePrompt::Blocking,
+                    long_wait_message,
+                  );
+                  pb_update_guard = Some((guard, pb));
+                }
               }
 
               // sleep for a little bit
               tokio::time::sleep(Duration::from_millis(20)).await;
 
+              if let Some(queue_entry) = &mut queue_entry {
+                queue_entry.keep_alive();
+              }
+              if !is_turn {
+                // processes ahead in the queue are still waiting
+                error_count = 0;
+                continue;
+              }
+
               // Poll the last updated path to check if it's stopped updating,
               // which is an indication that the file lock is claimed, but
               // was never properly released.
@@ -761,7 +1396,7 @@ impl LaxSingleProcessFsFlag {
                         // the other process hasn't updated this file in a long time
                         // so maybe it was killed and the operating system hasn't
                         // released the file lock yet
-                        return Self(None);
+                        return Ok(Self(None));
                       } else {
                         error_count = 0; // reset
                       }
@@ -780,7 +1415,7 @@ impl LaxSingleProcessFsFlag {
         }
 
         drop(pb_update_guard); // explicit for clarity
-        Self(None)
+        Ok(Self(None))
       }
       Err(err) => {
         log::debug!(
@@ -788,12 +1423,101 @@ impl LaxSingleProcessFsFlag {
           file_path.display(),
           err
         );
-        Self(None) // let the process through
+        Ok(Self(None)) // let the process through
       }
     }
   }
 }
 
+/// A process waiting on a `LaxSingleProcessFsFlag`. Waiters create a file
+/// in the queue directory named after the time they started waiting and
+/// only attempt to acquire the flag once there are no older waiters, which
+/// prevents a process from being starved by the others.
+struct LaxFsFlagQueueEntry {
+  queue_dir: PathBuf,
+  file_path: PathBuf,
+  file_name: String,
+  last_keep_alive: std::time::Instant,
+}
+
+impl LaxFsFlagQueueEntry {
+  /// Time after which a waiter that stopped updating its entry, for
+  /// example because it was killed, is no longer waited on.
+  const STALE_DURATION: Duration = Duration::from_secs(2);
+  const KEEP_ALIVE_INTERVAL: Duration = Duration::from_millis(100);
+
+  fn create(queue_dir: &Path) -> Option<Self> {
+    let since_epoch = std::time::SystemTime::now()
+      .duration_since(std::time::UNIX_EPOCH)
+      .ok()?;
+    let file_name = format!(
+      "{:020}-{:08x}",
+      since_epoch.as_nanos(),
+      rand::random::<u32>()
+    );
+    let file_path = queue_dir.join(&file_name);
+    if let Err(err) = std::fs::create_dir_all(queue_dir)
+      .and_then(|_| std::fs::write(&file_path, ""))
+    {
+      log::debug!(
+        "Failed creating queue entry at {}. {:#}",
+        file_path.display(),
+        err
+      );
+      return None;
+    }
+    Some(Self {
+      queue_dir: queue_dir.to_path_buf(),
+      file_path,
+      file_name,
+      last_keep_alive: std::time::Instant::now(),
+    })
+  }
+
+  /// Updates the entry so other waiters know this process is still waiting.
+  fn keep_alive(&mut self) {
+    if self.last_keep_alive.elapsed() >= Self::KEEP_ALIVE_INTERVAL {
+      // this recreates the entry if another process removed it as stale
+      let _ignore = std::fs::write(&self.file_path, "");
+      self.last_keep_alive = std::time::Instant::now();
+    }
+  }
+
+  /// Gets if there are no other waiters that started waiting before this one.
+  fn is_first(&self) -> bool {
+    let Ok(read_dir) = std::fs::read_dir(&self.queue_dir) else {
+      return true;
+    };
+    let now = std::time::SystemTime::now();
+    for entry in read_dir.flatten() {
+      let file_name = entry.file_name();
+      let file_name = file_name.to_string_lossy();
+      if *file_name >= *self.file_name {
+        continue;
+      }
+      let is_stale = entry
+        .metadata()
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| now.duration_since(modified).ok())
+        .map(|duration| duration > Self::STALE_DURATION)
+        .unwrap_or(false);
+      if is_stale {
+        let _ignore = std::fs::remove_file(entry.path());
+      } else {
+        return false;
+      }
+    }
+    true
+  }
+}
+
+impl Drop for LaxFsFlagQueueEntry {
+  fn drop(&mut self) {
+    let _ignore = std::fs::remove_file(&self.file_path);
+  }
+}
+
 pub fn specifier_from_file_path(
   path: &Path,
 ) -> Result<ModuleSpecifier, AnyError> {
@@ -854,6 +1578,49 @@ mod tests {
     assert_eq!(resolve_from_cwd(expected).unwrap(), absolute_expected);
   }
 
+  #[cfg(windows)]
+  #[test]
+  fn test_to_long_path() {
+    let short_path = Path::new(r"C:\dir\file.txt");
+    assert!(matches!(to_long_path(short_path), Cow::Borrowed(_)));
+
+    let long_name = "a".repeat(250);
+    let long_path = PathBuf::from(format!(r"C:\dir\..\{}/file.txt", long_name));
+    assert_eq!(
+      to_long_path(&long_path),
+      PathBuf::from(format!(r"\\?\C:\{}\file.txt", long_name))
+    );
+    let long_unc_path = PathBuf::from(format!(r"\\server\share\{}", long_name));
+    assert_eq!(
+      to_long_path(&long_unc_path),
+      PathBuf::from(format!(r"\\?\UNC\server\share\{}", long_name))
+    );
+    // already verbatim
+    let verbatim_path = PathBuf::from(format!(r"\\?\C:\{}", long_name));
+    assert!(matches!(to_long_path(&verbatim_path), Cow::Borrowed(_)));
+  }
+
+  #[cfg(windows)]
+  #[test]
+  fn test_write_file_long_path() {
+    let t = TempDir::new();
+    let mut file_path = t.path().to_path_buf();
+    for _ in 0..10 {
+      file_path.push("a".repeat(30));
+    }
+    file_path.push("file.txt");
+    atomic_write_file(&file_path, "data", 0o644).unwrap();
+    let mut copy_path = t.path().to_path_buf();
+    copy_path.push("copy");
+    copy_dir_recursive(
+      &t.path().join("a".repeat(30)).to_path_buf(),
+      &copy_path,
+    )
+    .unwrap();
+    hard_link_dir_recursive(&copy_path, &t.path().join("link").to_path_buf())
+      .unwrap();
+  }
+
   #[test]
   fn test_collect_files() {
     fn create_files(dir_path: &PathRef, files: &[&str]) {
@@ -998,6 +1765,146 @@ mod tests {
     assert_eq!(file_names, expected);
   }
 
+  #[test]
+  fn test_collect_files_many_dirs_sorted() {
+    // enough directories for them to be read in parallel
+    let t = TempDir::new();
+    let mut expected = Vec::new();
+    for i in 0..64 {
+      for j in 0..4 {
+        let file_path = format!("dir_{i:02}/sub_{j}/mod.ts");
+        t.create_dir_all(format!("dir_{i:02}/sub_{j}"));
+        t.write(&file_path, "");
+        expected.push(t.path().join(file_path).to_path_buf());
+      }
+    }
+    t.create_dir_all("dir_00/node_modules");
+    t.write("dir_00/node_modules/mod.ts", "");
+
+    let result = FileCollector::new(|_| true)
+      .ignore_node_modules()
+      .collect_file_patterns(FilePatterns {
+        base: t.path().to_path_buf(),
+        include: None,
+        exclude: Default::default(),
+      })
+      .unwrap();
+    assert_eq!(result, expected);
+  }
+
+  #[test]
+  fn test_collect_files_max_depth() {
+    let t = TempDir::new();
+    t.create_dir_all("a/b/c");
+    t.write("root.ts", "");
+    t.write("a/a.ts", "");
+    t.write("a/b/b.ts", "");
+    t.write("a/b/c/c.ts", "");
+    let collect = |max_depth: usize| {
+      FileCollector::new(|_| true)
+        .max_depth(max_depth)
+        .collect_file_patterns(FilePatterns {
+          base: t.path().to_path_buf(),
+          include: None,
+          exclude: Default::default(),
+        })
+        .unwrap()
+        .into_iter()
+        .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(collect(0), Vec::<String>::new());
+    assert_eq!(collect(1), vec!["root.ts"]);
+    assert_eq!(collect(3), vec!["a.ts", "b.ts", "root.ts"]);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_canonicalized_path_cache() {
+    let t = TempDir::new();
+    t.create_dir_all("a");
+    t.create_dir_all("b");
+    t.symlink_dir("a", "link");
+    let cache = CanonicalizedPathCache::default();
+    let root = canonicalize_path(t.path().as_path()).unwrap();
+    let link_path = t.path().join("link").to_path_buf();
+    assert_eq!(cache.canonicalize(&link_path).unwrap(), root.join("a"));
+    // not found paths aren't cached
+    assert!(cache
+      .canonicalize(&t.path().join("c").to_path_buf())
+      .is_err());
+
+    // the entry is provided until the cache is dropped
+    std::fs::remove_file(&link_path).unwrap();
+    t.symlink_dir("b", "link");
+    assert_eq!(cache.canonicalize(&link_path).unwrap(), root.join("a"));
+    let cache = CanonicalizedPathCache::default();
+    assert_eq!(cache.canonicalize(&link_path).unwrap(), root.join("b"));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_collect_files_follow_symlinks() {
+    let t = TempDir::new();
+    t.create_dir_all("packages/a");
+    t.write("packages/a/mod.ts", "");
+    t.create_dir_all("workspace");
+    t.symlink_dir("packages/a", "workspace/a");
+    // symlink loop
+    t.symlink_dir("workspace", "workspace/loop");
+    let collect = |follow_symlinks: bool| {
+      FileCollector::new(|e| e.file_type.is_file())
+        .follow_symlinks(follow_symlinks)
+        .collect_file_patterns(FilePatterns {
+          base: t.path().join("workspace").to_path_buf(),
+          include: None,
+          exclude: Default::default(),
+        })
+        .unwrap()
+    };
+    assert_eq!(collect(false), Vec::<PathBuf>::new());
+    assert_eq!(
+      collect(true),
+      vec![t.path().join("workspace/a/mod.ts").to_path_buf()]
+    );
+  }
+
+  #[test]
+  fn test_clone_or_copy_file() {
+    let t = TempDir::new();
+    t.write("a.txt", "hello");
+    let from = t.path().join("a.txt");
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      std::fs::set_permissions(&from, std::fs::Permissions::from_mode(0o755))
+        .unwrap();
+    }
+    // overwrites the existing file
+    t.write("b.txt", "some longer text");
+    let to = t.path().join("b.txt");
+    clone_or_copy_file(from.as_path(), to.as_path()).unwrap();
+    assert_eq!(t.read_to_string("b.txt"), "hello");
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      let mode = std::fs::metadata(&to).unwrap().permissions().mode();
+      assert_eq!(mode & 0o777, 0o755);
+    }
+  }
+
+  #[test]
+  fn test_durable_atomic_write_file() {
+    let t = TempDir::new();
+    let file_path = t.path().join("sub_dir/file.txt");
+    durable_atomic_write_file(file_path.as_path(), "1", 0o644).unwrap();
+    durable_atomic_write_file(file_path.as_path(), "2", 0o644).unwrap();
+    assert_eq!(t.read_to_string("sub_dir/file.txt"), "2");
+    // the temporary file was renamed
+    let entries = std::fs::read_dir(t.path().join("sub_dir")).unwrap().count();
+    assert_eq!(entries, 1);
+  }
+
   #[test]
   fn test_collect_specifiers() {
     fn create_files(dir_path: &PathRef, files: &[&str]) {
@@ -1048,21 +1955,28 @@ mod tests {
       FilePatterns {
         base: root_dir_path.to_path_buf(),
         include: Some(
-          PathOrPatternSet::from_include_relative_path_or_patterns(
+          include_path_or_patterns(
             root_dir_path.as_path(),
             &[
               "http://localhost:8080".to_string(),
               "./".to_string(),
               "https://localhost:8080".to_string(),
+              "npm:chalk@5".to_string(),
+              "jsr:@std/assert@1/assert_equals".to_string(),
+              "data:application/javascript,console.log(1)".to_string(),
+              "npm:excluded@1".to_string(),
             ],
           )
           .unwrap(),
         ),
-        exclude: PathOrPatternSet::new(vec![PathOrPattern::Path(
-          ignore_dir_path.to_path_buf(),
-        )]),
+        exclude: exclude_path_or_patterns(
+          root_dir_path.as_path(),
+          &["ignore".to_string(), "npm:excluded@1".to_string()],
+        )
+        .unwrap(),
       },
       None,
+      false,
       predicate,
     )
     .unwrap();
@@ -1073,6 +1987,9 @@ mod tests {
     let expected = vec![
       "http://localhost:8080/".to_string(),
       "https://localhost:8080/".to_string(),
+      "npm:chalk@5".to_string(),
+      "jsr:@std/assert@1/assert_equals".to_string(),
+      "data:application/javascript,console.log(1)".to_string(),
       format!("{root_dir_url}/a.ts"),
       format!("{root_dir_url}/b.js"),
       format!("{root_dir_url}/c.tsx"),
@@ -1109,6 +2026,7 @@ mod tests {
         exclude: Default::default(),
       },
       None,
+      false,
       predicate,
     )
     .unwrap();
@@ -1128,6 +2046,30 @@ mod tests {
     );
   }
 
+  #[tokio::test]
+  async fn test_write_file_from_stream() {
+    let t = TempDir::new();
+    let file_path = t.path().join("stream.txt");
+    let stream = futures::stream::iter(vec![
+      Ok::<_, AnyError>(b"hello ".to_vec()),
+      Ok(b"from a stream".to_vec()),
+    ]);
+    let written = write_file_from_stream(file_path.as_path(), stream, 0o644)
+      .await
+      .unwrap();
+    assert_eq!(written, 19);
+    assert_eq!(t.read_to_string("stream.txt"), "hello from a stream");
+
+    let stream = futures::stream::iter(vec![
+      Ok(b"partial".to_vec()),
+      Err(anyhow!("connection reset")),
+    ]);
+    let err = write_file_from_stream(file_path.as_path(), stream, 0o644)
+      .await
+      .unwrap_err();
+    assert_eq!(err.to_string(), "connection reset");
+  }
+
   #[tokio::test]
   async fn lax_fs_lock() {
     let temp_dir = TempDir::new();
@@ -1146,7 +2088,8 @@ mod tests {
       async move {
         let flag =
           LaxSingleProcessFsFlag::lock(lock_path.to_path_buf(), "waiting")
-            .await;
+            .await
+            .unwrap();
         signal1.notify_one();
         signal2.notified().await;
         tokio::time::sleep(Duration::from_millis(10)).await; // give the other thread time to acquire the lock
@@ -1165,7 +2108,8 @@ mod tests {
         signal2.notify_one();
         let flag =
           LaxSingleProcessFsFlag::lock(lock_path.to_path_buf(), "waiting")
-            .await;
+            .await
+            .unwrap();
         temp_dir.write("file.txt", "update2");
         signal5.notify_one();
         drop(flag);
@@ -1179,6 +2123,55 @@ mod tests {
     assert_eq!(temp_dir.read_to_string("file.txt"), "update2");
   }
 
+  #[tokio::test]
+  async fn lax_fs_lock_try_lock_and_timeout() {
+    let temp_dir = TempDir::new();
+    let lock_path = temp_dir.path().join("file.lock").to_path_buf();
+    let flag = LaxSingleProcessFsFlag::try_lock(lock_path.clone()).await;
+    assert!(flag.is_some());
+    assert!(LaxSingleProcessFsFlag::try_lock(lock_path.clone())
+      .await
+      .is_none());
+    assert!(LaxSingleProcessFsFlag::lock_with_timeout(
+      lock_path.clone(),
+      "waiting",
+      Duration::from_millis(50),
+    )
+    .await
+    .is_err());
+    drop(flag);
+    assert!(LaxSingleProcessFsFlag::try_lock(lock_path.clone())
+      .await
+      .is_some());
+    // waiters remove their queue entry
+    let queue_dir = temp_dir.path().join("file.lock.queue");
+    assert_eq!(std::fs::read_dir(queue_dir).unwrap().count(), 0);
+  }
+
+  #[tokio::test]
+  async fn lax_fs_lock_waits_for_older_waiters() {
+    let temp_dir = TempDir::new();
+    let lock_path = temp_dir.path().join("file.lock").to_path_buf();
+    let queue_dir = temp_dir.path().join("file.lock.queue");
+    queue_dir.create_dir_all();
+    // an older waiter that's still alive
+    queue_dir.join("00000000000000000000-00000000").write("");
+    assert!(LaxSingleProcessFsFlag::try_lock(lock_path.clone())
+      .await
+      .is_none());
+    // a waiter that stopped updating its entry is skipped
+    let stale_time = std::time::SystemTime::now() - Duration::from_secs(10);
+    std::fs::File::options()
+      .write(true)
+      .open(queue_dir.join("00000000000000000000-00000000"))
+      .unwrap()
+      .set_modified(stale_time)
+      .unwrap();
+    assert!(LaxSingleProcessFsFlag::try_lock(lock_path.clone())
+      .await
+      .is_some());
+  }
+
   #[tokio::test]
   async fn lax_fs_lock_ordered() {
     let temp_dir = TempDir::new();
@@ -1197,7 +2190,8 @@ mod tests {
       tasks.push(tokio::spawn(async move {
         let flag =
           LaxSingleProcessFsFlag::lock(lock_path.to_path_buf(), "waiting")
-            .await;
+            .await
+            .unwrap();
         expected_order.lock().push(i.to_string());
         // be extremely racy
         let mut output = std::fs::read_to_string(&output_path).unwrap();
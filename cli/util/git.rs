@@ -0,0 +1,98 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+
+/// Gets the files of the git repository containing `cwd` that changed since
+/// the provided ref. This includes uncommitted changes and the untracked
+/// files that aren't ignored by git, but not deleted files.
+pub fn changed_files(
+  cwd: &Path,
+  git_ref: &str,
+) -> Result<Vec<PathBuf>, AnyError> {
+  let root = run_git(cwd, &["rev-parse", "--show-toplevel"])?;
+  let root = PathBuf::from(root.trim_end());
+  let changed = run_git(
+    &root,
+    &[
+      "diff",
+      "--name-only",
+      "-z",
+      "--no-renames",
+      "--diff-filter=d",
+      git_ref,
+      "--",
+    ],
+  )?;
+  let untracked =
+    run_git(&root, &["ls-files", "-z", "--others", "--exclude-standard"])?;
+  Ok(
+    changed
+      .split('\0')
+      .chain(untracked.split('\0'))
+      .filter(|path| !path.is_empty())
+      .map(|path| root.join(path))
+      .collect(),
+  )
+}
+
+fn run_git(cwd: &Path, args: &[&str]) -> Result<String, AnyError> {
+  let output = Command::new("git")
+    .current_dir(cwd)
+    .args(args)
+    .output()
+    .context("Failed running git. Is it installed?")?;
+  if !output.status.success() {
+    bail!(
+      "Failed running `git {}`: {}",
+      args.join(" "),
+      String::from_utf8_lossy(&output.stderr).trim()
+    );
+  }
+  String::from_utf8(output.stdout).context("git output was not valid UTF-8.")
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use test_util::TempDir;
+
+  #[test]
+  fn test_changed_files() {
+    let temp_dir = TempDir::new();
+    let git = |args: &[&str]| {
+      run_git(temp_dir.path().as_path(), args).unwrap();
+    };
+    git(&["init", "--quiet"]);
+    git(&["config", "user.email", "test@example.com"]);
+    git(&["config", "user.name", "Test"]);
+    git(&["config", "commit.gpgsign", "false"]);
+    temp_dir.write(".gitignore", "ignored.ts\n");
+    temp_dir.write("committed.ts", "");
+    temp_dir.write("modified.ts", "");
+    temp_dir.write("deleted.ts", "");
+    git(&["add", "."]);
+    git(&["commit", "--quiet", "-m", "initial"]);
+    temp_dir.write("modified.ts", "1");
+    temp_dir.remove_file("deleted.ts");
+    temp_dir.create_dir_all("sub");
+    temp_dir.write("sub/untracked.ts", "");
+    temp_dir.write("ignored.ts", "");
+
+    let root = temp_dir.path().canonicalize();
+    let mut files = changed_files(root.join("sub").as_path(), "HEAD").unwrap();
+    files.sort();
+    assert_eq!(
+      files,
+      vec![
+        root.join("modified.ts").to_path_buf(),
+        root.join("sub/untracked.ts").to_path_buf(),
+      ]
+    );
+  }
+}
@@ -2,6 +2,13 @@
 
 use std::io::Write;
 
+use console_static_text::ansi::strip_ansi_codes;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+
+use crate::args::LogFormat;
+use crate::util::time::utc_now;
+
 struct CliLogger(env_logger::Logger);
 
 impl CliLogger {
@@ -30,7 +37,7 @@ impl log::Log for CliLogger {
   }
 }
 
-pub fn init(maybe_level: Option<log::Level>) {
+pub fn init(maybe_level: Option<log::Level>, log_format: LogFormat) {
   let log_level = maybe_level.unwrap_or(log::Level::Info);
   let logger = env_logger::Builder::from_env(
     env_logger::Env::default()
@@ -45,7 +52,10 @@ pub fn init(maybe_level: Option<log::Level>) {
   // in the cli logger
   .filter_module("deno::lsp::performance", log::LevelFilter::Debug)
   .filter_module("rustls", log::LevelFilter::Off)
-  .format(|buf, record| {
+  .format(move |buf, record| {
+    if log_format == LogFormat::Json {
+      return writeln!(buf, "{}", json_record(record, &utc_now().to_rfc3339()));
+    }
     let mut target = record.target().to_string();
     if let Some(line_no) = record.line() {
       target.push(':');
@@ -78,3 +88,52 @@ pub fn init(maybe_level: Option<log::Level>) {
   }
   r.expect("Could not install logger.");
 }
+
+/// Creates the JSON object of a record, which is output on a single line.
+/// The message is logged without the ANSI codes of its colors.
+fn json_record(record: &log::Record, timestamp: &str) -> serde_json::Value {
+  let message = record.args().to_string();
+  let mut value = json!({
+    "timestamp": timestamp,
+    "level": record.level().as_str().to_lowercase(),
+    "target": record.target(),
+    "message": strip_ansi_codes(&message),
+  });
+  if let Some(file) = record.file() {
+    value["file"] = file.into();
+  }
+  if let Some(line) = record.line() {
+    value["line"] = line.into();
+  }
+  value
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_json_record() {
+    let value = json_record(
+      &log::Record::builder()
+        .args(format_args!(
+          "{} {}",
+          "\x1b[32mDownload\x1b[0m", "https://deno.land/x/mod.ts"
+        ))
+        .level(log::Level::Info)
+        .target("deno::file_fetcher")
+        .file(Some("cli/file_fetcher.rs"))
+        .line(Some(12))
+        .build(),
+      "2024-01-02T03:04:05+00:00",
+    );
+    assert_eq!(
+      value.to_string(),
+      concat!(
+        r#"{"timestamp":"2024-01-02T03:04:05+00:00","level":"info","#,
+        r#""target":"deno::file_fetcher","message":"Download https://deno.land/x/mod.ts","#,
+        r#""file":"cli/file_fetcher.rs","line":12}"#,
+      )
+    );
+  }
+}
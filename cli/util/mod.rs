@@ -8,6 +8,7 @@ pub mod display;
 pub mod draw_thread;
 pub mod file_watcher;
 pub mod fs;
+pub mod git;
 pub mod gitignore;
 pub mod logger;
 pub mod path;
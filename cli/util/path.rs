@@ -247,6 +247,142 @@ pub fn matches_pattern_or_exact_path(
   false
 }
 
+/// Whether the file systems of the current platform compare paths case
+/// insensitively by default.
+pub const IS_CASE_INSENSITIVE_FS: bool =
+  cfg!(any(windows, target_os = "macos"));
+
+/// Gets a key for the path that's the same for all paths that only differ
+/// in case (ex. `C:\Foo` and `c:\foo`) when the file system compares paths
+/// case insensitively, such as detected by `is_case_insensitive_fs`.
+pub fn path_case_key_with_sensitivity(
+  path: &Path,
+  case_insensitive: bool,
+) -> Cow<Path> {
+  if !case_insensitive {
+    return Cow::Borrowed(path);
+  }
+  match path.to_str() {
+    Some(text) if !text.chars().any(|c| c.is_uppercase()) => {
+      Cow::Borrowed(path)
+    }
+    _ => Cow::Owned(PathBuf::from(path.to_string_lossy().to_lowercase())),
+  }
+}
+
+/// Gets if the file system of the path compares paths case insensitively,
+/// which is detected by looking up the closest existing ancestor that has
+/// letters in its name with the case of those letters swapped. Falls back
+/// to the default of the platform when there's no such ancestor.
+pub fn is_case_insensitive_fs(path: &Path) -> bool {
+  for path in path.ancestors() {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+      continue;
+    };
+    let swapped_name = name
+      .chars()
+      .map(|c| {
+        if c.is_uppercase() {
+          c.to_lowercase().to_string()
+        } else {
+          c.to_uppercase().to_string()
+        }
+      })
+      .collect::<String>();
+    if swapped_name == name {
+      continue;
+    }
+    if !path.exists() {
+      continue;
+    }
+    return is_same_file(path, &path.with_file_name(swapped_name));
+  }
+  IS_CASE_INSENSITIVE_FS
+}
+
+fn is_same_file(a: &Path, b: &Path) -> bool {
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::MetadataExt;
+    match (std::fs::metadata(a), std::fs::metadata(b)) {
+      (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+      _ => false,
+    }
+  }
+  #[cfg(windows)]
+  {
+    // the file index of `std::fs::Metadata` isn't available on stable Rust
+    fn file_id(path: &Path) -> Option<(u32, u32, u32)> {
+      use std::os::windows::fs::OpenOptionsExt;
+      use std::os::windows::io::AsRawHandle;
+      use winapi::um::fileapi::GetFileInformationByHandle;
+      use winapi::um::fileapi::BY_HANDLE_FILE_INFORMATION;
+      use winapi::um::winbase::FILE_FLAG_BACKUP_SEMANTICS;
+
+      let file = std::fs::OpenOptions::new()
+        // only the metadata is read
+        .access_mode(0)
+        // required for opening directories
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)
+        .ok()?;
+      // SAFETY: the handle stays valid while `file` is alive and the
+      // function only writes to the provided struct
+      unsafe {
+        let mut info = std::mem::zeroed::<BY_HANDLE_FILE_INFORMATION>();
+        if GetFileInformationByHandle(file.as_raw_handle() as _, &mut info) == 0
+        {
+          return None;
+        }
+        Some((
+          info.dwVolumeSerialNumber,
+          info.nFileIndexHigh,
+          info.nFileIndexLow,
+        ))
+      }
+    }
+    match (file_id(a), file_id(b)) {
+      (Some(a), Some(b)) => a == b,
+      _ => false,
+    }
+  }
+}
+
+/// Gets if the paths refer to the same path, ignoring the case when the
+/// file system of the paths compares them case insensitively.
+pub fn paths_equal(a: &Path, b: &Path) -> bool {
+  if a == b {
+    return true;
+  }
+  // only look at the file system when the paths differ in case
+  path_case_key_with_sensitivity(a, true)
+    == path_case_key_with_sensitivity(b, true)
+    && is_case_insensitive_fs(a)
+}
+
+/// Gets a key for the specifier that's the same for all file specifiers
+/// that only differ in case. Returns `None` when the file system of the
+/// specifier compares paths case sensitively or it's not a file specifier.
+pub fn specifier_case_key(specifier: &ModuleSpecifier) -> Option<String> {
+  if specifier.scheme() != "file" {
+    return None;
+  }
+  let path = specifier.to_file_path().ok()?;
+  is_case_insensitive_fs(&path)
+    .then(|| specifier_case_key_with_sensitivity(specifier, true).into_owned())
+}
+
+fn specifier_case_key_with_sensitivity(
+  specifier: &ModuleSpecifier,
+  case_insensitive: bool,
+) -> Cow<str> {
+  if case_insensitive && specifier.scheme() == "file" {
+    Cow::Owned(specifier.as_str().to_lowercase())
+  } else {
+    Cow::Borrowed(specifier.as_str())
+  }
+}
+
 /// For decoding percent-encodeing string
 /// could be used for module specifier string literal of local modules,
 /// or local file path to display `non-ASCII` characters correctly
@@ -440,6 +576,69 @@ mod test {
     );
   }
 
+  #[test]
+  fn test_path_case_key() {
+    let path = Path::new("/Users/Deno/Mod.ts");
+    assert_eq!(path_case_key_with_sensitivity(path, false), path);
+    assert_eq!(
+      path_case_key_with_sensitivity(path, true),
+      Path::new("/users/deno/mod.ts")
+    );
+    assert!(matches!(
+      path_case_key_with_sensitivity(Path::new("/users/mod.ts"), true),
+      Cow::Borrowed(_)
+    ));
+    assert_eq!(
+      paths_equal(Path::new("/a/B.ts"), Path::new("/a/b.ts")),
+      IS_CASE_INSENSITIVE_FS
+    );
+    assert!(paths_equal(Path::new("/a/b.ts"), Path::new("/a/b.ts")));
+  }
+
+  #[test]
+  fn test_is_case_insensitive_fs() {
+    let temp_dir = test_util::TempDir::new();
+    temp_dir.create_dir_all("Dir");
+    let dir_path = temp_dir.path().join("Dir");
+    let is_case_insensitive = temp_dir.path().join("dir").as_path().exists();
+    assert_eq!(
+      is_case_insensitive_fs(dir_path.as_path()),
+      is_case_insensitive
+    );
+    // uses the closest existing ancestor
+    assert_eq!(
+      is_case_insensitive_fs(dir_path.join("missing.ts").as_path()),
+      is_case_insensitive
+    );
+    assert_eq!(
+      paths_equal(dir_path.as_path(), temp_dir.path().join("dir").as_path()),
+      is_case_insensitive
+    );
+    // a different file that only differs in case isn't the same file
+    if !is_case_insensitive {
+      temp_dir.create_dir_all("dir");
+      assert!(!is_case_insensitive_fs(dir_path.as_path()));
+    }
+  }
+
+  #[test]
+  fn test_specifier_case_key() {
+    let specifier = ModuleSpecifier::parse("file:///C:/Foo/mod.ts").unwrap();
+    assert_eq!(
+      specifier_case_key_with_sensitivity(&specifier, true),
+      "file:///c:/foo/mod.ts"
+    );
+    assert_eq!(
+      specifier_case_key_with_sensitivity(&specifier, false),
+      "file:///C:/Foo/mod.ts"
+    );
+    let specifier = ModuleSpecifier::parse("https://deno.land/X.ts").unwrap();
+    assert_eq!(
+      specifier_case_key_with_sensitivity(&specifier, true),
+      "https://deno.land/X.ts"
+    );
+  }
+
   #[test]
   fn test_to_percent_decoded_str() {
     let str = to_percent_decoded_str("%F0%9F%A6%95");
@@ -28,6 +28,7 @@ pub enum ProgressMessagePrompt {
   Download,
   Blocking,
   Initialize,
+  Check,
 }
 
 impl ProgressMessagePrompt {
@@ -38,6 +39,7 @@ impl ProgressMessagePrompt {
       ProgressMessagePrompt::Initialize => {
         colors::green("Initialize").to_string()
       }
+      ProgressMessagePrompt::Check => colors::green("Check").to_string(),
     }
   }
 }
@@ -67,11 +69,21 @@ impl UpdateGuard {
       entry.set_total_size(value);
     }
   }
+
+  pub fn set_message(&self, value: &str) {
+    if let Some(entry) = &self.maybe_entry {
+      entry.set_message(value);
+    }
+  }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProgressBarStyle {
   DownloadBars,
+  /// A single line with the transferred bytes, throughput and ETA.
+  DownloadBytes,
+  /// A line per pending entry.
+  MultiBars,
   TextOnly,
 }
 
@@ -79,13 +91,22 @@ pub enum ProgressBarStyle {
 struct ProgressBarEntry {
   id: usize,
   prompt: ProgressMessagePrompt,
-  pub message: String,
+  message: Arc<Mutex<String>>,
   pos: Arc<AtomicU64>,
   total_size: Arc<AtomicU64>,
+  start_time: Instant,
   progress_bar: ProgressBarInner,
 }
 
 impl ProgressBarEntry {
+  pub fn message(&self) -> String {
+    self.message.lock().clone()
+  }
+
+  pub fn set_message(&self, new_message: &str) {
+    *self.message.lock() = new_message.to_string();
+  }
+
   pub fn position(&self) -> u64 {
     self.pos.load(Ordering::Relaxed)
   }
@@ -106,6 +127,16 @@ impl ProgressBarEntry {
     self.progress_bar.finish_entry(self.id);
   }
 
+  fn display_entry(&self) -> ProgressDataDisplayEntry {
+    ProgressDataDisplayEntry {
+      prompt: self.prompt,
+      message: self.message(),
+      position: self.position(),
+      total_size: self.total_size(),
+      elapsed: self.start_time.elapsed(),
+    }
+  }
+
   pub fn percent(&self) -> f64 {
     let pos = self.pos.load(Ordering::Relaxed) as f64;
     let total_size = self.total_size.load(Ordering::Relaxed) as f64;
@@ -158,9 +189,10 @@ impl ProgressBarInner {
     let entry = ProgressBarEntry {
       id,
       prompt: kind,
-      message,
+      message: Arc::new(Mutex::new(message)),
       pos: Default::default(),
       total_size: Default::default(),
+      start_time: Instant::now(),
       progress_bar: self.clone(),
     };
     internal_state.entries.push(entry.clone());
@@ -232,12 +264,8 @@ impl DrawThreadRenderer for ProgressBarInner {
         terminal_width: size.cols,
         pending_entries: state.entries.len(),
         total_entries: state.total_entries,
-        display_entry: ProgressDataDisplayEntry {
-          prompt: preferred_entry.prompt,
-          message: preferred_entry.message.clone(),
-          position: preferred_entry.position(),
-          total_size: preferred_entry.total_size(),
-        },
+        display_entry: preferred_entry.display_entry(),
+        entries: state.entries.iter().map(|e| e.display_entry()).collect(),
         percent_done: {
           let mut total_percent_sum = 0f64;
           for entry in &state.entries {
@@ -270,6 +298,12 @@ impl ProgressBar {
         ProgressBarStyle::DownloadBars => {
           Arc::new(renderer::BarProgressBarRenderer)
         }
+        ProgressBarStyle::DownloadBytes => {
+          Arc::new(renderer::BytesProgressBarRenderer)
+        }
+        ProgressBarStyle::MultiBars => {
+          Arc::new(renderer::MultiBarProgressBarRenderer)
+        }
         ProgressBarStyle::TextOnly => {
           Arc::new(renderer::TextOnlyProgressBarRenderer)
         }
@@ -14,12 +14,16 @@ pub struct ProgressDataDisplayEntry {
   pub message: String,
   pub position: u64,
   pub total_size: u64,
+  /// Time since the entry was added.
+  pub elapsed: Duration,
 }
 
 #[derive(Clone)]
 pub struct ProgressData {
   pub terminal_width: u32,
   pub display_entry: ProgressDataDisplayEntry,
+  /// All the pending entries, oldest first.
+  pub entries: Vec<ProgressDataDisplayEntry>,
   pub pending_entries: usize,
   pub percent_done: f64,
   pub total_entries: usize,
@@ -36,23 +40,14 @@ pub struct BarProgressBarRenderer;
 
 impl ProgressBarRenderer for BarProgressBarRenderer {
   fn render(&self, data: ProgressData) -> String {
-    let (bytes_text, bytes_text_max_width) = {
-      let total_size = data.display_entry.total_size;
-      let pos = data.display_entry.position;
-      if total_size == 0 {
-        (String::new(), 0)
-      } else {
-        let total_size_str = human_download_size(total_size, total_size);
-        (
-          format!(
-            " {}/{}",
-            human_download_size(pos, total_size),
-            total_size_str,
-          ),
+    let (bytes_text, bytes_text_max_width) =
+      match position_text(&data.display_entry) {
+        Some((pos_str, total_size_str)) => (
+          format!(" {}/{}", pos_str, total_size_str),
           2 + total_size_str.len() * 2,
-        )
-      }
-    };
+        ),
+        None => (String::new(), 0),
+      };
     let (total_text, total_text_max_width) = if data.total_entries <= 1 {
       (String::new(), 0)
     } else {
@@ -72,7 +67,10 @@ impl ProgressBarRenderer for BarProgressBarRenderer {
     if !data.display_entry.message.is_empty() {
       text.push_str(&format!(
         "{} {}{}\n",
-        colors::green("Download"),
+        match data.display_entry.prompt {
+          ProgressMessagePrompt::Check => data.display_entry.prompt.as_text(),
+          _ => colors::green("Download").to_string(),
+        },
         data.display_entry.message,
         bytes_text,
       ));
@@ -120,18 +118,11 @@ pub struct TextOnlyProgressBarRenderer;
 
 impl ProgressBarRenderer for TextOnlyProgressBarRenderer {
   fn render(&self, data: ProgressData) -> String {
-    let bytes_text = {
-      let total_size = data.display_entry.total_size;
-      let pos = data.display_entry.position;
-      if total_size == 0 {
-        String::new()
-      } else {
-        format!(
-          " {}/{}",
-          human_download_size(pos, total_size),
-          human_download_size(total_size, total_size)
-        )
+    let bytes_text = match position_text(&data.display_entry) {
+      Some((pos_str, total_size_str)) => {
+        format!(" {}/{}", pos_str, total_size_str)
       }
+      None => String::new(),
     };
     let total_text = if data.total_entries <= 1 {
       String::new()
@@ -153,11 +144,135 @@ impl ProgressBarRenderer for TextOnlyProgressBarRenderer {
   }
 }
 
+/// Shows the transferred bytes of a download along with its throughput and
+/// the estimated time remaining.
+#[derive(Debug)]
+pub struct BytesProgressBarRenderer;
+
+impl ProgressBarRenderer for BytesProgressBarRenderer {
+  fn render(&self, data: ProgressData) -> String {
+    let mut text = String::new();
+    if !data.display_entry.message.is_empty() {
+      text.push_str(&format!(
+        "{} {} ",
+        data.display_entry.prompt.as_text(),
+        data.display_entry.message
+      ));
+    }
+    text.push_str(&transfer_text(&data.display_entry));
+    text
+  }
+}
+
+/// Renders a line for each pending entry, so concurrent downloads don't
+/// fight over a single line.
+#[derive(Debug)]
+pub struct MultiBarProgressBarRenderer;
+
+impl ProgressBarRenderer for MultiBarProgressBarRenderer {
+  fn render(&self, data: ProgressData) -> String {
+    const MAX_LINES: usize = 10;
+
+    let mut lines = data
+      .entries
+      .iter()
+      .take(MAX_LINES)
+      .map(|entry| {
+        let transfer_text = transfer_text(entry);
+        if transfer_text.is_empty() {
+          format!("{} {}", entry.prompt.as_text(), entry.message)
+        } else {
+          format!(
+            "{} {} {}",
+            entry.prompt.as_text(),
+            entry.message,
+            colors::gray(transfer_text)
+          )
+        }
+      })
+      .collect::<Vec<_>>();
+    if data.entries.len() > MAX_LINES {
+      lines.push(
+        colors::gray(format!("... {} more", data.entries.len() - MAX_LINES))
+          .to_string(),
+      );
+    }
+    if data.total_entries > 1 {
+      if let Some(last_line) = lines.last_mut() {
+        last_line.push_str(
+          &colors::gray(format!(
+            " ({}/{})",
+            data.total_entries - data.pending_entries,
+            data.total_entries
+          ))
+          .to_string(),
+        );
+      }
+    }
+    lines.join("\n")
+  }
+}
+
+/// Gets the text for the transferred bytes of the entry along with the
+/// throughput and time remaining once they can be estimated.
+fn transfer_text(entry: &ProgressDataDisplayEntry) -> String {
+  let mut parts = Vec::with_capacity(3);
+  match position_text(entry) {
+    Some((pos_str, total_size_str)) => {
+      parts.push(format!("{}/{}", pos_str, total_size_str));
+    }
+    None if entry.position > 0 => {
+      parts.push(human_download_size(entry.position, entry.position));
+    }
+    None => {}
+  }
+  let elapsed_secs = entry.elapsed.as_secs_f64();
+  // the rate is too noisy to show at the start
+  if !matches!(entry.prompt, ProgressMessagePrompt::Check)
+    && entry.position > 0
+    && elapsed_secs >= 0.5
+  {
+    let bytes_per_sec = entry.position as f64 / elapsed_secs;
+    let rate = bytes_per_sec as u64;
+    parts.push(format!("{}/s", human_download_size(rate, rate)));
+    if entry.total_size > entry.position {
+      let remaining_secs =
+        ((entry.total_size - entry.position) as f64 / bytes_per_sec).ceil();
+      parts.push(format!(
+        "ETA {}",
+        get_duration_text(Duration::from_secs(remaining_secs as u64))
+      ));
+    }
+  }
+  parts.join(" ")
+}
+
+/// Gets the text for the position and total of the entry, which is a
+/// count of files when type checking and a download size otherwise.
+fn position_text(entry: &ProgressDataDisplayEntry) -> Option<(String, String)> {
+  let total_size = entry.total_size;
+  let pos = entry.position;
+  if total_size == 0 {
+    return None;
+  }
+  Some(match entry.prompt {
+    ProgressMessagePrompt::Check => (pos.to_string(), total_size.to_string()),
+    _ => (
+      human_download_size(pos, total_size),
+      human_download_size(total_size, total_size),
+    ),
+  })
+}
+
 fn get_elapsed_text(elapsed: Duration) -> String {
-  let elapsed_secs = elapsed.as_secs();
-  let seconds = elapsed_secs % 60;
-  let minutes = elapsed_secs / 60;
-  format!("[{minutes:0>2}:{seconds:0>2}]")
+  format!("[{}]", get_duration_text(elapsed))
+}
+
+fn get_duration_text(duration: Duration) -> String {
+  let total_secs = duration.as_secs();
+  let seconds = total_secs % 60;
+  let minutes = total_secs / 60;
+  format!("{minutes:0>2}:{seconds:0>2}")
 }
 
 #[cfg(test)]
@@ -202,7 +317,9 @@ mod test {
         message: "data".to_string(),
         position: 0,
         total_size: 10 * BYTES_TO_KIB,
+        elapsed: Duration::ZERO,
       },
+      entries: Vec::new(),
       duration: Duration::from_secs(1),
       pending_entries: 1,
       total_entries: 1,
@@ -259,7 +376,9 @@ mod test {
         message: "data".to_string(),
         position: 0,
         total_size: 10 * BYTES_TO_KIB,
+        elapsed: Duration::ZERO,
       },
+      entries: Vec::new(),
       duration: Duration::from_secs(1),
       pending_entries: 1,
       total_entries: 3,
@@ -278,4 +397,116 @@ mod test {
     let text = test_util::strip_ansi_codes(&text);
     assert_eq!(text, "Blocking data");
   }
+
+  #[test]
+  fn should_render_check_progress_as_file_counts() {
+    let renderer = TextOnlyProgressBarRenderer;
+    let data = ProgressData {
+      display_entry: ProgressDataDisplayEntry {
+        prompt: ProgressMessagePrompt::Check,
+        message: "file:///main.ts".to_string(),
+        position: 12,
+        total_size: 340,
+        elapsed: Duration::from_secs(1),
+      },
+      entries: Vec::new(),
+      duration: Duration::from_secs(1),
+      pending_entries: 1,
+      total_entries: 1,
+      percent_done: 0f64,
+      terminal_width: 50,
+    };
+    let text = renderer.render(data.clone());
+    let text = test_util::strip_ansi_codes(&text);
+    assert_eq!(text, "Check file:///main.ts 12/340");
+
+    let text = BarProgressBarRenderer.render(data);
+    let text = test_util::strip_ansi_codes(&text);
+    assert_eq!(
+      text,
+      concat!(
+        "Check file:///main.ts 12/340\n",
+        "[00:01] [---------------------------]",
+      ),
+    );
+  }
+
+  #[test]
+  fn should_render_bytes_progress() {
+    let renderer = BytesProgressBarRenderer;
+    let mut data = ProgressData {
+      display_entry: ProgressDataDisplayEntry {
+        prompt: ProgressMessagePrompt::Download,
+        message: "deno.zip".to_string(),
+        position: 0,
+        total_size: 10 * BYTES_TO_KIB,
+        elapsed: Duration::ZERO,
+      },
+      entries: Vec::new(),
+      duration: Duration::ZERO,
+      pending_entries: 1,
+      total_entries: 1,
+      percent_done: 0f64,
+      terminal_width: 50,
+    };
+    let text = renderer.render(data.clone());
+    let text = test_util::strip_ansi_codes(&text);
+    assert_eq!(text, "Download deno.zip 0.00KiB/10.00KiB");
+
+    data.display_entry.position = 4 * BYTES_TO_KIB;
+    data.display_entry.elapsed = Duration::from_secs(2);
+    let text = renderer.render(data.clone());
+    let text = test_util::strip_ansi_codes(&text);
+    assert_eq!(
+      text,
+      "Download deno.zip 4.00KiB/10.00KiB 2.00KiB/s ETA 00:03"
+    );
+
+    // unknown size
+    data.display_entry.message = String::new();
+    data.display_entry.total_size = 0;
+    let text = renderer.render(data);
+    let text = test_util::strip_ansi_codes(&text);
+    assert_eq!(text, "4.00KiB 2.00KiB/s");
+  }
+
+  #[test]
+  fn should_render_multi_bar_progress() {
+    let renderer = MultiBarProgressBarRenderer;
+    let entry = |message: &str, position: u64| ProgressDataDisplayEntry {
+      prompt: ProgressMessagePrompt::Download,
+      message: message.to_string(),
+      position,
+      total_size: if position == 0 { 0 } else { 10 * BYTES_TO_KIB },
+      elapsed: Duration::from_secs(1),
+    };
+    let mut data = ProgressData {
+      display_entry: entry("a.tgz", 0),
+      entries: vec![entry("a.tgz", 0), entry("b.tgz", 5 * BYTES_TO_KIB)],
+      duration: Duration::from_secs(1),
+      pending_entries: 2,
+      total_entries: 3,
+      percent_done: 0f64,
+      terminal_width: 50,
+    };
+    let text = renderer.render(data.clone());
+    let text = test_util::strip_ansi_codes(&text);
+    assert_eq!(
+      text,
+      concat!(
+        "Download a.tgz\n",
+        "Download b.tgz 5.00KiB/10.00KiB 5.00KiB/s ETA 00:01 (1/3)",
+      )
+    );
+
+    data.entries = (0..12).map(|i| entry(&format!("{i}.tgz"), 0)).collect();
+    data.pending_entries = 12;
+    data.total_entries = 12;
+    let text = renderer.render(data);
+    let text = test_util::strip_ansi_codes(&text);
+    let lines = text.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 11);
+    assert_eq!(lines[9], "Download 9.tgz");
+    assert_eq!(lines[10], "... 2 more (0/12)");
+  }
 }
@@ -353,6 +353,119 @@ fn bundle_import_map_no_check() {
   assert_eq!(output.stderr, b"");
 }
 
+#[test]
+fn bundle_external_sourcemap() {
+  let mod1 = util::testdata_path().join("subdir/mod1.ts");
+  let t = TempDir::new();
+  let bundle = t.path().join("mod1.bundle.js");
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("bundle")
+    .arg("--sourcemap=external")
+    .arg(mod1)
+    .arg(&bundle)
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  let code = std::fs::read_to_string(&bundle).unwrap();
+  assert_ends_with!(code.trim_end(), "//# sourceMappingURL=mod1.bundle.js.map");
+  let map =
+    std::fs::read_to_string(t.path().join("mod1.bundle.js.map")).unwrap();
+  assert_contains!(map, "\"sourcesContent\"");
+
+  // an external source map can't be written to stdout
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("bundle")
+    .arg("--sourcemap=external")
+    .arg("subdir/mod1.ts")
+    .output()
+    .unwrap();
+  assert!(!output.status.success());
+  assert_contains!(
+    String::from_utf8(output.stderr).unwrap(),
+    "An output file is required to write an external source map."
+  );
+}
+
+#[test]
+fn bundle_iife_global_name() {
+  let mod1 = util::testdata_path().join("subdir/mod1.ts");
+  let t = TempDir::new();
+  let bundle = t.path().join("mod1.bundle.js");
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("bundle")
+    .arg("--format=iife")
+    .arg("--global-name=Mod1")
+    .arg(mod1)
+    .arg(&bundle)
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+
+  // evaluate the bundle as a classic script in the global scope
+  let test = t.path().join("test.js");
+  std::fs::write(
+    &test,
+    "
+      (0, eval)(Deno.readTextFileSync(\"./mod1.bundle.js\"));
+      Mod1.printHello3(); ",
+  )
+  .unwrap();
+  let output = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("run")
+    .arg("--allow-read")
+    .arg(&test)
+    .output()
+    .unwrap();
+  assert_ends_with!(
+    std::str::from_utf8(&output.stdout).unwrap().trim(),
+    "Hello",
+  );
+  assert_eq!(output.stderr, b"");
+}
+
+#[test]
+fn bundle_cjs() {
+  let mod1 = util::testdata_path().join("subdir/mod1.ts");
+  let t = TempDir::new();
+  let bundle = t.path().join("mod1.bundle.cjs");
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("bundle")
+    .arg("--format=cjs")
+    .arg(mod1)
+    .arg(&bundle)
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+
+  let test = t.path().join("test.js");
+  std::fs::write(
+    &test,
+    "
+      import { createRequire } from \"node:module\";
+      const require = createRequire(import.meta.url);
+      const { printHello3 } = require(\"./mod1.bundle.cjs\");
+      printHello3(); ",
+  )
+  .unwrap();
+  let output = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("run")
+    .arg("--allow-read")
+    .arg(&test)
+    .output()
+    .unwrap();
+  assert_ends_with!(
+    std::str::from_utf8(&output.stdout).unwrap().trim(),
+    "Hello",
+  );
+  assert_eq!(output.stderr, b"");
+}
+
 #[test]
 fn bundle_json_module() {
   // First we have to generate a bundle of some module that has exports.
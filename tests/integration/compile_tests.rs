@@ -612,12 +612,14 @@ fn workers_not_in_module_map() {
   output.assert_exit_code(0);
   output.skip_output_check();
 
-  let output = context.new_command().name(exe).env("NO_COLOR", "").run();
-  output.assert_exit_code(1);
-  output.assert_matches_text(concat!(
-    "error: Uncaught (in worker \"\") Module not found: [WILDCARD]",
-    "error: Uncaught (in promise) Error: Unhandled error in child worker.\n[WILDCARD]"
-  ));
+  // the worker entrypoint is found in the source and included
+  context
+    .new_command()
+    .name(&exe)
+    .env("NO_COLOR", "")
+    .run()
+    .assert_matches_text("Hello from worker!\nReceived 42\nClosing\n")
+    .assert_exit_code(0);
 }
 
 #[test]
@@ -715,6 +717,42 @@ fn dynamic_import_unanalyzable() {
     .assert_exit_code(0);
 }
 
+#[test]
+fn dynamic_import_unanalyzable_include_dir() {
+  let context = TestContext::with_http_server();
+  let dir = context.temp_dir();
+  let exe = if cfg!(windows) {
+    dir
+      .path()
+      .join("dynamic_import_unanalyzable_include_dir.exe")
+  } else {
+    dir.path().join("dynamic_import_unanalyzable_include_dir")
+  };
+  context
+    .new_command()
+    .args_vec([
+      "compile",
+      "--allow-read",
+      "--include",
+      "./compile/dynamic_imports/",
+      "--output",
+      &exe.to_string_lossy(),
+      "./compile/dynamic_imports/main_unanalyzable.ts",
+    ])
+    .run()
+    .skip_output_check()
+    .assert_exit_code(0);
+
+  context
+    .new_command()
+    .current_dir(util::root_path())
+    .name(&exe)
+    .env("NO_COLOR", "")
+    .run()
+    .assert_matches_file("./compile/dynamic_imports/main.out")
+    .assert_exit_code(0);
+}
+
 #[test]
 fn compile_npm_specifiers() {
   let context = TestContextBuilder::for_npm().use_temp_cwd().build();
@@ -4,6 +4,7 @@ use deno_core::serde_json;
 use test_util as util;
 use test_util::TempDir;
 use util::assert_contains;
+use util::assert_not_contains;
 use util::assert_starts_with;
 use util::env_vars_for_npm_tests;
 use util::PathRef;
@@ -516,6 +517,88 @@ fn test_html_reporter() {
   );
 }
 
+#[test]
+fn test_html_reporter_with_dir() {
+  let context = TestContext::default();
+  let tempdir = context.temp_dir();
+  let html_dir = tempdir.path().join("html_cov");
+  let tempdir = tempdir.path().join("cov");
+
+  let output = context
+    .new_command()
+    .args_vec(vec![
+      "test".to_string(),
+      "--quiet".to_string(),
+      format!("--coverage={}", tempdir),
+      "coverage/multisource".to_string(),
+    ])
+    .run();
+
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  let output = context
+    .new_command()
+    .args_vec(vec![
+      "coverage".to_string(),
+      format!("--html={}", html_dir),
+      format!("{}/", tempdir),
+    ])
+    .run();
+
+  output.assert_exit_code(0);
+  output.assert_matches_text(
+    "HTML coverage report has been generated at [WILDCARD]/html_cov/index.html\n",
+  );
+
+  assert!(!tempdir.join("html").exists());
+  let index_html = html_dir.join("index.html").read_to_string();
+  assert_contains!(index_html, "<h1>Coverage report for all files</h1>");
+  let foo_ts_html = html_dir.join("foo.ts.html").read_to_string();
+  assert_contains!(foo_ts_html, "<h1>Coverage report for foo.ts</h1>");
+}
+
+#[test]
+fn test_cobertura_reporter() {
+  let context = TestContext::default();
+  let tempdir = context.temp_dir();
+  let tempdir = tempdir.path().join("cov");
+
+  let output = context
+    .new_command()
+    .args_vec(vec![
+      "test".to_string(),
+      "--quiet".to_string(),
+      format!("--coverage={}", tempdir),
+      "coverage/multisource".to_string(),
+    ])
+    .run();
+
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  let output = context
+    .new_command()
+    .args_vec(vec![
+      "coverage".to_string(),
+      format!("--cobertura={}/coverage.xml", tempdir),
+      format!("{}/", tempdir),
+    ])
+    .run();
+
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  let xml = tempdir.join("coverage.xml").read_to_string();
+  assert_contains!(xml, "<!DOCTYPE coverage SYSTEM");
+  assert_contains!(xml, "<coverage line-rate=\"0.6");
+  assert_contains!(xml, "branch-rate=\"0.4000\"");
+  assert_contains!(xml, "<package name=\"\"");
+  assert_contains!(xml, "<package name=\"baz\"");
+  assert_contains!(xml, "<class name=\"foo.ts\" filename=\"foo.ts\"");
+  assert_contains!(xml, "<class name=\"qux.ts\" filename=\"baz/qux.ts\"");
+}
+
 #[test]
 fn test_summary_reporter() {
   let context = TestContext::default();
@@ -556,6 +639,127 @@ File         | Branch % | Line % |
   );
 }
 
+#[test]
+fn test_check_thresholds() {
+  let context = TestContext::default();
+  let temp_dir = context.temp_dir();
+  let tempdir = temp_dir.path().join("cov");
+
+  let output = context
+    .new_command()
+    .args_vec(vec![
+      "test".to_string(),
+      "--quiet".to_string(),
+      format!("--coverage={}", tempdir),
+      "coverage/multisource".to_string(),
+    ])
+    .run();
+
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  // the lines are 61.0% and the branches 40.0% covered
+  let output = context
+    .new_command()
+    .args_vec(vec![
+      "coverage".to_string(),
+      "--check".to_string(),
+      "--lines=60".to_string(),
+      "--branches=50".to_string(),
+      format!("{}/", tempdir),
+    ])
+    .run();
+
+  output.assert_exit_code(1);
+  output.assert_matches_text(
+    "[WILDCARD]error: Coverage check failed: branch coverage 40.0% is below the threshold of 50%\n",
+  );
+
+  let output = context
+    .new_command()
+    .args_vec(vec![
+      "coverage".to_string(),
+      "--check".to_string(),
+      "--lines=60".to_string(),
+      "--branches=40".to_string(),
+      format!("{}/", tempdir),
+    ])
+    .run();
+
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  // the thresholds of the config file are used without the flags
+  temp_dir.write(
+    "deno.json",
+    r#"{ "test": { "coverage": { "lines": 70, "branches": 40 } } }"#,
+  );
+  let output = context
+    .new_command()
+    .current_dir(temp_dir.path())
+    .args_vec(vec![
+      "coverage".to_string(),
+      "--check".to_string(),
+      format!("{}/", tempdir),
+    ])
+    .run();
+
+  output.assert_exit_code(1);
+  output.assert_matches_text(
+    "[WILDCARD]error: Coverage check failed: line coverage 61.0% is below the threshold of 70%\n",
+  );
+}
+
+#[test]
+fn test_config_include_exclude() {
+  let context = TestContext::default();
+  let temp_dir = context.temp_dir();
+  let project = temp_dir.path().join("project");
+  util::testdata_path()
+    .join("coverage/multisource")
+    .copy_to_recursive(&project);
+  temp_dir.write(
+    "project/deno.json",
+    r#"{ "test": { "coverage": { "exclude": ["baz/"] } } }"#,
+  );
+
+  let output = context
+    .new_command()
+    .current_dir(&project)
+    .args("test --quiet --coverage=cov")
+    .run();
+
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  // the excluded files aren't collected
+  let output = context
+    .new_command()
+    .current_dir(&project)
+    .args("coverage cov/")
+    .run();
+
+  output.assert_exit_code(0);
+  assert_contains!(output.combined_output(), "bar.ts");
+  assert_contains!(output.combined_output(), "foo.ts");
+  assert_not_contains!(output.combined_output(), "baz/");
+
+  // the config is also applied to the collected files when reporting them
+  temp_dir.write(
+    "project/deno.json",
+    r#"{ "test": { "coverage": { "include": ["foo.ts"] } } }"#,
+  );
+  let output = context
+    .new_command()
+    .current_dir(&project)
+    .args("coverage cov/")
+    .run();
+
+  output.assert_exit_code(0);
+  assert_contains!(output.combined_output(), "foo.ts");
+  assert_not_contains!(output.combined_output(), "bar.ts");
+}
+
 #[test]
 fn test_collect_summary_with_no_matches() {
   let context: TestContext = TestContext::default();
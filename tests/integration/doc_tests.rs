@@ -4,6 +4,7 @@ use test_util as util;
 use test_util::itest;
 use util::assert_contains;
 use util::TestContext;
+use util::TestContextBuilder;
 
 itest!(deno_doc_builtin {
   args: "doc",
@@ -71,6 +72,12 @@ itest!(deno_doc_html_lint_referenced_private_types_fixed {
   output: "doc/referenced_private_types_lint.out",
 });
 
+itest!(deno_doc_lint_param_description_error {
+  args: "doc --lint doc/lint_param_description.ts",
+  exit_code: 1,
+  output: "doc/lint_param_description.out",
+});
+
 itest!(deno_doc_lint_success {
   args: "doc --lint doc/lint_success.ts",
   output: "doc/lint_success.out",
@@ -142,3 +149,37 @@ fn deno_doc_html() {
     .join("~/MyClass.prototype.prop.html")
     .exists());
 }
+
+#[test]
+fn deno_doc_npm_package_types() {
+  let context = TestContextBuilder::for_npm().use_temp_cwd().build();
+  let output = context
+    .new_command()
+    .env("NO_COLOR", "1")
+    .args("doc npm:@denotest/esm-basic")
+    .split_output()
+    .run();
+
+  output.assert_exit_code(0);
+  assert_contains!(output.stdout(), "function setValue(val: number): void");
+  assert_contains!(output.stdout(), "const url: string");
+}
+
+#[test]
+fn deno_doc_npm_package_definitely_typed() {
+  let context = TestContextBuilder::for_npm().use_temp_cwd().build();
+  // the types of 1.2.3 are in the 1.2.0 version of the @types package
+  let output = context
+    .new_command()
+    .env("NO_COLOR", "1")
+    .args("doc npm:@denotest/untyped@^1.2.3")
+    .split_output()
+    .run();
+
+  output.assert_exit_code(0);
+  assert_contains!(
+    output.stdout(),
+    "function add(a: number, b: number): number"
+  );
+  assert_contains!(output.stdout(), "Adds two numbers.");
+}
@@ -9413,6 +9413,54 @@ fn lsp_format_mbc() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_format_range() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "const a = 'a'\nconsole.log('hello deno')\n"
+    }
+  }));
+  let res = client.write_request(
+    "textDocument/rangeFormatting",
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts"
+      },
+      "range": {
+        "start": { "line": 1, "character": 0 },
+        "end": { "line": 1, "character": 25 }
+      },
+      "options": {
+        "tabSize": 2,
+        "insertSpaces": true
+      }
+    }),
+  );
+  assert_eq!(
+    res,
+    json!([{
+      "range": {
+        "start": { "line": 1, "character": 12 },
+        "end": { "line": 1, "character": 13 }
+      },
+      "newText": "\""
+    }, {
+      "range": {
+        "start": { "line": 1, "character": 23 },
+        "end": { "line": 1, "character": 25 }
+      },
+      "newText": "\");"
+    }])
+  );
+  client.shutdown();
+}
+
 #[test]
 fn lsp_format_exclude_with_config() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
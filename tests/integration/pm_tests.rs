@@ -146,6 +146,76 @@ fn add_npm() {
   }));
 }
 
+#[test]
+fn add_alias() {
+  let context = pm_context_builder().build();
+  let temp_dir = context.temp_dir().path();
+  temp_dir.join("deno.json").write_json(&json!({}));
+
+  let output = context
+    .new_command()
+    .args("add add@jsr:@denotest/add my-chalk@npm:chalk@4.1")
+    .run();
+  output.assert_exit_code(0);
+  let output = output.combined_output();
+  assert_contains!(output, "Add add - jsr:@denotest/add@^1.0.0");
+  assert_contains!(output, "Add my-chalk - npm:chalk@^4.1.2");
+  temp_dir.join("deno.json").assert_matches_json(json!({
+    "imports": {
+      "add": "jsr:@denotest/add@^1.0.0",
+      "my-chalk": "npm:chalk@^4.1.2"
+    }
+  }));
+}
+
+#[test]
+fn add_updates_lockfile() {
+  let context = pm_context_builder().build();
+  let temp_dir = context.temp_dir().path();
+  temp_dir.join("deno.json").write_json(&json!({}));
+
+  let output = context.new_command().args("add @denotest/add").run();
+  output.assert_exit_code(0);
+  let lockfile = temp_dir.join("deno.lock").read_to_string();
+  assert_contains!(
+    lockfile,
+    r#""jsr:@denotest/add@^1.0.0": "jsr:@denotest/add@1.0.0""#
+  );
+}
+
+#[test]
+fn remove_basic() {
+  let context = pm_context_builder().build();
+  let temp_dir = context.temp_dir().path();
+  temp_dir.join("deno.json").write_json(&json!({}));
+
+  let output = context
+    .new_command()
+    .args("add @denotest/add @denotest/subset-type-graph")
+    .run();
+  output.assert_exit_code(0);
+
+  let output = context
+    .new_command()
+    .args("remove jsr:@denotest/add @denotest/non-existent")
+    .run();
+  output.assert_exit_code(0);
+  let output = output.combined_output();
+  assert_contains!(output, "Remove @denotest/add - jsr:@denotest/add@^1.0.0");
+  assert_contains!(
+    output,
+    "Warning @denotest/non-existent was not found in deno.json."
+  );
+  temp_dir.join("deno.json").assert_matches_json(json!({
+    "imports": {
+      "@denotest/subset-type-graph": "jsr:@denotest/subset-type-graph@^0.1.0"
+    }
+  }));
+  let lockfile = temp_dir.join("deno.lock").read_to_string();
+  assert!(!lockfile.contains("jsr:@denotest/add@^1.0.0"));
+  assert_contains!(lockfile, "jsr:@denotest/subset-type-graph@^0.1.0");
+}
+
 fn pm_context_builder() -> TestContextBuilder {
   TestContextBuilder::new()
     .use_http_server()
@@ -312,6 +312,23 @@ fn junit_path() {
     .assert_matches_text("<?xml [WILDCARD]");
 }
 
+#[test]
+fn junit_reporter_output() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("test.js", "Deno.test('does test', () => {});");
+  let output = context
+    .new_command()
+    .args("test --reporter=junit --output=report.xml test.js")
+    .run();
+  output.assert_exit_code(0);
+  // the report is only written to the file
+  assert!(!output.combined_output().contains("<?xml"));
+  temp_dir.path().join("report.xml").assert_matches_text(
+    "<?xml [WILDCARD]<testsuite name=\"./test.js\" tests=\"1\"[WILDCARD]<testcase name=\"does test\"[WILDCARD]",
+  );
+}
+
 itest!(clear_timeout {
   args: "test test/clear_timeout.ts",
   exit_code: 0,
@@ -6,6 +6,7 @@ use pretty_assertions::assert_eq;
 use std::fmt::Write as _;
 use std::path::PathBuf;
 use test_util as util;
+use test_util::assert_contains;
 use test_util::itest;
 use test_util::TempDir;
 use util::http_server;
@@ -74,6 +75,140 @@ fn output_dir_exists() {
   assert!(status.success());
 }
 
+#[test]
+fn prune_output_dir() {
+  let _server = http_server();
+  let t = TempDir::new();
+  t.write(
+    "my_app.ts",
+    "import 'http://localhost:4545/vendor/query_reexport.ts';",
+  );
+  t.create_dir_all("vendor/localhost_4545/old");
+  t.write("vendor/localhost_4545/old/mod.ts", "");
+  t.write("vendor/README.md", "");
+
+  // directories that weren't vendored before are not pruned
+  let output = util::deno_cmd()
+    .current_dir(t.path())
+    .env("NO_COLOR", "1")
+    .arg("vendor")
+    .arg("--prune")
+    .arg("my_app.ts")
+    .stderr_piped()
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert_eq!(
+    String::from_utf8_lossy(&output.stderr).trim(),
+    concat!(
+      "error: Output directory was not vendored before. Please specify a ",
+      "vendored directory or use --force to ignore this error and ",
+      "potentially overwrite its contents.",
+    ),
+  );
+  assert!(!output.status.success());
+
+  t.write(
+    "vendor/.vendor_manifest.json",
+    r#"{ "files": ["localhost_4545/old/mod.ts"] }"#,
+  );
+  let output = util::deno_cmd()
+    .current_dir(t.path())
+    .env("NO_COLOR", "1")
+    .arg("vendor")
+    .arg("--prune")
+    .arg("my_app.ts")
+    .piped_output()
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  assert_contains!(stderr, "Removed 1 unused file from vendor/ directory.");
+  assert!(output.status.success());
+  assert!(!t.path().join("vendor/localhost_4545/old").exists());
+  assert!(t
+    .path()
+    .join("vendor/localhost_4545/vendor/logger.ts")
+    .exists());
+  assert!(t.path().join("vendor/import_map.json").exists());
+  // files of the user are kept
+  assert!(t.path().join("vendor/README.md").exists());
+}
+
+#[test]
+fn check_vendored_files() {
+  let _server = http_server();
+  let t = TempDir::new();
+  t.write("deno.json", "{}");
+  t.write(
+    "my_app.ts",
+    "import 'http://localhost:4545/vendor/query_reexport.ts';",
+  );
+
+  // the lock file isn't created unless it's asked for
+  let status = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("vendor")
+    .arg("my_app.ts")
+    .spawn()
+    .unwrap()
+    .wait()
+    .unwrap();
+  assert!(status.success());
+  assert!(!t.path().join("deno.lock").exists());
+
+  let status = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("vendor")
+    .arg("--lock")
+    .arg("--update")
+    .arg("my_app.ts")
+    .spawn()
+    .unwrap()
+    .wait()
+    .unwrap();
+  assert!(status.success());
+  assert!(t.path().join("deno.lock").exists());
+
+  let output = util::deno_cmd()
+    .current_dir(t.path())
+    .env("NO_COLOR", "1")
+    .arg("vendor")
+    .arg("--check")
+    .piped_output()
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert_eq!(
+    String::from_utf8_lossy(&output.stderr).trim(),
+    "Checked 2 vendored files in vendor/ directory.",
+  );
+  assert!(output.status.success());
+
+  t.write("vendor/localhost_4545/vendor/logger.ts", "// tampered");
+  let output = util::deno_cmd()
+    .current_dir(t.path())
+    .env("NO_COLOR", "1")
+    .arg("vendor")
+    .arg("--check")
+    .piped_output()
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  assert_contains!(stderr, "modified ");
+  assert_contains!(stderr, "(http://localhost:4545/vendor/logger.ts?test)");
+  assert_contains!(
+    stderr,
+    "error: 1 of 2 vendored files does not match the lock file."
+  );
+  assert!(!output.status.success());
+}
+
 #[test]
 fn standard_test() {
   let _server = http_server();
@@ -561,6 +696,74 @@ fn update_existing_empty_config_test() {
   assert!(output.status.success());
 }
 
+#[test]
+fn merge_existing_config_test() {
+  let _server = http_server();
+  let t = TempDir::new();
+  t.write(
+    "my_app.ts",
+    "import {Logger} from 'http://localhost:4545/vendor/logger.ts'; new Logger().log('outputted');",
+  );
+  t.write(
+    "deno.json",
+    r#"{
+  // comment
+  "imports": {
+    "@std/path": "jsr:@std/path@0.220"
+  }
+}
+"#,
+  );
+
+  for args in [vec![], vec!["--force"]] {
+    let deno = util::deno_cmd()
+      .current_dir(t.path())
+      .arg("vendor")
+      .arg("--merge-config")
+      .args_vec(args)
+      .arg("my_app.ts")
+      .env("NO_COLOR", "1")
+      .piped_output()
+      .spawn()
+      .unwrap();
+    let output = deno.wait_with_output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+      stderr.contains("Merged the vendored import map"),
+      "{}",
+      stderr
+    );
+    assert!(output.status.success());
+    assert_eq!(
+      t.read_to_string("deno.json"),
+      r#"{
+  // comment
+  "imports": {
+    "@std/path": "jsr:@std/path@0.220",
+    "http://localhost:4545/": "./vendor/localhost_4545/"
+  }
+}
+"#
+    );
+  }
+
+  let deno = util::deno_cmd()
+    .current_dir(t.path())
+    .env("NO_COLOR", "1")
+    .arg("run")
+    .arg("--no-remote")
+    .arg("--check")
+    .arg("--quiet")
+    .arg("my_app.ts")
+    .piped_output()
+    .spawn()
+    .unwrap();
+  let output = deno.wait_with_output().unwrap();
+  assert_eq!(String::from_utf8_lossy(&output.stderr).trim(), "");
+  assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "outputted");
+  assert!(output.status.success());
+}
+
 #[test]
 fn vendor_npm_node_specifiers() {
   let context = TestContextBuilder::for_npm().use_temp_cwd().build();
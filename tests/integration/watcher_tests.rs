@@ -349,6 +349,64 @@ async fn lint_all_files_on_each_change_test() {
   drop(t);
 }
 
+#[flaky_test]
+#[tokio::main]
+async fn lint_watch_only_changed_files_test() {
+  let t = TempDir::new();
+  let badly_linted_fixed0 =
+    util::testdata_path().join("lint/watch/badly_linted.js");
+  let badly_linted_fixed1 =
+    util::testdata_path().join("lint/watch/badly_linted_fixed1.js");
+  let badly_linted_fixed2 =
+    util::testdata_path().join("lint/watch/badly_linted_fixed2.js");
+
+  let badly_linted_1 = t.path().join("badly_linted_1.js");
+  let badly_linted_2 = t.path().join("badly_linted_2.js");
+  badly_linted_fixed0.copy(&badly_linted_1);
+  badly_linted_fixed1.copy(&badly_linted_2);
+
+  let mut child = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("lint")
+    .arg(t.path())
+    .arg("--watch")
+    .piped_output()
+    .spawn()
+    .unwrap();
+  let (_stdout_lines, mut stderr_lines) = child_lines(&mut child);
+
+  assert_contains!(
+    wait_contains("Checked", &mut stderr_lines).await,
+    "Checked 2 files"
+  );
+
+  badly_linted_fixed2.copy(&badly_linted_2);
+
+  // the diagnostics of the unchanged file aren't reported again
+  let mut output = String::new();
+  while let Some(line) = next_line(&mut stderr_lines).await {
+    let line = util::strip_ansi_codes(&line).to_string();
+    output.push_str(&line);
+    output.push('\n');
+    if line.starts_with("Checked") {
+      break;
+    }
+  }
+  assert_not_contains!(output, "error[");
+  assert_contains!(output, "Checked 2 files");
+  let output = wait_contains("resolved problems", &mut stderr_lines).await;
+  assert_contains!(
+    output,
+    "0 new and 1 resolved problems since the previous run"
+  );
+  assert_contains!(
+    next_line(&mut stderr_lines).await.unwrap(),
+    "- badly_linted_2.js: `_a` is never reassigned (prefer-const)"
+  );
+
+  check_alive_then_kill(child);
+}
+
 #[flaky_test]
 #[tokio::main]
 async fn fmt_watch_test() {
@@ -1189,6 +1247,84 @@ async fn test_watch_doc() {
   check_alive_then_kill(child);
 }
 
+#[flaky_test]
+#[tokio::main]
+async fn test_watch_config_change_reruns_all() {
+  let t = TempDir::new();
+  let config_file = t.path().join("deno.json");
+  config_file.write("{}");
+  t.path()
+    .join("foo_test.js")
+    .write("Deno.test('foo', () => {});");
+  t.path()
+    .join("bar_test.js")
+    .write("Deno.test('bar', () => {});");
+
+  let mut child = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("test")
+    .arg("--watch")
+    .arg("--no-check")
+    .env("NO_COLOR", "1")
+    .piped_output()
+    .spawn()
+    .unwrap();
+  let (mut stdout_lines, mut stderr_lines) = child_lines(&mut child);
+
+  wait_contains("2 passed | 0 failed", &mut stdout_lines).await;
+  wait_contains("Test finished", &mut stderr_lines).await;
+
+  // none of the test modules import the config file, but its changes may
+  // affect the resolution of any of them
+  config_file.write(r#"{ "imports": {} }"#);
+  assert_contains!(next_line(&mut stderr_lines).await.unwrap(), "Restarting");
+  wait_contains("2 passed | 0 failed", &mut stdout_lines).await;
+  wait_contains("Test finished", &mut stderr_lines).await;
+
+  check_alive_then_kill(child);
+}
+
+#[flaky_test]
+#[tokio::main]
+async fn test_watch_setup_and_teardown() {
+  let t = TempDir::new();
+  t.path().join("deno.json").write(
+    r#"{ "test": { "setup": "./setup.js", "teardown": "./teardown.js" } }"#,
+  );
+  t.path().join("setup.js").write("console.log('setup');");
+  t.path()
+    .join("teardown.js")
+    .write("console.log('teardown');");
+  let foo_test = t.path().join("foo_test.js");
+  foo_test.write("Deno.test('foo', () => {});");
+
+  let mut child = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("test")
+    .arg("--watch")
+    .arg("--no-check")
+    .env("NO_COLOR", "1")
+    .piped_output()
+    .spawn()
+    .unwrap();
+  let (mut stdout_lines, mut stderr_lines) = child_lines(&mut child);
+
+  wait_contains("setup", &mut stdout_lines).await;
+  wait_contains("1 passed | 0 failed", &mut stdout_lines).await;
+  wait_contains("teardown", &mut stdout_lines).await;
+  wait_contains("Test finished", &mut stderr_lines).await;
+
+  // the setup and teardown run again for every rerun
+  foo_test.write("Deno.test('foobar', () => {});");
+  assert_contains!(next_line(&mut stderr_lines).await.unwrap(), "Restarting");
+  wait_contains("setup", &mut stdout_lines).await;
+  wait_contains("1 passed | 0 failed", &mut stdout_lines).await;
+  wait_contains("teardown", &mut stdout_lines).await;
+  wait_contains("Test finished", &mut stderr_lines).await;
+
+  check_alive_then_kill(child);
+}
+
 #[flaky_test]
 #[tokio::main]
 async fn test_watch_module_graph_error_referrer() {
@@ -1431,6 +1567,50 @@ async fn bench_watch_basic() {
 }
 
 // Regression test for https://github.com/denoland/deno/issues/15465.
+#[flaky_test]
+#[tokio::main]
+async fn check_watch_basic() {
+  let t = TempDir::new();
+  let file_to_watch = t.path().join("file_to_watch.ts");
+  let dep = t.path().join("dep.ts");
+  dep.write("export const value: string = 'hello';");
+  file_to_watch.write(
+    "import { value } from './dep.ts';\nconst num: number = value;\nconsole.log(num);",
+  );
+
+  let mut child = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("check")
+    .arg("--watch")
+    .arg("-L")
+    .arg("debug")
+    .arg(&file_to_watch)
+    .env("NO_COLOR", "1")
+    .piped_output()
+    .spawn()
+    .unwrap();
+  let (_stdout_lines, mut stderr_lines) = child_lines(&mut child);
+
+  wait_contains("Check started", &mut stderr_lines).await;
+  wait_contains("TS2322", &mut stderr_lines).await;
+  wait_contains("Check failed", &mut stderr_lines).await;
+  wait_for_watcher("dep.ts", &mut stderr_lines).await;
+
+  // fixing the dependency should make the check pass
+  dep.write("export const value: number = 1;");
+  wait_contains("File change detected", &mut stderr_lines).await;
+  wait_contains("Check finished", &mut stderr_lines).await;
+  wait_for_watcher("file_to_watch.ts", &mut stderr_lines).await;
+
+  // and introducing an error should report it again
+  file_to_watch.write("const str: string = 1;\nconsole.log(str);");
+  wait_contains("File change detected", &mut stderr_lines).await;
+  wait_contains("TS2322", &mut stderr_lines).await;
+  wait_contains("Check failed", &mut stderr_lines).await;
+
+  check_alive_then_kill(child);
+}
+
 #[flaky_test]
 #[tokio::main]
 async fn run_watch_reload_once() {
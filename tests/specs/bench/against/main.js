@@ -0,0 +1,3 @@
+Deno.bench("logs", { warmupTime: 0, minIterations: 1, maxTime: 0 }, () => {
+  console.log("{\n}");
+});
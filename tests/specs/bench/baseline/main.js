@@ -0,0 +1,7 @@
+Deno.bench("sum", () => {
+  let sum = 0;
+  for (let i = 0; i < 100; i++) {
+    sum += i;
+  }
+  return sum;
+});
@@ -0,0 +1 @@
+Deno.bench("invalid", { maxTime: -1 }, () => {});
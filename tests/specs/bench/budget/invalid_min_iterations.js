@@ -0,0 +1 @@
+Deno.bench("invalid", { minIterations: 0 }, () => {});
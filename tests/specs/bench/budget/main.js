@@ -0,0 +1,7 @@
+Deno.bench("flags", () => {});
+
+Deno.bench(
+  "options",
+  { warmupTime: 0, minIterations: 2, maxTime: 0 },
+  () => {},
+);
@@ -0,0 +1,14 @@
+Deno.bench("noop", () => {});
+
+Deno.bench("parse url", { group: "url", baseline: true }, () => {
+  new URL("https://deno.land/std/http/server.ts");
+});
+
+Deno.bench("parse url 2x", { group: "url" }, () => {
+  new URL("https://deno.land/std/http/server.ts");
+  new URL("https://deno.land/std/http/server.ts");
+});
+
+Deno.bench("encode", { group: "text" }, () => {
+  new TextEncoder().encode("deno");
+});
@@ -0,0 +1,2 @@
+let   unused : number = "string";
+Deno.bench("generated", () => { throw new Error("should be excluded") });
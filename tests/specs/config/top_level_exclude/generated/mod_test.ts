@@ -0,0 +1,3 @@
+// not formatted, has lint errors, type errors and failing tests
+let   unused : number = "string";
+Deno.test("generated", () => { throw new Error("should be excluded") });
@@ -0,0 +1,5 @@
+import { add } from "./main.ts";
+
+Deno.bench("add", () => {
+  add(1, 2);
+});
@@ -0,0 +1,7 @@
+import { add } from "./main.ts";
+
+Deno.test("add", () => {
+  if (add(1, 2) !== 3) {
+    throw new Error("fail");
+  }
+});
@@ -0,0 +1 @@
+const c = 3
@@ -0,0 +1,4 @@
+import { add } from "utils";
+import { value } from "@app/value.ts";
+
+console.log(add(value, 1));
@@ -0,0 +1 @@
+import "jsr:@denotest/deps";
@@ -0,0 +1,2 @@
+import "npm:@denotest/esm-import-cjs-default@1.0.0";
+import "npm:@denotest/cjs-default-export@1.0.0";
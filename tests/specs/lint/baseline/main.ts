@@ -0,0 +1 @@
+export var value: any = 1;
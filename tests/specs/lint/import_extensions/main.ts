@@ -0,0 +1,6 @@
+import { a } from "./a";
+import { b } from "./b.ts";
+import { c } from "./c";
+import { missing } from "./missing";
+
+console.log(a, b, c, missing);
@@ -0,0 +1,10 @@
+export function Gallery(props: { onOpen: () => void }) {
+  return (
+    <div role="list" aria-labeledby="title">
+      <img src="a.png" />
+      <img src="b.png" alt="" />
+      <span onClick={props.onOpen}>open</span>
+      <button type="button" onClick={props.onOpen}>open</button>
+    </div>
+  );
+}
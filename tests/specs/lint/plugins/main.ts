@@ -0,0 +1,4 @@
+alert("hello");
+
+// deno-lint-ignore my-plugin/no-alert
+alert("ignored");
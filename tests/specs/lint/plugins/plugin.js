@@ -0,0 +1,20 @@
+export default {
+  name: "my-plugin",
+  rules: {
+    "no-alert": {
+      create(context) {
+        return {
+          CallExpr(node) {
+            if (context.getText(node).startsWith("alert(")) {
+              context.report({
+                node,
+                message: "alert() blocks the page",
+                hint: "show a dialog instead",
+              });
+            }
+          },
+        };
+      },
+    },
+  },
+};
@@ -0,0 +1,4 @@
+export function parse(value: any): any {
+  if (value) {}
+  return value;
+}
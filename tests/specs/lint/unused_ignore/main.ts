@@ -0,0 +1,5 @@
+// deno-lint-ignore no-explicit-any no-empty -- the type is unknown
+export function parse(value: any) {
+  // deno-lint-ignore no-debugger
+  return value;
+}
@@ -0,0 +1,11 @@
+#!/usr/bin/env -S deno run
+/* deno-script
+{
+  "imports": { "greet": "./greet.ts" },
+  "permissions": ["--allow-env=NAME"],
+  "deno": ">=1.0.0"
+}
+*/
+import { greet } from "greet";
+
+console.log(greet(Deno.env.get("NAME") ?? "world"));
@@ -0,0 +1,6 @@
+/* deno-script
+{
+  "deno": "<1.0.0"
+}
+*/
+console.log("should not run");
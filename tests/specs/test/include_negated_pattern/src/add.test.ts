@@ -0,0 +1,5 @@
+Deno.test("should add", () => {
+  if (1 + 2 !== 3) {
+    throw new Error("FAIL");
+  }
+});
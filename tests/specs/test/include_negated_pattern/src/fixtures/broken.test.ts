@@ -0,0 +1,3 @@
+Deno.test("should not run", () => {
+  throw new Error("FAIL");
+});
@@ -0,0 +1,3 @@
+Deno.test("sets an env var", () => {
+  Deno.env.set("DENO_TEST_JOBS_LEAKED", "1");
+});
@@ -0,0 +1,5 @@
+Deno.test("doesn't see the env var of another module", () => {
+  if (Deno.env.get("DENO_TEST_JOBS_LEAKED") !== undefined) {
+    throw new Error("The env var leaked from another test module.");
+  }
+});
@@ -0,0 +1,12 @@
+Deno.test("first", () => {
+  throw new Error("should not run");
+});
+
+Deno.test("second", () => {
+  const value = 1;
+  if (value !== 1) throw new Error("unreachable");
+});
+
+Deno.test("third", () => {
+  throw new Error("should not run");
+});
@@ -0,0 +1,11 @@
+Deno.test("adds", () => {
+  throw new Error("The tests aren't run when listing them.");
+});
+
+Deno.test("subtracts", () => {});
+
+Deno.test({
+  name: "divides",
+  ignore: true,
+  fn() {},
+});
@@ -0,0 +1,9 @@
+Deno.test("fast", () => {});
+
+Deno.test("slow", async () => {
+  await new Promise((resolve) => setTimeout(resolve, 200));
+});
+
+Deno.test("steps", async (t) => {
+  await t.step("fast step", () => {});
+});
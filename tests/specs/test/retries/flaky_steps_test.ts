@@ -0,0 +1,10 @@
+let attempts = 0;
+
+Deno.test("step passes on the second attempt", async (t) => {
+  attempts++;
+  await t.step("step", () => {
+    if (attempts < 2) {
+      throw new Error(`attempt ${attempts} failed`);
+    }
+  });
+});
@@ -0,0 +1,10 @@
+let attempts = 0;
+
+Deno.test("passes on the third attempt", () => {
+  attempts++;
+  if (attempts < 3) {
+    throw new Error(`attempt ${attempts} failed`);
+  }
+});
+
+Deno.test("passes", () => {});
@@ -0,0 +1,8 @@
+let attempts = 0;
+
+Deno.test("passes on the second attempt", { retries: 1 }, () => {
+  attempts++;
+  if (attempts < 2) {
+    throw new Error(`attempt ${attempts} failed`);
+  }
+});
@@ -0,0 +1,3 @@
+Deno.test("test", () => {
+  throw new Error("fail");
+});
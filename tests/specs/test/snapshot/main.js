@@ -0,0 +1,12 @@
+Deno.test("config", (t) => {
+  t.assertSnapshot({
+    port: Number(Deno.env.get("PORT") ?? 8000),
+    hostname: "localhost",
+  });
+});
+
+Deno.test("steps", async (t) => {
+  await t.step("words", (t) => {
+    t.assertSnapshot(["hello", "world"]);
+  });
+});
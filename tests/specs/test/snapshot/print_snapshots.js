@@ -0,0 +1 @@
+console.log(Deno.readTextFileSync("__snapshots__/main.js.snap"));
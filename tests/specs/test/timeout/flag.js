@@ -0,0 +1,7 @@
+Deno.test("uses the default timeout", async () => {
+  await new Promise((resolve) => setTimeout(resolve, 60_000));
+});
+
+Deno.test("overrides the default timeout", { timeout: 10_000 }, async () => {
+  await new Promise((resolve) => setTimeout(resolve, 200));
+});
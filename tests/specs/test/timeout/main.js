@@ -0,0 +1,23 @@
+Deno.test("waits too long", { timeout: 100 }, async () => {
+  await new Promise((resolve) => setTimeout(resolve, 60_000));
+});
+
+function spin() {
+  while (true) {
+    // blocks the thread
+  }
+}
+
+Deno.test("blocks the thread", { timeout: 100 }, () => {
+  spin();
+});
+
+Deno.test("step", async (t) => {
+  await t.step({
+    name: "waits too long",
+    timeout: 100,
+    fn: () => new Promise(() => {}),
+  });
+});
+
+Deno.test("completes in time", { timeout: 10_000 }, () => {});
@@ -801,6 +801,7 @@ impl TestCommandBuilder {
     // todo(dsherret): use monch to extract out the vars
     text
       .replace("$DENO_DIR", &self.deno_dir.path().to_string_lossy())
+      .replace("$DENO_EXE", &deno_exe_path().to_string_lossy())
       .replace("$TESTDATA", &testdata_path().to_string_lossy())
       .replace("$PWD", &cwd.to_string_lossy())
   }